@@ -0,0 +1,16 @@
+fn main() {
+    // gRPC stubs for the remote API (rpc feature)
+    #[cfg(feature = "rpc")]
+    {
+        // Bundled protoc, so building needs no system protobuf install
+        if std::env::var_os("PROTOC").is_none() {
+            if let Ok(protoc) = protoc_bin_vendored::protoc_bin_path() {
+                std::env::set_var("PROTOC", protoc);
+            }
+        }
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos(&["proto/glowbarn.proto"], &["proto"])
+            .expect("compiling proto/glowbarn.proto");
+    }
+}
@@ -3,9 +3,19 @@
 //! Command-line interface for managing GlowBarn sessions and data.
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use glowbarn_sensors::recording::EventRecorder;
-use std::path::PathBuf;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use glowbarn_hal::{CalibrationSource, FaultKind, FaultSpec, SweepSummary};
+use glowbarn_sensors::redact::{RedactMode, Redaction};
+use glowbarn_sensors::significance::{self, SignificanceConfig, SignificanceReport};
+use glowbarn_sensors::history::{self, HistoryConfig, LocationHistory};
+use glowbarn_sensors::summary::{self, SummaryRecord};
+use glowbarn_sensors::reliability::{self, ReliabilityConfig, ReliabilityReport};
+use glowbarn_sensors::manifest::{SignedManifest, SigningKey};
+use glowbarn_sensors::units::{DisplayConfig, UnitSystem};
+use glowbarn_sensors::{export, recording::{EventRecorder, SessionExport}, EventType, SCHEMA_VERSION};
+use std::path::{Path, PathBuf};
+
+mod completions;
 
 #[derive(Parser)]
 #[command(name = "glowbarn-cli")]
@@ -19,6 +29,84 @@ struct Cli {
     /// Data directory
     #[arg(short, long, default_value = "/var/lib/glowbarn/data")]
     data_dir: PathBuf,
+    
+    /// Control socket of the running daemon
+    #[arg(long, default_value = "/run/glowbarn/control.sock")]
+    socket: PathBuf,
+    
+    /// Print results as tables, or as JSON for scripts
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+    
+    /// Configuration file to take `[display]` settings from
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    
+    /// Units for tables and reports (metric, imperial); data stays in SI
+    #[arg(long, global = true)]
+    units: Option<UnitSystem>,
+    
+    /// Locale for dates and times in tables and reports, e.g. en-US
+    #[arg(long, global = true)]
+    locale: Option<String>,
+}
+
+impl Cli {
+    /// `[display]` of the configuration file, with command-line overrides
+    fn display(&self) -> Result<DisplayConfig> {
+        #[derive(serde::Deserialize)]
+        struct File {
+            #[serde(default)]
+            display: DisplayConfig,
+        }
+        
+        let path = self.config.clone().or_else(|| {
+            let home = std::env::var("XDG_CONFIG_HOME").map(PathBuf::from)
+                .or_else(|_| std::env::var("HOME").map(|h| PathBuf::from(h).join(".config")))
+                .map(|dir| dir.join("glowbarn/config.toml"));
+            [Ok(PathBuf::from("/etc/glowbarn/config.toml")), home, Ok(PathBuf::from("./config.toml"))]
+                .into_iter()
+                .flatten()
+                .find(|path| path.exists())
+        });
+        let mut display = match path {
+            Some(path) => toml::from_str::<File>(&std::fs::read_to_string(&path)?)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?
+                .display,
+            None => DisplayConfig::default(),
+        };
+        
+        if let Some(units) = self.units {
+            display.units = units;
+        }
+        if let Some(locale) = &self.locale {
+            display.locale = locale.clone();
+        }
+        Ok(display)
+    }
+}
+
+/// How command results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
+impl OutputFormat {
+    /// Print `value` as JSON, or through `table` for people
+    fn print<T: serde::Serialize + ?Sized>(self, value: &T, table: impl FnOnce(&T)) -> Result<()> {
+        match self {
+            OutputFormat::Json => print_json(value)?,
+            OutputFormat::Table => table(value),
+        }
+        Ok(())
+    }
+}
+
+fn print_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
 }
 
 #[derive(Subcommand)]
@@ -35,7 +123,7 @@ enum Commands {
         /// Session ID
         session_id: String,
         
-        /// Filter by event type
+        /// Filter by event type name or alias (emf, contact, vibration, humidity, pressure, ...)
         #[arg(short = 't', long)]
         event_type: Option<String>,
         
@@ -43,9 +131,9 @@ enum Commands {
         #[arg(short, long)]
         min_confidence: Option<f64>,
         
-        /// Output format (json, table)
-        #[arg(short, long, default_value = "table")]
-        format: String,
+        /// Output format (json, table); use the global --output instead
+        #[arg(short, long, hide = true)]
+        format: Option<String>,
     },
     
     /// Export session data
@@ -55,223 +143,1866 @@ enum Commands {
         
         /// Output file path
         #[arg(short, long)]
-        output: PathBuf,
+        out: PathBuf,
+        
+        /// Output format (json, geojson, kml, html, heatmap)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+        
+        /// Strip or hash location names, GPS coordinates and operator names (strip, hash)
+        #[arg(long)]
+        redact: Option<RedactMode>,
+        
+        /// Salt for `--redact hash`, to hash alike across exports (random otherwise)
+        #[arg(long, requires = "redact")]
+        salt: Option<String>,
+    },
+    
+    /// Import a JSON session export into the data directory
+    Import {
+        /// Export file (from `export --format json`, any GlowBarn version)
+        file: PathBuf,
+    },
+    
+    /// Build review tracks (subtitles, chapters, audio cue sheet)
+    Review {
+        /// Session ID
+        session_id: String,
+        
+        /// Output directory
+        #[arg(short, long)]
+        out: PathBuf,
+        
+        /// Session video to mux the event tracks into (requires ffmpeg)
+        #[arg(long)]
+        video: Option<PathBuf>,
+        
+        /// Session audio to reference from the cue sheet
+        #[arg(long)]
+        audio: Option<PathBuf>,
+        
+        /// Seconds between session start and the first media frame
+        #[arg(long, default_value = "0")]
+        media_offset: f64,
+    },
+    
+    /// Average a session's wideband sweeps per band, optionally against another session
+    Sweeps {
+        /// Session ID
+        session_id: String,
+        
+        /// Session to compare against (e.g. last night's)
+        #[arg(long)]
+        compare: Option<String>,
+        
+        /// Only list bands whose mean power changed by at least this much (dB)
+        #[arg(long, default_value = "0")]
+        min_change: f64,
+    },
+    
+    /// Per-window min/max/mean/std of a session's sensors (summaries.jsonl)
+    Summaries {
+        /// Session ID
+        session_id: String,
+        
+        /// Only sensors whose name contains this
+        #[arg(long)]
+        sensor: Option<String>,
+        
+        /// Combine the saved windows into rows of this many minutes
+        #[arg(long)]
+        minutes: Option<u64>,
+    },
+    
+    /// Test a session's event rates and sensor readings against a control window
+    ///
+    /// Shows the report saved when the session ended, if there is one and no
+    /// options are given; otherwise runs the tests and saves the result.
+    Significance {
+        /// Session ID
+        session_id: String,
+        
+        /// Session to use as the control
+        #[arg(long)]
+        control: Option<String>,
+        
+        /// Use the same hours this far before the session as the control (default 24)
+        #[arg(long, conflicts_with = "control")]
+        offset_hours: Option<f64>,
+        
+        /// Bin length in minutes (default 10)
+        #[arg(long)]
+        bin_minutes: Option<f64>,
+        
+        /// Random relabellings per test (default 9999)
+        #[arg(long)]
+        permutations: Option<usize>,
+    },
+    
+    /// Recurring times, zones and patterns over all sessions at a location
+    ///
+    /// Lists the recorded locations when none is given.
+    History {
+        /// Location, as set in the config when the sessions were recorded
+        location: Option<String>,
+        
+        /// Time-of-day bin length in minutes (default 10)
+        #[arg(long)]
+        bin_minutes: Option<u32>,
+        
+        /// Widest spread of a recurring pattern in minutes (default 20)
+        #[arg(long)]
+        window_minutes: Option<u32>,
+        
+        /// Sessions a hotspot or pattern must recur in (default 2)
+        #[arg(long)]
+        min_sessions: Option<usize>,
+    },
+    
+    /// Record a reviewer's verdict on an event of a session
+    ///
+    /// Saved as an annotation tagged `confirmed` or `debunked`, the labels
+    /// the reliability report is built from.
+    Label {
+        /// Session ID
+        session_id: String,
+        
+        /// Event ID
+        event_id: String,
+        
+        #[arg(value_parser = ["confirmed", "debunked"])]
+        verdict: String,
+        
+        /// Reason, e.g. "traced to the fridge compressor"
+        text: Vec<String>,
+        
+        /// Who is reviewing (defaults to $USER)
+        #[arg(short, long)]
+        author: Option<String>,
+    },
+    
+    /// Reported confidence against reviewer verdicts over all sessions
+    ///
+    /// Bins labelled events by confidence, shows a reliability diagram and
+    /// suggests a remapping to empirical confidence.
+    Reliability {
+        /// Only sessions at this location
+        #[arg(long)]
+        location: Option<String>,
+        
+        /// Only events of this type (e.g. emf, cold_spot)
+        #[arg(long = "type")]
+        event_type: Option<String>,
+        
+        /// Confidence bins (default 10)
+        #[arg(long)]
+        bins: Option<usize>,
+        
+        /// Labelled events a bin needs to shape the remapping (default 5)
+        #[arg(long)]
+        min_labelled: Option<usize>,
+    },
+    
+    /// Push a configuration file to one or more nodes
+    PushConfig {
+        /// Config file (its `config_version` must be newer than each node's)
+        file: PathBuf,
+        
+        /// Node API address (host:port), repeatable
+        #[arg(short, long = "node", required = true)]
+        nodes: Vec<String>,
+        
+        /// Bearer token matching the nodes' `api_token`
+        #[arg(long)]
+        token: Option<String>,
+    },
+    
+    /// Roll nodes back to their previous configuration
+    RollbackConfig {
+        /// Node API address (host:port), repeatable
+        #[arg(short, long = "node", required = true)]
+        nodes: Vec<String>,
+        
+        /// Bearer token matching the nodes' `api_token`
+        #[arg(long)]
+        token: Option<String>,
+    },
+    
+    /// Show the running daemon's status
+    Status,
+    
+    /// Show the running rig's signed manifest, or those saved in a session
+    ///
+    /// Signatures are checked against the node key in the data directory.
+    Manifest {
+        /// Session ID (default: ask the running daemon)
+        session_id: Option<String>,
+        
+        /// Fail unless every manifest carries a valid signature
+        #[arg(long)]
+        verify: bool,
+    },
+    
+    /// Run a session template's pre-flight checklist on the running daemon
+    Preflight {
+        /// Template name
+        template: String,
+    },
+    
+    /// Start recording on the running daemon
+    Start {
+        /// Session template whose checklist must pass first
+        #[arg(short, long)]
+        template: Option<String>,
+    },
+    
+    /// End the running daemon's session
+    Stop,
+    
+    /// Add a note to the running session
+    Note {
+        /// Note text
+        text: Vec<String>,
+    },
+    
+    /// Annotate the running session, optionally on one of its events
+    Annotate {
+        /// Annotation text
+        text: Vec<String>,
+        
+        /// Who is annotating (defaults to $USER)
+        #[arg(short, long)]
+        author: Option<String>,
+        
+        /// Event the annotation comments on
+        #[arg(short, long)]
+        event: Option<String>,
+        
+        /// Tag, repeatable
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+    },
+    
+    /// Import a handheld logger CSV into a session's sensor log via the daemon
+    ImportCsv {
+        /// Logger CSV file
+        file: PathBuf,
+        
+        /// `[[csv_import]]` mapping from the daemon's config
+        #[arg(short, long)]
+        mapping: String,
+        
+        /// Session to import into (the running session by default)
+        #[arg(short, long)]
+        session: Option<String>,
+    },
+    
+    /// Mark the current time in the running session
+    Mark {
+        /// Optional label
+        label: Vec<String>,
+    },
+    
+    /// Make the running daemon re-read its config file
+    Reload,
+    
+    /// Switch the running daemon to a detection profile
+    Profile {
+        /// Profile name
+        name: String,
+    },
+    
+    /// Trigger commands on the running daemon
+    Trigger {
+        #[command(subcommand)]
+        command: TriggerCommand,
+    },
+    
+    /// Let the running daemon's triggers run their actions
+    Arm,
+    
+    /// Keep evaluating triggers on the running daemon, but only log their firings
+    Disarm,
+    
+    /// Show or switch bus transaction logging on the running daemon
+    /// (until its config is reloaded)
+    Buslog {
+        /// "on" or "off"; omit to show the current state
+        #[arg(value_parser = ["on", "off"])]
+        state: Option<String>,
+    },
+    
+    /// Simulated sensor faults on the running daemon (needs `fault_injection = true`)
+    Fault {
+        #[command(subcommand)]
+        command: FaultCommand,
+    },
+    
+    /// Pause, resume, re-open, remove or re-rate a sensor of the running daemon
+    Sensor {
+        #[command(subcommand)]
+        command: SensorCommand,
     },
     
     /// Show sensor status
     Sensors,
     
-    /// Generate sample configuration
-    Config {
-        /// Output path
-        #[arg(short, long)]
-        output: Option<PathBuf>,
-    },
+    /// Calibrate an RTL-SDR's power readings to dBm at each gain setting
+    SdrCalibrate {
+        /// Dongle index
+        #[arg(long, default_value = "0")]
+        device: u32,
+        
+        /// Dongle serial the calibration is stored under
+        #[arg(long)]
+        serial: Option<String>,
+        
+        /// Frequency to tune to (Hz)
+        #[arg(long, default_value = "100000000")]
+        frequency: u64,
+        
+        /// Level of a signal generator inside the tuned span (dBm)
+        #[arg(long, allow_negative_numbers = true, conflicts_with = "noise_dbm_hz", required_unless_present = "noise_dbm_hz")]
+        signal_dbm: Option<f64>,
+        
+        /// Signal bandwidth counted around its peak (Hz)
+        #[arg(long, default_value = "10000")]
+        bandwidth: f64,
+        
+        /// Spectral density of a flat noise source (dBm/Hz)
+        #[arg(long, allow_negative_numbers = true)]
+        noise_dbm_hz: Option<f64>,
+        
+        /// Gain settings to measure (dB)
+        #[arg(long, value_delimiter = ',', default_value = "0,9.7,19.7,29.7,40.2,49.6")]
+        gains: Vec<f64>,
+    },
+    
+    /// Generate sample configuration
+    Config {
+        /// Output path
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+    
+    /// System information
+    Info,
+    
+    /// Print a shell completion script
+    ///
+    /// e.g. `glowbarn-cli completions bash > /etc/bash_completion.d/glowbarn-cli`
+    Completions {
+        /// Shell to complete for
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+}
+
+#[derive(Subcommand)]
+enum TriggerCommand {
+    /// Fire a trigger's action with a test event
+    Test {
+        /// Trigger name
+        name: String,
+    },
+    /// Log a trigger's firings without running its action, to vet it on live data
+    DryRun {
+        /// Trigger name
+        name: String,
+        /// Make the trigger live again
+        #[arg(long)]
+        off: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum FaultCommand {
+    /// Inject a fault into a sensor's reads
+    Inject {
+        /// Sensor name
+        sensor: String,
+        
+        /// Fault kind (timeout, garbage, bus_error, disappear)
+        kind: String,
+        
+        /// How long timed-out reads hang (ms)
+        #[arg(long)]
+        delay_ms: Option<u64>,
+        
+        /// Share of reads failing with bus errors (0-1)
+        #[arg(long)]
+        probability: Option<f64>,
+        
+        /// Seconds before the fault starts
+        #[arg(long)]
+        after: Option<f64>,
+        
+        /// Seconds the fault lasts (until cleared by default)
+        #[arg(long = "for")]
+        duration: Option<f64>,
+    },
+    
+    /// List scheduled and active faults
+    List,
+    
+    /// Remove the faults of a sensor, or all
+    Clear {
+        /// Sensor name
+        sensor: Option<String>,
+    },
+    
+    /// Schedule every `[[fault]]` of a TOML script
+    Script {
+        /// Script file
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum SensorCommand {
+    /// Health and polling state of a sensor
+    Status {
+        /// Sensor name
+        name: String,
+    },
+    /// Stop polling a sensor, keeping it open
+    Pause {
+        /// Sensor name
+        name: String,
+    },
+    /// Poll a paused sensor again
+    Resume {
+        /// Sensor name
+        name: String,
+    },
+    /// Close and re-open a sensor
+    Reinit {
+        /// Sensor name
+        name: String,
+    },
+    /// Close and unregister a sensor until restart (or until plugged in again)
+    Remove {
+        /// Sensor name
+        name: String,
+    },
+    /// Poll a sensor at another rate until restart
+    Rate {
+        /// Sensor name
+        name: String,
+        
+        /// Polls per second, or "default" for the global poll interval
+        rate: String,
+        
+        /// Delay of the sensor's polls within its period (ms)
+        #[arg(long, default_value = "0")]
+        phase_ms: u64,
+    },
+}
+
+/// Fault script file
+#[derive(serde::Deserialize)]
+struct FaultScript {
+    #[serde(default, rename = "fault")]
+    faults: Vec<FaultSpec>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let out = cli.output;
+    let display = cli.display()?;
+    
+    match cli.command {
+        Commands::Sessions { verbose } => {
+            list_sessions(&cli.data_dir, verbose, out, &display)?;
+        }
+        
+        Commands::Events { session_id, event_type, min_confidence, format } => {
+            // `--format json` predates the global `--output`
+            let out = if format.as_deref() == Some("json") { OutputFormat::Json } else { out };
+            show_events(&cli.data_dir, &session_id, event_type, min_confidence, out, &display)?;
+        }
+        
+        Commands::Export { session_id, out: path, format, redact, salt } => {
+            let redaction = redact.map(|mode| match &salt {
+                Some(salt) => Redaction::with_salt(mode, salt),
+                None => Redaction::new(mode),
+            });
+            export_session(&cli.data_dir, &session_id, &path, &format, redaction, out, &display)?;
+        }
+        
+        Commands::Import { file } => {
+            import_session(&cli.data_dir, &file, out)?;
+        }
+        
+        Commands::Review { session_id, out: path, video, audio, media_offset } => {
+            build_review(&cli.data_dir, &session_id, &path, video.as_deref(), audio.as_deref(), media_offset, out)?;
+        }
+        
+        Commands::Sweeps { session_id, compare, min_change } => {
+            show_sweeps(&cli.data_dir, &session_id, compare.as_deref(), min_change, out)?;
+        }
+        
+        Commands::Summaries { session_id, sensor, minutes } => {
+            let recorder = EventRecorder::new(&cli.data_dir)?;
+            let mut records = recorder.load_summaries(&session_id)?;
+            if let Some(ref sensor) = sensor {
+                records.retain(|r| r.sensor_name.contains(sensor.as_str()));
+            }
+            if let Some(minutes) = minutes {
+                records = summary::merge(&records, std::time::Duration::from_secs(minutes.max(1) * 60));
+            }
+            out.print(&records, |records| show_summaries(&session_id, records, &display))?;
+        }
+        
+        Commands::Significance { session_id, control, offset_hours, bin_minutes, permutations } => {
+            let recorder = EventRecorder::new(&cli.data_dir)?;
+            let saved = recorder.load_significance(&session_id)?;
+            let report = match saved {
+                Some(report) if control.is_none() && offset_hours.is_none() && bin_minutes.is_none() && permutations.is_none() => report,
+                _ => {
+                    let defaults = SignificanceConfig::default();
+                    let config = SignificanceConfig {
+                        control_session: control,
+                        control_offset_hours: offset_hours.unwrap_or(defaults.control_offset_hours),
+                        bin_minutes: bin_minutes.unwrap_or(defaults.bin_minutes),
+                        permutations: permutations.unwrap_or(defaults.permutations),
+                        ..defaults
+                    };
+                    let report = significance::compare(&recorder, &session_id, &config)?;
+                    recorder.record_significance(&report)?;
+                    report
+                }
+            };
+            out.print(&report, |report| show_significance(report, &display))?;
+        }
+        
+        Commands::Label { session_id, event_id, verdict, text, author } => {
+            let author = author.or_else(|| std::env::var("USER").ok())
+                .ok_or_else(|| anyhow::anyhow!("no author given and $USER is not set"))?;
+            let text = if text.is_empty() { verdict.clone() } else { text.join(" ") };
+            let recorder = EventRecorder::new(&cli.data_dir)?;
+            let session = recorder.load_session(&session_id)?;
+            if !recorder.load_events(&session_id)?.iter().any(|e| e.id == event_id) {
+                anyhow::bail!("No event {} in session {}", event_id, session_id);
+            }
+            
+            // A session still being recorded lives in the daemon until it ends
+            let recording = session.end_time.is_none()
+                && daemon_command(&cli.socket, "status")
+                    .is_ok_and(|status| status["session"]["id"].as_str() == Some(session_id.as_str()));
+            let annotation = if recording {
+                daemon_command(&cli.socket, &format!("annotate author={} event={} tag={} {}", author.replace(' ', "_"), event_id, verdict, text))?
+            } else {
+                serde_json::to_value(recorder.annotate_stored(&session_id, &author, &text, Some(&event_id), std::slice::from_ref(&verdict))?)?
+            };
+            out.print(&annotation, |_| println!("Event {} labelled {}", event_id, verdict))?;
+        }
+        
+        Commands::Reliability { location, event_type, bins, min_labelled } => {
+            let recorder = EventRecorder::new(&cli.data_dir)?;
+            let defaults = ReliabilityConfig::default();
+            let config = ReliabilityConfig {
+                bins: bins.unwrap_or(defaults.bins),
+                min_labelled: min_labelled.unwrap_or(defaults.min_labelled),
+                location,
+                event_type,
+            };
+            let report = reliability::analyze(&recorder, &config)?;
+            out.print(&report, show_reliability)?;
+        }
+        
+        Commands::History { location, bin_minutes, window_minutes, min_sessions } => {
+            let recorder = EventRecorder::new(&cli.data_dir)?;
+            match location {
+                Some(location) => {
+                    let defaults = HistoryConfig::default();
+                    let config = HistoryConfig {
+                        bin_minutes: bin_minutes.unwrap_or(defaults.bin_minutes),
+                        window_minutes: window_minutes.unwrap_or(defaults.window_minutes),
+                        min_sessions: min_sessions.unwrap_or(defaults.min_sessions),
+                        utc: display.utc,
+                    };
+                    let history = history::analyze(&recorder, &location, &config)?;
+                    out.print(&history, |history| show_history(history, &display))?;
+                }
+                None => {
+                    let locations = history::locations(&recorder)?;
+                    out.print(&locations, |locations| {
+                        for (location, sessions) in locations {
+                            println!("{:<40} {:>4} sessions", location, sessions);
+                        }
+                    })?;
+                }
+            }
+        }
+        
+        Commands::PushConfig { file, nodes, token } => {
+            let content = std::fs::read_to_string(&file)?;
+            send_to_nodes(&nodes, "PUT", "/config", token.as_deref(), Some(&content), out)?;
+        }
+        
+        Commands::RollbackConfig { nodes, token } => {
+            send_to_nodes(&nodes, "POST", "/config/rollback", token.as_deref(), None, out)?;
+        }
+        
+        Commands::Status => {
+            let status = daemon_command(&cli.socket, "status")?;
+            out.print(&status, |status| show_status(status, &display))?;
+        }
+        
+        Commands::Manifest { session_id, verify } => {
+            let key = SigningKey::load(&cli.data_dir)?;
+            let manifests: Vec<SignedManifest> = match &session_id {
+                Some(session_id) => EventRecorder::new(&cli.data_dir)?.load_manifests(session_id)?,
+                None => vec![serde_json::from_value(daemon_command(&cli.socket, "manifest")?)?],
+            };
+            let valid: Vec<Option<bool>> = manifests.iter()
+                .map(|m| key.as_ref().map(|key| m.verify(key)))
+                .collect();
+            
+            match &session_id {
+                Some(session_id) => out.print(&manifests, |manifests| show_manifests(session_id, manifests, &valid, &display))?,
+                None => out.print(&manifests[0], |manifest| show_manifest(manifest, valid[0], &display))?,
+            }
+            if verify {
+                let failed = valid.iter().filter(|v| **v != Some(true)).count();
+                match key {
+                    None => anyhow::bail!("No signing key in {}", cli.data_dir.display()),
+                    Some(_) if failed > 0 => anyhow::bail!("{} of {} manifests failed verification", failed, manifests.len()),
+                    Some(_) => {}
+                }
+            }
+        }
+        
+        Commands::Preflight { template } => {
+            let checklist = daemon_command(&cli.socket, &format!("preflight {}", template))?;
+            out.print(&checklist, show_checklist)?;
+        }
+        
+        Commands::Start { template } => {
+            let command = format!("start {}", template.as_deref().unwrap_or_default());
+            let session = daemon_command(&cli.socket, &command)?;
+            out.print(&session, |session| {
+                println!("Recording '{}' ({})",
+                    session["name"].as_str().unwrap_or_default(),
+                    session["id"].as_str().unwrap_or_default());
+                for note in session["notes"].as_array().into_iter().flatten() {
+                    if let Some(note) = note.as_str().filter(|n| n.contains("Pre-flight")) {
+                        println!("{}", note);
+                    }
+                }
+            })?;
+        }
+        
+        Commands::Stop => {
+            let session = daemon_command(&cli.socket, "stop")?;
+            out.print(&session, |session| {
+                println!("Session '{}' ended ({} events)",
+                    session["name"].as_str().unwrap_or_default(),
+                    session["event_count"]);
+            })?;
+        }
+        
+        Commands::Note { text } => {
+            let note = daemon_command(&cli.socket, &format!("note {}", text.join(" ")))?;
+            out.print(&note, |_| println!("Note added"))?;
+        }
+        
+        Commands::Annotate { text, author, event, tags } => {
+            let author = author.or_else(|| std::env::var("USER").ok())
+                .ok_or_else(|| anyhow::anyhow!("no author given and $USER is not set"))?;
+            let mut command = format!("annotate author={}", author.replace(' ', "_"));
+            if let Some(event) = event {
+                command.push_str(&format!(" event={}", event));
+            }
+            for tag in tags {
+                command.push_str(&format!(" tag={}", tag.replace(' ', "_")));
+            }
+            command.push(' ');
+            command.push_str(&text.join(" "));
+            let annotation = daemon_command(&cli.socket, &command)?;
+            out.print(&annotation, |annotation| println!("Annotation #{} added", annotation["id"]))?;
+        }
+        
+        Commands::ImportCsv { file, mapping, session } => {
+            let path = std::fs::canonicalize(&file)
+                .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+            let command = match session {
+                Some(session) => format!("import-csv {} session={} {}", mapping, session, path.display()),
+                None => format!("import-csv {} {}", mapping, path.display()),
+            };
+            let imported = daemon_command(&cli.socket, &command)?;
+            out.print(&imported, |imported| {
+                println!("{} readings imported into {}", imported["imported"], imported["session"].as_str().unwrap_or_default());
+                if imported["outside_session"].as_u64().unwrap_or(0) > 0 {
+                    println!("{} readings outside the session were left out (check the logger clock and utc_offset_minutes)",
+                        imported["outside_session"]);
+                }
+                if imported["skipped_rows"].as_u64().unwrap_or(0) > 0 {
+                    println!("{} rows had no readable timestamp (check time_format)", imported["skipped_rows"]);
+                }
+            })?;
+        }
+        
+        Commands::Mark { label } => {
+            let mark = daemon_command(&cli.socket, &format!("mark {}", label.join(" ")))?;
+            out.print(&mark, |mark| println!("{}", mark.as_str().unwrap_or_default()))?;
+        }
+        
+        Commands::Reload => {
+            let applied = daemon_command(&cli.socket, "reload")?;
+            out.print(&applied, |applied| {
+                println!("Config version {} applied{}", applied["version"],
+                    if applied["restart_required"].as_bool().unwrap_or(false) { " (restart required)" } else { "" });
+            })?;
+        }
+        
+        Commands::Profile { name } => {
+            let profile = daemon_command(&cli.socket, &format!("profile {}", name))?;
+            out.print(&profile, |profile| {
+                println!("Switched to profile '{}'", profile["name"].as_str().unwrap_or(&name));
+            })?;
+        }
+        
+        Commands::Trigger { command: TriggerCommand::Test { name } } => {
+            let fired = daemon_command(&cli.socket, &format!("trigger test {}", name))?;
+            out.print(&fired, |_| println!("Trigger '{}' fired", name))?;
+        }
+        
+        Commands::Trigger { command: TriggerCommand::DryRun { name, off } } => {
+            let state = daemon_command(&cli.socket, &format!("trigger dry-run {} {}", name, if off { "off" } else { "on" }))?;
+            out.print(&state, |_| {
+                if off {
+                    println!("Trigger '{}' is live", name);
+                } else {
+                    println!("Trigger '{}' in dry-run mode: firings are logged, actions not run", name);
+                }
+            })?;
+        }
+        
+        Commands::Arm | Commands::Disarm => {
+            let armed = matches!(cli.command, Commands::Arm);
+            let state = daemon_command(&cli.socket, if armed { "arm" } else { "disarm" })?;
+            out.print(&state, |_| {
+                if armed {
+                    println!("Triggers armed");
+                } else {
+                    println!("Triggers disarmed: firings are logged, actions not run");
+                }
+            })?;
+        }
+        
+        Commands::Buslog { state } => {
+            let command = match &state {
+                Some(state) => format!("buslog {}", state),
+                None => "buslog".to_string(),
+            };
+            let enabled = daemon_command(&cli.socket, &command)?;
+            out.print(&enabled, |enabled| {
+                if enabled.as_bool().unwrap_or(false) {
+                    println!("Bus logging on: transactions go to the session's bus.jsonl");
+                } else {
+                    println!("Bus logging off");
+                }
+            })?;
+        }
+        
+        Commands::Fault { command } => match command {
+            FaultCommand::Inject { sensor, kind, delay_ms, probability, after, duration } => {
+                let mut command = format!("fault {} {}", sensor, kind);
+                for (key, value) in [("delay", delay_ms.map(|d| d as f64)), ("p", probability), ("after", after), ("for", duration)] {
+                    if let Some(value) = value {
+                        command.push_str(&format!(" {}={}", key, value));
+                    }
+                }
+                out.print(&daemon_command(&cli.socket, &command)?, show_fault)?;
+            }
+            FaultCommand::List => {
+                let faults = daemon_command(&cli.socket, "faults")?;
+                let faults = faults.as_array().cloned().unwrap_or_default();
+                out.print(&faults, |faults| {
+                    if faults.is_empty() {
+                        println!("No faults scheduled");
+                    }
+                    faults.iter().for_each(show_fault);
+                })?;
+            }
+            FaultCommand::Clear { sensor } => {
+                let removed = daemon_command(&cli.socket, &format!("fault-clear {}", sensor.unwrap_or_default()))?;
+                out.print(&removed, |removed| println!("{} faults cleared", removed))?;
+            }
+            FaultCommand::Script { file } => {
+                let script: FaultScript = toml::from_str(&std::fs::read_to_string(&file)?)?;
+                if script.faults.is_empty() {
+                    anyhow::bail!("No [[fault]] entries in {}", file.display());
+                }
+                let scheduled = script.faults.iter()
+                    .map(|spec| daemon_command(&cli.socket, &fault_command(spec)))
+                    .collect::<Result<Vec<_>>>()?;
+                out.print(&scheduled, |scheduled| scheduled.iter().for_each(show_fault))?;
+            }
+        },
+        
+        Commands::Sensor { command } => {
+            let command = match command {
+                SensorCommand::Status { name } => format!("sensor status {}", name),
+                SensorCommand::Pause { name } => format!("sensor pause {}", name),
+                SensorCommand::Resume { name } => format!("sensor resume {}", name),
+                SensorCommand::Reinit { name } => format!("sensor reinit {}", name),
+                SensorCommand::Remove { name } => {
+                    daemon_command(&cli.socket, &format!("sensor remove {}", name))?;
+                    out.print(&serde_json::Value::Null, |_| println!("Sensor {} removed", name))?;
+                    return Ok(());
+                }
+                SensorCommand::Rate { name, rate, phase_ms } => format!("sensor rate {} {} phase={}", name, rate, phase_ms),
+            };
+            out.print(&daemon_command(&cli.socket, &command)?, show_sensor_health)?;
+        }
+        
+        Commands::Sensors => {
+            out.print(&scan_sensors(), show_sensors)?;
+        }
+        
+        Commands::SdrCalibrate { device, serial, frequency, signal_dbm, bandwidth, noise_dbm_hz, gains } => {
+            let source = match (signal_dbm, noise_dbm_hz) {
+                (Some(power_dbm), _) => CalibrationSource::KnownSignal { power_dbm, bandwidth_hz: bandwidth },
+                (None, Some(dbm_per_hz)) => CalibrationSource::NoiseSource { dbm_per_hz },
+                (None, None) => anyhow::bail!("--signal-dbm or --noise-dbm-hz is required"),
+            };
+            calibrate_sdr(&cli.data_dir, device, serial.as_deref(), frequency, source, &gains, out)?;
+        }
+        
+        Commands::Config { out: path } => {
+            generate_config(path, out)?;
+        }
+        
+        Commands::Info => {
+            out.print(&system_info(), show_info)?;
+        }
+        
+        Commands::Completions { shell } => {
+            print!("{}", completions::generate(shell, &Cli::command()));
+        }
+    }
+    
+    Ok(())
+}
+
+fn list_sessions(data_dir: &Path, verbose: bool, out: OutputFormat, display: &DisplayConfig) -> Result<()> {
+    let recorder = EventRecorder::new(data_dir)?;
+    let sessions = recorder.list_sessions()?;
+    
+    if out == OutputFormat::Json {
+        let mut listed = Vec::new();
+        for session in &sessions {
+            let mut entry = serde_json::to_value(session)?;
+            if verbose {
+                entry["clock"] = serde_json::to_value(recorder.load_clock(&session.id)?)?;
+            }
+            listed.push(entry);
+        }
+        return print_json(&listed);
+    }
+    
+    if sessions.is_empty() {
+        println!("No recording sessions found.");
+        return Ok(());
+    }
+    
+    println!("╭────────────────────────────────────────────────────────────────────╮");
+    println!("│                      Recording Sessions                             │");
+    println!("├────────────────────┬──────────────────────┬────────────┬───────────┤");
+    println!("│ Session ID         │ Name                 │ Events     │ Duration  │");
+    println!("├────────────────────┼──────────────────────┼────────────┼───────────┤");
+    
+    for session in &sessions {
+        let duration = session.duration();
+        let duration_str = format!("{}:{:02}:{:02}",
+            duration.num_hours(),
+            duration.num_minutes() % 60,
+            duration.num_seconds() % 60);
+        
+        println!("│ {:18} │ {:20} │ {:>10} │ {:>9} │",
+            truncate(&session.id, 18),
+            truncate(&session.name, 20),
+            session.event_count,
+            duration_str);
+    }
+    
+    println!("╰────────────────────┴──────────────────────┴────────────┴───────────╯");
+    
+    if verbose {
+        for session in &sessions {
+            println!("\n{}", "─".repeat(60));
+            println!("Session: {}", session.id);
+            println!("  Name: {}", session.name);
+            println!("  Location: {}", session.location);
+            println!("  Start: {}", display.datetime(session.start_time));
+            if let Some(end) = session.end_time {
+                println!("  End: {}", display.datetime(end));
+            }
+            println!("  Events: {}", session.event_count);
+            
+            if !session.notes.is_empty() {
+                println!("  Notes:");
+                for note in &session.notes {
+                    println!("    - {}", note);
+                }
+            }
+            
+            if !session.annotations.is_empty() {
+                println!("  Annotations:");
+                for annotation in &session.annotations {
+                    match &annotation.event_id {
+                        Some(event_id) => println!("    #{} {} (on {})", annotation.id, annotation_line(annotation, display), event_id),
+                        None => println!("    #{} {}", annotation.id, annotation_line(annotation, display)),
+                    }
+                }
+            }
+            
+            if let Some(clock) = recorder.load_clock(&session.id)? {
+                println!("  Stream delays (wall clock drift {:+.0} ms):", clock.wall_drift_ms);
+                for stream in &clock.streams {
+                    println!("    {:<24} {:>8.1} ms ± {:.1} ({:?})",
+                        stream.stream, stream.offset_ms, stream.jitter_ms, stream.kind);
+                }
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+fn show_events(data_dir: &Path, session_id: &str, event_type: Option<String>, 
+               min_confidence: Option<f64>, out: OutputFormat, display: &DisplayConfig) -> Result<()> {
+    let recorder = EventRecorder::new(data_dir)?;
+    let mut events = recorder.load_events(session_id)?;
+    
+    // Apply filters
+    if let Some(ref et) = event_type {
+        events.retain(|e| e.event_type.matches(et));
+    }
+    
+    if let Some(min_conf) = min_confidence {
+        events.retain(|e| e.confidence >= min_conf);
+    }
+    
+    if events.is_empty() && out == OutputFormat::Table {
+        println!("No events found matching criteria.");
+        return Ok(());
+    }
+    
+    match out {
+        OutputFormat::Json => print_json(&events)?,
+        OutputFormat::Table => {
+            println!("╭─────────────────────────────────────────────────────────────────────────╮");
+            println!("│                           Event Log                                     │");
+            println!("├────────────────────┬──────────────────────┬──────────────┬─────────────┤");
+            println!("│ Time               │ Event Type           │ Confidence   │ Sensors     │");
+            println!("├────────────────────┼──────────────────────┼──────────────┼─────────────┤");
+            
+            for event in &events {
+                let time_str = display.time_ms(event.timestamp.into());
+                
+                println!("│ {:18} │ {:20} │ {:>10.1}% │ {:>11} │",
+                    time_str,
+                    format!("{:?}", event.event_type),
+                    event.confidence * 100.0,
+                    event.sensor_data.len());
+            }
+            
+            println!("╰────────────────────┴──────────────────────┴──────────────┴─────────────╯");
+            println!("\nTotal events: {}", events.len());
+            
+            let session = recorder.load_session(session_id)?;
+            let threads: Vec<_> = events.iter()
+                .map(|event| (event, session.annotations_for(&event.id).collect::<Vec<_>>()))
+                .filter(|(_, annotations)| !annotations.is_empty())
+                .collect();
+            if !threads.is_empty() {
+                println!("\nAnnotations:");
+                for (event, annotations) in threads {
+                    println!("  {} {:?} ({})", display.time(event.timestamp.into()), event.event_type, event.id);
+                    for annotation in annotations {
+                        println!("    └ {}", annotation_line(annotation, display));
+                    }
+                }
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// An annotation as `[time] author: text #tags`, in the display locale
+fn annotation_line(annotation: &glowbarn_sensors::recording::Annotation, display: &DisplayConfig) -> String {
+    let mut line = format!("[{}] {}: {}", display.time(annotation.timestamp), annotation.author, annotation.text);
+    for tag in &annotation.tags {
+        line.push_str(&format!(" #{}", tag));
+    }
+    line
+}
+
+/// Mean and strongest peak per band over a session's sweeps, keyed by band start (kHz)
+fn sweep_bands(sweeps: &[SweepSummary]) -> std::collections::BTreeMap<i64, (f64, f64, f64)> {
+    let mut sums: std::collections::BTreeMap<i64, (f64, usize, f64, f64)> = Default::default();
+    for band in sweeps.iter().flat_map(|s| &s.bands) {
+        let entry = sums.entry((band.start_mhz * 1000.0).round() as i64)
+            .or_insert((0.0, 0, f64::MIN, 0.0));
+        entry.0 += band.mean;
+        entry.1 += 1;
+        if band.peak > entry.2 {
+            entry.2 = band.peak;
+            entry.3 = band.peak_mhz;
+        }
+    }
+    sums.into_iter()
+        .map(|(key, (sum, n, peak, peak_mhz))| (key, (sum / n as f64, peak, peak_mhz)))
+        .collect()
+}
+
+/// Per-band sweep averages of a session, optionally against another
+#[derive(serde::Serialize)]
+struct SweepComparison {
+    session_id: String,
+    sweeps: usize,
+    compare: Option<String>,
+    compare_sweeps: Option<usize>,
+    unit: String,
+    min_change: f64,
+    bands: Vec<BandChange>,
+}
+
+#[derive(serde::Serialize)]
+struct BandChange {
+    start_mhz: f64,
+    mean: f64,
+    peak: f64,
+    peak_mhz: f64,
+    change_db: Option<f64>,
+}
+
+fn show_sweeps(data_dir: &Path, session_id: &str, compare: Option<&str>, min_change: f64, out: OutputFormat) -> Result<()> {
+    let recorder = EventRecorder::new(data_dir)?;
+    let sweeps: Vec<SweepSummary> = recorder.load_sweeps(session_id)?;
+    if sweeps.is_empty() && out == OutputFormat::Table {
+        println!("No sweeps recorded in {}.", session_id);
+        return Ok(());
+    }
+    
+    let bands = sweep_bands(&sweeps);
+    let reference = match compare {
+        Some(other) => {
+            let reference: Vec<SweepSummary> = recorder.load_sweeps(other)?;
+            if reference.is_empty() {
+                anyhow::bail!("No sweeps recorded in {}", other);
+            }
+            Some((reference.len(), sweep_bands(&reference)))
+        }
+        None => None,
+    };
+    
+    let mut comparison = SweepComparison {
+        session_id: session_id.to_string(),
+        sweeps: sweeps.len(),
+        compare: compare.map(str::to_string),
+        compare_sweeps: reference.as_ref().map(|(n, _)| *n),
+        unit: sweeps.first().map(|s| s.unit.clone()).unwrap_or_default(),
+        min_change,
+        bands: Vec::new(),
+    };
+    for (key, (mean, peak, peak_mhz)) in &bands {
+        let change = reference.as_ref()
+            .and_then(|(_, r)| r.get(key))
+            .map(|(other_mean, _, _)| mean - other_mean);
+        if reference.is_some() && change.is_none_or(|c| c.abs() < min_change) {
+            continue;
+        }
+        comparison.bands.push(BandChange {
+            start_mhz: *key as f64 / 1000.0,
+            mean: *mean,
+            peak: *peak,
+            peak_mhz: *peak_mhz,
+            change_db: change,
+        });
+    }
+    
+    out.print(&comparison, show_sweep_comparison)
+}
+
+fn show_summaries(session_id: &str, records: &[SummaryRecord], display: &DisplayConfig) {
+    if records.is_empty() {
+        println!("No sensor summaries recorded in {}.", session_id);
+        return;
+    }
+    
+    println!("{:<17} {:<20} {:>7} {:>10} {:>10} {:>10} {:>9}  Unit", "Window", "Sensor", "Count", "Min", "Mean", "Max", "Std");
+    for record in records {
+        let (min, unit) = display.value(record.min, &record.unit);
+        let (mean, _) = display.value(record.mean, &record.unit);
+        let (max, _) = display.value(record.max, &record.unit);
+        // A spread converts by scale only
+        let std_dev = display.value(record.mean + record.std_dev, &record.unit).0 - mean;
+        let window = if record.interval_secs % 60 == 0 {
+            display.datetime_short(record.start.into())
+        } else {
+            display.time(record.start.into())
+        };
+        println!("{:<17} {:<20} {:>7} {:>10.3} {:>10.3} {:>10.3} {:>9.3}  {}",
+            window, record.sensor_name, record.count, min, mean, max, std_dev, unit);
+    }
+}
+
+fn show_sweep_comparison(comparison: &SweepComparison) {
+    match (&comparison.compare, comparison.compare_sweeps) {
+        (Some(other), Some(n)) => println!("{} sweeps in {}, {} in {}", comparison.sweeps, comparison.session_id, n, other),
+        _ => println!("{} sweeps in {}", comparison.sweeps, comparison.session_id),
+    }
+    
+    let unit = &comparison.unit;
+    println!("  Band (MHz)    Mean ({unit})   Peak ({unit}) at MHz   Change (dB)");
+    for band in &comparison.bands {
+        let change = band.change_db.map_or(String::new(), |c| format!("{:+.1}", c));
+        println!("  {:>10.3}    {:>10.1}   {:>10.1} at {:.3}   {:>11}",
+            band.start_mhz, band.mean, band.peak, band.peak_mhz, change);
+    }
+    if comparison.bands.is_empty() {
+        println!("  No band changed by {:.1} dB or more", comparison.min_change);
+    }
+}
+
+fn show_significance(report: &SignificanceReport, display: &DisplayConfig) {
+    let window = |w: &significance::WindowSummary| format!("{} to {}",
+        display.datetime_short(w.start),
+        display.datetime_short(w.end));
+    println!("Session {}: {} ({} bins)", report.session_id, window(&report.investigation), report.investigation.bins);
+    println!("Control: {} ({} bins, {})", window(&report.control), report.control.bins, report.control.sessions.join(", "));
+    println!("{} min bins, {} permutations, seed {}", report.bin_minutes, report.permutations, report.seed);
+    
+    if report.comparisons.is_empty() {
+        println!("Not enough overlapping bins to compare.");
+        return;
+    }
+    println!();
+    println!("  {:<24} {:>12} {:>12} {:<10} {:>8} {:>10}", "", "Session", "Control", "", "p", "Adjusted");
+    for c in &report.comparisons {
+        let (investigation, unit) = display.value(c.investigation, &c.unit);
+        let (control, _) = display.value(c.control, &c.unit);
+        println!("  {:<24} {:>12.2} {:>12.2} {:<10} {:>8.4} {:>10.4}",
+            truncate(&c.subject, 24), investigation, control, unit, c.p_value, c.p_adjusted);
+    }
+    println!();
+    println!("p: share of random relabellings of the bins with a difference at least as large.");
+    println!("Adjusted: Holm correction for the {} tests above.", report.comparisons.len());
+}
+
+/// Minutes after midnight as a clock time
+fn clock_time(minute: u32, display: &DisplayConfig) -> String {
+    let (hour, minute) = (minute / 60 % 24, minute % 60);
+    if display.hour12() {
+        let suffix = if hour < 12 { "AM" } else { "PM" };
+        format!("{}:{:02} {}", (hour + 11) % 12 + 1, minute, suffix)
+    } else {
+        format!("{:02}:{:02}", hour, minute)
+    }
+}
+
+fn show_reliability(report: &ReliabilityReport) {
+    const WIDTH: usize = 30;
+    
+    println!("{} sessions, {} events, {} labelled ({} confirmed, {} debunked)",
+        report.sessions, report.events, report.labelled(), report.confirmed, report.debunked);
+    if report.labelled() == 0 {
+        println!("Label events with `glowbarn-cli label <session> <event> confirmed|debunked` to build the report.");
+        return;
+    }
+    
+    // Bar: share confirmed; '|' marks the mean reported confidence
+    println!("\n  Confidence   Labelled  Reported  Observed");
+    for bin in report.bins.iter().filter(|b| b.labelled() > 0) {
+        let (Some(reported), Some(observed)) = (bin.mean_confidence, bin.observed) else {
+            continue;
+        };
+        let filled = (observed * WIDTH as f64).round() as usize;
+        let marker = ((reported * WIDTH as f64).round() as usize).min(WIDTH - 1);
+        let bar: String = (0..WIDTH)
+            .map(|i| if i == marker { '|' } else if i < filled { '#' } else { '.' })
+            .collect();
+        println!("  {:.2} - {:.2}  {:>8}  {:>8.2}  {:>8.2}  {}", bin.lower, bin.upper, bin.labelled(), reported, observed, bar);
+    }
+    
+    if let (Some(brier), Some(error)) = (report.brier_score, report.calibration_error) {
+        println!("\nBrier score {:.3}, expected calibration error {:.3}", brier, error);
+    }
+    if report.remap.is_empty() {
+        println!("Too few labelled events per bin to suggest a remapping.");
+    } else {
+        println!("Suggested remapping (reported -> empirical):");
+        for point in &report.remap {
+            println!("  {:.2} -> {:.2}  ({} events)", point.reported, point.calibrated, point.events);
+        }
+    }
+}
+
+fn show_history(history: &LocationHistory, display: &DisplayConfig) {
+    let zone = |zone: &Option<String>| zone.clone().unwrap_or_else(|| "(no zone)".to_string());
+    println!("{}: {} sessions, {:.1} h recorded, {} events", history.location, history.sessions, history.recorded_hours, history.events);
+    if let (Some(first), Some(last)) = (history.first_event, history.last_event) {
+        println!("Events from {} to {}", display.datetime_short(first), display.datetime_short(last));
+    }
+    let clock = if history.utc { "UTC" } else { "local time" };
+    
+    println!("\nRecurring patterns ({}):", clock);
+    if history.patterns.is_empty() {
+        println!("  None");
+    }
+    for pattern in &history.patterns {
+        println!("  {:<20} {:<20} {} ±{} min  in {} of {} sessions ({} events)",
+            truncate(&zone(&pattern.zone), 20),
+            format!("{:?}", pattern.event_type),
+            clock_time(pattern.minute, display),
+            pattern.spread_minutes.div_ceil(2),
+            pattern.sessions.len(),
+            pattern.covered,
+            pattern.events);
+    }
+    
+    println!("\nTime-of-day hotspots ({} min bins, {}):", history.bin_minutes, clock);
+    if history.hotspots.is_empty() {
+        println!("  None");
+    }
+    for hotspot in history.hotspots.iter().take(10) {
+        println!("  {:>8} - {:<8}  in {} of {} sessions ({:.0}%), {} events",
+            clock_time(hotspot.start_minute, display),
+            clock_time(hotspot.end_minute, display),
+            hotspot.sessions,
+            hotspot.covered,
+            hotspot.recurrence * 100.0,
+            hotspot.events);
+    }
     
-    /// System information
-    Info,
+    println!("\nZones:");
+    for activity in &history.zones {
+        let types: Vec<String> = activity.types.iter().map(|(t, n)| format!("{} {}", t, n)).collect();
+        println!("  {:<20} {:>6} events {:>6.2}/h  in {} sessions  {}",
+            truncate(&zone(&activity.zone), 20),
+            activity.events,
+            activity.per_hour,
+            activity.sessions,
+            types.join(", "));
+    }
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-    
-    match cli.command {
-        Commands::Sessions { verbose } => {
-            list_sessions(&cli.data_dir, verbose)?;
-        }
-        
-        Commands::Events { session_id, event_type, min_confidence, format } => {
-            show_events(&cli.data_dir, &session_id, event_type, min_confidence, &format)?;
+fn export_session(data_dir: &Path, session_id: &str, output: &Path, format: &str, mut redaction: Option<Redaction>,
+                  out: OutputFormat, display: &DisplayConfig) -> Result<()> {
+    let recorder = EventRecorder::new(data_dir)?;
+    let mut summary = serde_json::json!({
+        "session_id": session_id,
+        "format": format,
+        "output": output,
+        "redacted": redaction.is_some(),
+    });
+    let mut load = || -> Result<SessionExport> {
+        let mut export = recorder.session_export(session_id)?;
+        if let Some(redaction) = &mut redaction {
+            redaction.export(&mut export);
         }
-        
-        Commands::Export { session_id, output } => {
-            export_session(&cli.data_dir, &session_id, &output)?;
+        Ok(export)
+    };
+    
+    match format {
+        "json" => EventRecorder::write_export(&load()?, output)?,
+        "geojson" => {
+            let events = load()?.events;
+            let geojson = export::to_geojson(&events);
+            std::fs::write(output, serde_json::to_string_pretty(&geojson)?)?;
+            let located = geojson["features"].as_array().map_or(0, |f| f.len());
+            if out == OutputFormat::Table {
+                println!("{} of {} events located", located, events.len());
+            }
+            summary["located"] = located.into();
+            summary["events"] = events.len().into();
         }
-        
-        Commands::Sensors => {
-            show_sensors()?;
+        "kml" => {
+            let events = load()?.events;
+            std::fs::write(output, export::to_kml(session_id, &events))?;
         }
-        
-        Commands::Config { output } => {
-            generate_config(output)?;
+        "html" => {
+            let SessionExport { session, events, .. } = load()?;
+            let samples = recorder.load_sensor_records(session_id)?;
+            let significance = recorder.load_significance(session_id)?;
+            std::fs::write(output, export::to_timeline_html(&session, &events, &samples, significance.as_ref(), display))?;
         }
-        
-        Commands::Info => {
-            show_info()?;
+        "heatmap" => {
+            let heatmaps = recorder.load_heatmaps(session_id)?;
+            if heatmaps.is_empty() {
+                anyhow::bail!("No motion heatmaps saved in {}", session_id);
+            }
+            std::fs::create_dir_all(output)?;
+            let mut written = Vec::new();
+            for heatmap in &heatmaps {
+                let path = output.join(format!("{}.png", heatmap.camera));
+                std::fs::write(&path, heatmap.to_png())?;
+                if out == OutputFormat::Table {
+                    println!("{}: {} frames -> {}", heatmap.camera, heatmap.frames, path.display());
+                }
+                written.push(serde_json::json!({ "camera": heatmap.camera, "frames": heatmap.frames, "path": path }));
+            }
+            summary["heatmaps"] = written.into();
         }
+        _ => anyhow::bail!("Unknown export format: {} (expected json, geojson, kml, html or heatmap)", format),
     }
     
-    Ok(())
+    out.print(&summary, |_| println!("Session exported to: {:?}", output))
+}
+
+fn import_session(data_dir: &Path, file: &Path, out: OutputFormat) -> Result<()> {
+    let export = EventRecorder::load_export(file)?;
+    let unknown = export.events.iter().filter(|e| e.event_type == EventType::Unknown).count();
+    EventRecorder::new(data_dir)?.import_export(&export)?;
+    
+    let summary = serde_json::json!({
+        "session_id": export.session.id,
+        "events": export.events.len(),
+        "unknown_events": unknown,
+        "schema_version": export.schema_version,
+    });
+    out.print(&summary, |_| {
+        if export.schema_version > SCHEMA_VERSION {
+            println!("Note: exported with schema {} (this CLI reads {}); newer fields are dropped",
+                export.schema_version, SCHEMA_VERSION);
+        }
+        println!("Imported session {} ({} events)", export.session.id, export.events.len());
+        if unknown > 0 {
+            println!("{} events have types this version does not know (shown as Unknown)", unknown);
+        }
+    })
 }
 
-fn list_sessions(data_dir: &PathBuf, verbose: bool) -> Result<()> {
+fn build_review(
+    data_dir: &Path,
+    session_id: &str,
+    output: &Path,
+    video: Option<&Path>,
+    audio: Option<&Path>,
+    media_offset: f64,
+    out: OutputFormat,
+) -> Result<()> {
     let recorder = EventRecorder::new(data_dir)?;
-    let sessions = recorder.list_sessions()?;
+    let session = recorder.load_session(session_id)?;
+    let events = recorder.load_events(session_id)?;
+    let media_start = session.start_time + chrono::Duration::milliseconds((media_offset * 1000.0) as i64);
     
-    if sessions.is_empty() {
-        println!("No recording sessions found.");
-        return Ok(());
-    }
+    std::fs::create_dir_all(output)?;
+    let srt_path = output.join("events.srt");
+    let chapters_path = output.join("chapters.txt");
+    std::fs::write(&srt_path, export::to_srt(media_start, &events))?;
+    std::fs::write(&chapters_path, export::to_ffmetadata(&session.name, media_start, &events))?;
+    let mut summary = serde_json::json!({
+        "session_id": session_id,
+        "subtitles": srt_path,
+        "chapters": chapters_path,
+    });
     
-    println!("╭────────────────────────────────────────────────────────────────────╮");
-    println!("│                      Recording Sessions                             │");
-    println!("├────────────────────┬──────────────────────┬────────────┬───────────┤");
-    println!("│ Session ID         │ Name                 │ Events     │ Duration  │");
-    println!("├────────────────────┼──────────────────────┼────────────┼───────────┤");
+    if let Some(audio) = audio {
+        let cue_path = output.join("audio.cue");
+        let audio_name = audio.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        std::fs::write(&cue_path, export::to_cue_sheet(&session.name, &audio_name, media_start, &events))?;
+        summary["cue_sheet"] = serde_json::json!(cue_path);
+    }
     
-    for session in &sessions {
-        let duration = session.duration();
-        let duration_str = format!("{}:{:02}:{:02}",
-            duration.num_hours(),
-            duration.num_minutes() % 60,
-            duration.num_seconds() % 60);
+    if let Some(video) = video {
+        let reviewed = output.join("review.mkv");
+        let status = std::process::Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-i"])
+            .arg(video)
+            .arg("-i").arg(&srt_path)
+            .arg("-i").arg(&chapters_path)
+            .args(["-map", "0", "-map", "1", "-map_chapters", "2", "-c", "copy", "-c:s", "srt"])
+            .args(["-metadata:s:s:0", "title=GlowBarn events"])
+            .arg(&reviewed)
+            .status()
+            .map_err(|e| anyhow::anyhow!("Failed to run ffmpeg: {}", e))?;
         
-        println!("│ {:18} │ {:20} │ {:>10} │ {:>9} │",
-            truncate(&session.id, 18),
-            truncate(&session.name, 20),
-            session.event_count,
-            duration_str);
+        if !status.success() {
+            anyhow::bail!("ffmpeg exited with {}", status);
+        }
+        summary["video"] = serde_json::json!(reviewed);
     }
     
-    println!("╰────────────────────┴──────────────────────┴────────────┴───────────╯");
+    out.print(&summary, |summary| {
+        println!("Subtitles: {}", summary["subtitles"]);
+        println!("Chapters:  {}", summary["chapters"]);
+        if let Some(audio) = audio {
+            let audio_name = audio.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            println!("Cue sheet: {} (place next to {})", summary["cue_sheet"], audio_name);
+        }
+        if video.is_some() {
+            println!("Review video: {}", summary["video"]);
+        }
+    })
+}
+
+/// Outcome of a config request to one node
+#[derive(serde::Serialize)]
+struct NodeResult {
+    node: String,
+    ok: bool,
+    /// Applied config version and restart flag, on success
+    #[serde(skip_serializing_if = "Option::is_none")]
+    applied: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Send a config request to every node, reporting each result
+fn send_to_nodes(nodes: &[String], method: &str, path: &str, token: Option<&str>, body: Option<&str>, out: OutputFormat) -> Result<()> {
+    let mut results = Vec::new();
     
-    if verbose {
-        for session in &sessions {
-            println!("\n{}", "─".repeat(60));
-            println!("Session: {}", session.id);
-            println!("  Name: {}", session.name);
-            println!("  Location: {}", session.location);
-            println!("  Start: {}", session.start_time);
-            if let Some(end) = session.end_time {
-                println!("  End: {}", end);
+    for node in nodes {
+        let mut request = ureq::request(method, &format!("http://{}{}", node, path));
+        if let Some(token) = token {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+        let result = match body {
+            Some(body) => request.set("Content-Type", "application/toml").send_string(body),
+            None => request.call(),
+        };
+        
+        let mut outcome = NodeResult { node: node.clone(), ok: false, applied: None, status: None, error: None };
+        match result {
+            Ok(response) => {
+                outcome.ok = true;
+                outcome.applied = Some(response.into_json()?);
             }
-            println!("  Events: {}", session.event_count);
-            
-            if !session.notes.is_empty() {
-                println!("  Notes:");
-                for note in &session.notes {
-                    println!("    - {}", note);
-                }
+            Err(ureq::Error::Status(code, response)) => {
+                outcome.status = Some(code);
+                outcome.error = Some(response.into_string().unwrap_or_default().trim().to_string());
             }
+            Err(e) => outcome.error = Some(e.to_string()),
         }
+        results.push(outcome);
     }
     
+    out.print(&results, |results| {
+        for result in results {
+            match (&result.applied, result.status) {
+                (Some(applied), _) => {
+                    let restart = if applied["restart_required"].as_bool().unwrap_or(false) {
+                        " (restart required)"
+                    } else {
+                        ""
+                    };
+                    println!("✓ {:<24} version {}{}", result.node, applied["version"], restart);
+                }
+                (None, Some(code)) => println!("✗ {:<24} {} {}", result.node, code, result.error.as_deref().unwrap_or_default()),
+                (None, None) => println!("✗ {:<24} {}", result.node, result.error.as_deref().unwrap_or_default()),
+            }
+        }
+    })?;
+    
+    let failed = results.iter().filter(|r| !r.ok).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} nodes failed", failed, nodes.len());
+    }
     Ok(())
 }
 
-fn show_events(data_dir: &PathBuf, session_id: &str, event_type: Option<String>, 
-               min_confidence: Option<f64>, format: &str) -> Result<()> {
-    let recorder = EventRecorder::new(data_dir)?;
-    let mut events = recorder.load_events(session_id)?;
+/// Send one command to the daemon's control socket and return its result
+#[cfg(unix)]
+fn daemon_command(socket: &Path, command: &str) -> Result<serde_json::Value> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
     
-    // Apply filters
-    if let Some(ref et) = event_type {
-        events.retain(|e| format!("{:?}", e.event_type).to_lowercase().contains(&et.to_lowercase()));
+    let mut stream = UnixStream::connect(socket)
+        .map_err(|e| anyhow::anyhow!("glowbarn daemon not reachable at {:?}: {}", socket, e))?;
+    writeln!(stream, "{}", command.trim())?;
+    
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let response: serde_json::Value = serde_json::from_str(&line)?;
+    
+    if response["ok"].as_bool().unwrap_or(false) {
+        Ok(response["result"].clone())
+    } else {
+        anyhow::bail!("{}", response["error"].as_str().unwrap_or("command failed"))
+    }
+}
+
+#[cfg(not(unix))]
+fn daemon_command(_socket: &Path, _command: &str) -> Result<serde_json::Value> {
+    anyhow::bail!("the control socket is only available on Unix")
+}
+
+fn show_checklist(checklist: &serde_json::Value) {
+    println!("Pre-flight: {}", checklist["template"].as_str().unwrap_or_default());
+    let mut passed = true;
+    for check in checklist["checks"].as_array().into_iter().flatten() {
+        let ok = check["passed"].as_bool().unwrap_or(false);
+        passed &= ok;
+        println!("  {} {:<28} {}",
+            if ok { "✓" } else { "✗" },
+            check["item"].as_str().unwrap_or_default(),
+            check["detail"].as_str().unwrap_or_default());
+    }
+    println!("{}", if passed { "Ready" } else { "NOT READY" });
+}
+
+/// Socket command scheduling `spec`
+fn fault_command(spec: &FaultSpec) -> String {
+    let mut command = match spec.kind {
+        FaultKind::Timeout { delay_ms } => format!("fault {} timeout delay={}", spec.sensor, delay_ms),
+        FaultKind::Garbage => format!("fault {} garbage", spec.sensor),
+        FaultKind::BusError { probability } => format!("fault {} bus_error p={}", spec.sensor, probability),
+        FaultKind::Disappear => format!("fault {} disappear", spec.sensor),
+    };
+    if spec.after_secs > 0.0 {
+        command.push_str(&format!(" after={}", spec.after_secs));
+    }
+    if let Some(duration) = spec.duration_secs {
+        command.push_str(&format!(" for={}", duration));
+    }
+    command
+}
+
+fn show_fault(fault: &serde_json::Value) {
+    let kind = serde_json::from_value::<FaultKind>(fault.clone())
+        .map_or_else(|_| fault["kind"].to_string(), |kind| kind.to_string());
+    let state = if fault["active"].as_bool().unwrap_or(false) {
+        "active".to_string()
+    } else {
+        format!("starts in {:.0} s", fault["starts_in_secs"].as_f64().unwrap_or(0.0))
+    };
+    let ends = fault["remaining_secs"].as_f64().map_or("until cleared".to_string(), |s| format!("{:.0} s left", s));
+    println!("  #{} {}: {} - {}, {}, {} reads hit", fault["id"], fault["sensor"].as_str().unwrap_or_default(),
+        kind, state, ends, fault["hits"]);
+}
+
+fn show_sensor_health(health: &serde_json::Value) {
+    let state = if health["paused"].as_bool().unwrap_or(false) {
+        "paused"
+    } else if health["offline"].as_bool().unwrap_or(false) {
+        "offline"
+    } else if health["ready"].as_bool().unwrap_or(false) {
+        "polling"
+    } else {
+        "not ready"
+    };
+    let rate = health["poll_rate_hz"].as_f64().map_or("global interval".to_string(), |hz| format!("{} Hz", hz));
+    println!("{} ({}): {}, {}", health["name"].as_str().unwrap_or_default(), health["device_type"].as_str().unwrap_or_default(), state, rate);
+    println!("  {} failed reads ({} in a row)", health["read_errors"], health["consecutive_failures"]);
+    if let Some(error) = health["last_error"].as_str() {
+        println!("  Last error: {}", error);
+    }
+    if health["stuck"].as_bool().unwrap_or(false) {
+        println!("  Suspected stuck: {} identical reads", health["identical_reads"]);
     }
+}
+
+fn show_status(status: &serde_json::Value, display: &DisplayConfig) {
+    let empty = Vec::new();
+    let uptime = status["uptime_secs"].as_u64().unwrap_or(0);
+    println!("Location:       {}", status["location"].as_str().unwrap_or_default());
+    println!("Config version: {}", status["config_version"]);
+    println!("Uptime:         {}h {:02}m", uptime / 3600, uptime / 60 % 60);
     
-    if let Some(min_conf) = min_confidence {
-        events.retain(|e| e.confidence >= min_conf);
+    match status["session"].as_object() {
+        Some(session) => println!("Recording:      {} ({} events)",
+            session["name"].as_str().unwrap_or_default(),
+            session["event_count"]),
+        None => println!("Recording:      idle"),
     }
     
-    if events.is_empty() {
-        println!("No events found matching criteria.");
-        return Ok(());
+    let resources = &status["resources"];
+    println!("Memory:         {} MB (system {} / {} MB)",
+        resources["memory_mb"].as_u64().map_or("-".to_string(), |mb| mb.to_string()),
+        resources["system_memory_used_mb"],
+        resources["system_memory_total_mb"]);
+    println!("Load:           {:.2}", resources["load_average"][0].as_f64().unwrap_or_default());
+    if let Some(temperature) = resources["temperature_c"].as_f64() {
+        println!("Temperature:    {}", display.temperature(temperature));
+    }
+    if let Some(free) = resources["data_free_mb"].as_u64() {
+        println!("Data free:      {} MB", free);
+    }
+    if let Some(poll) = status["poll"].as_object() {
+        println!("Poll overruns:  {} of {} ticks", poll["overruns"], poll["ticks"]);
     }
     
-    match format {
-        "json" => {
-            let json = serde_json::to_string_pretty(&events)?;
-            println!("{}", json);
+    let sensors = status["sensors"].as_array().unwrap_or(&empty);
+    println!("\nSensors ({}):", sensors.len());
+    for sensor in sensors {
+        let deviation = sensor["deviation"].as_f64()
+            .map_or("baselining".to_string(), |z| format!("{:+.2}σ", z));
+        let (value, unit) = display.value(
+            sensor["value"].as_f64().unwrap_or_default(),
+            sensor["unit"].as_str().unwrap_or_default());
+        println!("  {:<24} {:>12.3} {:<6} {}",
+            sensor["sensor_name"].as_str().unwrap_or_default(),
+            value,
+            unit,
+            deviation);
+    }
+    
+    let cameras = status["cameras"].as_array().unwrap_or(&empty);
+    if !cameras.is_empty() {
+        println!("\nCameras ({}):", cameras.len());
+        for camera in cameras {
+            println!("  {:<24} {} captured, {} dropped, {} queued, latency {:.0} ms (worst {:.0} ms)",
+                camera["camera"].as_str().unwrap_or_default(),
+                camera["captured"],
+                camera["dropped"],
+                camera["queue_depth"],
+                camera["mean_latency_ms"].as_f64().unwrap_or_default(),
+                camera["worst_latency_ms"].as_f64().unwrap_or_default());
         }
-        _ => {
-            println!("╭─────────────────────────────────────────────────────────────────────────╮");
-            println!("│                           Event Log                                     │");
-            println!("├────────────────────┬──────────────────────┬──────────────┬─────────────┤");
-            println!("│ Time               │ Event Type           │ Confidence   │ Sensors     │");
-            println!("├────────────────────┼──────────────────────┼──────────────┼─────────────┤");
-            
-            for event in &events {
-                let time = chrono::DateTime::<chrono::Utc>::from(event.timestamp);
-                let time_str = time.format("%H:%M:%S%.3f").to_string();
-                
-                println!("│ {:18} │ {:20} │ {:>10.1}% │ {:>11} │",
-                    time_str,
-                    format!("{:?}", event.event_type),
-                    event.confidence * 100.0,
-                    event.sensor_data.len());
+    }
+    
+    let unhealthy: Vec<&serde_json::Value> = status["devices"].as_array().unwrap_or(&empty).iter()
+        .filter(|d| !d["ready"].as_bool().unwrap_or(true)
+            || d["consecutive_failures"].as_u64().unwrap_or(0) > 0
+            || d["stuck"].as_bool().unwrap_or(false)
+            || d["offline"].as_bool().unwrap_or(false))
+        .collect();
+    if !unhealthy.is_empty() {
+        println!("\nDevice problems:");
+        for device in unhealthy {
+            let name = device["name"].as_str().unwrap_or_default();
+            if device["offline"].as_bool().unwrap_or(false) {
+                println!("  {:<24} offline: {}", name, device["last_error"].as_str().unwrap_or("no readings"));
+            } else if device["stuck"].as_bool().unwrap_or(false) {
+                println!("  {:<24} stuck: {} identical reads", name, device["identical_reads"]);
+            } else {
+                println!("  {:<24} {} failed reads: {}",
+                    name,
+                    device["consecutive_failures"],
+                    device["last_error"].as_str().unwrap_or("not ready"));
             }
-            
-            println!("╰────────────────────┴──────────────────────┴──────────────┴─────────────╯");
-            println!("\nTotal events: {}", events.len());
         }
     }
     
-    Ok(())
+    let triggers = status["triggers"].as_array().unwrap_or(&empty);
+    if status["triggers_armed"].as_bool().unwrap_or(true) {
+        println!("\nTriggers ({}):", triggers.len());
+    } else {
+        println!("\nTriggers ({}, disarmed: actions not run):", triggers.len());
+    }
+    for trigger in triggers {
+        let state = if !trigger["enabled"].as_bool().unwrap_or(true) {
+            "disabled"
+        } else if trigger["value_active"].as_bool().unwrap_or(false) {
+            "active"
+        } else if trigger["last_triggered"].is_null() {
+            "armed"
+        } else {
+            "fired"
+        };
+        let dry_run = if trigger["dry_run"].as_bool().unwrap_or(false) { " (dry run)" } else { "" };
+        println!("  {:<24} {}{}", trigger["name"].as_str().unwrap_or_default(), state, dry_run);
+    }
 }
 
-fn export_session(data_dir: &PathBuf, session_id: &str, output: &PathBuf) -> Result<()> {
-    let recorder = EventRecorder::new(data_dir)?;
-    recorder.export_session(session_id, output)?;
-    println!("Session exported to: {:?}", output);
-    Ok(())
+fn signature_label(valid: Option<bool>) -> &'static str {
+    match valid {
+        Some(true) => "valid",
+        Some(false) => "INVALID",
+        None => "unchecked (no key)",
+    }
+}
+
+fn show_manifest(signed: &SignedManifest, valid: Option<bool>, display: &DisplayConfig) {
+    let empty = Vec::new();
+    let manifest = &signed.manifest;
+    let software = &manifest["software"];
+    let generated = manifest["generated_at"].as_str()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map_or("-".to_string(), |t| display.datetime_short(t.into()));
+    println!("Location:       {}", manifest["location"].as_str().unwrap_or_default());
+    println!("Generated:      {}", generated);
+    println!("Session:        {}", manifest["session_id"].as_str().unwrap_or("none"));
+    println!("Config version: {}", manifest["config_version"]);
+    println!("Software:       glowbarn {} ({})",
+        software["glowbarn"].as_str().unwrap_or_default(),
+        software["features"].as_array().unwrap_or(&empty).iter().filter_map(|f| f.as_str()).collect::<Vec<_>>().join(", "));
+    println!("Host:           {} ({}, kernel {})",
+        software["hostname"].as_str().unwrap_or("-"),
+        software["os"].as_str().unwrap_or("-"),
+        software["kernel"].as_str().unwrap_or("-"));
+    println!("Signature:      {} (key {})", signature_label(valid), signed.signature.key_id);
+    
+    let devices = manifest["devices"].as_array().unwrap_or(&empty);
+    println!("\nDevices ({}):", devices.len());
+    for device in devices {
+        println!("  {:<24} {:<16} {}",
+            device["name"].as_str().unwrap_or_default(),
+            device["device_type"].as_str().unwrap_or_default(),
+            if device["ready"].as_bool().unwrap_or(false) { "ready" } else { "not ready" });
+    }
+    
+    let sensors = manifest["sensors"].as_array().unwrap_or(&empty);
+    println!("\nSensors ({}):", sensors.len());
+    for sensor in sensors {
+        println!("  {:<24} {:<16} {}",
+            sensor["name"].as_str().unwrap_or_default(),
+            sensor["sensor_type"].as_str().unwrap_or_default(),
+            sensor["unit"].as_str().unwrap_or_default());
+    }
+    
+    let calibrations = manifest["calibrations"].as_array().unwrap_or(&empty);
+    if !calibrations.is_empty() {
+        println!("\nCalibrations ({}):", calibrations.len());
+        for calibration in calibrations {
+            println!("  {:<24} offset {:+.4}, {:.0} days old",
+                calibration["device"].as_str().unwrap_or_default(),
+                calibration["offset"].as_f64().unwrap_or_default(),
+                calibration["age_days"].as_f64().unwrap_or_default());
+        }
+    }
+    
+    let triggers = manifest["triggers"].as_array().unwrap_or(&empty);
+    println!("\nTriggers ({}, {}):", triggers.len(),
+        if manifest["triggers_armed"].as_bool().unwrap_or(false) { "armed" } else { "disarmed" });
+    for trigger in triggers {
+        let state = match (trigger["enabled"].as_bool().unwrap_or(false), trigger["dry_run"].as_bool().unwrap_or(false)) {
+            (false, _) => "disabled",
+            (true, true) => "dry run",
+            (true, false) => "enabled",
+        };
+        println!("  {:<24} {}", trigger["name"].as_str().unwrap_or_default(), state);
+    }
+}
+
+fn show_manifests(session_id: &str, manifests: &[SignedManifest], valid: &[Option<bool>], display: &DisplayConfig) {
+    if manifests.is_empty() {
+        println!("No manifests recorded in {}.", session_id);
+        return;
+    }
+    
+    println!("{:<17} {:>7} {:>8} {:>8} {:>9}  {:<10} {:<17} Signature", "Generated", "Config", "Devices", "Sensors", "Triggers", "Version", "Key");
+    for (signed, valid) in manifests.iter().zip(valid) {
+        let manifest = &signed.manifest;
+        let count = |field: &str| manifest[field].as_array().map_or(0, |a| a.len());
+        let generated = manifest["generated_at"].as_str()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map_or("-".to_string(), |t| display.datetime_short(t.into()));
+        println!("{:<17} {:>7} {:>8} {:>8} {:>9}  {:<10} {:<17} {}",
+            generated,
+            manifest["config_version"].as_u64().unwrap_or_default(),
+            count("devices"),
+            count("sensors"),
+            count("triggers"),
+            manifest["software"]["glowbarn"].as_str().unwrap_or_default(),
+            signed.signature.key_id,
+            signature_label(*valid));
+    }
+}
+
+/// Hardware visible to this host
+#[derive(serde::Serialize)]
+struct SensorScan {
+    i2c: Vec<I2cScan>,
+    usb: Vec<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usb_error: Option<String>,
+    cameras: Vec<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    camera_error: Option<String>,
 }
 
-fn show_sensors() -> Result<()> {
+#[derive(serde::Serialize)]
+struct I2cScan {
+    bus: String,
+    /// Kernel adapter name, usable in `i2c_buses` and aliases
+    #[serde(skip_serializing_if = "Option::is_none")]
+    adapter: Option<String>,
+    devices: Vec<glowbarn_hal::I2cDevice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Split a scan result into its items and error
+fn scanned<T>(result: Result<Vec<T>, glowbarn_hal::HalError>) -> (Vec<T>, Option<String>) {
+    match result {
+        Ok(items) => (items, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    }
+}
+
+fn scan_sensors() -> SensorScan {
     use glowbarn_hal::{i2c, usb, camera};
     
+    let i2c = ["/dev/i2c-0", "/dev/i2c-1", "/dev/i2c-2"].into_iter()
+        .filter(|bus| std::path::Path::new(bus).exists())
+        .map(|bus| {
+            let (devices, error) = scanned(i2c::identify_bus(bus));
+            I2cScan { bus: bus.to_string(), adapter: i2c::adapter_name(bus), devices, error }
+        })
+        .collect();
+    
+    let (usb, usb_error) = scanned(usb::enumerate_devices());
+    let usb = usb.iter()
+        .map(|device| serde_json::json!({
+            "vendor_id": device.vendor_id,
+            "product_id": device.product_id,
+            "manufacturer": device.manufacturer,
+            "product": device.product,
+        }))
+        .collect();
+    let (cameras, camera_error) = scanned(camera::enumerate_cameras());
+    
+    SensorScan { i2c, usb, usb_error, cameras, camera_error }
+}
+
+fn show_sensors(scan: &SensorScan) {
     println!("╭──────────────────────────────────────────────────────────────╮");
     println!("│                     Sensor Status                            │");
     println!("╰──────────────────────────────────────────────────────────────╯\n");
     
     // I2C devices
     println!("I2C Devices:");
-    for bus in ["/dev/i2c-0", "/dev/i2c-1", "/dev/i2c-2"] {
-        if std::path::Path::new(bus).exists() {
-            print!("  {}: ", bus);
-            match i2c::scan_bus(bus) {
-                Ok(devices) => {
-                    if devices.is_empty() {
-                        println!("No devices found");
-                    } else {
-                        println!("{}", devices.iter()
-                            .map(|d| format!("0x{:02X}", d))
-                            .collect::<Vec<_>>()
-                            .join(", "));
-                    }
-                }
-                Err(e) => println!("Error: {}", e),
+    for bus in &scan.i2c {
+        match &bus.adapter {
+            Some(adapter) => print!("  {} ({}): ", bus.bus, adapter),
+            None => print!("  {}: ", bus.bus),
+        }
+        if let Some(e) = &bus.error {
+            println!("Error: {}", e);
+        } else if bus.devices.is_empty() {
+            println!("No devices found");
+        } else {
+            println!();
+            for device in &bus.devices {
+                println!("    {}", device);
             }
         }
     }
     
     // USB devices
     println!("\nUSB Devices:");
-    match usb::enumerate_devices() {
-        Ok(devices) => {
-            for device in devices {
-                println!("  {:04X}:{:04X} - {} {}",
-                    device.vendor_id, device.product_id,
-                    device.manufacturer, device.product);
-            }
-        }
-        Err(e) => println!("  Error: {}", e),
+    if let Some(e) = &scan.usb_error {
+        println!("  Error: {}", e);
+    }
+    for device in &scan.usb {
+        println!("  {:04X}:{:04X} - {} {}",
+            device["vendor_id"].as_u64().unwrap_or_default(),
+            device["product_id"].as_u64().unwrap_or_default(),
+            device["manufacturer"].as_str().unwrap_or_default(),
+            device["product"].as_str().unwrap_or_default());
     }
     
     // Cameras
     println!("\nCameras:");
-    match camera::enumerate_cameras() {
-        Ok(cameras) => {
-            for cam in cameras {
-                println!("  {:?}", cam);
-            }
-        }
-        Err(e) => println!("  Error: {}", e),
+    if let Some(e) = &scan.camera_error {
+        println!("  Error: {}", e);
+    }
+    for cam in &scan.cameras {
+        println!("  {:?}", cam);
     }
-    
-    Ok(())
 }
 
-fn generate_config(output: Option<PathBuf>) -> Result<()> {
+fn generate_config(output: Option<PathBuf>, out: OutputFormat) -> Result<()> {
     let example = r#"# GlowBarn Configuration File
 # 
 # Copy this file to /etc/glowbarn/config.toml or ~/.config/glowbarn/config.toml
@@ -288,9 +2019,34 @@ data_directory = "/var/lib/glowbarn/data"
 # Auto-start recording on launch
 auto_record = true
 
-# I2C bus paths
+# Seconds allowed at shutdown for queued readings, events and trigger
+# actions to finish; recordings are flushed and devices closed regardless,
+# and the outcome saved to <data_directory>/shutdown.json
+shutdown_timeout_secs = 15
+
+# Disarmed, triggers are still evaluated and their firings logged and
+# audited as dry runs, but no action runs (`glowbarn-cli arm` / `disarm`).
+# Triggers listed in dry_run_triggers start that way on their own, e.g. to
+# watch a new one on live data before it is allowed to act
+triggers_armed = true
+dry_run_triggers = []
+
+# I2C bus paths, or adapter names as shown by `glowbarn-cli scan`
 i2c_buses = ["/dev/i2c-1"]
 
+# Register drivers for known chips found on the I2C buses (HMC5883L,
+# BME280, MLX90614); set to false to register aliased chips only
+i2c_auto_detect = true
+
+# Logical names for I2C sensors, kept when the bus is renumbered; aliased
+# chips are registered even without i2c_auto_detect. Further channels of a
+# chip take the alias as a prefix ("attic.humidity", "attic.pressure")
+# [[sensor_alias]]
+# name = "attic"
+# model = "BME280"
+# address = 0x76
+# bus = "bcm2835 (i2c@7e804000)"   # adapter name or path; any bus if unset
+
 # SPI device paths  
 spi_devices = ["/dev/spidev0.0"]
 
@@ -300,6 +2056,13 @@ gpio_chip = "/dev/gpiochip0"
 # Sensor poll interval in milliseconds
 poll_interval_ms = 100
 
+# Identical consecutive reads after which a sensor is suspected stuck (0 disables)
+stuck_reads = 200
+
+# Seconds without a successful read before a sensor is reported offline and
+# re-initialised (0 disables the watchdog)
+watchdog_timeout_secs = 30
+
 # Anomaly detection threshold (standard deviations)
 anomaly_threshold = 2.5
 
@@ -311,55 +2074,549 @@ correlation_window_ms = 5000
 
 # Minimum confidence for reporting events (0.0 - 1.0)
 min_confidence = 0.4
+
+# Seconds of readings before an event attached to it (0 disables)
+history_secs = 10
+
+# How event confidence is rated: "threshold" (z-score plus a bonus per
+# correlated sensor) or "kalman" (posterior probability that the sensor's
+# group is active, see [kalman])
+fusion_mode = "threshold"
+
+# Log every I2C/SPI/serial transaction to the session's bus.jsonl
+bus_logging = false
+
+# Allow `glowbarn-cli fault` to inject simulated sensor faults (timeouts,
+# garbage data, bus errors, vanishing devices) for resilience testing
+fault_injection = false
+
+# Event stream: curl -N http://<device>:8765/events (SSE) or /events.ndjson;
+# the dashboard with live graphs and session controls is at http://<device>:8765/
+http_listen = "0.0.0.0:8765"
+dashboard = true
+summary_interval_secs = 10
+
+# Prometheus metrics at http://<device>:8765/metrics (readings per sensor,
+# events per type, trigger firings, queue fill, device read errors)
+metrics = false
+
+# gRPC remote API (app/proto/glowbarn.proto): live readings and events,
+# session control; calls need the api_token when one is set
+# rpc_listen = "0.0.0.0:8766"
+
+# Write a system snapshot (GET /status) to the session's heartbeat.jsonl
+# every heartbeat_interval_secs (0 disables)
+heartbeat_interval_secs = 300
+
+# Token required to push config or control the node over HTTP
+# api_token = "change-me"
+
+# Local socket for glowbarn-cli status/note/mark/reload/profile/trigger
+control_socket = "/run/glowbarn/control.sock"
+
+# Post events as Grafana annotations
+# [grafana]
+# url = "http://grafana.local:3000"
+# api_key = "glsa_..."
+# dashboard_uid = "glowbarn"
+# tags = ["barn"]
+# min_confidence = 0.5
+
+# Publish readings and events to an MQTT broker, with Home Assistant discovery
+# [mqtt]
+# host = "homeassistant.local"
+# port = 1883
+# username = "glowbarn"
+# password = "..."
+# reading_topic = "glowbarn/sensors/{sensor}"
+# event_topic = "glowbarn/events"
+# status_topic = "glowbarn/status"
+# reading_interval_secs = 5
+# min_confidence = 0.5
+# discovery_prefix = "homeassistant"
+
+# Text-protocol serial sensors
+# [[serial_sensors]]
+# name = "arduino_temp"
+# port = "/dev/ttyACM0"
+# baud = 115200
+# poll_command = "READ"
+# pattern = 'T=(?P<value>-?\d+\.\d+)'
+# unit = "C"
+# scale = 1.0
+
+# Sensors opened by name; `driver` is a chip the HAL ships a driver for
+# (bme280, hmc5883l, mlx90614) or a factory the build registers with
+# HardwareManager::register_ehal_driver
+# [[ehal_sensors]]
+# name = "attic_temp"
+# driver = "bme280"
+# bus = "/dev/i2c-1"
+# address = 0x76
+# offset = 0.0
+
+# Rooms; events are located by the zone of their sensors
+# [[zone]]
+# name = "loft"
+# floor = 1
+# x = 4.0
+# y = 2.5
+# sensors = ["hmc5883l", "mlx90614"]
+
+# Cameras; role is "standard", "thermal" or "nightvision". A thermal
+# camera with a `reference` corrects warm-up drift against a target of known
+# temperature in its view: a sensor on the target, or a fixed setpoint.
+# latency_ms is delay the host cannot see (measure it once against the
+# microphones with a clapper board); measured delays are saved per session
+# [[camera]]
+# name = "loft_thermal"
+# device = "/dev/video2"
+# role = "thermal"
+# zone = "loft"
+# reference = { x = 150, y = 110, width = 6, height = 6, sensor = "blackbody_temp" }
+#
+# [[camera]]
+# name = "stairs_ir"
+# device = "/dev/video0"
+# role = "nightvision"
+# width = 1280
+# height = 720
+# zone = "stairs"
+# latency_ms = 40
+
+# Audio inputs; role is "evp_mic", "ambient" or "infrasound"
+# [[audio]]
+# name = "loft_mic"
+# device = "hw:1,0"
+# role = "evp_mic"
+# sample_rate = 48000
+# zone = "loft"
+# latency_ms = 8
+
+# Named audio outputs with the mixer control that sets their volume.
+# Alert sounds play on the "alerts" route when there is one; trigger
+# actions can change a route's volume or mute it
+# [[audio_route]]
+# name = "alerts"
+# device = "plughw:CARD=Headset,DEV=0"   # operator's earpiece
+# control = "Headset"
+# volume = 60.0
+#
+# [[audio_route]]
+# name = "room"
+# device = "plughw:0,0"
+# control = "PCM"
+# volume = 85.0
+
+# Analog sensors on ADS1256 channels; scale converts volts to `unit`
+# (e.g. 1 / sensitivity for a geophone). Bursts capture a block_secs
+# buffer from them at 30 kSPS
+# [[adc]]
+# name = "floor_geophone"
+# device = "/dev/spidev0.0"
+# channel = 0
+# unit = "m/s"
+# scale = 0.0347
+# zone = "loft"
+
+# Laser tripwire grids; each beam's position along the crossing and height
+# (m) locate passes, reported as MotionDetected events with the break
+# order in `beam_path` (e.g. "door_low>hall_low") and a direction
+# [[laser_grid]]
+# name = "hall_grid"
+# tx_pins = [5, 6, 13]
+# rx_pins = [19, 26, 21]
+# zone = "stairs"
+# beams = [
+#   { name = "door_low", position = 0.0, height = 0.3 },
+#   { name = "door_high", position = 0.0, height = 1.5 },
+#   { name = "hall_low", position = 1.2, height = 0.3 },
+# ]
+# Strobe the transmitters and read each receiver lit and dark, so sunlight
+# or a flashlight on a receiver is not taken for an intact beam
+# strobe = { frequency_hz = 500.0, duty = 0.5, cycles = 3 }
+
+# IR illuminators on PWM channels; with a night vision camera the level
+# follows its frames towards target_brightness (0-255). Above the sustained
+# duty the LEDs only run in bursts until the heat estimate catches up.
+# Override over HTTP with PUT/DELETE /illuminators/<name>
+# [[ir_illuminator]]
+# name = "loft_ir"
+# pwm_channel = 0
+# camera = "loft_nightvision"
+# target_brightness = 90.0
+# max_duty = 1.0
+# thermal = { sustained_duty = 0.6, time_constant_secs = 120.0 }
+
+# Sensor samples in the session's sensors.jsonl: "off", "full", or
+# "adaptive" (only samples more than `delta` standard deviations off
+# baseline, plus one every keepalive_secs per sensor). Whatever the mode,
+# min/max/mean/std of every sensor over each summary_secs window go to
+# summaries.jsonl (glowbarn-cli summaries <session>; 0 disables)
+# [sensor_log]
+# mode = "adaptive"
+# delta = 0.5
+# keepalive_secs = 60
+# summary_secs = 60
+
+# Mains hum (50/60 Hz + harmonics) on audio and EMF waveforms: reported as
+# <sensor>.hum, subtracted before waveform features, and anomalies during a
+# hum surge have their confidence multiplied by `penalty`
+# [hum]
+# enabled = true
+# mains_hz = 50          # detected from 50/60 Hz when unset
+# harmonics = 3
+# reject = true
+# surge_threshold = 2.5
+# penalty = 0.5
+
+# PressureAnomaly events when barometric pressure changes faster than
+# threshold_hpa_per_min over window_secs (doors, drafts); the default
+# cold-spot alert is suppressed for 60 s after one
+# [pressure]
+# window_secs = 10
+# threshold_hpa_per_min = 0.5
+
+# Camera motion heatmaps: cells of cell_px pixels count the frames in which
+# their luma changed by more than `threshold`; saved to the session every
+# save_interval_secs and exported with `export --format heatmap`
+# [heatmap]
+# enabled = true
+# cell_px = 4
+# threshold = 25
+# save_interval_secs = 60
+
+# When a session ends, compare its event rates and sensor readings with a
+# control window (the same hours control_offset_hours earlier, or a whole
+# control_session) using permutation tests; p-values are saved with the
+# session. See `glowbarn-cli significance <session>`
+# [significance]
+# enabled = true
+# control_offset_hours = 24.0
+# # control_session = "session_1700000000"
+# bin_minutes = 10.0
+# permutations = 9999
+
+# Wideband SDR sweep every interval_secs, logged to the session's
+# sweeps.jsonl as mean/peak power per band_mhz band; compare nights with
+# `glowbarn-cli sweeps <session> --compare <other>`
+# [sdr_sweep]
+# device = 0
+# start_mhz = 24.0
+# end_mhz = 1700.0
+# band_mhz = 10.0
+# interval_secs = 600
+# gain_db = 40.2
+
+# Direction finding: on each RfAnomaly a second dongle behind a GPIO RF
+# switch samples the peak frequency on every antenna, and the event gets
+# bearing_deg (power-weighted), bearing_concentration (0 = no preferred
+# direction, 1 = one antenna only) and antenna_powers
+# [rf_direction]
+# device = 1
+# select_pins = [5, 6]             # switch position bits, LSB first
+# antennas = [0, 90, 180, 270]     # bearing of each position (degrees)
+# cycles = 3
+# bandwidth_khz = 25.0
+
+# RfAnomaly events are labelled with the band and service that normally
+# occupy their peak frequency (rf_band, rf_service). `region` picks the
+# built-in plan ("eu", "us" or "any"); local bands are checked first
+# [bands]
+# region = "eu"
+# [[bands.band]]
+# name = "Taxi dispatch"
+# service = "land_mobile"
+# start_mhz = 450.1
+# end_mhz = 450.2
+
+# Per-sensor poll rates (Hz), keyed by sensor name or a part of it; a phase
+# offset (ms) spreads sensors on one bus that share a rate
+# [poll.hmc5883l]
+# rate_hz = 75.0
+# [poll.thermal]
+# rate_hz = 9.0
+# [poll.dht]
+# rate_hz = 0.5
+# phase_ms = 250
+
+# Baseline modes: "gaussian" (standard deviations from the mean),
+# "quantile" (beyond the given two-sided percentile of the last `window`
+# samples, for skewed sensors like PIR counts and RF power) or "robust"
+# (median and MAD of the last `window` samples, so spikes while the
+# baseline is collected don't desensitize the sensor) or "diurnal" (mean
+# and spread kept per hour of the day, older samples counting half as much
+# every `half_life_hours`, for sensors that follow the day like temperature
+# and light). Mode changes apply to new or reset baselines
+# [baseline]
+# mode = "gaussian"
+# percentile = 0.995
+# window = 2000
+# half_life_hours = 72.0
+# [baseline.sensors]
+# motion = "quantile"
+# sdr = "quantile"
+# temp = "diurnal"
+
+# Report an anomaly only once it has lasted `samples` consecutive readings
+# and `secs` seconds, filtering single-sample glitches from cheap sensors
+# [persistence]
+# samples = 1
+# secs = 0.0
+# [persistence.sensors]
+# dht = { samples = 3 }
+# pir = { secs = 2.0 }
+
+# Rate of change at an anomaly's onset, measured over the `window_ms` before
+# it: anomalies arriving at least as fast as their sensor's threshold (units
+# per second, either direction) gain `sudden_boost` confidence, slower drifts
+# to the same level are scaled by `drift_penalty`. Unlisted sensors are
+# rated by z-score alone
+# [rate]
+# window_ms = 2000
+# sudden_boost = 0.15
+# drift_penalty = 0.6
+# [rate.sensors]
+# emf = 5.0
+# temperature = 0.5
+
+# Kalman fusion: each group of sensors (every zone, plus the groups below)
+# shares one activity estimate that all its readings update; sensors in no
+# group are estimated alone. Higher process_noise trusts single readings
+# more, higher measurement_noise less
+# [kalman]
+# process_noise = 1.0
+# measurement_noise = 1.0
+# [kalman.groups]
+# cellar = ["emf_cellar", "BME280", "BME280.humidity"]
+
+# Trigger actions (sounds, notifications, commands) run in the background;
+# each is killed after timeout_secs and its outcome is logged to
+# trigger_audit.jsonl in the data directory
+# [trigger_actions]
+# workers = 4
+# timeout_secs = 30
+# queue_limit = 64
+
+# Sample the sensors behind an event faster for a while and save the
+# waveforms to the session's bursts/<event_id>.json
+# [burst]
+# enabled = true
+# rate_multiplier = 10
+# duration_secs = 30
+# block_secs = 1.0       # full-rate buffer from ADC channels
+# min_confidence = 0.6
+
+# WAV clips of the audio inputs involved in an event, saved to the session's
+# clips/<event_id>_<input>.wav and listed in the event's audio_clip metadata
+# [clips]
+# enabled = true
+# pre_roll_secs = 5.0
+# post_roll_secs = 5.0
+# all_events = false     # clip every input on any event
+
+# Simulated devices for development without hardware; replaces the
+# hardware scan unless exclusive = false. Injections play a scripted anomaly
+# after_secs from start-up, for duration_secs, every repeat_secs
+# [simulation]
+# seed = 1
+# [[simulation.sensor]]
+# name = "emf"
+# unit = "mG"
+# baseline = 0.8
+# waveform = { kind = "sine", amplitude = 0.1, period_secs = 60 }
+# noise = { kind = "gaussian", std_dev = 0.05 }
+# [[simulation.sensor.injection]]
+# kind = "step"          # step, ramp or oscillation
+# offset = 4.0
+# after_secs = 120
+# duration_secs = 5
+# repeat_secs = 600
+# [[simulation.camera]]
+# name = "cam0"
+# [[simulation.camera.injection]]
+# kind = "object"        # object crossing the frame, or flash
+# after_secs = 90
+# [[simulation.sdr]]
+# name = "sdr0"
+# center_mhz = 433.9
+# carriers = [{ kind = "carrier", frequency_mhz = 434.2, power_db = 30 }]
+
+# Session templates: `glowbarn-cli start --template <name>` runs the
+# checklist first and refuses to record unless it passes (warn_only records
+# anyway and notes the failures); `preflight <name>` only runs the checks.
+# A non-empty trigger list arms those triggers and disables the rest
+# [[session_template]]
+# name = "overnight"
+# location = "Hay loft"
+# required_sensors = ["emf", "audio", "thermal"]
+# max_calibration_age_days = 30
+# min_disk_free_mb = 20000
+# triggers = ["emf_spike", "cold_spot"]
+# warn_only = false
+
+# Handheld logger CSVs: `glowbarn-cli import-csv <file> --mapping <name>`
+# adds their readings to a session's sensor log. Columns are named by their
+# header (or numbered from 1 with header = false); time_format is a chrono
+# format, "unix" or "unix_ms", read in the host's time zone unless
+# utc_offset_minutes is set
+# [[csv_import]]
+# name = "k2"
+# delimiter = ";"
+# decimal_comma = true
+# skip_rows = 2
+# date_column = "Date"
+# time_column = "Time"
+# time_format = "%d.%m.%Y %H:%M:%S"
+# clock_correction_secs = -4.0
+# columns = [
+#     { column = "EMF", sensor = "k2.emf", unit = "mG" },
+#     { column = "Temp", sensor = "k2.temperature", unit = "°C" },
+# ]
+
+# How glowbarn-cli tables and HTML reports show values and times. Data is
+# always recorded in SI units (°C, hPa) and UTC; imperial shows °F and inHg.
+# The locale (e.g. "en-US", "de-DE") defaults to LC_TIME/LANG.
+# [display]
+# units = "imperial"
+# locale = "en-US"
+# utc = false
+
+# Automatic re-baselining during quiet windows
+# [[maintenance]]
+# name = "afternoon"
+# start = "14:00"
+# duration_minutes = 15
+# rebaseline = ["emf", "audio"]
+# rezero = ["emf"]
+
+# Physical controls (GPIO pins)
+[controls]
+# mark_button_pin = 22
+# encoder_pins = [17, 27]
+
+# [[controls.profiles]]
+# name = "quiet"
+# anomaly_threshold = 2.0
+# min_confidence = 0.3
 "#;
     
     if let Some(path) = output {
         std::fs::write(&path, example)?;
-        println!("Configuration written to: {:?}", path);
+        out.print(&serde_json::json!({ "path": path }), |_| println!("Configuration written to: {:?}", path))
     } else {
-        println!("{}", example);
+        out.print(&serde_json::json!({ "config": example }), |_| println!("{}", example))
+    }
+}
+
+fn calibrate_sdr(data_dir: &Path, device: u32, serial: Option<&str>, frequency: u64,
+                 source: CalibrationSource, gains: &[f64], out: OutputFormat) -> Result<()> {
+    use glowbarn_hal::{CalibrationStore, HardwareDevice, RtlSdr};
+    
+    let mut sdr = RtlSdr::open(device)?;
+    if let Some(serial) = serial {
+        sdr = sdr.with_serial(serial);
     }
+    sdr.init()?;
+    sdr.set_frequency(frequency)?;
     
-    Ok(())
+    let gains: Vec<i32> = gains.iter().map(|g| (g * 10.0).round() as i32).collect();
+    let calibration = sdr.calibrate_power(source, &gains, 262_144)?;
+    
+    let store = CalibrationStore::open(&data_dir.join("calibration"))?;
+    calibration.save(&store)?;
+    
+    let summary = serde_json::json!({
+        "device": device,
+        "serial": serial,
+        "frequency": frequency,
+        "calibration": calibration,
+        "saved_to": store.dir(),
+    });
+    out.print(&summary, |_| {
+        println!("SDR #{} ({}) at {:.3} MHz:", device, serial.unwrap_or("no serial"), frequency as f64 / 1e6);
+        println!("  Gain (dB)   dBFS → dBm");
+        for (gain, offset) in &calibration.offsets {
+            println!("  {:>9.1}   {:+.1} dB", *gain as f64 / 10.0, offset);
+        }
+        println!("Saved to {}", store.dir().display());
+    })
+}
+
+/// Host and hardware summary
+#[derive(serde::Serialize)]
+struct SystemInfo {
+    hostname: String,
+    os: String,
+    os_version: String,
+    kernel: String,
+    cpu: String,
+    cores: usize,
+    memory_total_mb: u64,
+    memory_used_mb: u64,
+    version: &'static str,
+    hal_version: &'static str,
+    sensors_version: &'static str,
+    /// Whether the default device node of each interface exists
+    hardware: std::collections::BTreeMap<&'static str, bool>,
 }
 
-fn show_info() -> Result<()> {
+fn system_info() -> SystemInfo {
     use sysinfo::System;
     
     let mut sys = System::new_all();
     sys.refresh_all();
     
+    let hardware = [
+        ("i2c", "/dev/i2c-1"),
+        ("spi", "/dev/spidev0.0"),
+        ("gpio", "/dev/gpiochip0"),
+        ("camera", "/dev/video0"),
+    ];
+    
+    SystemInfo {
+        hostname: System::host_name().unwrap_or_default(),
+        os: System::name().unwrap_or_default(),
+        os_version: System::os_version().unwrap_or_default(),
+        kernel: System::kernel_version().unwrap_or_default(),
+        cpu: sys.cpus().first().map(|c| c.brand().to_string()).unwrap_or_else(|| "Unknown".to_string()),
+        cores: sys.cpus().len(),
+        memory_total_mb: sys.total_memory() / 1024 / 1024,
+        memory_used_mb: sys.used_memory() / 1024 / 1024,
+        version: "0.1.0",
+        hal_version: "0.1.0",
+        sensors_version: "0.1.0",
+        hardware: hardware.into_iter()
+            .map(|(name, path)| (name, std::path::Path::new(path).exists()))
+            .collect(),
+    }
+}
+
+fn show_info(info: &SystemInfo) {
     println!("╭──────────────────────────────────────────────────────────────╮");
     println!("│             GlowBarn System Information                      │");
     println!("╰──────────────────────────────────────────────────────────────╯\n");
     
     println!("System:");
-    println!("  Hostname: {}", System::host_name().unwrap_or_default());
-    println!("  OS: {} {}", 
-        System::name().unwrap_or_default(),
-        System::os_version().unwrap_or_default());
-    println!("  Kernel: {}", System::kernel_version().unwrap_or_default());
+    println!("  Hostname: {}", info.hostname);
+    println!("  OS: {} {}", info.os, info.os_version);
+    println!("  Kernel: {}", info.kernel);
     
     println!("\nHardware:");
-    println!("  CPU: {}", sys.cpus().first().map(|c| c.brand()).unwrap_or("Unknown"));
-    println!("  Cores: {}", sys.cpus().len());
-    println!("  Memory: {} MB total, {} MB used",
-        sys.total_memory() / 1024 / 1024,
-        sys.used_memory() / 1024 / 1024);
+    println!("  CPU: {}", info.cpu);
+    println!("  Cores: {}", info.cores);
+    println!("  Memory: {} MB total, {} MB used", info.memory_total_mb, info.memory_used_mb);
     
     println!("\nGlowBarn:");
-    println!("  Version: 0.1.0");
-    println!("  HAL Version: 0.1.0");
-    println!("  Sensors Version: 0.1.0");
+    println!("  Version: {}", info.version);
+    println!("  HAL Version: {}", info.hal_version);
+    println!("  Sensors Version: {}", info.sensors_version);
     
     // Check for hardware
     println!("\nHardware Availability:");
-    println!("  I2C: {}", if std::path::Path::new("/dev/i2c-1").exists() { "✓" } else { "✗" });
-    println!("  SPI: {}", if std::path::Path::new("/dev/spidev0.0").exists() { "✓" } else { "✗" });
-    println!("  GPIO: {}", if std::path::Path::new("/dev/gpiochip0").exists() { "✓" } else { "✗" });
-    println!("  Camera: {}", if std::path::Path::new("/dev/video0").exists() { "✓" } else { "✗" });
-    
-    Ok(())
+    for (label, key) in [("I2C", "i2c"), ("SPI", "spi"), ("GPIO", "gpio"), ("Camera", "camera")] {
+        println!("  {}: {}", label, if info.hardware[key] { "✓" } else { "✗" });
+    }
 }
 
 fn truncate(s: &str, max: usize) -> String {
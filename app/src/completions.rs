@@ -0,0 +1,258 @@
+//! Shell completion scripts generated from the CLI definition
+//!
+//! Walks the clap command tree, so new subcommands and flags are picked up
+//! without touching this module.
+
+use clap::{Arg, Command, ValueEnum};
+use std::fmt::Write;
+
+/// Shells completion scripts can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Completion script for `shell`
+pub fn generate(shell: Shell, command: &Command) -> String {
+    let mut command = command.clone();
+    command.build();
+    match shell {
+        Shell::Bash => bash(&command),
+        Shell::Zsh => zsh(&command),
+        Shell::Fish => fish(&command),
+    }
+}
+
+/// Visible subcommands, without clap's generated `help`
+fn subcommands(command: &Command) -> impl Iterator<Item = &Command> {
+    command.get_subcommands().filter(|c| !c.is_hide_set() && c.get_name() != "help")
+}
+
+fn options(command: &Command) -> impl Iterator<Item = &Arg> {
+    command.get_arguments().filter(|a| !a.is_hide_set() && !a.is_positional())
+}
+
+fn positionals(command: &Command) -> impl Iterator<Item = &Arg> {
+    command.get_arguments().filter(|a| !a.is_hide_set() && a.is_positional())
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_num_args().is_some_and(|n| n.takes_values())
+}
+
+fn possible_values(arg: &Arg) -> Vec<String> {
+    arg.get_possible_values().iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name().to_string())
+        .collect()
+}
+
+/// First line of an item's help
+fn help(text: Option<&clap::builder::StyledStr>) -> String {
+    text.map(|h| h.to_string().lines().next().unwrap_or_default().to_string())
+        .unwrap_or_default()
+}
+
+/// Every `--long` and `-s` spelling of an option
+fn flags(arg: &Arg) -> Vec<String> {
+    let mut flags: Vec<String> = arg.get_long_and_visible_aliases().into_iter().flatten()
+        .map(|l| format!("--{}", l))
+        .collect();
+    flags.extend(arg.get_short_and_visible_aliases().into_iter().flatten().map(|s| format!("-{}", s)));
+    flags
+}
+
+fn bash(root: &Command) -> String {
+    let name = root.get_name();
+    let function = format!("_{}", name.replace('-', "_"));
+    
+    let mut transitions = String::new();
+    let mut words = String::new();
+    let mut values = String::new();
+    let mut stack = vec![(name.to_string(), root)];
+    while let Some((path, command)) = stack.pop() {
+        let mut candidates: Vec<String> = subcommands(command).map(|c| c.get_name().to_string()).collect();
+        for arg in options(command) {
+            candidates.extend(flags(arg));
+            if takes_value(arg) {
+                let choices = possible_values(arg);
+                let reply = if choices.is_empty() {
+                    "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string()
+                } else {
+                    format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", choices.join(" "))
+                };
+                for flag in flags(arg) {
+                    let _ = writeln!(values, "        {}:{}) {}; return ;;", path, flag, reply);
+                }
+            }
+        }
+        let _ = writeln!(words, "        {}) opts=\"{}\" ;;", path, candidates.join(" "));
+        
+        for sub in subcommands(command) {
+            let sub_path = format!("{}__{}", path, sub.get_name());
+            let _ = writeln!(transitions, "            {}:{}) cmd=\"{}\" ;;", path, sub.get_name(), sub_path);
+            stack.push((sub_path, sub));
+        }
+    }
+    
+    format!(r#"{function}() {{
+    local cur prev cmd opts i
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    cmd="{name}"
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{cmd}}:${{COMP_WORDS[i]}}" in
+{transitions}        esac
+    done
+
+    case "${{cmd}}:${{prev}}" in
+{values}    esac
+
+    case "${{cmd}}" in
+{words}    esac
+    COMPREPLY=($(compgen -W "${{opts}}" -- "$cur"))
+}}
+
+complete -o default -F {function} {name}
+"#)
+}
+
+/// Quote for a zsh `_arguments` spec
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh_function(out: &mut String, function: &str, command: &Command) {
+    let mut specs = Vec::new();
+    for arg in options(command) {
+        let help = zsh_escape(&help(arg.get_help()));
+        let action = if takes_value(arg) {
+            let choices = possible_values(arg);
+            if choices.is_empty() {
+                format!(":{}:_files", arg.get_id())
+            } else {
+                format!(":{}:({})", arg.get_id(), choices.join(" "))
+            }
+        } else {
+            String::new()
+        };
+        let repeat = if matches!(arg.get_action(), clap::ArgAction::Append) { "*" } else { "" };
+        for flag in flags(arg) {
+            specs.push(format!("'{}{}[{}]{}'", repeat, flag, help, action));
+        }
+    }
+    
+    let has_subcommands = subcommands(command).next().is_some();
+    if has_subcommands {
+        specs.push("': :->command'".to_string());
+        specs.push("'*:: :->args'".to_string());
+    } else {
+        for arg in positionals(command) {
+            let repeat = if matches!(arg.get_action(), clap::ArgAction::Append) { "*" } else { "" };
+            let choices = possible_values(arg);
+            let action = if choices.is_empty() { "_files".to_string() } else { format!("({})", choices.join(" ")) };
+            specs.push(format!("'{}:{}:{}'", repeat, zsh_escape(&help(arg.get_help())), action));
+        }
+    }
+    
+    let _ = writeln!(out, "{}() {{", function);
+    let _ = writeln!(out, "    local state line");
+    let _ = writeln!(out, "    _arguments -C \\");
+    for spec in &specs[..specs.len() - 1] {
+        let _ = writeln!(out, "        {} \\", spec);
+    }
+    let _ = writeln!(out, "        {}", specs[specs.len() - 1]);
+    
+    if has_subcommands {
+        let _ = writeln!(out, "    case $state in");
+        let _ = writeln!(out, "        command)");
+        let _ = writeln!(out, "            local -a commands");
+        let _ = writeln!(out, "            commands=(");
+        for sub in subcommands(command) {
+            let _ = writeln!(out, "                '{}:{}'", sub.get_name(), zsh_escape(&help(sub.get_about())));
+        }
+        let _ = writeln!(out, "            )");
+        let _ = writeln!(out, "            _describe -t commands 'command' commands");
+        let _ = writeln!(out, "            ;;");
+        let _ = writeln!(out, "        args)");
+        let _ = writeln!(out, "            case $line[1] in");
+        for sub in subcommands(command) {
+            let _ = writeln!(out, "                {}) {}__{} ;;", sub.get_name(), function, sub.get_name().replace('-', "_"));
+        }
+        let _ = writeln!(out, "            esac");
+        let _ = writeln!(out, "            ;;");
+        let _ = writeln!(out, "    esac");
+    }
+    let _ = writeln!(out, "}}\n");
+    
+    for sub in subcommands(command) {
+        zsh_function(out, &format!("{}__{}", function, sub.get_name().replace('-', "_")), sub);
+    }
+}
+
+fn zsh(root: &Command) -> String {
+    let name = root.get_name();
+    let function = format!("_{}", name.replace('-', "_"));
+    let mut out = format!("#compdef {}\n\n", name);
+    zsh_function(&mut out, &function, root);
+    let _ = writeln!(out, "{} \"$@\"", function);
+    out
+}
+
+/// Quote for fish
+fn fish_escape(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn fish_command(out: &mut String, name: &str, path: &[&str], command: &Command) {
+    // Subcommands of `path`, offered until one of them is given
+    let children: Vec<&str> = subcommands(command).map(|c| c.get_name()).collect();
+    let mut condition = match path.last() {
+        None => "__fish_use_subcommand".to_string(),
+        Some(last) => format!("__fish_seen_subcommand_from {}", last),
+    };
+    if !children.is_empty() && !path.is_empty() {
+        condition = format!("{}; and not __fish_seen_subcommand_from {}", condition, children.join(" "));
+    }
+    
+    for sub in subcommands(command) {
+        let _ = writeln!(out, "complete -c {} -n {} -f -a {} -d {}",
+            name, fish_escape(&condition), sub.get_name(), fish_escape(&help(sub.get_about())));
+    }
+    for arg in options(command) {
+        let mut line = format!("complete -c {} -n {}", name, fish_escape(&condition));
+        if let Some(long) = arg.get_long() {
+            let _ = write!(line, " -l {}", long);
+        }
+        if let Some(short) = arg.get_short() {
+            let _ = write!(line, " -s {}", short);
+        }
+        if takes_value(arg) {
+            let choices = possible_values(arg);
+            if choices.is_empty() {
+                line.push_str(" -r -F");
+            } else {
+                let _ = write!(line, " -r -f -a {}", fish_escape(&choices.join(" ")));
+            }
+        }
+        let _ = writeln!(out, "{} -d {}", line, fish_escape(&help(arg.get_help())));
+    }
+    
+    for sub in subcommands(command) {
+        let mut sub_path = path.to_vec();
+        sub_path.push(sub.get_name());
+        fish_command(out, name, &sub_path, sub);
+    }
+}
+
+fn fish(root: &Command) -> String {
+    let mut out = String::new();
+    fish_command(&mut out, root.get_name(), &[], root);
+    out
+}
@@ -1,12 +1,36 @@
 // Application Configuration
 
 use anyhow::Result;
+use glowbarn_hal::{AdcChannelConfig, AudioDeviceConfig, EhalSensorConfig, AudioRouteConfig, CameraConfig, IlluminatorConfig, LaserGridConfig, PollSchedule, SensorAlias, SerialSensorConfig, SimConfig, SweepConfig, DirectionConfig};
+use glowbarn_sensors::actions::ActionPoolConfig;
+use glowbarn_sensors::bands::BandConfig;
+use glowbarn_sensors::baseline::BaselineConfig;
+use glowbarn_sensors::handheld::CsvMapping;
+use glowbarn_sensors::heatmap::HeatmapConfig;
+use glowbarn_sensors::hum::HumConfig;
+use glowbarn_sensors::persistence::PersistenceConfig;
+use glowbarn_sensors::anomaly::RateConfig;
+use glowbarn_sensors::kalman::{FusionMode, KalmanConfig};
+use glowbarn_sensors::maintenance::MaintenanceWindow;
+use glowbarn_sensors::pressure::PressureConfig;
+use glowbarn_sensors::recording::{SensorLogMode, SensorLogPolicy};
+use glowbarn_sensors::significance::SignificanceConfig;
+use glowbarn_sensors::units::DisplayConfig;
+use glowbarn_sensors::zones::ZoneConfig;
+use glowbarn_sensors::{EventType, ParanormalEvent};
+use crate::grafana::GrafanaConfig;
+use crate::preflight::SessionTemplate;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Revision number, bumped by the aggregator on every push
+    #[serde(default)]
+    pub config_version: u64,
+    
     /// Location name for recordings
     #[serde(default = "default_location")]
     pub location: String,
@@ -23,10 +47,18 @@ pub struct AppConfig {
     #[serde(default)]
     pub auto_record: bool,
     
-    /// I2C bus paths
+    /// I2C bus paths or adapter names
     #[serde(default = "default_i2c")]
     pub i2c_buses: Vec<String>,
     
+    /// Register drivers for known chips found on the I2C buses
+    #[serde(default = "default_i2c_auto_detect")]
+    pub i2c_auto_detect: bool,
+    
+    /// Logical names of I2C sensors (`[[sensor_alias]]` sections)
+    #[serde(default, rename = "sensor_alias")]
+    pub sensor_aliases: Vec<SensorAlias>,
+    
     /// SPI device paths
     #[serde(default = "default_spi")]
     pub spi_devices: Vec<String>,
@@ -39,6 +71,21 @@ pub struct AppConfig {
     #[serde(default = "default_poll_interval")]
     pub poll_interval_ms: u64,
     
+    /// Poll rates and phase offsets of individual sensors, keyed by sensor
+    /// name or a part of it (`[poll.<sensor>]`)
+    #[serde(default)]
+    pub poll: BTreeMap<String, PollSchedule>,
+    
+    /// Identical consecutive reads after which a sensor is suspected stuck
+    /// and left out of correlation (0 disables)
+    #[serde(default = "default_stuck_reads")]
+    pub stuck_reads: u32,
+    
+    /// Seconds without a successful read after which a sensor is reported
+    /// offline and re-initialised with backoff (0 disables)
+    #[serde(default = "default_watchdog_timeout")]
+    pub watchdog_timeout_secs: u64,
+    
     /// Anomaly detection threshold (standard deviations)
     #[serde(default = "default_anomaly_threshold")]
     pub anomaly_threshold: f64,
@@ -55,11 +102,446 @@ pub struct AppConfig {
     #[serde(default = "default_min_confidence")]
     pub min_confidence: f64,
     
+    /// Seconds of lead-up readings attached to each event (0 disables)
+    #[serde(default = "default_history_secs")]
+    pub history_secs: u64,
+    
+    /// How event confidence is rated: "threshold" or "kalman"
+    #[serde(default)]
+    pub fusion_mode: FusionMode,
+    
+    /// Kalman fusion noise levels and sensor groups
+    #[serde(default)]
+    pub kalman: KalmanConfig,
+    
+    /// Log every I2C/SPI/serial transaction to the session's bus.jsonl
+    #[serde(default)]
+    pub bus_logging: bool,
+    
+    /// Allow sensor faults to be injected over the control socket
+    /// (resilience testing; never on a rig in use)
+    #[serde(default)]
+    pub fault_injection: bool,
+    
+    /// HTTP listen address for event streaming (unset disables)
+    #[serde(default = "default_http_listen")]
+    pub http_listen: Option<String>,
+    
+    /// Serve the browser dashboard at `/` of the HTTP API
+    #[serde(default = "default_dashboard")]
+    pub dashboard: bool,
+    
+    /// Serve Prometheus metrics at `/metrics` of the HTTP API
+    #[serde(default)]
+    pub metrics: bool,
+    
+    /// gRPC listen address of the remote API (unset disables; needs the
+    /// `rpc` feature)
+    #[serde(default)]
+    pub rpc_listen: Option<String>,
+    
+    /// Interval between streamed sensor summaries in seconds
+    #[serde(default = "default_summary_interval")]
+    pub summary_interval_secs: u64,
+    
+    /// Interval between system snapshots written to the session's
+    /// heartbeat.jsonl in seconds (0 disables)
+    #[serde(default = "default_heartbeat_interval")]
+    pub heartbeat_interval_secs: u64,
+    
+    /// Bearer token required for config pushes and control requests
+    #[serde(default)]
+    pub api_token: Option<String>,
+    
+    /// Local control socket used by `glowbarn-cli` (disabled if unset)
+    #[serde(default = "default_control_socket")]
+    pub control_socket: Option<String>,
+    
+    /// Grafana annotation push (disabled if unset)
+    #[serde(default)]
+    pub grafana: Option<GrafanaConfig>,
+    
+    /// MQTT publishing of readings and events (disabled if unset; needs the
+    /// `mqtt` feature)
+    #[serde(default)]
+    pub mqtt: Option<MqttConfig>,
+    
+    /// Text-protocol serial sensors (device manifest)
+    #[serde(default)]
+    pub serial_sensors: Vec<SerialSensorConfig>,
+    
+    /// Sensors behind external drivers compiled into the build (device
+    /// manifest)
+    #[serde(default)]
+    pub ehal_sensors: Vec<EhalSensorConfig>,
+    
+    /// Rooms and the sensors in them (`[[zone]]` sections)
+    #[serde(default, rename = "zone")]
+    pub zones: Vec<ZoneConfig>,
+    
+    /// Cameras (`[[camera]]` sections)
+    #[serde(default, rename = "camera")]
+    pub cameras: Vec<CameraConfig>,
+    
+    /// Audio inputs (`[[audio]]` sections)
+    #[serde(default, rename = "audio")]
+    pub audio_devices: Vec<AudioDeviceConfig>,
+    
+    /// Named audio outputs (`[[audio_route]]` sections)
+    #[serde(default, rename = "audio_route")]
+    pub audio_routes: Vec<AudioRouteConfig>,
+    
+    /// Analog sensors on ADC channels (`[[adc]]` sections)
+    #[serde(default, rename = "adc")]
+    pub adc_channels: Vec<AdcChannelConfig>,
+    
+    /// Laser tripwire grids (`[[laser_grid]]` sections)
+    #[serde(default, rename = "laser_grid")]
+    pub laser_grids: Vec<LaserGridConfig>,
+    
+    /// IR illuminators for night vision cameras (`[[ir_illuminator]]` sections)
+    #[serde(default, rename = "ir_illuminator")]
+    pub illuminators: Vec<IlluminatorConfig>,
+    
+    /// Scheduled wideband SDR sweep logged to the session (disabled if unset)
+    #[serde(default)]
+    pub sdr_sweep: Option<SweepConfig>,
+    
+    /// Switched-antenna direction finding for RF anomalies (disabled if unset)
+    #[serde(default)]
+    pub rf_direction: Option<DirectionConfig>,
+    
+    /// Session templates and their pre-flight checklists
+    /// (`[[session_template]]` sections)
+    #[serde(default, rename = "session_template")]
+    pub session_templates: Vec<SessionTemplate>,
+    
+    /// Column and time-format mappings of handheld logger CSVs
+    /// (`[[csv_import]]` sections)
+    #[serde(default, rename = "csv_import")]
+    pub csv_imports: Vec<CsvMapping>,
+    
+    /// Quiet windows for automatic re-baselining
+    #[serde(default)]
+    pub maintenance: Vec<MaintenanceWindow>,
+    
+    /// Physical controls for headless rigs
+    #[serde(default)]
+    pub controls: ControlsConfig,
+    
+    /// High-rate capture around detected events
+    #[serde(default)]
+    pub burst: BurstConfig,
+    
+    /// Simulated sensors, cameras and SDRs for development without hardware
+    #[serde(default)]
+    pub simulation: Option<SimConfig>,
+    
+    /// Time allowed for draining queues and finishing actions at shutdown;
+    /// recordings are flushed and devices closed even when it runs out
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout_secs: u64,
+    
+    /// WAV clips of the audio inputs around detected events
+    #[serde(default)]
+    pub clips: ClipConfig,
+    
+    /// Which sensor samples are written to the session's sensors.jsonl
+    #[serde(default)]
+    pub sensor_log: SensorLogConfig,
+    
+    /// Mains hum tracking and rejection on audio and EMF waveforms
+    #[serde(default)]
+    pub hum: HumConfig,
+    
+    /// Barometric pressure trend events
+    #[serde(default)]
+    pub pressure: PressureConfig,
+    
+    /// Band plan that labels RF anomalies with the likely service
+    #[serde(default)]
+    pub bands: BandConfig,
+    
+    /// Per-camera motion heatmaps saved with the session
+    #[serde(default)]
+    pub heatmap: HeatmapConfig,
+    
+    /// Baseline modes: Gaussian z-scores, percentiles or median/MAD
+    #[serde(default)]
+    pub baseline: BaselineConfig,
+    
+    /// Comparison of each finished session with a control window
+    #[serde(default)]
+    pub significance: SignificanceConfig,
+    
+    /// How long anomalies must last before they become events
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    
+    /// Rate-of-change thresholds telling sudden anomalies from drifts
+    #[serde(default)]
+    pub rate: RateConfig,
+    
+    /// Background workers running trigger actions
+    #[serde(default)]
+    pub trigger_actions: ActionPoolConfig,
+    
+    /// Whether triggers run their actions at start-up; disarmed, firings
+    /// are only logged and audited (`glowbarn-cli arm` / `disarm` at runtime)
+    #[serde(default = "default_triggers_armed")]
+    pub triggers_armed: bool,
+    
+    /// Triggers started in dry-run mode: evaluated and logged, actions not run
+    #[serde(default)]
+    pub dry_run_triggers: Vec<String>,
+    
+    /// Units and locale of CLI tables and reports (read by glowbarn-cli)
+    #[serde(default)]
+    pub display: DisplayConfig,
+    
     /// Path to config file (for reference)
     #[serde(skip)]
     pub config_path: PathBuf,
 }
 
+/// Button and encoder wiring
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControlsConfig {
+    /// GPIO pin of the "mark timestamp" button
+    #[serde(default)]
+    pub mark_button_pin: Option<u32>,
+    
+    /// GPIO pins (A, B) of the profile selector knob
+    #[serde(default)]
+    pub encoder_pins: Option<[u32; 2]>,
+    
+    /// Investigation profiles selectable with the knob
+    #[serde(default)]
+    pub profiles: Vec<ProfileConfig>,
+}
+
+/// Burst-mode capture around events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurstConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    
+    /// Burst poll rate as a multiple of `poll_interval_ms`
+    #[serde(default = "default_burst_multiplier")]
+    pub rate_multiplier: u32,
+    
+    /// How long to keep sampling after the event
+    #[serde(default = "default_burst_duration")]
+    pub duration_secs: u64,
+    
+    /// Length of the buffer filled at full rate by sensors with a block
+    /// capture mode (ADC channels) instead of polling
+    #[serde(default = "default_burst_block")]
+    pub block_secs: f64,
+    
+    /// Minimum event confidence that starts a burst
+    #[serde(default = "default_burst_confidence")]
+    pub min_confidence: f64,
+}
+
+impl Default for BurstConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rate_multiplier: default_burst_multiplier(),
+            duration_secs: default_burst_duration(),
+            block_secs: default_burst_block(),
+            min_confidence: default_burst_confidence(),
+        }
+    }
+}
+
+impl BurstConfig {
+    /// Block capture length
+    pub fn block(&self) -> std::time::Duration {
+        std::time::Duration::try_from_secs_f64(self.block_secs).unwrap_or_default()
+    }
+    
+    /// Whether an event should start a burst
+    pub fn applies_to(&self, event: &ParanormalEvent) -> bool {
+        self.enabled
+            && event.event_type != EventType::Maintenance
+            && event.confidence >= self.min_confidence
+            && !event.sensor_data.is_empty()
+    }
+}
+
+/// MQTT broker connection and topics
+///
+/// Readings go to `reading_topic` (with `{sensor}` replaced by the sensor
+/// name), events to `event_topic`, and `online`/`offline` to `status_topic`.
+/// With `discovery` on, Home Assistant picks up each sensor, an event entity
+/// and an activity binary sensor on its own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConfig {
+    /// Broker host name or address
+    pub host: String,
+    
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+    
+    /// MQTT client ID, also the Home Assistant device identifier
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+    
+    #[serde(default)]
+    pub username: Option<String>,
+    
+    #[serde(default)]
+    pub password: Option<String>,
+    
+    #[serde(default = "default_mqtt_reading_topic")]
+    pub reading_topic: String,
+    
+    #[serde(default = "default_mqtt_event_topic")]
+    pub event_topic: String,
+    
+    #[serde(default = "default_mqtt_status_topic")]
+    pub status_topic: String,
+    
+    /// Publish sensor readings, not only events
+    #[serde(default = "default_mqtt_readings")]
+    pub readings: bool,
+    
+    /// Minimum seconds between published readings of one sensor (0 publishes
+    /// every reading)
+    #[serde(default = "default_mqtt_reading_interval")]
+    pub reading_interval_secs: f64,
+    
+    /// Keep each sensor's latest reading on the broker for new subscribers
+    #[serde(default = "default_mqtt_retain")]
+    pub retain_readings: bool,
+    
+    /// Minimum event confidence to publish
+    #[serde(default)]
+    pub min_confidence: f64,
+    
+    /// Quality of service level (0, 1 or 2)
+    #[serde(default)]
+    pub qos: u8,
+    
+    /// Publish Home Assistant discovery configs
+    #[serde(default = "default_mqtt_discovery")]
+    pub discovery: bool,
+    
+    #[serde(default = "default_mqtt_discovery_prefix")]
+    pub discovery_prefix: String,
+    
+    /// Seconds the activity binary sensor stays on after an event
+    #[serde(default = "default_mqtt_activity")]
+    pub activity_secs: u64,
+}
+
+/// Audio clips around events, saved to the session's clips/ directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipConfig {
+    #[serde(default = "default_clips_enabled")]
+    pub enabled: bool,
+    
+    /// Audio kept from before the event
+    #[serde(default = "default_clip_roll")]
+    pub pre_roll_secs: f64,
+    
+    /// Audio recorded after the event
+    #[serde(default = "default_clip_roll")]
+    pub post_roll_secs: f64,
+    
+    /// Clip every audio input on any event, not only the inputs the event involves
+    #[serde(default)]
+    pub all_events: bool,
+}
+
+impl Default for ClipConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pre_roll_secs: default_clip_roll(),
+            post_roll_secs: default_clip_roll(),
+            all_events: false,
+        }
+    }
+}
+
+impl ClipConfig {
+    pub fn pre_roll(&self) -> std::time::Duration {
+        std::time::Duration::try_from_secs_f64(self.pre_roll_secs).unwrap_or_default()
+    }
+    
+    pub fn post_roll(&self) -> std::time::Duration {
+        std::time::Duration::try_from_secs_f64(self.post_roll_secs).unwrap_or_default()
+    }
+    
+    /// Audio inputs to clip for an event, out of `inputs`
+    pub fn inputs_for<'a>(&self, event: &ParanormalEvent, inputs: impl Iterator<Item = &'a String>) -> Vec<String> {
+        if !self.enabled || event.event_type == EventType::Maintenance {
+            return Vec::new();
+        }
+        inputs
+            .filter(|name| self.all_events || event.sensor_data.iter().any(|s| &s.sensor_name == *name))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Sensor sample logging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorLogConfig {
+    /// "off", "full" or "adaptive"
+    #[serde(default = "default_sensor_log_mode")]
+    pub mode: SensorLogMode,
+    
+    /// Deviation (standard deviations) that adaptive mode logs
+    #[serde(default = "default_sensor_log_delta")]
+    pub delta: f64,
+    
+    /// Keepalive interval per sensor in adaptive mode
+    #[serde(default = "default_sensor_log_keepalive")]
+    pub keepalive_secs: u64,
+    
+    /// Window of the per-sensor min/max/mean/std summaries saved to the
+    /// session's summaries.jsonl, in any mode (0 disables)
+    #[serde(default = "default_sensor_summary_secs")]
+    pub summary_secs: u64,
+}
+
+impl Default for SensorLogConfig {
+    fn default() -> Self {
+        Self {
+            mode: default_sensor_log_mode(),
+            delta: default_sensor_log_delta(),
+            keepalive_secs: default_sensor_log_keepalive(),
+            summary_secs: default_sensor_summary_secs(),
+        }
+    }
+}
+
+impl SensorLogConfig {
+    pub fn policy(&self) -> SensorLogPolicy {
+        SensorLogPolicy {
+            mode: self.mode,
+            delta: self.delta,
+            keepalive: std::time::Duration::from_secs(self.keepalive_secs),
+        }
+    }
+    
+    pub fn summary_interval(&self) -> Option<std::time::Duration> {
+        (self.summary_secs > 0).then(|| std::time::Duration::from_secs(self.summary_secs))
+    }
+}
+
+/// Named set of detection settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    pub name: String,
+    pub anomaly_threshold: f64,
+    pub min_confidence: f64,
+}
+
 fn default_location() -> String { "Unknown Location".to_string() }
 fn default_session() -> String { format!("session_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S")) }
 fn default_data_dir() -> String { "/var/lib/glowbarn/data".to_string() }
@@ -67,26 +549,111 @@ fn default_i2c() -> Vec<String> { vec!["/dev/i2c-1".to_string()] }
 fn default_spi() -> Vec<String> { vec!["/dev/spidev0.0".to_string()] }
 fn default_gpio() -> String { "/dev/gpiochip0".to_string() }
 fn default_poll_interval() -> u64 { 100 }
+fn default_stuck_reads() -> u32 { glowbarn_hal::DEFAULT_STUCK_READS }
+fn default_watchdog_timeout() -> u64 { 30 }
 fn default_anomaly_threshold() -> f64 { 2.5 }
 fn default_baseline_samples() -> usize { 100 }
 fn default_correlation_window() -> u64 { 5000 }
 fn default_min_confidence() -> f64 { 0.4 }
+fn default_history_secs() -> u64 { 10 }
+fn default_http_listen() -> Option<String> { Some("0.0.0.0:8765".to_string()) }
+fn default_summary_interval() -> u64 { 10 }
+fn default_heartbeat_interval() -> u64 { 300 }
+fn default_control_socket() -> Option<String> { Some("/run/glowbarn/control.sock".to_string()) }
+fn default_sensor_log_mode() -> SensorLogMode { SensorLogMode::Adaptive }
+fn default_sensor_log_delta() -> f64 { 0.5 }
+fn default_sensor_log_keepalive() -> u64 { 60 }
+fn default_sensor_summary_secs() -> u64 { 60 }
+fn default_triggers_armed() -> bool { true }
+fn default_dashboard() -> bool { true }
+fn default_i2c_auto_detect() -> bool { true }
+fn default_mqtt_port() -> u16 { 1883 }
+fn default_mqtt_client_id() -> String { "glowbarn".to_string() }
+fn default_mqtt_reading_topic() -> String { "glowbarn/sensors/{sensor}".to_string() }
+fn default_mqtt_event_topic() -> String { "glowbarn/events".to_string() }
+fn default_mqtt_status_topic() -> String { "glowbarn/status".to_string() }
+fn default_mqtt_readings() -> bool { true }
+fn default_mqtt_reading_interval() -> f64 { 5.0 }
+fn default_mqtt_retain() -> bool { true }
+fn default_mqtt_discovery() -> bool { true }
+fn default_mqtt_discovery_prefix() -> String { "homeassistant".to_string() }
+fn default_mqtt_activity() -> u64 { 30 }
+fn default_shutdown_timeout() -> u64 { 15 }
+fn default_clips_enabled() -> bool { true }
+fn default_clip_roll() -> f64 { 5.0 }
+fn default_burst_multiplier() -> u32 { 10 }
+fn default_burst_duration() -> u64 { 30 }
+fn default_burst_block() -> f64 { 1.0 }
+fn default_burst_confidence() -> f64 { 0.6 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            config_version: 0,
             location: default_location(),
             session_name: default_session(),
             data_directory: default_data_dir(),
             auto_record: false,
             i2c_buses: default_i2c(),
+            i2c_auto_detect: default_i2c_auto_detect(),
+            sensor_aliases: Vec::new(),
             spi_devices: default_spi(),
             gpio_chip: default_gpio(),
             poll_interval_ms: default_poll_interval(),
+            poll: BTreeMap::new(),
+            stuck_reads: default_stuck_reads(),
+            watchdog_timeout_secs: default_watchdog_timeout(),
             anomaly_threshold: default_anomaly_threshold(),
             baseline_samples: default_baseline_samples(),
             correlation_window_ms: default_correlation_window(),
             min_confidence: default_min_confidence(),
+            history_secs: default_history_secs(),
+            fusion_mode: FusionMode::default(),
+            kalman: KalmanConfig::default(),
+            bus_logging: false,
+            fault_injection: false,
+            http_listen: default_http_listen(),
+            dashboard: default_dashboard(),
+            metrics: false,
+            rpc_listen: None,
+            summary_interval_secs: default_summary_interval(),
+            heartbeat_interval_secs: default_heartbeat_interval(),
+            api_token: None,
+            control_socket: default_control_socket(),
+            grafana: None,
+            mqtt: None,
+            serial_sensors: Vec::new(),
+            ehal_sensors: Vec::new(),
+            zones: Vec::new(),
+            cameras: Vec::new(),
+            audio_devices: Vec::new(),
+            audio_routes: Vec::new(),
+            adc_channels: Vec::new(),
+            laser_grids: Vec::new(),
+            illuminators: Vec::new(),
+            sdr_sweep: None,
+            rf_direction: None,
+            session_templates: Vec::new(),
+            csv_imports: Vec::new(),
+            maintenance: Vec::new(),
+            controls: ControlsConfig::default(),
+            burst: BurstConfig::default(),
+            simulation: None,
+            shutdown_timeout_secs: default_shutdown_timeout(),
+            clips: ClipConfig::default(),
+            sensor_log: SensorLogConfig::default(),
+            hum: HumConfig::default(),
+            pressure: PressureConfig::default(),
+            bands: BandConfig::default(),
+            heatmap: HeatmapConfig::default(),
+            baseline: BaselineConfig::default(),
+            significance: SignificanceConfig::default(),
+            persistence: PersistenceConfig::default(),
+            rate: RateConfig::default(),
+            trigger_actions: ActionPoolConfig::default(),
+            triggers_armed: default_triggers_armed(),
+            dry_run_triggers: Vec::new(),
+            display: DisplayConfig::default(),
             config_path: PathBuf::new(),
         }
     }
@@ -122,6 +689,16 @@ impl AppConfig {
         Ok(config)
     }
     
+    /// Kalman settings, with each zone not named in `[kalman.groups]` added
+    /// as a group of its sensors
+    pub fn kalman_config(&self) -> KalmanConfig {
+        let mut kalman = self.kalman.clone();
+        for zone in self.zones.iter().filter(|zone| !zone.sensors.is_empty()) {
+            kalman.groups.entry(zone.name.clone()).or_insert_with(|| zone.sensors.clone());
+        }
+        kalman
+    }
+    
     /// Save configuration to file
     pub fn save(&self, path: &PathBuf) -> Result<()> {
         let content = toml::to_string_pretty(self)?;
@@ -135,6 +712,7 @@ impl AppConfig {
     }
     
     /// Generate example configuration
+    #[allow(dead_code)]
     pub fn example() -> String {
         let config = Self {
             location: "My Investigation Site".to_string(),
@@ -0,0 +1,1006 @@
+// Live control of a running node
+
+use crate::config::{AppConfig, ProfileConfig};
+use crate::logging::LogLevels;
+use crate::manifest::{self, RigManifest, Software};
+use crate::metrics::Metrics;
+use crate::preflight::{self, Checklist, SessionTemplate};
+use crate::snapshot::{ResourceUsage, SensorState, SystemSnapshot};
+use glowbarn_hal::{buslog, AudioFormat, AudioPlayback, CalibrationStore, DeviceHealth, FaultInjector, FaultSpec, FaultStatus, HardwareManager, IlluminatorMode, IlluminatorStatus, PollSchedule};
+use glowbarn_sensors::fusion::{FusionConfig, FusionEngine};
+use glowbarn_sensors::handheld;
+use glowbarn_sensors::manifest::{SignedManifest, SigningKey};
+use glowbarn_sensors::recording::{Annotation, EventRecorder, RecordingSession};
+use glowbarn_sensors::significance::{self, SignificanceConfig};
+use glowbarn_sensors::actions::AuditEntry;
+use glowbarn_sensors::triggers::{TriggerManager, TriggerState};
+use glowbarn_sensors::SensorSnapshot;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+use tokio::sync::{Mutex, RwLock};
+
+/// Number of superseded configs kept for rollback
+const HISTORY_LIMIT: usize = 10;
+
+/// Control request errors
+#[derive(Debug, thiserror::Error)]
+pub enum ControlError {
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+    
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    
+    #[error("Config version {pushed} is not newer than current version {current}")]
+    StaleVersion { current: u64, pushed: u64 },
+    
+    #[error("No previous config to roll back to")]
+    NoHistory,
+    
+    #[error("Session already recording: {0}")]
+    SessionActive(String),
+    
+    #[error("No session is recording")]
+    NoSession,
+    
+    #[error("Pre-flight check failed for template {template}: {failures}")]
+    PreflightFailed { template: String, failures: String },
+    
+    #[error("Unknown session template: {0}")]
+    UnknownTemplate(String),
+    
+    #[error("Unknown profile: {0}")]
+    UnknownProfile(String),
+    
+    #[error("Unknown session: {0}")]
+    UnknownSession(String),
+    
+    #[error("Unknown CSV import mapping: {0}")]
+    UnknownMapping(String),
+    
+    #[error("Unknown event: {0}")]
+    UnknownEvent(String),
+    
+    #[error("Unknown trigger: {0}")]
+    UnknownTrigger(String),
+    
+    #[error("Unknown illuminator: {0}")]
+    UnknownIlluminator(String),
+    
+    #[error("Unknown sensor: {0}")]
+    UnknownSensor(String),
+    
+    #[error("Unknown audio route: {0}")]
+    UnknownRoute(String),
+    
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Result of applying a config
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigApplied {
+    pub version: u64,
+    /// Settings changed that only take effect after a restart
+    pub restart_required: bool,
+}
+
+/// Result of importing a handheld logger file
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvImported {
+    pub session: String,
+    pub imported: usize,
+    /// Readings outside the session's time span, left out (a wrong logger
+    /// clock or time zone if it is most of them)
+    pub outside_session: usize,
+    /// Rows without a readable timestamp
+    pub skipped_rows: usize,
+}
+
+/// Longest text the intercom will speak
+const MAX_TALK_TEXT: usize = 500;
+
+/// What the intercom plays into the room
+#[derive(Debug, Clone)]
+pub enum Talk {
+    /// PCM WAV clip
+    Clip(Vec<u8>),
+    /// Text for speech synthesis, with an optional espeak-ng voice
+    Text { text: String, voice: Option<String> },
+}
+
+/// Result of an intercom playback
+#[derive(Debug, Clone, Serialize)]
+pub struct TalkPlayed {
+    /// Audio route played on, `None` for the default output
+    pub route: Option<String>,
+    pub device: String,
+    pub duration_secs: f64,
+}
+
+/// Handles shared by the HTTP API and the daemon
+pub struct NodeControl {
+    config: RwLock<AppConfig>,
+    history_dir: PathBuf,
+    fusion: Arc<RwLock<FusionEngine>>,
+    recorder: Arc<RwLock<EventRecorder>>,
+    triggers: Option<Arc<RwLock<TriggerManager>>>,
+    hardware: Option<Arc<HardwareManager>>,
+    log_levels: Option<LogLevels>,
+    metrics: Option<Metrics>,
+    /// Held while the intercom plays, so clips never overlap
+    intercom: Mutex<()>,
+    started: Instant,
+}
+
+/// Current log filter and per-device overrides
+#[derive(Debug, Clone, Serialize)]
+pub struct LogSettings {
+    pub filter: String,
+    pub devices: BTreeMap<String, String>,
+}
+
+impl NodeControl {
+    pub fn new(config: AppConfig, fusion: Arc<RwLock<FusionEngine>>, recorder: Arc<RwLock<EventRecorder>>) -> Self {
+        let history_dir = PathBuf::from(&config.data_directory).join("config_history");
+        
+        Self {
+            config: RwLock::new(config),
+            history_dir,
+            fusion,
+            recorder,
+            triggers: None,
+            hardware: None,
+            log_levels: None,
+            metrics: None,
+            intercom: Mutex::new(()),
+            started: Instant::now(),
+        }
+    }
+    
+    /// Allow triggers to be test-fired
+    pub fn with_triggers(mut self, triggers: Arc<RwLock<TriggerManager>>) -> Self {
+        self.triggers = Some(triggers);
+        self
+    }
+    
+    /// Report device health and poll timing in snapshots
+    pub fn with_hardware(mut self, hardware: Arc<HardwareManager>) -> Self {
+        self.hardware = Some(hardware);
+        self
+    }
+    
+    /// Allow per-device log levels to be changed at runtime
+    pub fn with_log_levels(mut self, log_levels: LogLevels) -> Self {
+        self.log_levels = Some(log_levels);
+        self
+    }
+    
+    /// Report the pipeline's reading, event and queue counters in metrics
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// Currently applied configuration
+    pub async fn config(&self) -> AppConfig {
+        self.config.read().await.clone()
+    }
+    
+    /// Check a bearer token against `api_token` (open if none is configured)
+    pub async fn authorize(&self, token: Option<&str>) -> bool {
+        match self.config.read().await.api_token {
+            Some(ref expected) => token == Some(expected.as_str()),
+            None => true,
+        }
+    }
+    
+    /// Validate, persist and apply a pushed TOML config
+    ///
+    /// The pushed `config_version` must be newer than the running one; the
+    /// replaced config is kept in `config_history/` for [`rollback`](Self::rollback).
+    pub async fn push_config(&self, content: &str) -> Result<ConfigApplied, ControlError> {
+        let new: AppConfig = toml::from_str(content)
+            .map_err(|e| ControlError::InvalidConfig(e.to_string()))?;
+        
+        let mut config = self.config.write().await;
+        if new.config_version <= config.config_version {
+            return Err(ControlError::StaleVersion {
+                current: config.config_version,
+                pushed: new.config_version,
+            });
+        }
+        
+        self.archive(&config)?;
+        self.prune_history()?;
+        persist(&config.config_path, content)?;
+        
+        tracing::info!("Config version {} pushed (was {})", new.config_version, config.config_version);
+        let applied = self.apply(&mut config, new).await;
+        drop(config);
+        self.save_manifest().await;
+        Ok(applied)
+    }
+    
+    /// Re-read the config file and apply it, regardless of `config_version`
+    pub async fn reload(&self) -> Result<ConfigApplied, ControlError> {
+        let mut config = self.config.write().await;
+        if config.config_path.as_os_str().is_empty() {
+            return Err(ControlError::InvalidRequest("running on default config, nothing to reload".to_string()));
+        }
+        
+        let content = std::fs::read_to_string(&config.config_path).map_err(anyhow::Error::from)?;
+        let reloaded: AppConfig = toml::from_str(&content)
+            .map_err(|e| ControlError::InvalidConfig(e.to_string()))?;
+        
+        tracing::info!("Config reloaded from {:?}", config.config_path);
+        let applied = self.apply(&mut config, reloaded).await;
+        drop(config);
+        self.save_manifest().await;
+        Ok(applied)
+    }
+    
+    /// Restore the most recently replaced config
+    pub async fn rollback(&self) -> Result<ConfigApplied, ControlError> {
+        let mut config = self.config.write().await;
+        let (version, path) = self.history()?
+            .pop()
+            .ok_or(ControlError::NoHistory)?;
+        
+        let content = std::fs::read_to_string(&path).map_err(anyhow::Error::from)?;
+        let previous: AppConfig = toml::from_str(&content)
+            .map_err(|e| ControlError::InvalidConfig(format!("{:?}: {}", path, e)))?;
+        
+        persist(&config.config_path, &content)?;
+        std::fs::remove_file(&path).map_err(anyhow::Error::from)?;
+        
+        tracing::info!("Config rolled back from version {} to {}", config.config_version, version);
+        let applied = self.apply(&mut config, previous).await;
+        drop(config);
+        self.save_manifest().await;
+        Ok(applied)
+    }
+    
+    /// Snapshot of the whole node (sensors, devices, session, triggers, resources)
+    pub async fn snapshot(&self) -> SystemSnapshot {
+        let (location, config_version, data_dir) = {
+            let config = self.config.read().await;
+            (config.location.clone(), config.config_version, PathBuf::from(&config.data_directory))
+        };
+        
+        let sensors = self.fusion.read().await.live().all().into_iter()
+            .map(|live| SensorState {
+                latest: live.snapshot(),
+                timestamp: live.reading.timestamp,
+                baseline_stats: live.baseline,
+            })
+            .collect();
+        let (triggers, triggers_armed) = match &self.triggers {
+            Some(triggers) => {
+                let triggers = triggers.read().await;
+                (triggers.states(), triggers.is_armed())
+            }
+            None => (Vec::new(), false),
+        };
+        let resources = tokio::task::spawn_blocking(move || ResourceUsage::sample(&data_dir))
+            .await
+            .unwrap_or_default();
+        
+        SystemSnapshot {
+            timestamp: SystemTime::now(),
+            location,
+            config_version,
+            uptime_secs: self.started.elapsed().as_secs(),
+            sensors,
+            devices: self.hardware.as_ref().map(|h| h.device_health()).unwrap_or_default(),
+            poll: self.hardware.as_ref().map(|h| h.poll_stats()),
+            cameras: self.hardware.as_ref().map(|h| h.frame_stats().snapshot()).unwrap_or_default(),
+            clock: self.hardware.as_ref().map(|h| h.clock().report()),
+            illuminators: self.illuminators(),
+            session: self.session().await,
+            triggers,
+            triggers_armed,
+            resources,
+        }
+    }
+    
+    /// Prometheus text exposition of the pipeline counters and a snapshot
+    pub async fn metrics(&self) -> String {
+        let snapshot = self.snapshot().await;
+        self.metrics.clone().unwrap_or_default().render(&snapshot)
+    }
+    
+    /// Signed description of the running rig: configuration, devices,
+    /// sensors, calibration ages, triggers and software versions
+    ///
+    /// The node's signing key is created in the data directory on first use.
+    pub async fn manifest(&self) -> Result<SignedManifest, ControlError> {
+        let config = self.config().await;
+        let data_dir = PathBuf::from(&config.data_directory);
+        let key = SigningKey::load_or_create(&data_dir).map_err(anyhow::Error::from)?;
+        
+        let calibrations = CalibrationStore::open(&data_dir.join("calibration"))
+            .and_then(|store| store.list())
+            .unwrap_or_else(|e| {
+                tracing::warn!("Calibrations not listed in manifest: {}", e);
+                Vec::new()
+            });
+        let (triggers, triggers_armed) = match &self.triggers {
+            Some(triggers) => {
+                let triggers = triggers.read().await;
+                (triggers.list_triggers().into_iter().map(Into::into).collect(), triggers.is_armed())
+            }
+            None => (Vec::new(), false),
+        };
+        let devices = self.hardware.as_ref()
+            .map(|h| h.device_health().iter().map(Into::into).collect())
+            .unwrap_or_default();
+        
+        let rig = RigManifest {
+            generated_at: chrono::Utc::now(),
+            session_id: self.session().await.map(|s| s.id),
+            location: config.location.clone(),
+            software: Software::current(),
+            config_version: config.config_version,
+            devices,
+            sensors: self.sensors().await.iter().map(Into::into).collect(),
+            calibrations: calibrations.iter().map(Into::into).collect(),
+            triggers,
+            triggers_armed,
+            config: manifest::redact_secrets(config),
+        };
+        Ok(SignedManifest::sign(&rig, &key).map_err(anyhow::Error::from)?)
+    }
+    
+    /// Append the rig manifest to the running session, if any
+    pub async fn save_manifest(&self) {
+        if self.session().await.is_none() {
+            return;
+        }
+        let result = match self.manifest().await {
+            Ok(manifest) => self.recorder.read().await.record_manifest(&manifest).map_err(anyhow::Error::from),
+            Err(e) => Err(anyhow::anyhow!(e)),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Rig manifest not saved: {}", e);
+        }
+    }
+    
+    /// Latest value of every sensor against its baseline
+    pub async fn sensors(&self) -> Vec<SensorSnapshot> {
+        self.fusion.read().await.live().all().iter()
+            .map(|live| live.snapshot())
+            .collect()
+    }
+    
+    /// Session being recorded, if any
+    pub async fn session(&self) -> Option<RecordingSession> {
+        self.recorder.read().await.current_session().cloned()
+    }
+    
+    /// Start recording; refuses if a session is already running
+    ///
+    /// With a `template`, its pre-flight checklist runs first: failures
+    /// refuse the start, or with a warn-only template are noted in the
+    /// session. The template's trigger set is armed once recording starts.
+    pub async fn start_session(&self, name: Option<&str>, location: Option<&str>, template: Option<&str>) -> Result<RecordingSession, ControlError> {
+        if let Some(active) = self.recorder.read().await.current_session() {
+            return Err(ControlError::SessionActive(active.name.clone()));
+        }
+        
+        let template = match template {
+            Some(name) => Some(self.template(name).await?),
+            None => None,
+        };
+        let checklist = match &template {
+            Some(template) => Some(self.run_checklist(template).await),
+            None => None,
+        };
+        if let (Some(template), Some(checklist)) = (&template, &checklist) {
+            if !checklist.passed() {
+                if !template.warn_only {
+                    return Err(ControlError::PreflightFailed {
+                        template: template.name.clone(),
+                        failures: checklist.failures(),
+                    });
+                }
+                tracing::warn!("Starting '{}' session despite failed checks: {}", template.name, checklist.failures());
+            }
+        }
+        
+        let default_location = template.as_ref()
+            .and_then(|t| t.location.clone())
+            .unwrap_or(self.config.read().await.location.clone());
+        let mut recorder = self.recorder.write().await;
+        
+        if let Some(active) = recorder.current_session() {
+            return Err(ControlError::SessionActive(active.name.clone()));
+        }
+        
+        let name = name.map(str::to_string)
+            .unwrap_or_else(|| format!("session_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S")));
+        recorder.start_session(&name, location.unwrap_or(&default_location))
+            .map_err(anyhow::Error::from)?;
+        
+        if let Some(checklist) = &checklist {
+            let outcome = if checklist.passed() { "all checks passed".to_string() } else { checklist.failures() };
+            recorder.add_note(&format!("Pre-flight ({}): {}", checklist.template, outcome));
+        }
+        let session = recorder.current_session().cloned().ok_or(ControlError::NoSession)?;
+        drop(recorder);
+        
+        if let (Some(template), Some(triggers)) = (&template, &self.triggers) {
+            if !template.triggers.is_empty() {
+                let mut triggers = triggers.write().await;
+                let names: Vec<String> = triggers.list_triggers().iter().map(|t| t.name.clone()).collect();
+                for name in names {
+                    let armed = template.triggers.contains(&name);
+                    triggers.set_trigger_enabled(&name, armed);
+                }
+            }
+        }
+        self.save_manifest().await;
+        
+        Ok(session)
+    }
+    
+    /// Run a session template's pre-flight checklist without starting
+    pub async fn preflight(&self, template: &str) -> Result<Checklist, ControlError> {
+        let template = self.template(template).await?;
+        Ok(self.run_checklist(&template).await)
+    }
+    
+    async fn template(&self, name: &str) -> Result<SessionTemplate, ControlError> {
+        self.config.read().await.session_templates.iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| ControlError::UnknownTemplate(name.to_string()))
+    }
+    
+    async fn run_checklist(&self, template: &SessionTemplate) -> Checklist {
+        let data_dir = PathBuf::from(&self.config.read().await.data_directory);
+        let triggers = match &self.triggers {
+            Some(triggers) => triggers.read().await.list_triggers().iter().map(|t| t.name.clone()).collect(),
+            None => Vec::new(),
+        };
+        preflight::run(template, self.hardware.as_deref(), &triggers, &data_dir)
+    }
+    
+    /// Stop recording and return the finished session
+    ///
+    /// The session is compared with its control window in the background.
+    pub async fn end_session(&self) -> Result<RecordingSession, ControlError> {
+        let session = self.recorder.write().await
+            .end_session()
+            .map_err(anyhow::Error::from)?
+            .ok_or(ControlError::NoSession)?;
+        
+        let config = self.config.read().await;
+        if config.significance.enabled {
+            tokio::spawn(save_significance(PathBuf::from(&config.data_directory), session.id.clone(), config.significance.clone()));
+        }
+        Ok(session)
+    }
+    
+    /// Add a note to the running session
+    pub async fn add_note(&self, note: &str) -> Result<(), ControlError> {
+        let mut recorder = self.recorder.write().await;
+        if recorder.current_session().is_none() {
+            return Err(ControlError::NoSession);
+        }
+        recorder.add_note(note);
+        Ok(())
+    }
+    
+    /// Add an attributed annotation to the running session, optionally on
+    /// one of its events
+    pub async fn annotate(&self, author: &str, text: &str, event_id: Option<&str>, tags: &[String]) -> Result<Annotation, ControlError> {
+        let (author, text) = (author.trim(), text.trim());
+        if author.is_empty() {
+            return Err(ControlError::InvalidRequest("annotation author required".to_string()));
+        }
+        if text.is_empty() {
+            return Err(ControlError::InvalidRequest("empty annotation".to_string()));
+        }
+        
+        let mut recorder = self.recorder.write().await;
+        let session_id = recorder.current_session().ok_or(ControlError::NoSession)?.id.clone();
+        if let Some(event_id) = event_id {
+            let events = recorder.load_events(&session_id).map_err(anyhow::Error::from)?;
+            if !events.iter().any(|e| e.id == event_id) {
+                return Err(ControlError::UnknownEvent(event_id.to_string()));
+            }
+        }
+        
+        let tags: Vec<String> = tags.iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        let annotation = recorder.annotate(author, text, event_id, &tags)
+            .map_err(anyhow::Error::from)?
+            .ok_or(ControlError::NoSession)?;
+        tracing::info!("Annotation by {}: {}", annotation.author, annotation.text);
+        Ok(annotation)
+    }
+    
+    /// Import a handheld logger CSV into a session's sensor log (the running
+    /// session if none is given) using a configured mapping
+    pub async fn import_csv(&self, mapping: &str, path: &Path, session_id: Option<&str>) -> Result<CsvImported, ControlError> {
+        let mapping = self.config.read().await.csv_imports.iter()
+            .find(|m| m.name.eq_ignore_ascii_case(mapping))
+            .cloned()
+            .ok_or_else(|| ControlError::UnknownMapping(mapping.to_string()))?;
+        let file = std::fs::File::open(path)
+            .map_err(|e| ControlError::InvalidRequest(format!("{}: {}", path.display(), e)))?;
+        let import = handheld::parse(&mapping, std::io::BufReader::new(file))
+            .map_err(|e| ControlError::InvalidRequest(e.to_string()))?;
+        
+        let mut recorder = self.recorder.write().await;
+        let session = match session_id {
+            Some(id) => recorder.load_session(id).map_err(|_| ControlError::UnknownSession(id.to_string()))?,
+            None => recorder.current_session().cloned().ok_or(ControlError::NoSession)?,
+        };
+        let start = SystemTime::from(session.start_time);
+        let end = session.end_time.map_or_else(SystemTime::now, SystemTime::from);
+        let (inside, outside): (Vec<_>, Vec<_>) = import.records.into_iter()
+            .partition(|r| r.timestamp >= start && r.timestamp <= end);
+        recorder.import_sensor_records(&session.id, &inside).map_err(anyhow::Error::from)?;
+        
+        tracing::info!("Imported {} readings from {:?} into {} ({} outside the session)",
+            inside.len(), path, session.id, outside.len());
+        Ok(CsvImported {
+            session: session.id,
+            imported: inside.len(),
+            outside_session: outside.len(),
+            skipped_rows: import.skipped_rows,
+        })
+    }
+    
+    /// Annotations of the running session
+    pub async fn annotations(&self) -> Result<Vec<Annotation>, ControlError> {
+        self.recorder.read().await
+            .current_session()
+            .map(|s| s.annotations.clone())
+            .ok_or(ControlError::NoSession)
+    }
+    
+    /// Note the current time in the running session, with an optional label
+    pub async fn mark(&self, label: Option<&str>) -> Result<String, ControlError> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let mark = match label {
+            Some(label) => format!("Mark at {}: {}", now, label),
+            None => format!("Mark at {}", now),
+        };
+        
+        tracing::info!("{}", mark);
+        self.add_note(&mark).await?;
+        Ok(mark)
+    }
+    
+    /// Fire a trigger's action with a test event
+    pub async fn test_trigger(&self, name: &str) -> Result<(), ControlError> {
+        let triggers = self.triggers.as_ref()
+            .ok_or_else(|| ControlError::UnknownTrigger(name.to_string()))?;
+        
+        let test = triggers.read().await.test_trigger(name)
+            .ok_or_else(|| ControlError::UnknownTrigger(name.to_string()))?;
+        test.await.map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+    
+    /// Arm or disarm all triggers, noting it in the running session
+    pub async fn set_armed(&self, armed: bool) -> Result<bool, ControlError> {
+        let triggers = self.triggers.as_ref()
+            .ok_or_else(|| ControlError::InvalidRequest("no trigger manager".to_string()))?;
+        triggers.write().await.set_armed(armed);
+        self.recorder.write().await.add_note(if armed { "Triggers armed" } else { "Triggers disarmed" });
+        Ok(armed)
+    }
+    
+    /// Whether bus transactions are being logged
+    pub fn bus_logging(&self) -> bool {
+        buslog::logger().is_enabled()
+    }
+    
+    /// Turn bus transaction logging on or off, until the config is next
+    /// applied, noting it in the running session
+    pub async fn set_bus_logging(&self, enabled: bool) -> bool {
+        buslog::logger().set_enabled(enabled);
+        self.recorder.write().await.add_note(if enabled { "Bus logging on" } else { "Bus logging off" });
+        enabled
+    }
+    
+    /// Put a trigger in or out of dry-run mode
+    pub async fn set_dry_run(&self, name: &str, dry_run: bool) -> Result<TriggerState, ControlError> {
+        let triggers = self.triggers.as_ref()
+            .ok_or_else(|| ControlError::UnknownTrigger(name.to_string()))?;
+        let mut triggers = triggers.write().await;
+        if !triggers.set_trigger_dry_run(name, dry_run) {
+            return Err(ControlError::UnknownTrigger(name.to_string()));
+        }
+        triggers.states().into_iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| ControlError::UnknownTrigger(name.to_string()))
+    }
+    
+    /// Outcomes of recent trigger actions, oldest first
+    pub async fn trigger_audit(&self) -> Vec<AuditEntry> {
+        match &self.triggers {
+            Some(triggers) => triggers.read().await.audit().recent(),
+            None => Vec::new(),
+        }
+    }
+    
+    /// Switch to a configured profile by name
+    pub async fn set_profile(&self, name: &str) -> Result<ProfileConfig, ControlError> {
+        let profile = self.config.read().await.controls.profiles.iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| ControlError::UnknownProfile(name.to_string()))?;
+        
+        self.apply_profile(&profile).await;
+        Ok(profile)
+    }
+    
+    /// Switch to the profile at `index` (wrapping), as selected by the knob
+    pub async fn select_profile(&self, index: i64) -> Option<ProfileConfig> {
+        let profiles = self.config.read().await.controls.profiles.clone();
+        if profiles.is_empty() {
+            return None;
+        }
+        
+        let profile = profiles[index.rem_euclid(profiles.len() as i64) as usize].clone();
+        self.apply_profile(&profile).await;
+        Some(profile)
+    }
+    
+    async fn apply_profile(&self, profile: &ProfileConfig) {
+        let mut engine = self.fusion.write().await;
+        let fusion_config = FusionConfig {
+            anomaly_threshold: profile.anomaly_threshold,
+            min_confidence: profile.min_confidence,
+            ..engine.config().clone()
+        };
+        engine.set_config(fusion_config);
+        drop(engine);
+        
+        tracing::info!("Switched to profile '{}'", profile.name);
+        self.recorder.write().await.add_note(&format!("Profile: {}", profile.name));
+    }
+    
+    /// Log filter in effect
+    pub fn log_settings(&self) -> Option<LogSettings> {
+        self.log_levels.as_ref().map(|levels| LogSettings {
+            filter: levels.base().to_string(),
+            devices: levels.devices(),
+        })
+    }
+    
+    /// State of every IR illuminator
+    pub fn illuminators(&self) -> Vec<IlluminatorStatus> {
+        self.hardware.as_ref().map(|h| h.illuminators().statuses()).unwrap_or_default()
+    }
+    
+    /// Set an illuminator's mode (`None` returns it to auto)
+    pub fn set_illuminator(&self, name: &str, mode: Option<IlluminatorMode>) -> Result<IlluminatorStatus, ControlError> {
+        let mode = mode.unwrap_or_default();
+        if let IlluminatorMode::Manual { level } = mode {
+            if !(0.0..=1.0).contains(&level) {
+                return Err(ControlError::InvalidRequest(format!("level {} is outside 0-1", level)));
+            }
+        }
+        
+        let illuminator = self.hardware.as_ref()
+            .and_then(|h| h.illuminators().get(name))
+            .ok_or_else(|| ControlError::UnknownIlluminator(name.to_string()))?;
+        let status = illuminator.set_mode(mode);
+        tracing::info!("Illuminator {} set to {}", name, mode);
+        Ok(status)
+    }
+    
+    /// Play a clip or spoken text on an audio route (the default output when
+    /// `None`), returning once it has finished
+    ///
+    /// The playback is noted in the running session, so responses in the
+    /// readings can be lined up with the call that prompted them.
+    pub async fn talk(&self, talk: Talk, route: Option<&str>) -> Result<TalkPlayed, ControlError> {
+        let device = match route {
+            Some(route) => self.hardware.as_ref()
+                .and_then(|h| h.audio_routes().device(route))
+                .ok_or_else(|| ControlError::UnknownRoute(route.to_string()))?,
+            None => "default".to_string(),
+        };
+        let output = route.unwrap_or("the default output");
+        
+        let note = match &talk {
+            Talk::Clip(wav) => {
+                let (format, data) = AudioFormat::parse_wav(wav).map_err(|e| ControlError::InvalidRequest(e.to_string()))?;
+                format!("Intercom: {:.1} s clip played on {}", format.duration(data.len()).as_secs_f64(), output)
+            }
+            Talk::Text { text, .. } => {
+                let text = text.trim();
+                if text.is_empty() {
+                    return Err(ControlError::InvalidRequest("empty text".to_string()));
+                }
+                if text.len() > MAX_TALK_TEXT {
+                    return Err(ControlError::InvalidRequest(format!("text longer than {} bytes", MAX_TALK_TEXT)));
+                }
+                format!("Intercom: \"{}\" spoken on {}", text, output)
+            }
+        };
+        
+        let _turn = self.intercom.lock().await;
+        self.recorder.write().await.add_note(&note);
+        tracing::info!("{}", note);
+        
+        let playback_device = device.clone();
+        let duration = tokio::task::spawn_blocking(move || {
+            let mut playback = AudioPlayback::new(&playback_device, AudioFormat::default())?;
+            match talk {
+                Talk::Clip(wav) => playback.play_wav(&wav),
+                Talk::Text { text, voice } => playback.speak(text.trim(), voice.as_deref()),
+            }
+        })
+        .await
+        .map_err(anyhow::Error::from)?
+        .map_err(|e| ControlError::Other(anyhow::anyhow!("intercom playback on {} failed: {}", device, e)))?;
+        
+        Ok(TalkPlayed {
+            route: route.map(str::to_string),
+            device,
+            duration_secs: duration.as_secs_f64(),
+        })
+    }
+    
+    /// Health of one device or sensor, with its polling state
+    pub fn sensor_status(&self, name: &str) -> Result<DeviceHealth, ControlError> {
+        self.hardware.as_ref()
+            .and_then(|h| h.device_health().into_iter().find(|d| d.name == name))
+            .ok_or_else(|| ControlError::UnknownSensor(name.to_string()))
+    }
+    
+    /// Pause or resume polling of a sensor, noting it in the session
+    pub async fn set_sensor_paused(&self, name: &str, paused: bool) -> Result<DeviceHealth, ControlError> {
+        let hardware = self.sensor_hardware(name)?;
+        let changed = hardware.is_paused(name) != paused;
+        if paused {
+            hardware.pause_sensor(name);
+        } else {
+            hardware.resume_sensor(name);
+        }
+        if changed {
+            self.recorder.write().await.add_note(&format!("Sensor {} {}", name, if paused { "paused" } else { "resumed" }));
+        }
+        self.sensor_status(name)
+    }
+    
+    /// Change how often a sensor is polled, until restart
+    pub async fn set_poll_schedule(&self, name: &str, schedule: PollSchedule) -> Result<DeviceHealth, ControlError> {
+        if schedule.rate_hz.is_some_and(|rate| !(rate > 0.0 && rate.is_finite())) {
+            return Err(ControlError::InvalidRequest(format!("invalid poll rate {:?}", schedule.rate_hz)));
+        }
+        self.sensor_hardware(name)?.set_poll_schedule(name, schedule);
+        let rate = schedule.rate_hz.map_or("the global interval".to_string(), |rate| format!("{} Hz", rate));
+        self.recorder.write().await.add_note(&format!("Sensor {} polled at {}", name, rate));
+        self.sensor_status(name)
+    }
+    
+    /// Close and re-open a sensor, as the watchdog does with offline ones
+    pub async fn reinit_sensor(&self, name: &str) -> Result<DeviceHealth, ControlError> {
+        let hardware = self.sensor_hardware(name)?.clone();
+        let sensor = name.to_string();
+        tokio::task::spawn_blocking(move || hardware.reinit_sensor(&sensor))
+            .await
+            .map_err(anyhow::Error::from)?
+            .map_err(anyhow::Error::from)?;
+        self.recorder.write().await.add_note(&format!("Sensor {} re-initialised", name));
+        self.sensor_status(name)
+    }
+    
+    /// Close and unregister a sensor until restart or until it is plugged in again
+    pub async fn remove_sensor(&self, name: &str) -> Result<(), ControlError> {
+        self.sensor_hardware(name)?.unregister_sensor(name);
+        self.recorder.write().await.add_note(&format!("Sensor {} removed", name));
+        Ok(())
+    }
+    
+    /// Hardware manager, if `name` is a registered sensor
+    fn sensor_hardware(&self, name: &str) -> Result<&Arc<HardwareManager>, ControlError> {
+        self.hardware.as_ref()
+            .filter(|h| h.poll_schedule(name).is_some())
+            .ok_or_else(|| ControlError::UnknownSensor(name.to_string()))
+    }
+    
+    /// Schedule a simulated fault on a sensor, noting it in the session
+    pub async fn inject_fault(&self, spec: &FaultSpec) -> Result<FaultStatus, ControlError> {
+        let faults = self.fault_injector()?;
+        let registered = self.hardware.as_ref()
+            .is_some_and(|h| h.list_devices().iter().any(|(name, _)| *name == spec.sensor));
+        if !registered {
+            return Err(ControlError::UnknownSensor(spec.sensor.clone()));
+        }
+        
+        let status = faults.inject(spec).map_err(|e| ControlError::InvalidRequest(e.to_string()))?;
+        let mut note = format!("Fault injected on {}: {}", spec.sensor, spec.kind);
+        if spec.after_secs > 0.0 {
+            note.push_str(&format!(" in {} s", spec.after_secs));
+        }
+        if let Some(duration) = spec.duration_secs {
+            note.push_str(&format!(" for {} s", duration));
+        }
+        self.recorder.write().await.add_note(&note);
+        Ok(status)
+    }
+    
+    /// Scheduled and active simulated faults
+    pub fn faults(&self) -> Result<Vec<FaultStatus>, ControlError> {
+        Ok(self.fault_injector()?.list())
+    }
+    
+    /// Remove the simulated faults of one sensor, or all
+    pub fn clear_faults(&self, sensor: Option<&str>) -> Result<usize, ControlError> {
+        Ok(self.fault_injector()?.clear(sensor))
+    }
+    
+    fn fault_injector(&self) -> Result<&FaultInjector, ControlError> {
+        self.hardware.as_ref()
+            .and_then(|h| h.faults())
+            .ok_or_else(|| ControlError::InvalidRequest("fault injection is disabled (fault_injection = false)".to_string()))
+    }
+    
+    /// Override the log level of one device (`None` restores the default)
+    pub fn set_log_level(&self, device: &str, level: Option<&str>) -> Result<LogSettings, ControlError> {
+        let levels = self.log_levels.as_ref()
+            .ok_or_else(|| ControlError::InvalidRequest("log levels cannot be changed on this node".to_string()))?;
+        levels.set_device(device, level)?;
+        
+        Ok(LogSettings {
+            filter: levels.base().to_string(),
+            devices: levels.devices(),
+        })
+    }
+    
+    /// Swap in `new`, updating the detection settings that can change live
+    async fn apply(&self, config: &mut AppConfig, mut new: AppConfig) -> ConfigApplied {
+        new.config_path = config.config_path.clone();
+        
+        let mut engine = self.fusion.write().await;
+        let fusion_config = FusionConfig {
+            anomaly_threshold: new.anomaly_threshold,
+            min_baseline_samples: new.baseline_samples,
+            correlation_window_ms: new.correlation_window_ms,
+            min_confidence: new.min_confidence,
+            history_secs: new.history_secs,
+            hum: new.hum.clone(),
+            pressure: new.pressure.clone(),
+            bands: new.bands.clone(),
+            heatmap: new.heatmap.clone(),
+            baseline: new.baseline.clone(),
+            persistence: new.persistence.clone(),
+            rate: new.rate.clone(),
+            mode: new.fusion_mode,
+            kalman: new.kalman_config(),
+            ..engine.config().clone()
+        };
+        engine.set_config(fusion_config);
+        drop(engine);
+        
+        if new.bus_logging != buslog::logger().is_enabled() {
+            buslog::logger().set_enabled(new.bus_logging);
+        }
+        
+        let restart_required = requires_restart(config, &new);
+        if restart_required {
+            tracing::warn!("Config version {} changes settings that apply after restart", new.config_version);
+        }
+        
+        *config = new;
+        ConfigApplied {
+            version: config.config_version,
+            restart_required,
+        }
+    }
+    
+    /// Keep the running config for rollback, preferring the file as written
+    fn archive(&self, config: &AppConfig) -> anyhow::Result<()> {
+        let path = self.history_dir.join(format!("v{}.toml", config.config_version));
+        match std::fs::read_to_string(&config.config_path) {
+            Ok(content) => {
+                std::fs::create_dir_all(&self.history_dir)?;
+                std::fs::write(path, content)?;
+            }
+            Err(_) => config.save(&path)?,
+        }
+        Ok(())
+    }
+    
+    /// Saved configs, oldest first
+    fn history(&self) -> anyhow::Result<Vec<(u64, PathBuf)>> {
+        let mut entries = Vec::new();
+        if !self.history_dir.exists() {
+            return Ok(entries);
+        }
+        
+        for entry in std::fs::read_dir(&self.history_dir)? {
+            let path = entry?.path();
+            let version = path.file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.strip_prefix('v'))
+                .and_then(|s| s.parse::<u64>().ok());
+            if let Some(version) = version {
+                entries.push((version, path));
+            }
+        }
+        
+        entries.sort_by_key(|(version, _)| *version);
+        Ok(entries)
+    }
+    
+    fn prune_history(&self) -> anyhow::Result<()> {
+        let history = self.history()?;
+        let excess = history.len().saturating_sub(HISTORY_LIMIT);
+        for (_, path) in &history[..excess] {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the config file so the change survives a restart
+fn persist(path: &Path, content: &str) -> anyhow::Result<()> {
+    if path.as_os_str().is_empty() {
+        tracing::warn!("Running on default config; pushed config will not survive a restart");
+        return Ok(());
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Compare a finished session with its control window and save the report
+pub async fn save_significance(data_dir: PathBuf, session_id: String, config: SignificanceConfig) {
+    let result = tokio::task::spawn_blocking(move || {
+        let recorder = EventRecorder::new(&data_dir)?;
+        let report = significance::compare(&recorder, &session_id, &config)?;
+        recorder.record_significance(&report)?;
+        Ok::<_, anyhow::Error>(report)
+    }).await;
+    
+    match result {
+        Ok(Ok(report)) => {
+            let lowest = report.comparisons.iter().map(|c| c.p_adjusted).fold(1.0, f64::min);
+            tracing::info!("Compared {} with its control window: {} tests, lowest adjusted p {:.4}",
+                report.session_id, report.comparisons.len(), lowest);
+        }
+        Ok(Err(e)) => tracing::warn!("No control comparison: {}", e),
+        Err(e) => tracing::error!("Control comparison failed: {}", e),
+    }
+}
+
+/// Whether anything besides the live-applied settings differs
+fn requires_restart(old: &AppConfig, new: &AppConfig) -> bool {
+    let mut normalized = new.clone();
+    normalized.config_version = old.config_version;
+    normalized.anomaly_threshold = old.anomaly_threshold;
+    normalized.baseline_samples = old.baseline_samples;
+    normalized.correlation_window_ms = old.correlation_window_ms;
+    normalized.min_confidence = old.min_confidence;
+    normalized.history_secs = old.history_secs;
+    normalized.hum = old.hum.clone();
+    normalized.pressure = old.pressure.clone();
+    normalized.bands = old.bands.clone();
+    normalized.heatmap = old.heatmap.clone();
+    normalized.baseline = old.baseline.clone();
+    normalized.bus_logging = old.bus_logging;
+    normalized.persistence = old.persistence.clone();
+    normalized.rate = old.rate.clone();
+    normalized.fusion_mode = old.fusion_mode;
+    normalized.kalman = old.kalman.clone();
+    normalized.api_token = old.api_token.clone();
+    normalized.session_name = old.session_name.clone();
+    normalized.controls.profiles = old.controls.profiles.clone();
+    normalized.session_templates = old.session_templates.clone();
+    normalized.csv_imports = old.csv_imports.clone();
+    normalized.significance = old.significance.clone();
+    
+    toml::to_string(&normalized).ok() != toml::to_string(old).ok()
+}
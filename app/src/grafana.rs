@@ -0,0 +1,125 @@
+// Grafana annotation integration
+
+use glowbarn_sensors::{EventHandler, ParanormalEvent};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Grafana annotation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaConfig {
+    /// Grafana base URL, e.g. "http://grafana.local:3000"
+    pub url: String,
+    
+    /// Service account token or API key
+    #[serde(default)]
+    pub api_key: Option<String>,
+    
+    /// Restrict annotations to one dashboard (global if unset)
+    #[serde(default)]
+    pub dashboard_uid: Option<String>,
+    
+    /// Restrict annotations to one panel
+    #[serde(default)]
+    pub panel_id: Option<u32>,
+    
+    /// Extra tags added to every annotation
+    #[serde(default)]
+    pub tags: Vec<String>,
+    
+    /// Minimum event confidence to annotate
+    #[serde(default)]
+    pub min_confidence: f64,
+}
+
+/// Body of POST /api/annotations
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Annotation {
+    time: u64,
+    tags: Vec<String>,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dashboard_uid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    panel_id: Option<u32>,
+}
+
+/// Posts events to Grafana as annotations
+#[derive(Clone)]
+pub struct GrafanaAnnotator {
+    config: GrafanaConfig,
+    agent: ureq::Agent,
+}
+
+impl GrafanaAnnotator {
+    pub fn new(config: GrafanaConfig) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(5))
+            .build();
+        Self { config, agent }
+    }
+    
+    fn annotation(&self, event: &ParanormalEvent) -> Annotation {
+        let time = event.timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        
+        let mut tags = vec!["glowbarn".to_string(), format!("{:?}", event.event_type)];
+        if let Some(zone) = event.location.as_ref().and_then(|l| l.zone.clone()) {
+            tags.push(format!("zone:{}", zone));
+        }
+        tags.extend(self.config.tags.iter().cloned());
+        
+        let sensors: Vec<&str> = event.sensor_data.iter()
+            .map(|s| s.sensor_name.as_str())
+            .collect();
+        let text = format!(
+            "{:?} ({:.0}% confidence)<br>Sensors: {}",
+            event.event_type,
+            event.confidence * 100.0,
+            sensors.join(", ")
+        );
+        
+        Annotation {
+            time,
+            tags,
+            text,
+            dashboard_uid: self.config.dashboard_uid.clone(),
+            panel_id: self.config.panel_id,
+        }
+    }
+    
+    /// Post one event (blocking)
+    pub fn post(&self, event: &ParanormalEvent) -> anyhow::Result<()> {
+        let url = format!("{}/api/annotations", self.config.url.trim_end_matches('/'));
+        let mut request = self.agent.post(&url);
+        if let Some(ref key) = self.config.api_key {
+            request = request.set("Authorization", &format!("Bearer {}", key));
+        }
+        
+        request.send_json(self.annotation(event))?;
+        Ok(())
+    }
+}
+
+impl EventHandler for GrafanaAnnotator {
+    fn on_event(&self, event: &ParanormalEvent) {
+        if event.confidence < self.config.min_confidence {
+            return;
+        }
+        
+        // Don't hold up event processing on the network
+        let annotator = self.clone();
+        let event = event.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = annotator.post(&event) {
+                tracing::warn!("Failed to post Grafana annotation: {}", e);
+            }
+        });
+    }
+    
+    fn on_sensor_offline(&self, _sensor_name: &str) {}
+    
+    fn on_sensor_online(&self, _sensor_name: &str) {}
+}
@@ -0,0 +1,109 @@
+// Logging setup and per-device log levels
+
+use crate::control::ControlError;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter, Registry};
+
+/// Filter used when `RUST_LOG` is unset
+const DEFAULT_FILTER: &str = "info,glowbarn=debug,glowbarn_hal=debug,glowbarn_sensors=debug";
+
+/// Runtime log levels for individual devices
+///
+/// Device transactions run in spans carrying a `device` field and fusion
+/// decisions in spans carrying `sensor`; an override adds span-field
+/// directives for both, so one device can log at `debug` or `trace` while
+/// the rest stay at the base filter.
+pub struct LogLevels {
+    handle: reload::Handle<EnvFilter, Registry>,
+    base: String,
+    devices: Mutex<BTreeMap<String, String>>,
+}
+
+/// Install the global subscriber
+pub fn init() -> LogLevels {
+    let base = std::env::var("RUST_LOG").unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+    let (base, filter) = match EnvFilter::try_new(&base) {
+        Ok(filter) => (base, filter),
+        Err(_) => (DEFAULT_FILTER.to_string(), EnvFilter::new(DEFAULT_FILTER)),
+    };
+    let (filter, handle) = reload::Layer::new(filter);
+    
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer()
+            .with_target(true)
+            .with_thread_ids(false)
+            .with_file(false)
+            .with_line_number(false))
+        .init();
+    
+    LogLevels {
+        handle,
+        base,
+        devices: Mutex::new(BTreeMap::new()),
+    }
+}
+
+impl LogLevels {
+    /// Base filter directives
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+    
+    /// Per-device overrides
+    pub fn devices(&self) -> BTreeMap<String, String> {
+        self.devices.lock().unwrap().clone()
+    }
+    
+    /// Set a device's level, or remove its override with `None`
+    pub fn set_device(&self, device: &str, level: Option<&str>) -> Result<(), ControlError> {
+        if device.is_empty() || device.contains([',', '[', ']', '{', '}', '=', '"']) {
+            return Err(ControlError::InvalidRequest(format!("invalid device name: {:?}", device)));
+        }
+        
+        let mut devices = self.devices.lock().unwrap();
+        let mut updated = devices.clone();
+        match level {
+            Some(level) => {
+                let level: LevelFilter = level.parse()
+                    .map_err(|_| ControlError::InvalidRequest(format!("invalid log level: {}", level)))?;
+                updated.insert(device.to_string(), level.to_string().to_lowercase());
+            }
+            None => {
+                updated.remove(device);
+            }
+        }
+        
+        let filter = EnvFilter::try_new(self.directives(&updated))
+            .map_err(|e| ControlError::InvalidRequest(e.to_string()))?;
+        self.handle.reload(filter).map_err(anyhow::Error::from)?;
+        *devices = updated;
+        
+        tracing::info!("Log level for {} set to {}", device, level.unwrap_or("default"));
+        Ok(())
+    }
+    
+    fn directives(&self, devices: &BTreeMap<String, String>) -> String {
+        let mut directives = self.base.clone();
+        for (device, level) in devices {
+            let pattern = escape_pattern(device);
+            directives.push_str(&format!(",[{{device={0}}}]={1},[{{sensor={0}}}]={1}", pattern, level));
+        }
+        directives
+    }
+}
+
+/// Field values in directives are regexes; match the name literally
+fn escape_pattern(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '^' | '$') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
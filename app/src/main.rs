@@ -3,26 +3,51 @@
 //! Main application entry point for the GlowBarn system.
 
 use anyhow::Result;
-use glowbarn_hal::{HardwareManager, HalConfig};
+use glowbarn_hal::{AudioClip, AudioClipRecorder, HardwareManager, HalConfig, DeviceEvent, WatchdogEvent, SyncClock, Button, RotaryEncoder, InputEvent, InputManager, LaserGridMonitor, ReadingPayload, SweepSummary, WidebandSweep, DirectionFinder};
 use glowbarn_sensors::{
+    actions::{ActionPool, TriggerAudit, TRIGGER_AUDIT_FILE},
     fusion::{FusionEngine, FusionConfig},
+    maintenance::{MaintenanceScheduler, MaintenanceOutcome},
     recording::EventRecorder,
-    triggers::TriggerManager,
-    EventHandler, LoggingEventHandler,
+    triggers::{TriggerManager, TRIGGER_STATE_FILE},
+    zones::ZoneMap,
+    EventHandler, EventType, LoggingEventHandler, ParanormalEvent,
 };
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tracing::Instrument;
 
 mod config;
+mod control;
+mod grafana;
+mod logging;
+mod manifest;
+mod metrics;
+mod preflight;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(feature = "rpc")]
+mod rpc;
+mod server;
+mod shutdown;
+mod snapshot;
+#[cfg(unix)]
+mod socket;
 
 use config::AppConfig;
+use control::NodeControl;
+use shutdown::Shutdown;
+use grafana::GrafanaAnnotator;
+use metrics::Metrics;
+use server::StreamHub;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
-    init_logging();
+    let log_levels = logging::init();
     
     tracing::info!("╔══════════════════════════════════════════╗");
     tracing::info!("║   GlowBarn Paranormal Detection Suite    ║");
@@ -37,12 +62,33 @@ async fn main() -> Result<()> {
     tracing::info!("Initializing Hardware Abstraction Layer...");
     let hal_config = HalConfig {
         i2c_buses: config.i2c_buses.clone(),
+        i2c_auto_detect: config.i2c_auto_detect,
+        sensor_aliases: config.sensor_aliases.clone(),
+        stuck_reads: config.stuck_reads,
+        poll_schedules: config.poll.clone(),
+        watchdog_timeout: Duration::from_secs(config.watchdog_timeout_secs),
         spi_devices: config.spi_devices.clone(),
         gpio_chip: config.gpio_chip.clone(),
+        serial_sensors: config.serial_sensors.clone(),
+        ehal_sensors: config.ehal_sensors.clone(),
+        cameras: config.cameras.clone(),
+        audio_devices: config.audio_devices.clone(),
+        adc_channels: config.adc_channels.clone(),
+        illuminators: config.illuminators.clone(),
+        audio_routes: config.audio_routes.clone(),
+        calibration_dir: Some(PathBuf::from(&config.data_directory).join("calibration")),
+        fault_injection: config.fault_injection,
+        simulation: config.simulation.clone(),
         ..Default::default()
     };
     
+    glowbarn_hal::buslog::logger().set_enabled(config.bus_logging);
+    
     let (mut hardware_manager, sensor_rx) = HardwareManager::new(hal_config);
+    // The chip drivers the HAL ships, for [[ehal_sensors]] entries
+    for probe in glowbarn_hal::i2c::KNOWN_CHIPS.iter().filter(|probe| probe.driver.is_some()) {
+        hardware_manager.register_ehal_driver(&probe.model.to_lowercase(), glowbarn_hal::i2c::open_manifest_sensor);
+    }
     hardware_manager.init().await?;
     tracing::info!("HAL initialized successfully");
     
@@ -53,10 +99,21 @@ async fn main() -> Result<()> {
         min_baseline_samples: config.baseline_samples,
         correlation_window_ms: config.correlation_window_ms,
         min_confidence: config.min_confidence,
+        history_secs: config.history_secs,
+        hum: config.hum.clone(),
+        pressure: config.pressure.clone(),
+        bands: config.bands.clone(),
+        heatmap: config.heatmap.clone(),
+        baseline: config.baseline.clone(),
+        persistence: config.persistence.clone(),
+        rate: config.rate.clone(),
+        mode: config.fusion_mode,
+        kalman: config.kalman_config(),
         ..Default::default()
     };
     
     let (fusion_engine, event_rx) = FusionEngine::new(fusion_config);
+    let live = fusion_engine.live();
     let fusion_engine = Arc::new(RwLock::new(fusion_engine));
     tracing::info!("Fusion engine initialized");
     
@@ -64,6 +121,8 @@ async fn main() -> Result<()> {
     tracing::info!("Initializing Event Recorder...");
     let data_dir = PathBuf::from(&config.data_directory);
     let mut recorder = EventRecorder::new(&data_dir)?;
+    recorder.set_log_policy(config.sensor_log.policy());
+    recorder.set_summary_interval(config.sensor_log.summary_interval());
     
     if config.auto_record {
         recorder.start_session(&config.session_name, &config.location)?;
@@ -71,9 +130,27 @@ async fn main() -> Result<()> {
     let recorder = Arc::new(RwLock::new(recorder));
     tracing::info!("Event recorder ready");
     
+    let zones = build_zone_map(&config);
+    
     // Initialize trigger manager
     tracing::info!("Initializing Trigger Manager...");
-    let trigger_manager = Arc::new(RwLock::new(TriggerManager::default()));
+    let trigger_manager = Arc::new(RwLock::new(TriggerManager::default()
+        .with_live(live)
+        .with_zones(zones.clone())
+        .with_state_file(&data_dir.join(TRIGGER_STATE_FILE))
+        .with_actions(ActionPool::new(config.trigger_actions.clone())
+            .with_audit(TriggerAudit::new().with_file(data_dir.join(TRIGGER_AUDIT_FILE)))
+            .with_illuminators(hardware_manager.illuminators().clone())
+            .with_audio_routes(hardware_manager.audio_routes().clone()))));
+    {
+        let mut triggers = trigger_manager.write().await;
+        triggers.set_armed(config.triggers_armed);
+        for name in &config.dry_run_triggers {
+            if !triggers.set_trigger_dry_run(name, true) {
+                tracing::warn!("Unknown trigger '{}' in dry_run_triggers", name);
+            }
+        }
+    }
     tracing::info!("Trigger manager ready with {} triggers", 
         trigger_manager.read().await.list_triggers().len());
     
@@ -81,15 +158,165 @@ async fn main() -> Result<()> {
     tracing::info!("Starting sensor polling (interval: {:?})...", 
         Duration::from_millis(config.poll_interval_ms));
     hardware_manager.start_polling(Duration::from_millis(config.poll_interval_ms)).await;
+    let device_events = hardware_manager.take_device_events();
+    let watchdog_events = hardware_manager.take_watchdog_events();
+    let hardware_manager = Arc::new(hardware_manager);
+    hardware_manager.start_hotplug();
+    hardware_manager.start_watchdog();
+    
+    // Start HTTP API (event streaming, config push)
+    let hub = StreamHub::new();
+    let metrics = Metrics::new();
+    let control = Arc::new(NodeControl::new(config.clone(), fusion_engine.clone(), recorder.clone())
+        .with_triggers(trigger_manager.clone())
+        .with_hardware(hardware_manager.clone())
+        .with_log_levels(log_levels)
+        .with_metrics(metrics.clone()));
+    control.save_manifest().await;
+    if let Some(addr) = config.http_listen.clone() {
+        let server_hub = hub.clone();
+        let server_control = control.clone();
+        tokio::spawn(async move {
+            if let Err(e) = server::serve(&addr, server_hub, server_control).await {
+                tracing::error!("HTTP server failed: {}", e);
+            }
+        });
+        tokio::spawn(hub.clone().run_summaries(Duration::from_secs(config.summary_interval_secs.max(1))));
+    }
+    
+    // Start gRPC remote API (live readings and events, session control)
+    if let Some(addr) = config.rpc_listen.clone() {
+        #[cfg(feature = "rpc")]
+        {
+            let rpc_hub = hub.clone();
+            let rpc_control = control.clone();
+            tokio::spawn(async move {
+                if let Err(e) = rpc::serve(&addr, rpc_hub, rpc_control).await {
+                    tracing::error!("gRPC server failed: {}", e);
+                }
+            });
+        }
+        #[cfg(not(feature = "rpc"))]
+        tracing::warn!("rpc_listen = {} ignored: built without the rpc feature", addr);
+    }
+    
+    // Start MQTT publisher (readings and events for home automation)
+    #[cfg(feature = "mqtt")]
+    let mqtt = config.mqtt.clone().map(|mqtt| {
+        tracing::info!("Publishing to MQTT broker {}:{}", mqtt.host, mqtt.port);
+        mqtt::MqttSink::start(mqtt, &config.location)
+    });
+    #[cfg(not(feature = "mqtt"))]
+    if config.mqtt.is_some() {
+        tracing::warn!("[mqtt] ignored: built without the mqtt feature");
+    }
+    
+    // Start local control socket (glowbarn-cli status/note/mark/...)
+    #[cfg(unix)]
+    if let Some(path) = config.control_socket.clone() {
+        let socket_control = control.clone();
+        tokio::spawn(async move {
+            if let Err(e) = socket::serve(std::path::Path::new(&path), socket_control).await {
+                tracing::error!("Control socket failed: {}", e);
+            }
+        });
+    }
+    
+    // Rolling audio of each input, for clips around events
+    let clips: ClipRecorders = Arc::new(std::sync::Mutex::new(if config.clips.enabled {
+        config.audio_devices.iter()
+            .map(|a| (a.name.clone(), AudioClipRecorder::new(&a.format(), config.clips.pre_roll(), config.clips.post_roll())))
+            .collect()
+    } else {
+        HashMap::new()
+    }));
     
     // Spawn sensor reading processor
     let fusion_clone = fusion_engine.clone();
-    let sensor_task = tokio::spawn(async move {
+    let sensor_hub = hub.clone();
+    let sensor_recorder = recorder.clone();
+    let frame_stats = hardware_manager.frame_stats().clone();
+    let sensor_clips = clips.clone();
+    let sensor_metrics = metrics.clone();
+    #[cfg(feature = "mqtt")]
+    let sensor_mqtt = mqtt.clone();
+    let (sensor_drain, mut draining) = shutdown::drain();
+    let mut sensor_task = tokio::spawn(async move {
         let mut rx = sensor_rx;
-        while let Some(reading) = rx.recv().await {
-            let engine = fusion_clone.read().await;
-            if let Err(e) = engine.process_reading(reading).await {
-                tracing::error!("Error processing reading: {}", e);
+        let mut drained = 0u64;
+        loop {
+            let reading = tokio::select! {
+                biased;
+                reading = rx.recv() => reading,
+                _ = draining.requested() => None,
+            };
+            let Some(reading) = reading else {
+                break;
+            };
+            if draining.is_requested() {
+                drained += 1;
+            }
+            // Channels of multi-channel sensors go on as sensors of their own
+            for reading in reading.split_channels() {
+                sensor_metrics.reading(&reading.sensor_name);
+                sensor_metrics.queue("readings", rx.len(), rx.max_capacity());
+                sensor_hub.add_reading(&reading).await;
+                #[cfg(feature = "mqtt")]
+                if let Some(mqtt) = &sensor_mqtt {
+                    mqtt.publish_reading(&reading);
+                }
+                if let ReadingPayload::Audio { samples, .. } = &reading.payload {
+                    feed_clips(&sensor_clips, &reading.sensor_name, samples);
+                }
+                let name = reading.sensor_name.clone();
+                let waveform = matches!(reading.payload, ReadingPayload::Audio { .. } | ReadingPayload::Waveform { .. });
+                let frame = matches!(reading.payload, ReadingPayload::Frame { .. }).then_some(reading.timestamp);
+                let engine = fusion_clone.read().await;
+                let mut snapshots = vec![engine.snapshot(&reading)];
+                if let Err(e) = engine.process_reading(reading).await {
+                    tracing::error!("Error processing reading: {}", e);
+                }
+                if let Some(captured_at) = frame {
+                    frame_stats.processed(&name, captured_at);
+                }
+                
+                // Hum level of waveform channels, reported as a diagnostic sensor
+                let hum = if waveform { engine.hum_reading(&name) } else { None };
+                if let Some(ref hum) = hum {
+                    snapshots.push(engine.snapshot(hum));
+                }
+                drop(engine);
+                if let Some(hum) = hum {
+                    sensor_hub.add_reading(&hum).await;
+                }
+                
+                let mut recorder = sensor_recorder.write().await;
+                for snapshot in &snapshots {
+                    if let Err(e) = recorder.record_sensor(snapshot) {
+                        tracing::debug!("Sensor sample not logged: {}", e);
+                    }
+                }
+            }
+        }
+        drained
+    });
+    
+    // Direction finder for RF anomalies
+    let direction_finder = config.rf_direction.clone().and_then(|df| {
+        let antennas = df.antennas.len();
+        match DirectionFinder::open(df) {
+            Ok(mut finder) => {
+                if let Some(store) = hardware_manager.calibrations() {
+                    if let Err(e) = finder.load_power_calibration(store) {
+                        tracing::warn!("Direction finder power calibration not loaded: {}", e);
+                    }
+                }
+                tracing::info!("RF direction finding over {} antennas", antennas);
+                Some(Arc::new(std::sync::Mutex::new(finder)))
+            }
+            Err(e) => {
+                tracing::warn!("RF direction finding unavailable: {}", e);
+                None
             }
         }
     });
@@ -97,25 +324,160 @@ async fn main() -> Result<()> {
     // Spawn event processor
     let recorder_clone = recorder.clone();
     let trigger_clone = trigger_manager.clone();
-    let event_task = tokio::spawn(async move {
+    let burst_hardware = hardware_manager.clone();
+    let burst_config = config.burst.clone();
+    let clip_config = config.clips.clone();
+    let event_clips = clips.clone();
+    let poll_interval = Duration::from_millis(config.poll_interval_ms);
+    let mut handlers: Vec<Box<dyn EventHandler>> = vec![Box::new(LoggingEventHandler)];
+    if let Some(grafana) = config.grafana.clone() {
+        tracing::info!("Posting event annotations to Grafana at {}", grafana.url);
+        handlers.push(Box::new(GrafanaAnnotator::new(grafana)));
+    }
+    #[cfg(feature = "mqtt")]
+    if let Some(mqtt) = mqtt.clone() {
+        handlers.push(Box::new(mqtt));
+    }
+    let handlers = Arc::new(handlers);
+    let sensor_handlers = handlers.clone();
+    let event_metrics = metrics.clone();
+    let (event_drain, mut draining) = shutdown::drain();
+    let mut event_task = tokio::spawn(async move {
         let mut rx = event_rx;
-        while let Some(event) = rx.recv().await {
-            // Log event
-            let handler = LoggingEventHandler;
-            handler.on_event(&event);
-            
-            // Record event
-            if let Err(e) = recorder_clone.write().await.record_event(&event) {
-                tracing::error!("Error recording event: {}", e);
+        let mut drained = 0u64;
+        let mut bursts = tokio::task::JoinSet::new();
+        loop {
+            let event = tokio::select! {
+                biased;
+                event = rx.recv() => event,
+                _ = draining.requested() => None,
+            };
+            let Some(mut event) = event else {
+                break;
+            };
+            if draining.is_requested() {
+                drained += 1;
             }
-            
-            // Process triggers
-            if let Err(e) = trigger_clone.write().await.process_event(event).await {
-                tracing::error!("Error processing triggers: {}", e);
+            event_metrics.event(&event.event_type);
+            event_metrics.queue("events", rx.len(), rx.max_capacity());
+            while bursts.try_join_next().is_some() {}
+            let span = tracing::info_span!("event", event_id = %event.id);
+            async {
+                zones.locate(&mut event);
+                if let Some(finder) = &direction_finder {
+                    find_rf_bearing(finder, &mut event).await;
+                }
+                start_clips(&event_clips, &clip_config, &recorder_clone, &mut event).await;
+                
+                // Dispatch to handlers (logging, integrations)
+                for handler in handlers.iter() {
+                    handler.on_event(&event);
+                }
+                hub.publish_event(&event);
+                
+                // Record event
+                if let Err(e) = recorder_clone.write().await.record_event(&event) {
+                    tracing::error!("Error recording event: {}", e);
+                }
+                
+                // Sample the involved sensors at a high rate for a while
+                if burst_config.applies_to(&event) {
+                    bursts.spawn(capture_burst(
+                        burst_hardware.clone(),
+                        recorder_clone.clone(),
+                        event.id.clone(),
+                        event.sensor_data.iter().map(|s| s.sensor_name.clone()).collect(),
+                        poll_interval / burst_config.rate_multiplier.max(1),
+                        Duration::from_secs(burst_config.duration_secs),
+                        burst_config.block(),
+                    ).in_current_span());
+                }
+                
+                // Process triggers
+                if let Err(e) = trigger_clone.write().await.process_event(event).await {
+                    tracing::error!("Error processing triggers: {}", e);
+                }
+            }.instrument(span).await;
+        }
+        (drained, bursts)
+    });
+    
+    // Check threshold triggers against the latest readings
+    let value_triggers = trigger_manager.clone();
+    let value_task = tokio::spawn(async move {
+        let mut timer = tokio::time::interval(poll_interval);
+        loop {
+            timer.tick().await;
+            if let Err(e) = value_triggers.write().await.process_values().await {
+                tracing::error!("Error processing value triggers: {}", e);
             }
         }
     });
     
+    // Periodic system snapshots in the session
+    if config.heartbeat_interval_secs > 0 {
+        tokio::spawn(run_heartbeat(control.clone(), recorder.clone(), Duration::from_secs(config.heartbeat_interval_secs)));
+    }
+    
+    // Save camera motion heatmaps with the session
+    if config.heatmap.enabled {
+        let interval = Duration::from_secs(config.heatmap.save_interval_secs.max(1));
+        tokio::spawn(run_heatmaps(fusion_engine.clone(), recorder.clone(), interval));
+    }
+    
+    // Note devices plugged in and out in the session
+    if let Some(events) = device_events {
+        tokio::spawn(run_device_events(events, recorder.clone()));
+    }
+    
+    // Tell the handlers about sensors the watchdog finds offline and back
+    if let Some(events) = watchdog_events {
+        tokio::spawn(run_watchdog_events(events, sensor_handlers, recorder.clone()));
+    }
+    
+    // Stream clock alignment, stored with the session for review
+    tokio::spawn(run_clock_reports(hardware_manager.clock().clone(), recorder.clone(), CLOCK_REPORT_INTERVAL));
+    
+    // Spawn maintenance scheduler
+    if !config.maintenance.is_empty() {
+        let scheduler = MaintenanceScheduler::new(config.maintenance.clone())?;
+        tokio::spawn(run_maintenance(scheduler, hardware_manager.clone(), fusion_engine.clone(), recorder.clone()));
+    }
+    
+    // Spawn physical control handler
+    if let Some(input_rx) = init_controls(&config) {
+        tokio::spawn(handle_controls(input_rx, control.clone()));
+    }
+    
+    // Report laser grid passes
+    for grid in &config.laser_grids {
+        match LaserGridMonitor::open(grid) {
+            Ok(monitor) => {
+                tracing::info!("Monitoring laser grid {} ({} beams)", grid.name, grid.rx_pins.len());
+                tokio::spawn(run_laser_grid(monitor.spawn(), fusion_engine.clone()));
+            }
+            Err(e) => tracing::warn!("Laser grid {} unavailable: {}", grid.name, e),
+        }
+    }
+    
+    // Log scheduled wideband sweeps in the session
+    if let Some(sweep) = &config.sdr_sweep {
+        match WidebandSweep::open(sweep.clone()) {
+            Ok(mut sweeper) => {
+                if let Some(store) = hardware_manager.calibrations() {
+                    if let Err(e) = sweeper.load_power_calibration(store) {
+                        tracing::warn!("SDR power calibration not loaded: {}", e);
+                    }
+                }
+                tracing::info!("Sweeping {:.0}-{:.0} MHz every {} s", sweep.start_mhz, sweep.end_mhz, sweep.interval_secs);
+                let (tx, rx) = tokio::sync::mpsc::channel(4);
+                sweeper.spawn(tx);
+                tokio::spawn(run_sdr_sweep(rx, recorder.clone()));
+            }
+            Err(e) => tracing::warn!("Wideband sweep unavailable: {}", e),
+        }
+    }
+    
     // Print system status
     print_system_status(&config).await;
     
@@ -123,45 +485,475 @@ async fn main() -> Result<()> {
     tracing::info!("Press Ctrl+C to stop");
     
     // Wait for shutdown signal
+    let (mut sensor_running, mut event_running) = (true, true);
     tokio::select! {
         _ = tokio::signal::ctrl_c() => {
             tracing::info!("Shutdown signal received");
         }
-        _ = sensor_task => {
+        _ = &mut sensor_task => {
             tracing::warn!("Sensor task ended unexpectedly");
+            sensor_running = false;
         }
-        _ = event_task => {
+        _ = &mut event_task => {
             tracing::warn!("Event task ended unexpectedly");
+            event_running = false;
+        }
+    }
+    
+    tracing::info!("Shutting down (up to {} s)...", config.shutdown_timeout_secs);
+    let mut shutdown = Shutdown::new(config.shutdown_timeout_secs);
+    
+    // Stop producing readings and action firings
+    let started = Instant::now();
+    value_task.abort();
+    let stopped = shutdown.wait(hardware_manager.stop_polling()).await.is_some();
+    shutdown.stage("stop_polling", started, stopped, None);
+    
+    // Process the readings and events already queued
+    let started = Instant::now();
+    sensor_drain.request();
+    let readings = if sensor_running { shutdown.wait(&mut sensor_task).await } else { Some(Ok(0)) };
+    match readings {
+        Some(Ok(n)) => shutdown.stage("drain_readings", started, true, Some(format!("{} readings", n))),
+        Some(Err(e)) => shutdown.stage("drain_readings", started, false, Some(e.to_string())),
+        None => {
+            sensor_task.abort();
+            shutdown.stage("drain_readings", started, false, None);
+        }
+    }
+    
+    let started = Instant::now();
+    event_drain.request();
+    let events = if event_running { shutdown.wait(&mut event_task).await } else { Some(Ok((0, tokio::task::JoinSet::new()))) };
+    let mut bursts = match events {
+        Some(Ok((n, bursts))) => {
+            shutdown.stage("drain_events", started, true, Some(format!("{} events", n)));
+            Some(bursts)
+        }
+        Some(Err(e)) => {
+            shutdown.stage("drain_events", started, false, Some(e.to_string()));
+            None
+        }
+        None => {
+            event_task.abort();
+            shutdown.stage("drain_events", started, false, None);
+            None
+        }
+    };
+    
+    // Let running and queued trigger actions finish
+    let started = Instant::now();
+    let actions = trigger_manager.read().await.actions().clone();
+    let finished = shutdown.wait(actions.drain()).await.is_some();
+    let detail = (!finished).then(|| format!("{} actions still queued", actions.queued()));
+    shutdown.stage("finish_actions", started, finished, detail);
+    
+    // Flush bursts, audio clips and the session
+    let started = Instant::now();
+    let mut flushed = true;
+    if let Some(bursts) = &mut bursts {
+        let pending = bursts.len();
+        if shutdown.wait(async { while bursts.join_next().await.is_some() {} }).await.is_none() {
+            tracing::warn!("Abandoning {} of {} burst captures", bursts.len(), pending);
+            bursts.abort_all();
+            flushed = false;
         }
     }
+    let pending: Vec<AudioClip> = clips.lock().unwrap().values_mut().flat_map(|clip| clip.flush()).collect();
+    let clip_count = pending.len();
+    if let Err(e) = tokio::task::spawn_blocking(move || pending.iter().for_each(write_clip)).await {
+        tracing::error!("Audio clip writer failed: {}", e);
+        flushed = false;
+    }
+    save_heatmaps(&fusion_engine, &recorder).await;
+    save_clock_report(hardware_manager.clock(), &recorder).await;
+    if let Err(e) = recorder.write().await.flush() {
+        tracing::error!("Error flushing recordings: {}", e);
+        flushed = false;
+    }
+    match recorder.write().await.end_session() {
+        Ok(Some(session)) => {
+            tracing::info!("Recording session ended: {} events captured", session.event_count);
+            let config = control.config().await;
+            if config.significance.enabled {
+                control::save_significance(PathBuf::from(&config.data_directory), session.id, config.significance).await;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("Error ending recording session: {}", e);
+            flushed = false;
+        }
+    }
+    shutdown.stage("flush_recorders", started, flushed, Some(format!("{} clips cut short", clip_count)));
     
-    // Cleanup
-    tracing::info!("Shutting down...");
+    // Close the hardware
+    let started = Instant::now();
+    let failed = hardware_manager.close_all();
+    let detail = (!failed.is_empty()).then(|| failed.iter()
+        .map(|(name, e)| format!("{}: {}", name, e))
+        .collect::<Vec<_>>()
+        .join("; "));
+    shutdown.stage("close_devices", started, failed.is_empty(), detail);
     
-    // End recording session
-    if let Some(session) = recorder.write().await.end_session()? {
-        tracing::info!("Recording session ended: {} events captured", session.event_count);
+    // Tell the broker the node is going offline
+    #[cfg(feature = "mqtt")]
+    if let Some(mqtt) = mqtt {
+        let started = Instant::now();
+        match shutdown.wait(mqtt.close()).await {
+            Some(Ok(())) => shutdown.stage("disconnect_mqtt", started, true, None),
+            Some(Err(e)) => shutdown.stage("disconnect_mqtt", started, false, Some(e.to_string())),
+            None => shutdown.stage("disconnect_mqtt", started, false, None),
+        }
     }
     
+    shutdown.finish(&data_dir);
     tracing::info!("GlowBarn shutdown complete");
     
     Ok(())
 }
 
-fn init_logging() {
-    use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+/// Turn completed laser grid passes into motion events
+async fn run_laser_grid(mut passes: tokio::sync::mpsc::Receiver<glowbarn_hal::BreakAnalysis>, fusion: Arc<RwLock<FusionEngine>>) {
+    while let Some(pass) = passes.recv().await {
+        if let Err(e) = fusion.read().await.process_beam_pass(&pass).await {
+            tracing::error!("Error processing laser grid pass: {}", e);
+        }
+    }
+}
+
+/// Record wideband sweep summaries in the running session
+async fn run_sdr_sweep(mut sweeps: tokio::sync::mpsc::Receiver<SweepSummary>, recorder: Arc<RwLock<EventRecorder>>) {
+    while let Some(summary) = sweeps.recv().await {
+        tracing::debug!("Wideband sweep of {} bands took {} ms", summary.bands.len(), summary.duration_ms);
+        if let Err(e) = recorder.read().await.record_sweep(&summary) {
+            tracing::warn!("Sweep not recorded: {}", e);
+        }
+    }
+}
+
+/// Log hot-plug events, noting in the session when manifest devices come and go
+async fn run_device_events(mut events: tokio::sync::mpsc::Receiver<DeviceEvent>, recorder: Arc<RwLock<EventRecorder>>) {
+    while let Some(event) = events.recv().await {
+        let (action, devnode) = match &event {
+            DeviceEvent::Attached { device, .. } => ("attached", &device.devnode),
+            DeviceEvent::Detached { device, .. } => ("detached", &device.devnode),
+        };
+        if event.affected().is_empty() {
+            tracing::debug!("Device {}: {}", action, devnode.display());
+            continue;
+        }
+        
+        let note = format!("Device {}: {} ({})", action, devnode.display(), event.affected().join(", "));
+        tracing::info!("{}", note);
+        recorder.write().await.add_note(&note);
+    }
+}
+
+/// Pass watchdog events to the handlers, noting them in the session
+async fn run_watchdog_events(
+    mut events: tokio::sync::mpsc::Receiver<WatchdogEvent>,
+    handlers: Arc<Vec<Box<dyn EventHandler>>>,
+    recorder: Arc<RwLock<EventRecorder>>,
+) {
+    while let Some(event) = events.recv().await {
+        let note = match &event {
+            WatchdogEvent::Offline { sensor, last_error } => {
+                for handler in handlers.iter() {
+                    handler.on_sensor_offline(sensor);
+                }
+                match last_error {
+                    Some(error) => format!("Sensor offline: {} ({})", sensor, error),
+                    None => format!("Sensor offline: {}", sensor),
+                }
+            }
+            WatchdogEvent::Online { sensor, downtime } => {
+                for handler in handlers.iter() {
+                    handler.on_sensor_online(sensor);
+                }
+                format!("Sensor online: {} after {:.0} s", sensor, downtime.as_secs_f64())
+            }
+        };
+        recorder.write().await.add_note(&note);
+    }
+}
+
+/// Run maintenance windows, applying re-zero offsets and recording system events
+async fn run_maintenance(
+    mut scheduler: MaintenanceScheduler,
+    hardware: Arc<HardwareManager>,
+    fusion: Arc<RwLock<FusionEngine>>,
+    recorder: Arc<RwLock<EventRecorder>>,
+) {
+    let mut timer = tokio::time::interval(Duration::from_secs(30));
+    
+    loop {
+        timer.tick().await;
+        
+        let engine = fusion.read().await;
+        let Some(outcome) = scheduler.tick(chrono::Local::now(), &engine) else {
+            continue;
+        };
+        
+        if let MaintenanceOutcome::Completed { ref rezero, .. } = outcome {
+            for adjustment in rezero {
+                match hardware.adjust_calibration(&adjustment.sensor_name, -adjustment.drift) {
+                    Ok(offset) => {
+                        tracing::info!("Re-zeroed {} (offset {:+.4})", adjustment.sensor_name, offset);
+                        engine.reset_baseline(&adjustment.sensor_name);
+                    }
+                    Err(e) => tracing::warn!("Failed to re-zero {}: {}", adjustment.sensor_name, e),
+                }
+            }
+        }
+        drop(engine);
+        
+        if let Err(e) = recorder.write().await.record_event(&outcome.to_event()) {
+            tracing::error!("Error recording maintenance event: {}", e);
+        }
+    }
+}
+
+/// Record a system snapshot in the running session every `interval`
+async fn run_heartbeat(control: Arc<NodeControl>, recorder: Arc<RwLock<EventRecorder>>, interval: Duration) {
+    let mut timer = tokio::time::interval(interval);
+    timer.tick().await;
+    
+    loop {
+        timer.tick().await;
+        
+        let snapshot = control.snapshot().await;
+        if let Err(e) = recorder.read().await.record_heartbeat(&snapshot) {
+            tracing::warn!("Heartbeat not recorded: {}", e);
+        }
+    }
+}
+
+/// Save heatmaps to the running session every `interval`, starting them
+/// over when a new session begins
+async fn run_heatmaps(fusion: Arc<RwLock<FusionEngine>>, recorder: Arc<RwLock<EventRecorder>>, interval: Duration) {
+    let mut timer = tokio::time::interval(interval);
+    timer.tick().await;
+    let mut session_id = recorder.read().await.current_session().map(|s| s.id.clone());
+    
+    loop {
+        timer.tick().await;
+        
+        let current = recorder.read().await.current_session().map(|s| s.id.clone());
+        if current != session_id {
+            fusion.read().await.reset_heatmaps();
+            session_id = current;
+            continue;
+        }
+        save_heatmaps(&fusion, &recorder).await;
+    }
+}
+
+async fn save_heatmaps(fusion: &Arc<RwLock<FusionEngine>>, recorder: &Arc<RwLock<EventRecorder>>) {
+    let heatmaps = fusion.read().await.heatmaps();
+    let recorder = recorder.read().await;
+    for heatmap in &heatmaps {
+        if let Err(e) = recorder.record_heatmap(heatmap) {
+            tracing::warn!("Heatmap of {} not saved: {}", heatmap.camera, e);
+        }
+    }
+}
+
+/// How often stream clock offsets are saved to the running session
+const CLOCK_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Save stream clock offsets to the running session every `interval`,
+/// measuring them afresh for each new session
+async fn run_clock_reports(clock: SyncClock, recorder: Arc<RwLock<EventRecorder>>, interval: Duration) {
+    let mut timer = tokio::time::interval(interval);
+    timer.tick().await;
+    let mut session_id = recorder.read().await.current_session().map(|s| s.id.clone());
     
-    let filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info,glowbarn=debug,glowbarn_hal=debug,glowbarn_sensors=debug"));
+    loop {
+        timer.tick().await;
+        
+        let current = recorder.read().await.current_session().map(|s| s.id.clone());
+        if current != session_id {
+            clock.reset();
+            session_id = current;
+            continue;
+        }
+        save_clock_report(&clock, &recorder).await;
+    }
+}
+
+async fn save_clock_report(clock: &SyncClock, recorder: &Arc<RwLock<EventRecorder>>) {
+    if let Err(e) = recorder.read().await.record_clock(&clock.report()) {
+        tracing::warn!("Clock offsets not saved: {}", e);
+    }
+}
+
+/// Capture a high-rate burst around an event and attach it to the session
+async fn capture_burst(
+    hardware: Arc<HardwareManager>,
+    recorder: Arc<RwLock<EventRecorder>>,
+    event_id: String,
+    sensors: Vec<String>,
+    interval: Duration,
+    duration: Duration,
+    block: Duration,
+) {
+    tracing::debug!("Burst capture for event {} ({:?} for {:?})", event_id, interval, duration);
     
-    tracing_subscriber::registry()
-        .with(filter)
-        .with(fmt::layer()
-            .with_target(true)
-            .with_thread_ids(false)
-            .with_file(false)
-            .with_line_number(false))
-        .init();
+    let traces = hardware.capture_burst(&sensors, interval, duration, block).await;
+    if traces.is_empty() {
+        return;
+    }
+    
+    if let Err(e) = recorder.read().await.record_burst(&event_id, &traces) {
+        tracing::error!("Error recording burst for event {}: {}", event_id, e);
+    }
+}
+
+/// Clip recorders by audio input name
+type ClipRecorders = Arc<std::sync::Mutex<HashMap<String, AudioClipRecorder>>>;
+
+/// Buffer an audio chunk and write the clips it completes
+fn feed_clips(clips: &ClipRecorders, input: &str, samples: &[i16]) {
+    let done = match clips.lock().unwrap().get_mut(input) {
+        Some(recorder) => recorder.push(samples),
+        None => return,
+    };
+    for clip in done {
+        tokio::task::spawn_blocking(move || write_clip(&clip));
+    }
+}
+
+fn write_clip(clip: &AudioClip) {
+    match clip.write() {
+        Ok(()) => tracing::debug!("Audio clip saved to {:?} ({:.1}s)", clip.path, clip.duration().as_secs_f64()),
+        Err(e) => tracing::error!("Audio clip {:?} not saved: {}", clip.path, e),
+    }
+}
+
+/// Start clips of the audio around an event and reference them in its
+/// metadata (`audio_clip`, session-relative paths)
+async fn start_clips(
+    clips: &ClipRecorders,
+    config: &config::ClipConfig,
+    recorder: &Arc<RwLock<EventRecorder>>,
+    event: &mut ParanormalEvent,
+) {
+    let inputs = config.inputs_for(event, clips.lock().unwrap().keys());
+    if inputs.is_empty() {
+        return;
+    }
+    
+    let recorder = recorder.read().await;
+    let mut clips = clips.lock().unwrap();
+    let mut saved = Vec::new();
+    for input in inputs {
+        let Some((path, relative)) = recorder.clip_path(&event.id, &input) else {
+            return;
+        };
+        let Some(buffer) = clips.get_mut(&input) else {
+            continue;
+        };
+        if buffer.capture(path) {
+            saved.push(relative);
+        } else {
+            tracing::warn!("Too many pending clips on {}, event {} not clipped", input, event.id);
+        }
+    }
+    if !saved.is_empty() {
+        event.metadata.insert("audio_clip".to_string(), saved.join(","));
+    }
+}
+
+/// Cycle the antenna switch on an RF anomaly's peak and attach the bearing
+async fn find_rf_bearing(finder: &Arc<std::sync::Mutex<DirectionFinder>>, event: &mut ParanormalEvent) {
+    if event.event_type != EventType::RfAnomaly {
+        return;
+    }
+    let Some(peak_mhz) = event.metadata.get("peak_mhz").and_then(|f| f.parse::<f64>().ok()) else {
+        return;
+    };
+    
+    let finder = finder.clone();
+    let result = tokio::task::spawn_blocking(move || finder.lock().unwrap().measure(peak_mhz * 1e6)).await;
+    match result {
+        Ok(Ok(Some(estimate))) => {
+            let powers: Vec<String> = estimate.antennas.iter()
+                .map(|a| format!("{:.0}:{:.1}", a.bearing_deg, a.power_db))
+                .collect();
+            event.metadata.insert("bearing_deg".to_string(), format!("{:.0}", estimate.bearing_deg));
+            event.metadata.insert("bearing_concentration".to_string(), format!("{:.2}", estimate.concentration));
+            event.metadata.insert("antenna_powers".to_string(), powers.join(","));
+        }
+        Ok(Ok(None)) => tracing::debug!("No bearing for {:.3} MHz: antenna powers cancel out", peak_mhz),
+        Ok(Err(e)) => tracing::warn!("Direction finding at {:.3} MHz failed: {}", peak_mhz, e),
+        Err(e) => tracing::error!("Direction finding task failed: {}", e),
+    }
+}
+
+/// Zone map from `[[zone]]` sections plus device zone assignments
+fn build_zone_map(config: &AppConfig) -> ZoneMap {
+    let mut zones = ZoneMap::new(&config.location, config.zones.clone());
+    
+    let devices = config.cameras.iter().map(|c| (&c.name, &c.zone))
+        .chain(config.audio_devices.iter().map(|a| (&a.name, &a.zone)))
+        .chain(config.adc_channels.iter().map(|a| (&a.name, &a.zone)))
+        .chain(config.laser_grids.iter().map(|g| (&g.name, &g.zone)));
+    for (name, zone) in devices {
+        if let Some(zone) = zone {
+            if !zones.assign(name, zone) {
+                tracing::warn!("{} is assigned to undefined zone '{}'", name, zone);
+            }
+        }
+    }
+    
+    zones
+}
+
+/// Set up the mark button and profile knob, if configured
+fn init_controls(config: &AppConfig) -> Option<tokio::sync::mpsc::Receiver<InputEvent>> {
+    let controls = &config.controls;
+    let mut inputs = InputManager::new();
+    let mut any = false;
+    
+    if let Some(pin) = controls.mark_button_pin {
+        match Button::new("mark", pin) {
+            Ok(button) => {
+                inputs.add_button(button);
+                any = true;
+            }
+            Err(e) => tracing::warn!("Mark button unavailable: {}", e),
+        }
+    }
+    
+    if let Some([a, b]) = controls.encoder_pins {
+        match RotaryEncoder::new("profile", a, b) {
+            Ok(encoder) => {
+                inputs.add_encoder(encoder);
+                any = true;
+            }
+            Err(e) => tracing::warn!("Profile knob unavailable: {}", e),
+        }
+    }
+    
+    any.then(|| inputs.spawn())
+}
+
+/// Mark timestamps and switch profiles from physical controls
+async fn handle_controls(mut rx: tokio::sync::mpsc::Receiver<InputEvent>, control: Arc<NodeControl>) {
+    while let Some(event) = rx.recv().await {
+        match event {
+            InputEvent::ButtonPressed { .. } => {
+                if let Err(e) = control.mark(None).await {
+                    tracing::debug!("Mark not recorded: {}", e);
+                }
+            }
+            InputEvent::EncoderTurned { position, .. } => {
+                control.select_profile(position).await;
+            }
+            _ => {}
+        }
+    }
 }
 
 async fn print_system_status(config: &AppConfig) {
@@ -0,0 +1,156 @@
+// Rig manifest: what the node is made of, for traceable evidence
+//
+// Built by NodeControl::manifest, signed with the node's key and appended
+// to the session's manifest.jsonl when recording starts and after each
+// config change (see glowbarn_sensors::manifest).
+
+use crate::config::AppConfig;
+use chrono::{DateTime, Utc};
+use glowbarn_hal::{CalibrationRecord, DeviceHealth, DeviceType};
+use glowbarn_sensors::triggers::Trigger;
+use glowbarn_sensors::SensorSnapshot;
+use serde::Serialize;
+
+/// Fully resolved description of the running rig
+#[derive(Debug, Clone, Serialize)]
+pub struct RigManifest {
+    pub generated_at: DateTime<Utc>,
+    /// Session being recorded when the manifest was taken
+    pub session_id: Option<String>,
+    pub location: String,
+    pub software: Software,
+    pub config_version: u64,
+    /// Running configuration, with the API token and passwords removed
+    pub config: AppConfig,
+    pub devices: Vec<DeviceEntry>,
+    pub sensors: Vec<SensorEntry>,
+    pub calibrations: Vec<CalibrationEntry>,
+    pub triggers: Vec<TriggerEntry>,
+    pub triggers_armed: bool,
+}
+
+/// Versions of the software and host
+#[derive(Debug, Clone, Serialize)]
+pub struct Software {
+    pub glowbarn: &'static str,
+    /// Optional features compiled in
+    pub features: Vec<&'static str>,
+    pub hostname: Option<String>,
+    pub os: Option<String>,
+    pub kernel: Option<String>,
+}
+
+impl Software {
+    pub fn current() -> Self {
+        use sysinfo::System;
+        
+        let mut features = Vec::new();
+        if cfg!(feature = "rpc") {
+            features.push("rpc");
+        }
+        if cfg!(feature = "mqtt") {
+            features.push("mqtt");
+        }
+        Self {
+            glowbarn: env!("CARGO_PKG_VERSION"),
+            features,
+            hostname: System::host_name(),
+            os: System::long_os_version(),
+            kernel: System::kernel_version(),
+        }
+    }
+}
+
+/// A registered device or sensor
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceEntry {
+    pub name: String,
+    pub device_type: DeviceType,
+    pub ready: bool,
+}
+
+impl From<&DeviceHealth> for DeviceEntry {
+    fn from(health: &DeviceHealth) -> Self {
+        Self {
+            name: health.name.clone(),
+            device_type: health.device_type,
+            ready: health.ready,
+        }
+    }
+}
+
+/// A sensor feeding the fusion engine
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorEntry {
+    pub name: String,
+    pub sensor_type: String,
+    pub unit: String,
+}
+
+impl From<&SensorSnapshot> for SensorEntry {
+    fn from(snapshot: &SensorSnapshot) -> Self {
+        Self {
+            name: snapshot.sensor_name.clone(),
+            sensor_type: snapshot.sensor_type.clone(),
+            unit: snapshot.unit.clone(),
+        }
+    }
+}
+
+/// A stored calibration and how old it is
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationEntry {
+    pub device: String,
+    pub serial: Option<String>,
+    pub offset: f64,
+    pub calibrated_at: DateTime<Utc>,
+    pub age_days: f64,
+}
+
+impl From<&CalibrationRecord> for CalibrationEntry {
+    fn from(record: &CalibrationRecord) -> Self {
+        Self {
+            device: record.device.clone(),
+            serial: record.serial.clone(),
+            offset: record.offset,
+            calibrated_at: record.calibrated_at.into(),
+            age_days: record.age().as_secs_f64() / 86_400.0,
+        }
+    }
+}
+
+/// A trigger as configured
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerEntry {
+    pub name: String,
+    pub enabled: bool,
+    pub dry_run: bool,
+    pub cooldown_secs: u64,
+    pub condition: String,
+    pub action: String,
+}
+
+impl From<&Trigger> for TriggerEntry {
+    fn from(trigger: &Trigger) -> Self {
+        Self {
+            name: trigger.name.clone(),
+            enabled: trigger.enabled,
+            dry_run: trigger.dry_run,
+            cooldown_secs: trigger.cooldown.as_secs(),
+            condition: format!("{:?}", trigger.condition),
+            action: format!("{:?}", trigger.action),
+        }
+    }
+}
+
+/// `config` without the secrets a manifest must not carry
+pub fn redact_secrets(mut config: AppConfig) -> AppConfig {
+    config.api_token = None;
+    if let Some(grafana) = config.grafana.as_mut() {
+        grafana.api_key = None;
+    }
+    if let Some(mqtt) = config.mqtt.as_mut() {
+        mqtt.password = None;
+    }
+    config
+}
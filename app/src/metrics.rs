@@ -0,0 +1,202 @@
+// Prometheus metrics (GET /metrics)
+//
+// The pipeline counts readings per sensor, events per type and how full
+// its queues get; everything else (device read errors, trigger firings,
+// dropped camera frames, poll overruns, resources) comes from the same
+// system snapshot as GET /status. Rendered in the Prometheus text format
+// so long-running sessions can be scraped and graphed in Grafana.
+
+use crate::snapshot::SystemSnapshot;
+use glowbarn_sensors::EventType;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Fill of one pipeline queue
+#[derive(Debug, Clone, Copy, Default)]
+struct QueueFill {
+    depth: usize,
+    capacity: usize,
+    /// Deepest the queue has been since startup
+    high_water: usize,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    readings: BTreeMap<String, u64>,
+    events: BTreeMap<String, u64>,
+    queues: BTreeMap<&'static str, QueueFill>,
+}
+
+/// Pipeline counters; cheap to clone
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    counters: Arc<Mutex<Counters>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    fn counters(&self) -> std::sync::MutexGuard<'_, Counters> {
+        self.counters.lock().unwrap_or_else(|e| e.into_inner())
+    }
+    
+    /// Count a reading taken from the reading queue
+    pub fn reading(&self, sensor: &str) {
+        let mut counters = self.counters();
+        match counters.readings.get_mut(sensor) {
+            Some(count) => *count += 1,
+            None => {
+                counters.readings.insert(sensor.to_string(), 1);
+            }
+        }
+    }
+    
+    /// Count a detected event
+    pub fn event(&self, event_type: &EventType) {
+        *self.counters().events.entry(format!("{:?}", event_type)).or_default() += 1;
+    }
+    
+    /// Record how many items wait in `queue` out of `capacity`
+    pub fn queue(&self, queue: &'static str, depth: usize, capacity: usize) {
+        let mut counters = self.counters();
+        let fill = counters.queues.entry(queue).or_default();
+        fill.depth = depth;
+        fill.capacity = capacity;
+        fill.high_water = fill.high_water.max(depth);
+    }
+    
+    /// Prometheus text exposition of the counters and `snapshot`
+    pub fn render(&self, snapshot: &SystemSnapshot) -> String {
+        let mut out = Exposition::default();
+        
+        out.family("glowbarn_uptime_seconds", "gauge", "Seconds since the daemon started");
+        out.sample("glowbarn_uptime_seconds", &[], snapshot.uptime_secs);
+        out.family("glowbarn_config_version", "gauge", "Version of the running configuration");
+        out.sample("glowbarn_config_version", &[], snapshot.config_version);
+        out.family("glowbarn_session_active", "gauge", "1 while a session is being recorded");
+        out.sample("glowbarn_session_active", &[], u8::from(snapshot.session.is_some()));
+        
+        {
+            let counters = self.counters();
+            out.family("glowbarn_readings_total", "counter", "Readings processed per sensor");
+            for (sensor, count) in &counters.readings {
+                out.sample("glowbarn_readings_total", &[("sensor", sensor)], count);
+            }
+            out.family("glowbarn_events_total", "counter", "Events detected per event type");
+            for (event_type, count) in &counters.events {
+                out.sample("glowbarn_events_total", &[("type", event_type)], count);
+            }
+            out.family("glowbarn_queue_depth", "gauge", "Items waiting in a pipeline queue");
+            for (queue, fill) in &counters.queues {
+                out.sample("glowbarn_queue_depth", &[("queue", queue)], fill.depth);
+            }
+            out.family("glowbarn_queue_capacity", "gauge", "Size of a pipeline queue");
+            for (queue, fill) in &counters.queues {
+                out.sample("glowbarn_queue_capacity", &[("queue", queue)], fill.capacity);
+            }
+            out.family("glowbarn_queue_high_water", "gauge", "Deepest a pipeline queue has been since startup");
+            for (queue, fill) in &counters.queues {
+                out.sample("glowbarn_queue_high_water", &[("queue", queue)], fill.high_water);
+            }
+        }
+        
+        out.family("glowbarn_trigger_firings_total", "counter", "Trigger firings since startup, dry runs included");
+        for trigger in &snapshot.triggers {
+            out.sample("glowbarn_trigger_firings_total", &[("trigger", &trigger.name)], trigger.fire_count);
+        }
+        out.family("glowbarn_triggers_armed", "gauge", "1 while triggers run their actions");
+        out.sample("glowbarn_triggers_armed", &[], u8::from(snapshot.triggers_armed));
+        
+        out.family("glowbarn_device_ready", "gauge", "1 while a device or sensor is ready");
+        for device in &snapshot.devices {
+            out.sample("glowbarn_device_ready", &[("device", &device.name)], u8::from(device.ready));
+        }
+        out.family("glowbarn_device_read_errors_total", "counter", "Failed reads per sensor since startup");
+        for device in &snapshot.devices {
+            out.sample("glowbarn_device_read_errors_total", &[("device", &device.name)], device.read_errors);
+        }
+        out.family("glowbarn_device_stuck", "gauge", "1 while a sensor is suspected stuck at one value");
+        for device in &snapshot.devices {
+            out.sample("glowbarn_device_stuck", &[("device", &device.name)], u8::from(device.stuck));
+        }
+        out.family("glowbarn_device_offline", "gauge", "1 while the watchdog reports a sensor offline");
+        for device in &snapshot.devices {
+            out.sample("glowbarn_device_offline", &[("device", &device.name)], u8::from(device.offline));
+        }
+        
+        out.family("glowbarn_camera_frames_captured_total", "counter", "Frames read from each camera");
+        for camera in &snapshot.cameras {
+            out.sample("glowbarn_camera_frames_captured_total", &[("camera", &camera.camera)], camera.captured);
+        }
+        out.family("glowbarn_camera_frames_dropped_total", "counter", "Frames dropped because the reading queue was full");
+        for camera in &snapshot.cameras {
+            out.sample("glowbarn_camera_frames_dropped_total", &[("camera", &camera.camera)], camera.dropped);
+        }
+        
+        if let Some(poll) = &snapshot.poll {
+            out.family("glowbarn_poll_ticks_total", "counter", "Sensor polling ticks");
+            out.sample("glowbarn_poll_ticks_total", &[], poll.ticks);
+            out.family("glowbarn_poll_overruns_total", "counter", "Polling ticks that took longer than the shortest poll interval they served");
+            out.sample("glowbarn_poll_overruns_total", &[], poll.overruns);
+            out.family("glowbarn_poll_last_tick_seconds", "gauge", "Duration of the last polling tick");
+            out.sample("glowbarn_poll_last_tick_seconds", &[], poll.last_tick.as_secs_f64());
+        }
+        
+        let resources = &snapshot.resources;
+        if let Some(memory) = resources.memory_mb {
+            out.family("glowbarn_memory_megabytes", "gauge", "Resident memory of the daemon");
+            out.sample("glowbarn_memory_megabytes", &[], memory);
+        }
+        if let Some(free) = resources.data_free_mb {
+            out.family("glowbarn_data_free_megabytes", "gauge", "Free space on the data directory's filesystem");
+            out.sample("glowbarn_data_free_megabytes", &[], free);
+        }
+        if let Some(temperature) = resources.temperature_c {
+            out.family("glowbarn_host_temperature_celsius", "gauge", "Hottest host temperature sensor");
+            out.sample("glowbarn_host_temperature_celsius", &[], temperature);
+        }
+        out.family("glowbarn_load_average", "gauge", "Host load average");
+        for (window, load) in ["1m", "5m", "15m"].iter().zip(resources.load_average) {
+            out.sample("glowbarn_load_average", &[("window", window)], load);
+        }
+        
+        out.family("glowbarn_scrape_timestamp_seconds", "gauge", "When this snapshot was taken");
+        let now = snapshot.timestamp.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+        out.sample("glowbarn_scrape_timestamp_seconds", &[], now.as_secs_f64());
+        
+        out.text
+    }
+}
+
+/// Builder of the text format
+#[derive(Default)]
+struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", name, kind);
+    }
+    
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+        self.text.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels.iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+                .collect();
+            let _ = write!(self.text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.text, " {}", value);
+    }
+}
+
+/// Label value with backslashes, quotes and newlines escaped
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
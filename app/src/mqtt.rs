@@ -0,0 +1,357 @@
+// MQTT publisher (Home Assistant and other home automation)
+//
+// Readings and events are published to the broker named in [mqtt] of
+// config.toml; Home Assistant discovery configs make the node show up as a
+// device with one sensor entity per GlowBarn sensor, an event entity and an
+// activity binary sensor that automations can trigger on. Publishing never
+// waits on the network: while the broker is unreachable messages queue up
+// to a limit and are then dropped.
+
+use crate::config::MqttConfig;
+use glowbarn_hal::SensorReading;
+use glowbarn_sensors::{EventHandler, EventType, ParanormalEvent};
+use rumqttc::{AsyncClient, ClientError, Event, EventLoop, LastWill, MqttOptions, Outgoing, Packet, QoS};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Messages queued while the broker is slow or unreachable
+const QUEUE: usize = 512;
+/// Wait before reconnecting after a connection error
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Event types offered to Home Assistant's event entity
+const EVENT_TYPES: &[EventType] = &[
+    EventType::EmfAnomaly,
+    EventType::TemperatureAnomaly,
+    EventType::AudioAnomaly,
+    EventType::VisualAnomaly,
+    EventType::MotionDetected,
+    EventType::InfrasoundDetected,
+    EventType::MultiSensorEvent,
+    EventType::RfAnomaly,
+    EventType::DoorEvent,
+    EventType::PressureAnomaly,
+    EventType::VibrationDetected,
+    EventType::HumidityAnomaly,
+    EventType::Maintenance,
+];
+
+/// Reading as published on the sensor's topic
+#[derive(Debug, Serialize)]
+struct ReadingMessage<'a> {
+    value: f64,
+    unit: &'a str,
+    timestamp: String,
+    quality: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<HashMap<&'a str, f64>>,
+}
+
+/// Event as published on the event topic
+#[derive(Debug, Serialize)]
+struct EventMessage<'a> {
+    id: &'a str,
+    event_type: String,
+    timestamp: String,
+    confidence: f64,
+    confidence_level: String,
+    sensors: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    zone: Option<&'a str>,
+    metadata: &'a HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct SinkState {
+    /// Unit of each sensor seen so far (announced to Home Assistant)
+    sensors: HashMap<String, String>,
+    /// When each sensor's reading was last published
+    published: HashMap<String, Instant>,
+}
+
+/// Publishes readings and events to an MQTT broker
+#[derive(Clone)]
+pub struct MqttSink {
+    config: Arc<MqttConfig>,
+    reading_interval: Duration,
+    /// Node location, part of the Home Assistant device name
+    location: String,
+    client: AsyncClient,
+    state: Arc<Mutex<SinkState>>,
+    connected: Arc<AtomicBool>,
+    connection: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+}
+
+impl MqttSink {
+    /// Connect to the broker in the background
+    pub fn start(config: MqttConfig, location: &str) -> Self {
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        options.set_max_packet_size(64 * 1024, 64 * 1024);
+        options.set_last_will(LastWill::new(&config.status_topic, "offline", qos(config.qos), true));
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.clone().unwrap_or_default());
+        }
+        
+        let (client, eventloop) = AsyncClient::new(options, QUEUE);
+        let sink = Self {
+            reading_interval: Duration::try_from_secs_f64(config.reading_interval_secs).unwrap_or_default(),
+            config: Arc::new(config),
+            location: location.to_string(),
+            client,
+            state: Arc::new(Mutex::new(SinkState::default())),
+            connected: Arc::new(AtomicBool::new(false)),
+            connection: Arc::new(Mutex::new(None)),
+        };
+        let handle = tokio::spawn(sink.clone().run(eventloop));
+        *sink.connection.lock().unwrap_or_else(|e| e.into_inner()) = Some(handle);
+        sink
+    }
+    
+    /// Drive the connection until disconnected, reconnecting after errors
+    async fn run(self, mut eventloop: EventLoop) {
+        // Only the first failure of an outage is worth a warning
+        let mut failing = false;
+        loop {
+            match eventloop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    tracing::info!("Connected to MQTT broker {}:{}", self.config.host, self.config.port);
+                    self.connected.store(true, Ordering::Relaxed);
+                    failing = false;
+                    self.announce();
+                }
+                Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    self.connected.store(false, Ordering::Relaxed);
+                    if failing {
+                        tracing::debug!("MQTT connection failed: {}", e);
+                    } else {
+                        tracing::warn!("MQTT connection to {}:{} failed: {}", self.config.host, self.config.port, e);
+                        failing = true;
+                    }
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    }
+    
+    /// Queue a message without waiting for room
+    fn send(&self, topic: &str, retain: bool, payload: Vec<u8>) {
+        if let Err(e) = self.client.try_publish(topic, qos(self.config.qos), retain, payload) {
+            tracing::debug!("MQTT message to {} dropped: {}", topic, e);
+        }
+    }
+    
+    fn send_json(&self, topic: &str, retain: bool, payload: &impl Serialize) {
+        match serde_json::to_vec(payload) {
+            Ok(payload) => self.send(topic, retain, payload),
+            Err(e) => tracing::warn!("MQTT message to {} not encoded: {}", topic, e),
+        }
+    }
+    
+    /// Mark the node online and (re)publish discovery configs, on every connect
+    fn announce(&self) {
+        self.send(&self.config.status_topic, true, b"online".to_vec());
+        if !self.config.discovery {
+            return;
+        }
+        
+        self.send_json(&self.discovery_topic("event", "events"), true, &self.event_discovery());
+        self.send_json(&self.discovery_topic("binary_sensor", "activity"), true, &self.activity_discovery());
+        let sensors = self.state.lock().unwrap_or_else(|e| e.into_inner()).sensors.clone();
+        for (name, unit) in &sensors {
+            self.send_json(&self.discovery_topic("sensor", &topic_safe(name)), true, &self.sensor_discovery(name, unit));
+        }
+    }
+    
+    /// Publish a reading, at most once per `reading_interval_secs` per sensor
+    pub fn publish_reading(&self, reading: &SensorReading) {
+        if !self.config.readings || !reading.value.is_finite() {
+            return;
+        }
+        
+        let new_sensor = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            if state.published.get(&reading.sensor_name)
+                .is_some_and(|last| now.duration_since(*last) < self.reading_interval) {
+                return;
+            }
+            state.published.insert(reading.sensor_name.clone(), now);
+            state.sensors.insert(reading.sensor_name.clone(), reading.unit.clone()).is_none()
+        };
+        if new_sensor && self.config.discovery {
+            self.send_json(
+                &self.discovery_topic("sensor", &topic_safe(&reading.sensor_name)),
+                true,
+                &self.sensor_discovery(&reading.sensor_name, &reading.unit),
+            );
+        }
+        
+        let components = reading.components();
+        let message = ReadingMessage {
+            value: reading.value,
+            unit: &reading.unit,
+            timestamp: rfc3339(reading.timestamp),
+            quality: reading.quality,
+            components: (!components.is_empty()).then(|| components.iter().map(|(axis, v)| (axis.as_str(), *v)).collect()),
+        };
+        self.send_json(&self.sensor_topic(&reading.sensor_name), self.config.retain_readings, &message);
+    }
+    
+    /// Publish an event that meets `min_confidence`
+    pub fn publish_event(&self, event: &ParanormalEvent) {
+        if event.confidence < self.config.min_confidence {
+            return;
+        }
+        
+        let message = EventMessage {
+            id: &event.id,
+            event_type: format!("{:?}", event.event_type),
+            timestamp: rfc3339(event.timestamp),
+            confidence: event.confidence,
+            confidence_level: format!("{:?}", event.confidence_level),
+            sensors: event.sensor_data.iter().map(|s| s.sensor_name.as_str()).collect(),
+            zone: event.location.as_ref().and_then(|l| l.zone.as_deref()),
+            metadata: &event.metadata,
+        };
+        self.send_json(&self.config.event_topic, false, &message);
+    }
+    
+    /// Mark the node offline and disconnect, waiting for both to be sent
+    ///
+    /// Without a connection there is nothing to wait for; the broker already
+    /// holds the `offline` last will.
+    pub async fn close(&self) -> Result<(), ClientError> {
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if !self.connected.load(Ordering::Relaxed) {
+            if let Some(connection) = connection {
+                connection.abort();
+            }
+            return Ok(());
+        }
+        
+        self.client.publish(&self.config.status_topic, qos(self.config.qos), true, "offline").await?;
+        self.client.disconnect().await?;
+        if let Some(connection) = connection {
+            let _ = connection.await;
+        }
+        Ok(())
+    }
+    
+    /// Topic carrying `sensor`'s readings
+    fn sensor_topic(&self, sensor: &str) -> String {
+        self.config.reading_topic.replace("{sensor}", &topic_safe(sensor))
+    }
+    
+    fn discovery_topic(&self, component: &str, object: &str) -> String {
+        format!("{}/{}/{}/{}/config", self.config.discovery_prefix, component, topic_safe(&self.config.client_id), object)
+    }
+    
+    fn unique_id(&self, object: &str) -> String {
+        format!("{}_{}", topic_safe(&self.config.client_id), object)
+    }
+    
+    /// Device all entities belong to
+    fn device(&self) -> Value {
+        json!({
+            "identifiers": [self.config.client_id],
+            "name": format!("GlowBarn {}", self.location),
+            "manufacturer": "GlowBarn",
+            "model": "GlowBarn node",
+            "sw_version": env!("CARGO_PKG_VERSION"),
+        })
+    }
+    
+    fn sensor_discovery(&self, name: &str, unit: &str) -> Value {
+        let topic = self.sensor_topic(name);
+        let mut config = json!({
+            "name": name,
+            "unique_id": self.unique_id(&topic_safe(name)),
+            "state_topic": topic,
+            "value_template": "{{ value_json.value }}",
+            "json_attributes_topic": topic,
+            "state_class": "measurement",
+            "availability_topic": self.config.status_topic,
+            "device": self.device(),
+        });
+        let (device_class, unit) = device_class(unit);
+        if !unit.is_empty() {
+            config["unit_of_measurement"] = json!(unit);
+        }
+        if let Some(device_class) = device_class {
+            config["device_class"] = json!(device_class);
+        }
+        config
+    }
+    
+    fn event_discovery(&self) -> Value {
+        json!({
+            "name": "Event",
+            "unique_id": self.unique_id("events"),
+            "state_topic": self.config.event_topic,
+            "event_types": EVENT_TYPES.iter().map(|t| format!("{:?}", t)).collect::<Vec<_>>(),
+            "availability_topic": self.config.status_topic,
+            "device": self.device(),
+        })
+    }
+    
+    /// On for `activity_secs` after any published event
+    fn activity_discovery(&self) -> Value {
+        json!({
+            "name": "Activity",
+            "unique_id": self.unique_id("activity"),
+            "state_topic": self.config.event_topic,
+            "value_template": "ON",
+            "off_delay": self.config.activity_secs,
+            "availability_topic": self.config.status_topic,
+            "device": self.device(),
+        })
+    }
+}
+
+impl EventHandler for MqttSink {
+    fn on_event(&self, event: &ParanormalEvent) {
+        self.publish_event(event);
+    }
+    
+    fn on_sensor_offline(&self, _sensor_name: &str) {}
+    
+    fn on_sensor_online(&self, _sensor_name: &str) {}
+}
+
+/// Name usable as a single topic level or Home Assistant object ID
+fn topic_safe(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn qos(level: u8) -> QoS {
+    match level {
+        0 => QoS::AtMostOnce,
+        1 => QoS::AtLeastOnce,
+        _ => QoS::ExactlyOnce,
+    }
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()
+}
+
+/// Home Assistant device class and unit for a GlowBarn unit
+fn device_class(unit: &str) -> (Option<&'static str>, &str) {
+    match unit {
+        "C" | "°C" => (Some("temperature"), "°C"),
+        "%RH" => (Some("humidity"), "%"),
+        "hPa" | "Pa" => (Some("pressure"), unit),
+        "dBm" => (Some("signal_strength"), unit),
+        "dB" => (Some("sound_pressure"), unit),
+        "V" => (Some("voltage"), unit),
+        _ => (None, unit),
+    }
+}
@@ -0,0 +1,152 @@
+// Session templates and their pre-flight checklist
+//
+// A template names what a kind of session depends on: sensors that must be
+// up, how fresh their calibration has to be, the disk space a night of
+// recording needs and the triggers to arm. Starting a session from a
+// template runs the checklist first, so a dead microphone is found before
+// the investigation rather than at dawn.
+
+use glowbarn_hal::{DeviceHealth, HardwareManager};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// Session template (`[[session_template]]` sections)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTemplate {
+    pub name: String,
+    
+    /// Location recorded for sessions started from the template
+    #[serde(default)]
+    pub location: Option<String>,
+    
+    /// Sensors and devices that must be registered, ready and reading
+    #[serde(default)]
+    pub required_sensors: Vec<String>,
+    
+    /// Oldest acceptable calibration of the required sensors (days)
+    #[serde(default)]
+    pub max_calibration_age_days: Option<f64>,
+    
+    /// Free space needed in the data directory (MB)
+    #[serde(default)]
+    pub min_disk_free_mb: Option<u64>,
+    
+    /// Triggers armed for the session; the others are disabled (all are
+    /// left as they are if empty)
+    #[serde(default)]
+    pub triggers: Vec<String>,
+    
+    /// Start anyway when checks fail, noting the failures in the session
+    #[serde(default)]
+    pub warn_only: bool,
+}
+
+/// Outcome of one checklist item
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub item: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Outcome of a template's checklist
+#[derive(Debug, Clone, Serialize)]
+pub struct Checklist {
+    pub template: String,
+    pub checks: Vec<Check>,
+}
+
+impl Checklist {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+    
+    /// Failed items as one line, e.g. for an error or a session note
+    pub fn failures(&self) -> String {
+        self.checks.iter()
+            .filter(|c| !c.passed)
+            .map(|c| format!("{}: {}", c.item, c.detail))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Run `template`'s checklist against the rig
+///
+/// `triggers` are the names of the configured triggers.
+pub fn run(template: &SessionTemplate, hardware: Option<&HardwareManager>, triggers: &[String], data_dir: &Path) -> Checklist {
+    let mut checks = Vec::new();
+    let mut check = |item: String, result: Result<String, String>| {
+        let passed = result.is_ok();
+        let detail = result.unwrap_or_else(|e| e);
+        checks.push(Check { item, passed, detail });
+    };
+    
+    let health = hardware.map(|h| h.device_health()).unwrap_or_default();
+    for name in &template.required_sensors {
+        check(format!("sensor {}", name), sensor_ready(health.iter().find(|d| &d.name == name)));
+        
+        if let Some(days) = template.max_calibration_age_days {
+            let max_age = Duration::from_secs_f64(days.max(0.0) * 86_400.0);
+            check(format!("calibration {}", name), calibration_fresh(hardware, name, max_age));
+        }
+    }
+    
+    if let Some(min_mb) = template.min_disk_free_mb {
+        let result = match crate::snapshot::data_free_mb(data_dir) {
+            Some(free) if free >= min_mb => Ok(format!("{} MB free", free)),
+            Some(free) => Err(format!("{} MB free, {} MB needed", free, min_mb)),
+            None => Err(format!("free space of {:?} unknown", data_dir)),
+        };
+        check("disk space".to_string(), result);
+    }
+    
+    for name in &template.triggers {
+        let result = if triggers.contains(name) {
+            Ok("configured".to_string())
+        } else {
+            Err("no such trigger".to_string())
+        };
+        check(format!("trigger {}", name), result);
+    }
+    
+    Checklist {
+        template: template.name.clone(),
+        checks,
+    }
+}
+
+fn sensor_ready(health: Option<&DeviceHealth>) -> Result<String, String> {
+    let health = health.ok_or("not registered")?;
+    if !health.ready {
+        return Err("not ready".to_string());
+    }
+    if health.offline {
+        return Err("offline: no reading within the watchdog timeout".to_string());
+    }
+    if health.consecutive_failures > 0 {
+        return Err(format!(
+            "{} failed reads: {}",
+            health.consecutive_failures,
+            health.last_error.as_deref().unwrap_or("unknown error")
+        ));
+    }
+    if health.stuck {
+        return Err(format!("stuck: {} identical reads", health.identical_reads));
+    }
+    Ok("ready".to_string())
+}
+
+fn calibration_fresh(hardware: Option<&HardwareManager>, name: &str, max_age: Duration) -> Result<String, String> {
+    let store = hardware.and_then(|h| h.calibrations()).ok_or("calibration store disabled")?;
+    let record = store.load(name, None)
+        .map_err(|e| e.to_string())?
+        .ok_or("never calibrated")?;
+    
+    let days = record.age().as_secs_f64() / 86_400.0;
+    if record.age() > max_age {
+        return Err(format!("calibrated {:.1} days ago", days));
+    }
+    Ok(format!("calibrated {:.1} days ago", days))
+}
@@ -0,0 +1,218 @@
+// gRPC remote API (glowbarn-rpc)
+//
+// Lets dashboards on other machines follow a running node: readings and
+// events stream as they happen, and sessions can be started, stopped and
+// annotated. The service is defined in proto/glowbarn.proto; calls go
+// through the same NodeControl as the HTTP API and the control socket.
+
+use crate::control::{ControlError, NodeControl};
+use crate::server::{StreamHub, StreamItem};
+use anyhow::Result;
+use glowbarn_hal::SensorReading;
+use glowbarn_sensors::recording::RecordingSession;
+use glowbarn_sensors::{ParanormalEvent, SensorSnapshot};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+pub mod proto {
+    tonic::include_proto!("glowbarn.v1");
+}
+
+use proto::glowbarn_rpc_server::{GlowbarnRpc, GlowbarnRpcServer};
+
+type RpcStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Serve the remote API on `addr`
+pub async fn serve(addr: &str, hub: StreamHub, control: Arc<NodeControl>) -> Result<()> {
+    let addr = addr.parse()?;
+    tracing::info!("gRPC server listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(GlowbarnRpcServer::new(RpcService { hub, control }))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+struct RpcService {
+    hub: StreamHub,
+    control: Arc<NodeControl>,
+}
+
+impl RpcService {
+    /// Check the `authorization: Bearer <token>` metadata against the API token
+    async fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let token = request.metadata().get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.strip_prefix("Bearer ").unwrap_or(value));
+        if self.control.authorize(token).await {
+            Ok(())
+        } else {
+            Err(Status::unauthenticated("missing or wrong API token"))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl GlowbarnRpc for RpcService {
+    type StreamReadingsStream = RpcStream<proto::Reading>;
+    type StreamEventsStream = RpcStream<proto::Event>;
+    
+    async fn stream_readings(&self, request: Request<proto::ReadingFilter>) -> Result<Response<Self::StreamReadingsStream>, Status> {
+        self.authorize(&request).await?;
+        let sensors = request.into_inner().sensors;
+        
+        let stream = BroadcastStream::new(self.hub.subscribe_readings())
+            .filter_map(move |item| match item {
+                Ok(reading) if sensors.is_empty() || sensors.contains(&reading.sensor_name) => Some(Ok(reading_message(&reading))),
+                Ok(_) => None,
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::debug!("gRPC reading stream lagged, skipped {} readings", skipped);
+                    None
+                }
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+    
+    async fn stream_events(&self, request: Request<proto::EventFilter>) -> Result<Response<Self::StreamEventsStream>, Status> {
+        self.authorize(&request).await?;
+        let filter = request.into_inner();
+        
+        let stream = BroadcastStream::new(self.hub.subscribe())
+            .filter_map(move |item| match item {
+                Ok(StreamItem::Event(event)) => {
+                    let wanted = event.confidence >= filter.min_confidence
+                        && (filter.event_types.is_empty() || filter.event_types.iter().any(|t| event.event_type.matches(t)));
+                    if wanted { Some(Ok(event_message(&event))) } else { None }
+                }
+                Ok(StreamItem::Summary { .. }) => None,
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::warn!("gRPC event stream lagged, skipped {} items", skipped);
+                    None
+                }
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+    
+    async fn start_session(&self, request: Request<proto::StartSessionRequest>) -> Result<Response<proto::Session>, Status> {
+        self.authorize(&request).await?;
+        let start = request.into_inner();
+        let session = self.control
+            .start_session(non_empty(&start.name), non_empty(&start.location), non_empty(&start.template))
+            .await
+            .map_err(status)?;
+        Ok(Response::new(session_message(&session)))
+    }
+    
+    async fn stop_session(&self, request: Request<proto::Empty>) -> Result<Response<proto::Session>, Status> {
+        self.authorize(&request).await?;
+        let session = self.control.end_session().await.map_err(status)?;
+        Ok(Response::new(session_message(&session)))
+    }
+    
+    async fn add_note(&self, request: Request<proto::NoteRequest>) -> Result<Response<proto::Empty>, Status> {
+        self.authorize(&request).await?;
+        let text = request.into_inner().text;
+        if text.trim().is_empty() {
+            return Err(Status::invalid_argument("empty note"));
+        }
+        self.control.add_note(&text).await.map_err(status)?;
+        Ok(Response::new(proto::Empty {}))
+    }
+    
+    async fn list_sensors(&self, request: Request<proto::Empty>) -> Result<Response<proto::SensorList>, Status> {
+        self.authorize(&request).await?;
+        let sensors = self.control.sensors().await.iter().map(sensor_message).collect();
+        Ok(Response::new(proto::SensorList { sensors }))
+    }
+}
+
+fn status(e: ControlError) -> Status {
+    let message = e.to_string();
+    match e {
+        ControlError::InvalidConfig(_) | ControlError::InvalidRequest(_) => Status::invalid_argument(message),
+        ControlError::StaleVersion { .. }
+        | ControlError::SessionActive(_)
+        | ControlError::PreflightFailed { .. }
+        | ControlError::NoSession => Status::failed_precondition(message),
+        ControlError::NoHistory
+        | ControlError::UnknownTemplate(_)
+        | ControlError::UnknownEvent(_)
+        | ControlError::UnknownSession(_)
+        | ControlError::UnknownMapping(_)
+        | ControlError::UnknownProfile(_)
+        | ControlError::UnknownTrigger(_)
+        | ControlError::UnknownIlluminator(_)
+        | ControlError::UnknownSensor(_)
+        | ControlError::UnknownRoute(_) => Status::not_found(message),
+        ControlError::Other(_) => Status::internal(message),
+    }
+}
+
+/// Proto3 strings are empty when unset
+fn non_empty(value: &str) -> Option<&str> {
+    Some(value).filter(|v| !v.is_empty())
+}
+
+fn unix_ms(time: SystemTime) -> i64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn components(components: &[(String, f64)]) -> Vec<proto::Component> {
+    components.iter()
+        .map(|(axis, value)| proto::Component { axis: axis.clone(), value: *value })
+        .collect()
+}
+
+fn reading_message(reading: &SensorReading) -> proto::Reading {
+    proto::Reading {
+        sensor: reading.sensor_name.clone(),
+        value: reading.value,
+        unit: reading.unit.clone(),
+        timestamp_ms: unix_ms(reading.timestamp),
+        quality: reading.quality,
+        components: components(reading.components()),
+    }
+}
+
+fn sensor_message(snapshot: &SensorSnapshot) -> proto::Sensor {
+    proto::Sensor {
+        name: snapshot.sensor_name.clone(),
+        sensor_type: snapshot.sensor_type.clone(),
+        value: snapshot.value,
+        unit: snapshot.unit.clone(),
+        baseline: snapshot.baseline,
+        deviation: snapshot.deviation,
+        components: components(&snapshot.components),
+    }
+}
+
+fn event_message(event: &ParanormalEvent) -> proto::Event {
+    proto::Event {
+        id: event.id.clone(),
+        event_type: format!("{:?}", event.event_type),
+        timestamp_ms: unix_ms(event.timestamp),
+        confidence: event.confidence,
+        confidence_level: format!("{:?}", event.confidence_level),
+        sensors: event.sensor_data.iter().map(sensor_message).collect(),
+        metadata: event.metadata.clone().into_iter().collect(),
+    }
+}
+
+fn session_message(session: &RecordingSession) -> proto::Session {
+    proto::Session {
+        id: session.id.clone(),
+        name: session.name.clone(),
+        location: session.location.clone(),
+        start_time_ms: session.start_time.timestamp_millis(),
+        end_time_ms: session.end_time.map(|t| t.timestamp_millis()),
+        event_count: session.event_count as u64,
+        notes: session.notes.clone(),
+    }
+}
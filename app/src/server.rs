@@ -0,0 +1,721 @@
+// HTTP API: event streaming and node control
+
+use crate::config::ProfileConfig;
+use crate::control::{ConfigApplied, ControlError, LogSettings, NodeControl, Talk, TalkPlayed};
+use crate::manifest::redact_secrets;
+use crate::preflight::Checklist;
+use crate::snapshot::SystemSnapshot;
+use anyhow::Result;
+use axum::body::{Body, Bytes};
+use axum::extract::{DefaultBodyLimit, Path, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use glowbarn_hal::{AudioFormat, AudioRole, IlluminatorMode, IlluminatorStatus, ReadingPayload, SensorReading};
+use glowbarn_sensors::actions::AuditEntry;
+use glowbarn_sensors::manifest::SignedManifest;
+use glowbarn_sensors::recording::{Annotation, RecordingSession};
+use glowbarn_sensors::{ParanormalEvent, SensorSnapshot};
+use hyper::server::conn::http1;
+use hyper_util::rt::{TokioIo, TokioTimer};
+use hyper_util::service::TowerToHyperService;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, RwLock, Semaphore};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+/// Browser dashboard served at `/`
+const DASHBOARD_HTML: &str = include_str!("../assets/dashboard.html");
+
+/// Events kept for clients that connect mid-session (`GET /events/recent`)
+const RECENT_EVENTS: usize = 50;
+
+/// Item pushed to stream subscribers
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StreamItem {
+    Event(Box<ParanormalEvent>),
+    Summary {
+        timestamp: SystemTime,
+        sensors: Vec<SensorSummary>,
+    },
+}
+
+/// Per-sensor statistics over one summary interval
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorSummary {
+    pub name: String,
+    pub unit: String,
+    pub last: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub count: usize,
+}
+
+impl SensorSummary {
+    fn new(reading: &SensorReading) -> Self {
+        Self {
+            name: reading.sensor_name.clone(),
+            unit: reading.unit.clone(),
+            last: reading.value,
+            min: reading.value,
+            max: reading.value,
+            mean: reading.value,
+            count: 1,
+        }
+    }
+    
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.last = value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.mean += (value - self.mean) / self.count as f64;
+    }
+}
+
+/// A reading as streamed on `GET /readings`
+#[derive(Debug, Clone, Serialize)]
+struct LiveReading {
+    sensor: String,
+    value: f64,
+    unit: String,
+    /// Milliseconds since the Unix epoch
+    timestamp_ms: u64,
+}
+
+impl From<&SensorReading> for LiveReading {
+    fn from(reading: &SensorReading) -> Self {
+        Self {
+            sensor: reading.sensor_name.clone(),
+            value: reading.value,
+            unit: reading.unit.clone(),
+            timestamp_ms: reading.timestamp.duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Block of samples from an audio input, for live monitoring
+#[derive(Debug, Clone)]
+struct AudioBlock {
+    input: String,
+    samples: Arc<[i16]>,
+}
+
+/// Shared state between the pipeline and HTTP clients
+#[derive(Clone)]
+pub struct StreamHub {
+    tx: broadcast::Sender<StreamItem>,
+    audio: broadcast::Sender<AudioBlock>,
+    /// Every reading, for dashboards and remote API clients streaming them live
+    readings: broadcast::Sender<SensorReading>,
+    pending: Arc<RwLock<HashMap<String, SensorSummary>>>,
+    recent: Arc<std::sync::Mutex<VecDeque<ParanormalEvent>>>,
+}
+
+impl StreamHub {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(256);
+        // A few seconds of blocks, so a listener on a slow link drops
+        // audio rather than falling ever further behind
+        let (audio, _) = broadcast::channel(64);
+        let (readings, _) = broadcast::channel(1024);
+        Self {
+            tx,
+            audio,
+            readings,
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            recent: Arc::new(std::sync::Mutex::new(VecDeque::with_capacity(RECENT_EVENTS))),
+        }
+    }
+    
+    /// Publish a detected event
+    pub fn publish_event(&self, event: &ParanormalEvent) {
+        if let Ok(mut recent) = self.recent.lock() {
+            if recent.len() == RECENT_EVENTS {
+                recent.pop_front();
+            }
+            recent.push_back(event.clone());
+        }
+        // No subscribers is not an error
+        let _ = self.tx.send(StreamItem::Event(Box::new(event.clone())));
+    }
+    
+    /// Accumulate a reading into the next summary, passing audio blocks
+    /// on to listeners
+    pub async fn add_reading(&self, reading: &SensorReading) {
+        if let ReadingPayload::Audio { samples, .. } = &reading.payload {
+            let _ = self.audio.send(AudioBlock {
+                input: reading.sensor_name.clone(),
+                samples: samples.clone(),
+            });
+        }
+        if self.readings.receiver_count() > 0 {
+            let _ = self.readings.send(reading.clone());
+        }
+        
+        let mut pending = self.pending.write().await;
+        match pending.get_mut(&reading.sensor_name) {
+            Some(summary) => summary.add(reading.value),
+            None => {
+                pending.insert(reading.sensor_name.clone(), SensorSummary::new(reading));
+            }
+        }
+    }
+    
+    /// Publish accumulated summaries every `interval`
+    pub async fn run_summaries(self, interval: Duration) {
+        let mut timer = tokio::time::interval(interval);
+        timer.tick().await;
+        
+        loop {
+            timer.tick().await;
+            
+            let mut sensors: Vec<SensorSummary> = self.pending.write().await
+                .drain()
+                .map(|(_, summary)| summary)
+                .collect();
+            if sensors.is_empty() {
+                continue;
+            }
+            sensors.sort_by(|a, b| a.name.cmp(&b.name));
+            
+            let _ = self.tx.send(StreamItem::Summary {
+                timestamp: SystemTime::now(),
+                sensors,
+            });
+        }
+    }
+    
+    pub fn subscribe(&self) -> broadcast::Receiver<StreamItem> {
+        self.tx.subscribe()
+    }
+    
+    pub fn subscribe_readings(&self) -> broadcast::Receiver<SensorReading> {
+        self.readings.subscribe()
+    }
+    
+    /// Latest published events, oldest first
+    fn recent_events(&self) -> Vec<ParanormalEvent> {
+        self.recent.lock().map(|recent| recent.iter().cloned().collect()).unwrap_or_default()
+    }
+    
+    fn subscribe_audio(&self) -> broadcast::Receiver<AudioBlock> {
+        self.audio.subscribe()
+    }
+}
+
+/// Serve HTTP on `addr`
+///
+/// - `GET /` - browser dashboard: live sensor graphs, recent events,
+///   baseline statistics and session controls (unless `dashboard = false`)
+/// - `GET /readings` - Server-Sent Events, one `reading` per polled value
+/// - `GET /events/recent` - the last 50 events, oldest first
+/// - `GET /events` - Server-Sent Events, one JSON object per `data:` line
+/// - `GET /events.ndjson` - the same stream as plain newline-delimited JSON
+/// - `GET /audio` - live 16-bit PCM WAV of the first EVP microphone, for
+///   listening in from the base room (`GET /audio/<input>` for another
+///   input); requires the API token when one is set
+/// - `GET /config` - the running configuration as TOML, without its secrets
+/// - `PUT /config` - push a newer TOML configuration (aggregator OTA)
+/// - `POST /config/rollback` - restore the previously running configuration
+/// - `GET /status` - system snapshot: sensors, device health, session,
+///   trigger states and resource usage
+/// - `GET /metrics` - Prometheus metrics: readings per sensor, events per
+///   type, trigger firings, queue fill and device read errors (when
+///   `metrics = true`)
+/// - `GET /manifest` - signed rig manifest: resolved configuration, devices,
+///   sensors, calibration ages, triggers and software versions
+/// - `GET /sensors` - latest value and baseline deviation of each sensor
+/// - `GET /triggers/audit` - outcomes of recent trigger actions
+/// - `GET /session` - the session being recorded (`null` when idle)
+/// - `GET /preflight/<template>` - run a session template's checklist
+/// - `POST /session/start` / `POST /session/stop` - control recording;
+///   `{"template": ...}` starts only once its checklist passes
+/// - `POST /session/note` - add `{"text": ...}` to the running session
+/// - `GET /session/annotations` - operator annotations of the running session
+/// - `POST /session/annotations` - annotate it as `{"author": ..., "text": ...}`,
+///   optionally with `"event_id"` and `"tags"`
+/// - `POST /profile` - switch to the configured profile `{"name": ...}`
+/// - `GET /log` - base log filter and per-device level overrides
+/// - `PUT /log/<device>` - log one device at `{"level": "debug"}`
+/// - `DELETE /log/<device>` - drop a device's level override
+pub async fn serve(addr: &str, hub: StreamHub, control: Arc<NodeControl>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!("HTTP server listening on {}", addr);
+    let app = router(ApiState {
+        hub,
+        control,
+        streams: Arc::new(Semaphore::new(MAX_STREAMS)),
+    });
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    
+    loop {
+        // Stop accepting while every slot is taken; waiting clients queue in the backlog
+        let permit = connections.clone().acquire_owned().await?;
+        let (stream, peer) = listener.accept().await?;
+        let service = TowerToHyperService::new(app.clone());
+        
+        tokio::spawn(async move {
+            let connection = http1::Builder::new()
+                .timer(TokioTimer::new())
+                .header_read_timeout(READ_TIMEOUT)
+                .keep_alive(false)
+                .serve_connection(TokioIo::new(stream), service);
+            if let Err(e) = connection.await {
+                tracing::debug!("HTTP client {} disconnected: {}", peer, e);
+            }
+            drop(permit);
+        });
+    }
+}
+
+/// Connections served at once, streams included
+const MAX_CONNECTIONS: usize = 64;
+
+/// Streams served at once, leaving the other connections to requests
+const MAX_STREAMS: usize = 32;
+
+/// Time allowed for the request headers, and again for the body
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest accepted request body (config files are a few KiB)
+const MAX_BODY: usize = 256 * 1024;
+
+/// Largest intercom clip (about 90 s of 16-bit mono at 44.1 kHz)
+const MAX_CLIP: usize = 8 * 1024 * 1024;
+
+/// State shared by the request handlers
+#[derive(Clone)]
+struct ApiState {
+    hub: StreamHub,
+    control: Arc<NodeControl>,
+    /// Slots of the unbounded streams
+    streams: Arc<Semaphore>,
+}
+
+fn router(state: ApiState) -> Router {
+    let public = Router::new()
+        .route("/", get(dashboard))
+        .route("/dashboard", get(dashboard))
+        .route("/events/recent", get(recent_events))
+        .route("/config", get(config))
+        .route("/status", get(status))
+        .route("/metrics", get(metrics))
+        .route("/manifest", get(manifest))
+        .route("/sensors", get(sensors))
+        .route("/triggers/audit", get(trigger_audit))
+        .route("/session", get(session))
+        .route("/session/annotations", get(annotations))
+        .route("/log", get(log_settings))
+        .route("/buslog", get(bus_logging))
+        .route("/illuminators", get(illuminators))
+        .route("/preflight/*template", get(preflight));
+    
+    let streams = Router::new()
+        .route("/readings", get(stream_readings))
+        .route("/events", get(stream_events))
+        .route("/events.ndjson", get(stream_ndjson))
+        .route_layer(middleware::from_fn_with_state(state.clone(), limit_streams));
+    
+    let audio = Router::new()
+        .route("/audio", get(stream_audio))
+        .route("/audio/*input", get(stream_audio))
+        .route_layer(middleware::from_fn_with_state(state.clone(), limit_streams));
+    
+    let talk = Router::new()
+        .route("/talk", post(talk))
+        .route("/talk/*route", post(talk))
+        .route_layer(middleware::from_fn_with_state(MAX_CLIP, read_body));
+    
+    // Bodies are read only once the token has been checked
+    let authorized = Router::new()
+        .route("/config", put(push_config))
+        .route("/config/rollback", post(rollback))
+        .route("/session/start", post(start_session))
+        .route("/session/stop", post(stop_session))
+        .route("/session/note", post(add_note))
+        .route("/session/annotations", post(annotate))
+        .route("/profile", post(set_profile))
+        .route("/buslog", put(set_bus_logging))
+        .route("/log/*device", put(set_log_level).delete(set_log_level))
+        .route("/illuminators/*name", put(set_illuminator).delete(set_illuminator))
+        .route_layer(middleware::from_fn_with_state(MAX_BODY, read_body))
+        .merge(talk)
+        .merge(audio)
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+    
+    public
+        .merge(streams)
+        .merge(authorized)
+        .fallback(|| async { (StatusCode::NOT_FOUND, "not found\n") })
+        // read_body enforces the limits per route
+        .layer(DefaultBodyLimit::disable())
+        .with_state(state)
+}
+
+/// Reject requests without the API token (when one is set)
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let token = request.headers().get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    if !state.control.authorize(token).await {
+        return (StatusCode::UNAUTHORIZED, "unauthorized\n").into_response();
+    }
+    next.run(request).await
+}
+
+/// Answer 503 once [`MAX_STREAMS`] streams are open
+///
+/// The slot is held until the client goes away and the response body is
+/// dropped.
+async fn limit_streams(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let Ok(slot) = state.streams.clone().try_acquire_owned() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "too many open streams\n").into_response();
+    };
+    let (parts, body) = next.run(request).await.into_parts();
+    let body = body.into_data_stream().map(move |chunk| {
+        let _slot = &slot;
+        chunk
+    });
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+/// Buffer the request body within [`READ_TIMEOUT`] and `limit` bytes
+async fn read_body(State(limit): State<usize>, request: Request, next: Next) -> Response {
+    let declared = request.headers().get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared.is_some_and(|length| length > limit) {
+        return (StatusCode::PAYLOAD_TOO_LARGE, "request body too large\n").into_response();
+    }
+    
+    let (parts, body) = request.into_parts();
+    let body = match tokio::time::timeout(READ_TIMEOUT, axum::body::to_bytes(body, limit)).await {
+        Ok(Ok(body)) => body,
+        Ok(Err(e)) => return (StatusCode::BAD_REQUEST, format!("{}\n", e)).into_response(),
+        Err(_) => return (StatusCode::REQUEST_TIMEOUT, "timed out reading request body\n").into_response(),
+    };
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+/// Failed control request, answered with the matching status
+struct ApiError(ControlError);
+
+impl From<ControlError> for ApiError {
+    fn from(e: ControlError) -> Self {
+        Self(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0 {
+            ControlError::InvalidConfig(_) | ControlError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            ControlError::StaleVersion { .. }
+            | ControlError::SessionActive(_)
+            | ControlError::PreflightFailed { .. }
+            | ControlError::NoSession => StatusCode::CONFLICT,
+            ControlError::NoHistory
+            | ControlError::UnknownTemplate(_)
+            | ControlError::UnknownEvent(_)
+            | ControlError::UnknownSession(_)
+            | ControlError::UnknownMapping(_)
+            | ControlError::UnknownProfile(_)
+            | ControlError::UnknownTrigger(_)
+            | ControlError::UnknownIlluminator(_)
+            | ControlError::UnknownSensor(_)
+            | ControlError::UnknownRoute(_) => StatusCode::NOT_FOUND,
+            ControlError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        tracing::warn!("HTTP request failed: {}", self.0);
+        (status, format!("{}\n", self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+async fn dashboard(State(state): State<ApiState>) -> Response {
+    if !state.control.config().await.dashboard {
+        return (StatusCode::NOT_FOUND, "not found\n").into_response();
+    }
+    Html(DASHBOARD_HTML).into_response()
+}
+
+async fn recent_events(State(state): State<ApiState>) -> Json<Vec<ParanormalEvent>> {
+    Json(state.hub.recent_events())
+}
+
+async fn config(State(state): State<ApiState>) -> Result<Response, ApiError> {
+    let config = redact_secrets(state.control.config().await);
+    let config = toml::to_string_pretty(&config).map_err(|e| ControlError::Other(e.into()))?;
+    Ok(([(header::CONTENT_TYPE, "application/toml")], config).into_response())
+}
+
+async fn status(State(state): State<ApiState>) -> Json<SystemSnapshot> {
+    Json(state.control.snapshot().await)
+}
+
+async fn metrics(State(state): State<ApiState>) -> Response {
+    if !state.control.config().await.metrics {
+        return (StatusCode::NOT_FOUND, "not found\n").into_response();
+    }
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], state.control.metrics().await).into_response()
+}
+
+async fn manifest(State(state): State<ApiState>) -> ApiResult<SignedManifest> {
+    Ok(Json(state.control.manifest().await?))
+}
+
+async fn sensors(State(state): State<ApiState>) -> Json<Vec<SensorSnapshot>> {
+    Json(state.control.sensors().await)
+}
+
+async fn trigger_audit(State(state): State<ApiState>) -> Json<Vec<AuditEntry>> {
+    Json(state.control.trigger_audit().await)
+}
+
+async fn session(State(state): State<ApiState>) -> Json<Option<RecordingSession>> {
+    Json(state.control.session().await)
+}
+
+async fn annotations(State(state): State<ApiState>) -> ApiResult<Vec<Annotation>> {
+    Ok(Json(state.control.annotations().await?))
+}
+
+async fn log_settings(State(state): State<ApiState>) -> Json<Option<LogSettings>> {
+    Json(state.control.log_settings())
+}
+
+async fn bus_logging(State(state): State<ApiState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "enabled": state.control.bus_logging() }))
+}
+
+async fn illuminators(State(state): State<ApiState>) -> Json<Vec<IlluminatorStatus>> {
+    Json(state.control.illuminators())
+}
+
+async fn preflight(State(state): State<ApiState>, Path(template): Path<String>) -> ApiResult<Checklist> {
+    Ok(Json(state.control.preflight(&template).await?))
+}
+
+/// Body of `POST /session/start`
+#[derive(Debug, Default, Deserialize)]
+struct StartRequest {
+    name: Option<String>,
+    location: Option<String>,
+    /// Session template whose checklist must pass first
+    template: Option<String>,
+}
+
+/// Body of `POST /session/note`
+#[derive(Debug, Default, Deserialize)]
+struct NoteRequest {
+    text: String,
+}
+
+/// Body of `POST /session/annotations`
+#[derive(Debug, Default, Deserialize)]
+struct AnnotationRequest {
+    author: String,
+    text: String,
+    event_id: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Body of `POST /profile`
+#[derive(Debug, Default, Deserialize)]
+struct ProfileRequest {
+    name: String,
+}
+
+/// JSON body of `POST /talk[/<route>]` (a WAV body is played as is)
+#[derive(Debug, Default, Deserialize)]
+struct TalkRequest {
+    text: String,
+    voice: Option<String>,
+}
+
+/// Body of `PUT /log/<device>`
+#[derive(Debug, Default, Deserialize)]
+struct LogLevelRequest {
+    level: String,
+}
+
+/// Body of `PUT /buslog`
+#[derive(Debug, Default, Deserialize)]
+struct BusLogRequest {
+    enabled: bool,
+}
+
+async fn push_config(State(state): State<ApiState>, body: String) -> ApiResult<ConfigApplied> {
+    Ok(Json(state.control.push_config(&body).await?))
+}
+
+async fn rollback(State(state): State<ApiState>) -> ApiResult<ConfigApplied> {
+    Ok(Json(state.control.rollback().await?))
+}
+
+async fn start_session(State(state): State<ApiState>, body: String) -> ApiResult<RecordingSession> {
+    let start: StartRequest = parse_body(&body)?;
+    Ok(Json(state.control.start_session(start.name.as_deref(), start.location.as_deref(), start.template.as_deref()).await?))
+}
+
+async fn stop_session(State(state): State<ApiState>) -> ApiResult<RecordingSession> {
+    Ok(Json(state.control.end_session().await?))
+}
+
+async fn add_note(State(state): State<ApiState>, body: String) -> ApiResult<serde_json::Value> {
+    let note: NoteRequest = parse_body(&body)?;
+    if note.text.trim().is_empty() {
+        return Err(ControlError::InvalidRequest("empty note".to_string()).into());
+    }
+    state.control.add_note(&note.text).await?;
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+async fn annotate(State(state): State<ApiState>, body: String) -> ApiResult<Annotation> {
+    let annotation: AnnotationRequest = parse_body(&body)?;
+    Ok(Json(state.control.annotate(&annotation.author, &annotation.text,
+        annotation.event_id.as_deref(), &annotation.tags).await?))
+}
+
+async fn set_profile(State(state): State<ApiState>, body: String) -> ApiResult<ProfileConfig> {
+    let profile: ProfileRequest = parse_body(&body)?;
+    Ok(Json(state.control.set_profile(&profile.name).await?))
+}
+
+async fn talk(State(state): State<ApiState>, route: Option<Path<String>>, body: Bytes) -> ApiResult<TalkPlayed> {
+    let talk = if body.starts_with(b"RIFF") {
+        Talk::Clip(body.to_vec())
+    } else {
+        let talk: TalkRequest = parse_body(&String::from_utf8_lossy(&body))?;
+        Talk::Text { text: talk.text, voice: talk.voice }
+    };
+    Ok(Json(state.control.talk(talk, route.as_deref().map(String::as_str)).await?))
+}
+
+async fn set_bus_logging(State(state): State<ApiState>, body: String) -> ApiResult<serde_json::Value> {
+    let request: BusLogRequest = parse_body(&body)?;
+    let enabled = state.control.set_bus_logging(request.enabled).await;
+    Ok(Json(serde_json::json!({ "enabled": enabled })))
+}
+
+async fn set_log_level(State(state): State<ApiState>, method: Method, Path(device): Path<String>, body: String) -> ApiResult<LogSettings> {
+    let level = match method {
+        Method::DELETE => None,
+        _ => Some(parse_body::<LogLevelRequest>(&body)?.level),
+    };
+    Ok(Json(state.control.set_log_level(&device, level.as_deref())?))
+}
+
+async fn set_illuminator(State(state): State<ApiState>, method: Method, Path(name): Path<String>, body: String) -> ApiResult<IlluminatorStatus> {
+    let mode = match method {
+        Method::DELETE => None,
+        _ => Some(parse_body::<IlluminatorMode>(&body)?),
+    };
+    Ok(Json(state.control.set_illuminator(&name, mode)?))
+}
+
+/// Parse a JSON body; an empty body means all defaults
+fn parse_body<T: DeserializeOwned + Default>(body: &str) -> Result<T, ControlError> {
+    if body.trim().is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(body).map_err(|e| ControlError::InvalidRequest(e.to_string()))
+}
+
+/// Headers of the unbounded streams
+const STREAM_HEADERS: [(header::HeaderName, &str); 2] = [
+    (header::CACHE_CONTROL, "no-cache"),
+    (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+];
+
+async fn stream_readings(State(state): State<ApiState>) -> impl IntoResponse {
+    let readings = BroadcastStream::new(state.hub.subscribe_readings()).filter_map(|reading| match reading {
+        Ok(reading) => Some(Event::default().event("reading").json_data(LiveReading::from(&reading))),
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            tracing::debug!("Reading stream client lagging, skipped {} readings", skipped);
+            None
+        }
+    });
+    (STREAM_HEADERS, Sse::new(readings).keep_alive(KeepAlive::default()))
+}
+
+/// Stream items as they are published, skipping those a slow client missed
+fn stream_items(hub: &StreamHub) -> impl Stream<Item = StreamItem> {
+    BroadcastStream::new(hub.subscribe()).filter_map(|item| match item {
+        Ok(item) => Some(item),
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            tracing::warn!("Stream client lagging, skipped {} items", skipped);
+            None
+        }
+    })
+}
+
+async fn stream_events(State(state): State<ApiState>) -> impl IntoResponse {
+    let events = stream_items(&state.hub).map(|item| {
+        let kind = match item {
+            StreamItem::Event(_) => "event",
+            StreamItem::Summary { .. } => "summary",
+        };
+        Event::default().event(kind).json_data(&item)
+    });
+    (STREAM_HEADERS, Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn stream_ndjson(State(state): State<ApiState>) -> impl IntoResponse {
+    let lines = stream_items(&state.hub).map(|item| serde_json::to_string(&item).map(|json| json + "\n"));
+    (STREAM_HEADERS, [(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines))
+}
+
+/// Stream an audio input as a WAV of unbounded length
+///
+/// Without a name, the first `evp_mic` input is streamed. Blocks a slow
+/// client falls behind on are skipped, leaving a gap instead of delay.
+async fn stream_audio(State(state): State<ApiState>, input: Option<Path<String>>) -> Response {
+    let config = state.control.config().await;
+    let device = config.audio_devices.iter().find(|a| match input.as_deref() {
+        Some(name) => a.name == *name,
+        None => a.role == AudioRole::EvpMic,
+    });
+    let Some(device) = device else {
+        return (StatusCode::NOT_FOUND, "no such audio input\n").into_response();
+    };
+    let name = device.name.clone();
+    let format = AudioFormat {
+        bits_per_sample: 16,
+        ..device.format()
+    };
+    
+    // Subscribe before the header goes out so no block is missed
+    let rx = state.hub.subscribe_audio();
+    tracing::info!("Audio listener attached to {}", name);
+    let header = tokio_stream::once(Bytes::from(format.wav_header(None)));
+    let blocks = BroadcastStream::new(rx).filter_map(move |block| match block {
+        Ok(block) if block.input == name => {
+            Some(Bytes::from_iter(block.samples.iter().flat_map(|s| s.to_le_bytes())))
+        }
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            tracing::warn!("Audio listener on {} lagging, skipped {} blocks", name, skipped);
+            None
+        }
+    });
+    let body = Body::from_stream(header.chain(blocks).map(Ok::<_, std::convert::Infallible>));
+    (STREAM_HEADERS, [(header::CONTENT_TYPE, "audio/wav")], body).into_response()
+}
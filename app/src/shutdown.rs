@@ -0,0 +1,139 @@
+// Ordered shutdown
+//
+// On Ctrl+C the producers stop first and every queue behind them runs
+// empty before the session is closed: polling stops, the sensor and event
+// loops process what is already queued, trigger actions finish, then
+// bursts, audio clips and the session are flushed and the devices closed.
+// The waiting stages share one time limit; flushing and closing always run.
+// What each stage did is logged and saved as shutdown.json in the data
+// directory.
+
+use serde::Serialize;
+use std::future::Future;
+use std::path::Path;
+use std::time::{Instant, SystemTime};
+use tokio::sync::watch;
+
+/// File in the data directory holding the last shutdown report
+pub const SHUTDOWN_REPORT_FILE: &str = "shutdown.json";
+
+/// Asks consumer loops to stop once their queue is empty
+pub struct DrainHandle(watch::Sender<bool>);
+
+impl DrainHandle {
+    pub fn request(&self) {
+        self.0.send_replace(true);
+    }
+}
+
+/// Consumer side of a [`DrainHandle`]
+///
+/// Select on [`requested`](Self::requested) after the queue, with `biased;`,
+/// so it only wins once the queue is empty.
+#[derive(Clone)]
+pub struct Drain(watch::Receiver<bool>);
+
+impl Drain {
+    /// Resolves once a drain is requested
+    pub async fn requested(&mut self) {
+        if self.0.wait_for(|requested| *requested).await.is_err() {
+            // Handle dropped without a request: never drain
+            std::future::pending::<()>().await;
+        }
+    }
+    
+    pub fn is_requested(&self) -> bool {
+        *self.0.borrow()
+    }
+}
+
+pub fn drain() -> (DrainHandle, Drain) {
+    let (tx, rx) = watch::channel(false);
+    (DrainHandle(tx), Drain(rx))
+}
+
+/// Outcome of one shutdown stage
+#[derive(Debug, Clone, Serialize)]
+pub struct StageReport {
+    pub stage: String,
+    pub elapsed_ms: u64,
+    /// False if the stage ran out of time or failed in part
+    pub completed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// What the shutdown sequence did (`shutdown.json`)
+#[derive(Debug, Clone, Serialize)]
+pub struct ShutdownReport {
+    pub started: SystemTime,
+    pub elapsed_ms: u64,
+    pub timeout_secs: u64,
+    pub stages: Vec<StageReport>,
+}
+
+/// Runs the stages against a common deadline and reports on them
+pub struct Shutdown {
+    report: ShutdownReport,
+    started: Instant,
+    deadline: tokio::time::Instant,
+}
+
+impl Shutdown {
+    pub fn new(timeout_secs: u64) -> Self {
+        let started = Instant::now();
+        Self {
+            report: ShutdownReport {
+                started: SystemTime::now(),
+                elapsed_ms: 0,
+                timeout_secs,
+                stages: Vec::new(),
+            },
+            started,
+            deadline: tokio::time::Instant::from_std(started) + std::time::Duration::from_secs(timeout_secs),
+        }
+    }
+    
+    /// Wait for `work` until the deadline; `None` if it ran out of time
+    pub async fn wait<T>(&self, work: impl Future<Output = T>) -> Option<T> {
+        tokio::time::timeout_at(self.deadline, work).await.ok()
+    }
+    
+    /// Record a finished stage that started at `since`
+    pub fn stage(&mut self, stage: &str, since: Instant, completed: bool, detail: Option<String>) {
+        let elapsed_ms = since.elapsed().as_millis() as u64;
+        match (&detail, completed) {
+            (Some(detail), true) => tracing::info!("Shutdown: {} done in {} ms ({})", stage, elapsed_ms, detail),
+            (None, true) => tracing::info!("Shutdown: {} done in {} ms", stage, elapsed_ms),
+            (detail, false) => tracing::warn!("Shutdown: {} incomplete after {} ms ({})", stage, elapsed_ms, detail.as_deref().unwrap_or("timed out")),
+        }
+        self.report.stages.push(StageReport {
+            stage: stage.to_string(),
+            elapsed_ms,
+            completed,
+            detail,
+        });
+    }
+    
+    /// Finish the report and save it to `data_dir`
+    pub fn finish(mut self, data_dir: &Path) -> ShutdownReport {
+        self.report.elapsed_ms = self.started.elapsed().as_millis() as u64;
+        let incomplete = self.report.stages.iter().filter(|s| !s.completed).count();
+        if incomplete == 0 {
+            tracing::info!("Shutdown completed in {} ms", self.report.elapsed_ms);
+        } else {
+            tracing::warn!("Shutdown took {} ms with {} incomplete stages", self.report.elapsed_ms, incomplete);
+        }
+        
+        let path = data_dir.join(SHUTDOWN_REPORT_FILE);
+        match serde_json::to_string_pretty(&self.report) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::warn!("Shutdown report not saved to {:?}: {}", path, e);
+                }
+            }
+            Err(e) => tracing::warn!("Shutdown report not serialized: {}", e),
+        }
+        self.report
+    }
+}
@@ -0,0 +1,102 @@
+// System snapshot: the whole node's state in one serializable value
+//
+// Built on demand for `GET /status`, `glowbarn-cli status` and the
+// periodic heartbeat records written to the running session.
+
+use glowbarn_hal::{CameraStats, ClockReport, DeviceHealth, IlluminatorStatus, PollStats};
+use glowbarn_sensors::fusion::SensorBaseline;
+use glowbarn_sensors::recording::RecordingSession;
+use glowbarn_sensors::triggers::TriggerState;
+use glowbarn_sensors::SensorSnapshot;
+use serde::Serialize;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// State of the running node
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemSnapshot {
+    pub timestamp: SystemTime,
+    pub location: String,
+    pub config_version: u64,
+    pub uptime_secs: u64,
+    /// Latest value and baseline of every sensor, by name
+    pub sensors: Vec<SensorState>,
+    pub devices: Vec<DeviceHealth>,
+    pub poll: Option<PollStats>,
+    /// Frames captured, dropped and processed per camera
+    pub cameras: Vec<CameraStats>,
+    /// Capture delay of each stream against the common clock
+    pub clock: Option<ClockReport>,
+    pub illuminators: Vec<IlluminatorStatus>,
+    pub session: Option<RecordingSession>,
+    pub triggers: Vec<TriggerState>,
+    /// Whether triggers run their actions (false: all dry runs)
+    pub triggers_armed: bool,
+    pub resources: ResourceUsage,
+}
+
+/// A sensor's latest reading against its baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorState {
+    #[serde(flatten)]
+    pub latest: SensorSnapshot,
+    pub timestamp: SystemTime,
+    /// Full baseline statistics, once the baseline is valid
+    pub baseline_stats: Option<SensorBaseline>,
+}
+
+/// Host resource usage
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResourceUsage {
+    /// Resident memory of this process
+    pub memory_mb: Option<u64>,
+    pub system_memory_used_mb: u64,
+    pub system_memory_total_mb: u64,
+    /// 1, 5 and 15 minute load averages
+    pub load_average: [f64; 3],
+    /// Hottest temperature sensor (usually the CPU)
+    pub temperature_c: Option<f32>,
+    /// Free space on the filesystem holding the data directory
+    pub data_free_mb: Option<u64>,
+}
+
+impl ResourceUsage {
+    /// Sample current usage; `data_dir` selects the filesystem reported
+    pub fn sample(data_dir: &Path) -> Self {
+        use sysinfo::{Components, System};
+        
+        let mut sys = System::new();
+        sys.refresh_memory();
+        
+        let memory_mb = sysinfo::get_current_pid().ok().and_then(|pid| {
+            sys.refresh_process(pid);
+            sys.process(pid).map(|p| p.memory() / 1024 / 1024)
+        });
+        
+        let load = System::load_average();
+        
+        let temperature_c = Components::new_with_refreshed_list().iter()
+            .map(|c| c.temperature())
+            .filter(|t| t.is_finite())
+            .max_by(|a, b| a.total_cmp(b));
+        
+        Self {
+            memory_mb,
+            system_memory_used_mb: sys.used_memory() / 1024 / 1024,
+            system_memory_total_mb: sys.total_memory() / 1024 / 1024,
+            load_average: [load.one, load.five, load.fifteen],
+            temperature_c,
+            data_free_mb: data_free_mb(data_dir),
+        }
+    }
+}
+
+/// Free space on the filesystem holding `data_dir`
+pub fn data_free_mb(data_dir: &Path) -> Option<u64> {
+    // The data directory lives on the disk with the longest matching mount point
+    let data_dir = data_dir.canonicalize().unwrap_or_else(|_| data_dir.to_path_buf());
+    sysinfo::Disks::new_with_refreshed_list().iter()
+        .filter(|d| data_dir.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(|d| d.available_space() / 1024 / 1024)
+}
@@ -0,0 +1,236 @@
+// Local control socket for the running daemon
+//
+// One command per line, answered with one JSON line:
+// `{"ok": true, "result": ...}` or `{"ok": false, "error": "..."}`.
+//
+//   status                  system snapshot (sensors, devices, session, triggers)
+//   manifest                signed rig manifest (config, devices, triggers, versions)
+//   preflight <template>    run a session template's checklist
+//   start [template]        start recording, from a template if given
+//   stop                    end the running session
+//   note <text>             add a note to the running session
+//   annotate author=<name> [event=<id>] [tag=<tag>]... <text>
+//                           attributed annotation, optionally on an event
+//   annotations             annotations of the running session
+//   mark [label]            timestamp mark in the running session
+//   import-csv <mapping> [session=<id>] <path>
+//                           import a handheld logger CSV into a session
+//   reload                  re-read the config file
+//   profile <name>          switch detection profile
+//   trigger test <name>     fire a trigger's action with a test event
+//   trigger dry-run <name> [on|off]
+//                           log a trigger's firings without running its action
+//   arm | disarm            let triggers run actions, or only log firings
+//   buslog [on|off]         whether bus transactions are logged, or turn
+//                           logging on or off until the config is reloaded
+//   sensor status|pause|resume|reinit|remove <name>
+//                           polling state of a sensor, or suspend, restart,
+//                           re-open or unregister it
+//   sensor rate <name> <hz>|default [phase=<ms>]
+//                           change a sensor's poll rate until restart
+//   fault <sensor> <kind> [delay=<ms>] [p=<probability>] [after=<s>] [for=<s>]
+//                           inject a simulated fault (timeout, garbage,
+//                           bus_error, disappear); needs fault_injection
+//   faults                  scheduled and active faults
+//   fault-clear [sensor]    remove the faults of a sensor, or all
+//
+// Access is controlled by the socket file's permissions.
+
+use crate::control::{ControlError, NodeControl};
+use anyhow::Result;
+use glowbarn_hal::{FaultSpec, PollSchedule};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Listen for commands on `path`, replacing a stale socket file
+pub async fn serve(path: &Path, control: Arc<NodeControl>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o660))?;
+    tracing::info!("Control socket listening on {:?}", path);
+    
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let control = control.clone();
+        
+        tokio::spawn(async move {
+            if let Err(e) = handle_client(stream, control).await {
+                tracing::debug!("Control socket client disconnected: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_client(stream: UnixStream, control: Arc<NodeControl>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        
+        let response = match run_command(line.trim(), &control).await {
+            Ok(result) => serde_json::json!({ "ok": true, "result": result }),
+            Err(e) => serde_json::json!({ "ok": false, "error": e.to_string() }),
+        };
+        writer.write_all(format!("{}\n", response).as_bytes()).await?;
+    }
+    
+    Ok(())
+}
+
+async fn run_command(line: &str, control: &NodeControl) -> Result<serde_json::Value, ControlError> {
+    let (command, args) = line.split_once(' ')
+        .map(|(command, args)| (command, args.trim()))
+        .unwrap_or((line, ""));
+    
+    let result = match (command, args) {
+        ("status", _) => serde_json::to_value(control.snapshot().await),
+        ("manifest", _) => serde_json::to_value(control.manifest().await?),
+        ("preflight", "") => return Err(ControlError::InvalidRequest("template name required".to_string())),
+        ("preflight", template) => serde_json::to_value(control.preflight(template).await?),
+        ("start", template) => {
+            let template = Some(template).filter(|t| !t.is_empty());
+            serde_json::to_value(control.start_session(None, None, template).await?)
+        }
+        ("stop", _) => serde_json::to_value(control.end_session().await?),
+        ("note", "") => return Err(ControlError::InvalidRequest("empty note".to_string())),
+        ("note", text) => {
+            control.add_note(text).await?;
+            Ok(serde_json::Value::Null)
+        }
+        ("annotate", args) => {
+            let mut author = None;
+            let mut event_id = None;
+            let mut tags = Vec::new();
+            let mut rest = args;
+            while let Some((token, tail)) = rest.split_once(' ') {
+                match token.split_once('=') {
+                    Some(("author", value)) => author = Some(value),
+                    Some(("event", value)) => event_id = Some(value),
+                    Some(("tag", value)) => tags.push(value.to_string()),
+                    _ => break,
+                }
+                rest = tail.trim_start();
+            }
+            let author = author.ok_or_else(|| ControlError::InvalidRequest(
+                "usage: annotate author=<name> [event=<id>] [tag=<tag>]... <text>".to_string()))?;
+            serde_json::to_value(control.annotate(author, rest, event_id, &tags).await?)
+        }
+        ("import-csv", args) => {
+            let usage = || ControlError::InvalidRequest("usage: import-csv <mapping> [session=<id>] <path>".to_string());
+            let (mapping, rest) = args.split_once(' ').ok_or_else(usage)?;
+            let (session_id, path) = match rest.trim_start().strip_prefix("session=") {
+                Some(rest) => rest.split_once(' ').map(|(id, path)| (Some(id), path)).ok_or_else(usage)?,
+                None => (None, rest),
+            };
+            serde_json::to_value(control.import_csv(mapping, Path::new(path.trim()), session_id).await?)
+        }
+        ("annotations", _) => serde_json::to_value(control.annotations().await?),
+        ("mark", label) => serde_json::to_value(control.mark(Some(label).filter(|l| !l.is_empty())).await?),
+        ("reload", _) => serde_json::to_value(control.reload().await?),
+        ("profile", "") => return Err(ControlError::InvalidRequest("profile name required".to_string())),
+        ("profile", name) => serde_json::to_value(control.set_profile(name).await?),
+        ("trigger", args) => match args.split_once(' ') {
+            Some(("test", name)) if !name.trim().is_empty() => {
+                control.test_trigger(name.trim()).await?;
+                Ok(serde_json::Value::Null)
+            }
+            Some(("dry-run", args)) if !args.trim().is_empty() => {
+                let (name, dry_run) = match args.trim().rsplit_once(' ') {
+                    Some((name, "off")) => (name.trim(), false),
+                    Some((name, "on")) => (name.trim(), true),
+                    _ => (args.trim(), true),
+                };
+                serde_json::to_value(control.set_dry_run(name, dry_run).await?)
+            }
+            _ => return Err(ControlError::InvalidRequest("usage: trigger test <name> | trigger dry-run <name> [on|off]".to_string())),
+        },
+        ("arm", _) => serde_json::to_value(control.set_armed(true).await?),
+        ("disarm", _) => serde_json::to_value(control.set_armed(false).await?),
+        ("buslog", "") => serde_json::to_value(control.bus_logging()),
+        ("buslog", "on") => serde_json::to_value(control.set_bus_logging(true).await),
+        ("buslog", "off") => serde_json::to_value(control.set_bus_logging(false).await),
+        ("buslog", _) => return Err(ControlError::InvalidRequest("usage: buslog [on|off]".to_string())),
+        ("sensor", args) => {
+            let usage = || ControlError::InvalidRequest(SENSOR_USAGE.to_string());
+            let mut tokens = args.split_whitespace();
+            let (Some(action), Some(name)) = (tokens.next(), tokens.next()) else {
+                return Err(usage());
+            };
+            match action {
+                "status" => serde_json::to_value(control.sensor_status(name)?),
+                "pause" => serde_json::to_value(control.set_sensor_paused(name, true).await?),
+                "resume" => serde_json::to_value(control.set_sensor_paused(name, false).await?),
+                "reinit" => serde_json::to_value(control.reinit_sensor(name).await?),
+                "rate" => serde_json::to_value(control.set_poll_schedule(name, parse_schedule(tokens)?).await?),
+                "remove" => {
+                    control.remove_sensor(name).await?;
+                    Ok(serde_json::Value::Null)
+                }
+                _ => return Err(usage()),
+            }
+        }
+        ("fault", args) => serde_json::to_value(control.inject_fault(&parse_fault(args)?).await?),
+        ("faults", _) => serde_json::to_value(control.faults()?),
+        ("fault-clear", sensor) => serde_json::to_value(control.clear_faults(Some(sensor).filter(|s| !s.is_empty()))?),
+        (command, _) => return Err(ControlError::InvalidRequest(format!("unknown command: {}", command))),
+    };
+    
+    result.map_err(|e| ControlError::Other(e.into()))
+}
+
+const SENSOR_USAGE: &str = "usage: sensor status|pause|resume|reinit|remove <name> | sensor rate <name> <hz>|default [phase=<ms>]";
+
+/// Poll schedule from `sensor rate <name>` arguments
+fn parse_schedule<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Result<PollSchedule, ControlError> {
+    let usage = || ControlError::InvalidRequest(SENSOR_USAGE.to_string());
+    let rate_hz = match tokens.next().ok_or_else(usage)? {
+        "default" => None,
+        rate => Some(rate.parse::<f64>().map_err(|_| ControlError::InvalidRequest(format!("{}: not a rate in Hz", rate)))?),
+    };
+    let phase_ms = match tokens.next() {
+        Some(token) => token.strip_prefix("phase=")
+            .and_then(|ms| ms.parse().ok())
+            .ok_or_else(usage)?,
+        None => 0,
+    };
+    Ok(PollSchedule { rate_hz, phase_ms })
+}
+
+const FAULT_USAGE: &str = "usage: fault <sensor> timeout|garbage|bus_error|disappear [delay=<ms>] [p=<probability>] [after=<s>] [for=<s>]";
+
+/// Fault spec from `fault` arguments
+fn parse_fault(args: &str) -> Result<FaultSpec, ControlError> {
+    let usage = || ControlError::InvalidRequest(FAULT_USAGE.to_string());
+    let mut tokens = args.split_whitespace();
+    let (Some(sensor), Some(kind)) = (tokens.next(), tokens.next()) else {
+        return Err(usage());
+    };
+    
+    let mut spec = serde_json::json!({ "sensor": sensor, "kind": kind });
+    for token in tokens {
+        let (key, value) = token.split_once('=').ok_or_else(usage)?;
+        let field = match key {
+            "delay" => "delay_ms",
+            "p" => "probability",
+            "after" => "after_secs",
+            "for" => "duration_secs",
+            _ => return Err(usage()),
+        };
+        let value: f64 = value.parse()
+            .map_err(|_| ControlError::InvalidRequest(format!("{}: not a number", token)))?;
+        spec[field] = if field == "delay_ms" { (value.max(0.0) as u64).into() } else { value.into() };
+    }
+    serde_json::from_value(spec).map_err(|e| ControlError::InvalidRequest(format!("{}; {}", e, FAULT_USAGE)))
+}
@@ -0,0 +1,133 @@
+//! Fast Fourier transform and spectra
+//!
+//! Transforms run on rustfft, with plans cached per thread and length.
+//! [`fft`] and the spectra zero-pad their input to the next power of two.
+
+use crate::window::Window;
+use num_complex::Complex64;
+use rustfft::{Fft, FftPlanner};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+thread_local! {
+    static PLANNER: RefCell<FftPlanner<f64>> = RefCell::new(FftPlanner::new());
+}
+
+/// Forward FFT plan for `len` points, planned once per thread
+fn plan(len: usize) -> Arc<dyn Fft<f64>> {
+    PLANNER.with(|planner| planner.borrow_mut().plan_fft_forward(len))
+}
+
+/// Forward FFT in place (unnormalised, any length)
+pub fn fft_in_place(buf: &mut [Complex64]) {
+    if buf.len() <= 1 {
+        return;
+    }
+    plan(buf.len()).process(buf);
+}
+
+/// Forward FFT, zero-padding to the next power of two
+pub fn fft(input: &[Complex64]) -> Vec<Complex64> {
+    let mut buf = input.to_vec();
+    buf.resize(input.len().next_power_of_two(), Complex64::new(0.0, 0.0));
+    fft_in_place(&mut buf);
+    buf
+}
+
+/// Single-sided amplitude spectrum of a real signal (`n / 2` bins)
+///
+/// Bin `k` is at `k * sample_rate / n`, where `n` is the padded length.
+/// Amplitudes are scaled so a full-scale sine reads its peak amplitude.
+pub fn magnitude_spectrum(samples: &[f64], window: Window) -> Vec<f64> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    
+    let coefficients = window.coefficients(samples.len());
+    let gain: f64 = coefficients.iter().sum();
+    let input: Vec<Complex64> = samples.iter().zip(&coefficients)
+        .map(|(&s, &w)| Complex64::new(s * w, 0.0))
+        .collect();
+    
+    let spectrum = fft(&input);
+    let half = spectrum.len() / 2;
+    spectrum[..half.max(1)].iter()
+        .map(|c| 2.0 * c.norm() / gain)
+        .collect()
+}
+
+/// Power spectrum of complex (IQ) samples, ordered from `-fs/2` to `+fs/2`
+///
+/// The DC bin sits at index `n / 2`, matching a display centred on the
+/// tuned frequency.
+pub fn power_spectrum_iq(samples: &[Complex64], window: Window) -> Vec<f64> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    
+    let coefficients = window.coefficients(samples.len());
+    let gain: f64 = coefficients.iter().sum();
+    let input: Vec<Complex64> = samples.iter().zip(&coefficients)
+        .map(|(&s, &w)| s * w)
+        .collect();
+    
+    let mut power: Vec<f64> = fft(&input).iter()
+        .map(|c| c.norm_sqr() / (gain * gain))
+        .collect();
+    let half = power.len() / 2;
+    power.rotate_left(half);
+    power
+}
+
+/// Frequency of bin `index` in a spectrum of `bins` bins covering `sample_rate / 2`
+pub fn bin_frequency(index: usize, bins: usize, sample_rate: f64) -> f64 {
+    index as f64 * sample_rate / (2 * bins.max(1)) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    
+    fn sine(frequency: f64, sample_rate: f64, amplitude: f64, len: usize) -> Vec<f64> {
+        (0..len).map(|n| amplitude * (2.0 * PI * frequency * n as f64 / sample_rate).sin()).collect()
+    }
+    
+    fn peak_bin(spectrum: &[f64]) -> usize {
+        (0..spectrum.len()).max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b])).unwrap()
+    }
+    
+    #[test]
+    fn magnitude_spectrum_finds_a_sine() {
+        let samples = sine(100.0, 1024.0, 0.5, 1024);
+        for window in [Window::Rectangular, Window::Hann] {
+            let spectrum = magnitude_spectrum(&samples, window);
+            assert_eq!(spectrum.len(), 512);
+            assert_eq!(peak_bin(&spectrum), 100);
+            assert!((spectrum[100] - 0.5).abs() < 1e-6, "{window:?}: {}", spectrum[100]);
+            assert_eq!(bin_frequency(100, spectrum.len(), 1024.0), 100.0);
+        }
+    }
+    
+    #[test]
+    fn power_spectrum_iq_is_centred_on_dc() {
+        let n = 256;
+        for (cycles, index) in [(32.0, 160), (-32.0, 96), (0.0, 128)] {
+            let samples: Vec<Complex64> = (0..n)
+                .map(|i| Complex64::from_polar(1.0, 2.0 * PI * cycles * i as f64 / n as f64))
+                .collect();
+            let power = power_spectrum_iq(&samples, Window::Rectangular);
+            assert_eq!(power.len(), n);
+            assert_eq!(peak_bin(&power), index);
+            assert!((power[index] - 1.0).abs() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn fft_pads_to_a_power_of_two() {
+        let input = vec![Complex64::new(1.0, 0.0); 5];
+        let spectrum = fft(&input);
+        assert_eq!(spectrum.len(), 8);
+        assert!((spectrum[0].re - 5.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,140 @@
+//! Biquad IIR filters
+//!
+//! Coefficients follow the RBJ audio EQ cookbook. Filters keep their state
+//! between calls, so a stream can be processed in chunks.
+
+use std::f64::consts::PI;
+
+/// Butterworth Q for a maximally flat second-order section
+pub const BUTTERWORTH_Q: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+/// Second-order IIR section (transposed direct form II)
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    /// Build from normalized coefficients (`a0` = 1)
+    pub fn new(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Self {
+        Self { b0, b1, b2, a1, a2, z1: 0.0, z2: 0.0 }
+    }
+    
+    /// Low-pass at `cutoff_hz`
+    pub fn lowpass(sample_rate: f64, cutoff_hz: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(sample_rate, cutoff_hz, q);
+        Self::normalized(
+            (1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0,
+            1.0 + alpha, -2.0 * cos, 1.0 - alpha,
+        )
+    }
+    
+    /// High-pass at `cutoff_hz`
+    pub fn highpass(sample_rate: f64, cutoff_hz: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(sample_rate, cutoff_hz, q);
+        Self::normalized(
+            (1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0,
+            1.0 + alpha, -2.0 * cos, 1.0 - alpha,
+        )
+    }
+    
+    /// Band-pass centred on `center_hz` (0 dB peak gain)
+    pub fn bandpass(sample_rate: f64, center_hz: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(sample_rate, center_hz, q);
+        Self::normalized(
+            alpha, 0.0, -alpha,
+            1.0 + alpha, -2.0 * cos, 1.0 - alpha,
+        )
+    }
+    
+    /// Notch removing `center_hz` (e.g. mains hum)
+    pub fn notch(sample_rate: f64, center_hz: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(sample_rate, center_hz, q);
+        Self::normalized(
+            1.0, -2.0 * cos, 1.0,
+            1.0 + alpha, -2.0 * cos, 1.0 - alpha,
+        )
+    }
+    
+    fn prewarp(sample_rate: f64, freq_hz: f64, q: f64) -> (f64, f64) {
+        let nyquist = sample_rate / 2.0;
+        let w0 = 2.0 * PI * freq_hz.clamp(1e-6, nyquist * 0.999) / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * q.max(1e-6)))
+    }
+    
+    fn normalized(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Self {
+        Self::new(b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0)
+    }
+    
+    /// Filter one sample
+    pub fn process(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+    
+    /// Filter a buffer in place
+    pub fn process_buffer(&mut self, samples: &mut [f64]) {
+        for sample in samples {
+            *sample = self.process(*sample);
+        }
+    }
+    
+    /// Clear the filter state
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const RATE: f64 = 8000.0;
+    
+    /// Steady-state gain of `filter` at `frequency`
+    fn gain(mut filter: Biquad, frequency: f64) -> f64 {
+        let len = 8000;
+        let output: Vec<f64> = (0..len)
+            .map(|n| filter.process((2.0 * PI * frequency * n as f64 / RATE).cos()))
+            .collect();
+        output[len / 2..].iter().fold(0.0f64, |peak, y| peak.max(y.abs()))
+    }
+    
+    #[test]
+    fn lowpass_gain() {
+        let filter = Biquad::lowpass(RATE, 1000.0, BUTTERWORTH_Q);
+        assert!((gain(filter, 0.0) - 1.0).abs() < 1e-6);
+        assert!((gain(filter, 1000.0) - BUTTERWORTH_Q).abs() < 0.01);
+        assert!(gain(filter, RATE / 2.0) < 1e-6);
+    }
+    
+    #[test]
+    fn highpass_gain() {
+        let filter = Biquad::highpass(RATE, 1000.0, BUTTERWORTH_Q);
+        assert!(gain(filter, 0.0) < 1e-6);
+        assert!((gain(filter, 1000.0) - BUTTERWORTH_Q).abs() < 0.01);
+        assert!((gain(filter, RATE / 2.0) - 1.0).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn bandpass_and_notch_gain() {
+        let bandpass = Biquad::bandpass(RATE, 1000.0, 2.0);
+        assert!(gain(bandpass, 0.0) < 1e-6);
+        assert!((gain(bandpass, 1000.0) - 1.0).abs() < 0.01);
+        assert!(gain(bandpass, RATE / 2.0) < 1e-6);
+        
+        let notch = Biquad::notch(RATE, 1000.0, 2.0);
+        assert!((gain(notch, 0.0) - 1.0).abs() < 1e-6);
+        assert!(gain(notch, 1000.0) < 0.01);
+        assert!((gain(notch, RATE / 2.0) - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,128 @@
+//! Goertzel single-frequency detector
+//!
+//! Cheaper than a full FFT when only a few frequencies matter, such as
+//! mains hum and its harmonics.
+
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+/// Power of one frequency over a block of samples
+#[derive(Debug, Clone, Copy)]
+pub struct Goertzel {
+    frequency: f64,
+    sample_rate: f64,
+    omega: f64,
+    coeff: f64,
+}
+
+impl Goertzel {
+    /// Detector for `frequency` Hz at `sample_rate`
+    pub fn new(frequency: f64, sample_rate: f64) -> Self {
+        let omega = 2.0 * PI * frequency / sample_rate;
+        Self {
+            frequency,
+            sample_rate,
+            omega,
+            coeff: 2.0 * omega.cos(),
+        }
+    }
+    
+    /// Target frequency in Hz
+    pub fn frequency(&self) -> f64 {
+        self.frequency
+    }
+    
+    /// Sample rate the detector was built for
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+    
+    fn run(&self, samples: &[f64]) -> (f64, f64) {
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for &x in samples {
+            let s0 = x + self.coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        (s1, s2)
+    }
+    
+    /// Squared magnitude of the target frequency in `samples`
+    pub fn power(&self, samples: &[f64]) -> f64 {
+        let (s1, s2) = self.run(samples);
+        (s1 * s1 + s2 * s2 - self.coeff * s1 * s2).max(0.0)
+    }
+    
+    /// DFT term of the target frequency, referenced to the first sample
+    pub fn dft(&self, samples: &[f64]) -> Complex64 {
+        if samples.is_empty() {
+            return Complex64::new(0.0, 0.0);
+        }
+        let (s1, s2) = self.run(samples);
+        let y = Complex64::new(s1 - s2 * self.omega.cos(), s2 * self.omega.sin());
+        y * Complex64::from_polar(1.0, -self.omega * (samples.len() - 1) as f64)
+    }
+    
+    /// Remove the target frequency's sinusoid from `samples` in place
+    ///
+    /// Subtracts the least-squares tone estimate, which is exact when the
+    /// block spans whole cycles and close otherwise. Returns its amplitude.
+    pub fn subtract(&self, samples: &mut [f64]) -> f64 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let tone = self.dft(samples) * (2.0 / samples.len() as f64);
+        for (n, sample) in samples.iter_mut().enumerate() {
+            *sample -= (tone * Complex64::from_polar(1.0, self.omega * n as f64)).re;
+        }
+        tone.norm()
+    }
+    
+    /// Amplitude of the target frequency (a sine of amplitude A reads ≈ A)
+    pub fn amplitude(&self, samples: &[f64]) -> f64 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        2.0 * self.power(samples).sqrt() / samples.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::{fft, magnitude_spectrum};
+    use crate::window::Window;
+    
+    fn samples() -> Vec<f64> {
+        (0..512)
+            .map(|n| {
+                let t = n as f64 / 512.0;
+                0.8 * (2.0 * PI * 50.0 * t + 0.3).sin() + 0.2 * (2.0 * PI * 120.0 * t).cos()
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn matches_the_fft_bin() {
+        let samples = samples();
+        let spectrum = magnitude_spectrum(&samples, Window::Rectangular);
+        let bins = fft(&samples.iter().map(|&s| Complex64::new(s, 0.0)).collect::<Vec<_>>());
+        
+        for (frequency, amplitude) in [(50.0, 0.8), (120.0, 0.2), (200.0, 0.0)] {
+            let detector = Goertzel::new(frequency, 512.0);
+            let bin = frequency as usize;
+            assert!((detector.amplitude(&samples) - spectrum[bin]).abs() < 1e-9);
+            assert!((detector.amplitude(&samples) - amplitude).abs() < 1e-9);
+            assert!((detector.dft(&samples) - bins[bin]).norm() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn subtract_removes_the_tone() {
+        let mut samples = samples();
+        let detector = Goertzel::new(50.0, 512.0);
+        assert!((detector.subtract(&mut samples) - 0.8).abs() < 1e-9);
+        assert!(detector.amplitude(&samples) < 1e-9);
+        assert!((Goertzel::new(120.0, 512.0).amplitude(&samples) - 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,44 @@
+//! GlowBarn Signal Processing
+//!
+//! Shared DSP primitives for the HAL (audio, SDR) and the sensor fusion
+//! library, so every modality computes spectra, levels and statistics the
+//! same way.
+//!
+//! # Modules
+//!
+//! - [`fft`] - FFT (rustfft) and power/magnitude spectra
+//! - [`window`] - Window functions (Hann, Hamming, Blackman)
+//! - [`filter`] - Biquad filters (low/high/band-pass, notch)
+//! - [`resample`] - Decimation and linear resampling
+//! - [`goertzel`] - Single-frequency power (tone and hum tracking)
+//! - [`stats`] - Mean, variance, RMS, median, zero crossings, correlation,
+//!   sliding-window quantiles
+
+pub mod fft;
+pub mod window;
+pub mod filter;
+pub mod resample;
+pub mod goertzel;
+pub mod stats;
+
+pub use num_complex::Complex64;
+pub use filter::Biquad;
+pub use goertzel::Goertzel;
+pub use resample::Decimator;
+pub use stats::SortedWindow;
+pub use window::Window;
+
+/// 16-bit PCM samples as `f64` (unscaled, ±32768)
+pub fn pcm_to_f64(samples: &[i16]) -> Vec<f64> {
+    samples.iter().map(|&s| s as f64).collect()
+}
+
+/// Power ratio in decibels, floored to avoid `-inf`
+pub fn power_to_db(power: f64) -> f64 {
+    10.0 * power.max(1e-12).log10()
+}
+
+/// Amplitude ratio in decibels, floored to avoid `-inf`
+pub fn amplitude_to_db(amplitude: f64) -> f64 {
+    20.0 * amplitude.max(1e-12).log10()
+}
@@ -0,0 +1,118 @@
+//! Sample-rate conversion
+
+use crate::filter::{Biquad, BUTTERWORTH_Q};
+
+/// Integer-factor decimator with an anti-aliasing low-pass
+///
+/// Two cascaded Butterworth sections cut at 80% of the output Nyquist
+/// frequency. State carries across calls for streaming input.
+#[derive(Debug, Clone)]
+pub struct Decimator {
+    factor: usize,
+    filters: [Biquad; 2],
+    phase: usize,
+}
+
+impl Decimator {
+    /// Decimate `input_rate` by `factor`
+    pub fn new(input_rate: f64, factor: usize) -> Self {
+        let factor = factor.max(1);
+        let cutoff = 0.8 * input_rate / (2.0 * factor as f64);
+        let section = Biquad::lowpass(input_rate, cutoff, BUTTERWORTH_Q);
+        
+        Self {
+            factor,
+            filters: [section, section],
+            phase: 0,
+        }
+    }
+    
+    /// Decimation factor
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+    
+    /// Filter and keep every `factor`-th sample
+    pub fn process(&mut self, samples: &[f64]) -> Vec<f64> {
+        let mut output = Vec::with_capacity(samples.len() / self.factor + 1);
+        
+        for &sample in samples {
+            let filtered = self.filters.iter_mut().fold(sample, |x, f| f.process(x));
+            if self.phase == 0 {
+                output.push(filtered);
+            }
+            self.phase = (self.phase + 1) % self.factor;
+        }
+        
+        output
+    }
+    
+    /// Clear filter state and phase
+    pub fn reset(&mut self) {
+        for filter in &mut self.filters {
+            filter.reset();
+        }
+        self.phase = 0;
+    }
+}
+
+/// Linear-interpolation resampling (no anti-aliasing; decimate first when downsampling a lot)
+pub fn resample_linear(samples: &[f64], from_rate: f64, to_rate: f64) -> Vec<f64> {
+    if samples.len() < 2 || from_rate <= 0.0 || to_rate <= 0.0 {
+        return samples.to_vec();
+    }
+    
+    let step = from_rate / to_rate;
+    let count = ((samples.len() - 1) as f64 / step).floor() as usize + 1;
+    
+    (0..count)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position.floor() as usize;
+            let frac = position - index as f64;
+            match samples.get(index + 1) {
+                Some(&next) => samples[index] * (1.0 - frac) + next * frac,
+                None => samples[index],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    
+    fn tone_through(frequency: f64) -> Vec<f64> {
+        let rate = 8000.0;
+        let samples: Vec<f64> = (0..8000).map(|n| (2.0 * PI * frequency * n as f64 / rate).sin()).collect();
+        let mut decimator = Decimator::new(rate, 4);
+        let mut output = decimator.process(&samples[..3001]);
+        output.extend(decimator.process(&samples[3001..]));
+        output
+    }
+    
+    fn settled_peak(output: &[f64]) -> f64 {
+        output[output.len() / 2..].iter().fold(0.0f64, |peak, y| peak.max(y.abs()))
+    }
+    
+    #[test]
+    fn decimator_output_length() {
+        // Phase carries across the uneven chunks: every 4th sample of the whole stream
+        assert_eq!(tone_through(100.0).len(), 2000);
+    }
+    
+    #[test]
+    fn decimator_rejects_aliases() {
+        // 2.7 kHz would fold onto 700 Hz at the 2 kHz output rate
+        assert!(settled_peak(&tone_through(200.0)) > 0.95);
+        assert!(settled_peak(&tone_through(2700.0)) < 0.01);
+    }
+    
+    #[test]
+    fn linear_resampling() {
+        let samples = [0.0, 1.0, 2.0, 3.0];
+        assert_eq!(resample_linear(&samples, 1.0, 2.0), vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0]);
+        assert_eq!(resample_linear(&samples, 2.0, 1.0), vec![0.0, 2.0]);
+    }
+}
@@ -0,0 +1,407 @@
+//! Descriptive statistics over sample blocks
+
+use std::collections::VecDeque;
+
+/// Arithmetic mean (0 for an empty block)
+pub fn mean(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+/// Sample variance (0 for fewer than two samples)
+pub fn variance(samples: &[f64]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(samples);
+    samples.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / (samples.len() - 1) as f64
+}
+
+/// Sample standard deviation
+pub fn std_dev(samples: &[f64]) -> f64 {
+    variance(samples).sqrt()
+}
+
+/// Root mean square
+pub fn rms(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|&x| x * x).sum::<f64>() / samples.len() as f64).sqrt()
+}
+
+/// Largest absolute value
+pub fn peak(samples: &[f64]) -> f64 {
+    samples.iter().map(|x| x.abs()).fold(0.0, f64::max)
+}
+
+/// Peak over RMS (high for clicks and knocks, √2 for a sine)
+pub fn crest_factor(samples: &[f64]) -> f64 {
+    let rms = rms(samples);
+    if rms > 0.0 { peak(samples) / rms } else { 0.0 }
+}
+
+/// Median (upper median for even lengths; 0 for an empty block)
+pub fn median(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted[sorted.len() / 2]
+}
+
+/// Zero-crossing rate expressed as a frequency in Hz
+///
+/// For a pure tone this is its frequency.
+pub fn zero_crossing_rate(samples: &[f64], sample_rate: f64) -> f64 {
+    if samples.len() < 2 || sample_rate <= 0.0 {
+        return 0.0;
+    }
+    let crossings = samples.windows(2).filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0)).count();
+    let duration = samples.len() as f64 / sample_rate;
+    crossings as f64 / (2.0 * duration)
+}
+
+/// Pearson correlation of the overlapping part of two blocks (-1 to 1)
+pub fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let len = a.len().min(b.len());
+    if len == 0 {
+        return 0.0;
+    }
+    
+    let (a, b) = (&a[..len], &b[..len]);
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    
+    let mut num = 0.0;
+    let mut denom_a = 0.0;
+    let mut denom_b = 0.0;
+    for (&x, &y) in a.iter().zip(b) {
+        num += (x - mean_a) * (y - mean_b);
+        denom_a += (x - mean_a).powi(2);
+        denom_b += (y - mean_b).powi(2);
+    }
+    
+    let denom = (denom_a * denom_b).sqrt();
+    if denom < f64::EPSILON { 0.0 } else { num / denom }
+}
+
+/// Streaming mean and variance (Welford)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunningStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl RunningStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add a sample
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+    
+    /// Samples seen
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    
+    /// Running mean
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+    
+    /// Running sample variance
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 { 0.0 } else { self.m2 / (self.count - 1) as f64 }
+    }
+    
+    /// Running sample standard deviation
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+/// Inverse of the standard normal CDF (Acklam's approximation, error < 1.2e-9)
+///
+/// Maps a percentile rank to the z-score with the same tail probability.
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2,
+        1.38357751867269e2, -3.066479806614716e1, 2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2,
+        6.680131188771972e1, -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838,
+        -2.549732539343734, 4.374664141464968, 2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+    
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Standard normal CDF (complementary error function by Chebyshev fit,
+/// error < 1.2e-7)
+///
+/// Probability that a standard normal variable is at most `z`.
+pub fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.5 * x);
+    let poly = -1.26551223 + t * (1.00002368 + t * (0.37409196 + t * (0.09678418
+        + t * (-0.18628806 + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587
+        + t * (-0.82215223 + t * 0.17087277))))))));
+    let erfc = t * (-x * x + poly).exp();
+    if z >= 0.0 { 1.0 - 0.5 * erfc } else { 0.5 * erfc }
+}
+
+/// The most recent samples, kept sorted for order statistics
+#[derive(Debug, Clone)]
+pub struct SortedWindow {
+    capacity: usize,
+    order: VecDeque<f64>,
+    sorted: Vec<f64>,
+}
+
+impl SortedWindow {
+    /// Window of the last `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            sorted: Vec::with_capacity(capacity),
+        }
+    }
+    
+    /// Add a sample, evicting the oldest when full (NaN is ignored)
+    pub fn push(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                let index = self.sorted.partition_point(|&x| x < oldest);
+                self.sorted.remove(index);
+            }
+        }
+        self.order.push_back(value);
+        let index = self.sorted.partition_point(|&x| x < value);
+        self.sorted.insert(index, value);
+    }
+    
+    /// Samples held
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+    
+    /// Samples in ascending order
+    pub fn sorted(&self) -> &[f64] {
+        &self.sorted
+    }
+    
+    /// Linearly interpolated quantile `q` (0-1; 0 for an empty window)
+    pub fn quantile(&self, q: f64) -> f64 {
+        match self.sorted.len() {
+            0 => 0.0,
+            1 => self.sorted[0],
+            n => {
+                let position = q.clamp(0.0, 1.0) * (n - 1) as f64;
+                let index = position.floor() as usize;
+                let frac = position - index as f64;
+                match self.sorted.get(index + 1) {
+                    Some(&next) => self.sorted[index] * (1.0 - frac) + next * frac,
+                    None => self.sorted[index],
+                }
+            }
+        }
+    }
+    
+    /// Median (0 for an empty window)
+    pub fn median(&self) -> f64 {
+        self.quantile(0.5)
+    }
+    
+    /// Median absolute deviation from the median (0 for an empty window)
+    pub fn mad(&self) -> f64 {
+        let n = self.sorted.len();
+        if n == 0 {
+            return 0.0;
+        }
+        
+        // Deviations grow outwards from the median on both sides, so the
+        // sorted deviations are a merge of the two halves
+        let median = self.median();
+        let sorted = &self.sorted;
+        let split = sorted.partition_point(|&x| x < median);
+        let (mut below, mut above) = (split, split);
+        let mut next = || {
+            let lower = below.checked_sub(1).map(|i| median - sorted[i]);
+            let upper = sorted.get(above).map(|&x| x - median);
+            match (lower, upper) {
+                (Some(l), Some(u)) if l <= u => { below -= 1; l }
+                (Some(l), None) => { below -= 1; l }
+                (_, Some(u)) => { above += 1; u }
+                (None, None) => 0.0,
+            }
+        };
+        
+        for _ in 0..(n - 1) / 2 {
+            next();
+        }
+        let first = next();
+        if n % 2 == 1 { first } else { (first + next()) / 2.0 }
+    }
+    
+    /// Percentile rank of `value` among the samples, strictly inside (0, 1)
+    ///
+    /// Ties count half, and the rank is shrunk towards 0.5 by one sample so
+    /// values beyond everything seen stay finite.
+    pub fn rank(&self, value: f64) -> f64 {
+        let n = self.sorted.len();
+        let below = self.sorted.partition_point(|&x| x < value);
+        let equal = self.sorted[below..].partition_point(|&x| x <= value);
+        (below as f64 + 0.5 * equal as f64 + 0.5) / (n as f64 + 1.0)
+    }
+    
+    /// Remove all samples
+    pub fn clear(&mut self) {
+        self.order.clear();
+        self.sorted.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{PI, SQRT_2};
+    
+    #[test]
+    fn block_statistics() {
+        let samples = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(mean(&samples), 5.0);
+        assert!((variance(&samples) - 32.0 / 7.0).abs() < 1e-12);
+        assert!((std_dev(&samples) - (32.0f64 / 7.0).sqrt()).abs() < 1e-12);
+        assert!((rms(&samples) - 29.0f64.sqrt()).abs() < 1e-12);
+        assert_eq!(peak(&[1.0, -3.0, 2.0]), 3.0);
+        assert_eq!(median(&samples), 5.0);
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(variance(&[1.0]), 0.0);
+    }
+    
+    #[test]
+    fn sine_statistics() {
+        let sine: Vec<f64> = (0..1000).map(|n| (2.0 * PI * 50.0 * n as f64 / 1000.0 + 0.1).sin()).collect();
+        assert!((rms(&sine) - 1.0 / SQRT_2).abs() < 1e-9);
+        assert!((crest_factor(&sine) - SQRT_2).abs() < 0.01);
+        // Within one crossing of the tone frequency over the one-second block
+        assert!((zero_crossing_rate(&sine, 1000.0) - 50.0).abs() <= 0.5);
+    }
+    
+    #[test]
+    fn correlation_sign_and_range() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [2.0, 4.0, 6.0, 8.0, 10.0, 99.0];
+        assert!((correlation(&a, &b) - 1.0).abs() < 1e-12);
+        assert!((correlation(&a, &[5.0, 4.0, 3.0, 2.0, 1.0]) + 1.0).abs() < 1e-12);
+        assert_eq!(correlation(&a, &[1.0; 5]), 0.0);
+    }
+    
+    #[test]
+    fn running_stats_match_the_block() {
+        let samples = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let mut running = RunningStats::new();
+        for &sample in &samples {
+            running.push(sample);
+        }
+        assert_eq!(running.count(), samples.len());
+        assert!((running.mean() - mean(&samples)).abs() < 1e-12);
+        assert!((running.std_dev() - std_dev(&samples)).abs() < 1e-12);
+    }
+    
+    #[test]
+    fn normal_cdf_values() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!((normal_cdf(-1.0) - 0.158_655_25).abs() < 1e-6);
+        for z in [-3.0, -0.5, 0.7, 2.5] {
+            assert!((normal_cdf(z) + normal_cdf(-z) - 1.0).abs() < 1e-12);
+            assert!((normal_quantile(normal_cdf(z)) - z).abs() < 1e-6);
+        }
+    }
+    
+    #[test]
+    fn sorted_window_order_statistics() {
+        let mut window = SortedWindow::new(5);
+        for value in [5.0, 1.0, f64::NAN, 4.0, 2.0, 3.0] {
+            window.push(value);
+        }
+        assert_eq!(window.sorted(), &[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(window.median(), 3.0);
+        assert_eq!(window.quantile(0.125), 1.5);
+        assert_eq!(window.rank(3.0), 0.5);
+        assert!(window.rank(-100.0) > 0.0 && window.rank(100.0) < 1.0);
+        
+        // The oldest sample is evicted first
+        window.push(10.0);
+        assert_eq!(window.sorted(), &[1.0, 2.0, 3.0, 4.0, 10.0]);
+        
+        window.clear();
+        assert!(window.is_empty());
+        assert_eq!(window.median(), 0.0);
+    }
+    
+    #[test]
+    fn sorted_window_mad() {
+        let mut window = SortedWindow::new(7);
+        assert_eq!(window.mad(), 0.0);
+        for value in [1.0, 4.0, 9.0, 2.0, 6.0, 1.0, 2.0] {
+            window.push(value);
+        }
+        assert_eq!(window.mad(), 1.0);
+        
+        // A single outlier barely moves it
+        window.push(1000.0);
+        assert_eq!(window.mad(), 2.0);
+    }
+}
+
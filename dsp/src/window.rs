@@ -0,0 +1,46 @@
+//! Window functions
+
+use std::f64::consts::PI;
+
+/// Spectral window applied before an FFT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Window {
+    /// No tapering
+    Rectangular,
+    /// Good general-purpose leakage/resolution trade-off
+    #[default]
+    Hann,
+    Hamming,
+    /// Lowest leakage, widest main lobe
+    Blackman,
+}
+
+impl Window {
+    /// Window coefficients for `n` samples
+    pub fn coefficients(&self, n: usize) -> Vec<f64> {
+        if n <= 1 {
+            return vec![1.0; n];
+        }
+        
+        let denom = (n - 1) as f64;
+        (0..n)
+            .map(|i| {
+                let x = 2.0 * PI * i as f64 / denom;
+                match self {
+                    Window::Rectangular => 1.0,
+                    Window::Hann => 0.5 - 0.5 * x.cos(),
+                    Window::Hamming => 0.54 - 0.46 * x.cos(),
+                    Window::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                }
+            })
+            .collect()
+    }
+    
+    /// Multiply `samples` by the window in place
+    pub fn apply(&self, samples: &mut [f64]) {
+        let coefficients = self.coefficients(samples.len());
+        for (sample, w) in samples.iter_mut().zip(coefficients) {
+            *sample *= w;
+        }
+    }
+}
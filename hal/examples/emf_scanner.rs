@@ -2,7 +2,10 @@
 //! 
 //! Demonstrates continuous EMF monitoring using RTL-SDR
 
+use glowbarn_hal::HardwareDevice;
 use glowbarn_hal::sdr::{RtlSdr, EmfAnalyzer, RadioScanner};
+#[cfg(not(feature = "rtlsdr"))]
+use glowbarn_hal::sdr::MockSdr;
 use std::time::Duration;
 
 #[tokio::main]
@@ -15,8 +18,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("║    GlowBarn EMF Spectrum Scanner     ║");
     println!("╚══════════════════════════════════════╝\n");
     
-    // Initialize SDR
+    // Initialize SDR (without the rtlsdr feature, a simulated one with an FM carrier)
+    #[cfg(feature = "rtlsdr")]
     let mut sdr = RtlSdr::open(0)?;
+    #[cfg(not(feature = "rtlsdr"))]
+    let mut sdr = RtlSdr::with_source("RTL-SDR (simulated)", MockSdr::new().with_tone(98_100_000.0, 0.5));
     sdr.init()?;
     
     println!("SDR initialized: {}", sdr.name());
@@ -71,7 +77,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             Ok(_) => {
-                if elapsed % 5 == 0 {
+                if elapsed.is_multiple_of(5) {
                     println!("{:>6}s | {:>9} | Normal", elapsed, anomaly_count);
                 }
             }
@@ -114,8 +120,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let avg_power: f64 = samples.iter().map(|s| s.power).sum::<f64>() / samples.len() as f64;
     
     println!("Frequencies with elevated activity:");
-    for sample in samples.iter().filter(|s| s.power > avg_power * 2.0) {
-        println!("  {:.1} MHz - Power: {:.2}", 
+    for sample in samples.iter().filter(|s| s.power > avg_power + 3.0) {
+        println!("  {:.1} MHz - Power: {:.1} dBFS", 
             sample.frequency as f64 / 1_000_000.0,
             sample.power);
     }
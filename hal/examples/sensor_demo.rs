@@ -23,7 +23,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Create hardware manager
     let config = HalConfig::default();
-    let (mut manager, mut readings) = HardwareManager::new(config);
+    let (mut manager, _readings) = HardwareManager::new(config);
     
     // Initialize hardware
     println!("Initializing hardware...");
@@ -70,12 +70,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n--- PIR Motion Sensor ---");
     if let Ok(mut pir) = PIRSensor::new("PIR_Main", 17) {
         println!("  Monitoring for motion (5 seconds)...");
-        let start = std::time::Instant::now();
-        while start.elapsed() < Duration::from_secs(5) {
-            if pir.check_motion()? {
-                println!("  ! Motion detected!");
-            }
-            tokio::time::sleep(Duration::from_millis(100)).await;
+        let mut motion = pir.motion_events()?;
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while let Ok(Some(event)) = tokio::time::timeout_at(deadline, motion.recv()).await {
+            println!("  ! Motion detected! (#{})", event.count);
         }
         println!("  Total motion events: {}", pir.motion_count());
     } else {
@@ -125,7 +123,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Demo: Infrasound Detector
     println!("\n--- Infrasound Detector ---");
-    if let Ok(infra) = InfrasoundDetector::new("plughw:0,0", -40.0) {
+    if let Ok(_infra) = InfrasoundDetector::new("plughw:0,0", -40.0) {
         println!("  Monitoring for infrasound (0-20 Hz)...");
         // In production, this would read actual audio samples
         println!("  [Monitoring active]");
@@ -0,0 +1,142 @@
+//! ADC channel sensors
+//!
+//! Analog sensors (geophones, EMF probes, photodiodes) wired to an ADS1256
+//! channel. Each is described by an [`AdcChannelConfig`] entry in the
+//! device manifest; channels on the same SPI device share one ADC.
+//!
+//! Besides regular polling, channels support block capture: on demand the
+//! ADC fills a buffer at its maximum rate, so knock and footfall signatures
+//! can be analyzed as waveforms rather than as one aggregated value.
+
+use crate::{HalError, HardwareDevice, Sensor, DeviceType};
+use crate::spi::ADS1256;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Manifest entry for an analog sensor on an ADC channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdcChannelConfig {
+    /// Sensor name
+    pub name: String,
+    /// SPI device of the ADS1256
+    #[serde(default = "default_device")]
+    pub device: String,
+    /// Input channel (0-7, single-ended against AINCOM)
+    pub channel: u8,
+    /// Unit of the scaled value
+    #[serde(default = "default_unit")]
+    pub unit: String,
+    /// Multiplier applied to the voltage (e.g. 1 / geophone sensitivity)
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// Offset added after scaling
+    #[serde(default)]
+    pub offset: f64,
+    /// Zone the sensor covers
+    #[serde(default)]
+    pub zone: Option<String>,
+}
+
+fn default_device() -> String { "/dev/spidev0.0".to_string() }
+fn default_unit() -> String { "V".to_string() }
+fn default_scale() -> f64 { 1.0 }
+
+/// Analog sensor driven by an [`AdcChannelConfig`]
+pub struct AdcChannel {
+    config: AdcChannelConfig,
+    adc: Arc<Mutex<ADS1256>>,
+    calibration_offset: f64,
+    ready: bool,
+}
+
+impl AdcChannel {
+    /// Channel of an opened and initialized ADC
+    pub fn new(config: AdcChannelConfig, adc: Arc<Mutex<ADS1256>>) -> Result<Self, HalError> {
+        if config.channel > 7 {
+            return Err(HalError::InvalidConfig(format!("{}: channel must be 0-7", config.name)));
+        }
+        
+        Ok(Self {
+            config,
+            adc,
+            calibration_offset: 0.0,
+            ready: false,
+        })
+    }
+    
+    /// Sensor configuration
+    pub fn config(&self) -> &AdcChannelConfig {
+        &self.config
+    }
+    
+    fn scale(&self, volts: f64) -> f64 {
+        volts * self.config.scale + self.config.offset + self.calibration_offset
+    }
+    
+    /// The ADC, unless another channel is capturing a block on it
+    fn try_adc(&self) -> Result<MutexGuard<'_, ADS1256>, HalError> {
+        self.adc.try_lock().map_err(|_| HalError::DeviceBusy(self.config.device.clone()))
+    }
+}
+
+impl HardwareDevice for AdcChannel {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::SPI
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.ready = self.adc.lock().unwrap().is_ready();
+        if self.ready {
+            Ok(())
+        } else {
+            Err(HalError::DeviceNotFound(self.config.device.clone()))
+        }
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.ready = false;
+        Ok(())
+    }
+}
+
+impl Sensor for AdcChannel {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        let raw = self.try_adc()?.read_channel(self.config.channel)?;
+        Ok(raw.to_be_bytes().to_vec())
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        let raw = self.try_adc()?.read_channel(self.config.channel)?;
+        Ok(self.scale(ADS1256::raw_to_voltage(raw)))
+    }
+    
+    fn unit(&self) -> &str {
+        &self.config.unit
+    }
+    
+    fn calibrate(&mut self, offset: f64) -> Result<(), HalError> {
+        self.calibration_offset = offset;
+        Ok(())
+    }
+    
+    fn bus(&self) -> Option<&str> {
+        Some(&self.config.device)
+    }
+    
+    fn block_rate(&self) -> Option<f64> {
+        Some(ADS1256::MAX_SPS)
+    }
+    
+    fn read_block(&self, count: usize) -> Result<Vec<f64>, HalError> {
+        let volts = self.adc.lock().unwrap().read_burst(self.config.channel, count)?;
+        Ok(volts.into_iter().map(|v| self.scale(v)).collect())
+    }
+}
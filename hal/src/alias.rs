@@ -0,0 +1,80 @@
+//! Logical Sensor Names
+//!
+//! Auto-detected I2C sensors are named after their chip, qualified by bus
+//! and address when the plain name is taken. Those names move when the
+//! kernel numbers the adapters differently, e.g. `/dev/i2c-1` coming back as
+//! `/dev/i2c-3` after a reboot or a USB-I2C bridge being replugged, and
+//! baselines, zones and triggers referring to them silently stop matching.
+//!
+//! A [`SensorAlias`] binds a logical name to what a device is rather than
+//! where it was enumerated: its chip model, its address, and the adapter's
+//! name as the kernel reports it (`/sys/bus/i2c/devices/i2c-N/name`). Chips
+//! matching an alias are registered under its name on whichever bus they
+//! turn up, whether or not auto-detection is enabled.
+
+use serde::{Deserialize, Serialize};
+
+/// Logical name of an I2C sensor (`[[sensor_alias]]` in the app config)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorAlias {
+    /// Name the sensor is registered, baselined and zoned under
+    pub name: String,
+    /// Chip model from the identification table, e.g. "BME280"
+    #[serde(default)]
+    pub model: Option<String>,
+    /// 7-bit address
+    #[serde(default)]
+    pub address: Option<u8>,
+    /// Adapter name (e.g. "bcm2835 (i2c@7e804000)") or bus device path;
+    /// any bus if unset
+    #[serde(default)]
+    pub bus: Option<String>,
+}
+
+impl SensorAlias {
+    /// Whether a chip found at `address` on `bus_path` (adapter `adapter`) is this sensor
+    pub fn matches(&self, model: Option<&str>, address: u8, bus_path: &str, adapter: Option<&str>) -> bool {
+        // An alias must pin down at least the chip or its address
+        if self.model.is_none() && self.address.is_none() {
+            return false;
+        }
+        let model_ok = self.model.as_deref().is_none_or(|wanted| model.is_some_and(|m| m.eq_ignore_ascii_case(wanted)));
+        let address_ok = self.address.is_none_or(|wanted| wanted == address);
+        let bus_ok = self.bus.as_deref().is_none_or(|wanted| wanted == bus_path || Some(wanted) == adapter);
+        model_ok && address_ok && bus_ok
+    }
+}
+
+/// Configured aliases, resolved in order
+#[derive(Debug, Clone, Default)]
+pub struct SensorAliases {
+    aliases: Vec<SensorAlias>,
+}
+
+impl SensorAliases {
+    pub fn new(aliases: Vec<SensorAlias>) -> Self {
+        for alias in aliases.iter().filter(|a| a.model.is_none() && a.address.is_none()) {
+            tracing::warn!("Sensor alias {} has neither a model nor an address and never matches", alias.name);
+        }
+        Self { aliases }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+    
+    pub fn iter(&self) -> impl Iterator<Item = &SensorAlias> {
+        self.aliases.iter()
+    }
+    
+    /// Logical name of a chip, skipping aliases whose name is `taken`
+    ///
+    /// Identical chips without a `bus` to tell them apart take the matching
+    /// aliases in scan order.
+    pub fn resolve(&self, model: Option<&str>, address: u8, bus_path: &str, adapter: Option<&str>, taken: impl Fn(&str) -> bool) -> Option<&str> {
+        self.aliases.iter()
+            .filter(|alias| alias.matches(model, address, bus_path, adapter))
+            .map(|alias| alias.name.as_str())
+            .find(|name| !taken(name))
+    }
+}
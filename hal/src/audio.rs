@@ -1,8 +1,22 @@
 //! Audio interface for GlowBarn HAL
 //! Supports ALSA for audio capture and playback
+//!
+//! Capture reads an ALSA PCM through libasound when built with the `alsa`
+//! feature; otherwise, and off Linux, a [`MockAudioCapture`] stands in,
+//! delivering silence (or test tones) at the device's pace.
 
-use crate::{HalError, HardwareDevice, DeviceType};
+use crate::{HalError, HardwareDevice, DeviceType, SensorReading};
+use crate::mixer::AudioRouteConfig;
+use crate::platform::SIMULATED;
+use crate::clock::{StreamClock, StreamKind, SyncClock};
+use glowbarn_dsp::{self as dsp, filter::BUTTERWORTH_Q, Biquad, Window};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
 
 /// Audio format configuration
 #[derive(Debug, Clone)]
@@ -22,79 +36,373 @@ impl Default for AudioFormat {
     }
 }
 
+impl AudioFormat {
+    /// 44-byte PCM WAV header for `data_len` bytes of samples
+    ///
+    /// Without a length the sizes are set to their maximum, which players
+    /// take as a stream of unknown length.
+    pub fn wav_header(&self, data_len: Option<u32>) -> Vec<u8> {
+        let data_len = data_len.unwrap_or(u32::MAX - 36);
+        let block_align = self.channels * self.bits_per_sample.div_ceil(8);
+        
+        let mut header = Vec::with_capacity(44);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&data_len.saturating_add(36).to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&1u16.to_le_bytes());  // PCM
+        header.extend_from_slice(&self.channels.to_le_bytes());
+        header.extend_from_slice(&self.sample_rate.to_le_bytes());
+        header.extend_from_slice(&(self.sample_rate * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&self.bits_per_sample.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&data_len.to_le_bytes());
+        header
+    }
+    
+    /// Format and sample bytes of a PCM WAV file
+    ///
+    /// A data size past the end of the file (as streaming writers leave it)
+    /// is cut to the bytes actually present.
+    pub fn parse_wav(wav: &[u8]) -> Result<(AudioFormat, &[u8]), HalError> {
+        let invalid = |what: &str| HalError::InvalidConfig(format!("Not a PCM WAV file: {}", what));
+        if wav.len() < 12 || &wav[..4] != b"RIFF" || &wav[8..12] != b"WAVE" {
+            return Err(invalid("missing RIFF/WAVE header"));
+        }
+        
+        let mut format = None;
+        let mut pos = 12;
+        while pos + 8 <= wav.len() {
+            let id = &wav[pos..pos + 4];
+            let size = u32::from_le_bytes([wav[pos + 4], wav[pos + 5], wav[pos + 6], wav[pos + 7]]) as usize;
+            let body = &wav[pos + 8..(pos + 8).saturating_add(size).min(wav.len())];
+            match id {
+                b"fmt " if body.len() >= 16 => {
+                    let tag = u16::from_le_bytes([body[0], body[1]]);
+                    // PCM, or WAVE_FORMAT_EXTENSIBLE wrapping it
+                    if tag != 1 && tag != 0xFFFE {
+                        return Err(invalid(&format!("format tag {:#x}", tag)));
+                    }
+                    format = Some(AudioFormat {
+                        channels: u16::from_le_bytes([body[2], body[3]]),
+                        sample_rate: u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+                        bits_per_sample: u16::from_le_bytes([body[14], body[15]]),
+                    });
+                }
+                b"data" => {
+                    let format = format.ok_or_else(|| invalid("data before fmt chunk"))?;
+                    return Ok((format, body));
+                }
+                _ => {}
+            }
+            // Chunks are padded to an even length
+            pos = (pos + 8).saturating_add(size).saturating_add(size & 1);
+        }
+        Err(invalid("no data chunk"))
+    }
+    
+    /// Play time of `bytes` of samples
+    pub fn duration(&self, bytes: usize) -> Duration {
+        let block_align = self.channels.max(1) as u64 * self.bits_per_sample.div_ceil(8).max(1) as u64;
+        let rate = self.sample_rate.max(1) as f64 * block_align as f64;
+        Duration::from_secs_f64(bytes as f64 / rate)
+    }
+}
+
+/// What a configured audio input is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioRole {
+    /// Voice-band microphone for EVP capture
+    #[default]
+    EvpMic,
+    /// Room ambience / reference microphone
+    Ambient,
+    /// Low-frequency microphone or geophone for infrasound
+    Infrasound,
+}
+
+/// Device manifest entry for an audio input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioDeviceConfig {
+    /// Device name
+    pub name: String,
+    /// ALSA device (e.g. `hw:1,0`)
+    pub device: String,
+    #[serde(default)]
+    pub role: AudioRole,
+    /// Overrides for the role's default format
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    #[serde(default)]
+    pub channels: Option<u16>,
+    #[serde(default)]
+    pub bits_per_sample: Option<u16>,
+    /// Zone the microphone covers
+    #[serde(default)]
+    pub zone: Option<String>,
+    /// Converter and driver delay not visible to the host (ms), e.g.
+    /// measured against the cameras with a clapper board
+    #[serde(default)]
+    pub latency_ms: f64,
+}
+
+impl AudioDeviceConfig {
+    /// Capture format: the role default with any overrides applied
+    pub fn format(&self) -> AudioFormat {
+        let base = match self.role {
+            AudioRole::EvpMic | AudioRole::Ambient => AudioFormat::default(),
+            AudioRole::Infrasound => InfrasoundDetector::default_format(),
+        };
+        
+        AudioFormat {
+            sample_rate: self.sample_rate.unwrap_or(base.sample_rate),
+            channels: self.channels.unwrap_or(base.channels),
+            bits_per_sample: self.bits_per_sample.unwrap_or(base.bits_per_sample),
+        }
+    }
+    
+    /// Open the capture device
+    pub fn open(&self) -> Result<AudioCapture, HalError> {
+        AudioCapture::new(&self.device, self.format())
+    }
+    
+    /// Open the capture device, stamping chunks on `clock`
+    pub fn open_with(&self, clock: &SyncClock) -> Result<AudioCapture, HalError> {
+        let latency = Duration::from_secs_f64(self.latency_ms.max(0.0) / 1000.0);
+        Ok(self.open()?.with_clock(clock.stream(&self.name, StreamKind::Audio, latency)))
+    }
+}
+
+/// Interleaved PCM behind an [`AudioCapture`]
+pub trait PcmSource: Send {
+    /// Fill `samples`, blocking until they are captured; returns the number read
+    fn read(&mut self, samples: &mut [i16]) -> Result<usize, HalError>;
+}
+
+/// Simulated input: silence plus optional test tones, as slowly as a device
+/// delivers it
+pub struct MockAudioCapture {
+    format: AudioFormat,
+    tones: Vec<(f64, f64)>,
+    /// Frames generated so far, for continuous tone phase
+    frames: u64,
+}
+
+impl MockAudioCapture {
+    pub fn new(format: AudioFormat) -> Self {
+        Self { format, tones: Vec::new(), frames: 0 }
+    }
+    
+    /// Add a sine at `frequency` Hz with `amplitude` of full scale
+    pub fn with_tone(mut self, frequency: f64, amplitude: f64) -> Self {
+        self.tones.push((frequency, amplitude));
+        self
+    }
+}
+
+impl PcmSource for MockAudioCapture {
+    fn read(&mut self, samples: &mut [i16]) -> Result<usize, HalError> {
+        let channels = self.format.channels.max(1) as usize;
+        let rate = self.format.sample_rate.max(1) as f64;
+        for frame in samples.chunks_mut(channels) {
+            let t = self.frames as f64 / rate;
+            let value: f64 = self.tones.iter()
+                .map(|&(frequency, amplitude)| amplitude * (2.0 * std::f64::consts::PI * frequency * t).sin())
+                .sum();
+            frame.fill((value.clamp(-1.0, 1.0) * i16::MAX as f64) as i16);
+            self.frames += 1;
+        }
+        std::thread::sleep(Duration::from_secs_f64(samples.len() as f64 / (rate * channels as f64)));
+        Ok(samples.len())
+    }
+}
+
+/// Chunks of captured samples, from [`AudioCapture::samples`]
+pub struct AudioStream {
+    rx: mpsc::Receiver<Vec<i16>>,
+}
+
+impl AudioStream {
+    /// Next chunk; `None` once capture has stopped
+    pub async fn next(&mut self) -> Option<Vec<i16>> {
+        self.rx.recv().await
+    }
+    
+    /// Next chunk, from outside the runtime
+    pub fn blocking_next(&mut self) -> Option<Vec<i16>> {
+        self.rx.blocking_recv()
+    }
+}
+
 /// Audio capture device
+///
+/// Clones share the recording state and the open PCM, so a reader spawned
+/// with [`AudioCapture::spawn_reader`] stops when the original is stopped or
+/// closed.
+#[derive(Clone)]
 pub struct AudioCapture {
     name: String,
     device: String,
     format: AudioFormat,
-    buffer: Arc<Mutex<Vec<i16>>>,
-    recording: bool,
+    recording: Arc<AtomicBool>,
+    /// Set by `init` and cleared by `close`, so `is_ready` never waits on a
+    /// read blocked in the source
+    ready: Arc<AtomicBool>,
+    source: Arc<Mutex<Option<Box<dyn PcmSource>>>>,
+    clock: Option<StreamClock>,
 }
 
 impl AudioCapture {
-    /// Create new audio capture device
+    /// Audio capture device, opened by [`init`](HardwareDevice::init)
     pub fn new(device: &str, format: AudioFormat) -> Result<Self, HalError> {
         Ok(Self {
             name: format!("Audio Capture {}", device),
             device: device.to_string(),
             format,
-            buffer: Arc::new(Mutex::new(Vec::new())),
-            recording: false,
+            recording: Arc::new(AtomicBool::new(false)),
+            ready: Arc::new(AtomicBool::new(false)),
+            source: Arc::new(Mutex::new(None)),
+            clock: None,
         })
     }
     
+    /// Capture reading from `source` instead of an ALSA device (e.g. a [`MockAudioCapture`])
+    pub fn with_source(device: &str, format: AudioFormat, source: impl PcmSource + 'static) -> Self {
+        Self {
+            name: format!("Audio Capture {}", device),
+            device: device.to_string(),
+            format,
+            recording: Arc::new(AtomicBool::new(false)),
+            ready: Arc::new(AtomicBool::new(false)),
+            source: Arc::new(Mutex::new(Some(Box::new(source)))),
+            clock: None,
+        }
+    }
+    
+    /// Stamp chunks on a common stream clock
+    pub fn with_clock(mut self, clock: StreamClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+    
     /// Start recording
     pub fn start(&mut self) -> Result<(), HalError> {
-        self.recording = true;
+        self.recording.store(true, Ordering::Relaxed);
         tracing::info!("Audio capture started on {}", self.device);
         Ok(())
     }
     
     /// Stop recording
     pub fn stop(&mut self) -> Result<(), HalError> {
-        self.recording = false;
+        self.recording.store(false, Ordering::Relaxed);
         Ok(())
     }
     
-    /// Read samples (returns number of samples read)
+    /// Capture `chunk`-long readings on a background thread while recording,
+    /// sending them to `tx` as `sensor_name`
+    ///
+    /// The thread ends when capture stops or the receiver is dropped.
+    pub fn spawn_reader(&self, sensor_name: &str, chunk: Duration, tx: mpsc::Sender<SensorReading>) {
+        let capture = self.clone();
+        let sensor_name = sensor_name.to_string();
+        let count = self.chunk_len(chunk);
+        
+        std::thread::spawn(move || {
+            while capture.recording.load(Ordering::Relaxed) {
+                match capture.read_reading(&sensor_name, count) {
+                    Ok(reading) => {
+                        if tx.blocking_send(reading).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Audio capture on {} failed: {}", capture.device, e);
+                        std::thread::sleep(chunk);
+                    }
+                }
+            }
+            tracing::debug!("Audio reader for {} stopped", sensor_name);
+        });
+    }
+    
+    /// `chunk`-long blocks of interleaved samples while recording
+    ///
+    /// Samples are read on a background thread, which ends when capture
+    /// stops or the stream is dropped.
+    pub fn samples(&self, chunk: Duration) -> AudioStream {
+        let (tx, rx) = mpsc::channel(8);
+        let capture = self.clone();
+        let count = self.chunk_len(chunk);
+        
+        std::thread::spawn(move || {
+            let mut samples = vec![0i16; count];
+            while capture.recording.load(Ordering::Relaxed) {
+                match capture.read_samples(&mut samples) {
+                    Ok(n) => {
+                        if tx.blocking_send(samples[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Audio capture on {} failed: {}", capture.device, e);
+                        std::thread::sleep(chunk);
+                    }
+                }
+            }
+        });
+        AudioStream { rx }
+    }
+    
+    /// Interleaved samples in `chunk` of capture
+    fn chunk_len(&self, chunk: Duration) -> usize {
+        let rate = self.format.sample_rate.max(1) as f64 * self.format.channels.max(1) as f64;
+        ((rate * chunk.as_secs_f64()) as usize).max(1)
+    }
+    
+    /// ALSA device name
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    
+    /// Capture format
+    pub fn format(&self) -> &AudioFormat {
+        &self.format
+    }
+    
+    /// Read interleaved samples, blocking until captured (returns number of samples read)
     pub fn read_samples(&self, samples: &mut [i16]) -> Result<usize, HalError> {
-        // In production, this would read from ALSA
-        // For now, simulate reading silence
-        for sample in samples.iter_mut() {
-            *sample = 0;
+        match self.source.lock().unwrap().as_mut() {
+            Some(source) => source.read(samples),
+            None => Err(HalError::DeviceNotFound(format!("Audio capture {} not initialized", self.device))),
         }
-        Ok(samples.len())
+    }
+    
+    /// Capture `count` samples as a typed reading for the fusion pipeline
+    pub fn read_reading(&self, sensor_name: &str, count: usize) -> Result<SensorReading, HalError> {
+        let mut samples = vec![0i16; count];
+        let n = self.read_samples(&mut samples)?;
+        samples.truncate(n);
+        
+        // The first sample was captured a whole chunk before the read returned
+        let rate = self.format.sample_rate.max(1) as f64 * self.format.channels.max(1) as f64;
+        let buffered = Duration::from_secs_f64(n as f64 / rate);
+        let mut reading = SensorReading::audio(sensor_name, self.format.sample_rate, samples);
+        if let Some(clock) = &self.clock {
+            reading.timestamp = clock.captured(buffered);
+        }
+        Ok(reading)
     }
     
     /// Get RMS level (for visualization)
     pub fn get_rms_level(&self, samples: &[i16]) -> f64 {
-        if samples.is_empty() {
-            return 0.0;
-        }
-        
-        let sum: f64 = samples.iter()
-            .map(|&s| (s as f64).powi(2))
-            .sum();
-        
-        (sum / samples.len() as f64).sqrt()
+        dsp::stats::rms(&dsp::pcm_to_f64(samples))
     }
     
-    /// Calculate frequency spectrum (simple FFT placeholder)
+    /// Calculate magnitude spectrum (Hann-windowed FFT, `n / 2` bins up to Nyquist)
     pub fn calculate_spectrum(&self, samples: &[i16]) -> Vec<f64> {
-        // Placeholder - in production use rustfft
-        let mut spectrum = vec![0.0; samples.len() / 2];
-        
-        // Simple magnitude calculation (not real FFT)
-        for (i, chunk) in samples.chunks(2).enumerate() {
-            if chunk.len() == 2 {
-                let mag = ((chunk[0] as f64).powi(2) + (chunk[1] as f64).powi(2)).sqrt();
-                if i < spectrum.len() {
-                    spectrum[i] = mag;
-                }
-            }
-        }
-        
-        spectrum
+        dsp::fft::magnitude_spectrum(&dsp::pcm_to_f64(samples), Window::Hann)
     }
     
     /// Detect EVP-like anomalies (frequency patterns not matching ambient)
@@ -131,17 +439,283 @@ impl HardwareDevice for AudioCapture {
     }
     
     fn init(&mut self) -> Result<(), HalError> {
-        // Initialize ALSA device
         tracing::info!("Initializing audio device: {}", self.device);
+        let mut source = self.source.lock().unwrap();
+        if source.is_none() {
+            *source = Some(open_pcm(&self.device, &self.format)?);
+        }
+        self.ready.store(true, Ordering::Relaxed);
         Ok(())
     }
     
     fn is_ready(&self) -> bool {
-        true
+        self.ready.load(Ordering::Relaxed)
     }
     
     fn close(&mut self) -> Result<(), HalError> {
-        self.stop()
+        self.stop()?;
+        self.ready.store(false, Ordering::Relaxed);
+        self.source.lock().unwrap().take();
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "alsa", target_os = "linux"))]
+fn open_pcm(device: &str, format: &AudioFormat) -> Result<Box<dyn PcmSource>, HalError> {
+    Ok(Box::new(alsa::Pcm::open_capture(device, format)?))
+}
+
+#[cfg(not(all(feature = "alsa", target_os = "linux")))]
+fn open_pcm(device: &str, format: &AudioFormat) -> Result<Box<dyn PcmSource>, HalError> {
+    if !SIMULATED {
+        tracing::warn!("Built without the alsa feature, simulating audio input {}", device);
+    }
+    Ok(Box::new(MockAudioCapture::new(format.clone())))
+}
+
+/// PCM capture through libasound
+#[cfg(all(feature = "alsa", target_os = "linux"))]
+mod alsa {
+    use super::{AudioFormat, PcmSource};
+    use crate::HalError;
+    use std::ffi::{c_char, c_int, c_long, c_uint, c_ulong, c_void, CStr, CString};
+    
+    const SND_PCM_STREAM_CAPTURE: c_int = 1;
+    const SND_PCM_ACCESS_RW_INTERLEAVED: c_int = 3;
+    const SND_PCM_FORMAT_U8: c_int = 1;
+    const SND_PCM_FORMAT_S16_LE: c_int = 2;
+    const SND_PCM_FORMAT_S32_LE: c_int = 10;
+    const SND_PCM_FORMAT_S24_3LE: c_int = 32;
+    /// Buffer latency asked of the driver (µs)
+    const LATENCY_US: c_uint = 100_000;
+    
+    #[link(name = "asound")]
+    extern "C" {
+        fn snd_pcm_open(pcm: *mut *mut c_void, name: *const c_char, stream: c_int, mode: c_int) -> c_int;
+        fn snd_pcm_set_params(pcm: *mut c_void, format: c_int, access: c_int, channels: c_uint, rate: c_uint, soft_resample: c_int, latency: c_uint) -> c_int;
+        fn snd_pcm_readi(pcm: *mut c_void, buffer: *mut c_void, frames: c_ulong) -> c_long;
+        fn snd_pcm_recover(pcm: *mut c_void, err: c_int, silent: c_int) -> c_int;
+        fn snd_pcm_close(pcm: *mut c_void) -> c_int;
+        fn snd_strerror(err: c_int) -> *const c_char;
+    }
+    
+    fn error(what: &str, device: &str, err: c_int) -> HalError {
+        let reason = unsafe { CStr::from_ptr(snd_strerror(err)) }.to_string_lossy().into_owned();
+        HalError::CommunicationError(format!("{} {}: {}", what, device, reason))
+    }
+    
+    /// ALSA sample formats for a bit depth, in order of preference, with
+    /// their size in bytes
+    fn formats(bits: u16) -> &'static [(c_int, usize)] {
+        match bits {
+            8 => &[(SND_PCM_FORMAT_U8, 1)],
+            17..=24 => &[(SND_PCM_FORMAT_S24_3LE, 3), (SND_PCM_FORMAT_S32_LE, 4)],
+            25.. => &[(SND_PCM_FORMAT_S32_LE, 4)],
+            _ => &[(SND_PCM_FORMAT_S16_LE, 2)],
+        }
+    }
+    
+    /// Open capture PCM
+    pub struct Pcm {
+        pcm: *mut c_void,
+        device: String,
+        channels: usize,
+        sample_bytes: usize,
+        buffer: Vec<u8>,
+    }
+    
+    // A PCM handle may be used from one thread at a time; AudioCapture keeps
+    // its source behind a mutex
+    unsafe impl Send for Pcm {}
+    
+    impl Pcm {
+        pub fn open_capture(device: &str, format: &AudioFormat) -> Result<Self, HalError> {
+            let name = CString::new(device)
+                .map_err(|_| HalError::InvalidConfig(format!("Invalid ALSA device {:?}", device)))?;
+            let mut pcm = std::ptr::null_mut();
+            let err = unsafe { snd_pcm_open(&mut pcm, name.as_ptr(), SND_PCM_STREAM_CAPTURE, 0) };
+            if err < 0 {
+                return Err(error("Failed to open", device, err));
+            }
+            let mut opened = Self {
+                pcm,
+                device: device.to_string(),
+                channels: format.channels.max(1) as usize,
+                sample_bytes: 2,
+                buffer: Vec::new(),
+            };
+            
+            let mut err = 0;
+            for &(pcm_format, bytes) in formats(format.bits_per_sample) {
+                err = unsafe {
+                    snd_pcm_set_params(opened.pcm, pcm_format, SND_PCM_ACCESS_RW_INTERLEAVED,
+                        opened.channels as c_uint, format.sample_rate, 1, LATENCY_US)
+                };
+                if err >= 0 {
+                    opened.sample_bytes = bytes;
+                    tracing::debug!("ALSA capture {}: {} Hz, {} ch, {}-byte samples", device, format.sample_rate, opened.channels, bytes);
+                    return Ok(opened);
+                }
+            }
+            Err(error(&format!("{}-bit {} Hz {}-channel capture unsupported by", format.bits_per_sample, format.sample_rate, opened.channels), device, err))
+        }
+    }
+    
+    impl PcmSource for Pcm {
+        fn read(&mut self, samples: &mut [i16]) -> Result<usize, HalError> {
+            let frames = samples.len() / self.channels;
+            self.buffer.resize(frames * self.channels * self.sample_bytes, 0);
+            
+            let mut read = 0;
+            while read < frames {
+                let offset = read * self.channels * self.sample_bytes;
+                let n = unsafe {
+                    snd_pcm_readi(self.pcm, self.buffer[offset..].as_mut_ptr().cast(), (frames - read) as c_ulong)
+                };
+                if n < 0 {
+                    // Overruns (-EPIPE) and suspends are recovered from; the gap is lost
+                    let err = unsafe { snd_pcm_recover(self.pcm, n as c_int, 1) };
+                    if err < 0 {
+                        return Err(error("Capture failed on", &self.device, err));
+                    }
+                    tracing::debug!("ALSA capture {} recovered from overrun", self.device);
+                    continue;
+                }
+                read += n as usize;
+            }
+            
+            let count = frames * self.channels;
+            // Keep the 16 most significant bits
+            for (sample, bytes) in samples[..count].iter_mut().zip(self.buffer.chunks_exact(self.sample_bytes)) {
+                *sample = match bytes.len() {
+                    1 => ((bytes[0] as i16) - 128) << 8,
+                    n => i16::from_le_bytes([bytes[n - 2], bytes[n - 1]]),
+                };
+            }
+            Ok(count)
+        }
+    }
+    
+    impl Drop for Pcm {
+        fn drop(&mut self) {
+            unsafe {
+                snd_pcm_close(self.pcm);
+            }
+        }
+    }
+}
+
+/// Clips being filled at once, beyond which new captures are refused
+const MAX_PENDING_CLIPS: usize = 16;
+
+/// Audio around an event, ready to be written
+pub struct AudioClip {
+    pub path: PathBuf,
+    /// Rate and channels of the input; samples are always 16-bit
+    pub format: AudioFormat,
+    pub samples: Vec<i16>,
+}
+
+impl AudioClip {
+    /// Length of the clip
+    pub fn duration(&self) -> Duration {
+        self.format.duration(self.samples.len() * 2)
+    }
+    
+    /// Write the clip as a 16-bit PCM WAV file
+    pub fn write(&self) -> Result<(), HalError> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut wav = self.format.wav_header(Some((self.samples.len() * 2) as u32));
+        wav.reserve(self.samples.len() * 2);
+        for sample in &self.samples {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+        std::fs::write(&self.path, wav)?;
+        Ok(())
+    }
+}
+
+/// Clip waiting for its post-roll
+struct PendingClip {
+    clip: AudioClip,
+    remaining: usize,
+}
+
+/// Keeps the last few seconds of an input so the audio around an event can
+/// be saved, EVP candidates included
+///
+/// Feed it every captured chunk with [`push`](Self::push). A
+/// [`capture`](Self::capture) starts a clip with the buffered pre-roll; it
+/// is returned by the `push` that completes its post-roll.
+pub struct AudioClipRecorder {
+    format: AudioFormat,
+    pre_roll: usize,
+    post_roll: usize,
+    buffer: VecDeque<i16>,
+    pending: Vec<PendingClip>,
+}
+
+impl AudioClipRecorder {
+    /// Recorder for an input of `format`, keeping `pre_roll` before and
+    /// `post_roll` after each capture
+    pub fn new(format: &AudioFormat, pre_roll: Duration, post_roll: Duration) -> Self {
+        // Whole frames, so clips never start or end mid-frame
+        let channels = format.channels.max(1) as usize;
+        let samples = |d: Duration| (format.sample_rate as f64 * d.as_secs_f64()) as usize * channels;
+        Self {
+            format: AudioFormat { bits_per_sample: 16, ..format.clone() },
+            pre_roll: samples(pre_roll),
+            post_roll: samples(post_roll),
+            buffer: VecDeque::with_capacity(samples(pre_roll)),
+            pending: Vec::new(),
+        }
+    }
+    
+    /// Add captured interleaved samples, returning the clips they complete
+    pub fn push(&mut self, samples: &[i16]) -> Vec<AudioClip> {
+        let mut done = Vec::new();
+        for pending in &mut self.pending {
+            let take = pending.remaining.min(samples.len());
+            pending.clip.samples.extend_from_slice(&samples[..take]);
+            pending.remaining -= take;
+        }
+        let mut i = 0;
+        while i < self.pending.len() {
+            if self.pending[i].remaining == 0 {
+                done.push(self.pending.swap_remove(i).clip);
+            } else {
+                i += 1;
+            }
+        }
+        
+        let keep = samples.len().min(self.pre_roll);
+        let overflow = (self.buffer.len() + keep).saturating_sub(self.pre_roll);
+        self.buffer.drain(..overflow);
+        self.buffer.extend(&samples[samples.len() - keep..]);
+        done
+    }
+    
+    /// Start a clip to be written to `path`, beginning with the pre-roll
+    ///
+    /// Returns false when too many clips are already waiting for their post-roll.
+    pub fn capture(&mut self, path: PathBuf) -> bool {
+        if self.pending.len() >= MAX_PENDING_CLIPS {
+            return false;
+        }
+        let mut samples = Vec::with_capacity(self.pre_roll + self.post_roll);
+        samples.extend(&self.buffer);
+        self.pending.push(PendingClip {
+            clip: AudioClip { path, format: self.format.clone(), samples },
+            remaining: self.post_roll,
+        });
+        true
+    }
+    
+    /// Clips still waiting for post-roll, cut short (e.g. at shutdown)
+    pub fn flush(&mut self) -> Vec<AudioClip> {
+        self.pending.drain(..).map(|pending| pending.clip).collect()
     }
 }
 
@@ -182,6 +756,11 @@ impl AudioPlayback {
         })
     }
     
+    /// Playback device of a named output route
+    pub fn on_route(route: &AudioRouteConfig, format: AudioFormat) -> Result<Self, HalError> {
+        Self::new(&route.device, format)
+    }
+    
     /// Play samples
     pub fn play_samples(&mut self, samples: &[i16]) -> Result<(), HalError> {
         if samples.is_empty() {
@@ -194,6 +773,55 @@ impl AudioPlayback {
         Ok(())
     }
     
+    /// Play a PCM WAV clip to the end, returning its length
+    ///
+    /// The clip is piped to `aplay`, which converts its format as the
+    /// device requires; the stub backend waits as long as playback would.
+    pub fn play_wav(&mut self, wav: &[u8]) -> Result<Duration, HalError> {
+        let (format, data) = AudioFormat::parse_wav(wav)?;
+        let duration = format.duration(data.len());
+        
+        self.playing = true;
+        let result = if SIMULATED {
+            std::thread::sleep(duration);
+            Ok(())
+        } else {
+            pipe_to("aplay", &["-q", "-D", &self.device, "-"], wav)
+        };
+        self.playing = false;
+        result.map(|_| duration)
+    }
+    
+    /// Speak `text` with `espeak-ng` (or `espeak`), returning the speech length
+    pub fn speak(&mut self, text: &str, voice: Option<&str>) -> Result<Duration, HalError> {
+        if SIMULATED {
+            // About 150 words a minute
+            let duration = Duration::from_secs_f64(text.split_whitespace().count() as f64 * 0.4);
+            std::thread::sleep(duration);
+            return Ok(duration);
+        }
+        
+        let mut args = vec!["--stdout"];
+        if let Some(voice) = voice {
+            args.extend(["-v", voice]);
+        }
+        args.extend(["--", text]);
+        
+        let output = std::process::Command::new("espeak-ng").args(&args).output()
+            .or_else(|_| std::process::Command::new("espeak").args(&args).output())
+            .map_err(|e| HalError::DeviceNotFound(format!("espeak-ng: {}", e)))?;
+        if !output.status.success() {
+            return Err(HalError::CommunicationError(format!("espeak-ng exited with {}: {}",
+                output.status, String::from_utf8_lossy(&output.stderr).trim())));
+        }
+        self.play_wav(&output.stdout)
+    }
+    
+    /// ALSA device name
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    
     /// Generate tone
     pub fn generate_tone(&self, frequency: f64, duration_ms: u32) -> Vec<i16> {
         let num_samples = (self.format.sample_rate as f64 * duration_ms as f64 / 1000.0) as usize;
@@ -215,6 +843,31 @@ impl AudioPlayback {
     }
 }
 
+/// Run `program` with `input` on its stdin and wait for it to exit
+fn pipe_to(program: &str, args: &[&str], input: &[u8]) -> Result<(), HalError> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| HalError::DeviceNotFound(format!("{}: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A write error means the program quit early; its exit status says why
+        let _ = stdin.write_all(input);
+    }
+    
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(HalError::CommunicationError(format!("{} exited with {}: {}",
+            program, output.status, String::from_utf8_lossy(&output.stderr).trim())))
+    }
+}
+
 impl HardwareDevice for AudioPlayback {
     fn name(&self) -> &str {
         &self.name
@@ -241,6 +894,7 @@ impl HardwareDevice for AudioPlayback {
 /// Spirit Box emulation (frequency sweeping radio scanner)
 pub struct SpiritBox {
     capture: AudioCapture,
+    output: Option<AudioPlayback>,
     sweep_rate: f64,  // MHz per second
     current_freq: f64,
     running: bool,
@@ -258,12 +912,32 @@ impl SpiritBox {
         
         Ok(Self {
             capture,
+            output: None,
             sweep_rate,
             current_freq: 88.0,  // FM range start
             running: false,
         })
     }
     
+    /// Play the sweep audio on `output` (e.g. the room speaker route)
+    pub fn with_output(mut self, output: AudioPlayback) -> Self {
+        self.output = Some(output);
+        self
+    }
+    
+    /// Playback device the sweep is heard on
+    pub fn output_device(&self) -> Option<&str> {
+        self.output.as_ref().map(|o| o.device())
+    }
+    
+    /// Pass captured sweep audio through to the output
+    pub fn monitor(&mut self, samples: &[i16]) -> Result<(), HalError> {
+        match &mut self.output {
+            Some(output) => output.play_samples(samples),
+            None => Ok(()),
+        }
+    }
+    
     /// Start sweep
     pub fn start(&mut self) -> Result<(), HalError> {
         self.running = true;
@@ -300,13 +974,7 @@ pub struct InfrasoundDetector {
 
 impl InfrasoundDetector {
     pub fn new(device: &str, threshold_db: f64) -> Result<Self, HalError> {
-        let format = AudioFormat {
-            sample_rate: 96000,  // High sample rate for low freq
-            channels: 1,
-            bits_per_sample: 24,
-        };
-        
-        let capture = AudioCapture::new(device, format)?;
+        let capture = AudioCapture::new(device, Self::default_format())?;
         
         Ok(Self {
             capture,
@@ -314,6 +982,15 @@ impl InfrasoundDetector {
         })
     }
     
+    /// Default capture format
+    pub fn default_format() -> AudioFormat {
+        AudioFormat {
+            sample_rate: 96000,  // High sample rate for low freq
+            channels: 1,
+            bits_per_sample: 24,
+        }
+    }
+    
     /// Check for infrasound presence
     pub fn detect(&self, samples: &[i16]) -> Option<InfrasoundEvent> {
         // Apply low-pass filter and detect presence
@@ -332,34 +1009,14 @@ impl InfrasoundDetector {
     }
     
     fn low_pass_filter(&self, samples: &[i16], cutoff: f64) -> Vec<i16> {
-        // Simple RC low-pass filter
-        let rc = 1.0 / (2.0 * std::f64::consts::PI * cutoff);
-        let dt = 1.0 / self.capture.format.sample_rate as f64;
-        let alpha = dt / (rc + dt);
-        
-        let mut filtered = Vec::with_capacity(samples.len());
-        let mut prev = 0.0;
-        
-        for &sample in samples {
-            let curr = alpha * sample as f64 + (1.0 - alpha) * prev;
-            filtered.push(curr as i16);
-            prev = curr;
-        }
-        
-        filtered
+        let mut filter = Biquad::lowpass(self.capture.format.sample_rate as f64, cutoff, BUTTERWORTH_Q);
+        let mut filtered = dsp::pcm_to_f64(samples);
+        filter.process_buffer(&mut filtered);
+        filtered.into_iter().map(|s| s as i16).collect()
     }
     
     fn estimate_frequency(&self, samples: &[i16]) -> f64 {
-        // Zero-crossing frequency estimation
-        let mut crossings = 0;
-        for window in samples.windows(2) {
-            if (window[0] >= 0) != (window[1] >= 0) {
-                crossings += 1;
-            }
-        }
-        
-        let duration = samples.len() as f64 / self.capture.format.sample_rate as f64;
-        crossings as f64 / (2.0 * duration)
+        dsp::stats::zero_crossing_rate(&dsp::pcm_to_f64(samples), self.capture.format.sample_rate as f64)
     }
 }
 
@@ -0,0 +1,187 @@
+//! Bus traffic logging (sniffer mode)
+//!
+//! Records every I2C, SPI and serial transaction (address, register, bytes,
+//! latency, result) as JSON lines. Logging is off by default and can be
+//! toggled at runtime; the output file is normally the per-session
+//! `bus.jsonl` opened by the event recorder.
+
+use crate::HalError;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Number of transactions kept in memory for inspection
+const RECENT_CAPACITY: usize = 256;
+
+/// Bus the transaction ran on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BusKind {
+    I2C,
+    SPI,
+    Serial,
+}
+
+/// Transaction direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BusOp {
+    Read,
+    Write,
+    /// Full-duplex transfer (SPI)
+    Transfer,
+}
+
+/// Single logged bus transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusTransaction {
+    pub timestamp: SystemTime,
+    pub kind: BusKind,
+    pub bus: String,
+    pub op: BusOp,
+    pub address: Option<u8>,
+    pub register: Option<u8>,
+    /// Bytes written (writes) or received (reads, transfers)
+    pub bytes: Vec<u8>,
+    /// Bytes clocked out during a full-duplex transfer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sent: Vec<u8>,
+    pub latency_us: u64,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Global bus transaction logger
+pub struct BusLogger {
+    enabled: AtomicBool,
+    writer: Mutex<Option<BufWriter<File>>>,
+    recent: Mutex<VecDeque<BusTransaction>>,
+}
+
+impl BusLogger {
+    fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            writer: Mutex::new(None),
+            recent: Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)),
+        }
+    }
+    
+    /// Enable or disable logging
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        tracing::info!("Bus logging {}", if enabled { "enabled" } else { "disabled" });
+    }
+    
+    /// Check if logging is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+    
+    /// Direct log output to a file (appending)
+    pub fn open(&self, path: &Path) -> Result<(), HalError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        *self.writer.lock().unwrap() = Some(BufWriter::new(file));
+        Ok(())
+    }
+    
+    /// Flush and close the log file
+    pub fn close(&self) {
+        if let Some(mut writer) = self.writer.lock().unwrap().take() {
+            let _ = writer.flush();
+        }
+    }
+    
+    /// Flush buffered transactions to disk
+    pub fn flush(&self) -> Result<(), HalError> {
+        if let Some(ref mut writer) = *self.writer.lock().unwrap() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+    
+    /// Most recent transactions, oldest first
+    pub fn recent(&self) -> Vec<BusTransaction> {
+        self.recent.lock().unwrap().iter().cloned().collect()
+    }
+    
+    /// Record a transaction
+    pub fn record(&self, transaction: BusTransaction) {
+        if let Some(ref mut writer) = *self.writer.lock().unwrap() {
+            if let Ok(json) = serde_json::to_string(&transaction) {
+                let _ = writeln!(writer, "{}", json);
+            }
+        }
+        
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() >= RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(transaction);
+    }
+}
+
+/// Global logger instance
+pub fn logger() -> &'static BusLogger {
+    static LOGGER: OnceLock<BusLogger> = OnceLock::new();
+    LOGGER.get_or_init(BusLogger::new)
+}
+
+/// Transaction details passed by bus drivers
+#[cfg(any(feature = "i2c", feature = "spi", feature = "usb"))]
+pub(crate) struct Trace<'a> {
+    pub kind: BusKind,
+    pub bus: &'a str,
+    pub op: BusOp,
+    pub address: Option<u8>,
+    pub register: Option<u8>,
+}
+
+#[cfg(any(feature = "i2c", feature = "spi", feature = "usb"))]
+impl Trace<'_> {
+    /// Log the outcome of a transaction started at `started`
+    #[cfg(any(feature = "i2c", feature = "usb"))]
+    pub fn finish<T>(self, started: std::time::Instant, bytes: &[u8], result: &Result<T, HalError>) {
+        self.finish_transfer(started, &[], bytes, result);
+    }
+    
+    /// Log a full-duplex transfer
+    pub fn finish_transfer<T>(self, started: std::time::Instant, sent: &[u8], bytes: &[u8], result: &Result<T, HalError>) {
+        tracing::trace!(
+            bus = self.bus,
+            kind = ?self.kind,
+            op = ?self.op,
+            address = self.address,
+            register = self.register,
+            len = bytes.len(),
+            latency_us = started.elapsed().as_micros() as u64,
+            ok = result.is_ok(),
+            "bus transaction"
+        );
+        
+        let logger = logger();
+        if !logger.is_enabled() {
+            return;
+        }
+        
+        logger.record(BusTransaction {
+            timestamp: SystemTime::now(),
+            kind: self.kind,
+            bus: self.bus.to_string(),
+            op: self.op,
+            address: self.address,
+            register: self.register,
+            bytes: bytes.to_vec(),
+            sent: sent.to_vec(),
+            latency_us: started.elapsed().as_micros() as u64,
+            ok: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+    }
+}
@@ -0,0 +1,149 @@
+//! Persistent sensor calibration
+//!
+//! Stores each device's calibration offset in its own JSON file, keyed by
+//! device name (and serial number when known), so offsets survive a
+//! restart. Records older than the configured maximum age are reported as
+//! [`HalError::CalibrationRequired`].
+//!
+//! Devices needing more than an offset (such as an SDR's per-gain power
+//! table) store their own serializable records through
+//! [`CalibrationStore::save_value`].
+
+use crate::HalError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Stored calibration for one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationRecord {
+    pub device: String,
+    #[serde(default)]
+    pub serial: Option<String>,
+    pub offset: f64,
+    pub calibrated_at: SystemTime,
+}
+
+impl CalibrationRecord {
+    pub fn new(device: &str, serial: Option<&str>, offset: f64) -> Self {
+        Self {
+            device: device.to_string(),
+            serial: serial.map(str::to_string),
+            offset,
+            calibrated_at: SystemTime::now(),
+        }
+    }
+    
+    /// Time since calibration
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.calibrated_at)
+            .unwrap_or_default()
+    }
+}
+
+/// Directory of per-device calibration files
+#[derive(Debug, Clone)]
+pub struct CalibrationStore {
+    dir: PathBuf,
+    max_age: Duration,
+}
+
+impl CalibrationStore {
+    /// Open (and create) a calibration directory
+    pub fn open(dir: &Path) -> Result<Self, HalError> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_age: Duration::from_secs(30 * 24 * 3600),
+        })
+    }
+    
+    /// Age after which a calibration is considered expired
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+    
+    /// Storage directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    
+    fn path_for(&self, device: &str, serial: Option<&str>) -> PathBuf {
+        let key = match serial {
+            Some(serial) => format!("{}_{}", device, serial),
+            None => device.to_string(),
+        };
+        let key: String = key.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.json", key))
+    }
+    
+    /// Save a calibration record
+    pub fn save(&self, record: &CalibrationRecord) -> Result<(), HalError> {
+        self.save_value(&record.device, record.serial.as_deref(), record)
+    }
+    
+    /// Load a calibration record, if one exists
+    pub fn load(&self, device: &str, serial: Option<&str>) -> Result<Option<CalibrationRecord>, HalError> {
+        self.load_value(device, serial)
+    }
+    
+    /// Save a device-specific calibration under `device` and `serial`
+    pub fn save_value<T: Serialize>(&self, device: &str, serial: Option<&str>, value: &T) -> Result<(), HalError> {
+        let path = self.path_for(device, serial);
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| HalError::InvalidConfig(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+    
+    /// Load a value saved with [`save_value`](Self::save_value), if one exists
+    pub fn load_value<T: DeserializeOwned>(&self, device: &str, serial: Option<&str>) -> Result<Option<T>, HalError> {
+        let path = self.path_for(device, serial);
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        let json = std::fs::read_to_string(&path)?;
+        let value = serde_json::from_str(&json)
+            .map_err(|e| HalError::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+        Ok(Some(value))
+    }
+    
+    /// Remove a stored calibration
+    pub fn remove(&self, device: &str, serial: Option<&str>) -> Result<(), HalError> {
+        let path = self.path_for(device, serial);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+    
+    /// All stored calibrations
+    pub fn list(&self) -> Result<Vec<CalibrationRecord>, HalError> {
+        let mut records = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)?.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Ok(json) = std::fs::read_to_string(&path) {
+                    if let Ok(record) = serde_json::from_str(&json) {
+                        records.push(record);
+                    }
+                }
+            }
+        }
+        Ok(records)
+    }
+    
+    /// Load a calibration, failing if it has expired
+    pub fn check(&self, device: &str, serial: Option<&str>) -> Result<Option<CalibrationRecord>, HalError> {
+        match self.load(device, serial)? {
+            Some(record) if record.age() > self.max_age => Err(HalError::CalibrationRequired),
+            record => Ok(record),
+        }
+    }
+}
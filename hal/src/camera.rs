@@ -1,10 +1,14 @@
 //! Camera interface for GlowBarn HAL
 //! Supports V4L2 for video capture and thermal imaging
 
-use crate::{HalError, HardwareDevice, DeviceType};
-use std::fs::{File, OpenOptions};
-use std::os::unix::io::AsRawFd;
+use crate::{HalError, HardwareDevice, DeviceType, SensorReading};
+use crate::illuminator::{Illuminator, IlluminatorMode};
+use crate::thermal::{DriftCorrection, ReferenceTemperatures, ThermalReference};
+use crate::clock::{StreamClock, StreamKind, SyncClock};
+use crate::platform::DeviceHandle;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Video format configuration
 #[derive(Debug, Clone)]
@@ -27,7 +31,8 @@ impl Default for VideoFormat {
 }
 
 /// Pixel format
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PixelFormat {
     YUYV,
     MJPEG,
@@ -38,6 +43,18 @@ pub enum PixelFormat {
 }
 
 impl PixelFormat {
+    #[cfg(target_os = "linux")]
+    fn from_fourcc(fourcc: u32) -> Option<Self> {
+        [
+            PixelFormat::YUYV,
+            PixelFormat::MJPEG,
+            PixelFormat::RGB24,
+            PixelFormat::BGR24,
+            PixelFormat::GREY,
+            PixelFormat::Y16,
+        ].into_iter().find(|format| format.fourcc() == fourcc)
+    }
+    
     fn fourcc(&self) -> u32 {
         match self {
             PixelFormat::YUYV => 0x56595559,   // 'YUYV'
@@ -50,105 +67,171 @@ impl PixelFormat {
     }
 }
 
+/// What a configured camera is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CameraRole {
+    #[default]
+    Standard,
+    Thermal,
+    Nightvision,
+}
+
+/// Device manifest entry for a camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraConfig {
+    /// Device name
+    pub name: String,
+    /// V4L2 device path
+    pub device: String,
+    /// Capture pipeline to use
+    #[serde(default)]
+    pub role: CameraRole,
+    /// Overrides for the role's default format
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fps: Option<u32>,
+    #[serde(default)]
+    pub pixel_format: Option<PixelFormat>,
+    /// Zone the camera covers
+    #[serde(default)]
+    pub zone: Option<String>,
+    /// Known-temperature target in a thermal camera's view, for drift correction
+    #[serde(default)]
+    pub reference: Option<ThermalReference>,
+    /// Delay between exposure and delivery not visible to the host (ms),
+    /// e.g. measured against the microphones with a clapper board
+    #[serde(default)]
+    pub latency_ms: f64,
+}
+
+impl CameraConfig {
+    /// Capture format: the role default with any overrides applied
+    pub fn format(&self) -> VideoFormat {
+        let base = match self.role {
+            CameraRole::Standard => VideoFormat::default(),
+            CameraRole::Thermal => ThermalCamera::default_format(),
+            CameraRole::Nightvision => NightVisionCamera::default_format(),
+        };
+        
+        VideoFormat {
+            width: self.width.unwrap_or(base.width),
+            height: self.height.unwrap_or(base.height),
+            pixel_format: self.pixel_format.unwrap_or(base.pixel_format),
+            fps: self.fps.unwrap_or(base.fps),
+        }
+    }
+    
+    /// Open the camera for its role
+    pub fn open(&self) -> Result<Box<dyn HardwareDevice>, HalError> {
+        self.open_with(None, &ReferenceTemperatures::new(), &SyncClock::new())
+    }
+    
+    /// Open the camera, letting a night vision camera drive `illuminator`
+    /// and a thermal camera read its reference sensor from `references`;
+    /// frames are stamped on `clock`
+    pub fn open_with(&self, illuminator: Option<Illuminator>, references: &ReferenceTemperatures, clock: &SyncClock) -> Result<Box<dyn HardwareDevice>, HalError> {
+        let format = self.format();
+        let latency = Duration::from_secs_f64(self.latency_ms.max(0.0) / 1000.0);
+        let clock = clock.stream(&self.name, StreamKind::Video, latency);
+        Ok(match self.role {
+            CameraRole::Standard => Box::new(Camera::open(&self.device, format)?.with_clock(clock)),
+            CameraRole::Thermal => {
+                let camera = ThermalCamera::open_with_format(&self.device, format)?.with_clock(clock);
+                match &self.reference {
+                    Some(reference) => Box::new(camera.with_reference(reference.clone(), references.clone())),
+                    None => Box::new(camera),
+                }
+            }
+            CameraRole::Nightvision => {
+                let camera = NightVisionCamera::open_with_format(&self.device, format)?.with_clock(clock);
+                match illuminator {
+                    Some(illuminator) => Box::new(camera.with_illuminator(illuminator)),
+                    None => Box::new(camera),
+                }
+            }
+        })
+    }
+}
+
 /// V4L2 camera device
 pub struct Camera {
     name: String,
     device: String,
     format: VideoFormat,
-    file: Option<File>,
+    // Declared before `handle` so streaming stops before the device closes
+    #[cfg(target_os = "linux")]
+    stream: Option<capture::Stream>,
+    handle: DeviceHandle,
     ready: bool,
-    buffers: Vec<Vec<u8>>,
+    clock: Option<StreamClock>,
 }
 
 impl Camera {
     /// Open camera device
     pub fn open(device: &str, format: VideoFormat) -> Result<Self, HalError> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(device)?;
-        
         Ok(Self {
             name: format!("Camera {}", device),
             device: device.to_string(),
             format,
-            file: Some(file),
+            #[cfg(target_os = "linux")]
+            stream: None,
+            handle: DeviceHandle::open(device)?,
             ready: false,
-            buffers: Vec::new(),
+            clock: None,
         })
     }
     
+    /// Stamp frames on a common stream clock
+    pub fn with_clock(mut self, clock: StreamClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+    
+    /// Device path
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    
+    /// Video format, as negotiated with the driver once initialised
+    pub fn format(&self) -> &VideoFormat {
+        &self.format
+    }
+    
     /// Configure video format
+    ///
+    /// Drivers adjust a format they cannot deliver to the nearest one they
+    /// can; frames are tagged with the format the driver settled on.
     fn configure_format(&mut self) -> Result<(), HalError> {
         #[cfg(target_os = "linux")]
-        if let Some(ref file) = self.file {
-            let fd = file.as_raw_fd();
-            
-            // VIDIOC_S_FMT = 0xC0D05605
-            #[repr(C)]
-            struct V4l2Format {
-                format_type: u32,
-                pix: V4l2PixFormat,
-                raw_data: [u8; 156],
-            }
-            
-            #[repr(C)]
-            #[derive(Default)]
-            struct V4l2PixFormat {
-                width: u32,
-                height: u32,
-                pixelformat: u32,
-                field: u32,
-                bytesperline: u32,
-                sizeimage: u32,
-                colorspace: u32,
-                priv_: u32,
-                flags: u32,
-                quantization: u32,
-                xfer_func: u32,
-            }
-            
-            let mut fmt = V4l2Format {
-                format_type: 1,  // V4L2_BUF_TYPE_VIDEO_CAPTURE
-                pix: V4l2PixFormat {
-                    width: self.format.width,
-                    height: self.format.height,
-                    pixelformat: self.format.pixel_format.fourcc(),
-                    ..Default::default()
-                },
-                raw_data: [0; 156],
-            };
-            
-            unsafe {
-                let ret = libc::ioctl(fd, 0xC0D05605, &mut fmt);
-                if ret < 0 {
-                    return Err(HalError::CommunicationError("Failed to set video format".to_string()));
-                }
+        if let Some(fd) = self.handle.raw_fd() {
+            let negotiated = capture::set_format(fd, &self.format)?;
+            if (negotiated.width, negotiated.height, negotiated.pixel_format)
+                != (self.format.width, self.format.height, self.format.pixel_format)
+            {
+                tracing::warn!(
+                    "{} delivers {}x{} {:?} instead of the configured {}x{} {:?}",
+                    self.name, negotiated.width, negotiated.height, negotiated.pixel_format,
+                    self.format.width, self.format.height, self.format.pixel_format
+                );
             }
+            self.format = negotiated;
         }
         Ok(())
     }
     
-    /// Request and map buffers
-    fn setup_buffers(&mut self, count: u32) -> Result<(), HalError> {
-        // Allocate internal buffers
-        let buffer_size = (self.format.width * self.format.height * 2) as usize;
-        self.buffers = (0..count).map(|_| vec![0u8; buffer_size]).collect();
-        Ok(())
-    }
-    
     /// Start streaming
+    ///
+    /// Maps driver buffers for streaming I/O, or reads frames with read()
+    /// from devices that only support that.
     pub fn start_streaming(&mut self) -> Result<(), HalError> {
-        self.setup_buffers(4)?;
-        
         #[cfg(target_os = "linux")]
-        if let Some(ref file) = self.file {
-            let fd = file.as_raw_fd();
-            let buf_type: u32 = 1;  // V4L2_BUF_TYPE_VIDEO_CAPTURE
-            
-            unsafe {
-                // VIDIOC_STREAMON = 0x40045612
-                libc::ioctl(fd, 0x40045612, &buf_type);
+        if let Some(fd) = self.handle.raw_fd() {
+            if self.stream.is_none() {
+                self.stream = Some(capture::Stream::start(fd)?);
             }
         }
         
@@ -159,14 +242,8 @@ impl Camera {
     /// Stop streaming
     pub fn stop_streaming(&mut self) -> Result<(), HalError> {
         #[cfg(target_os = "linux")]
-        if let Some(ref file) = self.file {
-            let fd = file.as_raw_fd();
-            let buf_type: u32 = 1;
-            
-            unsafe {
-                // VIDIOC_STREAMOFF = 0x40045613
-                libc::ioctl(fd, 0x40045613, &buf_type);
-            }
+        {
+            self.stream = None;
         }
         
         self.ready = false;
@@ -174,22 +251,37 @@ impl Camera {
     }
     
     /// Capture single frame
+    ///
+    /// Waits for the next frame from the device, up to four frame intervals
+    /// (at least a second). The stub backend returns blank frames.
     pub fn capture_frame(&mut self) -> Result<Frame, HalError> {
         if !self.ready {
             return Err(HalError::DeviceNotFound("Camera not streaming".to_string()));
         }
         
-        // In production, this would dequeue a buffer from V4L2
-        let data = self.buffers.first()
-            .cloned()
-            .unwrap_or_else(|| vec![0u8; (self.format.width * self.format.height * 2) as usize]);
+        #[cfg(target_os = "linux")]
+        let data = match &mut self.stream {
+            Some(stream) => {
+                let timeout = Duration::from_secs_f64((4.0 / self.format.fps.max(1) as f64).max(1.0));
+                stream.next_frame(timeout)?
+            }
+            None => vec![0u8; (self.format.width * self.format.height * 2) as usize],
+        };
+        #[cfg(not(target_os = "linux"))]
+        let data = vec![0u8; (self.format.width * self.format.height * 2) as usize];
+        
+        // Stamped at mid-exposure, half a frame interval before delivery
+        let timestamp = match &self.clock {
+            Some(clock) => clock.captured(Duration::from_secs_f64(0.5 / self.format.fps.max(1) as f64)),
+            None => std::time::SystemTime::now(),
+        };
         
         Ok(Frame {
             width: self.format.width,
             height: self.format.height,
             format: self.format.pixel_format,
             data,
-            timestamp: std::time::SystemTime::now(),
+            timestamp,
         })
     }
 }
@@ -214,7 +306,7 @@ impl HardwareDevice for Camera {
     
     fn close(&mut self) -> Result<(), HalError> {
         self.stop_streaming()?;
-        self.file = None;
+        self.handle.close();
         Ok(())
     }
 }
@@ -230,7 +322,17 @@ pub struct Frame {
 }
 
 impl Frame {
+    /// Package as a typed reading for the fusion pipeline
+    pub fn to_reading(&self, sensor_name: &str) -> SensorReading {
+        let mut reading = SensorReading::frame(sensor_name, self.width, self.height, self.to_grayscale());
+        reading.timestamp = self.timestamp;
+        reading
+    }
+    
     /// Convert to grayscale
+    ///
+    /// MJPEG frames are decoded with the `mjpeg` feature; frames that fail to
+    /// decode (truncated by a USB hiccup, say) come out black.
     pub fn to_grayscale(&self) -> Vec<u8> {
         match self.format {
             PixelFormat::GREY | PixelFormat::Y16 => self.data.clone(),
@@ -241,6 +343,18 @@ impl Frame {
                     .cloned()
                     .collect()
             }
+            #[cfg(feature = "mjpeg")]
+            PixelFormat::MJPEG => match crate::jpeg::decode_luma(&self.data) {
+                Ok(luma) if luma.width == self.width && luma.height == self.height => luma.pixels,
+                Ok(luma) => {
+                    tracing::debug!("MJPEG frame is {}x{}, expected {}x{}", luma.width, luma.height, self.width, self.height);
+                    vec![0; (self.width * self.height) as usize]
+                }
+                Err(e) => {
+                    tracing::debug!("MJPEG frame not decoded: {}", e);
+                    vec![0; (self.width * self.height) as usize]
+                }
+            },
             _ => {
                 // Placeholder for other formats
                 vec![0; (self.width * self.height) as usize]
@@ -280,27 +394,55 @@ pub struct ThermalCamera {
     camera: Camera,
     min_temp: f64,
     max_temp: f64,
+    drift: Option<DriftCorrection>,
 }
 
 impl ThermalCamera {
-    /// Open thermal camera
+    /// Open thermal camera (160x120 Y16, Lepton-style)
     pub fn open(device: &str) -> Result<Self, HalError> {
-        let format = VideoFormat {
+        Self::open_with_format(device, Self::default_format())
+    }
+    
+    /// Default capture format
+    pub fn default_format() -> VideoFormat {
+        VideoFormat {
             width: 160,
             height: 120,
             pixel_format: PixelFormat::Y16,
             fps: 9,
-        };
-        
+        }
+    }
+    
+    /// Open a thermal camera with a different resolution or rate
+    pub fn open_with_format(device: &str, format: VideoFormat) -> Result<Self, HalError> {
         let camera = Camera::open(device, format)?;
         
         Ok(Self {
             camera,
             min_temp: -40.0,
             max_temp: 330.0,
+            drift: None,
         })
     }
     
+    /// Stamp frames on a common stream clock
+    pub fn with_clock(mut self, clock: StreamClock) -> Self {
+        self.camera = self.camera.with_clock(clock);
+        self
+    }
+    
+    /// Correct drift against a reference target in view, reading its
+    /// sensor (if any) from `readings`
+    pub fn with_reference(mut self, reference: ThermalReference, readings: ReferenceTemperatures) -> Self {
+        self.drift = Some(DriftCorrection::new(reference, readings));
+        self
+    }
+    
+    /// Current drift offset (°C the sensor reads high), once measured
+    pub fn drift_offset(&self) -> Option<f64> {
+        self.drift.as_ref().and_then(DriftCorrection::offset)
+    }
+    
     /// Set temperature range
     pub fn set_range(&mut self, min: f64, max: f64) {
         self.min_temp = min;
@@ -312,17 +454,22 @@ impl ThermalCamera {
         let frame = self.camera.capture_frame()?;
         
         // Convert Y16 to temperature values
-        let temps: Vec<f64> = frame.data.chunks(2)
+        let mut temps: Vec<f64> = frame.data.chunks(2)
             .map(|chunk| {
                 let raw = u16::from_le_bytes([chunk[0], chunk.get(1).copied().unwrap_or(0)]);
                 self.raw_to_temperature(raw)
             })
             .collect();
+        let drift_offset = match &mut self.drift {
+            Some(drift) => drift.correct(frame.width, &mut temps),
+            None => 0.0,
+        };
         
         Ok(ThermalFrame {
             width: frame.width,
             height: frame.height,
             temperatures: temps,
+            drift_offset,
             timestamp: frame.timestamp,
         })
     }
@@ -363,6 +510,8 @@ pub struct ThermalFrame {
     pub width: u32,
     pub height: u32,
     pub temperatures: Vec<f64>,
+    /// Drift correction already subtracted from `temperatures` (°C)
+    pub drift_offset: f64,
     pub timestamp: std::time::SystemTime,
 }
 
@@ -429,41 +578,73 @@ pub struct ColdSpot {
 pub struct NightVisionCamera {
     camera: Camera,
     ir_led_enabled: bool,
+    illuminator: Option<Illuminator>,
 }
 
 impl NightVisionCamera {
     pub fn open(device: &str) -> Result<Self, HalError> {
-        let format = VideoFormat {
+        Self::open_with_format(device, Self::default_format())
+    }
+    
+    /// Default capture format
+    pub fn default_format() -> VideoFormat {
+        VideoFormat {
             width: 1920,
             height: 1080,
             pixel_format: PixelFormat::YUYV,
             fps: 30,
-        };
-        
+        }
+    }
+    
+    /// Open a night vision camera with a different format
+    pub fn open_with_format(device: &str, format: VideoFormat) -> Result<Self, HalError> {
         let camera = Camera::open(device, format)?;
         
         Ok(Self {
             camera,
             ir_led_enabled: false,
+            illuminator: None,
         })
     }
     
-    /// Enable IR illumination
+    /// Stamp frames on a common stream clock
+    pub fn with_clock(mut self, clock: StreamClock) -> Self {
+        self.camera = self.camera.with_clock(clock);
+        self
+    }
+    
+    /// Adjust `illuminator` to the brightness of captured frames
+    pub fn with_illuminator(mut self, illuminator: Illuminator) -> Self {
+        self.ir_led_enabled = illuminator.status().mode != IlluminatorMode::Off;
+        self.illuminator = Some(illuminator);
+        self
+    }
+    
+    /// Enable IR illumination (auto brightness with an illuminator)
     pub fn enable_ir(&mut self) -> Result<(), HalError> {
-        // In production, this would control IR LED GPIO
+        if let Some(illuminator) = &self.illuminator {
+            illuminator.set_mode(IlluminatorMode::Auto);
+        }
         self.ir_led_enabled = true;
         Ok(())
     }
     
     /// Disable IR illumination
     pub fn disable_ir(&mut self) -> Result<(), HalError> {
+        if let Some(illuminator) = &self.illuminator {
+            illuminator.set_mode(IlluminatorMode::Off);
+        }
         self.ir_led_enabled = false;
         Ok(())
     }
     
-    /// Capture frame
+    /// Capture frame, feeding its brightness to the illuminator
     pub fn capture(&mut self) -> Result<Frame, HalError> {
-        self.camera.capture_frame()
+        let frame = self.camera.capture_frame()?;
+        if let Some(illuminator) = &self.illuminator {
+            illuminator.expose(frame.average_brightness());
+        }
+        Ok(frame)
     }
     
     /// Detect light anomalies (orbs, etc.)
@@ -534,3 +715,811 @@ pub fn enumerate_cameras() -> Result<Vec<PathBuf>, HalError> {
     
     Ok(cameras)
 }
+
+/// Hardware H.264 encoder settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncoderConfig {
+    /// V4L2 memory-to-memory encoder node (bcm2835-codec on a Raspberry Pi)
+    #[serde(default = "default_encoder_device")]
+    pub device: String,
+    /// Target bitrate (kbit/s)
+    #[serde(default = "default_bitrate")]
+    pub bitrate_kbps: u32,
+    /// Frames between keyframes; each keyframe repeats the stream headers,
+    /// so recordings can be cut at any of them
+    #[serde(default = "default_keyframe_interval")]
+    pub keyframe_interval: u32,
+}
+
+fn default_encoder_device() -> String { "/dev/video11".to_string() }
+fn default_bitrate() -> u32 { 8_000 }
+fn default_keyframe_interval() -> u32 { 60 }
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            device: default_encoder_device(),
+            bitrate_kbps: default_bitrate(),
+            keyframe_interval: default_keyframe_interval(),
+        }
+    }
+}
+
+/// H.264 encoder on a V4L2 memory-to-memory device
+///
+/// Raw frames are queued to the device and come back as an Annex B
+/// elementary stream, so continuous 1080p recording costs a copy per frame
+/// instead of the CPU that fusion and audio analysis need. The stub backend
+/// produces no bitstream.
+pub struct H264Encoder {
+    config: EncoderConfig,
+    format: VideoFormat,
+    // Declared before `handle` so streaming stops before the device closes
+    #[cfg(target_os = "linux")]
+    session: Option<m2m::Session>,
+    handle: DeviceHandle,
+    frames: u64,
+}
+
+impl H264Encoder {
+    /// Open the encoder for frames of `format`
+    pub fn open(config: EncoderConfig, format: &VideoFormat) -> Result<Self, HalError> {
+        if !matches!(format.pixel_format, PixelFormat::YUYV | PixelFormat::RGB24 | PixelFormat::BGR24) {
+            return Err(HalError::InvalidConfig(format!(
+                "H.264 encoder cannot take {:?} frames", format.pixel_format
+            )));
+        }
+        
+        let handle = DeviceHandle::open(&config.device)?;
+        
+        #[cfg(target_os = "linux")]
+        let session = match handle.raw_fd() {
+            Some(fd) => Some(m2m::Session::start(fd, format, &config)?),
+            None => None,
+        };
+        
+        tracing::info!(
+            "H.264 encoder {} at {}x{}, {} kbit/s",
+            config.device, format.width, format.height, config.bitrate_kbps
+        );
+        
+        Ok(Self {
+            config,
+            format: format.clone(),
+            #[cfg(target_os = "linux")]
+            session,
+            handle,
+            frames: 0,
+        })
+    }
+    
+    /// Encode one frame, returning the H.264 data the device produced for it
+    ///
+    /// Consecutive results appended to a file form a playable `.h264` stream.
+    pub fn encode(&mut self, frame: &Frame) -> Result<Vec<u8>, HalError> {
+        if frame.width != self.format.width || frame.height != self.format.height
+            || frame.format != self.format.pixel_format
+        {
+            return Err(HalError::InvalidConfig(format!(
+                "Encoder expects {}x{} {:?} frames, got {}x{} {:?}",
+                self.format.width, self.format.height, self.format.pixel_format,
+                frame.width, frame.height, frame.format
+            )));
+        }
+        
+        self.frames += 1;
+        
+        #[cfg(target_os = "linux")]
+        if let Some(session) = &mut self.session {
+            return session.encode(&frame.data);
+        }
+        Ok(Vec::new())
+    }
+    
+    /// Frames submitted so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+    
+    /// Encoder device path
+    pub fn device(&self) -> &str {
+        self.handle.path()
+    }
+    
+    pub fn config(&self) -> &EncoderConfig {
+        &self.config
+    }
+}
+
+/// V4L2 multi-planar memory-to-memory streaming, as used by the encoder
+#[cfg(target_os = "linux")]
+mod m2m {
+    use super::{EncoderConfig, VideoFormat};
+    use crate::HalError;
+    use std::io;
+    use std::mem::size_of;
+    
+    const OUTPUT_MPLANE: u32 = 10;
+    const CAPTURE_MPLANE: u32 = 9;
+    const MEMORY_MMAP: u32 = 1;
+    const FIELD_NONE: u32 = 1;
+    const H264: u32 = 0x34363248;  // 'H264'
+    
+    const CID_BITRATE: u32 = 0x009909cf;
+    const CID_REPEAT_SEQ_HEADER: u32 = 0x009909e2;
+    const CID_H264_I_PERIOD: u32 = 0x00990a66;
+    
+    /// Buffers requested per queue
+    const BUFFERS: u32 = 4;
+    /// Longest wait for the device to finish a frame
+    const TIMEOUT_MS: i32 = 1000;
+    
+    pub(super) const fn request(dir: u32, nr: u32, size: usize) -> libc::Ioctl {
+        ((dir << 30) | ((size as u32) << 16) | ((b'V' as u32) << 8) | nr) as libc::Ioctl
+    }
+    
+    pub(super) const fn iowr<T>(nr: u32) -> libc::Ioctl {
+        request(3, nr, size_of::<T>())
+    }
+    
+    const VIDIOC_S_FMT: libc::Ioctl = iowr::<Format>(5);
+    const VIDIOC_REQBUFS: libc::Ioctl = iowr::<RequestBuffers>(8);
+    const VIDIOC_QUERYBUF: libc::Ioctl = iowr::<Buffer>(9);
+    const VIDIOC_QBUF: libc::Ioctl = iowr::<Buffer>(15);
+    const VIDIOC_DQBUF: libc::Ioctl = iowr::<Buffer>(17);
+    const VIDIOC_STREAMON: libc::Ioctl = request(1, 18, size_of::<u32>());
+    const VIDIOC_STREAMOFF: libc::Ioctl = request(1, 19, size_of::<u32>());
+    const VIDIOC_S_CTRL: libc::Ioctl = iowr::<Control>(28);
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct PlanePixFormat {
+        sizeimage: u32,
+        bytesperline: u32,
+        reserved: [u16; 6],
+    }
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct PixFormatMplane {
+        width: u32,
+        height: u32,
+        pixelformat: u32,
+        field: u32,
+        colorspace: u32,
+        plane_fmt: [PlanePixFormat; 8],
+        num_planes: u8,
+        flags: u8,
+        ycbcr_enc: u8,
+        quantization: u8,
+        xfer_func: u8,
+        reserved: [u8; 7],
+    }
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    union FormatData {
+        pix_mp: PixFormatMplane,
+        raw: [u8; 200],
+        // The kernel union holds pointers, which set its alignment
+        _align: *mut libc::c_void,
+    }
+    
+    #[repr(C)]
+    struct Format {
+        buf_type: u32,
+        fmt: FormatData,
+    }
+    
+    #[repr(C)]
+    pub(super) struct RequestBuffers {
+        pub(super) count: u32,
+        pub(super) buf_type: u32,
+        pub(super) memory: u32,
+        pub(super) capabilities: u32,
+        pub(super) flags: u8,
+        pub(super) reserved: [u8; 3],
+    }
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    union PlaneMemory {
+        mem_offset: u32,
+        userptr: libc::c_ulong,
+    }
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Plane {
+        bytesused: u32,
+        length: u32,
+        m: PlaneMemory,
+        data_offset: u32,
+        reserved: [u32; 11],
+    }
+    
+    #[repr(C)]
+    struct Buffer {
+        index: u32,
+        buf_type: u32,
+        bytesused: u32,
+        flags: u32,
+        field: u32,
+        timestamp: libc::timeval,
+        timecode: [u32; 4],
+        sequence: u32,
+        memory: u32,
+        planes: *mut Plane,
+        length: u32,
+        reserved2: u32,
+        request_fd: i32,
+    }
+    
+    #[repr(C)]
+    struct Control {
+        id: u32,
+        value: i32,
+    }
+    
+    fn ioctl<T>(fd: i32, request: libc::Ioctl, arg: &mut T, what: &str) -> Result<(), HalError> {
+        loop {
+            if unsafe { libc::ioctl(fd, request, arg as *mut T) } >= 0 {
+                return Ok(());
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(HalError::CommunicationError(format!("Encoder {}: {}", what, err)));
+            }
+        }
+    }
+    
+    /// Buffer memory-mapped from the device
+    pub(super) struct MappedBuffer {
+        pub(super) ptr: *mut u8,
+        pub(super) len: usize,
+    }
+    
+    // The mapping is owned exclusively, like a Vec's allocation, and only
+    // written through `&mut self`
+    unsafe impl Send for MappedBuffer {}
+    unsafe impl Sync for MappedBuffer {}
+    
+    impl MappedBuffer {
+        pub(super) fn as_slice(&self) -> &[u8] {
+            unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+        }
+        
+        fn as_mut_slice(&mut self) -> &mut [u8] {
+            unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+        }
+    }
+    
+    impl Drop for MappedBuffer {
+        fn drop(&mut self) {
+            unsafe {
+                libc::munmap(self.ptr as *mut libc::c_void, self.len);
+            }
+        }
+    }
+    
+    /// Streaming state of an open encoder
+    pub struct Session {
+        fd: i32,
+        raw: Vec<MappedBuffer>,
+        free: Vec<u32>,
+        encoded: Vec<MappedBuffer>,
+        pending: Vec<u8>,
+        completed: u64,
+    }
+    
+    impl Session {
+        /// Configure both queues, map their buffers and start streaming
+        pub fn start(fd: i32, format: &VideoFormat, config: &EncoderConfig) -> Result<Self, HalError> {
+            set_format(fd, OUTPUT_MPLANE, format.width, format.height, format.pixel_format.fourcc())?;
+            set_format(fd, CAPTURE_MPLANE, format.width, format.height, H264)?;
+            
+            set_control(fd, CID_BITRATE, config.bitrate_kbps.saturating_mul(1000))?;
+            set_control(fd, CID_H264_I_PERIOD, config.keyframe_interval.max(1))?;
+            if let Err(e) = set_control(fd, CID_REPEAT_SEQ_HEADER, 1) {
+                tracing::warn!("Encoder cannot repeat stream headers: {}", e);
+            }
+            
+            let raw = map_buffers(fd, OUTPUT_MPLANE)?;
+            let encoded = map_buffers(fd, CAPTURE_MPLANE)?;
+            let mut session = Self {
+                fd,
+                free: (0..raw.len() as u32).rev().collect(),
+                raw,
+                encoded,
+                pending: Vec::new(),
+                completed: 0,
+            };
+            
+            for index in 0..session.encoded.len() as u32 {
+                session.queue(CAPTURE_MPLANE, index, 0)?;
+            }
+            session.stream(OUTPUT_MPLANE, VIDIOC_STREAMON)?;
+            session.stream(CAPTURE_MPLANE, VIDIOC_STREAMON)?;
+            Ok(session)
+        }
+        
+        /// Queue a raw frame and wait for its encoded data
+        pub fn encode(&mut self, data: &[u8]) -> Result<Vec<u8>, HalError> {
+            let index = loop {
+                match self.free.pop() {
+                    Some(index) => break index,
+                    None => self.service()?,
+                }
+            };
+            
+            let buffer = self.raw[index as usize].as_mut_slice();
+            let len = data.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&data[..len]);
+            self.queue(OUTPUT_MPLANE, index, len as u32)?;
+            
+            let before = self.completed;
+            while self.completed == before {
+                self.service()?;
+            }
+            Ok(std::mem::take(&mut self.pending))
+        }
+        
+        /// Wait for the device, then take back consumed raw buffers and
+        /// collect encoded data
+        fn service(&mut self) -> Result<(), HalError> {
+            let mut pfd = libc::pollfd {
+                fd: self.fd,
+                events: libc::POLLIN | libc::POLLOUT,
+                revents: 0,
+            };
+            match unsafe { libc::poll(&mut pfd, 1, TIMEOUT_MS) } {
+                0 => return Err(HalError::Timeout),
+                n if n < 0 => return Err(io::Error::last_os_error().into()),
+                _ => {}
+            }
+            if pfd.revents & libc::POLLERR != 0 {
+                return Err(HalError::CommunicationError("Encoder reported an error".to_string()));
+            }
+            
+            if pfd.revents & libc::POLLOUT != 0 {
+                let (index, _) = self.dequeue(OUTPUT_MPLANE)?;
+                self.free.push(index);
+            }
+            if pfd.revents & libc::POLLIN != 0 {
+                let (index, range) = self.dequeue(CAPTURE_MPLANE)?;
+                let data = self.encoded[index as usize].as_slice();
+                self.pending.extend_from_slice(data.get(range).unwrap_or_default());
+                self.queue(CAPTURE_MPLANE, index, 0)?;
+                self.completed += 1;
+            }
+            Ok(())
+        }
+        
+        fn queue(&mut self, buf_type: u32, index: u32, bytesused: u32) -> Result<(), HalError> {
+            let buffers = if buf_type == OUTPUT_MPLANE { &self.raw } else { &self.encoded };
+            let mut plane: Plane = unsafe { std::mem::zeroed() };
+            plane.bytesused = bytesused;
+            plane.length = buffers[index as usize].len as u32;
+            
+            let mut buf = buffer(buf_type, &mut plane);
+            buf.index = index;
+            ioctl(self.fd, VIDIOC_QBUF, &mut buf, "queue buffer")
+        }
+        
+        /// Dequeue a finished buffer: its index and the range holding data
+        fn dequeue(&mut self, buf_type: u32) -> Result<(u32, std::ops::Range<usize>), HalError> {
+            let mut plane: Plane = unsafe { std::mem::zeroed() };
+            let mut buf = buffer(buf_type, &mut plane);
+            ioctl(self.fd, VIDIOC_DQBUF, &mut buf, "dequeue buffer")?;
+            Ok((buf.index, plane.data_offset as usize..plane.bytesused as usize))
+        }
+        
+        fn stream(&self, buf_type: u32, request: libc::Ioctl) -> Result<(), HalError> {
+            let mut buf_type = buf_type;
+            ioctl(self.fd, request, &mut buf_type, "stream on/off")
+        }
+    }
+    
+    impl Drop for Session {
+        fn drop(&mut self) {
+            let _ = self.stream(OUTPUT_MPLANE, VIDIOC_STREAMOFF);
+            let _ = self.stream(CAPTURE_MPLANE, VIDIOC_STREAMOFF);
+        }
+    }
+    
+    /// Single-plane MMAP buffer descriptor
+    fn buffer(buf_type: u32, plane: &mut Plane) -> Buffer {
+        let mut buf: Buffer = unsafe { std::mem::zeroed() };
+        buf.buf_type = buf_type;
+        buf.memory = MEMORY_MMAP;
+        buf.planes = plane;
+        buf.length = 1;
+        buf
+    }
+    
+    fn set_format(fd: i32, buf_type: u32, width: u32, height: u32, fourcc: u32) -> Result<(), HalError> {
+        let mut pix_mp: PixFormatMplane = unsafe { std::mem::zeroed() };
+        pix_mp.width = width;
+        pix_mp.height = height;
+        pix_mp.pixelformat = fourcc;
+        pix_mp.field = FIELD_NONE;
+        pix_mp.num_planes = 1;
+        
+        let mut format = Format {
+            buf_type,
+            fmt: FormatData { pix_mp },
+        };
+        ioctl(fd, VIDIOC_S_FMT, &mut format, "set format")?;
+        
+        let applied = unsafe { format.fmt.pix_mp };
+        if applied.pixelformat != fourcc || applied.width != width || applied.height != height {
+            return Err(HalError::InvalidConfig(format!(
+                "Encoder does not support {}x{} in this format", width, height
+            )));
+        }
+        Ok(())
+    }
+    
+    fn set_control(fd: i32, id: u32, value: u32) -> Result<(), HalError> {
+        let mut control = Control {
+            id,
+            value: value.min(i32::MAX as u32) as i32,
+        };
+        ioctl(fd, VIDIOC_S_CTRL, &mut control, "set control")
+    }
+    
+    fn map_buffers(fd: i32, buf_type: u32) -> Result<Vec<MappedBuffer>, HalError> {
+        let mut request = RequestBuffers {
+            count: BUFFERS,
+            buf_type,
+            memory: MEMORY_MMAP,
+            capabilities: 0,
+            flags: 0,
+            reserved: [0; 3],
+        };
+        ioctl(fd, VIDIOC_REQBUFS, &mut request, "request buffers")?;
+        
+        (0..request.count).map(|index| {
+            let mut plane: Plane = unsafe { std::mem::zeroed() };
+            let mut buf = buffer(buf_type, &mut plane);
+            buf.index = index;
+            ioctl(fd, VIDIOC_QUERYBUF, &mut buf, "query buffer")?;
+            
+            let len = plane.length as usize;
+            let ptr = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd,
+                    plane.m.mem_offset as libc::off_t,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error().into());
+            }
+            Ok(MappedBuffer { ptr: ptr as *mut u8, len })
+        }).collect()
+    }
+}
+
+/// V4L2 single-planar capture: memory-mapped streaming, or read() for
+/// devices without streaming I/O
+#[cfg(target_os = "linux")]
+mod capture {
+    use super::m2m::{iowr, request, MappedBuffer, RequestBuffers};
+    use super::{PixelFormat, VideoFormat};
+    use crate::HalError;
+    use std::io;
+    use std::mem::size_of;
+    use std::time::Duration;
+    
+    const VIDEO_CAPTURE: u32 = 1;
+    const MEMORY_MMAP: u32 = 1;
+    const FIELD_ANY: u32 = 0;
+    
+    const CAP_READWRITE: u32 = 0x01000000;
+    const CAP_STREAMING: u32 = 0x04000000;
+    const CAP_DEVICE_CAPS: u32 = 0x80000000;
+    
+    /// Buffers requested for streaming
+    const BUFFERS: u32 = 4;
+    
+    const VIDIOC_QUERYCAP: libc::Ioctl = request(2, 0, size_of::<Capability>());
+    const VIDIOC_G_FMT: libc::Ioctl = iowr::<Format>(4);
+    const VIDIOC_S_FMT: libc::Ioctl = iowr::<Format>(5);
+    const VIDIOC_REQBUFS: libc::Ioctl = iowr::<RequestBuffers>(8);
+    const VIDIOC_QUERYBUF: libc::Ioctl = iowr::<Buffer>(9);
+    const VIDIOC_QBUF: libc::Ioctl = iowr::<Buffer>(15);
+    const VIDIOC_DQBUF: libc::Ioctl = iowr::<Buffer>(17);
+    const VIDIOC_STREAMON: libc::Ioctl = request(1, 18, size_of::<u32>());
+    const VIDIOC_STREAMOFF: libc::Ioctl = request(1, 19, size_of::<u32>());
+    
+    #[repr(C)]
+    struct Capability {
+        driver: [u8; 16],
+        card: [u8; 32],
+        bus_info: [u8; 32],
+        version: u32,
+        capabilities: u32,
+        device_caps: u32,
+        reserved: [u32; 3],
+    }
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct PixFormat {
+        width: u32,
+        height: u32,
+        pixelformat: u32,
+        field: u32,
+        bytesperline: u32,
+        sizeimage: u32,
+        colorspace: u32,
+        priv_: u32,
+        flags: u32,
+        ycbcr_enc: u32,
+        quantization: u32,
+        xfer_func: u32,
+    }
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    union FormatData {
+        pix: PixFormat,
+        raw: [u8; 200],
+        // The kernel union holds pointers, which set its alignment
+        _align: *mut libc::c_void,
+    }
+    
+    #[repr(C)]
+    struct Format {
+        buf_type: u32,
+        fmt: FormatData,
+    }
+    
+    // struct v4l2_format, as in VIDIOC_S_FMT = 0xC0D05605 on 64-bit targets
+    #[cfg(target_pointer_width = "64")]
+    const _: () = assert!(size_of::<Format>() == 208);
+    
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    union BufferMemory {
+        offset: u32,
+        userptr: libc::c_ulong,
+        planes: *mut libc::c_void,
+        fd: i32,
+    }
+    
+    #[repr(C)]
+    struct Buffer {
+        index: u32,
+        buf_type: u32,
+        bytesused: u32,
+        flags: u32,
+        field: u32,
+        timestamp: libc::timeval,
+        timecode: [u32; 4],
+        sequence: u32,
+        memory: u32,
+        m: BufferMemory,
+        length: u32,
+        reserved2: u32,
+        request_fd: i32,
+    }
+    
+    fn ioctl<T>(fd: i32, request: libc::Ioctl, arg: &mut T, what: &str) -> Result<(), HalError> {
+        loop {
+            if unsafe { libc::ioctl(fd, request, arg as *mut T) } >= 0 {
+                return Ok(());
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(HalError::CommunicationError(format!("Camera {}: {}", what, err)));
+            }
+        }
+    }
+    
+    /// Request `format` and return the format the driver settled on
+    pub fn set_format(fd: i32, format: &VideoFormat) -> Result<VideoFormat, HalError> {
+        let mut request = Format {
+            buf_type: VIDEO_CAPTURE,
+            fmt: FormatData { raw: [0; 200] },
+        };
+        request.fmt.pix = PixFormat {
+            width: format.width,
+            height: format.height,
+            pixelformat: format.pixel_format.fourcc(),
+            field: FIELD_ANY,
+            bytesperline: 0,
+            sizeimage: 0,
+            colorspace: 0,
+            priv_: 0,
+            flags: 0,
+            ycbcr_enc: 0,
+            quantization: 0,
+            xfer_func: 0,
+        };
+        ioctl(fd, VIDIOC_S_FMT, &mut request, "set format")?;
+        
+        let mut current = Format {
+            buf_type: VIDEO_CAPTURE,
+            fmt: FormatData { raw: [0; 200] },
+        };
+        ioctl(fd, VIDIOC_G_FMT, &mut current, "get format")?;
+        let pix = unsafe { current.fmt.pix };
+        let pixel_format = PixelFormat::from_fourcc(pix.pixelformat).ok_or_else(|| {
+            let fourcc = pix.pixelformat.to_le_bytes().map(char::from);
+            HalError::InvalidConfig(format!(
+                "Camera delivers unsupported pixel format '{}'", String::from_iter(fourcc)
+            ))
+        })?;
+        Ok(VideoFormat {
+            width: pix.width,
+            height: pix.height,
+            pixel_format,
+            fps: format.fps,
+        })
+    }
+    
+    /// MMAP capture buffer descriptor
+    fn buffer(index: u32) -> Buffer {
+        let mut buf: Buffer = unsafe { std::mem::zeroed() };
+        buf.index = index;
+        buf.buf_type = VIDEO_CAPTURE;
+        buf.memory = MEMORY_MMAP;
+        buf
+    }
+    
+    /// How frames come off the device
+    enum Io {
+        /// Driver-filled buffers, cycled through the queue
+        Mmap(Vec<MappedBuffer>),
+        /// read() of whole frames of at most this many bytes
+        Read(usize),
+    }
+    
+    /// Capture state of a streaming camera
+    pub struct Stream {
+        fd: i32,
+        io: Io,
+    }
+    
+    impl Stream {
+        /// Map and queue buffers and start streaming, falling back to read()
+        /// when the driver has no streaming I/O
+        pub fn start(fd: i32) -> Result<Self, HalError> {
+            let mut capability: Capability = unsafe { std::mem::zeroed() };
+            ioctl(fd, VIDIOC_QUERYCAP, &mut capability, "query capabilities")?;
+            let caps = if capability.capabilities & CAP_DEVICE_CAPS != 0 {
+                capability.device_caps
+            } else {
+                capability.capabilities
+            };
+            
+            if caps & CAP_STREAMING != 0 {
+                let stream = Self { fd, io: Io::Mmap(map_buffers(fd)?) };
+                if let Io::Mmap(buffers) = &stream.io {
+                    for index in 0..buffers.len() as u32 {
+                        stream.queue(index)?;
+                    }
+                }
+                stream.stream(VIDIOC_STREAMON)?;
+                return Ok(stream);
+            }
+            
+            if caps & CAP_READWRITE != 0 {
+                let mut format = Format {
+                    buf_type: VIDEO_CAPTURE,
+                    fmt: FormatData { raw: [0; 200] },
+                };
+                ioctl(fd, VIDIOC_G_FMT, &mut format, "get format")?;
+                let size = unsafe { format.fmt.pix.sizeimage } as usize;
+                tracing::info!("Camera has no streaming I/O, reading frames of {} bytes", size);
+                return Ok(Self { fd, io: Io::Read(size.max(1)) });
+            }
+            
+            Err(HalError::InvalidConfig("Camera supports neither streaming nor read() I/O".to_string()))
+        }
+        
+        /// Wait up to `timeout` for the next frame and copy out its data
+        pub fn next_frame(&mut self, timeout: Duration) -> Result<Vec<u8>, HalError> {
+            let mut pfd = libc::pollfd {
+                fd: self.fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+            match unsafe { libc::poll(&mut pfd, 1, timeout_ms) } {
+                0 => return Err(HalError::Timeout),
+                n if n < 0 => return Err(io::Error::last_os_error().into()),
+                _ => {}
+            }
+            if pfd.revents & libc::POLLERR != 0 {
+                return Err(HalError::CommunicationError("Camera reported an error".to_string()));
+            }
+            
+            match &self.io {
+                Io::Mmap(buffers) => {
+                    let mut buf = buffer(0);
+                    ioctl(self.fd, VIDIOC_DQBUF, &mut buf, "dequeue buffer")?;
+                    let data = buffers.get(buf.index as usize)
+                        .map(|b| b.as_slice()[..(buf.bytesused as usize).min(b.len)].to_vec())
+                        .unwrap_or_default();
+                    self.queue(buf.index)?;
+                    Ok(data)
+                }
+                Io::Read(size) => {
+                    let mut data = vec![0u8; *size];
+                    loop {
+                        let n = unsafe { libc::read(self.fd, data.as_mut_ptr() as *mut libc::c_void, data.len()) };
+                        if n >= 0 {
+                            data.truncate(n as usize);
+                            return Ok(data);
+                        }
+                        let err = io::Error::last_os_error();
+                        if err.kind() != io::ErrorKind::Interrupted {
+                            return Err(err.into());
+                        }
+                    }
+                }
+            }
+        }
+        
+        fn queue(&self, index: u32) -> Result<(), HalError> {
+            let mut buf = buffer(index);
+            ioctl(self.fd, VIDIOC_QBUF, &mut buf, "queue buffer")
+        }
+        
+        fn stream(&self, request: libc::Ioctl) -> Result<(), HalError> {
+            let mut buf_type = VIDEO_CAPTURE;
+            ioctl(self.fd, request, &mut buf_type, "stream on/off")
+        }
+    }
+    
+    impl Drop for Stream {
+        fn drop(&mut self) {
+            // Buffers are unmapped after this, once the queue is stopped
+            if matches!(self.io, Io::Mmap(_)) {
+                let _ = self.stream(VIDIOC_STREAMOFF);
+            }
+        }
+    }
+    
+    fn map_buffers(fd: i32) -> Result<Vec<MappedBuffer>, HalError> {
+        let mut request = RequestBuffers {
+            count: BUFFERS,
+            buf_type: VIDEO_CAPTURE,
+            memory: MEMORY_MMAP,
+            capabilities: 0,
+            flags: 0,
+            reserved: [0; 3],
+        };
+        ioctl(fd, VIDIOC_REQBUFS, &mut request, "request buffers")?;
+        if request.count == 0 {
+            return Err(HalError::CommunicationError("Camera granted no capture buffers".to_string()));
+        }
+        
+        (0..request.count).map(|index| {
+            let mut buf = buffer(index);
+            ioctl(fd, VIDIOC_QUERYBUF, &mut buf, "query buffer")?;
+            
+            let len = buf.length as usize;
+            let ptr = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd,
+                    buf.m.offset as libc::off_t,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error().into());
+            }
+            Ok(MappedBuffer { ptr: ptr as *mut u8, len })
+        }).collect()
+    }
+}
@@ -0,0 +1,180 @@
+//! Stream clock synchronization
+//!
+//! Every stream reaches the host late by its own amount: a sound card hands
+//! over a buffer after the last sample in it was captured, a camera pipeline
+//! delivers a frame well after its exposure, a sensor read waits for the
+//! rest of its bus. [`SyncClock`] stamps all streams against one monotonic
+//! clock — wall time at startup plus elapsed monotonic time, so an NTP step
+//! mid-session cannot tear streams apart — backdates each reading by its
+//! stream's delay, and keeps per-stream delay statistics. The statistics are
+//! stored with the session so review can line audio, video and sensor
+//! tracks up to within tens of milliseconds.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// What a stream carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamKind {
+    Sensor,
+    Audio,
+    Video,
+}
+
+/// Measured delay between capture and arrival of one stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamOffset {
+    pub stream: String,
+    pub kind: StreamKind,
+    /// Mean delay (ms) subtracted from arrival times
+    pub offset_ms: f64,
+    /// Mean absolute deviation of the delay (ms)
+    pub jitter_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub samples: u64,
+}
+
+/// Clock alignment of a session (the session's `clock.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockReport {
+    /// Wall time of the common clock's zero
+    pub epoch: SystemTime,
+    /// How far the system wall clock has moved from the common clock (ms),
+    /// e.g. through NTP corrections
+    pub wall_drift_ms: f64,
+    pub streams: Vec<StreamOffset>,
+}
+
+impl ClockReport {
+    /// Delay of `stream`, if it was measured
+    pub fn offset(&self, stream: &str) -> Option<&StreamOffset> {
+        self.streams.iter().find(|s| s.stream == stream)
+    }
+}
+
+/// Common monotonic clock for all streams
+///
+/// Cheap to clone; every capture path stamps its readings through it.
+#[derive(Debug, Clone)]
+pub struct SyncClock {
+    epoch: Instant,
+    epoch_wall: SystemTime,
+    streams: Arc<Mutex<HashMap<String, StreamOffset>>>,
+}
+
+impl SyncClock {
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            epoch_wall: SystemTime::now(),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+    
+    /// Current time on the common clock
+    pub fn now(&self) -> SystemTime {
+        self.at(Instant::now())
+    }
+    
+    /// Common-clock time of a monotonic instant
+    pub fn at(&self, instant: Instant) -> SystemTime {
+        match instant.checked_duration_since(self.epoch) {
+            Some(elapsed) => self.epoch_wall + elapsed,
+            None => self.epoch_wall - self.epoch.duration_since(instant),
+        }
+    }
+    
+    /// Capture time of data from `stream` arriving now, `delay` after it
+    /// was captured; the delay is added to the stream's statistics
+    pub fn captured(&self, stream: &str, kind: StreamKind, delay: Duration) -> SystemTime {
+        self.record(stream, kind, delay);
+        self.now() - delay
+    }
+    
+    /// Add a measured capture-to-arrival delay to a stream's statistics
+    pub fn record(&self, stream: &str, kind: StreamKind, delay: Duration) {
+        let ms = delay.as_secs_f64() * 1000.0;
+        let mut streams = self.streams.lock().unwrap();
+        let offset = streams.entry(stream.to_string()).or_insert_with(|| StreamOffset {
+            stream: stream.to_string(),
+            kind,
+            offset_ms: ms,
+            jitter_ms: 0.0,
+            min_ms: ms,
+            max_ms: ms,
+            samples: 0,
+        });
+        
+        offset.samples += 1;
+        let n = offset.samples as f64;
+        offset.offset_ms += (ms - offset.offset_ms) / n;
+        offset.jitter_ms += ((ms - offset.offset_ms).abs() - offset.jitter_ms) / n;
+        offset.min_ms = offset.min_ms.min(ms);
+        offset.max_ms = offset.max_ms.max(ms);
+    }
+    
+    /// Handle for a stream whose devices add a fixed `latency` the host
+    /// cannot see (e.g. measured once with a clapper board)
+    pub fn stream(&self, name: &str, kind: StreamKind, latency: Duration) -> StreamClock {
+        StreamClock {
+            clock: self.clone(),
+            stream: name.to_string(),
+            kind,
+            latency,
+        }
+    }
+    
+    /// Forget the statistics gathered so far, e.g. when a new session starts
+    pub fn reset(&self) {
+        self.streams.lock().unwrap().clear();
+    }
+    
+    /// Current alignment of every stream
+    pub fn report(&self) -> ClockReport {
+        let now = Instant::now();
+        let wall_drift_ms = match SystemTime::now().duration_since(self.at(now)) {
+            Ok(ahead) => ahead.as_secs_f64() * 1000.0,
+            Err(behind) => -behind.duration().as_secs_f64() * 1000.0,
+        };
+        
+        let mut streams: Vec<StreamOffset> = self.streams.lock().unwrap().values().cloned().collect();
+        streams.sort_by(|a, b| a.stream.cmp(&b.stream));
+        
+        ClockReport {
+            epoch: self.epoch_wall,
+            wall_drift_ms,
+            streams,
+        }
+    }
+}
+
+impl Default for SyncClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One stream's view of the common clock
+#[derive(Debug, Clone)]
+pub struct StreamClock {
+    clock: SyncClock,
+    stream: String,
+    kind: StreamKind,
+    latency: Duration,
+}
+
+impl StreamClock {
+    /// Capture time of data arriving now that spent `buffered` in host-visible
+    /// buffers on top of the stream's fixed latency
+    pub fn captured(&self, buffered: Duration) -> SystemTime {
+        self.clock.captured(&self.stream, self.kind, self.latency + buffered)
+    }
+    
+    pub fn stream(&self) -> &str {
+        &self.stream
+    }
+}
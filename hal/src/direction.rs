@@ -0,0 +1,201 @@
+//! RF direction finding with switched antennas
+//!
+//! A GPIO-driven RF switch connects one of several directional antennas to
+//! the SDR at a time. Cycling through them while a signal is up and
+//! comparing the power each one receives at the signal's frequency gives a
+//! rough bearing: the power-weighted mean of the antennas' pointing
+//! directions. How concentrated that power is on one side says how much to
+//! trust it; reflections inside buildings spread it out.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gpio")]
+use glowbarn_dsp as dsp;
+#[cfg(feature = "gpio")]
+use crate::calibration::CalibrationStore;
+#[cfg(feature = "gpio")]
+use crate::gpio::{Direction, GpioPin};
+#[cfg(feature = "gpio")]
+use crate::sdr::RtlSdr;
+#[cfg(feature = "gpio")]
+use crate::{HalError, HardwareDevice};
+#[cfg(feature = "gpio")]
+use std::time::Duration;
+
+/// Antenna switch and direction finder (`[rf_direction]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectionConfig {
+    /// Dongle index (not the one used for wideband sweeps)
+    #[serde(default)]
+    pub device: u32,
+    /// Dongle serial, for its power calibration
+    #[serde(default)]
+    pub serial: Option<String>,
+    /// GPIO pins selecting the switch position, least significant bit first
+    pub select_pins: Vec<u32>,
+    /// Bearing each antenna points at (degrees clockwise from north), by
+    /// switch position
+    pub antennas: Vec<f64>,
+    /// Wait after switching before sampling
+    #[serde(default = "default_settle")]
+    pub settle_ms: u64,
+    /// IQ samples captured per antenna per cycle
+    #[serde(default = "default_samples")]
+    pub samples_per_antenna: usize,
+    /// Passes over all antennas averaged into one estimate
+    #[serde(default = "default_cycles")]
+    pub cycles: usize,
+    /// Width around the signal frequency counted as the signal (kHz)
+    #[serde(default = "default_bandwidth")]
+    pub bandwidth_khz: f64,
+    /// Tuner gain (dB)
+    #[serde(default = "default_gain")]
+    pub gain_db: f64,
+}
+
+fn default_settle() -> u64 { 5 }
+fn default_samples() -> usize { 16_384 }
+fn default_cycles() -> usize { 3 }
+fn default_bandwidth() -> f64 { 25.0 }
+fn default_gain() -> f64 { 40.2 }
+
+/// Power received on one antenna
+#[derive(Debug, Clone, Serialize)]
+pub struct AntennaPower {
+    pub bearing_deg: f64,
+    pub power_db: f64,
+}
+
+/// Bearing estimate for a signal
+#[derive(Debug, Clone, Serialize)]
+pub struct BearingEstimate {
+    pub frequency_hz: f64,
+    /// Degrees clockwise from north
+    pub bearing_deg: f64,
+    /// 1 when all power arrives on one antenna, 0 when it is spread evenly
+    pub concentration: f64,
+    pub antennas: Vec<AntennaPower>,
+}
+
+/// Power-weighted circular mean of antenna bearings
+///
+/// `None` without antennas or when the powers cancel out.
+pub fn estimate_bearing(frequency_hz: f64, antennas: Vec<AntennaPower>) -> Option<BearingEstimate> {
+    let (mut x, mut y, mut total) = (0.0, 0.0, 0.0);
+    for antenna in &antennas {
+        let power = 10f64.powf(antenna.power_db / 10.0);
+        let angle = antenna.bearing_deg.to_radians();
+        x += power * angle.sin();
+        y += power * angle.cos();
+        total += power;
+    }
+    
+    let resultant = x.hypot(y);
+    if total <= 0.0 || resultant < f64::EPSILON * total {
+        return None;
+    }
+    
+    Some(BearingEstimate {
+        frequency_hz,
+        bearing_deg: x.atan2(y).to_degrees().rem_euclid(360.0),
+        concentration: resultant / total,
+        antennas,
+    })
+}
+
+/// Signal power within `bandwidth_hz` of `offset_hz` from the tuned
+/// frequency, in a centred spectrum spanning `sample_rate`
+#[cfg(feature = "gpio")]
+fn band_power(spectrum: &[f64], sample_rate: f64, offset_hz: f64, bandwidth_hz: f64) -> f64 {
+    let bin_hz = sample_rate / spectrum.len().max(1) as f64;
+    let center = ((offset_hz + sample_rate / 2.0) / bin_hz).round() as isize;
+    let half = ((bandwidth_hz / 2.0 / bin_hz).ceil() as isize).max(1);
+    (center - half..=center + half)
+        .filter_map(|i| usize::try_from(i).ok())
+        .filter_map(|i| spectrum.get(i))
+        .sum()
+}
+
+/// SDR behind a GPIO-driven antenna switch
+#[cfg(feature = "gpio")]
+pub struct DirectionFinder {
+    sdr: RtlSdr,
+    select: Vec<GpioPin>,
+    config: DirectionConfig,
+}
+
+#[cfg(feature = "gpio")]
+impl DirectionFinder {
+    pub fn open(config: DirectionConfig) -> Result<Self, HalError> {
+        if config.antennas.len() < 2 {
+            return Err(HalError::InvalidConfig("Direction finding needs at least two antennas".to_string()));
+        }
+        if config.antennas.len() > 1 << config.select_pins.len() {
+            return Err(HalError::InvalidConfig(format!(
+                "{} select pins cannot switch {} antennas", config.select_pins.len(), config.antennas.len()
+            )));
+        }
+        
+        let select = config.select_pins.iter().enumerate()
+            .map(|(bit, &pin)| GpioPin::new(&format!("antenna_select_{}", bit), pin, Direction::Output))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let mut sdr = RtlSdr::open(config.device)?;
+        if let Some(serial) = &config.serial {
+            sdr = sdr.with_serial(serial);
+        }
+        sdr.init()?;
+        sdr.set_gain((config.gain_db * 10.0).round() as i32)?;
+        
+        Ok(Self { sdr, select, config })
+    }
+    
+    /// Apply the dongle's stored power calibration, if there is one
+    pub fn load_power_calibration(&mut self, store: &CalibrationStore) -> Result<bool, HalError> {
+        self.sdr.load_power_calibration(store)
+    }
+    
+    /// Connect the antenna at switch `position`
+    pub fn select(&self, position: usize) -> Result<(), HalError> {
+        for (bit, pin) in self.select.iter().enumerate() {
+            pin.write(position >> bit & 1 == 1)?;
+        }
+        Ok(())
+    }
+    
+    /// Cycle the antennas on `frequency_hz` and estimate the signal's bearing
+    ///
+    /// Tunes a quarter of the sample rate off the signal to keep it clear
+    /// of the dongle's DC spike.
+    pub fn measure(&mut self, frequency_hz: f64) -> Result<Option<BearingEstimate>, HalError> {
+        let sample_rate = self.sdr.config().sample_rate as f64;
+        let offset = sample_rate / 4.0;
+        self.sdr.set_frequency((frequency_hz - offset).round() as u64)?;
+        
+        let settle = Duration::from_millis(self.config.settle_ms);
+        let bandwidth = self.config.bandwidth_khz * 1e3;
+        let mut sums = vec![0.0; self.config.antennas.len()];
+        let cycles = self.config.cycles.max(1);
+        for _ in 0..cycles {
+            for (position, sum) in sums.iter_mut().enumerate() {
+                self.select(position)?;
+                std::thread::sleep(settle);
+                let samples = self.sdr.read_samples(self.config.samples_per_antenna)?;
+                let spectrum = self.sdr.power_spectrum(&samples);
+                *sum += band_power(&spectrum, sample_rate, offset, bandwidth);
+            }
+        }
+        
+        let calibration = self.sdr.to_dbm(0.0).unwrap_or(0.0);
+        let antennas = self.config.antennas.iter().zip(&sums)
+            .map(|(&bearing_deg, &sum)| AntennaPower {
+                bearing_deg,
+                power_db: dsp::power_to_db(sum / cycles as f64) + calibration,
+            })
+            .collect();
+        Ok(estimate_bearing(frequency_hz, antennas))
+    }
+    
+    pub fn config(&self) -> &DirectionConfig {
+        &self.config
+    }
+}
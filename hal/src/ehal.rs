@@ -0,0 +1,153 @@
+//! Adapter for external embedded-hal style drivers
+//!
+//! Wraps any third-party driver that exposes a measurement method into
+//! the GlowBarn [`Sensor`] trait so it can be registered with the
+//! [`HardwareManager`](crate::HardwareManager) like a native sensor.
+//!
+//! Drivers are compiled in by the integrator: a named [`EhalDriverFactory`]
+//! is registered with
+//! [`HardwareManager::register_ehal_driver`](crate::HardwareManager::register_ehal_driver)
+//! before `init`, and each [`EhalSensorConfig`] entry in the device manifest
+//! naming it opens one adapter. The daemon registers the I2C chip drivers
+//! the HAL ships the same way, with `i2c::open_manifest_sensor`.
+
+use crate::{HalError, HardwareDevice, Sensor, DeviceType};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+type ReadFn<D> = Box<dyn Fn(&mut D) -> Result<f64, String> + Send + Sync>;
+
+/// Manifest entry for a sensor behind an external driver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EhalSensorConfig {
+    /// Sensor name
+    pub name: String,
+    /// Registered driver factory that opens it
+    pub driver: String,
+    /// Bus device path the driver is opened on (e.g. `/dev/i2c-1`)
+    #[serde(default)]
+    pub bus: Option<String>,
+    /// Bus address, for drivers of chips with more than one
+    #[serde(default)]
+    pub address: Option<u8>,
+    /// Unit reported instead of the driver's default
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Initial calibration offset
+    #[serde(default)]
+    pub offset: f64,
+}
+
+/// Opens the sensor of a manifest entry
+pub type EhalDriverFactory = fn(&EhalSensorConfig) -> Result<Box<dyn Sensor>, HalError>;
+
+/// Generic sensor adapter around an external driver
+pub struct EhalSensorAdapter<D> {
+    name: String,
+    unit: String,
+    device_type: DeviceType,
+    driver: Mutex<D>,
+    read: ReadFn<D>,
+    calibration_offset: f64,
+    ready: bool,
+}
+
+impl<D: Send> EhalSensorAdapter<D> {
+    /// Wrap a driver; `read` extracts one measurement from it
+    pub fn new<F, E>(name: &str, unit: &str, driver: D, read: F) -> Self
+    where
+        F: Fn(&mut D) -> Result<f64, E> + Send + Sync + 'static,
+        E: std::fmt::Debug,
+    {
+        Self {
+            name: name.to_string(),
+            unit: unit.to_string(),
+            device_type: DeviceType::I2C,
+            driver: Mutex::new(driver),
+            read: Box::new(move |d| read(d).map_err(|e| format!("{:?}", e))),
+            calibration_offset: 0.0,
+            ready: false,
+        }
+    }
+    
+    /// Wrap a driver opened for a manifest entry, taking its name, unit
+    /// (`unit` unless the entry sets one) and offset
+    pub fn from_config<F, E>(config: &EhalSensorConfig, unit: &str, driver: D, read: F) -> Self
+    where
+        F: Fn(&mut D) -> Result<f64, E> + Send + Sync + 'static,
+        E: std::fmt::Debug,
+    {
+        Self::new(&config.name, config.unit.as_deref().unwrap_or(unit), driver, read)
+            .with_offset(config.offset)
+    }
+    
+    /// Set the bus type reported for this device
+    pub fn with_device_type(mut self, device_type: DeviceType) -> Self {
+        self.device_type = device_type;
+        self
+    }
+    
+    /// Set initial calibration offset
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.calibration_offset = offset;
+        self
+    }
+    
+    /// Run a closure against the wrapped driver (for driver-specific setup)
+    pub fn with_driver<R>(&self, f: impl FnOnce(&mut D) -> R) -> R {
+        let mut driver = self.driver.lock().unwrap();
+        f(&mut driver)
+    }
+    
+    /// Unwrap the adapter, returning the driver
+    pub fn into_inner(self) -> D {
+        self.driver.into_inner().unwrap()
+    }
+}
+
+impl<D: Send> HardwareDevice for EhalSensorAdapter<D> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        self.device_type
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.ready = true;
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.ready = false;
+        Ok(())
+    }
+}
+
+impl<D: Send> Sensor for EhalSensorAdapter<D> {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        let value = self.read_value()? - self.calibration_offset;
+        Ok(value.to_le_bytes().to_vec())
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        let mut driver = self.driver.lock().unwrap();
+        let value = (self.read)(&mut driver)
+            .map_err(|e| HalError::CommunicationError(format!("{}: {}", self.name, e)))?;
+        Ok(value + self.calibration_offset)
+    }
+    
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+    
+    fn calibrate(&mut self, offset: f64) -> Result<(), HalError> {
+        self.calibration_offset = offset;
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+//! Simulated fault injection
+//!
+//! The watchdog, retry and health paths only run when hardware misbehaves,
+//! which on a bench is rarely and never on cue. With `fault_injection`
+//! enabled in [`HalConfig`](crate::HalConfig), faults can be scheduled per
+//! sensor: reads that hang and time out, garbage values, intermittent bus
+//! errors and a device that vanishes. Each fault can start after a delay and
+//! end after a duration, so a sequence of them plays out a script. Faults
+//! apply to polled readings.
+
+use crate::{HalError, ReadingPayload, SensorReading};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// What an injected fault does to a sensor's reads
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FaultKind {
+    /// Reads hang for `delay_ms`, then fail with a timeout
+    Timeout {
+        #[serde(default = "default_delay")]
+        delay_ms: u64,
+    },
+    /// Reads succeed with nonsense values (NaN, infinities, wild numbers)
+    Garbage,
+    /// Reads fail with a bus error with the given probability
+    BusError {
+        #[serde(default = "default_probability")]
+        probability: f64,
+    },
+    /// The device is gone: not ready, reads fail
+    Disappear,
+}
+
+fn default_delay() -> u64 { 2000 }
+fn default_probability() -> f64 { 0.5 }
+
+impl std::fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout { delay_ms } => write!(f, "timeout after {} ms", delay_ms),
+            Self::Garbage => write!(f, "garbage data"),
+            Self::BusError { probability } => write!(f, "bus errors ({:.0}% of reads)", probability * 100.0),
+            Self::Disappear => write!(f, "device gone"),
+        }
+    }
+}
+
+/// One step of a fault script (`[[fault]]` in a script file)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaultSpec {
+    pub sensor: String,
+    #[serde(flatten)]
+    pub kind: FaultKind,
+    /// Delay before the fault starts (s)
+    #[serde(default)]
+    pub after_secs: f64,
+    /// How long the fault lasts (s); until cleared if unset
+    #[serde(default)]
+    pub duration_secs: Option<f64>,
+}
+
+/// State of a scheduled or active fault
+#[derive(Debug, Clone, Serialize)]
+pub struct FaultStatus {
+    pub id: u32,
+    pub sensor: String,
+    #[serde(flatten)]
+    pub kind: FaultKind,
+    pub active: bool,
+    /// Seconds until the fault starts (0 once active)
+    pub starts_in_secs: f64,
+    /// Seconds until the fault ends, if it ends by itself
+    pub remaining_secs: Option<f64>,
+    /// Reads the fault has affected so far
+    pub hits: u64,
+}
+
+struct Fault {
+    id: u32,
+    sensor: String,
+    kind: FaultKind,
+    starts: Instant,
+    ends: Option<Instant>,
+    hits: u64,
+}
+
+impl Fault {
+    fn active(&self, now: Instant) -> bool {
+        now >= self.starts && self.ends.is_none_or(|end| now < end)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    faults: Vec<Fault>,
+    next_id: u32,
+    rng: u64,
+}
+
+impl State {
+    /// Drop faults that have run their course
+    fn prune(&mut self, now: Instant) {
+        self.faults.retain(|f| f.ends.is_none_or(|end| now < end));
+    }
+    
+    fn random(&mut self) -> f64 {
+        // xorshift64*
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        (self.rng.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Scheduled faults, shared by the polling tasks
+///
+/// Cheap to clone.
+#[derive(Clone)]
+pub struct FaultInjector {
+    state: Arc<Mutex<State>>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                rng: 0x9e37_79b9_7f4a_7c15,
+                ..Default::default()
+            })),
+        }
+    }
+    
+    /// Schedule a fault
+    pub fn inject(&self, spec: &FaultSpec) -> Result<FaultStatus, HalError> {
+        let seconds = |s: f64, what: &str| Duration::try_from_secs_f64(s)
+            .map_err(|_| HalError::InvalidConfig(format!("invalid fault {} {}", what, s)));
+        if let FaultKind::BusError { probability } = spec.kind {
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(HalError::InvalidConfig(format!("bus error probability {} is outside 0-1", probability)));
+            }
+        }
+        
+        let now = Instant::now();
+        let starts = now + seconds(spec.after_secs, "delay")?;
+        let ends = spec.duration_secs.map(|d| seconds(d, "duration")).transpose()?.map(|d| starts + d);
+        
+        let mut state = self.state.lock().unwrap();
+        state.prune(now);
+        state.next_id += 1;
+        let fault = Fault {
+            id: state.next_id,
+            sensor: spec.sensor.clone(),
+            kind: spec.kind,
+            starts,
+            ends,
+            hits: 0,
+        };
+        let status = status(&fault, now);
+        state.faults.push(fault);
+        
+        tracing::warn!("Fault injected on {}: {} (id {})", spec.sensor, spec.kind, status.id);
+        Ok(status)
+    }
+    
+    /// Remove the faults of `sensor`, or all; returns how many were removed
+    pub fn clear(&self, sensor: Option<&str>) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let before = state.faults.len();
+        state.faults.retain(|f| sensor.is_some_and(|s| s != f.sensor));
+        let removed = before - state.faults.len();
+        if removed > 0 {
+            tracing::info!("Cleared {} injected faults", removed);
+        }
+        removed
+    }
+    
+    /// Scheduled and active faults
+    pub fn list(&self) -> Vec<FaultStatus> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        state.prune(now);
+        state.faults.iter().map(|f| status(f, now)).collect()
+    }
+    
+    /// Whether a "device gone" fault is active on `sensor`
+    pub fn disappeared(&self, sensor: &str) -> bool {
+        let now = Instant::now();
+        self.state.lock().unwrap().faults.iter()
+            .any(|f| f.sensor == sensor && f.kind == FaultKind::Disappear && f.active(now))
+    }
+    
+    /// Take a reading of `sensor` through its active fault, if any
+    ///
+    /// Runs on the polling thread, so a timeout fault holds up the sensor's
+    /// bus just like a hung device would.
+    pub fn read(&self, sensor: &str, read: impl FnOnce() -> Result<SensorReading, HalError>) -> Result<SensorReading, HalError> {
+        let kind = {
+            let now = Instant::now();
+            let mut state = self.state.lock().unwrap();
+            state.faults.iter_mut()
+                .find(|f| f.sensor == sensor && f.active(now))
+                .map(|fault| {
+                    fault.hits += 1;
+                    fault.kind
+                })
+        };
+        let Some(kind) = kind else {
+            return read();
+        };
+        
+        match kind {
+            FaultKind::Timeout { delay_ms } => {
+                std::thread::sleep(Duration::from_millis(delay_ms));
+                Err(HalError::Timeout)
+            }
+            FaultKind::Garbage => {
+                let mut reading = read()?;
+                let mut state = self.state.lock().unwrap();
+                reading.value = garbage(&mut state);
+                match &mut reading.payload {
+                    ReadingPayload::Vector(components) => {
+                        for (_, value) in components.iter_mut() {
+                            *value = garbage(&mut state);
+                        }
+                    }
+                    ReadingPayload::Channels(channels) => {
+                        for channel in channels.iter_mut() {
+                            channel.value = garbage(&mut state);
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(reading)
+            }
+            FaultKind::BusError { probability } => {
+                if self.state.lock().unwrap().random() < probability {
+                    Err(HalError::CommunicationError(format!("{}: injected bus error", sensor)))
+                } else {
+                    read()
+                }
+            }
+            FaultKind::Disappear => Err(HalError::DeviceNotFound(format!("{} (injected)", sensor))),
+        }
+    }
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn status(fault: &Fault, now: Instant) -> FaultStatus {
+    FaultStatus {
+        id: fault.id,
+        sensor: fault.sensor.clone(),
+        kind: fault.kind,
+        active: fault.active(now),
+        starts_in_secs: fault.starts.saturating_duration_since(now).as_secs_f64(),
+        remaining_secs: fault.ends.map(|end| end.saturating_duration_since(now).as_secs_f64()),
+        hits: fault.hits,
+    }
+}
+
+/// A value no healthy sensor would report
+fn garbage(state: &mut State) -> f64 {
+    match (state.random() * 5.0) as u32 {
+        0 => f64::NAN,
+        1 => f64::INFINITY,
+        2 => f64::NEG_INFINITY,
+        3 => (state.random() - 0.5) * 2e9,
+        _ => (state.random() * 65536.0).floor() - 32768.0,
+    }
+}
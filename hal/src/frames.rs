@@ -0,0 +1,94 @@
+//! Camera pipeline accounting
+//!
+//! Frames travel from a camera's capture loop through the reading channel
+//! to the fusion engine. When the engine falls behind, the channel fills
+//! and new frames are dropped, so motion detection quietly misses events.
+//! Counting each camera's frames at both ends makes that visible: frames
+//! captured and dropped on the way in, processed frames and their
+//! capture-to-processed latency on the way out.
+
+use crate::SensorReading;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/// Frame counts and latency of one camera
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CameraStats {
+    pub camera: String,
+    /// Frames read from the device
+    pub captured: u64,
+    /// Frames discarded because the reading channel was full
+    pub dropped: u64,
+    /// Frames the fusion engine has finished with
+    pub processed: u64,
+    /// Frames sent but not yet processed
+    pub queue_depth: u64,
+    /// Capture to end of processing (ms)
+    pub last_latency_ms: f64,
+    pub mean_latency_ms: f64,
+    pub worst_latency_ms: f64,
+}
+
+/// Frame accounting of every camera
+///
+/// Cheap to clone; capture loops count frames in through
+/// [`publish`](Self::publish) and the reading processor counts them out.
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    cameras: Arc<Mutex<HashMap<String, CameraStats>>>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Send a captured frame to the fusion pipeline without waiting
+    ///
+    /// A frame that does not fit in the channel is dropped and counted.
+    /// Returns `false` once the channel is closed.
+    pub fn publish(&self, tx: &mpsc::Sender<SensorReading>, frame: SensorReading) -> bool {
+        let camera = frame.sensor_name.clone();
+        let result = tx.try_send(frame);
+        self.update(&camera, |stats| {
+            stats.captured += 1;
+            match &result {
+                Ok(()) => stats.queue_depth += 1,
+                Err(TrySendError::Full(_)) => stats.dropped += 1,
+                Err(TrySendError::Closed(_)) => {}
+            }
+        });
+        !matches!(result, Err(TrySendError::Closed(_)))
+    }
+    
+    /// Count a frame captured at `captured_at` as processed
+    pub fn processed(&self, camera: &str, captured_at: SystemTime) {
+        let latency_ms = captured_at.elapsed().unwrap_or_default().as_secs_f64() * 1000.0;
+        self.update(camera, |stats| {
+            stats.processed += 1;
+            stats.queue_depth = stats.queue_depth.saturating_sub(1);
+            stats.last_latency_ms = latency_ms;
+            stats.mean_latency_ms += (latency_ms - stats.mean_latency_ms) / stats.processed as f64;
+            stats.worst_latency_ms = stats.worst_latency_ms.max(latency_ms);
+        });
+    }
+    
+    /// Statistics of every camera seen so far, by name
+    pub fn snapshot(&self) -> Vec<CameraStats> {
+        let mut cameras: Vec<CameraStats> = self.cameras.lock().unwrap().values().cloned().collect();
+        cameras.sort_by(|a, b| a.camera.cmp(&b.camera));
+        cameras
+    }
+    
+    fn update(&self, camera: &str, f: impl FnOnce(&mut CameraStats)) {
+        let mut cameras = self.cameras.lock().unwrap();
+        let stats = cameras.entry(camera.to_string()).or_insert_with(|| CameraStats {
+            camera: camera.to_string(),
+            ..Default::default()
+        });
+        f(stats);
+    }
+}
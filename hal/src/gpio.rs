@@ -1,9 +1,23 @@
 //! GPIO interface for GlowBarn HAL
+//!
+//! Pins are driven through a [`GpioBackend`]: the GPIO character device
+//! ([`CdevGpio`]) where the kernel has one, or the legacy sysfs interface
+//! ([`SysfsGpio`]) on older kernels that still export `/sys/class/gpio`.
 
-use crate::{HalError, HardwareDevice, DeviceType};
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Write};
-use std::path::Path;
+use crate::{HalError, HardwareDevice, Sensor, DeviceType};
+use crate::laser::{BeamSignal, StrobeConfig};
+use crate::platform::{self, read_sysfs, sysfs_exists, write_sysfs};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+#[cfg(target_os = "linux")]
+use tokio::io::{unix::AsyncFd, Interest};
+
+/// Pin sampling interval when the interface cannot signal edges
+const EDGE_POLL_INTERVAL: Duration = Duration::from_millis(5);
 
 /// GPIO direction
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -48,7 +62,83 @@ impl From<Level> for bool {
     }
 }
 
+/// Access to a single GPIO line
+pub trait GpioBackend: Send + Sync {
+    /// Line number (global sysfs number or offset on the chip)
+    fn pin(&self) -> u32;
+    
+    /// Interface name, for logs
+    fn backend_name(&self) -> &'static str;
+    
+    /// Set direction
+    fn set_direction(&self, direction: Direction) -> Result<(), HalError>;
+    
+    /// Get current direction
+    fn get_direction(&self) -> Result<Direction, HalError>;
+    
+    /// Set output value
+    fn set_value(&self, level: Level) -> Result<(), HalError>;
+    
+    /// Get input value
+    fn get_value(&self) -> Result<Level, HalError>;
+    
+    /// Set edge trigger mode
+    fn set_edge(&self, edge: Edge) -> Result<(), HalError>;
+    
+    /// Hand the line back to the kernel
+    fn release(&mut self) -> Result<(), HalError>;
+    
+    /// Descriptor that signals the edges chosen with [`set_edge`](Self::set_edge)
+    ///
+    /// `None` when the interface cannot signal edges; waits then poll.
+    #[cfg(target_os = "linux")]
+    fn edge_fd(&self) -> Result<Option<EdgeFd>, HalError> {
+        Ok(None)
+    }
+}
+
+/// Descriptor that becomes ready on GPIO edges
+#[cfg(target_os = "linux")]
+pub enum EdgeFd {
+    /// Character device line; an event record is read per edge
+    Events(File),
+    /// Sysfs `value` file; priority data is flagged on each edge
+    Value(File),
+}
+
+/// Chip used by [`open_line`]
+fn default_chip_lock() -> &'static RwLock<String> {
+    static CHIP: OnceLock<RwLock<String>> = OnceLock::new();
+    CHIP.get_or_init(|| RwLock::new("/dev/gpiochip0".to_string()))
+}
+
+/// Set the GPIO chip that pins opened by number are requested from
+pub fn set_default_chip(chip: &str) {
+    *default_chip_lock().write().unwrap() = chip.to_string();
+}
+
+/// GPIO chip that pins opened by number are requested from
+pub fn default_chip() -> String {
+    default_chip_lock().read().unwrap().clone()
+}
+
+/// Open a pin on the best interface this kernel offers
+///
+/// Uses the character device when the default chip exists and falls back
+/// to sysfs otherwise. With the character device `pin` is the line offset
+/// on the chip; with sysfs it is the global GPIO number.
+pub fn open_line(pin: u32, consumer: &str, direction: Direction) -> Result<Box<dyn GpioBackend>, HalError> {
+    let chip = default_chip();
+    if !platform::SIMULATED && std::path::Path::new(&chip).exists() {
+        return Ok(Box::new(CdevGpio::request(&chip, pin, consumer, direction)?));
+    }
+    Ok(Box::new(SysfsGpio::export(pin)?))
+}
+
 /// Sysfs GPIO controller
+///
+/// `/sys/class/gpio` is deprecated and absent on many kernels since 5.10;
+/// prefer [`CdevGpio`] where available.
 pub struct SysfsGpio {
     pin: u32,
     exported: bool,
@@ -63,15 +153,11 @@ impl SysfsGpio {
         
         // Check if already exported
         let pin_path = format!("{}/gpio{}", Self::GPIO_PATH, pin);
-        if Path::new(&pin_path).exists() {
+        if sysfs_exists(&pin_path) {
             return Ok(Self { pin, exported: true });
         }
         
-        let mut file = OpenOptions::new()
-            .write(true)
-            .open(&export_path)?;
-        
-        file.write_all(pin.to_string().as_bytes())?;
+        write_sysfs(&export_path, &pin.to_string())?;
         
         // Wait for sysfs to create the directory
         std::thread::sleep(std::time::Duration::from_millis(50));
@@ -86,11 +172,7 @@ impl SysfsGpio {
         }
         
         let unexport_path = format!("{}/unexport", Self::GPIO_PATH);
-        let mut file = OpenOptions::new()
-            .write(true)
-            .open(&unexport_path)?;
-        
-        file.write_all(self.pin.to_string().as_bytes())?;
+        write_sysfs(&unexport_path, &self.pin.to_string())?;
         self.exported = false;
         Ok(())
     }
@@ -98,27 +180,21 @@ impl SysfsGpio {
     /// Set direction
     pub fn set_direction(&self, direction: Direction) -> Result<(), HalError> {
         let path = format!("{}/gpio{}/direction", Self::GPIO_PATH, self.pin);
-        let mut file = OpenOptions::new()
-            .write(true)
-            .open(&path)?;
         
         let dir_str = match direction {
             Direction::Input => "in",
             Direction::Output => "out",
         };
         
-        file.write_all(dir_str.as_bytes())?;
-        Ok(())
+        write_sysfs(&path, dir_str)
     }
     
     /// Get current direction
     pub fn get_direction(&self) -> Result<Direction, HalError> {
         let path = format!("{}/gpio{}/direction", Self::GPIO_PATH, self.pin);
-        let mut file = File::open(&path)?;
-        let mut buf = String::new();
-        file.read_to_string(&mut buf)?;
+        let buf = read_sysfs(&path, "in")?;
         
-        match buf.trim() {
+        match buf.as_str() {
             "in" => Ok(Direction::Input),
             "out" => Ok(Direction::Output),
             _ => Err(HalError::InvalidConfig("Unknown direction".to_string())),
@@ -128,22 +204,15 @@ impl SysfsGpio {
     /// Set output value
     pub fn set_value(&self, level: Level) -> Result<(), HalError> {
         let path = format!("{}/gpio{}/value", Self::GPIO_PATH, self.pin);
-        let mut file = OpenOptions::new()
-            .write(true)
-            .open(&path)?;
-        
-        file.write_all((level as u8).to_string().as_bytes())?;
-        Ok(())
+        write_sysfs(&path, &(level as u8).to_string())
     }
     
     /// Get input value
     pub fn get_value(&self) -> Result<Level, HalError> {
         let path = format!("{}/gpio{}/value", Self::GPIO_PATH, self.pin);
-        let mut file = File::open(&path)?;
-        let mut buf = String::new();
-        file.read_to_string(&mut buf)?;
+        let buf = read_sysfs(&path, "0")?;
         
-        match buf.trim() {
+        match buf.as_str() {
             "0" => Ok(Level::Low),
             "1" => Ok(Level::High),
             _ => Err(HalError::InvalidConfig("Invalid GPIO value".to_string())),
@@ -153,9 +222,6 @@ impl SysfsGpio {
     /// Set edge trigger mode
     pub fn set_edge(&self, edge: Edge) -> Result<(), HalError> {
         let path = format!("{}/gpio{}/edge", Self::GPIO_PATH, self.pin);
-        let mut file = OpenOptions::new()
-            .write(true)
-            .open(&path)?;
         
         let edge_str = match edge {
             Edge::None => "none",
@@ -164,8 +230,7 @@ impl SysfsGpio {
             Edge::Both => "both",
         };
         
-        file.write_all(edge_str.as_bytes())?;
-        Ok(())
+        write_sysfs(&path, edge_str)
     }
     
     /// Toggle output
@@ -177,24 +242,417 @@ impl SysfsGpio {
     }
 }
 
+impl GpioBackend for SysfsGpio {
+    fn pin(&self) -> u32 {
+        self.pin
+    }
+    
+    fn backend_name(&self) -> &'static str {
+        "sysfs"
+    }
+    
+    fn set_direction(&self, direction: Direction) -> Result<(), HalError> {
+        SysfsGpio::set_direction(self, direction)
+    }
+    
+    fn get_direction(&self) -> Result<Direction, HalError> {
+        SysfsGpio::get_direction(self)
+    }
+    
+    fn set_value(&self, level: Level) -> Result<(), HalError> {
+        SysfsGpio::set_value(self, level)
+    }
+    
+    fn get_value(&self) -> Result<Level, HalError> {
+        SysfsGpio::get_value(self)
+    }
+    
+    fn set_edge(&self, edge: Edge) -> Result<(), HalError> {
+        SysfsGpio::set_edge(self, edge)
+    }
+    
+    fn release(&mut self) -> Result<(), HalError> {
+        self.unexport()
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn edge_fd(&self) -> Result<Option<EdgeFd>, HalError> {
+        let path = format!("{}/gpio{}/value", Self::GPIO_PATH, self.pin);
+        Ok(Some(EdgeFd::Value(File::open(path)?)))
+    }
+}
+
 impl Drop for SysfsGpio {
     fn drop(&mut self) {
         let _ = self.unexport();
     }
 }
 
+/// GPIO line requested from a `/dev/gpiochipN` character device (uAPI v2)
+///
+/// The line stays claimed, and shows up under the consumer label in
+/// `gpioinfo`, until it is released or dropped. On the stub backend reads
+/// return low and writes are discarded.
+pub struct CdevGpio {
+    chip: String,
+    line: u32,
+    consumer: String,
+    flags: AtomicU64,
+    fd: Option<File>,
+}
+
+impl CdevGpio {
+    /// Request `line` on `chip` with the given direction
+    pub fn request(chip: &str, line: u32, consumer: &str, direction: Direction) -> Result<Self, HalError> {
+        let flags = uapi::direction_flags(direction);
+        let handle = platform::DeviceHandle::open(chip)?;
+        
+        #[cfg(target_os = "linux")]
+        let fd = match handle.raw_fd() {
+            Some(chip_fd) => Some(uapi::request_line(chip_fd, line, consumer, flags)
+                .map_err(|e| HalError::CommunicationError(format!("{} line {}: {}", chip, line, e)))?),
+            None => None,
+        };
+        #[cfg(not(target_os = "linux"))]
+        let fd = {
+            let _ = &handle;
+            None
+        };
+        
+        Ok(Self {
+            chip: chip.to_string(),
+            line,
+            consumer: consumer.to_string(),
+            flags: AtomicU64::new(flags),
+            fd,
+        })
+    }
+    
+    /// Chip the line belongs to
+    pub fn chip(&self) -> &str {
+        &self.chip
+    }
+    
+    /// Consumer label the line was requested under
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+    
+    /// Set bias resistors (inputs only)
+    pub fn set_pull(&self, pull: Pull) -> Result<(), HalError> {
+        let flags = self.flags.load(Ordering::Relaxed) & !uapi::BIAS_MASK;
+        self.reconfigure(flags | uapi::pull_flags(pull), None)
+    }
+    
+    fn reconfigure(&self, flags: u64, output: Option<Level>) -> Result<(), HalError> {
+        #[cfg(target_os = "linux")]
+        if let Some(ref fd) = self.fd {
+            use std::os::fd::AsRawFd;
+            uapi::set_config(fd.as_raw_fd(), flags, output.map(bool::from))
+                .map_err(|e| self.error("configure", e))?;
+        }
+        let _ = output;
+        self.flags.store(flags, Ordering::Relaxed);
+        Ok(())
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn error(&self, what: &str, err: std::io::Error) -> HalError {
+        HalError::CommunicationError(format!("{} line {} {}: {}", self.chip, self.line, what, err))
+    }
+}
+
+impl GpioBackend for CdevGpio {
+    fn pin(&self) -> u32 {
+        self.line
+    }
+    
+    fn backend_name(&self) -> &'static str {
+        "cdev"
+    }
+    
+    fn set_direction(&self, direction: Direction) -> Result<(), HalError> {
+        let flags = self.flags.load(Ordering::Relaxed);
+        let mut new = (flags & !uapi::DIRECTION_MASK) | uapi::direction_flags(direction);
+        if direction == Direction::Output {
+            // Edges and bias only apply to inputs
+            new &= !(uapi::EDGE_MASK | uapi::BIAS_MASK);
+        }
+        if new == flags {
+            return Ok(());
+        }
+        self.reconfigure(new, None)
+    }
+    
+    fn get_direction(&self) -> Result<Direction, HalError> {
+        if self.flags.load(Ordering::Relaxed) & uapi::OUTPUT != 0 {
+            Ok(Direction::Output)
+        } else {
+            Ok(Direction::Input)
+        }
+    }
+    
+    fn set_value(&self, level: Level) -> Result<(), HalError> {
+        #[cfg(target_os = "linux")]
+        if let Some(ref fd) = self.fd {
+            use std::os::fd::AsRawFd;
+            uapi::set_value(fd.as_raw_fd(), level.into()).map_err(|e| self.error("set value", e))?;
+        }
+        let _ = level;
+        Ok(())
+    }
+    
+    fn get_value(&self) -> Result<Level, HalError> {
+        #[cfg(target_os = "linux")]
+        if let Some(ref fd) = self.fd {
+            use std::os::fd::AsRawFd;
+            return uapi::get_value(fd.as_raw_fd())
+                .map(Level::from)
+                .map_err(|e| self.error("get value", e));
+        }
+        Ok(Level::Low)
+    }
+    
+    fn set_edge(&self, edge: Edge) -> Result<(), HalError> {
+        let flags = self.flags.load(Ordering::Relaxed);
+        if edge != Edge::None && flags & uapi::OUTPUT != 0 {
+            return Err(HalError::InvalidConfig(format!("line {} is an output, edges need an input", self.line)));
+        }
+        self.reconfigure((flags & !uapi::EDGE_MASK) | uapi::edge_flags(edge), None)
+    }
+    
+    fn release(&mut self) -> Result<(), HalError> {
+        // Closing the line fd frees the line
+        self.fd = None;
+        Ok(())
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn edge_fd(&self) -> Result<Option<EdgeFd>, HalError> {
+        use std::os::fd::AsRawFd;
+        let Some(ref fd) = self.fd else {
+            return Ok(None);
+        };
+        let events = fd.try_clone()?;
+        uapi::set_nonblocking(events.as_raw_fd())?;
+        Ok(Some(EdgeFd::Events(events)))
+    }
+}
+
+/// GPIO character device uAPI v2 (linux/gpio.h)
+mod uapi {
+    use super::{Direction, Edge, Pull};
+    
+    pub const INPUT: u64 = 1 << 2;
+    pub const OUTPUT: u64 = 1 << 3;
+    const EDGE_RISING: u64 = 1 << 4;
+    const EDGE_FALLING: u64 = 1 << 5;
+    const BIAS_PULL_UP: u64 = 1 << 8;
+    const BIAS_PULL_DOWN: u64 = 1 << 9;
+    const BIAS_DISABLED: u64 = 1 << 10;
+    
+    pub const DIRECTION_MASK: u64 = INPUT | OUTPUT;
+    pub const EDGE_MASK: u64 = EDGE_RISING | EDGE_FALLING;
+    pub const BIAS_MASK: u64 = BIAS_PULL_UP | BIAS_PULL_DOWN | BIAS_DISABLED;
+    
+    pub fn direction_flags(direction: Direction) -> u64 {
+        match direction {
+            Direction::Input => INPUT,
+            Direction::Output => OUTPUT,
+        }
+    }
+    
+    pub fn edge_flags(edge: Edge) -> u64 {
+        match edge {
+            Edge::None => 0,
+            Edge::Rising => EDGE_RISING,
+            Edge::Falling => EDGE_FALLING,
+            Edge::Both => EDGE_RISING | EDGE_FALLING,
+        }
+    }
+    
+    pub fn pull_flags(pull: Pull) -> u64 {
+        match pull {
+            Pull::None => BIAS_DISABLED,
+            Pull::Up => BIAS_PULL_UP,
+            Pull::Down => BIAS_PULL_DOWN,
+        }
+    }
+    
+    #[cfg(target_os = "linux")]
+    pub use ioctl::{get_value, read_edge, request_line, set_config, set_nonblocking, set_value};
+    
+    #[cfg(target_os = "linux")]
+    mod ioctl {
+        use std::fs::File;
+        use std::io;
+        use std::mem::size_of;
+        use std::os::fd::{FromRawFd, OwnedFd};
+        
+        const LINES_MAX: usize = 64;
+        const NAME_SIZE: usize = 32;
+        const NUM_ATTRS_MAX: usize = 10;
+        const ATTR_ID_OUTPUT_VALUES: u32 = 2;
+        const EVENT_RISING_EDGE: u32 = 1;
+        /// Size of `struct gpio_v2_line_event`
+        const EVENT_SIZE: usize = 48;
+        
+        const fn iowr<T>(nr: u32) -> libc::Ioctl {
+            ((3 << 30) | ((size_of::<T>() as u32) << 16) | (0xB4 << 8) | nr) as libc::Ioctl
+        }
+        
+        const GET_LINE: libc::Ioctl = iowr::<LineRequest>(0x07);
+        const SET_CONFIG: libc::Ioctl = iowr::<LineConfig>(0x0D);
+        const GET_VALUES: libc::Ioctl = iowr::<LineValues>(0x0E);
+        const SET_VALUES: libc::Ioctl = iowr::<LineValues>(0x0F);
+        
+        #[repr(C)]
+        #[derive(Clone, Copy, Default)]
+        struct LineAttribute {
+            id: u32,
+            padding: u32,
+            // Union of flags, output values and debounce period
+            value: u64,
+        }
+        
+        #[repr(C)]
+        #[derive(Clone, Copy, Default)]
+        struct ConfigAttribute {
+            attr: LineAttribute,
+            mask: u64,
+        }
+        
+        #[repr(C)]
+        #[derive(Clone, Copy, Default)]
+        struct LineConfig {
+            flags: u64,
+            num_attrs: u32,
+            padding: [u32; 5],
+            attrs: [ConfigAttribute; NUM_ATTRS_MAX],
+        }
+        
+        #[repr(C)]
+        struct LineRequest {
+            offsets: [u32; LINES_MAX],
+            consumer: [u8; NAME_SIZE],
+            config: LineConfig,
+            num_lines: u32,
+            event_buffer_size: u32,
+            padding: [u32; 5],
+            fd: i32,
+        }
+        
+        #[repr(C)]
+        #[derive(Default)]
+        struct LineValues {
+            bits: u64,
+            mask: u64,
+        }
+        
+        fn ioctl<T>(fd: i32, request: libc::Ioctl, arg: &mut T) -> io::Result<()> {
+            loop {
+                if unsafe { libc::ioctl(fd, request, arg as *mut T) } >= 0 {
+                    return Ok(());
+                }
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+        }
+        
+        fn line_config(flags: u64, output: Option<bool>) -> LineConfig {
+            let mut config = LineConfig { flags, ..Default::default() };
+            if let Some(high) = output {
+                config.num_attrs = 1;
+                config.attrs[0] = ConfigAttribute {
+                    attr: LineAttribute { id: ATTR_ID_OUTPUT_VALUES, padding: 0, value: high as u64 },
+                    mask: 1,
+                };
+            }
+            config
+        }
+        
+        /// Claim one line from the chip and return the line's fd
+        pub fn request_line(chip_fd: i32, line: u32, consumer: &str, flags: u64) -> io::Result<File> {
+            let mut request = LineRequest {
+                offsets: [0; LINES_MAX],
+                consumer: [0; NAME_SIZE],
+                config: line_config(flags, None),
+                num_lines: 1,
+                event_buffer_size: 0,
+                padding: [0; 5],
+                fd: -1,
+            };
+            request.offsets[0] = line;
+            // Keep the trailing NUL
+            let label = consumer.as_bytes();
+            let len = label.len().min(NAME_SIZE - 1);
+            request.consumer[..len].copy_from_slice(&label[..len]);
+            
+            ioctl(chip_fd, GET_LINE, &mut request)?;
+            // SAFETY: the kernel handed us a new descriptor we now own
+            Ok(File::from(unsafe { OwnedFd::from_raw_fd(request.fd) }))
+        }
+        
+        pub fn set_config(fd: i32, flags: u64, output: Option<bool>) -> io::Result<()> {
+            ioctl(fd, SET_CONFIG, &mut line_config(flags, output))
+        }
+        
+        pub fn get_value(fd: i32) -> io::Result<bool> {
+            let mut values = LineValues { bits: 0, mask: 1 };
+            ioctl(fd, GET_VALUES, &mut values)?;
+            Ok(values.bits & 1 != 0)
+        }
+        
+        pub fn set_value(fd: i32, high: bool) -> io::Result<()> {
+            ioctl(fd, SET_VALUES, &mut LineValues { bits: high as u64, mask: 1 })
+        }
+        
+        /// Read one edge event from a line fd; true for a rising edge
+        pub fn read_edge(fd: i32) -> io::Result<bool> {
+            let mut event = [0u8; EVENT_SIZE];
+            let n = unsafe { libc::read(fd, event.as_mut_ptr() as *mut libc::c_void, EVENT_SIZE) };
+            if n < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if n as usize != EVENT_SIZE {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "short GPIO event"));
+            }
+            // id follows the u64 timestamp
+            let id = u32::from_ne_bytes([event[8], event[9], event[10], event[11]]);
+            Ok(id == EVENT_RISING_EDGE)
+        }
+        
+        pub fn set_nonblocking(fd: i32) -> io::Result<()> {
+            unsafe {
+                let flags = libc::fcntl(fd, libc::F_GETFL);
+                if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
 /// GPIO Pin wrapper with higher-level interface
 pub struct GpioPin {
-    gpio: SysfsGpio,
+    gpio: Box<dyn GpioBackend>,
     name: String,
     direction: Direction,
 }
 
 impl GpioPin {
-    /// Create new GPIO pin
+    /// Create new GPIO pin on the default interface (see [`open_line`])
     pub fn new(name: &str, pin: u32, direction: Direction) -> Result<Self, HalError> {
-        let gpio = SysfsGpio::export(pin)?;
+        Self::with_backend(name, open_line(pin, name, direction)?, direction)
+    }
+    
+    /// Create a GPIO pin on an already opened line
+    pub fn with_backend(name: &str, gpio: Box<dyn GpioBackend>, direction: Direction) -> Result<Self, HalError> {
         gpio.set_direction(direction)?;
+        tracing::debug!("GPIO {} on {} line {}", name, gpio.backend_name(), gpio.pin());
         
         Ok(Self {
             gpio,
@@ -203,6 +661,11 @@ impl GpioPin {
         })
     }
     
+    /// Interface the pin is driven through
+    pub fn backend(&self) -> &dyn GpioBackend {
+        self.gpio.as_ref()
+    }
+    
     /// Read pin value
     pub fn read(&self) -> Result<bool, HalError> {
         Ok(self.gpio.get_value()? == Level::High)
@@ -220,6 +683,119 @@ impl GpioPin {
         self.write(false)?;
         Ok(())
     }
+    
+    /// Edges of an input pin, woken by the kernel where the interface allows
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn edge_events(&self, edge: Edge) -> Result<EdgeEvents<'_>, HalError> {
+        if edge == Edge::None {
+            return Err(HalError::InvalidConfig(format!("{}: no edge to wait for", self.name)));
+        }
+        self.gpio.set_edge(edge)?;
+        
+        #[cfg(target_os = "linux")]
+        let source = match self.gpio.edge_fd()? {
+            Some(EdgeFd::Events(fd)) => EdgeSource::Events(AsyncFd::with_interest(fd, Interest::READABLE)?),
+            Some(EdgeFd::Value(fd)) => EdgeSource::Value {
+                initial: Some(self.gpio.get_value()?),
+                fd: AsyncFd::with_interest(fd, Interest::PRIORITY | Interest::ERROR)?,
+            },
+            None => EdgeSource::Poll(self.gpio.get_value()?),
+        };
+        #[cfg(not(target_os = "linux"))]
+        let source = EdgeSource::Poll(self.gpio.get_value()?);
+        
+        Ok(EdgeEvents { pin: self, edge, source })
+    }
+    
+    /// Wait for one `edge` and return the level after it, or `None` on timeout
+    pub async fn wait_for_edge(&self, edge: Edge, timeout: Option<Duration>) -> Result<Option<Level>, HalError> {
+        self.edge_events(edge)?.next(timeout).await
+    }
+}
+
+enum EdgeSource {
+    #[cfg(target_os = "linux")]
+    Events(AsyncFd<File>),
+    /// Sysfs value file; `initial` is the level before the first wake-up,
+    /// which may be the spurious one every newly polled sysfs file gives
+    #[cfg(target_os = "linux")]
+    Value { fd: AsyncFd<File>, initial: Option<Level> },
+    /// Last level sampled
+    Poll(Level),
+}
+
+/// Edges of a pin, from [`GpioPin::edge_events`]
+///
+/// Edges are queued by the kernel between calls to [`next`](Self::next)
+/// on the character device; the sysfs and polled fallbacks only see the
+/// level at wake-up.
+pub struct EdgeEvents<'a> {
+    pin: &'a GpioPin,
+    edge: Edge,
+    source: EdgeSource,
+}
+
+impl EdgeEvents<'_> {
+    /// Wait for the next edge and return the level after it, or `None` on timeout
+    pub async fn next(&mut self, timeout: Option<Duration>) -> Result<Option<Level>, HalError> {
+        match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, self.wait()).await {
+                Ok(level) => level.map(Some),
+                Err(_) => Ok(None),
+            },
+            None => self.wait().await.map(Some),
+        }
+    }
+    
+    fn wanted(&self, level: Level) -> bool {
+        match self.edge {
+            Edge::Rising => level == Level::High,
+            Edge::Falling => level == Level::Low,
+            Edge::Both => true,
+            Edge::None => false,
+        }
+    }
+    
+    async fn wait(&mut self) -> Result<Level, HalError> {
+        loop {
+            match &mut self.source {
+                #[cfg(target_os = "linux")]
+                EdgeSource::Events(fd) => {
+                    use std::os::fd::AsRawFd;
+                    let mut guard = fd.readable().await?;
+                    if let Ok(rising) = guard.try_io(|fd| uapi::read_edge(fd.as_raw_fd())) {
+                        return Ok(Level::from(rising?));
+                    }
+                }
+                #[cfg(target_os = "linux")]
+                EdgeSource::Value { fd, initial } => {
+                    use std::os::unix::fs::FileExt;
+                    let mut guard = fd.ready(Interest::PRIORITY | Interest::ERROR).await?;
+                    guard.clear_ready();
+                    let mut buf = [0u8; 2];
+                    fd.get_ref().read_at(&mut buf, 0)?;
+                    let level = Level::from(buf[0] == b'1');
+                    
+                    // Only the configured edges wake us after the first time
+                    let real = initial.take().is_none_or(|before| before != level);
+                    if real && self.wanted(level) {
+                        return Ok(level);
+                    }
+                }
+                EdgeSource::Poll(last) => {
+                    tokio::time::sleep(EDGE_POLL_INTERVAL).await;
+                    let level = self.pin.gpio.get_value()?;
+                    if level != *last {
+                        *last = level;
+                        if self.wanted(level) {
+                            return Ok(level);
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl HardwareDevice for GpioPin {
@@ -241,15 +817,25 @@ impl HardwareDevice for GpioPin {
     }
     
     fn close(&mut self) -> Result<(), HalError> {
-        self.gpio.unexport()
+        self.gpio.release()
     }
 }
 
+/// Motion seen by a PIR sensor
+#[derive(Debug, Clone)]
+pub struct MotionEvent {
+    pub sensor_name: String,
+    /// Motion events counted so far, this one included
+    pub count: u64,
+    pub timestamp: SystemTime,
+}
+
 /// PIR Motion sensor
 pub struct PIRSensor {
-    gpio: GpioPin,
+    gpio: Arc<GpioPin>,
     last_state: bool,
-    motion_count: u64,
+    motion_count: Arc<AtomicU64>,
+    watcher: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl PIRSensor {
@@ -257,9 +843,10 @@ impl PIRSensor {
         let gpio = GpioPin::new(name, pin, Direction::Input)?;
         
         Ok(Self {
-            gpio,
+            gpio: Arc::new(gpio),
             last_state: false,
-            motion_count: 0,
+            motion_count: Arc::new(AtomicU64::new(0)),
+            watcher: None,
         })
     }
     
@@ -270,21 +857,399 @@ impl PIRSensor {
         self.last_state = current;
         
         if motion {
-            self.motion_count += 1;
-            tracing::info!("Motion detected! Total count: {}", self.motion_count);
+            let count = self.motion_count.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::info!("Motion detected! Total count: {}", count);
         }
         
         Ok(motion)
     }
     
+    /// Motion events as they happen, woken by the pin's rising edges
+    ///
+    /// Spawns a task on the current tokio runtime that runs until the
+    /// receiver or the sensor is dropped. Replaces any earlier stream.
+    pub fn motion_events(&mut self) -> Result<mpsc::Receiver<MotionEvent>, HalError> {
+        // Fail here rather than in the task if the pin can't do edges
+        self.gpio.backend().set_edge(Edge::Rising)?;
+        
+        let (tx, rx) = mpsc::channel(64);
+        let gpio = self.gpio.clone();
+        let motion_count = self.motion_count.clone();
+        
+        let watcher = tokio::spawn(async move {
+            let mut edges = match gpio.edge_events(Edge::Rising) {
+                Ok(edges) => edges,
+                Err(e) => {
+                    tracing::warn!("{}: edge detection unavailable: {}", gpio.name(), e);
+                    return;
+                }
+            };
+            loop {
+                let edge = tokio::select! {
+                    _ = tx.closed() => break,
+                    edge = edges.next(None) => edge,
+                };
+                if let Err(e) = edge {
+                    tracing::warn!("{}: edge wait failed: {}", gpio.name(), e);
+                    break;
+                }
+                
+                let count = motion_count.fetch_add(1, Ordering::Relaxed) + 1;
+                tracing::info!("Motion detected! Total count: {}", count);
+                let event = MotionEvent {
+                    sensor_name: gpio.name().to_string(),
+                    count,
+                    timestamp: SystemTime::now(),
+                };
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        
+        if let Some(old) = self.watcher.replace(watcher) {
+            old.abort();
+        }
+        Ok(rx)
+    }
+    
     /// Get total motion events
     pub fn motion_count(&self) -> u64 {
-        self.motion_count
+        self.motion_count.load(Ordering::Relaxed)
     }
     
     /// Reset counter
     pub fn reset_count(&mut self) {
-        self.motion_count = 0;
+        self.motion_count.store(0, Ordering::Relaxed);
+    }
+}
+
+impl Drop for PIRSensor {
+    fn drop(&mut self) {
+        if let Some(watcher) = self.watcher.take() {
+            watcher.abort();
+        }
+    }
+}
+
+/// Reed switch wiring (pin pulled up, switch to ground)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContactMode {
+    /// Switch closes when the magnet is present (door closed)
+    NormallyOpen,
+    /// Switch opens when the magnet is present (door closed)
+    NormallyClosed,
+}
+
+/// Debounced door/window state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactState {
+    Closed,
+    Open,
+}
+
+/// Door/window state change
+#[derive(Debug, Clone)]
+pub struct ContactEvent {
+    pub sensor_name: String,
+    pub state: ContactState,
+    pub timestamp: SystemTime,
+}
+
+struct ContactDebounce {
+    stable: ContactState,
+    pending: Option<(ContactState, Instant)>,
+    changes: u64,
+}
+
+/// Reed switch contact sensor for doors, windows and cabinets
+///
+/// Reads 1.0 when open and 0.0 when closed (unit "open").
+pub struct ContactSensor {
+    gpio: GpioPin,
+    mode: ContactMode,
+    debounce: Duration,
+    state: Mutex<ContactDebounce>,
+}
+
+impl ContactSensor {
+    pub fn new(name: &str, pin: u32, mode: ContactMode) -> Result<Self, HalError> {
+        let gpio = GpioPin::new(name, pin, Direction::Input)?;
+        
+        Ok(Self {
+            gpio,
+            mode,
+            debounce: Duration::from_millis(50),
+            state: Mutex::new(ContactDebounce {
+                stable: ContactState::Closed,
+                pending: None,
+                changes: 0,
+            }),
+        })
+    }
+    
+    /// Time the switch must hold a new state before it is accepted
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+    
+    /// Undebounced state straight from the pin
+    pub fn read_raw_state(&self) -> Result<ContactState, HalError> {
+        let high = self.gpio.read()?;
+        // With a pull-up, a closed switch reads low
+        let switch_closed = !high;
+        let open = match self.mode {
+            ContactMode::NormallyOpen => !switch_closed,
+            ContactMode::NormallyClosed => switch_closed,
+        };
+        Ok(if open { ContactState::Open } else { ContactState::Closed })
+    }
+    
+    /// Sample the pin and return an event if the debounced state changed
+    pub fn poll(&self) -> Result<Option<ContactEvent>, HalError> {
+        let raw = self.read_raw_state()?;
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        
+        if raw == state.stable {
+            state.pending = None;
+            return Ok(None);
+        }
+        
+        match state.pending {
+            Some((pending, since)) if pending == raw => {
+                if now.duration_since(since) < self.debounce {
+                    return Ok(None);
+                }
+            }
+            _ => {
+                state.pending = Some((raw, now));
+                if !self.debounce.is_zero() {
+                    return Ok(None);
+                }
+            }
+        }
+        
+        state.stable = raw;
+        state.pending = None;
+        state.changes += 1;
+        tracing::info!("{} {:?}", self.gpio.name(), raw);
+        
+        Ok(Some(ContactEvent {
+            sensor_name: self.gpio.name().to_string(),
+            state: raw,
+            timestamp: SystemTime::now(),
+        }))
+    }
+    
+    /// Current debounced state
+    pub fn state(&self) -> ContactState {
+        self.state.lock().unwrap().stable
+    }
+    
+    /// Number of state changes seen
+    pub fn change_count(&self) -> u64 {
+        self.state.lock().unwrap().changes
+    }
+}
+
+impl HardwareDevice for ContactSensor {
+    fn name(&self) -> &str {
+        self.gpio.name()
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::GPIO
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.gpio.init()?;
+        // Start from the current position so boot doesn't report a change
+        let raw = self.read_raw_state()?;
+        let mut state = self.state.lock().unwrap();
+        state.stable = raw;
+        state.pending = None;
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.gpio.is_ready()
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.gpio.close()
+    }
+}
+
+impl Sensor for ContactSensor {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        Ok(vec![self.gpio.read()? as u8])
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        self.poll()?;
+        Ok(match self.state() {
+            ContactState::Open => 1.0,
+            ContactState::Closed => 0.0,
+        })
+    }
+    
+    fn unit(&self) -> &str {
+        "open"
+    }
+    
+    fn calibrate(&mut self, _offset: f64) -> Result<(), HalError> {
+        Ok(())
+    }
+    
+    fn discrete(&self) -> bool {
+        true
+    }
+}
+
+/// SW-420 vibration sensor (knocks, footsteps)
+///
+/// A background thread samples the pin and counts pulses; the sensor value
+/// is the pulse rate over a sliding window in pulses per second.
+pub struct VibrationSensor {
+    name: String,
+    gpio: Arc<GpioPin>,
+    window: Duration,
+    sample_interval: Duration,
+    pulses: Arc<Mutex<VecDeque<Instant>>>,
+    running: Arc<AtomicBool>,
+    sampler: Option<std::thread::JoinHandle<()>>,
+}
+
+impl VibrationSensor {
+    pub fn new(name: &str, pin: u32) -> Result<Self, HalError> {
+        let gpio = GpioPin::new(name, pin, Direction::Input)?;
+        
+        Ok(Self {
+            name: name.to_string(),
+            gpio: Arc::new(gpio),
+            window: Duration::from_secs(1),
+            sample_interval: Duration::from_millis(1),
+            pulses: Arc::new(Mutex::new(VecDeque::new())),
+            running: Arc::new(AtomicBool::new(false)),
+            sampler: None,
+        })
+    }
+    
+    /// Length of the counting window
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+    
+    /// Pin sampling interval (shorter catches shorter pulses)
+    pub fn with_sample_interval(mut self, interval: Duration) -> Self {
+        self.sample_interval = interval;
+        self
+    }
+    
+    /// Pulses counted in the current window
+    pub fn count(&self) -> usize {
+        let mut pulses = self.pulses.lock().unwrap();
+        let now = Instant::now();
+        while pulses.front().is_some_and(|t| now.duration_since(*t) > self.window) {
+            pulses.pop_front();
+        }
+        pulses.len()
+    }
+    
+    /// Pulse rate over the window (pulses per second)
+    pub fn rate(&self) -> f64 {
+        self.count() as f64 / self.window.as_secs_f64()
+    }
+    
+    fn stop_sampler(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.sampler.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl HardwareDevice for VibrationSensor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::GPIO
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        if self.sampler.is_some() {
+            return Ok(());
+        }
+        
+        let gpio = self.gpio.clone();
+        let pulses = self.pulses.clone();
+        let running = self.running.clone();
+        let interval = self.sample_interval;
+        let window = self.window;
+        running.store(true, Ordering::Relaxed);
+        
+        self.sampler = Some(std::thread::spawn(move || {
+            let mut last = false;
+            while running.load(Ordering::Relaxed) {
+                // Count rising edges
+                let current = gpio.read().unwrap_or(false);
+                if current && !last {
+                    let now = Instant::now();
+                    let mut pulses = pulses.lock().unwrap();
+                    pulses.push_back(now);
+                    while pulses.front().is_some_and(|t| now.duration_since(*t) > window) {
+                        pulses.pop_front();
+                    }
+                }
+                last = current;
+                std::thread::sleep(interval);
+            }
+        }));
+        
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.sampler.is_some()
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.stop_sampler();
+        Ok(())
+    }
+}
+
+impl Drop for VibrationSensor {
+    fn drop(&mut self) {
+        self.stop_sampler();
+    }
+}
+
+impl Sensor for VibrationSensor {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        Ok((self.count() as u32).to_le_bytes().to_vec())
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        Ok(self.rate())
+    }
+    
+    fn unit(&self) -> &str {
+        "pulses/s"
+    }
+    
+    fn calibrate(&mut self, _offset: f64) -> Result<(), HalError> {
+        Ok(())
+    }
+    
+    /// Idle at 0 pulses/s until something shakes it
+    fn discrete(&self) -> bool {
+        true
     }
 }
 
@@ -292,6 +1257,10 @@ impl PIRSensor {
 pub struct LaserGrid {
     transmitters: Vec<GpioPin>,
     receivers: Vec<GpioPin>,
+    strobe: Option<StrobeConfig>,
+    /// Last state seen of each beam, kept while its receiver is blinded
+    held: Mutex<Vec<bool>>,
+    ambient_rejections: AtomicU64,
 }
 
 impl LaserGrid {
@@ -311,7 +1280,24 @@ impl LaserGrid {
             receivers.push(GpioPin::new(&format!("laser_rx_{}", i), pin, Direction::Input)?);
         }
         
-        Ok(Self { transmitters, receivers })
+        Ok(Self {
+            held: Mutex::new(vec![false; receivers.len()]),
+            transmitters,
+            receivers,
+            strobe: None,
+            ambient_rejections: AtomicU64::new(0),
+        })
+    }
+    
+    /// Pulse the lasers on each check and sample receivers in both phases
+    pub fn with_strobe(mut self, strobe: StrobeConfig) -> Self {
+        self.strobe = Some(strobe);
+        self
+    }
+    
+    /// Beam readings discarded because ambient light swamped a receiver
+    pub fn ambient_rejections(&self) -> u64 {
+        self.ambient_rejections.load(Ordering::Relaxed)
     }
     
     /// Enable all lasers
@@ -331,23 +1317,76 @@ impl LaserGrid {
     }
     
     /// Check if any beams are broken
+    ///
+    /// When strobing, a blinded receiver keeps its beam's last state.
     pub fn check_beams(&self) -> Result<Vec<bool>, HalError> {
-        let mut results = Vec::new();
-        for rx in &self.receivers {
-            // Low = beam broken
-            results.push(!rx.read()?);
+        if self.strobe.is_none() {
+            let mut results = Vec::new();
+            for rx in &self.receivers {
+                // Low = beam broken
+                results.push(!rx.read()?);
+            }
+            return Ok(results);
+        }
+        
+        let signals = self.strobe_sample()?;
+        let mut held = self.held.lock().unwrap();
+        for (i, signal) in signals.iter().enumerate() {
+            match signal {
+                BeamSignal::Intact => held[i] = false,
+                BeamSignal::Broken => held[i] = true,
+                BeamSignal::Blinded => {
+                    self.ambient_rejections.fetch_add(1, Ordering::Relaxed);
+                    tracing::trace!("Laser receiver {} blinded by ambient light", i);
+                }
+            }
         }
-        Ok(results)
+        Ok(held.clone())
     }
     
     /// Check if any beam is broken
     pub fn any_broken(&self) -> Result<bool, HalError> {
-        for rx in &self.receivers {
-            if !rx.read()? {
-                return Ok(true);
+        Ok(self.check_beams()?.iter().any(|&broken| broken))
+    }
+    
+    /// Pulse the lasers and classify each receiver by majority over the pulses
+    ///
+    /// Leaves the lasers off. Fails unless the grid was set up
+    /// [`with_strobe`](Self::with_strobe).
+    pub fn strobe_sample(&self) -> Result<Vec<BeamSignal>, HalError> {
+        let strobe = self.strobe
+            .ok_or_else(|| HalError::InvalidConfig("laser grid is not strobed".to_string()))?;
+        let (on, off) = strobe.phases();
+        let cycles = strobe.cycles.max(1);
+        
+        // Receivers are read at the end of each phase, after they settle
+        let mut lit_on = vec![0u32; self.receivers.len()];
+        let mut lit_off = vec![0u32; self.receivers.len()];
+        for _ in 0..cycles {
+            for (phase, duration, lit) in [(true, on, &mut lit_on), (false, off, &mut lit_off)] {
+                for tx in &self.transmitters {
+                    tx.write(phase)?;
+                }
+                std::thread::sleep(duration);
+                for (count, rx) in lit.iter_mut().zip(&self.receivers) {
+                    if rx.read()? {
+                        *count += 1;
+                    }
+                }
             }
         }
-        Ok(false)
+        
+        Ok(lit_on.iter().zip(&lit_off)
+            .map(|(&on, &off)| {
+                if off * 2 > cycles {
+                    BeamSignal::Blinded
+                } else if on * 2 > cycles {
+                    BeamSignal::Intact
+                } else {
+                    BeamSignal::Broken
+                }
+            })
+            .collect())
     }
 }
 
@@ -367,9 +1406,7 @@ impl PwmOutput {
         
         // Export PWM
         let export_path = format!("{}/export", Self::PWM_PATH);
-        if let Ok(mut file) = OpenOptions::new().write(true).open(&export_path) {
-            let _ = file.write_all(pin.to_string().as_bytes());
-        }
+        let _ = write_sysfs(&export_path, &pin.to_string());
         
         std::thread::sleep(std::time::Duration::from_millis(50));
         
@@ -386,9 +1423,7 @@ impl PwmOutput {
     
     fn write_attribute(&self, attr: &str, value: &str) -> Result<(), HalError> {
         let path = format!("{}/pwm{}/{}", Self::PWM_PATH, self.pin, attr);
-        let mut file = OpenOptions::new().write(true).open(&path)?;
-        file.write_all(value.as_bytes())?;
-        Ok(())
+        write_sysfs(&path, value)
     }
     
     /// Set period in nanoseconds
@@ -0,0 +1,181 @@
+//! Device hot-plug
+//!
+//! USB serial meters, webcams and sound cards come and go during an
+//! investigation: a cable gets kicked, a meter is carried to another room.
+//! The watcher listens to kernel uevents on a netlink socket and reports
+//! device nodes appearing and disappearing, so the hardware manager can
+//! re-open manifest devices when they return and drop them when they leave.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Subsystems whose device nodes back manifest devices
+pub const WATCHED_SUBSYSTEMS: &[&str] = &["tty", "video4linux", "sound", "hidraw"];
+
+/// Device node reported by the kernel
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeviceInfo {
+    pub subsystem: String,
+    /// sysfs path, e.g. `/devices/platform/.../ttyUSB0`
+    pub devpath: String,
+    /// Device node, e.g. `/dev/ttyUSB0`
+    pub devnode: PathBuf,
+}
+
+/// Device lifecycle event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum DeviceEvent {
+    /// A device node appeared; `registered` lists the manifest sensors and
+    /// devices opened on it
+    Attached { device: DeviceInfo, registered: Vec<String> },
+    /// A device node went away; `unregistered` lists what was dropped
+    Detached { device: DeviceInfo, unregistered: Vec<String> },
+}
+
+impl DeviceEvent {
+    pub fn device(&self) -> &DeviceInfo {
+        match self {
+            Self::Attached { device, .. } | Self::Detached { device, .. } => device,
+        }
+    }
+    
+    /// Sensors and devices registered or unregistered because of the event
+    pub fn affected(&self) -> &[String] {
+        match self {
+            Self::Attached { registered, .. } => registered,
+            Self::Detached { unregistered, .. } => unregistered,
+        }
+    }
+}
+
+/// Parse a kernel uevent (`add@/devices/...\0ACTION=add\0KEY=value\0...`)
+///
+/// Returns `None` for actions other than add/remove, devices without a node
+/// and subsystems outside [`WATCHED_SUBSYSTEMS`].
+pub fn parse_uevent(message: &[u8]) -> Option<DeviceEvent> {
+    let mut action = None;
+    let mut devpath = None;
+    let mut subsystem = None;
+    let mut devname = None;
+    
+    for field in message.split(|&b| b == 0).skip(1) {
+        let field = std::str::from_utf8(field).ok()?;
+        match field.split_once('=') {
+            Some(("ACTION", value)) => action = Some(value),
+            Some(("DEVPATH", value)) => devpath = Some(value),
+            Some(("SUBSYSTEM", value)) => subsystem = Some(value),
+            Some(("DEVNAME", value)) => devname = Some(value),
+            _ => {}
+        }
+    }
+    
+    let subsystem = subsystem.filter(|s| WATCHED_SUBSYSTEMS.contains(s))?;
+    let devname = devname?;
+    let device = DeviceInfo {
+        subsystem: subsystem.to_string(),
+        devpath: devpath.unwrap_or_default().to_string(),
+        devnode: Path::new("/dev").join(devname),
+    };
+    
+    match action? {
+        "add" => Some(DeviceEvent::Attached { device, registered: Vec::new() }),
+        "remove" => Some(DeviceEvent::Detached { device, unregistered: Vec::new() }),
+        _ => None,
+    }
+}
+
+/// Whether a manifest device path refers to `device`
+///
+/// `/dev` paths match the node directly or through a symlink (e.g.
+/// `/dev/serial/by-id/...`); a symlink that has vanished matches a removed
+/// node. ALSA names (`hw:1,0`, `plughw:1`) match the card's PCM nodes.
+pub fn matches(path: &str, device: &DeviceInfo, attached: bool) -> bool {
+    if let Some((card, pcm)) = alsa_card(path) {
+        return device.subsystem == "sound" && pcm_node(&device.devnode)
+            .is_some_and(|(c, d)| c == card && pcm.is_none_or(|p| p == d));
+    }
+    
+    let path = Path::new(path);
+    if path == device.devnode {
+        return true;
+    }
+    if attached {
+        std::fs::canonicalize(path).is_ok_and(|target| target == device.devnode)
+    } else {
+        path.starts_with("/dev") && !path.exists()
+    }
+}
+
+/// Card and optional device of an ALSA name
+fn alsa_card(name: &str) -> Option<(u32, Option<u32>)> {
+    let (_, spec) = name.split_once(':')?;
+    let mut parts = spec.split(',');
+    let card = parts.next()?.trim().parse().ok()?;
+    let device = parts.next().and_then(|d| d.trim().parse().ok());
+    Some((card, device))
+}
+
+/// Card and device of a PCM node (`/dev/snd/pcmC1D0c`)
+fn pcm_node(node: &Path) -> Option<(u32, u32)> {
+    let name = node.file_name()?.to_str()?.strip_prefix("pcmC")?;
+    let (card, rest) = name.split_once('D')?;
+    let device = rest.trim_end_matches(['c', 'p']);
+    Some((card.parse().ok()?, device.parse().ok()?))
+}
+
+#[cfg(target_os = "linux")]
+pub use netlink::UeventSocket;
+
+#[cfg(target_os = "linux")]
+mod netlink {
+    use crate::HalError;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    
+    /// Kernel uevent multicast group
+    const KERNEL_GROUP: u32 = 1;
+    
+    /// Netlink socket receiving kernel uevents
+    pub struct UeventSocket {
+        fd: OwnedFd,
+        buffer: Vec<u8>,
+    }
+    
+    impl UeventSocket {
+        pub fn open() -> Result<Self, HalError> {
+            // SAFETY: plain socket syscalls on a descriptor we own
+            unsafe {
+                let fd = libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_KOBJECT_UEVENT);
+                if fd < 0 {
+                    return Err(HalError::IoError(std::io::Error::last_os_error()));
+                }
+                let fd = OwnedFd::from_raw_fd(fd);
+                
+                let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+                addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+                addr.nl_groups = KERNEL_GROUP;
+                let bound = libc::bind(
+                    fd.as_raw_fd(),
+                    &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                );
+                if bound < 0 {
+                    return Err(HalError::IoError(std::io::Error::last_os_error()));
+                }
+                
+                Ok(Self { fd, buffer: vec![0; 8192] })
+            }
+        }
+        
+        /// Block until the next uevent and return it raw
+        pub fn recv(&mut self) -> Result<&[u8], HalError> {
+            // SAFETY: the buffer outlives the call and its length is passed
+            let len = unsafe {
+                libc::recv(self.fd.as_raw_fd(), self.buffer.as_mut_ptr() as *mut libc::c_void, self.buffer.len(), 0)
+            };
+            if len < 0 {
+                return Err(HalError::IoError(std::io::Error::last_os_error()));
+            }
+            Ok(&self.buffer[..len as usize])
+        }
+    }
+}
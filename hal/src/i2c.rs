@@ -1,26 +1,33 @@
 //! I2C interface for GlowBarn HAL
+//!
+//! Sensors on the same bus share one [`AsyncI2CBus`] handle, which holds the
+//! bus for the whole of each transaction so that a magnetometer and a
+//! BME280 polled at the same moment cannot interleave their register reads.
 
-use crate::{HalError, HardwareDevice, Sensor, DeviceType};
-use std::fs::OpenOptions;
-use std::os::unix::io::AsRawFd;
+use crate::{Channel, EhalSensorConfig, HalError, HardwareDevice, ReadingPayload, Sensor, DeviceType};
+use crate::buslog::{BusKind, BusOp, Trace};
+use crate::platform::DeviceHandle;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Instant;
+
+/// Marker for "no slave address selected yet"
+const NO_SLAVE: u16 = u16::MAX;
 
 /// I2C Bus wrapper
 pub struct I2CBus {
-    path: String,
-    fd: Option<i32>,
+    handle: DeviceHandle,
+    slave: AtomicU16,
 }
 
 impl I2CBus {
     /// Open I2C bus
     pub fn open(path: &str) -> Result<Self, HalError> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(path)?;
-        
         Ok(Self {
-            path: path.to_string(),
-            fd: Some(file.as_raw_fd()),
+            handle: DeviceHandle::open(path)?,
+            slave: AtomicU16::new(NO_SLAVE),
         })
     }
     
@@ -29,7 +36,7 @@ impl I2CBus {
         // ioctl I2C_SLAVE = 0x0703
         #[cfg(target_os = "linux")]
         unsafe {
-            if let Some(fd) = self.fd {
+            if let Some(fd) = self.handle.raw_fd() {
                 let ret = libc::ioctl(fd, 0x0703, addr as libc::c_ulong);
                 if ret < 0 {
                     return Err(HalError::CommunicationError(
@@ -38,86 +45,344 @@ impl I2CBus {
                 }
             }
         }
+        self.slave.store(addr as u16, Ordering::Relaxed);
         Ok(())
     }
     
+    /// Currently selected slave address
+    pub fn slave(&self) -> Option<u8> {
+        match self.slave.load(Ordering::Relaxed) {
+            NO_SLAVE => None,
+            addr => Some(addr as u8),
+        }
+    }
+    
     /// Read bytes from I2C device
     pub fn read(&self, buf: &mut [u8]) -> Result<usize, HalError> {
-        #[cfg(target_os = "linux")]
-        unsafe {
-            if let Some(fd) = self.fd {
-                let ret = libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len());
-                if ret < 0 {
-                    return Err(HalError::CommunicationError("I2C read failed".to_string()));
-                }
-                return Ok(ret as usize);
-            }
-        }
-        Err(HalError::DeviceNotFound("I2C bus not open".to_string()))
+        let started = Instant::now();
+        let result = self.read_inner(buf);
+        self.trace(BusOp::Read, None).finish(started, buf, &result);
+        result
     }
     
     /// Write bytes to I2C device
     pub fn write(&self, buf: &[u8]) -> Result<usize, HalError> {
-        #[cfg(target_os = "linux")]
-        unsafe {
-            if let Some(fd) = self.fd {
-                let ret = libc::write(fd, buf.as_ptr() as *const libc::c_void, buf.len());
-                if ret < 0 {
-                    return Err(HalError::CommunicationError("I2C write failed".to_string()));
-                }
-                return Ok(ret as usize);
-            }
-        }
-        Err(HalError::DeviceNotFound("I2C bus not open".to_string()))
+        let started = Instant::now();
+        let result = self.write_inner(buf);
+        self.trace(BusOp::Write, None).finish(started, buf, &result);
+        result
     }
     
     /// Read register
     pub fn read_register(&self, addr: u8, reg: u8) -> Result<u8, HalError> {
-        self.set_slave(addr)?;
-        self.write(&[reg])?;
         let mut buf = [0u8; 1];
-        self.read(&mut buf)?;
+        self.read_registers(addr, reg, &mut buf)?;
         Ok(buf[0])
     }
     
     /// Write register
     pub fn write_register(&self, addr: u8, reg: u8, value: u8) -> Result<(), HalError> {
-        self.set_slave(addr)?;
-        self.write(&[reg, value])?;
-        Ok(())
+        let started = Instant::now();
+        let result = self.set_slave(addr)
+            .and_then(|_| self.write_inner(&[reg, value]));
+        self.trace(BusOp::Write, Some(reg)).finish(started, &[value], &result);
+        result.map(|_| ())
+    }
+    
+    /// Read multiple bytes from register
+    pub fn read_registers(&self, addr: u8, reg: u8, buf: &mut [u8]) -> Result<usize, HalError> {
+        let started = Instant::now();
+        let result = self.set_slave(addr)
+            .and_then(|_| self.write_inner(&[reg]))
+            .and_then(|_| self.read_inner(buf));
+        self.trace(BusOp::Read, Some(reg)).finish(started, buf, &result);
+        result
+    }
+    
+    /// Bus device path
+    pub fn path(&self) -> &str {
+        self.handle.path()
+    }
+    
+    fn read_inner(&self, buf: &mut [u8]) -> Result<usize, HalError> {
+        self.handle.read(buf)
+            .map_err(|e| HalError::CommunicationError(format!("I2C read failed: {}", e)))
+    }
+    
+    fn write_inner(&self, buf: &[u8]) -> Result<usize, HalError> {
+        self.handle.write(buf)
+            .map_err(|e| HalError::CommunicationError(format!("I2C write failed: {}", e)))
+    }
+    
+    fn trace(&self, op: BusOp, register: Option<u8>) -> Trace<'_> {
+        Trace {
+            kind: BusKind::I2C,
+            bus: self.path(),
+            op,
+            address: self.slave(),
+            register,
+        }
+    }
+}
+
+/// I2C bus shared between sensors
+///
+/// Cheap to clone. Every handle opened for the same path uses one open bus,
+/// and a transaction keeps the bus to itself from slave select to the last
+/// byte read. The async methods run transactions on tokio's blocking pool.
+#[derive(Clone)]
+pub struct AsyncI2CBus {
+    bus: Arc<Mutex<I2CBus>>,
+    path: Arc<str>,
+}
+
+impl AsyncI2CBus {
+    /// Handle to the bus at `path`, opening it unless another handle has
+    pub fn open(path: &str) -> Result<Self, HalError> {
+        static BUSES: OnceLock<Mutex<HashMap<String, Weak<Mutex<I2CBus>>>>> = OnceLock::new();
+        let mut buses = BUSES.get_or_init(Default::default).lock().unwrap();
+        
+        let bus = match buses.get(path).and_then(Weak::upgrade) {
+            Some(bus) => bus,
+            None => {
+                let bus = Arc::new(Mutex::new(I2CBus::open(path)?));
+                buses.retain(|_, bus| bus.strong_count() > 0);
+                buses.insert(path.to_string(), Arc::downgrade(&bus));
+                bus
+            }
+        };
+        Ok(Self { bus, path: path.into() })
+    }
+    
+    /// Run `f` with the bus to itself
+    pub fn transaction<T>(&self, f: impl FnOnce(&I2CBus) -> Result<T, HalError>) -> Result<T, HalError> {
+        f(&self.bus.lock().unwrap())
+    }
+    
+    /// Read register
+    pub fn read_register(&self, addr: u8, reg: u8) -> Result<u8, HalError> {
+        self.transaction(|bus| bus.read_register(addr, reg))
+    }
+    
+    /// Write register
+    pub fn write_register(&self, addr: u8, reg: u8, value: u8) -> Result<(), HalError> {
+        self.transaction(|bus| bus.write_register(addr, reg, value))
     }
     
     /// Read multiple bytes from register
     pub fn read_registers(&self, addr: u8, reg: u8, buf: &mut [u8]) -> Result<usize, HalError> {
-        self.set_slave(addr)?;
-        self.write(&[reg])?;
-        self.read(buf)
+        self.transaction(|bus| bus.read_registers(addr, reg, buf))
+    }
+    
+    /// [`transaction`](Self::transaction) on the blocking pool
+    pub async fn transaction_async<T, F>(&self, f: F) -> Result<T, HalError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&I2CBus) -> Result<T, HalError> + Send + 'static,
+    {
+        let bus = self.clone();
+        tokio::task::spawn_blocking(move || bus.transaction(f))
+            .await
+            .map_err(|e| HalError::CommunicationError(format!("I2C transaction on {} failed: {}", self.path, e)))?
+    }
+    
+    /// Read `len` bytes from register without blocking the runtime
+    pub async fn read_registers_async(&self, addr: u8, reg: u8, len: usize) -> Result<Vec<u8>, HalError> {
+        self.transaction_async(move |bus| {
+            let mut buf = vec![0u8; len];
+            bus.read_registers(addr, reg, &mut buf)?;
+            Ok(buf)
+        }).await
+    }
+    
+    /// Write register without blocking the runtime
+    pub async fn write_register_async(&self, addr: u8, reg: u8, value: u8) -> Result<(), HalError> {
+        self.transaction_async(move |bus| bus.write_register(addr, reg, value)).await
+    }
+    
+    /// Bus device path
+    pub fn path(&self) -> &str {
+        &self.path
     }
+    
+    /// Handles currently sharing the bus
+    pub fn handles(&self) -> usize {
+        Arc::strong_count(&self.bus)
+    }
+}
+
+/// Kernel name of the adapter behind a bus device, e.g. "bcm2835 (i2c@7e804000)"
+///
+/// Unlike the bus number, it stays the same across reboots and replugs.
+pub fn adapter_name(path: &str) -> Option<String> {
+    let bus = std::path::Path::new(path).file_name()?.to_str()?;
+    let name = std::fs::read_to_string(format!("/sys/bus/i2c/devices/{}/name", bus)).ok()?;
+    Some(name.trim().to_string()).filter(|name| !name.is_empty())
+}
+
+/// Device path of a configured bus: a path as given, or the bus whose
+/// adapter has that name
+pub fn resolve_bus(spec: &str) -> Option<String> {
+    if spec.starts_with('/') {
+        return Some(spec.to_string());
+    }
+    let entries = std::fs::read_dir("/sys/bus/i2c/devices").ok()?;
+    entries.flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|bus| bus.starts_with("i2c-"))
+        .map(|bus| format!("/dev/{}", bus))
+        .find(|path| adapter_name(path).as_deref() == Some(spec))
 }
 
 /// Scan I2C bus for devices
 pub fn scan_bus(path: &str) -> Result<Vec<u8>, HalError> {
-    let bus = I2CBus::open(path)?;
+    let bus = AsyncI2CBus::open(path)?;
     let mut found = Vec::new();
     
-    // Scan addresses 0x03 to 0x77
-    for addr in 0x03..=0x77 {
-        if bus.set_slave(addr).is_ok() {
-            let mut buf = [0u8; 1];
-            if bus.read(&mut buf).is_ok() {
-                found.push(addr);
-                tracing::info!("Found I2C device at 0x{:02X}", addr);
+    // The stub backend acks every address; report an empty bus instead
+    if crate::platform::SIMULATED {
+        return Ok(found);
+    }
+    
+    // Scan addresses 0x03 to 0x77, keeping sensors off the bus meanwhile
+    bus.transaction(|bus| {
+        for addr in 0x03..=0x77 {
+            if bus.set_slave(addr).is_ok() {
+                let mut buf = [0u8; 1];
+                if bus.read(&mut buf).is_ok() {
+                    found.push(addr);
+                    tracing::info!("Found I2C device at 0x{:02X}", addr);
+                }
             }
         }
-    }
+        Ok(())
+    })?;
     
     Ok(found)
 }
 
+/// Opens the driver of an identified chip on a bus at an address
+pub type DriverFactory = fn(&str, u8) -> Result<Box<dyn Sensor>, HalError>;
+
+/// How a known chip is recognised on the bus
+pub struct ChipProbe {
+    pub model: &'static str,
+    /// Addresses the chip can be strapped to
+    pub addresses: &'static [u8],
+    /// ID register and the bytes it reads back; `None` when the chip has no
+    /// ID register and is only recognised by its address
+    pub id: Option<(u8, &'static [u8])>,
+    /// Driver registered for the chip by auto-detection
+    pub driver: Option<DriverFactory>,
+}
+
+/// Chips identified by [`identify_bus`], with the drivers auto-detection
+/// registers for them
+///
+/// Probes with an ID register come first, so a chip sharing an address
+/// with another (LIS3MDL and HMC5883L at 0x1E) is told apart by its ID.
+pub const KNOWN_CHIPS: &[ChipProbe] = &[
+    ChipProbe { model: "HMC5883L", addresses: &[0x1E], id: Some((0x0A, b"H43")),
+        driver: Some(|bus, address| Ok(Box::new(HMC5883L::at(bus, address)?))) },
+    ChipProbe { model: "LIS3MDL", addresses: &[0x1C, 0x1E], id: Some((0x0F, &[0x3D])), driver: None },
+    ChipProbe { model: "QMC5883L", addresses: &[0x0D], id: Some((0x0D, &[0xFF])), driver: None },
+    ChipProbe { model: "BME280", addresses: &[0x76, 0x77], id: Some((0xD0, &[BME280_CHIP_ID])),
+        driver: Some(|bus, address| Ok(Box::new(BME280::at(bus, address)?))) },
+    ChipProbe { model: "BMP280", addresses: &[0x76, 0x77], id: Some((0xD0, &[0x58])), driver: None },
+    ChipProbe { model: "BME680", addresses: &[0x76, 0x77], id: Some((0xD0, &[0x61])), driver: None },
+    ChipProbe { model: "MPU6050", addresses: &[0x68, 0x69], id: Some((0x75, &[0x68])), driver: None },
+    ChipProbe { model: "ADXL345", addresses: &[0x1D, 0x53], id: Some((0x00, &[0xE5])), driver: None },
+    ChipProbe { model: "MCP9808", addresses: &[0x18, 0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F], id: Some((0x06, &[0x00, 0x54])), driver: None },
+    // SMBus devices without an ID register
+    ChipProbe { model: "MLX90614", addresses: &[0x5A], id: None,
+        driver: Some(|bus, address| Ok(Box::new(MLX90614::at(bus, address)?))) },
+    ChipProbe { model: "SHT31", addresses: &[0x44, 0x45], id: None, driver: None },
+    ChipProbe { model: "BH1750", addresses: &[0x23, 0x5C], id: None, driver: None },
+    ChipProbe { model: "AHT20", addresses: &[0x38], id: None, driver: None },
+    ChipProbe { model: "SI7021", addresses: &[0x40], id: None, driver: None },
+    ChipProbe { model: "ADS1115", addresses: &[0x48, 0x49, 0x4A, 0x4B], id: None, driver: None },
+];
+
+/// Open the shipped driver of the chip an `[[ehal_sensors]]` entry names
+/// as its `driver` (e.g. `bme280`), for registering under the chip's
+/// lowercase model with
+/// [`HardwareManager::register_ehal_driver`](crate::HardwareManager::register_ehal_driver)
+///
+/// The chip is opened on the entry's `bus` (`/dev/i2c-1` by default) at its
+/// `address` (the chip's first address by default) and keeps its own unit.
+pub fn open_manifest_sensor(config: &EhalSensorConfig) -> Result<Box<dyn Sensor>, HalError> {
+    let (probe, open) = KNOWN_CHIPS.iter()
+        .find_map(|probe| Some((probe, probe.driver?)).filter(|(probe, _)| probe.model.eq_ignore_ascii_case(&config.driver)))
+        .ok_or_else(|| HalError::InvalidConfig(format!("no I2C driver for {}", config.driver)))?;
+    let bus = config.bus.as_deref().unwrap_or("/dev/i2c-1");
+    let address = config.address.unwrap_or(probe.addresses[0]);
+    let mut sensor = open(bus, address)?;
+    if config.offset != 0.0 {
+        sensor.calibrate(config.offset)?;
+    }
+    Ok(sensor)
+}
+
+/// Device answering on a bus, identified where possible
+#[derive(Debug, Clone, Serialize)]
+pub struct I2cDevice {
+    pub address: u8,
+    /// Model from [`KNOWN_CHIPS`], if one matched
+    pub model: Option<&'static str>,
+    /// Matched by chip ID rather than address alone
+    pub confirmed: bool,
+}
+
+impl I2cDevice {
+    /// Probe of the identified chip
+    pub fn probe(&self) -> Option<&'static ChipProbe> {
+        KNOWN_CHIPS.iter().find(|probe| Some(probe.model) == self.model)
+    }
+}
+
+impl std::fmt::Display for I2cDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.model, self.confirmed) {
+            (Some(model), true) => write!(f, "0x{:02X}: {}", self.address, model),
+            (Some(model), false) => write!(f, "0x{:02X}: {}?", self.address, model),
+            (None, _) => write!(f, "0x{:02X}: unknown", self.address),
+        }
+    }
+}
+
+/// Identify the chip at `address` from [`KNOWN_CHIPS`]
+pub fn identify(bus: &I2CBus, address: u8) -> I2cDevice {
+    let candidates = || KNOWN_CHIPS.iter().filter(|probe| probe.addresses.contains(&address));
+    
+    let by_id = candidates().find(|probe| {
+        let Some((register, expected)) = probe.id else {
+            return false;
+        };
+        let mut id = vec![0u8; expected.len()];
+        bus.read_registers(address, register, &mut id).is_ok() && id == expected
+    });
+    if let Some(probe) = by_id {
+        return I2cDevice { address, model: Some(probe.model), confirmed: true };
+    }
+    
+    I2cDevice {
+        address,
+        model: candidates().find(|probe| probe.id.is_none()).map(|probe| probe.model),
+        confirmed: false,
+    }
+}
+
+/// Scan an I2C bus and identify the devices found
+pub fn identify_bus(path: &str) -> Result<Vec<I2cDevice>, HalError> {
+    let addresses = scan_bus(path)?;
+    let bus = AsyncI2CBus::open(path)?;
+    bus.transaction(|bus| Ok(addresses.iter().map(|&address| identify(bus, address)).collect()))
+}
+
 /// Generic I2C sensor
 pub struct I2CSensor {
     name: String,
-    bus: I2CBus,
+    bus: AsyncI2CBus,
     address: u8,
     unit: String,
     calibration_offset: f64,
@@ -125,9 +390,9 @@ pub struct I2CSensor {
 }
 
 impl I2CSensor {
-    /// Create new I2C sensor
+    /// Create new I2C sensor, sharing the bus with other sensors on it
     pub fn new(name: &str, bus_path: &str, address: u8, unit: &str) -> Result<Self, HalError> {
-        let bus = I2CBus::open(bus_path)?;
+        let bus = AsyncI2CBus::open(bus_path)?;
         
         Ok(Self {
             name: name.to_string(),
@@ -151,7 +416,7 @@ impl HardwareDevice for I2CSensor {
     
     fn init(&mut self) -> Result<(), HalError> {
         // Verify device responds
-        self.bus.set_slave(self.address)?;
+        self.bus.transaction(|bus| bus.set_slave(self.address))?;
         self.ready = true;
         Ok(())
     }
@@ -188,28 +453,52 @@ impl Sensor for I2CSensor {
         self.calibration_offset = offset;
         Ok(())
     }
+    
+    fn bus(&self) -> Option<&str> {
+        Some(self.bus.path())
+    }
 }
 
 // Common I2C sensor implementations
 
 /// HMC5883L Magnetometer (EMF sensor)
+///
+/// Run in continuous mode at 15 Hz, averaging 8 samples, with the ±1.3 Ga
+/// range (0.92 mG per count).
 pub struct HMC5883L {
     base: I2CSensor,
 }
 
+/// HMC5883L output rate in continuous mode (Hz)
+const HMC5883L_RATE: f64 = 15.0;
+/// Count an HMC5883L axis reads when the field is out of range
+const HMC5883L_OVERFLOW: i16 = -4096;
+
 impl HMC5883L {
     pub fn new(bus_path: &str) -> Result<Self, HalError> {
-        let base = I2CSensor::new("HMC5883L", bus_path, 0x1E, "mG")?;
+        Self::at(bus_path, 0x1E)
+    }
+    
+    pub fn at(bus_path: &str, address: u8) -> Result<Self, HalError> {
+        let base = I2CSensor::new("HMC5883L", bus_path, address, "mG")?;
         Ok(Self { base })
     }
     
+    /// Field along each axis (mG)
     pub fn read_xyz(&self) -> Result<(f64, f64, f64), HalError> {
         let mut buf = [0u8; 6];
         self.base.bus.read_registers(self.base.address, 0x03, &mut buf)?;
         
-        let x = ((buf[0] as i16) << 8 | buf[1] as i16) as f64 * 0.92;
-        let y = ((buf[2] as i16) << 8 | buf[3] as i16) as f64 * 0.92;
-        let z = ((buf[4] as i16) << 8 | buf[5] as i16) as f64 * 0.92;
+        // Output registers hold X, Z, Y in that order
+        let counts = [
+            i16::from_be_bytes([buf[0], buf[1]]),
+            i16::from_be_bytes([buf[4], buf[5]]),
+            i16::from_be_bytes([buf[2], buf[3]]),
+        ];
+        if counts.contains(&HMC5883L_OVERFLOW) {
+            return Err(HalError::CommunicationError(format!("{}: field out of range", self.base.name)));
+        }
+        let [x, y, z] = counts.map(|count| count as f64 * 0.92);
         
         Ok((x, y, z))
     }
@@ -218,55 +507,598 @@ impl HMC5883L {
         let (x, y, z) = self.read_xyz()?;
         Ok((x * x + y * y + z * z).sqrt())
     }
+    
+    fn components((x, y, z): (f64, f64, f64)) -> Vec<(String, f64)> {
+        vec![
+            ("x".to_string(), x),
+            ("y".to_string(), y),
+            ("z".to_string(), z),
+        ]
+    }
+}
+
+impl Sensor for HMC5883L {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        let mut buf = vec![0u8; 6];
+        self.base.bus.read_registers(self.base.address, 0x03, &mut buf)?;
+        Ok(buf)
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        Ok(self.read_magnitude()? + self.base.calibration_offset)
+    }
+    
+    fn unit(&self) -> &str {
+        "mG"
+    }
+    
+    fn calibrate(&mut self, offset: f64) -> Result<(), HalError> {
+        self.base.calibrate(offset)
+    }
+    
+    fn bus(&self) -> Option<&str> {
+        self.base.bus()
+    }
+    
+    fn read_components(&self) -> Result<Vec<(String, f64)>, HalError> {
+        Ok(Self::components(self.read_xyz()?))
+    }
+    
+    /// Magnitude and axes from the same conversion
+    fn read_sample(&self) -> Result<(f64, ReadingPayload), HalError> {
+        let (x, y, z) = self.read_xyz()?;
+        let magnitude = (x * x + y * y + z * z).sqrt() + self.base.calibration_offset;
+        Ok((magnitude, ReadingPayload::Vector(Self::components((x, y, z)))))
+    }
+    
+    fn poll_rate(&self) -> Option<f64> {
+        Some(HMC5883L_RATE)
+    }
+}
+
+impl HardwareDevice for HMC5883L {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::I2C
+    }
+    
+    /// Check the chip ID and start continuous measurement
+    fn init(&mut self) -> Result<(), HalError> {
+        let address = self.base.address;
+        self.base.bus.transaction(|bus| {
+            let mut id = [0u8; 3];
+            bus.read_registers(address, 0x0A, &mut id)?;
+            if &id != b"H43" {
+                return Err(HalError::DeviceNotFound(format!("HMC5883L at 0x{:02X} (id {:02X?})", address, id)));
+            }
+            
+            bus.write_register(address, 0x00, 0x70)?;
+            bus.write_register(address, 0x01, 0x20)?;
+            bus.write_register(address, 0x02, 0x00)
+        })?;
+        self.base.init()
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.base.is_ready()
+    }
+    
+    /// Put the chip in idle mode
+    fn close(&mut self) -> Result<(), HalError> {
+        if self.base.is_ready() {
+            let _ = self.base.bus.write_register(self.base.address, 0x02, 0x03);
+        }
+        self.base.close()
+    }
+}
+
+/// BME280 chip ID (register 0xD0)
+const BME280_CHIP_ID: u8 = 0x60;
+
+/// Factory trimming parameters of one BME280
+///
+/// Every chip stores its own in registers 0x88-0xA1 and 0xE1-0xE7; raw
+/// readings mean nothing without them. The compensation follows the Bosch
+/// datasheet's integer formulas (section 4.2.3).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bme280Calibration {
+    pub dig_t1: u16,
+    pub dig_t2: i16,
+    pub dig_t3: i16,
+    pub dig_p1: u16,
+    pub dig_p2: i16,
+    pub dig_p3: i16,
+    pub dig_p4: i16,
+    pub dig_p5: i16,
+    pub dig_p6: i16,
+    pub dig_p7: i16,
+    pub dig_p8: i16,
+    pub dig_p9: i16,
+    pub dig_h1: u8,
+    pub dig_h2: i16,
+    pub dig_h3: u8,
+    pub dig_h4: i16,
+    pub dig_h5: i16,
+    pub dig_h6: i8,
+}
+
+impl Bme280Calibration {
+    /// Parse the register blocks 0x88-0xA1 (26 bytes) and 0xE1-0xE7 (7 bytes)
+    pub fn from_registers(tp: &[u8; 26], h: &[u8; 7]) -> Self {
+        let u16_at = |i: usize| u16::from_le_bytes([tp[i], tp[i + 1]]);
+        let i16_at = |i: usize| i16::from_le_bytes([tp[i], tp[i + 1]]);
+        
+        Self {
+            dig_t1: u16_at(0),
+            dig_t2: i16_at(2),
+            dig_t3: i16_at(4),
+            dig_p1: u16_at(6),
+            dig_p2: i16_at(8),
+            dig_p3: i16_at(10),
+            dig_p4: i16_at(12),
+            dig_p5: i16_at(14),
+            dig_p6: i16_at(16),
+            dig_p7: i16_at(18),
+            dig_p8: i16_at(20),
+            dig_p9: i16_at(22),
+            dig_h1: tp[25],
+            dig_h2: i16::from_le_bytes([h[0], h[1]]),
+            dig_h3: h[2],
+            // 12-bit values sharing register 0xE5
+            dig_h4: ((h[3] as i8 as i16) << 4) | (h[4] & 0x0F) as i16,
+            dig_h5: ((h[5] as i8 as i16) << 4) | (h[4] >> 4) as i16,
+            dig_h6: h[6] as i8,
+        }
+    }
+    
+    /// Temperature (°C) and the fine temperature the other channels need
+    pub fn temperature(&self, adc_t: i32) -> (f64, i32) {
+        let t1 = self.dig_t1 as i32;
+        let var1 = (((adc_t >> 3) - (t1 << 1)) * self.dig_t2 as i32) >> 11;
+        let var2 = (((((adc_t >> 4) - t1) * ((adc_t >> 4) - t1)) >> 12) * self.dig_t3 as i32) >> 14;
+        let t_fine = var1 + var2;
+        (((t_fine * 5 + 128) >> 8) as f64 / 100.0, t_fine)
+    }
+    
+    /// Pressure (Pa)
+    pub fn pressure(&self, adc_p: i32, t_fine: i32) -> f64 {
+        let mut var1 = t_fine as i64 - 128_000;
+        let mut var2 = var1 * var1 * self.dig_p6 as i64;
+        var2 += (var1 * self.dig_p5 as i64) << 17;
+        var2 += (self.dig_p4 as i64) << 35;
+        var1 = ((var1 * var1 * self.dig_p3 as i64) >> 8) + ((var1 * self.dig_p2 as i64) << 12);
+        var1 = (((1i64 << 47) + var1) * self.dig_p1 as i64) >> 33;
+        if var1 == 0 {
+            return 0.0;
+        }
+        
+        let mut p = 1_048_576 - adc_p as i64;
+        p = (((p << 31) - var2) * 3125) / var1;
+        let var1 = (self.dig_p9 as i64 * (p >> 13) * (p >> 13)) >> 25;
+        let var2 = (self.dig_p8 as i64 * p) >> 19;
+        p = ((p + var1 + var2) >> 8) + ((self.dig_p7 as i64) << 4);
+        p as f64 / 256.0
+    }
+    
+    /// Relative humidity (%)
+    pub fn humidity(&self, adc_h: i32, t_fine: i32) -> f64 {
+        let x = t_fine - 76_800;
+        let x = (((adc_h << 14) - ((self.dig_h4 as i32) << 20) - (self.dig_h5 as i32 * x) + 16_384) >> 15)
+            * (((((((x * self.dig_h6 as i32) >> 10) * (((x * self.dig_h3 as i32) >> 11) + 32_768)) >> 10) + 2_097_152)
+                * self.dig_h2 as i32 + 8192) >> 14);
+        let x = x - (((((x >> 15) * (x >> 15)) >> 7) * self.dig_h1 as i32) >> 4);
+        let x = x.clamp(0, 419_430_400);
+        (x >> 12) as f64 / 1024.0
+    }
+}
+
+/// Quantity a BME280 sensor reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bme280Channel {
+    Temperature,
+    Humidity,
+    Pressure,
+}
+
+impl Bme280Channel {
+    pub const ALL: [Self; 3] = [Self::Temperature, Self::Humidity, Self::Pressure];
+    
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Temperature => "C",
+            Self::Humidity => "%RH",
+            Self::Pressure => "hPa",
+        }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Temperature => "temperature",
+            Self::Humidity => "humidity",
+            Self::Pressure => "pressure",
+        }
+    }
+    
+    /// This channel's value out of [`BME280::read_all`]
+    fn select(&self, (temperature, humidity, pressure): (f64, f64, f64)) -> f64 {
+        match self {
+            Self::Temperature => temperature,
+            Self::Humidity => humidity,
+            Self::Pressure => pressure,
+        }
+    }
 }
 
 /// BME280 Temperature/Humidity/Pressure sensor
+///
+/// As a [`Sensor`] its value is one [`Bme280Channel`], the temperature unless
+/// another is chosen with [`channel`](Self::channel), and the other two are
+/// reported as channels of the reading. The chip measures every second in
+/// normal mode.
 pub struct BME280 {
     base: I2CSensor,
+    channel: Bme280Channel,
+    calibration: OnceLock<Bme280Calibration>,
 }
 
 impl BME280 {
+    /// BME280 at its default address (SDO pulled high)
     pub fn new(bus_path: &str) -> Result<Self, HalError> {
-        let base = I2CSensor::new("BME280", bus_path, 0x77, "C")?;
-        Ok(Self { base })
+        Self::at(bus_path, 0x77)
     }
     
+    /// BME280 at `address` (0x76 with SDO tied low)
+    pub fn at(bus_path: &str, address: u8) -> Result<Self, HalError> {
+        let base = I2CSensor::new("BME280", bus_path, address, "C")?;
+        Ok(Self { base, channel: Bme280Channel::Temperature, calibration: OnceLock::new() })
+    }
+    
+    /// Report `channel` instead
+    pub fn channel(mut self, channel: Bme280Channel) -> Self {
+        self.channel = channel;
+        self.base.unit = channel.unit().to_string();
+        self
+    }
+    
+    /// Factory calibration, read from the chip on first use
+    pub fn calibration(&self) -> Result<&Bme280Calibration, HalError> {
+        if let Some(calibration) = self.calibration.get() {
+            return Ok(calibration);
+        }
+        
+        let address = self.base.address;
+        let calibration = self.base.bus.transaction(|bus| {
+            let id = bus.read_register(address, 0xD0)?;
+            if id != BME280_CHIP_ID {
+                return Err(HalError::DeviceNotFound(format!("BME280 at 0x{:02X} (chip id 0x{:02X})", address, id)));
+            }
+            
+            let mut tp = [0u8; 26];
+            let mut h = [0u8; 7];
+            bus.read_registers(address, 0x88, &mut tp)?;
+            bus.read_registers(address, 0xE1, &mut h)?;
+            Ok(Bme280Calibration::from_registers(&tp, &h))
+        })?;
+        Ok(self.calibration.get_or_init(|| calibration))
+    }
+    
+    /// Put the chip in normal mode with 1x oversampling on all channels, so
+    /// results are ready by the next read
+    ///
+    /// The mode registers reset with the chip (after a brown-out, say), so
+    /// this runs on every init.
+    fn start_measuring(&self) -> Result<(), HalError> {
+        let address = self.base.address;
+        self.base.bus.transaction(|bus| {
+            // ctrl_hum only takes effect after a write to ctrl_meas
+            bus.write_register(address, 0xF2, 0x01)?;
+            bus.write_register(address, 0xF5, 0xA0)?;
+            bus.write_register(address, 0xF4, 0x27)
+        })
+    }
+    
+    /// Temperature (°C), relative humidity (%) and pressure (hPa)
     pub fn read_all(&self) -> Result<(f64, f64, f64), HalError> {
-        // Read temperature, humidity, pressure
+        let calibration = self.calibration()?;
         let mut buf = [0u8; 8];
         self.base.bus.read_registers(self.base.address, 0xF7, &mut buf)?;
         
-        // Simplified conversion (real implementation needs calibration data)
-        let pressure = ((buf[0] as u32) << 12 | (buf[1] as u32) << 4 | (buf[2] as u32) >> 4) as f64 / 256.0;
-        let temperature = ((buf[3] as u32) << 12 | (buf[4] as u32) << 4 | (buf[5] as u32) >> 4) as f64 / 5120.0 - 40.0;
-        let humidity = ((buf[6] as u16) << 8 | buf[7] as u16) as f64 / 1024.0;
+        let adc_p = (buf[0] as i32) << 12 | (buf[1] as i32) << 4 | (buf[2] as i32) >> 4;
+        let adc_t = (buf[3] as i32) << 12 | (buf[4] as i32) << 4 | (buf[5] as i32) >> 4;
+        let adc_h = (buf[6] as i32) << 8 | buf[7] as i32;
+        
+        let (temperature, t_fine) = calibration.temperature(adc_t);
+        let pressure = calibration.pressure(adc_p, t_fine) / 100.0;
+        let humidity = calibration.humidity(adc_h, t_fine);
         
         Ok((temperature, humidity, pressure))
     }
+    
+    /// The channels other than the selected one
+    fn channels(&self, values: (f64, f64, f64)) -> Vec<Channel> {
+        Bme280Channel::ALL.iter()
+            .filter(|&&channel| channel != self.channel)
+            .map(|channel| Channel::new(channel.name(), channel.select(values), channel.unit()))
+            .collect()
+    }
+}
+
+impl Sensor for BME280 {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        let mut buf = vec![0u8; 8];
+        self.base.bus.read_registers(self.base.address, 0xF7, &mut buf)?;
+        Ok(buf)
+    }
+    
+    /// Compensated value of the channel, plus the calibration offset
+    fn read_value(&self) -> Result<f64, HalError> {
+        Ok(self.channel.select(self.read_all()?) + self.base.calibration_offset)
+    }
+    
+    fn unit(&self) -> &str {
+        self.channel.unit()
+    }
+    
+    fn read_channels(&self) -> Result<Vec<Channel>, HalError> {
+        Ok(self.channels(self.read_all()?))
+    }
+    
+    /// Selected channel and the other two from one burst read
+    fn read_sample(&self) -> Result<(f64, ReadingPayload), HalError> {
+        let values = self.read_all()?;
+        let value = self.channel.select(values) + self.base.calibration_offset;
+        Ok((value, ReadingPayload::Channels(self.channels(values))))
+    }
+    
+    fn calibrate(&mut self, offset: f64) -> Result<(), HalError> {
+        self.base.calibrate(offset)
+    }
+    
+    fn bus(&self) -> Option<&str> {
+        self.base.bus()
+    }
+    
+    fn poll_rate(&self) -> Option<f64> {
+        Some(1.0)
+    }
+}
+
+impl HardwareDevice for BME280 {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::I2C
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.base.init()?;
+        self.calibration()?;
+        self.start_measuring()
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.base.is_ready()
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.base.close()
+    }
+}
+
+/// Temperature an MLX90614 sensor reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mlx90614Channel {
+    /// Surface in the field of view (IR)
+    Object,
+    /// The sensor's own package
+    Ambient,
+}
+
+impl Mlx90614Channel {
+    pub const ALL: [Self; 2] = [Self::Object, Self::Ambient];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Object => "object",
+            Self::Ambient => "ambient",
+        }
+    }
+    
+    /// RAM register holding the temperature
+    fn register(&self) -> u8 {
+        match self {
+            Self::Object => 0x07,
+            Self::Ambient => 0x06,
+        }
+    }
 }
 
 /// MLX90614 IR Temperature sensor
+///
+/// As a [`Sensor`] its value is the object temperature unless the ambient
+/// one is chosen with [`channel`](Self::channel); the other is reported as a
+/// channel of the reading.
 pub struct MLX90614 {
     base: I2CSensor,
+    channel: Mlx90614Channel,
 }
 
 impl MLX90614 {
     pub fn new(bus_path: &str) -> Result<Self, HalError> {
-        let base = I2CSensor::new("MLX90614", bus_path, 0x5A, "C")?;
-        Ok(Self { base })
+        Self::at(bus_path, 0x5A)
+    }
+    
+    /// MLX90614 at `address` (reprogrammed in its EEPROM to share a bus)
+    pub fn at(bus_path: &str, address: u8) -> Result<Self, HalError> {
+        let base = I2CSensor::new("MLX90614", bus_path, address, "C")?;
+        Ok(Self { base, channel: Mlx90614Channel::Object })
+    }
+    
+    /// Report `channel` instead
+    pub fn channel(mut self, channel: Mlx90614Channel) -> Self {
+        self.channel = channel;
+        self
     }
     
     pub fn read_ambient(&self) -> Result<f64, HalError> {
-        let mut buf = [0u8; 3];
-        self.base.bus.read_registers(self.base.address, 0x06, &mut buf)?;
-        let raw = (buf[0] as u16) | ((buf[1] as u16) << 8);
-        Ok(raw as f64 * 0.02 - 273.15)
+        self.read_temperature(Mlx90614Channel::Ambient)
     }
     
     pub fn read_object(&self) -> Result<f64, HalError> {
+        self.read_temperature(Mlx90614Channel::Object)
+    }
+    
+    /// Temperature (°C) of `channel`
+    ///
+    /// The top bit of the register flags a failed conversion.
+    fn read_temperature(&self, channel: Mlx90614Channel) -> Result<f64, HalError> {
         let mut buf = [0u8; 3];
-        self.base.bus.read_registers(self.base.address, 0x07, &mut buf)?;
-        let raw = (buf[0] as u16) | ((buf[1] as u16) << 8);
+        self.base.bus.read_registers(self.base.address, channel.register(), &mut buf)?;
+        let raw = u16::from_le_bytes([buf[0], buf[1]]);
+        if raw & 0x8000 != 0 {
+            return Err(HalError::CommunicationError(format!("{}: error flag set in register 0x{:02X}", self.base.name, channel.register())));
+        }
         Ok(raw as f64 * 0.02 - 273.15)
     }
 }
+
+impl Sensor for MLX90614 {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        let mut buf = vec![0u8; 3];
+        self.base.bus.read_registers(self.base.address, self.channel.register(), &mut buf)?;
+        Ok(buf)
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        Ok(self.read_temperature(self.channel)? + self.base.calibration_offset)
+    }
+    
+    fn unit(&self) -> &str {
+        "C"
+    }
+    
+    fn read_channels(&self) -> Result<Vec<Channel>, HalError> {
+        Mlx90614Channel::ALL.iter()
+            .filter(|&&channel| channel != self.channel)
+            .map(|&channel| Ok(Channel::new(channel.name(), self.read_temperature(channel)?, "C")))
+            .collect()
+    }
+    
+    fn calibrate(&mut self, offset: f64) -> Result<(), HalError> {
+        self.base.calibrate(offset)
+    }
+    
+    fn bus(&self) -> Option<&str> {
+        self.base.bus()
+    }
+}
+
+impl HardwareDevice for MLX90614 {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::I2C
+    }
+    
+    /// Read the ambient temperature, the chip having no ID register
+    fn init(&mut self) -> Result<(), HalError> {
+        self.read_ambient()?;
+        self.base.init()
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.base.is_ready()
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.base.close()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Compensation example of the Bosch datasheet, with humidity trims of a real part
+    fn datasheet() -> Bme280Calibration {
+        Bme280Calibration {
+            dig_t1: 27504,
+            dig_t2: 26435,
+            dig_t3: -1000,
+            dig_p1: 36477,
+            dig_p2: -10685,
+            dig_p3: 3024,
+            dig_p4: 2855,
+            dig_p5: 140,
+            dig_p6: -7,
+            dig_p7: 15500,
+            dig_p8: -14600,
+            dig_p9: 6000,
+            dig_h1: 75,
+            dig_h2: 362,
+            dig_h3: 0,
+            dig_h4: 313,
+            dig_h5: 50,
+            dig_h6: 30,
+        }
+    }
+    
+    #[test]
+    fn bme280_temperature() {
+        let (celsius, t_fine) = datasheet().temperature(519_888);
+        assert_eq!(t_fine, 128_422);
+        assert_eq!(celsius, 25.08);
+    }
+    
+    #[test]
+    fn bme280_pressure() {
+        let pressure = datasheet().pressure(415_148, 128_422);
+        assert!((pressure - 100_653.27).abs() < 0.1, "{pressure} Pa");
+    }
+    
+    #[test]
+    fn bme280_humidity() {
+        // The datasheet's floating-point formula gives 55.00 %RH
+        let humidity = datasheet().humidity(30_000, 128_422);
+        assert!((humidity - 55.0).abs() < 0.01, "{humidity} %RH");
+    }
+    
+    #[test]
+    fn bme280_register_layout() {
+        let words: [i32; 12] = [27504, 26435, -1000, 36477, -10685, 3024, 2855, 140, -7, 15500, -14600, 6000];
+        let mut tp = [0u8; 26];
+        for (i, word) in words.iter().enumerate() {
+            tp[i * 2..i * 2 + 2].copy_from_slice(&(*word as u16).to_le_bytes());
+        }
+        tp[25] = 75;
+        // 0xE4/0xE5 hold dig_H4 = 0x139, 0xE5/0xE6 hold dig_H5 = 0x032
+        let h = [0x6A, 0x01, 0x00, 0x13, 0x29, 0x03, 0x1E];
+        
+        let calibration = Bme280Calibration::from_registers(&tp, &h);
+        let expected = datasheet();
+        assert_eq!(calibration.dig_t1, expected.dig_t1);
+        assert_eq!(calibration.dig_t2, expected.dig_t2);
+        assert_eq!(calibration.dig_t3, expected.dig_t3);
+        assert_eq!(calibration.dig_p1, expected.dig_p1);
+        assert_eq!(calibration.dig_p6, expected.dig_p6);
+        assert_eq!(calibration.dig_p9, expected.dig_p9);
+        assert_eq!(calibration.dig_h1, expected.dig_h1);
+        assert_eq!(calibration.dig_h2, expected.dig_h2);
+        assert_eq!(calibration.dig_h3, expected.dig_h3);
+        assert_eq!(calibration.dig_h4, expected.dig_h4);
+        assert_eq!(calibration.dig_h5, expected.dig_h5);
+        assert_eq!(calibration.dig_h6, expected.dig_h6);
+        
+        // Negative 12-bit trims keep their sign across the shared nibble
+        let calibration = Bme280Calibration::from_registers(&tp, &[0x6A, 0x01, 0x00, 0xFE, 0xE7, 0xFC, 0x1E]);
+        assert_eq!(calibration.dig_h4, -25);
+        assert_eq!(calibration.dig_h5, -50);
+    }
+}
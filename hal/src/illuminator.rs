@@ -0,0 +1,365 @@
+//! IR illuminator control
+//!
+//! Night vision cameras see by an IR LED array. Too little light buries
+//! the scene in sensor noise, too much blows out anything near the lens and
+//! cooks the LEDs. In auto mode each captured frame's average brightness
+//! nudges the PWM level towards a target exposure; a manual level or off
+//! can be set over the API or by a trigger action.
+//!
+//! The LEDs heat with the power they are driven at, so the controller keeps
+//! a first-order estimate of that heat: the array may run above its
+//! sustained duty in bursts, but once the estimate reaches the sustained
+//! level the output is capped there until it cools.
+
+use crate::HalError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+#[cfg(feature = "gpio")]
+use crate::gpio::PwmOutput;
+#[cfg(feature = "gpio")]
+use std::time::Duration;
+
+/// How an illuminator's level is chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum IlluminatorMode {
+    /// Follow the camera's frame brightness
+    #[default]
+    Auto,
+    /// Fixed level (0-1)
+    Manual { level: f64 },
+    Off,
+}
+
+impl fmt::Display for IlluminatorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IlluminatorMode::Auto => write!(f, "auto"),
+            IlluminatorMode::Manual { level } => write!(f, "manual {:.0}%", level * 100.0),
+            IlluminatorMode::Off => write!(f, "off"),
+        }
+    }
+}
+
+/// Heat budget of an LED array
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThermalLimit {
+    /// Duty the array can run at indefinitely (0-1)
+    #[serde(default = "default_sustained_duty")]
+    pub sustained_duty: f64,
+    /// Time constant of the heat estimate (s); longer allows longer bursts
+    #[serde(default = "default_time_constant")]
+    pub time_constant_secs: f64,
+}
+
+fn default_sustained_duty() -> f64 { 0.6 }
+fn default_time_constant() -> f64 { 120.0 }
+
+impl Default for ThermalLimit {
+    fn default() -> Self {
+        Self {
+            sustained_duty: default_sustained_duty(),
+            time_constant_secs: default_time_constant(),
+        }
+    }
+}
+
+/// Device manifest entry for an IR illuminator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IlluminatorConfig {
+    pub name: String,
+    /// PWM channel driving the LEDs
+    pub pwm_channel: u32,
+    /// PWM frequency (Hz)
+    #[serde(default = "default_frequency")]
+    pub frequency_hz: u32,
+    /// Night vision camera whose frames drive auto mode; without one the
+    /// illuminator starts off and is only set manually
+    #[serde(default)]
+    pub camera: Option<String>,
+    /// Average frame brightness auto mode aims for (0-255)
+    #[serde(default = "default_target")]
+    pub target_brightness: f64,
+    /// Level change per frame for a full-scale brightness error
+    #[serde(default = "default_gain")]
+    pub gain: f64,
+    /// Highest level ever driven (0-1)
+    #[serde(default = "default_max_duty")]
+    pub max_duty: f64,
+    #[serde(default)]
+    pub thermal: ThermalLimit,
+    /// How often the output and heat estimate are updated
+    #[serde(default = "default_update")]
+    pub update_ms: u64,
+}
+
+fn default_frequency() -> u32 { 1000 }
+fn default_target() -> f64 { 90.0 }
+fn default_gain() -> f64 { 0.5 }
+fn default_max_duty() -> f64 { 1.0 }
+fn default_update() -> u64 { 200 }
+
+/// Current state of an illuminator, for status views
+#[derive(Debug, Clone, Serialize)]
+pub struct IlluminatorStatus {
+    pub name: String,
+    pub camera: Option<String>,
+    #[serde(flatten)]
+    pub mode: IlluminatorMode,
+    /// Level the mode asks for
+    pub requested: f64,
+    /// Level driven after the thermal limit
+    pub duty: f64,
+    /// Heat estimate, in units of steady-state duty
+    pub heat: f64,
+    /// Output capped by the thermal limit
+    pub throttled: bool,
+    /// Average brightness of the camera's latest frame
+    pub frame_brightness: Option<f64>,
+}
+
+/// Exposure and thermal control of one illuminator, without the hardware
+#[derive(Debug, Clone)]
+pub struct BrightnessControl {
+    config: IlluminatorConfig,
+    mode: IlluminatorMode,
+    auto_level: f64,
+    frame_brightness: Option<f64>,
+    heat: f64,
+    duty: f64,
+    last_tick: Option<Instant>,
+}
+
+impl BrightnessControl {
+    pub fn new(config: IlluminatorConfig) -> Self {
+        let mode = match config.camera {
+            Some(_) => IlluminatorMode::Auto,
+            None => IlluminatorMode::Off,
+        };
+        
+        Self {
+            config,
+            mode,
+            auto_level: 0.0,
+            frame_brightness: None,
+            heat: 0.0,
+            duty: 0.0,
+            last_tick: None,
+        }
+    }
+    
+    pub fn mode(&self) -> IlluminatorMode {
+        self.mode
+    }
+    
+    pub fn set_mode(&mut self, mode: IlluminatorMode) {
+        self.mode = mode;
+    }
+    
+    /// Feed the average brightness (0-255) of a captured frame
+    ///
+    /// Only moves the level in auto mode; the brightness is still reported.
+    pub fn expose(&mut self, brightness: f64) {
+        self.frame_brightness = Some(brightness);
+        if self.mode == IlluminatorMode::Auto {
+            let error = (self.config.target_brightness - brightness) / 255.0;
+            self.auto_level = (self.auto_level + self.config.gain * error).clamp(0.0, self.max_duty());
+        }
+    }
+    
+    /// Level the current mode asks for
+    pub fn requested(&self) -> f64 {
+        match self.mode {
+            IlluminatorMode::Auto => self.auto_level,
+            IlluminatorMode::Manual { level } => level.clamp(0.0, self.max_duty()),
+            IlluminatorMode::Off => 0.0,
+        }
+    }
+    
+    /// Advance the heat estimate to `now` and return the duty to drive
+    pub fn tick(&mut self, now: Instant) -> f64 {
+        if let Some(last) = self.last_tick {
+            let dt = now.saturating_duration_since(last).as_secs_f64();
+            let tau = self.config.thermal.time_constant_secs.max(1.0);
+            self.heat += (self.duty - self.heat) * (1.0 - (-dt / tau).exp());
+        }
+        self.last_tick = Some(now);
+        
+        let requested = self.requested();
+        let sustained = self.config.thermal.sustained_duty.clamp(0.0, 1.0);
+        self.duty = if self.heat >= sustained {
+            requested.min(sustained)
+        } else {
+            requested
+        };
+        self.duty
+    }
+    
+    pub fn status(&self) -> IlluminatorStatus {
+        let requested = self.requested();
+        IlluminatorStatus {
+            name: self.config.name.clone(),
+            camera: self.config.camera.clone(),
+            mode: self.mode,
+            requested,
+            duty: self.duty,
+            heat: self.heat,
+            throttled: self.duty < requested,
+            frame_brightness: self.frame_brightness,
+        }
+    }
+    
+    fn max_duty(&self) -> f64 {
+        self.config.max_duty.clamp(0.0, 1.0)
+    }
+}
+
+/// Shared handle to an illuminator's control state
+///
+/// Cheap to clone; the camera, the API and trigger actions all hold one.
+#[derive(Debug, Clone)]
+pub struct Illuminator {
+    name: String,
+    camera: Option<String>,
+    control: Arc<Mutex<BrightnessControl>>,
+}
+
+impl Illuminator {
+    pub fn new(config: IlluminatorConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            camera: config.camera.clone(),
+            control: Arc::new(Mutex::new(BrightnessControl::new(config))),
+        }
+    }
+    
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    /// Camera driving auto mode
+    pub fn camera(&self) -> Option<&str> {
+        self.camera.as_deref()
+    }
+    
+    pub fn set_mode(&self, mode: IlluminatorMode) -> IlluminatorStatus {
+        let mut control = self.control.lock().unwrap();
+        control.set_mode(mode);
+        control.status()
+    }
+    
+    /// Feed the average brightness (0-255) of a captured frame
+    pub fn expose(&self, brightness: f64) {
+        self.control.lock().unwrap().expose(brightness);
+    }
+    
+    /// Advance the heat estimate and return the duty to drive
+    pub fn tick(&self, now: Instant) -> f64 {
+        self.control.lock().unwrap().tick(now)
+    }
+    
+    pub fn status(&self) -> IlluminatorStatus {
+        self.control.lock().unwrap().status()
+    }
+}
+
+/// Illuminators by name
+///
+/// Cheap to clone; clones share the registry.
+#[derive(Debug, Clone, Default)]
+pub struct Illuminators {
+    by_name: Arc<RwLock<BTreeMap<String, Illuminator>>>,
+}
+
+impl Illuminators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn add(&self, illuminator: Illuminator) {
+        self.by_name.write().unwrap().insert(illuminator.name().to_string(), illuminator);
+    }
+    
+    pub fn get(&self, name: &str) -> Option<Illuminator> {
+        self.by_name.read().unwrap().get(name).cloned()
+    }
+    
+    /// Illuminator whose auto mode follows `camera`
+    pub fn for_camera(&self, camera: &str) -> Option<Illuminator> {
+        self.by_name.read().unwrap().values()
+            .find(|i| i.camera() == Some(camera))
+            .cloned()
+    }
+    
+    /// Set the mode of a named illuminator
+    pub fn set_mode(&self, name: &str, mode: IlluminatorMode) -> Result<IlluminatorStatus, HalError> {
+        let illuminator = self.get(name)
+            .ok_or_else(|| HalError::DeviceNotFound(format!("illuminator {}", name)))?;
+        Ok(illuminator.set_mode(mode))
+    }
+    
+    /// Status of every illuminator, sorted by name
+    pub fn statuses(&self) -> Vec<IlluminatorStatus> {
+        self.by_name.read().unwrap().values().map(|i| i.status()).collect()
+    }
+}
+
+/// Drives an illuminator's PWM output from its control state
+#[cfg(feature = "gpio")]
+pub struct IRIlluminator {
+    pwm: PwmOutput,
+    handle: Illuminator,
+    update_interval: Duration,
+}
+
+#[cfg(feature = "gpio")]
+impl IRIlluminator {
+    /// Open the PWM channel with the LEDs off
+    pub fn open(config: &IlluminatorConfig) -> Result<Self, HalError> {
+        let mut pwm = PwmOutput::new(config.pwm_channel, config.frequency_hz.max(1))?;
+        pwm.set_duty(0.0)?;
+        pwm.enable()?;
+        
+        Ok(Self {
+            pwm,
+            handle: Illuminator::new(config.clone()),
+            update_interval: Duration::from_millis(config.update_ms.max(10)),
+        })
+    }
+    
+    /// Handle for changing the mode and feeding frames
+    pub fn handle(&self) -> Illuminator {
+        self.handle.clone()
+    }
+    
+    /// Update the output on a background thread until every handle is dropped
+    pub fn spawn(mut self) {
+        std::thread::spawn(move || {
+            let mut driven = None;
+            let mut throttled = false;
+            while Arc::strong_count(&self.handle.control) > 1 {
+                let duty = self.handle.tick(Instant::now());
+                if driven != Some(duty) {
+                    match self.pwm.set_duty(duty) {
+                        Ok(()) => driven = Some(duty),
+                        Err(e) => tracing::warn!("Illuminator {} PWM write failed: {}", self.handle.name(), e),
+                    }
+                }
+                
+                let status = self.handle.status();
+                if status.throttled && !throttled {
+                    tracing::info!("Illuminator {} at thermal limit, capped at {:.0}%",
+                        status.name, status.duty * 100.0);
+                }
+                throttled = status.throttled;
+                
+                std::thread::sleep(self.update_interval);
+            }
+            let _ = self.pwm.set_duty(0.0);
+            let _ = self.pwm.disable();
+        });
+    }
+}
@@ -0,0 +1,283 @@
+//! Physical input controls for GlowBarn HAL
+//!
+//! Debounced push buttons and quadrature rotary encoders on GPIO, polled
+//! on a background thread and delivered as [`InputEvent`]s over a channel,
+//! so a headless rig can be driven without a screen.
+
+use crate::HalError;
+use crate::gpio::{GpioPin, Direction};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Event from a physical control
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    /// Button went down (after debounce)
+    ButtonPressed { name: String },
+    /// Button released after being held for `held`
+    ButtonReleased { name: String, held: Duration },
+    /// Button held past the long-press threshold
+    ButtonLongPress { name: String },
+    /// Encoder moved by `delta` detents
+    EncoderTurned { name: String, delta: i32, position: i64 },
+}
+
+/// Debounced push button (active low, pulled up)
+pub struct Button {
+    name: String,
+    gpio: GpioPin,
+    debounce: Duration,
+    long_press: Duration,
+    pressed: bool,
+    pending: Option<(bool, Instant)>,
+    pressed_at: Option<Instant>,
+    long_reported: bool,
+}
+
+impl Button {
+    pub fn new(name: &str, pin: u32) -> Result<Self, HalError> {
+        let gpio = GpioPin::new(name, pin, Direction::Input)?;
+        
+        Ok(Self {
+            name: name.to_string(),
+            gpio,
+            debounce: Duration::from_millis(20),
+            long_press: Duration::from_secs(1),
+            pressed: false,
+            pending: None,
+            pressed_at: None,
+            long_reported: false,
+        })
+    }
+    
+    /// Time the contact must settle before a change is accepted
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+    
+    /// Hold time that produces a long-press event
+    pub fn with_long_press(mut self, long_press: Duration) -> Self {
+        self.long_press = long_press;
+        self
+    }
+    
+    /// Button name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    /// Current debounced state
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+    
+    /// Sample the pin and return an event if the debounced state changed
+    pub fn poll(&mut self) -> Result<Option<InputEvent>, HalError> {
+        let raw = !self.gpio.read()?;
+        let now = Instant::now();
+        
+        if raw == self.pressed {
+            self.pending = None;
+            return Ok(self.check_long_press(now));
+        }
+        
+        match self.pending {
+            Some((state, since)) if state == raw => {
+                if now.duration_since(since) < self.debounce {
+                    return Ok(None);
+                }
+            }
+            _ => {
+                self.pending = Some((raw, now));
+                return Ok(None);
+            }
+        }
+        
+        self.pending = None;
+        self.pressed = raw;
+        
+        if raw {
+            self.pressed_at = Some(now);
+            self.long_reported = false;
+            Ok(Some(InputEvent::ButtonPressed { name: self.name.clone() }))
+        } else {
+            let held = self.pressed_at.take()
+                .map(|t| now.duration_since(t))
+                .unwrap_or_default();
+            Ok(Some(InputEvent::ButtonReleased { name: self.name.clone(), held }))
+        }
+    }
+    
+    fn check_long_press(&mut self, now: Instant) -> Option<InputEvent> {
+        let pressed_at = self.pressed_at?;
+        if self.pressed && !self.long_reported && now.duration_since(pressed_at) >= self.long_press {
+            self.long_reported = true;
+            return Some(InputEvent::ButtonLongPress { name: self.name.clone() });
+        }
+        None
+    }
+}
+
+/// Quadrature state transition table, indexed by (previous << 2) | current
+const QUADRATURE: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+/// Quadrature rotary encoder (KY-040 style)
+pub struct RotaryEncoder {
+    name: String,
+    pin_a: GpioPin,
+    pin_b: GpioPin,
+    steps_per_detent: i32,
+    state: u8,
+    accumulator: i32,
+    position: i64,
+}
+
+impl RotaryEncoder {
+    pub fn new(name: &str, pin_a: u32, pin_b: u32) -> Result<Self, HalError> {
+        let pin_a = GpioPin::new(&format!("{}_a", name), pin_a, Direction::Input)?;
+        let pin_b = GpioPin::new(&format!("{}_b", name), pin_b, Direction::Input)?;
+        
+        let mut encoder = Self {
+            name: name.to_string(),
+            pin_a,
+            pin_b,
+            steps_per_detent: 4,
+            state: 0,
+            accumulator: 0,
+            position: 0,
+        };
+        encoder.state = encoder.read_state()?;
+        
+        Ok(encoder)
+    }
+    
+    /// Quadrature transitions per mechanical detent (usually 4 or 2)
+    pub fn with_steps_per_detent(mut self, steps: i32) -> Self {
+        self.steps_per_detent = steps.max(1);
+        self
+    }
+    
+    /// Encoder name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    /// Position in detents since creation
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+    
+    fn read_state(&self) -> Result<u8, HalError> {
+        Ok(((self.pin_a.read()? as u8) << 1) | self.pin_b.read()? as u8)
+    }
+    
+    /// Sample the pins and return an event when a full detent is reached
+    pub fn poll(&mut self) -> Result<Option<InputEvent>, HalError> {
+        let current = self.read_state()?;
+        if current == self.state {
+            return Ok(None);
+        }
+        
+        self.accumulator += QUADRATURE[((self.state << 2) | current) as usize] as i32;
+        self.state = current;
+        
+        let delta = self.accumulator / self.steps_per_detent;
+        if delta == 0 {
+            return Ok(None);
+        }
+        
+        self.accumulator -= delta * self.steps_per_detent;
+        self.position += delta as i64;
+        
+        Ok(Some(InputEvent::EncoderTurned {
+            name: self.name.clone(),
+            delta,
+            position: self.position,
+        }))
+    }
+}
+
+/// Polls registered controls and publishes their events
+pub struct InputManager {
+    buttons: Vec<Button>,
+    encoders: Vec<RotaryEncoder>,
+    poll_interval: Duration,
+}
+
+impl InputManager {
+    pub fn new() -> Self {
+        Self {
+            buttons: Vec::new(),
+            encoders: Vec::new(),
+            poll_interval: Duration::from_millis(2),
+        }
+    }
+    
+    /// Pin sampling interval
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+    
+    /// Add a button
+    pub fn add_button(&mut self, button: Button) {
+        self.buttons.push(button);
+    }
+    
+    /// Add a rotary encoder
+    pub fn add_encoder(&mut self, encoder: RotaryEncoder) {
+        self.encoders.push(encoder);
+    }
+    
+    /// Poll every control once
+    pub fn poll(&mut self) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+        
+        for button in &mut self.buttons {
+            match button.poll() {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Button {} read failed: {}", button.name(), e),
+            }
+        }
+        
+        for encoder in &mut self.encoders {
+            match encoder.poll() {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Encoder {} read failed: {}", encoder.name(), e),
+            }
+        }
+        
+        events
+    }
+    
+    /// Start polling on a background thread; stops when the receiver is dropped
+    pub fn spawn(mut self) -> mpsc::Receiver<InputEvent> {
+        let (tx, rx) = mpsc::channel(32);
+        
+        std::thread::spawn(move || {
+            loop {
+                for event in self.poll() {
+                    tracing::debug!("Input event: {:?}", event);
+                    if tx.blocking_send(event).is_err() {
+                        return;
+                    }
+                }
+                if tx.is_closed() {
+                    return;
+                }
+                std::thread::sleep(self.poll_interval);
+            }
+        });
+        
+        rx
+    }
+}
+
+impl Default for InputManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
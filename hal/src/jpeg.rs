@@ -0,0 +1,163 @@
+//! JPEG decoding of the luma plane, for MJPEG camera frames
+//!
+//! Most USB cameras stream MJPEG: baseline JPEG frames, usually without
+//! Huffman tables, in which case the standard tables of the JPEG spec
+//! (Annex K) apply. Frames are decoded with `jpeg-decoder`; motion and
+//! brightness analysis only needs grayscale, so colour frames are reduced
+//! to their luma.
+
+use crate::HalError;
+use jpeg_decoder::{Decoder, PixelFormat};
+
+/// APP0 segment marking an AVI1 (MJPEG) frame, which makes the decoder fall
+/// back to the Annex K tables for any Huffman table the frame leaves out
+const AVI1_SEGMENT: [u8; 9] = [0xFF, 0xE0, 0x00, 0x07, b'A', b'V', b'I', b'1', 0x00];
+
+/// Largest decoded image accepted (a 4K frame in RGB is about 25 MiB)
+const MAX_DECODED: usize = 64 * 1024 * 1024;
+
+/// Decoded luma plane
+#[derive(Debug, Clone)]
+pub struct Luma {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height` samples, row-major
+    pub pixels: Vec<u8>,
+}
+
+fn corrupt(what: impl std::fmt::Display) -> HalError {
+    HalError::CommunicationError(format!("Corrupt JPEG: {}", what))
+}
+
+/// Decode the luma plane of a JPEG image
+pub fn decode_luma(data: &[u8]) -> Result<Luma, HalError> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(corrupt("missing start of image"));
+    }
+    
+    let mut frame = Vec::with_capacity(data.len() + AVI1_SEGMENT.len());
+    frame.extend_from_slice(&data[..2]);
+    frame.extend_from_slice(&AVI1_SEGMENT);
+    frame.extend_from_slice(&data[2..]);
+    
+    let mut decoder = Decoder::new(frame.as_slice());
+    decoder.set_max_decoding_buffer_size(MAX_DECODED);
+    // The decoder sizes its planes from the frame header before checking the
+    // limit, so a corrupt header must be caught here
+    decoder.read_info().map_err(corrupt)?;
+    let info = decoder.info().ok_or_else(|| corrupt("no frame header"))?;
+    if info.width as usize * info.height as usize * info.pixel_format.pixel_bytes() > MAX_DECODED {
+        return Err(corrupt(format!("{}x{} frame is too large", info.width, info.height)));
+    }
+    let pixels = decoder.decode().map_err(corrupt)?;
+    
+    let pixels = match info.pixel_format {
+        PixelFormat::L8 => pixels,
+        // BT.601 weights, as in the JFIF YCbCr conversion
+        PixelFormat::RGB24 => pixels.chunks_exact(3)
+            .map(|rgb| ((77 * rgb[0] as u32 + 150 * rgb[1] as u32 + 29 * rgb[2] as u32 + 128) >> 8) as u8)
+            .collect(),
+        format => return Err(HalError::InvalidConfig(format!("Unsupported JPEG pixel format {:?}", format))),
+    };
+    
+    Ok(Luma {
+        width: info.width as u32,
+        height: info.height as u32,
+        pixels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jpeg_encoder::{ColorType, Encoder, SamplingFactor};
+    
+    const WIDTH: u16 = 64;
+    const HEIGHT: u16 = 48;
+    
+    /// Gray level of the test pattern: a diagonal gradient
+    fn level(x: usize, y: usize) -> u8 {
+        (x * 2 + y * 2) as u8
+    }
+    
+    /// A 4:2:2 colour frame of the test pattern, as a webcam would send it
+    fn encode() -> Vec<u8> {
+        let mut rgb = Vec::new();
+        for y in 0..HEIGHT as usize {
+            for x in 0..WIDTH as usize {
+                rgb.extend_from_slice(&[level(x, y); 3]);
+            }
+        }
+        let mut jpeg = Vec::new();
+        let mut encoder = Encoder::new(&mut jpeg, 90);
+        encoder.set_sampling_factor(SamplingFactor::R_4_2_2);
+        encoder.encode(&rgb, WIDTH, HEIGHT, ColorType::Rgb).unwrap();
+        jpeg
+    }
+    
+    /// The frame with its Huffman table segments removed, as UVC cameras send it
+    fn strip_huffman_tables(jpeg: &[u8]) -> Vec<u8> {
+        let mut out = jpeg[..2].to_vec();
+        let mut pos = 2;
+        loop {
+            let marker = jpeg[pos + 1];
+            if marker == 0xDA {
+                out.extend_from_slice(&jpeg[pos..]);
+                return out;
+            }
+            let len = u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+            if marker != 0xC4 {
+                out.extend_from_slice(&jpeg[pos..pos + 2 + len]);
+            }
+            pos += 2 + len;
+        }
+    }
+    
+    fn assert_pattern(luma: &Luma) {
+        assert_eq!((luma.width, luma.height), (WIDTH as u32, HEIGHT as u32));
+        assert_eq!(luma.pixels.len(), WIDTH as usize * HEIGHT as usize);
+        for (i, &pixel) in luma.pixels.iter().enumerate() {
+            let expected = level(i % WIDTH as usize, i / WIDTH as usize);
+            assert!(pixel.abs_diff(expected) <= 4, "pixel {}: {} != {}", i, pixel, expected);
+        }
+    }
+    
+    #[test]
+    fn decodes_a_frame() {
+        assert_pattern(&decode_luma(&encode()).unwrap());
+    }
+    
+    #[test]
+    fn decodes_a_frame_without_huffman_tables() {
+        let jpeg = encode();
+        let stripped = strip_huffman_tables(&jpeg);
+        assert!(stripped.len() < jpeg.len());
+        assert_pattern(&decode_luma(&stripped).unwrap());
+    }
+    
+    #[test]
+    fn rejects_corrupt_frames() {
+        let jpeg = encode();
+        assert!(decode_luma(&[]).is_err());
+        assert!(decode_luma(&jpeg[2..]).is_err());
+        assert!(decode_luma(&jpeg[..jpeg.len() / 3]).is_err());
+        
+        // Frame header claiming a 65535x65535 image
+        let mut huge = jpeg.clone();
+        let sof = huge.windows(2).position(|w| w == [0xFF, 0xC0]).unwrap();
+        huge[sof + 5..sof + 9].copy_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]);
+        assert!(decode_luma(&huge).is_err());
+        
+        // Invalid Huffman table: 17 codes of length 1
+        let mut bad_table = jpeg.clone();
+        let dht = bad_table.windows(2).position(|w| w == [0xFF, 0xC4]).unwrap();
+        bad_table[dht + 5] = 17;
+        assert!(decode_luma(&bad_table).is_err());
+        
+        // Scan coded with Huffman tables the frame never defines
+        let mut undefined_table = jpeg.clone();
+        let sos = undefined_table.windows(2).position(|w| w == [0xFF, 0xDA]).unwrap();
+        undefined_table[sos + 6] = 0x33;
+        assert!(decode_luma(&undefined_table).is_err());
+    }
+}
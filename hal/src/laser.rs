@@ -0,0 +1,412 @@
+//! Laser grid geometry and break analysis
+//!
+//! A [`LaserGrid`](crate::gpio::LaserGrid) only knows which receivers are
+//! dark. Given where each beam sits (position along the crossing and height
+//! above the floor), a [`BreakTracker`] follows beams going dark and clear
+//! again and, once the grid has been clear for a moment, reports the pass:
+//! which beams broke in what order, the direction and speed of travel and
+//! the span of heights blocked.
+//!
+//! Receivers also respond to ambient light, so sunrise through a window or
+//! a sweeping flashlight can read as a beam change. With a [`StrobeConfig`]
+//! the transmitters are pulsed and receivers sampled in both phases: only a
+//! receiver lit while its laser is on and dark while it is off sees the beam.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime};
+#[cfg(feature = "gpio")]
+use crate::{gpio::LaserGrid, HalError};
+#[cfg(feature = "gpio")]
+use tokio::sync::mpsc;
+
+/// Where one beam crosses the monitored space
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeamGeometry {
+    pub name: String,
+    /// Distance along the crossing (m), e.g. from the doorway
+    #[serde(default)]
+    pub position: f64,
+    /// Height above the floor (m)
+    #[serde(default)]
+    pub height: f64,
+}
+
+impl BeamGeometry {
+    /// Beams named `beam_<i>`, one metre apart at floor level
+    pub fn default_layout(count: usize) -> Vec<Self> {
+        (0..count)
+            .map(|i| Self {
+                name: format!("beam_{}", i),
+                position: i as f64,
+                height: 0.0,
+            })
+            .collect()
+    }
+}
+
+/// Transmitter strobing for ambient light rejection
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StrobeConfig {
+    /// Pulse frequency (Hz)
+    #[serde(default = "default_strobe_hz")]
+    pub frequency_hz: f64,
+    /// Share of each period the lasers are on (0-1)
+    #[serde(default = "default_duty")]
+    pub duty: f64,
+    /// Pulses per sample; the majority decides each beam's state
+    #[serde(default = "default_cycles")]
+    pub cycles: u32,
+}
+
+fn default_strobe_hz() -> f64 { 500.0 }
+fn default_duty() -> f64 { 0.5 }
+fn default_cycles() -> u32 { 3 }
+
+impl Default for StrobeConfig {
+    fn default() -> Self {
+        Self {
+            frequency_hz: default_strobe_hz(),
+            duty: default_duty(),
+            cycles: default_cycles(),
+        }
+    }
+}
+
+impl StrobeConfig {
+    /// Lasers-on and lasers-off time of one pulse
+    pub fn phases(&self) -> (Duration, Duration) {
+        let period = Duration::from_secs_f64(1.0 / self.frequency_hz.clamp(1.0, 100_000.0));
+        let on = period.mul_f64(self.duty.clamp(0.05, 0.95));
+        (on, period - on)
+    }
+}
+
+/// What a strobed receiver saw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeamSignal {
+    /// Lit with the laser on, dark with it off
+    Intact,
+    /// Dark in both phases
+    Broken,
+    /// Lit with the laser off: ambient light swamps the receiver
+    Blinded,
+}
+
+/// Manifest entry for a laser grid (`[[laser_grid]]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaserGridConfig {
+    pub name: String,
+    /// Transmitter GPIO pins, one per beam
+    pub tx_pins: Vec<u32>,
+    /// Receiver GPIO pins, in the same order
+    pub rx_pins: Vec<u32>,
+    /// Beam geometry in pin order (default: [`BeamGeometry::default_layout`])
+    #[serde(default)]
+    pub beams: Vec<BeamGeometry>,
+    /// Receiver poll interval
+    #[serde(default = "default_poll_ms")]
+    pub poll_ms: u64,
+    /// Time the grid must be clear before a pass is reported
+    #[serde(default = "default_settle_ms")]
+    pub settle_ms: u64,
+    /// Pulse the lasers to reject ambient light (steady beams when unset)
+    #[serde(default)]
+    pub strobe: Option<StrobeConfig>,
+    /// Zone the grid covers
+    #[serde(default)]
+    pub zone: Option<String>,
+}
+
+fn default_poll_ms() -> u64 { 10 }
+fn default_settle_ms() -> u64 { 500 }
+
+impl LaserGridConfig {
+    /// Beam geometry, falling back to the default layout
+    pub fn geometry(&self) -> Vec<BeamGeometry> {
+        if self.beams.is_empty() {
+            BeamGeometry::default_layout(self.rx_pins.len())
+        } else {
+            self.beams.clone()
+        }
+    }
+}
+
+/// One beam going dark during a pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeamBreak {
+    pub beam: String,
+    pub position: f64,
+    pub height: f64,
+    /// Time from the start of the pass to the break
+    pub offset: Duration,
+    /// How long the beam stayed dark
+    pub duration: Duration,
+}
+
+/// Direction of travel through the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassDirection {
+    /// Towards higher beam positions
+    Forward,
+    /// Towards lower beam positions
+    Backward,
+    /// Beams at a single position broke
+    Stationary,
+    /// Beams broke out of positional order
+    Erratic,
+}
+
+/// A completed pass through the grid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakAnalysis {
+    pub grid: String,
+    pub started: SystemTime,
+    /// From the first break until the last beam cleared
+    pub duration: Duration,
+    /// Every break, in the order they happened
+    pub breaks: Vec<BeamBreak>,
+    pub direction: PassDirection,
+    /// Speed between the first and last position broken (m/s)
+    pub speed: Option<f64>,
+    /// Mean position of the beams broken (m)
+    pub position: f64,
+    /// Lowest and highest beam broken (m)
+    pub height_range: (f64, f64),
+}
+
+impl BreakAnalysis {
+    /// Beams in the order they first broke
+    pub fn order(&self) -> Vec<&str> {
+        let mut order: Vec<&str> = Vec::new();
+        for b in &self.breaks {
+            if !order.contains(&b.beam.as_str()) {
+                order.push(&b.beam);
+            }
+        }
+        order
+    }
+    
+    /// Break order as `beam_a>beam_b>...`
+    pub fn path(&self) -> String {
+        self.order().join(">")
+    }
+}
+
+/// Beam being tracked during a pass
+#[derive(Debug, Clone)]
+struct OpenBreak {
+    beam: usize,
+    broken_at: Instant,
+    cleared_at: Option<Instant>,
+}
+
+/// Turns receiver states into completed passes
+#[derive(Debug, Clone)]
+pub struct BreakTracker {
+    grid: String,
+    beams: Vec<BeamGeometry>,
+    settle: Duration,
+    previous: Vec<bool>,
+    breaks: Vec<OpenBreak>,
+    started: Option<(Instant, SystemTime)>,
+    clear_since: Option<Instant>,
+}
+
+impl BreakTracker {
+    pub fn new(grid: &str, beams: Vec<BeamGeometry>, settle: Duration) -> Self {
+        Self {
+            grid: grid.to_string(),
+            previous: vec![false; beams.len()],
+            beams,
+            settle,
+            breaks: Vec::new(),
+            started: None,
+            clear_since: None,
+        }
+    }
+    
+    /// Beam geometry in receiver order
+    pub fn beams(&self) -> &[BeamGeometry] {
+        &self.beams
+    }
+    
+    /// Feed the broken state of each beam; returns a pass once it completes
+    pub fn update(&mut self, broken: &[bool], now: Instant) -> Option<BreakAnalysis> {
+        for (beam, (&is_broken, was_broken)) in broken.iter().zip(self.previous.iter_mut()).enumerate() {
+            match (is_broken, *was_broken) {
+                (true, false) => {
+                    self.started.get_or_insert((now, SystemTime::now()));
+                    self.breaks.push(OpenBreak { beam, broken_at: now, cleared_at: None });
+                }
+                (false, true) => {
+                    if let Some(open) = self.breaks.iter_mut().rev().find(|b| b.beam == beam) {
+                        open.cleared_at = Some(now);
+                    }
+                }
+                _ => {}
+            }
+            *was_broken = is_broken;
+        }
+        
+        self.started?;
+        if broken.iter().any(|&b| b) {
+            self.clear_since = None;
+            return None;
+        }
+        
+        let clear_since = *self.clear_since.get_or_insert(now);
+        if now.duration_since(clear_since) < self.settle {
+            return None;
+        }
+        self.finish()
+    }
+    
+    /// Summarize and reset the current pass
+    fn finish(&mut self) -> Option<BreakAnalysis> {
+        let (start, started) = self.started.take()?;
+        self.clear_since = None;
+        let open = std::mem::take(&mut self.breaks);
+        
+        let breaks: Vec<BeamBreak> = open.iter()
+            .filter_map(|b| {
+                let geometry = self.beams.get(b.beam)?;
+                let cleared = b.cleared_at.unwrap_or(b.broken_at);
+                Some(BeamBreak {
+                    beam: geometry.name.clone(),
+                    position: geometry.position,
+                    height: geometry.height,
+                    offset: b.broken_at.duration_since(start),
+                    duration: cleared.duration_since(b.broken_at),
+                })
+            })
+            .collect();
+        if breaks.is_empty() {
+            return None;
+        }
+        
+        let end = open.iter().filter_map(|b| b.cleared_at).max().unwrap_or(start);
+        let n = breaks.len() as f64;
+        let heights = breaks.iter().fold((f64::MAX, f64::MIN), |(lo, hi), b| (lo.min(b.height), hi.max(b.height)));
+        let (direction, speed) = travel(&breaks);
+        
+        Some(BreakAnalysis {
+            grid: self.grid.clone(),
+            started,
+            duration: end.duration_since(start),
+            direction,
+            speed,
+            position: breaks.iter().map(|b| b.position).sum::<f64>() / n,
+            height_range: heights,
+            breaks,
+        })
+    }
+}
+
+/// Direction and speed from the positions in first-break order
+fn travel(breaks: &[BeamBreak]) -> (PassDirection, Option<f64>) {
+    // First break at each distinct position
+    let mut firsts: Vec<(f64, Duration)> = Vec::new();
+    for b in breaks {
+        if !firsts.iter().any(|(p, _)| (p - b.position).abs() < f64::EPSILON) {
+            firsts.push((b.position, b.offset));
+        }
+    }
+    
+    let (Some(&(first_pos, first_t)), Some(&(last_pos, last_t))) = (firsts.first(), firsts.last()) else {
+        return (PassDirection::Stationary, None);
+    };
+    if firsts.len() < 2 {
+        return (PassDirection::Stationary, None);
+    }
+    
+    let steps: Vec<f64> = firsts.windows(2).map(|w| w[1].0 - w[0].0).collect();
+    let direction = if steps.iter().all(|&s| s > 0.0) {
+        PassDirection::Forward
+    } else if steps.iter().all(|&s| s < 0.0) {
+        PassDirection::Backward
+    } else {
+        PassDirection::Erratic
+    };
+    
+    let elapsed = (last_t - first_t).as_secs_f64();
+    let speed = (elapsed > 0.0).then(|| (last_pos - first_pos).abs() / elapsed);
+    (direction, speed)
+}
+
+/// Polls a laser grid on a background thread and reports passes
+#[cfg(feature = "gpio")]
+pub struct LaserGridMonitor {
+    grid: LaserGrid,
+    tracker: BreakTracker,
+    poll_interval: Duration,
+}
+
+#[cfg(feature = "gpio")]
+impl LaserGridMonitor {
+    /// Open the grid's pins and switch the lasers on
+    pub fn open(config: &LaserGridConfig) -> Result<Self, HalError> {
+        let beams = config.geometry();
+        if beams.len() != config.rx_pins.len() {
+            return Err(HalError::InvalidConfig(format!(
+                "{}: {} beams configured for {} receivers", config.name, beams.len(), config.rx_pins.len()
+            )));
+        }
+        
+        let grid = LaserGrid::new(&config.tx_pins, &config.rx_pins)?;
+        let grid = match config.strobe {
+            Some(strobe) => grid.with_strobe(strobe),
+            None => {
+                grid.enable()?;
+                grid
+            }
+        };
+        
+        Ok(Self {
+            grid,
+            tracker: BreakTracker::new(&config.name, beams, Duration::from_millis(config.settle_ms)),
+            poll_interval: Duration::from_millis(config.poll_ms.max(1)),
+        })
+    }
+    
+    /// Poll until the receiver is dropped, sending each completed pass
+    pub fn spawn(mut self) -> mpsc::Receiver<BreakAnalysis> {
+        let (tx, rx) = mpsc::channel(32);
+        
+        std::thread::spawn(move || {
+            let mut rejections = 0;
+            let mut blinded = false;
+            loop {
+                match self.grid.check_beams() {
+                    Ok(broken) => {
+                        if let Some(pass) = self.tracker.update(&broken, Instant::now()) {
+                            tracing::debug!("Laser grid pass: {} ({:?})", pass.path(), pass.direction);
+                            if tx.blocking_send(pass).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("Laser grid read failed: {}", e),
+                }
+                
+                // Report ambient light once per episode, not per sample
+                let total = self.grid.ambient_rejections();
+                let now_blinded = total > rejections;
+                if now_blinded && !blinded {
+                    tracing::info!("Ambient light on laser grid; holding beam states");
+                }
+                blinded = now_blinded;
+                rejections = total;
+                
+                if tx.is_closed() {
+                    break;
+                }
+                std::thread::sleep(self.poll_interval);
+            }
+            let _ = self.grid.disable();
+        });
+        
+        rx
+    }
+}
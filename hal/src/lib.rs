@@ -7,11 +7,29 @@
 //! 
 //! - [`i2c`] - I2C bus interface for sensors like HMC5883L, BME280, MLX90614
 //! - [`spi`] - SPI interface for high-precision ADCs (ADS1256, MCP3008)
-//! - [`gpio`] - GPIO for PIR sensors, laser grids, and PWM control
+//! - [`adc`] - Manifest-defined analog sensors on ADC channels, with block capture
+//! - [`gpio`] - GPIO (character device or sysfs) for PIR, vibration and door contact sensors, laser grids, and PWM control
+//! - [`laser`] - Laser grid geometry and beam-break analysis
+//! - [`stepper`] - Stepper motors and rotating scan platforms
+//! - [`input`] - Buttons and rotary encoders for headless field control
 //! - [`usb`] - USB device enumeration and serial communication
+//! - [`serial_sensor`] - Manifest-defined ASCII serial sensors
 //! - [`audio`] - ALSA audio capture for EVP detection
-//! - [`camera`] - V4L2 video capture, thermal imaging, night vision
+//! - [`camera`] - V4L2 video capture, thermal imaging, night vision, H.264 encoding
+//! - [`jpeg`] - JPEG luma decoding for MJPEG cameras
+//! - [`frames`] - Per-camera frame drop and latency accounting
+//! - [`clock`] - Common stream clock and per-stream capture delays
+//! - [`hotplug`] - Kernel uevent watcher for devices plugged in and out
+//! - [`fault`] - Scripted sensor faults for resilience testing
 //! - [`sdr`] - RTL-SDR for EMF spectrum analysis
+//! - [`direction`] - RF bearing estimates from switched directional antennas
+//! - [`ehal`] - Adapter for external embedded-hal drivers
+//! - [`platform`] - Linux backends and no-op stubs for other platforms
+//! - [`buslog`] - I2C/SPI/serial transaction logging (sniffer mode)
+//! - [`calibration`] - Persistent calibration offsets with expiry
+//! - [`alias`] - Logical sensor names that survive bus renumbering
+//!
+//! Each hardware module is gated by the Cargo feature of the same name.
 //!
 //! # Example
 //! 
@@ -32,27 +50,105 @@
 //! }
 //! ```
 
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::mpsc;
 
+pub mod platform;
+pub mod buslog;
+pub mod calibration;
+pub mod alias;
+pub mod sim;
+#[cfg(feature = "i2c")]
 pub mod i2c;
+#[cfg(feature = "spi")]
 pub mod spi;
+#[cfg(feature = "spi")]
+pub mod adc;
+#[cfg(feature = "gpio")]
 pub mod gpio;
+pub mod laser;
+pub mod illuminator;
+pub mod mixer;
+pub mod thermal;
+pub mod frames;
+pub mod clock;
+pub mod hotplug;
+pub mod fault;
+pub mod watchdog;
+pub mod schedule;
+#[cfg(feature = "gpio")]
+pub mod stepper;
+#[cfg(feature = "gpio")]
+pub mod input;
+#[cfg(feature = "usb")]
 pub mod usb;
+#[cfg(feature = "usb")]
+pub mod serial_sensor;
+#[cfg(feature = "audio")]
 pub mod audio;
+#[cfg(feature = "camera")]
 pub mod camera;
+#[cfg(feature = "mjpeg")]
+pub mod jpeg;
+#[cfg(feature = "sdr")]
 pub mod sdr;
+#[cfg(feature = "sdr")]
+pub mod direction;
+pub mod ehal;
+
+pub use calibration::{CalibrationStore, CalibrationRecord};
+pub use alias::{SensorAlias, SensorAliases};
+pub use sim::{SimConfig, SimSensorConfig, SimCameraConfig, SimSdrConfig, SimulatedSensor, SimulatedCamera, SimulatedSdr};
 
 // Re-exports for convenience
-pub use i2c::{I2CBus, I2CSensor, HMC5883L, BME280, MLX90614};
+#[cfg(feature = "i2c")]
+pub use i2c::{AsyncI2CBus, Bme280Calibration, Bme280Channel, ChipProbe, I2CBus, I2CSensor, I2cDevice, Mlx90614Channel, HMC5883L, BME280, MLX90614};
+#[cfg(feature = "spi")]
 pub use spi::{SpiDevice, SpiConfig, SpiMode, ADS1256, MCP3008};
-pub use gpio::{GpioPin, Direction, Level, PIRSensor, LaserGrid, PwmOutput};
+#[cfg(feature = "spi")]
+pub use adc::{AdcChannel, AdcChannelConfig};
+#[cfg(feature = "gpio")]
+pub use gpio::{GpioPin, GpioBackend, CdevGpio, SysfsGpio, Direction, Edge, EdgeEvents, Level, PIRSensor, MotionEvent, ContactSensor, ContactMode, ContactState, VibrationSensor, LaserGrid, PwmOutput};
+#[cfg(feature = "gpio")]
+pub use stepper::{StepperMotor, StepperConfig, Scanner};
+#[cfg(feature = "gpio")]
+pub use input::{Button, RotaryEncoder, InputEvent, InputManager};
+#[cfg(feature = "usb")]
 pub use usb::{UsbSerial, UsbHid, UsbDeviceInfo};
-pub use audio::{AudioCapture, AudioPlayback, AudioFormat, SpiritBox, InfrasoundDetector};
-pub use camera::{Camera, ThermalCamera, NightVisionCamera, Frame, ThermalFrame, VideoFormat};
-pub use sdr::{RtlSdr, SdrConfig, EmfAnalyzer, RadioScanner};
+#[cfg(feature = "usb")]
+pub use serial_sensor::{SerialSensor, SerialSensorConfig};
+#[cfg(feature = "audio")]
+pub use audio::{AudioCapture, AudioClip, AudioClipRecorder, AudioStream, PcmSource, MockAudioCapture, AudioPlayback, AudioFormat, AudioRole, AudioDeviceConfig, SpiritBox, InfrasoundDetector};
+#[cfg(feature = "camera")]
+pub use camera::{Camera, CameraRole, CameraConfig, ThermalCamera, NightVisionCamera, Frame, ThermalFrame, VideoFormat, H264Encoder, EncoderConfig};
+#[cfg(feature = "sdr")]
+pub use sdr::{RtlSdr, IqSource, MockSdr, SdrConfig, Spectrum, SpectrumConfig, EmfAnalyzer, RadioScanner, CalibrationSource, PowerCalibration, SweepConfig, SweepSummary, BandPower, WidebandSweep};
+#[cfg(feature = "sdr")]
+pub use direction::{AntennaPower, BearingEstimate, DirectionConfig};
+#[cfg(all(feature = "sdr", feature = "gpio"))]
+pub use direction::DirectionFinder;
+pub use ehal::{EhalDriverFactory, EhalSensorAdapter, EhalSensorConfig};
+pub use laser::{BeamGeometry, BeamBreak, BeamSignal, BreakAnalysis, BreakTracker, LaserGridConfig, PassDirection, StrobeConfig};
+#[cfg(feature = "gpio")]
+pub use laser::LaserGridMonitor;
+pub use thermal::{DriftCorrection, ReferenceTemperatures, ThermalReference};
+pub use frames::{CameraStats, FrameStats};
+pub use clock::{ClockReport, StreamClock, StreamKind, StreamOffset, SyncClock};
+pub use hotplug::{DeviceEvent, DeviceInfo};
+pub use fault::{FaultInjector, FaultKind, FaultSpec, FaultStatus};
+pub use watchdog::WatchdogEvent;
+pub use schedule::PollSchedule;
+pub use mixer::{AudioRouteConfig, AudioRouteStatus, AudioRoutes, Mixer, MixerLevel};
+pub use illuminator::{BrightnessControl, Illuminator, IlluminatorConfig, IlluminatorMode, IlluminatorStatus, Illuminators, ThermalLimit};
+#[cfg(feature = "gpio")]
+pub use illuminator::IRIlluminator;
 
 /// Hardware device trait
 pub trait HardwareDevice: Send + Sync {
@@ -70,6 +166,13 @@ pub trait HardwareDevice: Send + Sync {
     
     /// Close the device
     fn close(&mut self) -> Result<(), HalError>;
+    
+    /// Hardware serial number, when the device reports one; stored
+    /// calibrations are keyed by it so a swapped device of the same name
+    /// doesn't inherit the old one's offset
+    fn serial(&self) -> Option<String> {
+        None
+    }
 }
 
 /// Sensor trait for data acquisition
@@ -85,10 +188,86 @@ pub trait Sensor: HardwareDevice {
     
     /// Calibrate sensor
     fn calibrate(&mut self, offset: f64) -> Result<(), HalError>;
+    
+    /// Read per-axis values of a vector sensor (empty for scalar sensors)
+    ///
+    /// Reported alongside [`read_value`](Self::read_value), which should
+    /// return the calibrated magnitude.
+    fn read_components(&self) -> Result<Vec<(String, f64)>, HalError> {
+        Ok(Vec::new())
+    }
+    
+    /// Read the other quantities a multi-channel chip measures along with
+    /// [`read_value`](Self::read_value), each in its own unit (e.g. a
+    /// BME280's humidity and pressure next to its temperature)
+    ///
+    /// Ignored for vector sensors, whose payload holds their components.
+    fn read_channels(&self) -> Result<Vec<Channel>, HalError> {
+        Ok(Vec::new())
+    }
+    
+    /// Read the value together with its payload
+    ///
+    /// The default calls [`read_value`](Self::read_value), then
+    /// [`read_components`](Self::read_components) or
+    /// [`read_channels`](Self::read_channels); chips that deliver everything
+    /// in one register burst override it so a poll touches the bus once.
+    fn read_sample(&self) -> Result<(f64, ReadingPayload), HalError> {
+        let value = self.read_value()?;
+        let components = self.read_components()?;
+        if !components.is_empty() {
+            return Ok((value, ReadingPayload::Vector(components)));
+        }
+        let channels = self.read_channels()?;
+        if !channels.is_empty() {
+            return Ok((value, ReadingPayload::Channels(channels)));
+        }
+        Ok((value, ReadingPayload::Scalar))
+    }
+    
+    /// Switch the hardware into (or out of) a high sample-rate mode for bursts
+    ///
+    /// Sensors whose conversion rate is fixed keep the default no-op.
+    fn set_high_rate(&mut self, _enabled: bool) -> Result<(), HalError> {
+        Ok(())
+    }
+    
+    /// Sample rate of the hardware's block capture mode, if it has one
+    ///
+    /// Bursts of such sensors fill a buffer at this rate with
+    /// [`read_block`](Self::read_block) instead of being polled.
+    fn block_rate(&self) -> Option<f64> {
+        None
+    }
+    
+    /// Capture `count` consecutive samples at [`block_rate`](Self::block_rate)
+    fn read_block(&self, _count: usize) -> Result<Vec<f64>, HalError> {
+        Err(HalError::InvalidConfig(format!("{} has no block capture mode", self.name())))
+    }
+    
+    /// Whether readings are states (contact open/closed, meter LEDs) that
+    /// legitimately repeat, exempting the sensor from stuck-value detection
+    fn discrete(&self) -> bool {
+        false
+    }
+    
+    /// Bus shared with other sensors (its device path)
+    ///
+    /// Reads on one bus are serialized; different buses, and sensors with a
+    /// dedicated connection (`None`), are read concurrently.
+    fn bus(&self) -> Option<&str> {
+        None
+    }
+    
+    /// Polls per second the sensor is best read at (`None` for the global
+    /// poll interval); a `[poll.<sensor>]` entry overrides it
+    fn poll_rate(&self) -> Option<f64> {
+        None
+    }
 }
 
 /// Device types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum DeviceType {
     I2C,
     SPI,
@@ -98,6 +277,7 @@ pub enum DeviceType {
     Camera,
     SDR,
     Serial,
+    Simulated,
 }
 
 /// HAL Error types
@@ -125,7 +305,45 @@ pub enum HalError {
     CalibrationRequired,
 }
 
+/// Typed data carried by a reading beyond its scalar value
+#[derive(Debug, Clone, Default)]
+pub enum ReadingPayload {
+    /// Value only (most sensors)
+    #[default]
+    Scalar,
+    /// Named axis values for vector sensors (e.g. x/y/z)
+    Vector(Vec<(String, f64)>),
+    /// Further quantities of a multi-channel sensor, each in its own unit
+    Channels(Vec<Channel>),
+    /// Block of PCM samples
+    Audio { sample_rate: u32, samples: Arc<[i16]> },
+    /// Block of analog samples in the reading's unit (e.g. an EMF probe on an ADC)
+    Waveform { sample_rate: f64, samples: Arc<[f64]> },
+    /// Power spectrum in dB, `bins[i]` at `start_hz + i * bin_hz`
+    Spectrum { start_hz: f64, bin_hz: f64, bins: Arc<[f64]> },
+    /// Grayscale video frame, shared rather than copied
+    Frame { width: u32, height: u32, luma: Arc<[u8]> },
+}
+
+/// One quantity of a multi-channel sensor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    pub name: String,
+    pub value: f64,
+    pub unit: String,
+}
+
+impl Channel {
+    pub fn new(name: &str, value: f64, unit: &str) -> Self {
+        Self { name: name.to_string(), value, unit: unit.to_string() }
+    }
+}
+
 /// Sensor reading with metadata
+///
+/// `value` is always a scalar summary (magnitude, RMS level, peak power,
+/// brightness) so every reading can be baselined; `payload` carries the
+/// full data for modality-specific detectors.
 #[derive(Debug, Clone)]
 pub struct SensorReading {
     pub sensor_name: String,
@@ -133,14 +351,244 @@ pub struct SensorReading {
     pub unit: String,
     pub timestamp: std::time::SystemTime,
     pub quality: f32,  // 0.0 - 1.0
+    pub payload: ReadingPayload,
+}
+
+impl SensorReading {
+    /// Scalar reading taken now
+    pub fn new(sensor_name: &str, value: f64, unit: &str) -> Self {
+        Self {
+            sensor_name: sensor_name.to_string(),
+            value,
+            unit: unit.to_string(),
+            timestamp: std::time::SystemTime::now(),
+            quality: 1.0,
+            payload: ReadingPayload::Scalar,
+        }
+    }
+    
+    /// Take one reading from a sensor
+    pub fn sample(name: &str, sensor: &dyn Sensor) -> Result<Self, HalError> {
+        let _span = tracing::debug_span!("read", device = name, kind = ?sensor.device_type()).entered();
+        let (value, payload) = sensor.read_sample()?;
+        let mut reading = Self::new(name, value, sensor.unit());
+        reading.payload = payload;
+        Ok(reading)
+    }
+    
+    /// Audio chunk; the value is the RMS level in dBFS
+    pub fn audio(sensor_name: &str, sample_rate: u32, samples: Vec<i16>) -> Self {
+        let rms = glowbarn_dsp::stats::rms(&glowbarn_dsp::pcm_to_f64(&samples));
+        let dbfs = 20.0 * (rms.max(1.0) / 32768.0).log10();
+        
+        let mut reading = Self::new(sensor_name, dbfs, "dBFS");
+        reading.payload = ReadingPayload::Audio { sample_rate, samples: samples.into() };
+        reading
+    }
+    
+    /// Analog sample block; the value is its RMS in `unit`
+    pub fn waveform(sensor_name: &str, unit: &str, sample_rate: f64, samples: Vec<f64>) -> Self {
+        let rms = glowbarn_dsp::stats::rms(&samples);
+        
+        let mut reading = Self::new(sensor_name, rms, unit);
+        reading.payload = ReadingPayload::Waveform { sample_rate, samples: samples.into() };
+        reading
+    }
+    
+    /// Power spectrum; the value is the strongest bin in dB
+    pub fn spectrum(sensor_name: &str, start_hz: f64, bin_hz: f64, bins: Vec<f64>) -> Self {
+        let peak = bins.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let peak = if peak.is_finite() { peak } else { 0.0 };
+        
+        let mut reading = Self::new(sensor_name, peak, "dB");
+        reading.payload = ReadingPayload::Spectrum { start_hz, bin_hz, bins: bins.into() };
+        reading
+    }
+    
+    /// Grayscale frame; the value is the mean brightness (0-255)
+    pub fn frame(sensor_name: &str, width: u32, height: u32, luma: Vec<u8>) -> Self {
+        let brightness = if luma.is_empty() {
+            0.0
+        } else {
+            luma.iter().map(|&p| p as f64).sum::<f64>() / luma.len() as f64
+        };
+        
+        let mut reading = Self::new(sensor_name, brightness, "luma");
+        reading.payload = ReadingPayload::Frame { width, height, luma: luma.into() };
+        reading
+    }
+    
+    /// Axis values of a vector reading (empty otherwise)
+    pub fn components(&self) -> &[(String, f64)] {
+        match self.payload {
+            ReadingPayload::Vector(ref components) => components,
+            _ => &[],
+        }
+    }
+    
+    /// Value of a named component
+    pub fn component(&self, name: &str) -> Option<f64> {
+        self.components().iter().find(|(n, _)| n == name).map(|(_, v)| *v)
+    }
+    
+    /// Further channels of a multi-channel reading (empty otherwise)
+    pub fn channels(&self) -> &[Channel] {
+        match self.payload {
+            ReadingPayload::Channels(ref channels) => channels,
+            _ => &[],
+        }
+    }
+    
+    /// The reading followed by one scalar reading per channel, named
+    /// `<sensor>.<channel>`, so each quantity is baselined, streamed and
+    /// recorded in its own unit
+    pub fn split_channels(self) -> Vec<Self> {
+        let channels: Vec<Self> = self.channels().iter()
+            .map(|channel| Self {
+                sensor_name: format!("{}.{}", self.sensor_name, channel.name),
+                value: channel.value,
+                unit: channel.unit.clone(),
+                timestamp: self.timestamp,
+                quality: self.quality,
+                payload: ReadingPayload::Scalar,
+            })
+            .collect();
+        std::iter::once(self).chain(channels).collect()
+    }
+}
+
+/// High-rate samples of one sensor captured around an event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurstTrace {
+    pub sensor_name: String,
+    pub unit: String,
+    /// Sample spacing requested for the burst
+    pub interval: Duration,
+    /// Rate of a block capture; its samples are evenly spaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<f64>,
+    /// (seconds since burst start, value)
+    pub samples: Vec<(f64, f64)>,
+}
+
+impl BurstTrace {
+    /// Trace of a block captured at `sample_rate`
+    pub fn block(sensor_name: &str, unit: &str, sample_rate: f64, values: Vec<f64>) -> Self {
+        Self {
+            sensor_name: sensor_name.to_string(),
+            unit: unit.to_string(),
+            interval: Duration::from_secs_f64(1.0 / sample_rate),
+            sample_rate: Some(sample_rate),
+            samples: values.into_iter()
+                .enumerate()
+                .map(|(i, v)| (i as f64 / sample_rate, v))
+                .collect(),
+        }
+    }
+    
+    /// Waveform reading of a block capture, for the DSP and fusion detectors
+    pub fn waveform(&self) -> Option<SensorReading> {
+        let sample_rate = self.sample_rate?;
+        let values = self.samples.iter().map(|&(_, v)| v).collect();
+        let mut reading = SensorReading::waveform(&self.sensor_name, &self.unit, sample_rate, values);
+        reading.quality = if self.samples.is_empty() { 0.0 } else { 1.0 };
+        Some(reading)
+    }
+}
+
+/// Poll loop timing
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PollStats {
+    pub ticks: u64,
+    /// Ticks whose reads took longer than the poll interval
+    pub overruns: u64,
+    pub last_tick: Duration,
+    pub worst_tick: Duration,
+}
+
+/// Health of one device or sensor
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceHealth {
+    pub name: String,
+    pub device_type: DeviceType,
+    pub ready: bool,
+    /// Failed reads since the last successful one (sensors only)
+    pub consecutive_failures: u32,
+    /// Failed reads since startup (sensors only)
+    pub read_errors: u64,
+    pub last_error: Option<String>,
+    pub last_success: Option<SystemTime>,
+    /// Consecutive reads with a bit-identical value (sensors only)
+    pub identical_reads: u32,
+    /// Suspected stuck: `identical_reads` reached
+    /// [`HalConfig::stuck_reads`]; its readings carry quality 0 until the
+    /// value changes
+    pub stuck: bool,
+    /// Reported offline by the watchdog: no successful read for
+    /// [`HalConfig::watchdog_timeout`]
+    pub offline: bool,
+    /// Polling suspended with [`HardwareManager::pause_sensor`]
+    pub paused: bool,
+    /// Polls per second (sensors only; `None` for the global poll interval)
+    pub poll_rate_hz: Option<f64>,
+}
+
+/// Read outcomes of one sensor
+#[derive(Debug, Clone, Default)]
+struct ReadHealth {
+    consecutive_failures: u32,
+    total_failures: u64,
+    last_error: Option<String>,
+    last_success: Option<SystemTime>,
+    /// Hash of the last value's bits
+    fingerprint: Option<u64>,
+    identical_reads: u32,
+    /// Exempt from stuck-value detection
+    discrete: bool,
+    /// Reported offline by the watchdog
+    offline: bool,
+}
+
+impl ReadHealth {
+    fn stuck(&self, stuck_reads: u32) -> bool {
+        !self.discrete && stuck_reads > 0 && self.identical_reads >= stuck_reads
+    }
 }
 
 /// Hardware manager
 pub struct HardwareManager {
     devices: Arc<RwLock<HashMap<String, Box<dyn HardwareDevice>>>>,
     sensors: Arc<RwLock<HashMap<String, Box<dyn Sensor>>>>,
+    bursting: Arc<Mutex<HashSet<String>>>,
+    poll_stats: Arc<Mutex<PollStats>>,
+    read_health: Arc<Mutex<HashMap<String, ReadHealth>>>,
+    schedules: Arc<Mutex<HashMap<String, PollSchedule>>>,
+    paused: Arc<Mutex<HashSet<String>>>,
+    /// Sensors taken out of `sensors` while they are reconfigured
+    detached: Mutex<HashSet<String>>,
     reading_tx: mpsc::Sender<SensorReading>,
+    poller: Mutex<Option<Poller>>,
+    illuminators: Illuminators,
+    audio_routes: AudioRoutes,
+    references: ReferenceTemperatures,
+    frame_stats: FrameStats,
+    clock: SyncClock,
+    faults: FaultInjector,
+    device_tx: mpsc::Sender<DeviceEvent>,
+    device_rx: Option<mpsc::Receiver<DeviceEvent>>,
+    watchdog_tx: mpsc::Sender<WatchdogEvent>,
+    watchdog_rx: Option<mpsc::Receiver<WatchdogEvent>>,
+    aliases: SensorAliases,
+    /// External driver factories for manifest entries, by name
+    ehal_drivers: HashMap<String, EhalDriverFactory>,
     config: HalConfig,
+    calibrations: Option<CalibrationStore>,
+}
+
+/// Running polling task and its stop flag
+struct Poller {
+    stop: Arc<AtomicBool>,
+    task: tokio::task::JoinHandle<()>,
 }
 
 /// HAL Configuration
@@ -148,10 +596,49 @@ pub struct HardwareManager {
 pub struct HalConfig {
     pub scan_interval: Duration,
     pub hotplug_enabled: bool,
+    /// Time without a successful read after which a sensor is reported
+    /// offline and re-initialised (zero disables the watchdog)
     pub watchdog_timeout: Duration,
+    /// Allow faults to be injected into sensor reads (testing only)
+    pub fault_injection: bool,
     pub i2c_buses: Vec<String>,
+    /// Register drivers for chips identified on the I2C buses (otherwise
+    /// only aliased chips are registered)
+    pub i2c_auto_detect: bool,
+    /// Bit-identical consecutive reads after which a sensor is suspected
+    /// stuck (0 disables)
+    pub stuck_reads: u32,
+    /// Poll rates of individual sensors, keyed by sensor name or a part of it
+    pub poll_schedules: BTreeMap<String, PollSchedule>,
+    /// Logical names of I2C sensors, registered even without auto-detection
+    pub sensor_aliases: Vec<SensorAlias>,
     pub spi_devices: Vec<String>,
     pub gpio_chip: String,
+    /// Directory for persisted calibration offsets (disabled if unset)
+    pub calibration_dir: Option<PathBuf>,
+    /// Age after which a stored calibration must be redone
+    pub calibration_max_age: Duration,
+    /// Text-protocol serial sensors from the device manifest
+    #[cfg(feature = "usb")]
+    pub serial_sensors: Vec<SerialSensorConfig>,
+    /// Cameras from the device manifest
+    #[cfg(feature = "camera")]
+    pub cameras: Vec<CameraConfig>,
+    /// Audio inputs from the device manifest
+    #[cfg(feature = "audio")]
+    pub audio_devices: Vec<AudioDeviceConfig>,
+    /// Sensors behind external drivers from the device manifest
+    pub ehal_sensors: Vec<EhalSensorConfig>,
+    /// Analog sensors on ADC channels from the device manifest
+    #[cfg(feature = "spi")]
+    pub adc_channels: Vec<AdcChannelConfig>,
+    /// IR illuminators from the device manifest
+    #[cfg(feature = "gpio")]
+    pub illuminators: Vec<IlluminatorConfig>,
+    /// Named audio outputs and their mixer controls
+    pub audio_routes: Vec<AudioRouteConfig>,
+    /// Simulated devices to register, alone or next to the hardware
+    pub simulation: Option<SimConfig>,
 }
 
 impl Default for HalConfig {
@@ -160,9 +647,29 @@ impl Default for HalConfig {
             scan_interval: Duration::from_secs(10),
             hotplug_enabled: true,
             watchdog_timeout: Duration::from_secs(30),
+            fault_injection: false,
             i2c_buses: vec!["/dev/i2c-1".to_string()],
+            i2c_auto_detect: true,
+            stuck_reads: DEFAULT_STUCK_READS,
+            poll_schedules: BTreeMap::new(),
+            sensor_aliases: Vec::new(),
             spi_devices: vec!["/dev/spidev0.0".to_string()],
             gpio_chip: "/dev/gpiochip0".to_string(),
+            calibration_dir: None,
+            calibration_max_age: Duration::from_secs(30 * 24 * 3600),
+            #[cfg(feature = "usb")]
+            serial_sensors: Vec::new(),
+            #[cfg(feature = "camera")]
+            cameras: Vec::new(),
+            #[cfg(feature = "audio")]
+            audio_devices: Vec::new(),
+            ehal_sensors: Vec::new(),
+            #[cfg(feature = "spi")]
+            adc_channels: Vec::new(),
+            #[cfg(feature = "gpio")]
+            illuminators: Vec::new(),
+            audio_routes: Vec::new(),
+            simulation: None,
         }
     }
 }
@@ -171,17 +678,61 @@ impl HardwareManager {
     /// Create new hardware manager
     pub fn new(config: HalConfig) -> (Self, mpsc::Receiver<SensorReading>) {
         let (tx, rx) = mpsc::channel(1000);
+        let (device_tx, device_rx) = mpsc::channel(64);
+        let (watchdog_tx, watchdog_rx) = mpsc::channel(64);
         
         (Self {
             devices: Arc::new(RwLock::new(HashMap::new())),
             sensors: Arc::new(RwLock::new(HashMap::new())),
+            bursting: Arc::new(Mutex::new(HashSet::new())),
+            poll_stats: Arc::new(Mutex::new(PollStats::default())),
+            read_health: Arc::new(Mutex::new(HashMap::new())),
+            schedules: Arc::new(Mutex::new(HashMap::new())),
+            paused: Arc::new(Mutex::new(HashSet::new())),
+            detached: Mutex::new(HashSet::new()),
             reading_tx: tx,
+            poller: Mutex::new(None),
+            illuminators: Illuminators::new(),
+            audio_routes: AudioRoutes::new(config.audio_routes.clone()),
+            references: ReferenceTemperatures::new(),
+            frame_stats: FrameStats::new(),
+            clock: SyncClock::new(),
+            faults: FaultInjector::new(),
+            device_tx,
+            device_rx: Some(device_rx),
+            watchdog_tx,
+            watchdog_rx: Some(watchdog_rx),
+            aliases: SensorAliases::new(config.sensor_aliases.clone()),
+            ehal_drivers: HashMap::new(),
             config,
+            calibrations: None,
         }, rx)
     }
     
     /// Initialize all hardware
     pub async fn init(&mut self) -> Result<(), HalError> {
+        if platform::SIMULATED {
+            tracing::warn!("No hardware backend on this platform, using {} devices", platform::backend_name());
+        }
+        
+        // Open calibration store before sensors are registered
+        if let Some(dir) = self.config.calibration_dir.clone() {
+            match CalibrationStore::open(&dir) {
+                Ok(store) => {
+                    self.calibrations = Some(store.with_max_age(self.config.calibration_max_age));
+                }
+                Err(e) => tracing::warn!("Failed to open calibration store {:?}: {}", dir, e),
+            }
+        }
+        
+        if let Some(sim) = self.config.simulation.clone() {
+            self.init_simulation(&sim);
+            if sim.exclusive {
+                tracing::info!("Simulation only, hardware not scanned");
+                return Ok(());
+            }
+        }
+        
         // Scan I2C buses
         let buses = self.config.i2c_buses.clone();
         for bus in buses {
@@ -189,6 +740,9 @@ impl HardwareManager {
                 tracing::warn!("Failed to scan I2C bus {}: {}", bus, e);
             }
         }
+        for alias in self.aliases.iter().filter(|alias| !self.is_registered(&alias.name)) {
+            tracing::warn!("Sensor alias {} matched no I2C device", alias.name);
+        }
         
         // Initialize GPIO
         if let Err(e) = self.init_gpio().await {
@@ -200,27 +754,122 @@ impl HardwareManager {
             tracing::warn!("Failed to scan USB devices: {}", e);
         }
         
+        // Open manifest-defined serial sensors
+        self.init_serial_sensors();
+        
+        // Open manifest-defined external-driver sensors
+        self.init_ehal_sensors();
+        
+        // Open manifest-defined ADC channels
+        self.init_adc_channels();
+        
+        // Illuminators first, so night vision cameras can drive them
+        self.init_illuminators();
+        
+        // Open manifest-defined cameras
+        self.init_cameras();
+        
         // Initialize audio
         if let Err(e) = self.init_audio().await {
             tracing::warn!("Failed to initialize audio: {}", e);
         }
         
+        // Startup volumes of the output routes
+        for (route, e) in self.audio_routes.apply() {
+            tracing::warn!("Failed to set mixer for audio route {}: {}", route, e);
+        }
+        
+        for (name, e) in self.check_calibrations() {
+            tracing::warn!("Sensor {}: {}", name, e);
+        }
+        
         Ok(())
     }
     
-    /// Scan I2C bus for devices
-    async fn scan_i2c_bus(&mut self, bus: &str) -> Result<Vec<u8>, HalError> {
-        tracing::info!("Scanning I2C bus: {}", bus);
-        i2c::scan_bus(bus)
+    /// Scan I2C bus for devices, registering drivers for aliased chips and,
+    /// when auto-detection is enabled, for all identified chips
+    ///
+    /// `bus` is a device path or an adapter name.
+    #[cfg(feature = "i2c")]
+    async fn scan_i2c_bus(&mut self, bus: &str) -> Result<(), HalError> {
+        let path = i2c::resolve_bus(bus)
+            .ok_or_else(|| HalError::DeviceNotFound(format!("No I2C adapter named {:?}", bus)))?;
+        let adapter = i2c::adapter_name(&path);
+        match &adapter {
+            Some(adapter) => tracing::info!("Scanning I2C bus: {} ({})", path, adapter),
+            None => tracing::info!("Scanning I2C bus: {}", path),
+        }
+        
+        for device in &i2c::identify_bus(&path)? {
+            let alias = {
+                let sensors = self.sensors.read().unwrap();
+                self.aliases.resolve(device.model, device.address, &path, adapter.as_deref(), |name| sensors.contains_key(name))
+                    .map(str::to_string)
+            };
+            match (device.probe(), alias) {
+                (None, Some(alias)) => tracing::warn!("I2C device at 0x{:02X} on {} matches alias {}, but is not identified",
+                    device.address, path, alias),
+                (None, None) => tracing::warn!("Unknown I2C device at 0x{:02X} on {}", device.address, path),
+                (Some(probe), alias) => {
+                    tracing::info!("I2C {} {}", path, device);
+                    if alias.is_none() && !self.config.i2c_auto_detect {
+                        continue;
+                    }
+                    match probe.driver {
+                        Some(open) => self.register_detected(&path, device, alias, open),
+                        None => tracing::info!("No driver for {} at 0x{:02X}, not registered", probe.model, device.address),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Open, initialize and register the driver of a detected chip
+    ///
+    /// The sensor takes its alias if it has one, otherwise it is named after
+    /// the chip, qualified by bus and address when that name is already taken.
+    #[cfg(feature = "i2c")]
+    fn register_detected(&self, bus: &str, device: &I2cDevice, alias: Option<String>, open: i2c::DriverFactory) {
+        let model = device.model.unwrap_or("i2c");
+        let name = alias.unwrap_or_else(|| {
+            if self.has_sensor(model) {
+                let bus_name = bus.rsplit('/').next().unwrap_or(bus);
+                format!("{}-{}-{:02x}", model, bus_name, device.address)
+            } else {
+                model.to_string()
+            }
+        });
+        
+        let result = open(bus, device.address).and_then(|mut sensor| {
+            sensor.init()?;
+            Ok(sensor)
+        });
+        match result {
+            Ok(sensor) => {
+                tracing::info!("Registered detected sensor {} ({} 0x{:02X})", name, bus, device.address);
+                self.register_sensor(&name, sensor);
+            }
+            Err(e) => tracing::warn!("Failed to open detected {} on {}: {}", model, bus, e),
+        }
+    }
+    
+    #[cfg(not(feature = "i2c"))]
+    async fn scan_i2c_bus(&mut self, bus: &str) -> Result<(), HalError> {
+        tracing::debug!("I2C support disabled, skipping bus {}", bus);
+        Ok(())
     }
     
     /// Initialize GPIO
     async fn init_gpio(&mut self) -> Result<(), HalError> {
         tracing::info!("Initializing GPIO: {}", self.config.gpio_chip);
+        #[cfg(feature = "gpio")]
+        gpio::set_default_chip(&self.config.gpio_chip);
         Ok(())  // GPIO pins are initialized on demand
     }
     
     /// Scan USB devices
+    #[cfg(feature = "usb")]
     async fn scan_usb_devices(&mut self) -> Result<(), HalError> {
         tracing::info!("Scanning USB devices");
         let devices = usb::enumerate_devices()?;
@@ -232,37 +881,713 @@ impl HardwareManager {
         Ok(())
     }
     
-    /// Initialize audio subsystem
+    #[cfg(not(feature = "usb"))]
+    async fn scan_usb_devices(&mut self) -> Result<(), HalError> {
+        Ok(())
+    }
+    
+    /// Open and register serial sensors from the manifest
+    #[cfg(feature = "usb")]
+    fn init_serial_sensors(&mut self) {
+        for config in &self.config.serial_sensors {
+            if let Err(e) = self.open_serial_sensor(config) {
+                tracing::warn!("Failed to open serial sensor {}: {}", config.name, e);
+            }
+        }
+    }
+    
+    #[cfg(feature = "usb")]
+    fn open_serial_sensor(&self, config: &SerialSensorConfig) -> Result<(), HalError> {
+        let mut sensor = SerialSensor::from_config(config.clone())?;
+        sensor.init()?;
+        tracing::info!("Registered serial sensor {}", config.name);
+        self.register_sensor(&config.name, Box::new(sensor));
+        Ok(())
+    }
+    
+    #[cfg(not(feature = "usb"))]
+    fn init_serial_sensors(&mut self) {}
+    
+    /// Make an external driver available to `[[ehal_sensors]]` manifest
+    /// entries naming it (before [`init`](Self::init))
+    pub fn register_ehal_driver(&mut self, name: &str, factory: EhalDriverFactory) {
+        self.ehal_drivers.insert(name.to_string(), factory);
+    }
+    
+    /// Open and register external-driver sensors from the manifest
+    fn init_ehal_sensors(&mut self) {
+        for config in &self.config.ehal_sensors {
+            if let Err(e) = self.open_ehal_sensor(config) {
+                tracing::warn!("Failed to open sensor {}: {}", config.name, e);
+            }
+        }
+    }
+    
+    fn open_ehal_sensor(&self, config: &EhalSensorConfig) -> Result<(), HalError> {
+        let factory = self.ehal_drivers.get(&config.driver)
+            .ok_or_else(|| HalError::InvalidConfig(format!("no driver named {} is registered", config.driver)))?;
+        let mut sensor = factory(config)?;
+        sensor.init()?;
+        tracing::info!("Registered sensor {} (driver {})", config.name, config.driver);
+        self.register_sensor(&config.name, sensor);
+        Ok(())
+    }
+    
+    /// Open the ADCs behind manifest ADC channels and register the channels
+    #[cfg(feature = "spi")]
+    fn init_adc_channels(&mut self) {
+        let mut adcs: HashMap<String, Option<Arc<Mutex<ADS1256>>>> = HashMap::new();
+        
+        for config in self.config.adc_channels.clone() {
+            let adc = adcs.entry(config.device.clone()).or_insert_with(|| {
+                let result = ADS1256::new(&config.device).and_then(|mut adc| {
+                    adc.init()?;
+                    Ok(adc)
+                });
+                match result {
+                    Ok(adc) => Some(Arc::new(Mutex::new(adc))),
+                    Err(e) => {
+                        tracing::warn!("Failed to open ADC {}: {}", config.device, e);
+                        None
+                    }
+                }
+            });
+            let Some(adc) = adc.clone() else {
+                continue;
+            };
+            
+            let name = config.name.clone();
+            let channel = config.channel;
+            let result = AdcChannel::new(config, adc).and_then(|mut sensor| {
+                sensor.init()?;
+                Ok(sensor)
+            });
+            
+            match result {
+                Ok(sensor) => {
+                    tracing::info!("Registered ADC sensor {} (channel {})", name, channel);
+                    self.register_sensor(&name, Box::new(sensor));
+                }
+                Err(e) => tracing::warn!("Failed to open ADC sensor {}: {}", name, e),
+            }
+        }
+    }
+    
+    #[cfg(not(feature = "spi"))]
+    fn init_adc_channels(&mut self) {}
+    
+    /// Open manifest IR illuminators and start driving their outputs
+    #[cfg(feature = "gpio")]
+    fn init_illuminators(&mut self) {
+        for config in self.config.illuminators.clone() {
+            match IRIlluminator::open(&config) {
+                Ok(illuminator) => {
+                    tracing::info!("Registered IR illuminator {} (PWM {}, camera {})",
+                        config.name, config.pwm_channel, config.camera.as_deref().unwrap_or("-"));
+                    self.illuminators.add(illuminator.handle());
+                    illuminator.spawn();
+                }
+                Err(e) => tracing::warn!("Failed to open IR illuminator {}: {}", config.name, e),
+            }
+        }
+    }
+    
+    #[cfg(not(feature = "gpio"))]
+    fn init_illuminators(&mut self) {}
+    
+    /// Open and register cameras from the manifest
+    #[cfg(feature = "camera")]
+    fn init_cameras(&mut self) {
+        for config in &self.config.cameras {
+            if let Err(e) = self.open_camera(config) {
+                tracing::warn!("Failed to open camera {}: {}", config.name, e);
+            }
+        }
+    }
+    
+    #[cfg(feature = "camera")]
+    fn open_camera(&self, config: &CameraConfig) -> Result<(), HalError> {
+        let illuminator = self.illuminators.for_camera(&config.name);
+        let mut camera = config.open_with(illuminator, &self.references, &self.clock)?;
+        camera.init()?;
+        tracing::info!("Registered {:?} camera {} ({}, zone {})",
+            config.role, config.name, config.device, config.zone.as_deref().unwrap_or("-"));
+        self.register_device(&config.name, camera);
+        Ok(())
+    }
+    
+    #[cfg(not(feature = "camera"))]
+    fn init_cameras(&mut self) {}
+    
+    /// Initialize audio subsystem and open manifest-defined inputs
+    #[cfg(feature = "audio")]
     async fn init_audio(&mut self) -> Result<(), HalError> {
         tracing::info!("Initializing audio subsystem");
-        Ok(())  // Audio devices are initialized on demand
+        
+        for config in &self.config.audio_devices {
+            if let Err(e) = self.open_audio_input(config) {
+                tracing::warn!("Failed to open audio input {}: {}", config.name, e);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    #[cfg(feature = "audio")]
+    fn open_audio_input(&self, config: &AudioDeviceConfig) -> Result<(), HalError> {
+        let mut capture = config.open_with(&self.clock)?;
+        capture.init()?;
+        capture.start()?;
+        capture.spawn_reader(&config.name, AUDIO_CHUNK, self.reading_tx.clone());
+        tracing::info!("Registered {:?} audio input {} ({}, zone {})",
+            config.role, config.name, config.device, config.zone.as_deref().unwrap_or("-"));
+        self.register_device(&config.name, Box::new(capture));
+        Ok(())
+    }
+    
+    #[cfg(not(feature = "audio"))]
+    async fn init_audio(&mut self) -> Result<(), HalError> {
+        Ok(())
+    }
+    
+    /// Register the simulated sensors, cameras and SDRs
+    fn init_simulation(&self, config: &SimConfig) {
+        let (sensors, cameras, sdrs) = sim::open_all(config);
+        for mut sensor in sensors {
+            let name = sensor.name().to_string();
+            if let Err(e) = sensor.init() {
+                tracing::warn!("Failed to open simulated sensor {}: {}", name, e);
+                continue;
+            }
+            tracing::info!("Registered simulated sensor {}", name);
+            self.register_sensor(&name, Box::new(sensor));
+        }
+        for mut camera in cameras {
+            let name = camera.name().to_string();
+            if let Err(e) = camera.init() {
+                tracing::warn!("Failed to open simulated camera {}: {}", name, e);
+                continue;
+            }
+            camera.spawn_reader(self.reading_tx.clone(), self.frame_stats.clone());
+            tracing::info!("Registered simulated camera {}", name);
+            self.register_device(&name, Box::new(camera));
+        }
+        for mut sdr in sdrs {
+            let name = sdr.name().to_string();
+            if let Err(e) = sdr.init() {
+                tracing::warn!("Failed to open simulated SDR {}: {}", name, e);
+                continue;
+            }
+            sdr.spawn_reader(self.reading_tx.clone());
+            tracing::info!("Registered simulated SDR {}", name);
+            self.register_device(&name, Box::new(sdr));
+        }
+    }
+    
+    /// Sender for readings produced outside the polling loop
+    ///
+    /// Cameras, audio inputs and SDRs capture on their own schedule and
+    /// publish typed readings ([`SensorReading::frame`], [`SensorReading::audio`],
+    /// [`SensorReading::spectrum`]) into the same channel as polled sensors.
+    pub fn reading_sender(&self) -> mpsc::Sender<SensorReading> {
+        self.reading_tx.clone()
+    }
+    
+    /// Register a non-sensor device (outputs, controllers)
+    pub fn register_device(&self, name: &str, device: Box<dyn HardwareDevice>) {
+        let mut devices = self.devices.write().unwrap();
+        devices.insert(name.to_string(), device);
     }
     
-    /// Register a sensor
-    pub fn register_sensor(&mut self, name: &str, sensor: Box<dyn Sensor>) {
+    /// Register a sensor, applying any stored calibration
+    pub fn register_sensor(&self, name: &str, mut sensor: Box<dyn Sensor>) {
+        if let Some(ref store) = self.calibrations {
+            match store.load(name, sensor.serial().as_deref()) {
+                Ok(Some(record)) => {
+                    tracing::debug!("Applying stored calibration to {}: {:+}", name, record.offset);
+                    if let Err(e) = sensor.calibrate(record.offset) {
+                        tracing::warn!("Failed to apply calibration to {}: {}", name, e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to load calibration for {}: {}", name, e),
+            }
+        }
+        
+        self.read_health.lock().unwrap().insert(name.to_string(), ReadHealth {
+            discrete: sensor.discrete(),
+            ..Default::default()
+        });
+        let schedule = schedule::lookup(&self.config.poll_schedules, name)
+            .unwrap_or(PollSchedule { rate_hz: sensor.poll_rate(), phase_ms: 0 });
+        self.schedules.lock().unwrap().insert(name.to_string(), schedule);
         let mut sensors = self.sensors.write().unwrap();
         sensors.insert(name.to_string(), sensor);
     }
     
-    /// Read from all sensors
-    pub async fn read_all_sensors(&self) -> Vec<SensorReading> {
-        let sensors = self.sensors.read().unwrap();
-        let mut readings = Vec::new();
+    /// Remove and close a sensor; the polling task skips it from its next
+    /// tick
+    ///
+    /// Waits for a read of the sensor in progress to finish. A sensor being
+    /// reconfigured is closed once that finishes.
+    pub fn unregister_sensor(&self, name: &str) -> bool {
+        let sensor = self.sensors.write().unwrap().remove(name);
+        let removed = sensor.is_some() || self.detached.lock().unwrap().remove(name);
+        if removed {
+            self.read_health.lock().unwrap().remove(name);
+            self.schedules.lock().unwrap().remove(name);
+            self.paused.lock().unwrap().remove(name);
+        }
+        if let Some(mut sensor) = sensor {
+            if let Err(e) = sensor.close() {
+                tracing::warn!("Closing sensor {} failed: {}", name, e);
+            }
+        }
+        removed
+    }
+    
+    /// Stop polling a sensor, keeping it registered and open
+    ///
+    /// The watchdog leaves paused sensors alone. Returns `false` for an
+    /// unknown sensor.
+    pub fn pause_sensor(&self, name: &str) -> bool {
+        if !self.has_sensor(name) {
+            return false;
+        }
+        let newly_paused = self.paused.lock().unwrap().insert(name.to_string());
+        if newly_paused {
+            if let Some(reads) = self.read_health.lock().unwrap().get_mut(name) {
+                reads.offline = false;
+            }
+            tracing::info!("Paused polling of {}", name);
+        }
+        true
+    }
+    
+    /// Poll a paused sensor again from its next due time
+    ///
+    /// Returns `false` for an unknown sensor.
+    pub fn resume_sensor(&self, name: &str) -> bool {
+        if !self.has_sensor(name) {
+            return false;
+        }
+        if self.paused.lock().unwrap().remove(name) {
+            tracing::info!("Resumed polling of {}", name);
+        }
+        true
+    }
+    
+    /// Whether a sensor's polling is paused
+    pub fn is_paused(&self, name: &str) -> bool {
+        self.paused.lock().unwrap().contains(name)
+    }
+    
+    /// Change a registered sensor's driver settings while polling runs
+    ///
+    /// The sensor is taken out of polling while `configure` runs, so a slow
+    /// `configure` (re-opening a device that has gone away) holds up neither
+    /// the other sensors nor their buses.
+    pub fn reconfigure_sensor<R>(&self, name: &str, configure: impl FnOnce(&mut dyn Sensor) -> Result<R, HalError>) -> Result<R, HalError> {
+        let mut sensor = {
+            let mut sensors = self.sensors.write().unwrap();
+            let sensor = sensors.remove(name)
+                .ok_or_else(|| HalError::DeviceNotFound(name.to_string()))?;
+            self.detached.lock().unwrap().insert(name.to_string());
+            sensor
+        };
+        
+        let result = configure(sensor.as_mut());
         
-        for (name, sensor) in sensors.iter() {
-            match sensor.read_value() {
-                Ok(value) => {
-                    let reading = SensorReading {
-                        sensor_name: name.clone(),
-                        value,
-                        unit: sensor.unit().to_string(),
-                        timestamp: std::time::SystemTime::now(),
-                        quality: 1.0,
+        // Unless it was unregistered or replaced in the meantime
+        let mut sensors = self.sensors.write().unwrap();
+        if self.detached.lock().unwrap().remove(name) && !sensors.contains_key(name) {
+            sensors.insert(name.to_string(), sensor);
+        } else {
+            drop(sensors);
+            if let Err(e) = sensor.close() {
+                tracing::warn!("Closing sensor {} failed: {}", name, e);
+            }
+        }
+        result
+    }
+    
+    /// Poll schedule of a registered sensor
+    pub fn poll_schedule(&self, name: &str) -> Option<PollSchedule> {
+        self.schedules.lock().unwrap().get(name).copied()
+    }
+    
+    /// Change how often a registered sensor is polled, from its next poll on
+    pub fn set_poll_schedule(&self, name: &str, schedule: PollSchedule) -> bool {
+        match self.schedules.lock().unwrap().get_mut(name) {
+            Some(current) => {
+                *current = schedule;
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Remove a non-sensor device, closing it
+    pub fn unregister_device(&self, name: &str) -> bool {
+        let Some(mut device) = self.devices.write().unwrap().remove(name) else {
+            return false;
+        };
+        if let Err(e) = device.close() {
+            tracing::warn!("Closing device {} failed: {}", name, e);
+        }
+        true
+    }
+    
+    /// Close every sensor and device, returning those that failed to close
+    ///
+    /// Meant for shutdown, after [`stop_polling`](Self::stop_polling); the
+    /// devices stay registered but closed.
+    pub fn close_all(&self) -> Vec<(String, HalError)> {
+        let mut failed = Vec::new();
+        for (name, sensor) in self.sensors.write().unwrap().iter_mut() {
+            if let Err(e) = sensor.close() {
+                failed.push((name.clone(), e));
+            }
+        }
+        for (name, device) in self.devices.write().unwrap().iter_mut() {
+            if let Err(e) = device.close() {
+                failed.push((name.clone(), e));
+            }
+        }
+        failed
+    }
+    
+    /// Whether a sensor is registered, including while it is reconfigured
+    fn has_sensor(&self, name: &str) -> bool {
+        self.sensors.read().unwrap().contains_key(name) || self.detached.lock().unwrap().contains(name)
+    }
+    
+    fn is_registered(&self, name: &str) -> bool {
+        self.has_sensor(name) || self.devices.read().unwrap().contains_key(name)
+    }
+    
+    /// Receiver for device hot-plug events; `None` once taken
+    pub fn take_device_events(&mut self) -> Option<mpsc::Receiver<DeviceEvent>> {
+        self.device_rx.take()
+    }
+    
+    /// Watch for devices being plugged in and out (if `hotplug_enabled`)
+    ///
+    /// Manifest devices whose node appears are opened and registered, those
+    /// whose node disappears are unregistered. Every event then goes to the
+    /// [`take_device_events`](Self::take_device_events) channel.
+    pub fn start_hotplug(self: &Arc<Self>) {
+        if !self.config.hotplug_enabled {
+            return;
+        }
+        
+        #[cfg(target_os = "linux")]
+        {
+            let mut socket = match hotplug::UeventSocket::open() {
+                Ok(socket) => socket,
+                Err(e) => {
+                    tracing::warn!("Hot-plug watcher unavailable: {}", e);
+                    return;
+                }
+            };
+            
+            let (raw_tx, mut raw_rx) = mpsc::channel::<DeviceEvent>(64);
+            let spawned = std::thread::Builder::new().name("hotplug".to_string()).spawn(move || loop {
+                match socket.recv() {
+                    Ok(message) => {
+                        if let Some(event) = hotplug::parse_uevent(message) {
+                            if raw_tx.blocking_send(event).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Hot-plug watcher stopped: {}", e);
+                        return;
+                    }
+                }
+            });
+            if let Err(e) = spawned {
+                tracing::warn!("Failed to start hot-plug watcher: {}", e);
+                return;
+            }
+            
+            let manager = self.clone();
+            tokio::spawn(async move {
+                while let Some(event) = raw_rx.recv().await {
+                    // Let udev create the node, its permissions and symlinks
+                    if matches!(event, DeviceEvent::Attached { .. }) {
+                        tokio::time::sleep(HOTPLUG_SETTLE).await;
+                    }
+                    
+                    let handler = manager.clone();
+                    let event = match tokio::task::spawn_blocking(move || handler.handle_device_event(event)).await {
+                        Ok(event) => event,
+                        Err(e) => {
+                            tracing::error!("Hot-plug handler failed: {}", e);
+                            continue;
+                        }
                     };
-                    readings.push(reading);
+                    let _ = manager.device_tx.try_send(event);
                 }
-                Err(e) => {
-                    tracing::warn!("Failed to read sensor {}: {}", name, e);
+            });
+            tracing::info!("Watching for hot-plugged devices");
+        }
+        
+        #[cfg(not(target_os = "linux"))]
+        tracing::debug!("Hot-plug is not supported on this platform");
+    }
+    
+    /// Receiver for sensors going offline and coming back; `None` once taken
+    pub fn take_watchdog_events(&mut self) -> Option<mpsc::Receiver<WatchdogEvent>> {
+        self.watchdog_rx.take()
+    }
+    
+    /// Watch for sensors that stop returning readings (if `watchdog_timeout`
+    /// is set)
+    ///
+    /// Offline sensors are closed and re-initialised with backoff until
+    /// they read again. Changes go to the
+    /// [`take_watchdog_events`](Self::take_watchdog_events) channel. Checks
+    /// pause while polling is stopped, so sensors closed for shutdown are
+    /// left alone.
+    pub fn start_watchdog(self: &Arc<Self>) {
+        if self.config.watchdog_timeout.is_zero() {
+            return;
+        }
+        
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut watchdog = watchdog::Watchdog::new(manager.config.watchdog_timeout);
+            let mut timer = tokio::time::interval(watchdog.check_interval());
+            let mut last_ticks = 0;
+            loop {
+                timer.tick().await;
+                let ticks = manager.poll_stats().ticks;
+                if ticks == last_ticks {
+                    continue;
+                }
+                last_ticks = ticks;
+                
+                let verdicts = {
+                    let sensors = manager.sensors.read().unwrap();
+                    let paused = manager.paused.lock().unwrap();
+                    let mut health = manager.read_health.lock().unwrap();
+                    let polled = sensors.keys().filter(|name| !paused.contains(*name));
+                    let verdicts = watchdog.check(Instant::now(), polled.map(|name| {
+                        let reads = health.get(name);
+                        watchdog::Liveness {
+                            name,
+                            last_success: reads.and_then(|r| r.last_success),
+                            last_error: reads.and_then(|r| r.last_error.as_deref()),
+                        }
+                    }));
+                    for event in &verdicts.events {
+                        if let Some(reads) = health.get_mut(event.sensor()) {
+                            reads.offline = matches!(event, WatchdogEvent::Offline { .. });
+                        }
+                    }
+                    verdicts
+                };
+                
+                for event in verdicts.events {
+                    let _ = manager.watchdog_tx.try_send(event);
+                }
+                for name in verdicts.reinit {
+                    let handler = manager.clone();
+                    let result = tokio::task::spawn_blocking(move || {
+                        let result = handler.reinit_sensor(&name);
+                        (name, result)
+                    }).await;
+                    match result {
+                        Ok((name, Ok(()))) => tracing::info!("Re-initialised offline sensor {}", name),
+                        Ok((name, Err(e))) => tracing::debug!("Re-initialising {} failed: {}", name, e),
+                        Err(e) => tracing::error!("Watchdog re-init task failed: {}", e),
+                    }
+                }
+            }
+        });
+        tracing::info!("Sensor watchdog started (timeout {:?})", self.config.watchdog_timeout);
+    }
+    
+    /// Close and re-open a sensor in place
+    pub fn reinit_sensor(&self, name: &str) -> Result<(), HalError> {
+        self.reconfigure_sensor(name, |sensor| {
+            if let Err(e) = sensor.close() {
+                tracing::debug!("Closing sensor {} failed: {}", name, e);
+            }
+            sensor.init()
+        })
+    }
+    
+    /// Register or unregister the manifest devices behind a hot-plug event
+    fn handle_device_event(&self, event: DeviceEvent) -> DeviceEvent {
+        match event {
+            DeviceEvent::Attached { device, .. } => {
+                let registered = self.attach_manifest_devices(&device);
+                tracing::info!("Device attached: {:?} ({})", device.devnode, device.subsystem);
+                DeviceEvent::Attached { device, registered }
+            }
+            DeviceEvent::Detached { device, .. } => {
+                let unregistered: Vec<String> = self.manifest_paths().into_iter()
+                    .filter(|(_, path)| hotplug::matches(path, &device, false))
+                    .filter(|(name, _)| self.unregister_sensor(name) || self.unregister_device(name))
+                    .map(|(name, _)| name)
+                    .collect();
+                for name in &unregistered {
+                    tracing::warn!("Unregistered {}: {:?} was unplugged", name, device.devnode);
+                }
+                DeviceEvent::Detached { device, unregistered }
+            }
+        }
+    }
+    
+    /// (name, device path) of manifest entries that can be hot-plugged
+    fn manifest_paths(&self) -> Vec<(String, String)> {
+        #[allow(unused_mut)]
+        let mut paths = Vec::new();
+        #[cfg(feature = "usb")]
+        paths.extend(self.config.serial_sensors.iter().map(|c| (c.name.clone(), c.port.clone())));
+        #[cfg(feature = "camera")]
+        paths.extend(self.config.cameras.iter().map(|c| (c.name.clone(), c.device.clone())));
+        #[cfg(feature = "audio")]
+        paths.extend(self.config.audio_devices.iter().map(|c| (c.name.clone(), c.device.clone())));
+        paths
+    }
+    
+    /// Open the unregistered manifest entries on a new device node
+    #[allow(unused_variables, unused_mut)]
+    fn attach_manifest_devices(&self, device: &DeviceInfo) -> Vec<String> {
+        let mut registered = Vec::new();
+        let wanted = |name: &str, path: &str| !self.is_registered(name) && hotplug::matches(path, device, true);
+        
+        #[cfg(feature = "usb")]
+        for config in self.config.serial_sensors.iter().filter(|c| wanted(&c.name, &c.port)) {
+            match self.open_serial_sensor(config) {
+                Ok(()) => registered.push(config.name.clone()),
+                Err(e) => tracing::warn!("Failed to open serial sensor {}: {}", config.name, e),
+            }
+        }
+        #[cfg(feature = "camera")]
+        for config in self.config.cameras.iter().filter(|c| wanted(&c.name, &c.device)) {
+            match self.open_camera(config) {
+                Ok(()) => registered.push(config.name.clone()),
+                Err(e) => tracing::warn!("Failed to open camera {}: {}", config.name, e),
+            }
+        }
+        #[cfg(feature = "audio")]
+        for config in self.config.audio_devices.iter().filter(|c| wanted(&c.name, &c.device)) {
+            match self.open_audio_input(config) {
+                Ok(()) => registered.push(config.name.clone()),
+                Err(e) => tracing::warn!("Failed to open audio input {}: {}", config.name, e),
+            }
+        }
+        registered
+    }
+    
+    /// Calibrate a sensor and persist the offset
+    pub fn calibrate_sensor(&self, name: &str, offset: f64) -> Result<(), HalError> {
+        let serial = {
+            let mut sensors = self.sensors.write().unwrap();
+            let sensor = sensors.get_mut(name)
+                .ok_or_else(|| HalError::DeviceNotFound(name.to_string()))?;
+            sensor.calibrate(offset)?;
+            sensor.serial()
+        };
+        
+        if let Some(ref store) = self.calibrations {
+            store.save(&CalibrationRecord::new(name, serial.as_deref(), offset))?;
+        }
+        Ok(())
+    }
+    
+    /// Serial number of a registered sensor, if it reports one
+    fn sensor_serial(&self, name: &str) -> Option<String> {
+        self.sensors.read().unwrap().get(name).and_then(|sensor| sensor.serial())
+    }
+    
+    /// Shift a sensor's persisted offset by `delta` (re-zeroing drift)
+    ///
+    /// The current offset is taken from the calibration store, or 0 if none is stored.
+    pub fn adjust_calibration(&self, name: &str, delta: f64) -> Result<f64, HalError> {
+        let current = match self.calibrations {
+            Some(ref store) => store.load(name, self.sensor_serial(name).as_deref())?.map(|r| r.offset).unwrap_or(0.0),
+            None => 0.0,
+        };
+        let offset = current + delta;
+        self.calibrate_sensor(name, offset)?;
+        Ok(offset)
+    }
+    
+    /// Sensors whose stored calibration has expired
+    pub fn check_calibrations(&self) -> Vec<(String, HalError)> {
+        let Some(ref store) = self.calibrations else {
+            return Vec::new();
+        };
+        
+        let sensors = self.sensors.read().unwrap();
+        sensors.iter()
+            .filter_map(|(name, sensor)| store.check(name, sensor.serial().as_deref()).err().map(|e| (name.clone(), e)))
+            .collect()
+    }
+    
+    /// Calibration store, if configured
+    pub fn calibrations(&self) -> Option<&CalibrationStore> {
+        self.calibrations.as_ref()
+    }
+    
+    /// IR illuminators opened from the manifest
+    pub fn illuminators(&self) -> &Illuminators {
+        &self.illuminators
+    }
+    
+    /// Named audio outputs
+    pub fn audio_routes(&self) -> &AudioRoutes {
+        &self.audio_routes
+    }
+    
+    /// Frame counts and latency of each camera
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.frame_stats
+    }
+    
+    /// Clock all readings are stamped against
+    pub fn clock(&self) -> &SyncClock {
+        &self.clock
+    }
+    
+    /// Fault injection, if enabled in the config
+    pub fn faults(&self) -> Option<&FaultInjector> {
+        self.config.fault_injection.then_some(&self.faults)
+    }
+    
+    /// Names and types of all registered devices and sensors
+    pub fn list_devices(&self) -> Vec<(String, DeviceType)> {
+        let devices = self.devices.read().unwrap();
+        let sensors = self.sensors.read().unwrap();
+        devices.iter()
+            .map(|(name, d)| (name.clone(), d.device_type()))
+            .chain(sensors.iter().map(|(name, s)| (name.clone(), s.device_type())))
+            .collect()
+    }
+    
+    /// Read from all sensors, buses in parallel
+    pub async fn read_all_sensors(&self) -> Vec<SensorReading> {
+        let mut readings = Vec::new();
+        let names: Vec<String> = {
+            let paused = self.paused.lock().unwrap();
+            self.sensors.read().unwrap().keys().filter(|name| !paused.contains(*name)).cloned().collect()
+        };
+        let mut buses = sample_all(&self.sensors, &names, &self.clock, &self.faults).await;
+        update_health(&self.read_health, &mut buses, self.config.stuck_reads);
+        
+        for bus in buses {
+            for (name, result) in bus.results {
+                match result {
+                    Ok(reading) => readings.push(reading),
+                    Err(e) => {
+                        tracing::warn!("Failed to read sensor {}: {}", name, e);
+                    }
                 }
             }
         }
@@ -270,44 +1595,513 @@ impl HardwareManager {
         readings
     }
     
+    /// Timing of the polling loop
+    pub fn poll_stats(&self) -> PollStats {
+        *self.poll_stats.lock().unwrap()
+    }
+    
+    /// Readiness and recent read failures of every device and sensor, by name
+    pub fn device_health(&self) -> Vec<DeviceHealth> {
+        let devices = self.devices.read().unwrap();
+        let sensors = self.sensors.read().unwrap();
+        let schedules = self.schedules.lock().unwrap();
+        let paused = self.paused.lock().unwrap();
+        let read_health = self.read_health.lock().unwrap();
+        
+        let mut health: Vec<DeviceHealth> = devices.iter()
+            .map(|(name, d)| (name, d.device_type(), d.is_ready()))
+            .chain(sensors.iter().map(|(name, s)| (name, s.device_type(), s.is_ready() && !self.faults.disappeared(name))))
+            .map(|(name, device_type, ready)| {
+                let reads = read_health.get(name).cloned().unwrap_or_default();
+                DeviceHealth {
+                    name: name.clone(),
+                    device_type,
+                    ready,
+                    consecutive_failures: reads.consecutive_failures,
+                    read_errors: reads.total_failures,
+                    stuck: reads.stuck(self.config.stuck_reads),
+                    offline: reads.offline,
+                    paused: paused.contains(name),
+                    poll_rate_hz: schedules.get(name).and_then(|s| s.rate_hz),
+                    identical_reads: reads.identical_reads,
+                    last_error: reads.last_error,
+                    last_success: reads.last_success,
+                }
+            })
+            .collect();
+        health.sort_by(|a, b| a.name.cmp(&b.name));
+        health
+    }
+    
+    /// Sample `names` every `interval` for `duration` on a blocking thread
+    ///
+    /// Sensors are switched to their high-rate mode for the burst. Sensors
+    /// with a block capture mode instead fill a buffer of `block` length at
+    /// their maximum rate. Sensors already bursting, or not registered, are
+    /// skipped; regular polling continues at its normal rate alongside.
+    pub async fn capture_burst(&self, names: &[String], interval: Duration, duration: Duration, block: Duration) -> Vec<BurstTrace> {
+        let (blocks, polled): (Vec<String>, Vec<String>) = {
+            let registered = self.sensors.read().unwrap();
+            let mut bursting = self.bursting.lock().unwrap();
+            names.iter()
+                .filter(|name| registered.contains_key(*name))
+                .filter(|name| bursting.insert(name.to_string()))
+                .cloned()
+                .partition(|name| registered[name].block_rate().is_some())
+        };
+        if blocks.is_empty() && polled.is_empty() {
+            return Vec::new();
+        }
+        
+        set_high_rate(&self.sensors, &polled, true);
+        
+        let block_task = {
+            let (sensors, names) = (self.sensors.clone(), blocks.clone());
+            tokio::task::spawn_blocking(move || capture_blocks(&sensors, &names, block))
+        };
+        let poll_task = {
+            let (sensors, names) = (self.sensors.clone(), polled.clone());
+            tokio::task::spawn_blocking(move || poll_burst(&sensors, &names, interval, duration))
+        };
+        let (block_traces, poll_traces) = tokio::join!(block_task, poll_task);
+        
+        set_high_rate(&self.sensors, &polled, false);
+        let mut bursting = self.bursting.lock().unwrap();
+        for name in blocks.iter().chain(&polled) {
+            bursting.remove(name);
+        }
+        
+        let mut traces = poll_traces.unwrap_or_default();
+        traces.extend(block_traces.unwrap_or_default());
+        traces
+    }
+    
     /// Start continuous sensor polling
+    ///
+    /// Each sensor is polled on its own [`PollSchedule`], `interval` for
+    /// those without a rate. Each tick reads the sensors that are due, all
+    /// buses concurrently. Ticks that take longer than the shortest interval
+    /// among them are counted in [`poll_stats`](Self::poll_stats) and
+    /// reported at most every [`OVERRUN_REPORT_INTERVAL`].
     pub async fn start_polling(&self, interval: Duration) {
         let sensors = self.sensors.clone();
+        let schedules = self.schedules.clone();
+        let paused = self.paused.clone();
+        let stats = self.poll_stats.clone();
+        let health = self.read_health.clone();
         let tx = self.reading_tx.clone();
+        let references = self.references.clone();
+        let clock = self.clock.clone();
+        let faults = self.faults.clone();
+        let stuck_reads = self.config.stuck_reads;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
         
-        tokio::spawn(async move {
-            let mut interval_timer = tokio::time::interval(interval);
+        let task = tokio::spawn(async move {
+            let mut scheduler = schedule::Scheduler::new(interval);
+            let mut last_report: Option<Instant> = None;
+            let mut unreported = 0u64;
             
             loop {
-                interval_timer.tick().await;
+                tokio::time::sleep_until(scheduler.next_wake(Instant::now()).into()).await;
+                if stopped.load(Ordering::Relaxed) {
+                    break;
+                }
                 
-                // Clone readings out of the lock to avoid holding it across await
-                let readings: Vec<(String, f64, String)> = {
-                    let sensors = sensors.read().unwrap();
-                    sensors.iter()
-                        .filter_map(|(name, sensor)| {
-                            sensor.read_value().ok().map(|value| {
-                                (name.clone(), value, sensor.unit().to_string())
-                            })
-                        })
-                        .collect()
+                let due = {
+                    let registered = sensors.read().unwrap();
+                    let schedules = schedules.lock().unwrap();
+                    let paused = paused.lock().unwrap();
+                    scheduler.due(Instant::now(), registered.keys().filter(|name| !paused.contains(*name)).map(|name| {
+                        (name.as_str(), schedules.get(name).copied().unwrap_or_default())
+                    }))
                 };
+                if due.is_empty() {
+                    continue;
+                }
+                let budget = scheduler.shortest_interval(&due);
                 
-                for (sensor_name, value, unit) in readings {
-                    let reading = SensorReading {
-                        sensor_name,
-                        value,
-                        unit,
-                        timestamp: std::time::SystemTime::now(),
-                        quality: 1.0,
-                    };
-                    
-                    if tx.send(reading).await.is_err() {
-                        tracing::error!("Failed to send sensor reading");
-                        return;
+                let started = Instant::now();
+                let mut buses = sample_all(&sensors, &due, &clock, &faults).await;
+                let elapsed = started.elapsed();
+                update_health(&health, &mut buses, stuck_reads);
+                
+                {
+                    let mut stats = stats.lock().unwrap();
+                    stats.ticks += 1;
+                    stats.last_tick = elapsed;
+                    stats.worst_tick = stats.worst_tick.max(elapsed);
+                    if elapsed > budget {
+                        stats.overruns += 1;
+                    }
+                }
+                
+                if elapsed > budget {
+                    unreported += 1;
+                    if last_report.is_none_or(|t| t.elapsed() >= OVERRUN_REPORT_INTERVAL) {
+                        let slowest = buses.iter().max_by_key(|b| b.elapsed);
+                        tracing::warn!(
+                            "Poll tick took {:?} (interval {:?}, {} overruns since last report); slowest bus {} took {:?} for {} sensors",
+                            elapsed,
+                            budget,
+                            unreported,
+                            slowest.map_or("-", |b| b.bus.as_str()),
+                            slowest.map_or(Duration::ZERO, |b| b.elapsed),
+                            slowest.map_or(0, |b| b.results.len()),
+                        );
+                        last_report = Some(Instant::now());
+                        unreported = 0;
+                    }
+                }
+                
+                for bus in buses {
+                    tracing::trace!(bus = %bus.bus, elapsed = ?bus.elapsed, sensors = bus.results.len(), "Bus read");
+                    for (name, result) in bus.results {
+                        let reading = match result {
+                            Ok(reading) => reading,
+                            Err(e) => {
+                                tracing::debug!("Failed to read sensor {}: {}", name, e);
+                                continue;
+                            }
+                        };
+                        references.update(&reading);
+                        let delay = clock.now().duration_since(reading.timestamp).unwrap_or_default();
+                        clock.record(&name, StreamKind::Sensor, delay);
+                        if tx.send(reading).await.is_err() {
+                            tracing::error!("Failed to send sensor reading");
+                            return;
+                        }
                     }
                 }
             }
+            tracing::debug!("Sensor polling stopped");
         });
+        *self.poller.lock().unwrap() = Some(Poller { stop, task });
+    }
+    
+    /// Stop the polling task, returning once its last tick's readings are sent
+    ///
+    /// Readings from audio inputs, cameras and SDRs keep coming until their
+    /// devices are closed.
+    pub async fn stop_polling(&self) {
+        let Some(poller) = self.poller.lock().unwrap().take() else {
+            return;
+        };
+        poller.stop.store(true, Ordering::Relaxed);
+        if let Err(e) = poller.task.await {
+            tracing::error!("Polling task failed: {}", e);
+        }
+    }
+}
+
+/// Time given to udev after a device appears before it is opened
+pub const HOTPLUG_SETTLE: Duration = Duration::from_secs(1);
+
+/// Minimum time between poll overrun warnings
+pub const OVERRUN_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Length of the sample blocks audio inputs publish as readings
+pub const AUDIO_CHUNK: Duration = Duration::from_millis(100);
+
+/// Default [`HalConfig::stuck_reads`]: 20 s at the default 100 ms poll
+pub const DEFAULT_STUCK_READS: u32 = 200;
+
+/// Results of reading the sensors on one bus
+struct BusSample {
+    bus: String,
+    elapsed: Duration,
+    results: Vec<(String, Result<SensorReading, HalError>)>,
+}
+
+/// Record read successes and failures, and flag readings of sensors stuck
+/// at one value with quality 0
+fn update_health(health: &Mutex<HashMap<String, ReadHealth>>, buses: &mut [BusSample], stuck_reads: u32) {
+    let mut health = health.lock().unwrap();
+    for (name, result) in buses.iter_mut().flat_map(|bus| &mut bus.results) {
+        let entry = health.entry(name.clone()).or_default();
+        match result {
+            Ok(reading) => {
+                entry.consecutive_failures = 0;
+                entry.last_success = Some(reading.timestamp);
+                
+                let fingerprint = fingerprint(reading);
+                if entry.fingerprint == Some(fingerprint) {
+                    entry.identical_reads += 1;
+                } else {
+                    if entry.stuck(stuck_reads) {
+                        tracing::info!("Sensor {} is varying again after {} identical reads", name, entry.identical_reads);
+                    }
+                    entry.fingerprint = Some(fingerprint);
+                    entry.identical_reads = 1;
+                }
+                
+                if entry.stuck(stuck_reads) {
+                    if entry.identical_reads == stuck_reads {
+                        tracing::warn!("Sensor {} returned {} identical values in a row, suspected stuck", name, stuck_reads);
+                    }
+                    reading.quality = 0.0;
+                }
+            }
+            Err(e) => {
+                entry.consecutive_failures += 1;
+                entry.total_failures += 1;
+                entry.last_error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+/// Hash of the bits of a reading's value, components and channels
+fn fingerprint(reading: &SensorReading) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    reading.value.to_bits().hash(&mut hasher);
+    for (_, value) in reading.components() {
+        value.to_bits().hash(&mut hasher);
+    }
+    for channel in reading.channels() {
+        channel.value.to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Sample every sensor, one blocking task per bus
+///
+/// Sensors without a shared bus get a task of their own. Readings are
+/// stamped on `clock` at the middle of their read, after any injected fault.
+async fn sample_all(sensors: &Arc<RwLock<HashMap<String, Box<dyn Sensor>>>>, names: &[String], clock: &SyncClock, faults: &FaultInjector) -> Vec<BusSample> {
+    let groups: BTreeMap<String, Vec<String>> = {
+        let sensors = sensors.read().unwrap();
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (name, sensor) in names.iter().filter_map(|name| Some((name, sensors.get(name)?))) {
+            let bus = sensor.bus().map_or_else(|| name.clone(), str::to_string);
+            groups.entry(bus).or_default().push(name.clone());
+        }
+        groups
+    };
+    
+    let mut tasks = tokio::task::JoinSet::new();
+    for (bus, names) in groups {
+        let sensors = sensors.clone();
+        let clock = clock.clone();
+        let faults = faults.clone();
+        tasks.spawn_blocking(move || {
+            let started = Instant::now();
+            let sensors = sensors.read().unwrap();
+            let results = names.into_iter()
+                .filter_map(|name| {
+                    let sensor = sensors.get(&name)?;
+                    let before = Instant::now();
+                    let reading = faults.read(&name, || SensorReading::sample(&name, sensor.as_ref()))
+                        .map(|mut reading| {
+                            reading.timestamp = clock.at(before + before.elapsed() / 2);
+                            reading
+                        });
+                    Some((name, reading))
+                })
+                .collect();
+            BusSample { bus, elapsed: started.elapsed(), results }
+        });
+    }
+    
+    let mut samples = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok(sample) => samples.push(sample),
+            Err(e) => tracing::error!("Sensor read task failed: {}", e),
+        }
+    }
+    samples
+}
+
+/// Read `names` every `interval` for `duration`
+fn poll_burst(sensors: &RwLock<HashMap<String, Box<dyn Sensor>>>, names: &[String], interval: Duration, duration: Duration) -> Vec<BurstTrace> {
+    if names.is_empty() {
+        return Vec::new();
+    }
+    
+    let mut traces: Vec<BurstTrace> = {
+        let sensors = sensors.read().unwrap();
+        names.iter()
+            .filter_map(|name| sensors.get(name).map(|s| BurstTrace {
+                sensor_name: name.clone(),
+                unit: s.unit().to_string(),
+                interval,
+                sample_rate: None,
+                samples: Vec::new(),
+            }))
+            .collect()
+    };
+    
+    let started = Instant::now();
+    let mut next = started;
+    while started.elapsed() < duration {
+        {
+            let sensors = sensors.read().unwrap();
+            for trace in &mut traces {
+                let Some(sensor) = sensors.get(&trace.sensor_name) else {
+                    continue;
+                };
+                if let Ok(value) = sensor.read_value() {
+                    trace.samples.push((started.elapsed().as_secs_f64(), value));
+                }
+            }
+        }
+        
+        next += interval;
+        std::thread::sleep(next.saturating_duration_since(Instant::now()));
+    }
+    
+    traces
+}
+
+/// Capture a `duration` block from each of `names` in turn
+fn capture_blocks(sensors: &RwLock<HashMap<String, Box<dyn Sensor>>>, names: &[String], duration: Duration) -> Vec<BurstTrace> {
+    let sensors = sensors.read().unwrap();
+    names.iter()
+        .filter_map(|name| {
+            let sensor = sensors.get(name)?;
+            let rate = sensor.block_rate()?;
+            let count = (duration.as_secs_f64() * rate).ceil() as usize;
+            match sensor.read_block(count) {
+                Ok(values) => Some(BurstTrace::block(name, sensor.unit(), rate, values)),
+                Err(e) => {
+                    tracing::warn!("Block capture of {} failed: {}", name, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+fn set_high_rate(sensors: &RwLock<HashMap<String, Box<dyn Sensor>>>, names: &[String], enabled: bool) {
+    let mut sensors = sensors.write().unwrap();
+    for name in names {
+        if let Some(sensor) = sensors.get_mut(name) {
+            if let Err(e) = sensor.set_high_rate(enabled) {
+                tracing::warn!("Failed to switch {} sample rate: {}", name, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sim::{SimSensorConfig, SimulatedSensor};
+    
+    fn manager_with(names: &[&str]) -> HardwareManager {
+        let (manager, _) = HardwareManager::new(HalConfig::default());
+        for name in names {
+            let mut sensor = SimulatedSensor::new(SimSensorConfig {
+                name: name.to_string(),
+                unit: String::new(),
+                baseline: 1.0,
+                waveform: Default::default(),
+                noise: Default::default(),
+                injections: Vec::new(),
+            }, 1);
+            sensor.init().unwrap();
+            manager.register_sensor(name, Box::new(sensor));
+        }
+        manager
+    }
+    
+    #[test]
+    fn reconfigures_a_sensor_outside_the_map_lock() {
+        let manager = manager_with(&["emf", "temp"]);
+        let other = manager.reconfigure_sensor("emf", |sensor| {
+            // The other sensors stay readable meanwhile
+            assert!(manager.has_sensor("emf"));
+            let other = manager.sensors.read().unwrap()["temp"].read_value();
+            sensor.close()?;
+            sensor.init()?;
+            other
+        }).unwrap();
+        assert_eq!(other, 1.0);
+        assert_eq!(manager.sensors.read().unwrap()["emf"].read_value().unwrap(), 1.0);
+    }
+    
+    #[test]
+    fn drops_a_sensor_unregistered_while_reconfigured() {
+        let manager = manager_with(&["emf"]);
+        manager.reconfigure_sensor("emf", |_| {
+            assert!(manager.unregister_sensor("emf"));
+            Ok(())
+        }).unwrap();
+        assert!(!manager.has_sensor("emf"));
+        assert!(manager.reinit_sensor("emf").is_err());
+    }
+    
+    /// Sensor recording whether it has been closed
+    struct Closable(Arc<AtomicBool>);
+    
+    impl HardwareDevice for Closable {
+        fn name(&self) -> &str {
+            "closable"
+        }
+        
+        fn device_type(&self) -> DeviceType {
+            DeviceType::Simulated
+        }
+        
+        fn init(&mut self) -> Result<(), HalError> {
+            Ok(())
+        }
+        
+        fn is_ready(&self) -> bool {
+            !self.0.load(Ordering::SeqCst)
+        }
+        
+        fn close(&mut self) -> Result<(), HalError> {
+            self.0.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+    
+    impl Sensor for Closable {
+        fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+            Ok(Vec::new())
+        }
+        
+        fn read_value(&self) -> Result<f64, HalError> {
+            Ok(0.0)
+        }
+        
+        fn unit(&self) -> &str {
+            ""
+        }
+        
+        fn calibrate(&mut self, _offset: f64) -> Result<(), HalError> {
+            Ok(())
+        }
+    }
+    
+    #[test]
+    fn closes_unregistered_sensors() {
+        let manager = manager_with(&[]);
+        let closed = Arc::new(AtomicBool::new(false));
+        manager.register_sensor("door", Box::new(Closable(closed.clone())));
+        assert!(manager.unregister_sensor("door"));
+        assert!(closed.load(Ordering::SeqCst));
+        
+        // Unregistered while reconfigured: closed once that finishes
+        let closed = Arc::new(AtomicBool::new(false));
+        manager.register_sensor("door", Box::new(Closable(closed.clone())));
+        manager.reconfigure_sensor("door", |_| {
+            assert!(manager.unregister_sensor("door"));
+            assert!(!closed.load(Ordering::SeqCst));
+            Ok(())
+        }).unwrap();
+        assert!(closed.load(Ordering::SeqCst));
+    }
+    
+    #[test]
+    fn closes_unregistered_devices() {
+        let manager = manager_with(&[]);
+        let closed = Arc::new(AtomicBool::new(false));
+        manager.register_device("door", Box::new(Closable(closed.clone())));
+        assert!(manager.unregister_device("door"));
+        assert!(closed.load(Ordering::SeqCst));
+        assert!(!manager.unregister_device("door"));
     }
 }
@@ -0,0 +1,251 @@
+//! ALSA mixer control and named audio output routes
+//!
+//! A node often drives more than one output: an earpiece for the operator
+//! and a speaker in the room. A route names one of them in the config with
+//! the ALSA playback device sounds are written to and the mixer control
+//! that sets its level, so alert sounds and spirit box audio can be sent to
+//! different places and turned up, down or muted from triggers.
+//!
+//! The mixer is driven through `amixer`; on the stub backend it does nothing.
+
+use crate::platform::SIMULATED;
+use crate::HalError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// Level of a mixer control
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MixerLevel {
+    /// Volume (0-100 %)
+    pub volume: f64,
+    pub muted: bool,
+}
+
+/// Simple mixer controls of one sound card
+#[derive(Debug, Clone)]
+pub struct Mixer {
+    card: Option<String>,
+}
+
+impl Mixer {
+    /// Mixer of `card` (index or name); `None` is the default card
+    pub fn new(card: Option<&str>) -> Self {
+        Self {
+            card: card.map(str::to_string),
+        }
+    }
+    
+    pub fn card(&self) -> Option<&str> {
+        self.card.as_deref()
+    }
+    
+    /// Names of the card's simple controls (e.g. `Master`, `PCM`, `Speaker`)
+    pub fn controls(&self) -> Result<Vec<String>, HalError> {
+        let output = self.amixer(&["scontrols"])?;
+        Ok(output.lines()
+            .filter_map(|line| line.split('\'').nth(1))
+            .map(str::to_string)
+            .collect())
+    }
+    
+    /// Current level of a control
+    pub fn level(&self, control: &str) -> Result<MixerLevel, HalError> {
+        if SIMULATED {
+            return Ok(MixerLevel { volume: 100.0, muted: false });
+        }
+        let output = self.amixer(&["sget", control])?;
+        parse_level(&output)
+            .ok_or_else(|| HalError::CommunicationError(format!("no playback level in amixer output for {}", control)))
+    }
+    
+    /// Set a control's volume (0-100 %)
+    pub fn set_volume(&self, control: &str, percent: f64) -> Result<(), HalError> {
+        let volume = format!("{:.0}%", percent.clamp(0.0, 100.0));
+        self.amixer(&["-q", "sset", control, &volume]).map(|_| ())
+    }
+    
+    /// Mute or unmute a control (controls without a switch ignore this)
+    pub fn set_muted(&self, control: &str, muted: bool) -> Result<(), HalError> {
+        self.amixer(&["-q", "sset", control, if muted { "mute" } else { "unmute" }]).map(|_| ())
+    }
+    
+    fn amixer(&self, args: &[&str]) -> Result<String, HalError> {
+        if SIMULATED {
+            return Ok(String::new());
+        }
+        
+        let mut command = std::process::Command::new("amixer");
+        if let Some(card) = &self.card {
+            command.args(["-c", card]);
+        }
+        let output = command.args(args).output()?;
+        if !output.status.success() {
+            return Err(HalError::CommunicationError(format!(
+                "amixer {}: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// First `[NN%]` and `[on]`/`[off]` of `amixer sget` output
+fn parse_level(output: &str) -> Option<MixerLevel> {
+    let line = output.lines().find(|l| l.contains('%'))?;
+    let volume = line.split('[')
+        .filter_map(|part| part.split(']').next())
+        .find_map(|field| field.strip_suffix('%')?.parse().ok())?;
+    Some(MixerLevel {
+        volume,
+        muted: line.contains("[off]"),
+    })
+}
+
+/// Named audio output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioRouteConfig {
+    pub name: String,
+    /// ALSA playback device (e.g. `plughw:1,0` or `plughw:CARD=Headset`)
+    pub device: String,
+    /// Mixer card; defaults to the card of `device`
+    #[serde(default)]
+    pub card: Option<String>,
+    /// Mixer control that sets the route's volume
+    #[serde(default = "default_control")]
+    pub control: String,
+    /// Volume applied at startup (0-100 %)
+    #[serde(default)]
+    pub volume: Option<f64>,
+    /// Start muted
+    #[serde(default)]
+    pub muted: bool,
+}
+
+fn default_control() -> String { "Master".to_string() }
+
+impl AudioRouteConfig {
+    /// Mixer card: configured, or parsed from `hw:1,0` / `plughw:CARD=Headset,DEV=0`
+    pub fn mixer_card(&self) -> Option<String> {
+        if self.card.is_some() {
+            return self.card.clone();
+        }
+        
+        let (_, spec) = self.device.split_once(':')?;
+        let first = spec.split(',').next()?;
+        let card = first.strip_prefix("CARD=").unwrap_or(first);
+        (!card.is_empty()).then(|| card.to_string())
+    }
+    
+    pub fn mixer(&self) -> Mixer {
+        Mixer::new(self.mixer_card().as_deref())
+    }
+}
+
+/// Current state of a route, for status views
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioRouteStatus {
+    pub name: String,
+    pub device: String,
+    pub control: String,
+    /// Volume last set through the route (0-100 %)
+    pub volume: Option<f64>,
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone)]
+struct RouteState {
+    config: AudioRouteConfig,
+    volume: Option<f64>,
+    muted: bool,
+}
+
+impl RouteState {
+    fn status(&self) -> AudioRouteStatus {
+        AudioRouteStatus {
+            name: self.config.name.clone(),
+            device: self.config.device.clone(),
+            control: self.config.control.clone(),
+            volume: self.volume,
+            muted: self.muted,
+        }
+    }
+}
+
+/// Audio routes by name
+///
+/// Cheap to clone; clones share the routes.
+#[derive(Debug, Clone, Default)]
+pub struct AudioRoutes {
+    routes: Arc<RwLock<BTreeMap<String, RouteState>>>,
+}
+
+impl AudioRoutes {
+    pub fn new(configs: Vec<AudioRouteConfig>) -> Self {
+        let routes = configs.into_iter()
+            .map(|config| {
+                let state = RouteState {
+                    volume: config.volume,
+                    muted: config.muted,
+                    config,
+                };
+                (state.config.name.clone(), state)
+            })
+            .collect();
+        
+        Self {
+            routes: Arc::new(RwLock::new(routes)),
+        }
+    }
+    
+    /// Apply every route's startup volume and mute setting
+    pub fn apply(&self) -> Vec<(String, HalError)> {
+        let mut errors = Vec::new();
+        for state in self.routes.read().unwrap().values() {
+            let mixer = state.config.mixer();
+            let control = &state.config.control;
+            let result = state.volume
+                .map_or(Ok(()), |volume| mixer.set_volume(control, volume))
+                .and_then(|_| mixer.set_muted(control, state.muted));
+            if let Err(e) = result {
+                errors.push((state.config.name.clone(), e));
+            }
+        }
+        errors
+    }
+    
+    /// ALSA playback device of a route
+    pub fn device(&self, name: &str) -> Option<String> {
+        self.routes.read().unwrap().get(name).map(|s| s.config.device.clone())
+    }
+    
+    pub fn get(&self, name: &str) -> Option<AudioRouteConfig> {
+        self.routes.read().unwrap().get(name).map(|s| s.config.clone())
+    }
+    
+    /// Set a route's volume (0-100 %)
+    pub fn set_volume(&self, name: &str, percent: f64) -> Result<AudioRouteStatus, HalError> {
+        let mut routes = self.routes.write().unwrap();
+        let state = routes.get_mut(name).ok_or_else(|| unknown_route(name))?;
+        state.config.mixer().set_volume(&state.config.control, percent)?;
+        state.volume = Some(percent.clamp(0.0, 100.0));
+        Ok(state.status())
+    }
+    
+    /// Mute or unmute a route
+    pub fn set_muted(&self, name: &str, muted: bool) -> Result<AudioRouteStatus, HalError> {
+        let mut routes = self.routes.write().unwrap();
+        let state = routes.get_mut(name).ok_or_else(|| unknown_route(name))?;
+        state.config.mixer().set_muted(&state.config.control, muted)?;
+        state.muted = muted;
+        Ok(state.status())
+    }
+    
+    /// Status of every route, sorted by name
+    pub fn statuses(&self) -> Vec<AudioRouteStatus> {
+        self.routes.read().unwrap().values().map(RouteState::status).collect()
+    }
+}
+
+fn unknown_route(name: &str) -> HalError {
+    HalError::DeviceNotFound(format!("audio route {}", name))
+}
@@ -0,0 +1,138 @@
+//! Platform abstraction for GlowBarn HAL
+//!
+//! On Linux every backend talks to real device nodes, sysfs and ioctls.
+//! On other platforms (macOS, Windows) the same API is backed by no-op
+//! stubs: opens succeed, reads return no data and writes are discarded, so
+//! the sensor stack, CLI and dashboards can be developed off-target.
+
+use crate::HalError;
+use std::fs::File;
+
+/// True when hardware access is stubbed out on this platform
+pub const SIMULATED: bool = cfg!(not(target_os = "linux"));
+
+/// Name of the active backend (for logs and status output)
+pub fn backend_name() -> &'static str {
+    if SIMULATED {
+        "stub"
+    } else {
+        "linux"
+    }
+}
+
+/// Open device node (character device, tty, etc.)
+pub struct DeviceHandle {
+    path: String,
+    file: Option<File>,
+    open: bool,
+}
+
+impl DeviceHandle {
+    /// Open a device node for read/write
+    #[cfg(target_os = "linux")]
+    pub fn open(path: &str) -> Result<Self, HalError> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)?;
+        
+        Ok(Self {
+            path: path.to_string(),
+            file: Some(file),
+            open: true,
+        })
+    }
+    
+    /// Open a device node for read/write (stub backend)
+    #[cfg(not(target_os = "linux"))]
+    pub fn open(path: &str) -> Result<Self, HalError> {
+        tracing::debug!("Stub backend: simulating device {}", path);
+        Ok(Self {
+            path: path.to_string(),
+            file: None,
+            open: true,
+        })
+    }
+    
+    /// Device path
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    
+    /// Whether the device is open (always real on Linux, stubbed elsewhere)
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+    
+    /// Underlying file, if a real device is open
+    pub fn file(&self) -> Option<&File> {
+        self.file.as_ref()
+    }
+    
+    /// Raw file descriptor for ioctls
+    #[cfg(target_os = "linux")]
+    pub fn raw_fd(&self) -> Option<i32> {
+        use std::os::unix::io::AsRawFd;
+        self.file.as_ref().map(|f| f.as_raw_fd())
+    }
+    
+    /// Read bytes; the stub backend never has data available
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, HalError> {
+        use std::io::Read;
+        match self.file {
+            Some(ref file) => {
+                let mut file = file;
+                Ok(file.read(buf)?)
+            }
+            None if self.open => Ok(0),
+            None => Err(HalError::DeviceNotFound(format!("{} not open", self.path))),
+        }
+    }
+    
+    /// Write bytes; the stub backend discards them
+    pub fn write(&self, data: &[u8]) -> Result<usize, HalError> {
+        use std::io::Write;
+        match self.file {
+            Some(ref file) => {
+                let mut file = file;
+                let n = file.write(data)?;
+                file.flush()?;
+                Ok(n)
+            }
+            None if self.open => Ok(data.len()),
+            None => Err(HalError::DeviceNotFound(format!("{} not open", self.path))),
+        }
+    }
+    
+    /// Release the device
+    pub fn close(&mut self) {
+        self.file = None;
+        self.open = false;
+    }
+}
+
+/// Write a sysfs attribute; no-op on the stub backend
+pub fn write_sysfs(path: &str, value: &str) -> Result<(), HalError> {
+    if SIMULATED {
+        return Ok(());
+    }
+    
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    file.write_all(value.as_bytes())?;
+    Ok(())
+}
+
+/// Read a sysfs attribute; the stub backend returns `default`
+pub fn read_sysfs(path: &str, default: &str) -> Result<String, HalError> {
+    if SIMULATED {
+        return Ok(default.to_string());
+    }
+    
+    Ok(std::fs::read_to_string(path)?.trim().to_string())
+}
+
+/// Check whether a sysfs path exists; always true on the stub backend
+pub fn sysfs_exists(path: &str) -> bool {
+    SIMULATED || std::path::Path::new(path).exists()
+}
@@ -0,0 +1,122 @@
+//! Per-sensor poll scheduling
+//!
+//! Sensors differ by orders of magnitude in how fast they have anything new
+//! to say: a magnetometer converts at 75 Hz, a thermal camera delivers 9
+//! frames a second, a DHT22 needs two seconds between reads. Each sensor is
+//! polled at its own rate: set in `[poll.<sensor>]`, else the driver's
+//! preferred [`poll_rate`](crate::Sensor::poll_rate), else the global poll
+//! interval. A phase offset delays a sensor's polls within its period, so
+//! sensors sharing a bus at the same rate can be spread out instead of all
+//! being read on the same tick.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Poll rate of one sensor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PollSchedule {
+    /// Polls per second (`None` for the global poll interval)
+    #[serde(default)]
+    pub rate_hz: Option<f64>,
+    /// Delay of the sensor's polls within its period (ms)
+    #[serde(default)]
+    pub phase_ms: u64,
+}
+
+impl PollSchedule {
+    /// Interval between polls, `default` unless a positive rate is set
+    pub fn interval(&self, default: Duration) -> Duration {
+        match self.rate_hz {
+            Some(rate) if rate > 0.0 && rate.is_finite() => Duration::from_secs_f64(1.0 / rate).max(MIN_INTERVAL),
+            _ => default,
+        }
+    }
+    
+    pub fn phase(&self) -> Duration {
+        Duration::from_millis(self.phase_ms)
+    }
+}
+
+/// Shortest interval a sensor is polled at (1 kHz)
+pub const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Configured schedule of `name`: its own entry, else the first entry whose
+/// key is part of the name (ignoring case)
+pub fn lookup(schedules: &BTreeMap<String, PollSchedule>, name: &str) -> Option<PollSchedule> {
+    if let Some(schedule) = schedules.get(name) {
+        return Some(*schedule);
+    }
+    
+    let name = name.to_lowercase();
+    schedules.iter()
+        .find(|(key, _)| !key.is_empty() && name.contains(&key.to_lowercase()))
+        .map(|(_, schedule)| *schedule)
+}
+
+/// Next poll of one sensor
+struct Slot {
+    due: Instant,
+    interval: Duration,
+}
+
+/// Due times of the registered sensors
+pub(crate) struct Scheduler {
+    default: Duration,
+    slots: HashMap<String, Slot>,
+}
+
+impl Scheduler {
+    pub fn new(default: Duration) -> Self {
+        Self { default: default.max(MIN_INTERVAL), slots: HashMap::new() }
+    }
+    
+    /// Sensors due at `now`, out of `sensors` with their schedules
+    ///
+    /// A sensor seen for the first time is first due one phase offset from
+    /// now. Polls missed because a read took too long are skipped rather
+    /// than made up in a rush; sensors no longer listed are forgotten.
+    pub fn due<'a>(&mut self, now: Instant, sensors: impl IntoIterator<Item = (&'a str, PollSchedule)>) -> Vec<String> {
+        let mut due = Vec::new();
+        let mut seen = HashSet::new();
+        
+        for (name, schedule) in sensors {
+            seen.insert(name.to_string());
+            let interval = schedule.interval(self.default);
+            let slot = self.slots.entry(name.to_string()).or_insert_with(|| Slot {
+                due: now + schedule.phase(),
+                interval,
+            });
+            if slot.interval != interval {
+                slot.interval = interval;
+                slot.due = slot.due.min(now + interval);
+            }
+            
+            if slot.due <= now {
+                due.push(name.to_string());
+                let periods = now.duration_since(slot.due).as_nanos() / interval.as_nanos() + 1;
+                slot.due += interval * periods.min(u32::MAX as u128) as u32;
+            }
+        }
+        
+        self.slots.retain(|name, _| seen.contains(name));
+        due
+    }
+    
+    /// When the next sensor is due, no later than one default interval away
+    pub fn next_wake(&self, now: Instant) -> Instant {
+        self.slots.values()
+            .map(|slot| slot.due)
+            .min()
+            .map_or(now + self.default, |due| due.min(now + self.default))
+    }
+    
+    /// Shortest interval among `names`
+    pub fn shortest_interval(&self, names: &[String]) -> Duration {
+        names.iter()
+            .filter_map(|name| self.slots.get(name))
+            .map(|slot| slot.interval)
+            .min()
+            .unwrap_or(self.default)
+    }
+}
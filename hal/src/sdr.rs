@@ -1,8 +1,37 @@
 //! SDR (Software Defined Radio) interface for GlowBarn HAL
 //! Supports RTL-SDR for radio spectrum analysis
+//!
+//! RTL-SDR samples are in arbitrary units that shift with the tuner gain
+//! and differ between dongles. A [`PowerCalibration`] measures a known
+//! signal or noise source at each gain setting and records the offset from
+//! dB full scale to dBm, stored per dongle serial, so EMF magnitudes can be
+//! compared across rigs and sessions.
+//!
+//! Spectra are averaged periodograms ([`Spectrum`]): the capture is cut
+//! into half-overlapping windowed segments of [`SpectrumConfig::fft_size`]
+//! points whose power spectra are averaged, trading time resolution for a
+//! steadier noise floor.
+//!
+//! IQ samples come from an [`IqSource`]: the dongle through librtlsdr when
+//! built with the `rtlsdr` feature. Without it opening a dongle fails (off
+//! Linux it is simulated); a [`MockSdr`] generating noise and test tones
+//! can be given explicitly with [`RtlSdr::with_source`].
+//!
+//! A [`WidebandSweep`] retunes across a wide range on a schedule and
+//! reduces each pass to mean and peak power per fixed-width band, small
+//! enough to log every few minutes for a whole session.
 
-use crate::{HalError, HardwareDevice, DeviceType};
-use std::sync::{Arc, Mutex};
+use crate::calibration::CalibrationStore;
+use crate::{HalError, HardwareDevice, DeviceType, SensorReading};
+use glowbarn_dsp::{self as dsp, Complex64, Window};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+
+/// Device name power calibrations are stored under
+pub const POWER_CALIBRATION_DEVICE: &str = "rtl-sdr-power";
 
 /// SDR device configuration
 #[derive(Debug, Clone)]
@@ -24,90 +53,566 @@ impl Default for SdrConfig {
     }
 }
 
+/// How spectra are computed from IQ captures
+#[derive(Debug, Clone, Copy)]
+pub struct SpectrumConfig {
+    /// Points per FFT, and so bins per spectrum (rounded up to a power of two)
+    pub fft_size: usize,
+    pub window: Window,
+    /// FFTs averaged per spectrum
+    pub averages: usize,
+}
+
+impl Default for SpectrumConfig {
+    fn default() -> Self {
+        Self {
+            fft_size: 1024,
+            window: Window::Hann,
+            averages: 8,
+        }
+    }
+}
+
+impl SpectrumConfig {
+    fn size(&self) -> usize {
+        self.fft_size.max(2).next_power_of_two()
+    }
+    
+    /// IQ samples needed for a fully averaged spectrum
+    pub fn samples(&self) -> usize {
+        let size = self.size();
+        size + (self.averages.max(1) - 1) * size / 2
+    }
+}
+
+/// Averaged power spectrum of an IQ capture, from `-fs/2` to `+fs/2`
+/// around the tuned frequency
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    /// Centre frequency of the first bin (Hz)
+    pub start_hz: f64,
+    pub bin_hz: f64,
+    /// Power per bin, relative to full scale (a full-scale tone reads 1.0)
+    pub power: Vec<f64>,
+    /// FFTs averaged
+    pub averages: usize,
+}
+
+impl Spectrum {
+    /// Spectrum of `samples` captured at `sample_rate` around `center_hz`
+    ///
+    /// Uses as many half-overlapping segments as the capture holds, up to
+    /// `config.averages`; a capture shorter than one FFT is zero-padded.
+    pub fn compute(samples: &[Complex], config: &SpectrumConfig, center_hz: f64, sample_rate: f64) -> Self {
+        let size = config.size();
+        let hop = size / 2;
+        let iq: Vec<Complex64> = samples.iter().map(|&c| c.into()).collect();
+        
+        let segments = if iq.len() <= size {
+            1
+        } else {
+            ((iq.len() - size) / hop + 1).min(config.averages.max(1))
+        };
+        let mut power = vec![0.0; size];
+        for segment in 0..segments {
+            let start = segment * hop;
+            let end = (start + size).min(iq.len());
+            let mut padded = iq[start..end].to_vec();
+            padded.resize(size, Complex64::new(0.0, 0.0));
+            let segment_power = dsp::fft::power_spectrum_iq(&padded, config.window);
+            for (total, p) in power.iter_mut().zip(segment_power) {
+                *total += p / segments as f64;
+            }
+        }
+        
+        let bin_hz = sample_rate / size as f64;
+        Self {
+            start_hz: center_hz - sample_rate / 2.0,
+            bin_hz,
+            power,
+            averages: segments,
+        }
+    }
+    
+    /// Bin powers in dB full scale
+    pub fn db(&self) -> Vec<f64> {
+        self.power.iter().map(|&p| dsp::power_to_db(p)).collect()
+    }
+    
+    /// Centre frequency of bin `index` (Hz)
+    pub fn frequency(&self, index: usize) -> f64 {
+        self.start_hz + index as f64 * self.bin_hz
+    }
+    
+    /// Total power of the bins within `low..high` Hz
+    pub fn band_power(&self, low: f64, high: f64) -> f64 {
+        self.power.iter().enumerate()
+            .filter(|&(i, _)| (low..high).contains(&self.frequency(i)))
+            .map(|(_, &p)| p)
+            .sum()
+    }
+    
+    /// Median bin power, a robust noise floor estimate
+    pub fn noise_floor(&self) -> f64 {
+        dsp::stats::median(&self.power)
+    }
+    
+    /// Strongest bin as (frequency, power)
+    pub fn peak(&self) -> Option<(f64, f64)> {
+        self.power.iter().enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, &p)| (self.frequency(i), p))
+    }
+}
+
+/// Scheduled wideband sweep (`[sdr_sweep]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepConfig {
+    /// Dongle index
+    #[serde(default)]
+    pub device: u32,
+    /// Dongle serial, for its power calibration
+    #[serde(default)]
+    pub serial: Option<String>,
+    #[serde(default = "default_sweep_start")]
+    pub start_mhz: f64,
+    #[serde(default = "default_sweep_end")]
+    pub end_mhz: f64,
+    /// Width of each summarised band (MHz)
+    #[serde(default = "default_band_width")]
+    pub band_mhz: f64,
+    /// Time between the starts of consecutive sweeps
+    #[serde(default = "default_sweep_interval")]
+    pub interval_secs: u64,
+    /// Tuner gain (dB)
+    #[serde(default = "default_sweep_gain")]
+    pub gain_db: f64,
+    /// IQ samples captured at each tuning step
+    #[serde(default = "default_step_samples")]
+    pub samples_per_step: usize,
+}
+
+fn default_sweep_start() -> f64 { 24.0 }
+fn default_sweep_end() -> f64 { 1700.0 }
+fn default_band_width() -> f64 { 10.0 }
+fn default_sweep_interval() -> u64 { 600 }
+fn default_sweep_gain() -> f64 { 40.2 }
+fn default_step_samples() -> usize { 16_384 }
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        Self {
+            device: 0,
+            serial: None,
+            start_mhz: default_sweep_start(),
+            end_mhz: default_sweep_end(),
+            band_mhz: default_band_width(),
+            interval_secs: default_sweep_interval(),
+            gain_db: default_sweep_gain(),
+            samples_per_step: default_step_samples(),
+        }
+    }
+}
+
+/// Reference used to calibrate power readings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum CalibrationSource {
+    /// Signal generator at a known level, within the tuned span
+    KnownSignal {
+        power_dbm: f64,
+        /// Width around the strongest bin counted as the signal (Hz)
+        bandwidth_hz: f64,
+    },
+    /// Noise source flat across the tuned span
+    NoiseSource { dbm_per_hz: f64 },
+}
+
+impl CalibrationSource {
+    /// Expected power (dBm) and measured power (dBFS) of the source in `spectrum`
+    fn measure(&self, spectrum: &[f64], sample_rate: f64) -> Option<(f64, f64)> {
+        if spectrum.is_empty() {
+            return None;
+        }
+        
+        match *self {
+            CalibrationSource::KnownSignal { power_dbm, bandwidth_hz } => {
+                let bin_hz = sample_rate / spectrum.len() as f64;
+                // At least two bins each side for the window's leakage
+                let half = ((bandwidth_hz / 2.0 / bin_hz).ceil() as usize).max(2);
+                let peak = spectrum.iter().enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map(|(i, _)| i)?;
+                let band = &spectrum[peak.saturating_sub(half)..(peak + half + 1).min(spectrum.len())];
+                Some((power_dbm, dsp::power_to_db(band.iter().sum())))
+            }
+            CalibrationSource::NoiseSource { dbm_per_hz } => {
+                let expected = dbm_per_hz + 10.0 * sample_rate.log10();
+                Some((expected, dsp::power_to_db(spectrum.iter().sum())))
+            }
+        }
+    }
+}
+
+/// Offsets from dB full scale to dBm per gain setting of one dongle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerCalibration {
+    pub serial: Option<String>,
+    /// Frequency the calibration was measured at (Hz)
+    pub frequency: u64,
+    pub sample_rate: u32,
+    pub source: CalibrationSource,
+    /// dB to add to a dBFS power, by gain in 0.1 dB units
+    pub offsets: BTreeMap<i32, f64>,
+    pub calibrated_at: SystemTime,
+}
+
+impl PowerCalibration {
+    /// Offset at `gain` (0.1 dB units)
+    ///
+    /// Interpolated between measured gains; outside them the nearest offset
+    /// is moved by the gain difference, as the tuner gain is close to
+    /// linear in dB. `None` when nothing was measured.
+    pub fn offset_db(&self, gain: i32) -> Option<f64> {
+        let below = self.offsets.range(..=gain).next_back();
+        let above = self.offsets.range(gain..).next();
+        
+        match (below, above) {
+            (Some((&g0, &o0)), Some((&g1, &o1))) if g1 != g0 => {
+                let t = (gain - g0) as f64 / (g1 - g0) as f64;
+                Some(o0 + (o1 - o0) * t)
+            }
+            (Some((_, &offset)), Some(_)) => Some(offset),
+            (Some((&g, &offset)), None) | (None, Some((&g, &offset))) => {
+                Some(offset - (gain - g) as f64 / 10.0)
+            }
+            (None, None) => None,
+        }
+    }
+    
+    /// dBm of a dBFS power measured at `gain`
+    pub fn to_dbm(&self, dbfs: f64, gain: i32) -> Option<f64> {
+        self.offset_db(gain).map(|offset| dbfs + offset)
+    }
+    
+    /// Save under the dongle's serial
+    pub fn save(&self, store: &CalibrationStore) -> Result<(), HalError> {
+        store.save_value(POWER_CALIBRATION_DEVICE, self.serial.as_deref(), self)
+    }
+    
+    /// Stored calibration of a dongle, if any
+    pub fn load(store: &CalibrationStore, serial: Option<&str>) -> Result<Option<Self>, HalError> {
+        store.load_value(POWER_CALIBRATION_DEVICE, serial)
+    }
+}
+
+/// Tuner and sample stream behind an [`RtlSdr`]
+pub trait IqSource: Send {
+    /// Apply tuning, sample rate and gain (`None` gain is AGC)
+    fn configure(&mut self, config: &SdrConfig) -> Result<(), HalError>;
+    
+    /// Read `count` IQ samples, scaled to -1..1
+    fn read(&mut self, count: usize) -> Result<Vec<Complex>, HalError>;
+    
+    /// Serial from the dongle's EEPROM, if it has one
+    fn serial(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Simulated dongle: 8-bit quantised white noise plus optional test tones
+pub struct MockSdr {
+    seed: u64,
+    noise: f64,
+    tones: Vec<(f64, f64)>,
+    config: SdrConfig,
+    /// Samples generated since the last retune, for continuous tone phase
+    elapsed: u64,
+}
+
+impl MockSdr {
+    pub fn new() -> Self {
+        Self {
+            seed: 12345,
+            noise: 1.0,
+            tones: Vec::new(),
+            config: SdrConfig::default(),
+            elapsed: 0,
+        }
+    }
+    
+    /// Noise amplitude (full scale is 1.0)
+    pub fn with_noise(mut self, amplitude: f64) -> Self {
+        self.noise = amplitude;
+        self
+    }
+    
+    /// Add a carrier at `frequency` Hz, heard while it is within the tuned span
+    pub fn with_tone(mut self, frequency: f64, amplitude: f64) -> Self {
+        self.tones.push((frequency, amplitude));
+        self
+    }
+    
+    /// Simple LCG, good enough for noise
+    fn next_byte(&mut self) -> u8 {
+        self.seed = self.seed.wrapping_mul(1103515245).wrapping_add(12345);
+        (self.seed >> 16) as u8
+    }
+}
+
+impl Default for MockSdr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IqSource for MockSdr {
+    fn configure(&mut self, config: &SdrConfig) -> Result<(), HalError> {
+        self.config = config.clone();
+        self.elapsed = 0;
+        Ok(())
+    }
+    
+    fn read(&mut self, count: usize) -> Result<Vec<Complex>, HalError> {
+        let rate = self.config.sample_rate.max(1) as f64;
+        let center = self.config.center_frequency as f64;
+        let mut samples = Vec::with_capacity(count);
+        
+        for _ in 0..count {
+            let mut sample = Complex {
+                i: (self.next_byte() as f64 - 127.5) / 127.5 * self.noise,
+                q: (self.next_byte() as f64 - 127.5) / 127.5 * self.noise,
+            };
+            let t = self.elapsed as f64 / rate;
+            for &(frequency, amplitude) in &self.tones {
+                let offset = frequency - center;
+                if offset.abs() < rate / 2.0 {
+                    let phase = 2.0 * std::f64::consts::PI * offset * t;
+                    sample.i += amplitude * phase.cos();
+                    sample.q += amplitude * phase.sin();
+                }
+            }
+            self.elapsed += 1;
+            samples.push(sample);
+        }
+        Ok(samples)
+    }
+}
+
 /// RTL-SDR device
 pub struct RtlSdr {
     name: String,
     config: SdrConfig,
     device_index: u32,
+    serial: Option<String>,
+    power_calibration: Option<PowerCalibration>,
+    spectrum: SpectrumConfig,
+    /// Given source, or the one opened by `init`
+    source: Option<Mutex<Box<dyn IqSource>>>,
+    /// Whether `source` was opened by `init`, and is dropped by `close`
+    opened: bool,
     ready: bool,
-    buffer: Arc<Mutex<Vec<u8>>>,
 }
 
 impl RtlSdr {
-    /// Open RTL-SDR device
+    /// RTL-SDR device `device_index`, opened by [`init`](HardwareDevice::init)
     pub fn open(device_index: u32) -> Result<Self, HalError> {
         Ok(Self {
             name: format!("RTL-SDR #{}", device_index),
             config: SdrConfig::default(),
             device_index,
+            serial: None,
+            power_calibration: None,
+            spectrum: SpectrumConfig::default(),
+            source: None,
+            opened: false,
             ready: false,
-            buffer: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+    
+    /// SDR reading from `source` instead of a dongle (e.g. a [`MockSdr`])
+    pub fn with_source(name: &str, source: impl IqSource + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            config: SdrConfig::default(),
+            device_index: 0,
+            serial: None,
+            power_calibration: None,
+            spectrum: SpectrumConfig::default(),
+            source: Some(Mutex::new(Box::new(source))),
+            opened: false,
+            ready: false,
+        }
+    }
+    
+    /// Push the current settings to the source, once it is open
+    fn apply_config(&self) -> Result<(), HalError> {
+        match &self.source {
+            Some(source) if self.ready => source.lock().unwrap().configure(&self.config),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Set the dongle's serial (from its EEPROM), which keys its calibration
+    pub fn with_serial(mut self, serial: &str) -> Self {
+        self.serial = Some(serial.to_string());
+        self
+    }
+    
+    /// Dongle serial, if known
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+    
+    /// Current settings
+    pub fn config(&self) -> &SdrConfig {
+        &self.config
+    }
+    
+    /// FFT size, window and averaging of spectra
+    pub fn set_spectrum_config(&mut self, config: SpectrumConfig) {
+        self.spectrum = config;
+    }
+    
+    pub fn spectrum_config(&self) -> &SpectrumConfig {
+        &self.spectrum
+    }
+    
+    /// Report powers in dBm using `calibration`
+    pub fn set_power_calibration(&mut self, calibration: PowerCalibration) {
+        self.power_calibration = Some(calibration);
+    }
+    
+    /// Apply the dongle's stored power calibration, if there is one
+    pub fn load_power_calibration(&mut self, store: &CalibrationStore) -> Result<bool, HalError> {
+        let calibration = PowerCalibration::load(store, self.serial())?;
+        let found = calibration.is_some();
+        self.power_calibration = calibration;
+        Ok(found)
+    }
+    
+    pub fn power_calibration(&self) -> Option<&PowerCalibration> {
+        self.power_calibration.as_ref()
+    }
+    
+    /// dBm of a dBFS power at the current gain (`None` uncalibrated or under AGC)
+    pub fn to_dbm(&self, dbfs: f64) -> Option<f64> {
+        if self.config.agc {
+            return None;
+        }
+        self.power_calibration.as_ref()?.to_dbm(dbfs, self.config.gain)
+    }
+    
+    /// Measure `source` at each of `gains` (0.1 dB units)
+    ///
+    /// Connect the source to the antenna input and tune so a known signal
+    /// falls inside the span first. `count` samples are averaged per gain.
+    /// The previous gain setting is restored afterwards.
+    pub fn calibrate_power(&mut self, source: CalibrationSource, gains: &[i32], count: usize) -> Result<PowerCalibration, HalError> {
+        let previous = (self.config.gain, self.config.agc);
+        let mut offsets = BTreeMap::new();
+        
+        let result: Result<(), HalError> = gains.iter().try_for_each(|&gain| {
+            self.set_gain(gain)?;
+            let samples = self.read_samples(count)?;
+            let spectrum = self.power_spectrum(&samples);
+            let (expected, measured) = source.measure(&spectrum, self.config.sample_rate as f64)
+                .ok_or_else(|| HalError::CommunicationError("no samples to calibrate with".to_string()))?;
+            tracing::debug!("SDR gain {:.1} dB: {:.1} dBFS for {:.1} dBm", gain as f64 / 10.0, measured, expected);
+            offsets.insert(gain, expected - measured);
+            Ok(())
+        });
+        
+        self.config.gain = previous.0;
+        self.config.agc = previous.1;
+        let restored = self.apply_config();
+        result?;
+        restored?;
+        
+        Ok(PowerCalibration {
+            serial: self.serial.clone(),
+            frequency: self.config.center_frequency,
+            sample_rate: self.config.sample_rate,
+            source,
+            offsets,
+            calibrated_at: SystemTime::now(),
         })
     }
     
     /// Set center frequency
     pub fn set_frequency(&mut self, freq: u64) -> Result<(), HalError> {
-        if freq < 24_000_000 || freq > 1_766_000_000 {
+        if !(24_000_000..=1_766_000_000).contains(&freq) {
             return Err(HalError::InvalidConfig(
                 "Frequency must be between 24 MHz and 1766 MHz".to_string()
             ));
         }
         self.config.center_frequency = freq;
-        // In production: rtlsdr_set_center_freq()
-        Ok(())
+        self.apply_config()
     }
     
     /// Set sample rate
     pub fn set_sample_rate(&mut self, rate: u32) -> Result<(), HalError> {
-        if rate < 225_000 || rate > 3_200_000 {
+        if !(225_000..=3_200_000).contains(&rate) {
             return Err(HalError::InvalidConfig(
                 "Sample rate must be between 225 kHz and 3.2 MHz".to_string()
             ));
         }
         self.config.sample_rate = rate;
-        Ok(())
+        self.apply_config()
     }
     
     /// Set gain (in 0.1 dB units)
     pub fn set_gain(&mut self, gain: i32) -> Result<(), HalError> {
         self.config.gain = gain;
         self.config.agc = false;
-        Ok(())
+        self.apply_config()
     }
     
     /// Enable automatic gain control
     pub fn enable_agc(&mut self) -> Result<(), HalError> {
         self.config.agc = true;
-        Ok(())
+        self.apply_config()
     }
     
     /// Read IQ samples
     pub fn read_samples(&self, count: usize) -> Result<Vec<Complex>, HalError> {
-        if !self.ready {
-            return Err(HalError::DeviceNotFound("SDR not initialized".to_string()));
-        }
-        
-        // In production, this would read from RTL-SDR
-        // RTL-SDR outputs interleaved I/Q bytes (unsigned 8-bit)
-        let mut samples = Vec::with_capacity(count);
-        
-        // Simulate noise for testing
-        for _ in 0..count {
-            samples.push(Complex {
-                i: (rand_byte() as f64 - 127.5) / 127.5,
-                q: (rand_byte() as f64 - 127.5) / 127.5,
-            });
+        match &self.source {
+            Some(source) if self.ready => source.lock().unwrap().read(count),
+            _ => Err(HalError::DeviceNotFound("SDR not initialized".to_string())),
         }
-        
-        Ok(samples)
     }
     
-    /// Calculate power spectrum (simplified FFT)
+    /// Spectrum of `samples` at the current tuning
+    pub fn spectrum(&self, samples: &[Complex]) -> Spectrum {
+        Spectrum::compute(samples, &self.spectrum, self.config.center_frequency as f64, self.config.sample_rate as f64)
+    }
+    
+    /// Capture enough samples for a fully averaged spectrum
+    pub fn capture_spectrum(&self) -> Result<Spectrum, HalError> {
+        let samples = self.read_samples(self.spectrum.samples())?;
+        Ok(self.spectrum(&samples))
+    }
+    
+    /// Bin powers of `samples`' spectrum (see [`RtlSdr::spectrum`])
     pub fn power_spectrum(&self, samples: &[Complex]) -> Vec<f64> {
-        // In production, use rustfft for proper FFT
-        samples.iter()
-            .map(|c| (c.i * c.i + c.q * c.q).sqrt())
-            .collect()
+        self.spectrum(samples).power
+    }
+    
+    /// Capture `count` samples as a spectrum reading centred on the tuned frequency
+    ///
+    /// Bins are in dBm when the dongle is calibrated, dBFS otherwise.
+    pub fn read_spectrum(&self, sensor_name: &str, count: usize) -> Result<SensorReading, HalError> {
+        let spectrum = self.spectrum(&self.read_samples(count)?);
+        let offset = self.to_dbm(0.0);
+        let bins: Vec<f64> = spectrum.db().iter()
+            .map(|&db| db + offset.unwrap_or(0.0))
+            .collect();
+        
+        let mut reading = SensorReading::spectrum(sensor_name, spectrum.start_hz, spectrum.bin_hz, bins);
+        if offset.is_some() {
+            reading.unit = "dBm".to_string();
+        }
+        Ok(reading)
     }
     
     /// Scan frequency range for signals
@@ -139,6 +644,73 @@ impl RtlSdr {
         
         Ok(peaks)
     }
+    
+    /// Sweep `config`'s range once and summarise power per band
+    ///
+    /// Only the middle 80% of each capture is used, away from the
+    /// anti-alias filter's roll-off. Tuning and gain are restored afterwards.
+    pub fn sweep(&mut self, config: &SweepConfig) -> Result<SweepSummary, HalError> {
+        let previous = self.config.clone();
+        let started = Instant::now();
+        let started_at = SystemTime::now();
+        
+        let start_hz = config.start_mhz * 1e6;
+        let end_hz = config.end_mhz * 1e6;
+        let band_hz = config.band_mhz.max(0.01) * 1e6;
+        if end_hz <= start_hz {
+            return Err(HalError::InvalidConfig("Sweep end must be above its start".to_string()));
+        }
+        
+        let count = ((end_hz - start_hz) / band_hz).ceil() as usize;
+        let mut bands: Vec<BandAccumulator> = vec![BandAccumulator::default(); count];
+        let step_hz = self.config.sample_rate as f64 * 0.8;
+        
+        let result: Result<String, HalError> = (|| {
+            self.set_gain((config.gain_db * 10.0).round() as i32)?;
+            let mut low = start_hz;
+            let mut unit = String::new();
+            while low < end_hz {
+                let center = low + step_hz / 2.0;
+                self.set_frequency(center.round() as u64)?;
+                let reading = self.read_spectrum("sweep", config.samples_per_step)?;
+                let crate::ReadingPayload::Spectrum { start_hz: first, bin_hz, bins } = &reading.payload else {
+                    unreachable!("read_spectrum returns a spectrum");
+                };
+                
+                for (i, &db) in bins.iter().enumerate() {
+                    let freq = first + i as f64 * bin_hz;
+                    if freq < low || freq >= (low + step_hz).min(end_hz) {
+                        continue;
+                    }
+                    let index = ((freq - start_hz) / band_hz) as usize;
+                    if let Some(band) = bands.get_mut(index) {
+                        band.add(freq, db);
+                    }
+                }
+                unit = reading.unit;
+                low += step_hz;
+            }
+            Ok(unit)
+        })();
+        
+        self.config = previous;
+        let restored = self.apply_config();
+        let unit = result?;
+        restored?;
+        
+        let bands = bands.iter().enumerate()
+            .filter_map(|(i, band)| band.summary(config.start_mhz + i as f64 * config.band_mhz.max(0.01)))
+            .collect();
+        
+        Ok(SweepSummary {
+            timestamp: started_at,
+            duration_ms: started.elapsed().as_millis() as u64,
+            serial: self.serial.clone(),
+            unit,
+            band_mhz: config.band_mhz,
+            bands,
+        })
+    }
 }
 
 impl HardwareDevice for RtlSdr {
@@ -151,9 +723,21 @@ impl HardwareDevice for RtlSdr {
     }
     
     fn init(&mut self) -> Result<(), HalError> {
-        // In production: rtlsdr_open()
+        if self.source.is_none() {
+            let source = open_dongle(self.device_index, self.serial.as_deref())?;
+            if self.serial.is_none() {
+                self.serial = source.serial();
+            }
+            self.source = Some(Mutex::new(source));
+            self.opened = true;
+        }
+        
         self.ready = true;
-        tracing::info!("RTL-SDR #{} initialized", self.device_index);
+        if let Err(e) = self.apply_config() {
+            self.ready = false;
+            return Err(e);
+        }
+        tracing::info!("{} initialized", self.name);
         Ok(())
     }
     
@@ -161,8 +745,14 @@ impl HardwareDevice for RtlSdr {
         self.ready
     }
     
+    /// Release the dongle, so a re-init opens it afresh (after a replug,
+    /// say); a source given with [`with_source`](RtlSdr::with_source) is kept
     fn close(&mut self) -> Result<(), HalError> {
         self.ready = false;
+        if self.opened {
+            self.source = None;
+            self.opened = false;
+        }
         Ok(())
     }
 }
@@ -184,6 +774,12 @@ impl Complex {
     }
 }
 
+impl From<Complex> for Complex64 {
+    fn from(c: Complex) -> Self {
+        Complex64::new(c.i, c.q)
+    }
+}
+
 /// Detected signal peak
 #[derive(Debug, Clone)]
 pub struct SignalPeak {
@@ -194,8 +790,8 @@ pub struct SignalPeak {
 
 /// EMF spectrum analyzer using SDR
 pub struct EmfAnalyzer {
-    sdr: RtlSdr,
-    baseline: Option<Vec<f64>>,
+    pub sdr: RtlSdr,
+    baseline: Option<Spectrum>,
 }
 
 impl EmfAnalyzer {
@@ -209,38 +805,43 @@ impl EmfAnalyzer {
     }
     
     /// Capture baseline (ambient EMF)
+    ///
+    /// Recapture after changing the SDR's tuning or spectrum settings.
     pub fn capture_baseline(&mut self) -> Result<(), HalError> {
-        let samples = self.sdr.read_samples(4096)?;
-        self.baseline = Some(self.sdr.power_spectrum(&samples));
+        self.baseline = Some(self.sdr.capture_spectrum()?);
         tracing::info!("EMF baseline captured");
         Ok(())
     }
     
-    /// Detect EMF anomalies compared to baseline
+    /// Baseline spectrum, if captured
+    pub fn baseline(&self) -> Option<&Spectrum> {
+        self.baseline.as_ref()
+    }
+    
+    /// Detect bins whose power is more than `threshold` times the baseline's
     pub fn detect_anomalies(&self, threshold: f64) -> Result<Vec<EmfAnomaly>, HalError> {
-        let samples = self.sdr.read_samples(4096)?;
-        let current = self.sdr.power_spectrum(&samples);
-        
         let baseline = self.baseline.as_ref()
             .ok_or_else(|| HalError::InvalidConfig("No baseline captured".to_string()))?;
+        let current = self.sdr.capture_spectrum()?;
+        if current.power.len() != baseline.power.len() {
+            return Err(HalError::InvalidConfig("FFT size changed since the baseline was captured".to_string()));
+        }
         
-        let mut anomalies = Vec::new();
-        
-        for (i, (&curr, &base)) in current.iter().zip(baseline.iter()).enumerate() {
-            let ratio = if base > 0.0 { curr / base } else { curr };
-            
-            if ratio > threshold {
-                // Calculate approximate frequency offset
-                let bin_hz = self.sdr.config.sample_rate as f64 / baseline.len() as f64;
-                let freq_offset = (i as f64 - baseline.len() as f64 / 2.0) * bin_hz;
-                
-                anomalies.push(EmfAnomaly {
-                    frequency_offset: freq_offset as i64,
+        // Empty baseline bins are compared against the noise floor instead
+        let floor = baseline.noise_floor();
+        let center = self.sdr.config.center_frequency as f64;
+        let anomalies = current.power.iter().zip(&baseline.power).enumerate()
+            .filter_map(|(i, (&curr, &base))| {
+                let base = if base > 0.0 { base } else { floor };
+                let ratio = if base > 0.0 { curr / base } else { curr };
+                (ratio > threshold).then(|| EmfAnomaly {
+                    frequency_offset: (current.frequency(i) - center) as i64,
                     power_ratio: ratio,
                     absolute_power: curr,
-                });
-            }
-        }
+                    power_dbm: self.sdr.to_dbm(dsp::power_to_db(curr)),
+                })
+            })
+            .collect();
         
         Ok(anomalies)
     }
@@ -274,9 +875,13 @@ impl EmfAnalyzer {
 
 #[derive(Debug, Clone)]
 pub struct EmfAnomaly {
+    /// Bin frequency relative to the tuned frequency (Hz)
     pub frequency_offset: i64,
     pub power_ratio: f64,
+    /// Bin power relative to full scale
     pub absolute_power: f64,
+    /// Bin power in dBm, when the SDR is calibrated
+    pub power_dbm: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -288,10 +893,12 @@ pub struct EmfBurst {
 
 /// Radio scanner for EVP sessions
 pub struct RadioScanner {
-    sdr: RtlSdr,
+    pub sdr: RtlSdr,
     sweep_start: u64,
     sweep_end: u64,
     dwell_time_ms: u32,
+    /// Bandwidth of one station (Hz), measured around each step
+    channel_hz: f64,
 }
 
 impl RadioScanner {
@@ -303,6 +910,7 @@ impl RadioScanner {
             sweep_start: 88_000_000,   // 88 MHz
             sweep_end: 108_000_000,    // 108 MHz
             dwell_time_ms: 50,
+            channel_hz: 200_000.0,
         })
     }
     
@@ -314,6 +922,7 @@ impl RadioScanner {
             sweep_start: 530_000,      // 530 kHz
             sweep_end: 1_700_000,      // 1700 kHz
             dwell_time_ms: 30,
+            channel_hz: 10_000.0,
         })
     }
     
@@ -328,6 +937,19 @@ impl RadioScanner {
         self.dwell_time_ms = ms;
     }
     
+    /// Set the bandwidth measured around each step
+    pub fn set_channel_width(&mut self, hz: f64) {
+        self.channel_hz = hz;
+    }
+    
+    /// Power of the channel at the tuned frequency (dBFS)
+    fn channel_power(&self) -> Result<f64, HalError> {
+        let spectrum = self.sdr.capture_spectrum()?;
+        let center = self.sdr.config.center_frequency as f64;
+        let half = (self.channel_hz / 2.0).max(spectrum.bin_hz);
+        Ok(dsp::power_to_db(spectrum.band_power(center - half, center + half)))
+    }
+    
     /// Perform single sweep
     pub fn sweep(&mut self) -> Result<Vec<RadioSample>, HalError> {
         let step = 200_000;  // 200 kHz steps
@@ -338,8 +960,7 @@ impl RadioScanner {
             self.sdr.set_frequency(freq)?;
             std::thread::sleep(std::time::Duration::from_millis(self.dwell_time_ms as u64));
             
-            let iq = self.sdr.read_samples(1024)?;
-            let power = iq.iter().map(|c| c.magnitude()).sum::<f64>() / iq.len() as f64;
+            let power = self.channel_power()?;
             
             samples.push(RadioSample {
                 frequency: freq,
@@ -355,7 +976,7 @@ impl RadioScanner {
     /// Continuous sweep with callback
     pub fn continuous_sweep<F>(&mut self, mut callback: F) -> Result<(), HalError>
     where
-        F: FnMut(u64, f64) -> bool,  // frequency, power -> continue?
+        F: FnMut(u64, f64) -> bool,  // frequency, power (dBFS) -> continue?
     {
         let step = 200_000;
         let mut freq = self.sweep_start;
@@ -364,8 +985,7 @@ impl RadioScanner {
             self.sdr.set_frequency(freq)?;
             std::thread::sleep(std::time::Duration::from_millis(self.dwell_time_ms as u64));
             
-            let iq = self.sdr.read_samples(1024)?;
-            let power = iq.iter().map(|c| c.magnitude()).sum::<f64>() / iq.len() as f64;
+            let power = self.channel_power()?;
             
             if !callback(freq, power) {
                 break;
@@ -384,22 +1004,134 @@ impl RadioScanner {
 #[derive(Debug, Clone)]
 pub struct RadioSample {
     pub frequency: u64,
+    /// Channel power (dBFS)
     pub power: f64,
 }
 
-/// Simple pseudo-random byte generator for testing
-fn rand_byte() -> u8 {
-    static mut SEED: u64 = 12345;
-    unsafe {
-        SEED = SEED.wrapping_mul(1103515245).wrapping_add(12345);
-        (SEED >> 16) as u8
+/// Power summary of one band of a wideband sweep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandPower {
+    pub start_mhz: f64,
+    /// Power averaged over the band's bins
+    pub mean: f64,
+    /// Strongest bin
+    pub peak: f64,
+    pub peak_mhz: f64,
+}
+
+/// One pass of a wideband sweep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepSummary {
+    pub timestamp: SystemTime,
+    pub duration_ms: u64,
+    pub serial: Option<String>,
+    /// Unit of the band powers ("dBm" when calibrated, otherwise "dB" full scale)
+    pub unit: String,
+    pub band_mhz: f64,
+    pub bands: Vec<BandPower>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct BandAccumulator {
+    linear: f64,
+    bins: usize,
+    peak: Option<(f64, f64)>,
+}
+
+impl BandAccumulator {
+    fn add(&mut self, freq: f64, db: f64) {
+        self.linear += 10f64.powf(db / 10.0);
+        self.bins += 1;
+        if self.peak.is_none_or(|(_, peak)| db > peak) {
+            self.peak = Some((freq, db));
+        }
+    }
+    
+    /// Rounded to 0.1 dB and 1 kHz to keep logged sweeps small
+    fn summary(&self, start_mhz: f64) -> Option<BandPower> {
+        let (peak_hz, peak) = self.peak?;
+        let round = |x: f64, places: i32| {
+            let scale = 10f64.powi(places);
+            (x * scale).round() / scale
+        };
+        Some(BandPower {
+            start_mhz: round(start_mhz, 3),
+            mean: round(dsp::power_to_db(self.linear / self.bins as f64), 1),
+            peak: round(peak, 1),
+            peak_mhz: round(peak_hz / 1e6, 3),
+        })
+    }
+}
+
+/// Sweeps a dongle on a schedule, handing each summary to a channel
+pub struct WidebandSweep {
+    sdr: RtlSdr,
+    config: SweepConfig,
+}
+
+impl WidebandSweep {
+    /// Open and initialise the configured dongle
+    pub fn open(config: SweepConfig) -> Result<Self, HalError> {
+        let mut sdr = RtlSdr::open(config.device)?;
+        if let Some(serial) = &config.serial {
+            sdr = sdr.with_serial(serial);
+        }
+        sdr.init()?;
+        Ok(Self { sdr, config })
+    }
+    
+    /// Apply the dongle's stored power calibration, if there is one
+    pub fn load_power_calibration(&mut self, store: &CalibrationStore) -> Result<bool, HalError> {
+        self.sdr.load_power_calibration(store)
+    }
+    
+    /// Sweep on a background thread until the receiver is dropped
+    pub fn spawn(mut self, tx: mpsc::Sender<SweepSummary>) {
+        std::thread::spawn(move || {
+            let interval = Duration::from_secs(self.config.interval_secs.max(1));
+            loop {
+                let started = Instant::now();
+                match self.sdr.sweep(&self.config) {
+                    Ok(summary) => {
+                        if tx.blocking_send(summary).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => tracing::warn!("Wideband sweep on {} failed: {}", self.sdr.name(), e),
+                }
+                std::thread::sleep(interval.saturating_sub(started.elapsed()));
+                if tx.is_closed() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Open dongle `index`, or the one with `serial` when given
+#[cfg(feature = "rtlsdr")]
+fn open_dongle(index: u32, serial: Option<&str>) -> Result<Box<dyn IqSource>, HalError> {
+    Ok(Box::new(librtlsdr::Dongle::open(index, serial)?))
+}
+
+#[cfg(not(feature = "rtlsdr"))]
+fn open_dongle(index: u32, _serial: Option<&str>) -> Result<Box<dyn IqSource>, HalError> {
+    if !crate::platform::SIMULATED {
+        return Err(HalError::DeviceNotFound(format!("RTL-SDR #{} (built without the rtlsdr feature)", index)));
     }
+    Ok(Box::new(MockSdr::new()))
 }
 
 /// Enumerate RTL-SDR devices
+#[cfg(feature = "rtlsdr")]
 pub fn enumerate_devices() -> Vec<u32> {
-    // In production: rtlsdr_get_device_count()
-    // For now, assume up to 4 devices
+    (0..librtlsdr::device_count()).collect()
+}
+
+/// Enumerate RTL-SDR devices
+#[cfg(not(feature = "rtlsdr"))]
+pub fn enumerate_devices() -> Vec<u32> {
+    // Without librtlsdr, assume up to 4 devices
     let mut devices = Vec::new();
     for i in 0..4 {
         // Check if device exists
@@ -410,3 +1142,159 @@ pub fn enumerate_devices() -> Vec<u32> {
     }
     devices
 }
+
+/// Dongles through librtlsdr's synchronous API
+#[cfg(feature = "rtlsdr")]
+mod librtlsdr {
+    use super::{Complex, IqSource, SdrConfig};
+    use crate::HalError;
+    use std::ffi::{c_char, c_int, c_void, CStr, CString};
+    
+    #[link(name = "rtlsdr")]
+    extern "C" {
+        fn rtlsdr_get_device_count() -> u32;
+        fn rtlsdr_get_index_by_serial(serial: *const c_char) -> c_int;
+        fn rtlsdr_get_device_usb_strings(index: u32, manufacturer: *mut c_char, product: *mut c_char, serial: *mut c_char) -> c_int;
+        fn rtlsdr_open(dev: *mut *mut c_void, index: u32) -> c_int;
+        fn rtlsdr_close(dev: *mut c_void) -> c_int;
+        fn rtlsdr_set_center_freq(dev: *mut c_void, freq: u32) -> c_int;
+        fn rtlsdr_set_sample_rate(dev: *mut c_void, rate: u32) -> c_int;
+        fn rtlsdr_set_tuner_gain_mode(dev: *mut c_void, manual: c_int) -> c_int;
+        fn rtlsdr_set_tuner_gain(dev: *mut c_void, gain: c_int) -> c_int;
+        fn rtlsdr_set_agc_mode(dev: *mut c_void, on: c_int) -> c_int;
+        fn rtlsdr_reset_buffer(dev: *mut c_void) -> c_int;
+        fn rtlsdr_read_sync(dev: *mut c_void, buf: *mut c_void, len: c_int, n_read: *mut c_int) -> c_int;
+    }
+    
+    /// USB bulk transfers are whole multiples of this
+    const TRANSFER: usize = 512;
+    
+    pub fn device_count() -> u32 {
+        unsafe { rtlsdr_get_device_count() }
+    }
+    
+    fn check(ret: c_int, what: &str) -> Result<(), HalError> {
+        if ret < 0 {
+            Err(HalError::CommunicationError(format!("rtlsdr_{} failed ({})", what, ret)))
+        } else {
+            Ok(())
+        }
+    }
+    
+    /// Open dongle
+    pub struct Dongle {
+        dev: *mut c_void,
+        serial: Option<String>,
+    }
+    
+    // librtlsdr devices may be used from any one thread at a time; RtlSdr
+    // keeps its source behind a mutex
+    unsafe impl Send for Dongle {}
+    
+    impl Dongle {
+        pub fn open(index: u32, serial: Option<&str>) -> Result<Self, HalError> {
+            let index = match serial {
+                Some(serial) => {
+                    let c_serial = CString::new(serial)
+                        .map_err(|_| HalError::InvalidConfig(format!("Invalid SDR serial {:?}", serial)))?;
+                    let found = unsafe { rtlsdr_get_index_by_serial(c_serial.as_ptr()) };
+                    u32::try_from(found)
+                        .map_err(|_| HalError::DeviceNotFound(format!("RTL-SDR with serial {}", serial)))?
+                }
+                None => index,
+            };
+            if index >= device_count() {
+                return Err(HalError::DeviceNotFound(format!("RTL-SDR #{}", index)));
+            }
+            
+            let mut strings = [[0 as c_char; 256]; 3];
+            let [manufacturer, product, serial] = &mut strings;
+            let serial = unsafe {
+                (rtlsdr_get_device_usb_strings(index, manufacturer.as_mut_ptr(), product.as_mut_ptr(), serial.as_mut_ptr()) == 0)
+                    .then(|| CStr::from_ptr(serial.as_ptr()).to_string_lossy().trim().to_string())
+                    .filter(|serial| !serial.is_empty())
+            };
+            
+            let mut dev = std::ptr::null_mut();
+            check(unsafe { rtlsdr_open(&mut dev, index) }, "open")?;
+            let dongle = Self { dev, serial };
+            check(unsafe { rtlsdr_reset_buffer(dongle.dev) }, "reset_buffer")?;
+            Ok(dongle)
+        }
+    }
+    
+    impl IqSource for Dongle {
+        fn configure(&mut self, config: &SdrConfig) -> Result<(), HalError> {
+            let frequency = u32::try_from(config.center_frequency)
+                .map_err(|_| HalError::InvalidConfig(format!("Frequency {} Hz out of range", config.center_frequency)))?;
+            unsafe {
+                check(rtlsdr_set_sample_rate(self.dev, config.sample_rate), "set_sample_rate")?;
+                check(rtlsdr_set_center_freq(self.dev, frequency), "set_center_freq")?;
+                if config.agc {
+                    check(rtlsdr_set_tuner_gain_mode(self.dev, 0), "set_tuner_gain_mode")?;
+                    check(rtlsdr_set_agc_mode(self.dev, 1), "set_agc_mode")?;
+                } else {
+                    check(rtlsdr_set_agc_mode(self.dev, 0), "set_agc_mode")?;
+                    check(rtlsdr_set_tuner_gain_mode(self.dev, 1), "set_tuner_gain_mode")?;
+                    check(rtlsdr_set_tuner_gain(self.dev, config.gain), "set_tuner_gain")?;
+                }
+            }
+            Ok(())
+        }
+        
+        fn read(&mut self, count: usize) -> Result<Vec<Complex>, HalError> {
+            // Interleaved unsigned 8-bit I and Q
+            let len = (count * 2).div_ceil(TRANSFER).max(1) * TRANSFER;
+            let mut buf = vec![0u8; len];
+            let mut n_read: c_int = 0;
+            check(unsafe { rtlsdr_read_sync(self.dev, buf.as_mut_ptr().cast(), len as c_int, &mut n_read) }, "read_sync")?;
+            
+            let n = (n_read.max(0) as usize).min(len) / 2;
+            if n < count {
+                tracing::debug!("RTL-SDR short read: {} of {} samples", n, count);
+            }
+            Ok(buf[..n.min(count) * 2].chunks_exact(2)
+                .map(|iq| Complex {
+                    i: (iq[0] as f64 - 127.5) / 127.5,
+                    q: (iq[1] as f64 - 127.5) / 127.5,
+                })
+                .collect())
+        }
+        
+        fn serial(&self) -> Option<String> {
+            self.serial.clone()
+        }
+    }
+    
+    impl Drop for Dongle {
+        fn drop(&mut self) {
+            unsafe {
+                rtlsdr_close(self.dev);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn keeps_a_given_source_across_close() {
+        let mut sdr = RtlSdr::with_source("mock", MockSdr::new());
+        sdr.init().unwrap();
+        sdr.close().unwrap();
+        assert!(sdr.read_samples(16).is_err());
+        sdr.init().unwrap();
+        assert_eq!(sdr.read_samples(16).unwrap().len(), 16);
+    }
+    
+    #[test]
+    fn drops_an_opened_source_on_close() {
+        let mut sdr = RtlSdr::with_source("mock", MockSdr::new());
+        sdr.opened = true;
+        sdr.init().unwrap();
+        sdr.close().unwrap();
+        assert!(sdr.source.is_none());
+    }
+}
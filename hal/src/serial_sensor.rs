@@ -0,0 +1,164 @@
+//! Generic ASCII-protocol serial sensors
+//!
+//! Integrates one-off instruments (Arduino sketches, bench meters) that
+//! answer a text command with a line of text. Each sensor is described by
+//! a [`SerialSensorConfig`] entry in the device manifest: an optional poll
+//! command, a response regex with named captures, and a unit and scale.
+
+use crate::{HalError, HardwareDevice, Sensor, DeviceType};
+use crate::usb::UsbSerial;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// Manifest entry for a text-protocol serial sensor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialSensorConfig {
+    /// Sensor name
+    pub name: String,
+    /// Serial port path
+    pub port: String,
+    /// Baud rate
+    #[serde(default = "default_baud")]
+    pub baud: u32,
+    /// Command sent before each read (omit for free-running devices)
+    #[serde(default)]
+    pub poll_command: Option<String>,
+    /// Response regex, e.g. `T=(?P<value>-?\d+\.\d+)`
+    pub pattern: String,
+    /// Named capture holding the value
+    #[serde(default = "default_capture")]
+    pub capture: String,
+    /// Unit of the scaled value
+    pub unit: String,
+    /// Multiplier applied to the captured value
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// Offset added after scaling
+    #[serde(default)]
+    pub offset: f64,
+    /// Lines to read looking for a match before giving up
+    #[serde(default = "default_max_lines")]
+    pub max_lines: usize,
+}
+
+fn default_baud() -> u32 { 9600 }
+fn default_capture() -> String { "value".to_string() }
+fn default_scale() -> f64 { 1.0 }
+fn default_max_lines() -> usize { 4 }
+
+/// Serial sensor driven by a [`SerialSensorConfig`]
+pub struct SerialSensor {
+    config: SerialSensorConfig,
+    pattern: Regex,
+    serial: Mutex<UsbSerial>,
+    /// USB serial number of the port's device
+    device_serial: Option<String>,
+    calibration_offset: f64,
+    ready: bool,
+}
+
+impl SerialSensor {
+    /// Open the port and compile the response pattern
+    pub fn from_config(config: SerialSensorConfig) -> Result<Self, HalError> {
+        let pattern = Regex::new(&config.pattern)
+            .map_err(|e| HalError::InvalidConfig(format!("{}: bad pattern: {}", config.name, e)))?;
+        
+        if !pattern.capture_names().any(|name| name == Some(config.capture.as_str())) {
+            return Err(HalError::InvalidConfig(format!(
+                "{}: pattern has no capture named '{}'", config.name, config.capture
+            )));
+        }
+        
+        let serial = UsbSerial::open(&config.port, config.baud)?;
+        
+        Ok(Self {
+            config,
+            pattern,
+            device_serial: serial.serial(),
+            serial: Mutex::new(serial),
+            calibration_offset: 0.0,
+            ready: false,
+        })
+    }
+    
+    /// Sensor configuration
+    pub fn config(&self) -> &SerialSensorConfig {
+        &self.config
+    }
+    
+    /// Parse the value out of a response line
+    pub fn parse_line(&self, line: &str) -> Option<f64> {
+        let captures = self.pattern.captures(line)?;
+        let raw: f64 = captures.name(&self.config.capture)?.as_str().trim().parse().ok()?;
+        Some(raw * self.config.scale + self.config.offset)
+    }
+    
+    fn read_line(&self) -> Result<String, HalError> {
+        let mut serial = self.serial.lock().unwrap();
+        if let Some(ref command) = self.config.poll_command {
+            serial.writeln(command)?;
+        }
+        
+        for _ in 0..self.config.max_lines.max(1) {
+            let line = serial.read_line()?;
+            if self.pattern.is_match(&line) {
+                return Ok(line);
+            }
+        }
+        
+        Err(HalError::Timeout)
+    }
+}
+
+impl HardwareDevice for SerialSensor {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Serial
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.serial.get_mut().unwrap().init()?;
+        self.ready = true;
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.ready = false;
+        self.serial.get_mut().unwrap().close()
+    }
+    
+    fn serial(&self) -> Option<String> {
+        self.device_serial.clone()
+    }
+}
+
+impl Sensor for SerialSensor {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        Ok(self.read_line()?.into_bytes())
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        let line = self.read_line()?;
+        let value = self.parse_line(&line).ok_or_else(|| {
+            HalError::CommunicationError(format!("{}: unparseable response '{}'", self.config.name, line))
+        })?;
+        Ok(value + self.calibration_offset)
+    }
+    
+    fn unit(&self) -> &str {
+        &self.config.unit
+    }
+    
+    fn calibrate(&mut self, offset: f64) -> Result<(), HalError> {
+        self.calibration_offset = offset;
+        Ok(())
+    }
+}
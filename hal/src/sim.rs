@@ -0,0 +1,578 @@
+//! Simulated hardware
+//!
+//! Exercising the fusion pipeline otherwise takes a Raspberry Pi full of
+//! sensors. With [`HalConfig::simulation`](crate::HalConfig::simulation)
+//! set, the [`HardwareManager`](crate::HardwareManager) registers simulated
+//! devices next to (or instead of) the real ones: sensors following a
+//! waveform plus noise, cameras rendering noisy frames and SDRs producing
+//! spectra over a noise floor. Each takes a script of anomaly injections (a
+//! step, a ramp, an oscillation, an object crossing the frame, a carrier)
+//! that start after a delay and may repeat. Noise is seeded, so a script
+//! plays out the same way on every run.
+
+use crate::{DeviceType, FrameStats, HalError, HardwareDevice, Sensor, SensorReading};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::f64::consts::TAU;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Simulated devices (`[simulation]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimConfig {
+    /// Register only the simulated devices, without scanning for hardware
+    #[serde(default = "default_exclusive")]
+    pub exclusive: bool,
+    /// Seed of the noise generators
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default, rename = "sensor")]
+    pub sensors: Vec<SimSensorConfig>,
+    #[serde(default, rename = "camera")]
+    pub cameras: Vec<SimCameraConfig>,
+    #[serde(default, rename = "sdr")]
+    pub sdrs: Vec<SimSdrConfig>,
+}
+
+fn default_exclusive() -> bool { true }
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            exclusive: default_exclusive(),
+            seed: 0,
+            sensors: Vec::new(),
+            cameras: Vec::new(),
+            sdrs: Vec::new(),
+        }
+    }
+}
+
+impl SimConfig {
+    /// Noise seed of the device `name`, stable across runs
+    fn seed_for(&self, name: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        self.seed ^ hasher.finish()
+    }
+}
+
+/// Periodic shape of a simulated sensor's value around its baseline
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Waveform {
+    #[default]
+    Constant,
+    Sine { amplitude: f64, period_secs: f64 },
+    Square { amplitude: f64, period_secs: f64 },
+    Sawtooth { amplitude: f64, period_secs: f64 },
+    /// Steady drift, e.g. a room cooling overnight
+    Drift { rate_per_hour: f64 },
+}
+
+impl Waveform {
+    /// Offset from the baseline `t` seconds in
+    pub fn value(&self, t: f64) -> f64 {
+        let phase = |period: f64| if period > 0.0 { (t / period).fract() } else { 0.0 };
+        match *self {
+            Self::Constant => 0.0,
+            Self::Sine { amplitude, period_secs } => amplitude * (TAU * phase(period_secs)).sin(),
+            Self::Square { amplitude, period_secs } => if phase(period_secs) < 0.5 { amplitude } else { -amplitude },
+            Self::Sawtooth { amplitude, period_secs } => amplitude * (2.0 * phase(period_secs) - 1.0),
+            Self::Drift { rate_per_hour } => rate_per_hour * t / 3600.0,
+        }
+    }
+}
+
+/// Random variation added to a simulated value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Noise {
+    #[default]
+    None,
+    Gaussian { std_dev: f64 },
+    Uniform { amplitude: f64 },
+    /// Random walk taking Gaussian steps, pulled slowly back to zero
+    Brownian { step: f64 },
+}
+
+/// When a scripted injection is active
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Delay from start-up before the first occurrence (s)
+    #[serde(default)]
+    pub after_secs: f64,
+    #[serde(default = "default_injection_duration")]
+    pub duration_secs: f64,
+    /// Start again this long after each start (s); once if unset
+    #[serde(default)]
+    pub repeat_secs: Option<f64>,
+}
+
+fn default_injection_duration() -> f64 { 10.0 }
+
+impl Schedule {
+    /// How far into an occurrence `t` seconds after start-up is (0-1), if one is active
+    pub fn progress(&self, t: f64) -> Option<f64> {
+        let mut since = t - self.after_secs;
+        if since < 0.0 {
+            return None;
+        }
+        if let Some(repeat) = self.repeat_secs.filter(|r| *r > 0.0) {
+            since %= repeat;
+        }
+        let duration = self.duration_secs.max(f64::EPSILON);
+        (since < duration).then_some(since / duration)
+    }
+}
+
+/// Scripted anomaly: an effect played on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Injection<E> {
+    #[serde(flatten)]
+    pub effect: E,
+    #[serde(flatten)]
+    pub schedule: Schedule,
+}
+
+impl<E> Injection<E> {
+    /// The effect and its progress, if active `t` seconds after start-up
+    fn active(&self, t: f64) -> Option<(&E, f64)> {
+        self.schedule.progress(t).map(|progress| (&self.effect, progress))
+    }
+}
+
+/// Anomaly injected into a simulated sensor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SensorEffect {
+    /// Jump by `offset` for the duration
+    Step { offset: f64 },
+    /// Rise linearly to `offset`, then snap back
+    Ramp { offset: f64 },
+    Oscillation { amplitude: f64, period_secs: f64 },
+}
+
+impl SensorEffect {
+    fn value(&self, progress: f64, t: f64) -> f64 {
+        match *self {
+            Self::Step { offset } => offset,
+            Self::Ramp { offset } => offset * progress,
+            Self::Oscillation { amplitude, period_secs } => Waveform::Sine { amplitude, period_secs }.value(t),
+        }
+    }
+}
+
+/// Anomaly injected into a simulated camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CameraEffect {
+    /// Bright square crossing the frame left to right over the duration
+    Object {
+        #[serde(default = "default_object_size")]
+        size: u32,
+        #[serde(default = "default_object_brightness")]
+        brightness: u8,
+    },
+    /// Whole frame brightened (or darkened, if negative) by `offset`
+    Flash { offset: f64 },
+}
+
+fn default_object_size() -> u32 { 12 }
+fn default_object_brightness() -> u8 { 220 }
+
+/// Anomaly injected into a simulated SDR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SdrEffect {
+    /// Carrier at `frequency_mhz`, `power_db` above the noise floor
+    Carrier { frequency_mhz: f64, power_db: f64 },
+}
+
+/// Small seeded generator (xorshift64*), good enough for noise
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+    
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+    
+    /// Uniform in [0, 1)
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    
+    /// Standard normal (Box-Muller)
+    fn gaussian(&mut self) -> f64 {
+        let u = self.uniform().max(f64::MIN_POSITIVE);
+        (-2.0 * u.ln()).sqrt() * (TAU * self.uniform()).cos()
+    }
+}
+
+/// Simulated scalar sensor (`[[simulation.sensor]]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimSensorConfig {
+    pub name: String,
+    #[serde(default)]
+    pub unit: String,
+    /// Value the waveform and noise vary around
+    #[serde(default)]
+    pub baseline: f64,
+    #[serde(default)]
+    pub waveform: Waveform,
+    #[serde(default)]
+    pub noise: Noise,
+    #[serde(default, rename = "injection")]
+    pub injections: Vec<Injection<SensorEffect>>,
+}
+
+struct NoiseState {
+    rng: Rng,
+    walk: f64,
+}
+
+/// Sensor reading a configured waveform, noise and injections
+pub struct SimulatedSensor {
+    config: SimSensorConfig,
+    started: Instant,
+    state: Mutex<NoiseState>,
+    calibration_offset: f64,
+    ready: bool,
+}
+
+impl SimulatedSensor {
+    pub fn new(config: SimSensorConfig, seed: u64) -> Self {
+        Self {
+            config,
+            started: Instant::now(),
+            state: Mutex::new(NoiseState { rng: Rng::new(seed), walk: 0.0 }),
+            calibration_offset: 0.0,
+            ready: false,
+        }
+    }
+    
+    /// Value `t` seconds after start-up, before calibration
+    fn sample(&self, t: f64) -> f64 {
+        let mut state = self.state.lock().unwrap();
+        let noise = match self.config.noise {
+            Noise::None => 0.0,
+            Noise::Gaussian { std_dev } => std_dev * state.rng.gaussian(),
+            Noise::Uniform { amplitude } => amplitude * (2.0 * state.rng.uniform() - 1.0),
+            Noise::Brownian { step } => {
+                state.walk = 0.99 * state.walk + step * state.rng.gaussian();
+                state.walk
+            }
+        };
+        let injected: f64 = self.config.injections.iter()
+            .filter_map(|injection| injection.active(t))
+            .map(|(effect, progress)| effect.value(progress, t))
+            .sum();
+        self.config.baseline + self.config.waveform.value(t) + noise + injected
+    }
+}
+
+impl HardwareDevice for SimulatedSensor {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Simulated
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.ready = true;
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.ready = false;
+        Ok(())
+    }
+}
+
+impl Sensor for SimulatedSensor {
+    fn read_raw(&self) -> Result<Vec<u8>, HalError> {
+        Ok(self.read_value()?.to_be_bytes().to_vec())
+    }
+    
+    fn read_value(&self) -> Result<f64, HalError> {
+        if !self.ready {
+            return Err(HalError::DeviceNotFound(self.config.name.clone()));
+        }
+        Ok(self.sample(self.started.elapsed().as_secs_f64()) + self.calibration_offset)
+    }
+    
+    fn unit(&self) -> &str {
+        &self.config.unit
+    }
+    
+    fn calibrate(&mut self, offset: f64) -> Result<(), HalError> {
+        self.calibration_offset = offset;
+        Ok(())
+    }
+}
+
+/// Simulated camera (`[[simulation.camera]]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimCameraConfig {
+    pub name: String,
+    #[serde(default = "default_sim_width")]
+    pub width: u32,
+    #[serde(default = "default_sim_height")]
+    pub height: u32,
+    #[serde(default = "default_sim_fps")]
+    pub fps: u32,
+    /// Mean brightness of the empty scene (0-255)
+    #[serde(default = "default_sim_brightness")]
+    pub brightness: f64,
+    /// Standard deviation of the pixel noise
+    #[serde(default = "default_sim_pixel_noise")]
+    pub noise: f64,
+    #[serde(default, rename = "injection")]
+    pub injections: Vec<Injection<CameraEffect>>,
+}
+
+fn default_sim_width() -> u32 { 160 }
+fn default_sim_height() -> u32 { 120 }
+fn default_sim_fps() -> u32 { 10 }
+fn default_sim_brightness() -> f64 { 60.0 }
+fn default_sim_pixel_noise() -> f64 { 4.0 }
+
+/// Camera rendering noisy grayscale frames with scripted objects and flashes
+///
+/// Frames are published like a real camera's, through [`FrameStats`], by
+/// the thread [`spawn_reader`](Self::spawn_reader) starts.
+pub struct SimulatedCamera {
+    config: SimCameraConfig,
+    seed: u64,
+    running: Arc<AtomicBool>,
+}
+
+impl SimulatedCamera {
+    pub fn new(config: SimCameraConfig, seed: u64) -> Self {
+        Self {
+            config,
+            seed,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    
+    /// Render frames at the configured rate while the camera is open
+    pub fn spawn_reader(&self, tx: mpsc::Sender<SensorReading>, stats: FrameStats) {
+        let config = self.config.clone();
+        let running = self.running.clone();
+        let mut rng = Rng::new(self.seed);
+        let period = Duration::from_secs_f64(1.0 / config.fps.max(1) as f64);
+        
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            let mut next = started;
+            while running.load(Ordering::Relaxed) {
+                let luma = render(&config, started.elapsed().as_secs_f64(), &mut rng);
+                if !stats.publish(&tx, SensorReading::frame(&config.name, config.width, config.height, luma)) {
+                    break;
+                }
+                next += period;
+                std::thread::sleep(next.saturating_duration_since(Instant::now()));
+            }
+            tracing::debug!("Simulated camera {} stopped", config.name);
+        });
+    }
+}
+
+/// Frame of `config`'s scene `t` seconds after start-up
+fn render(config: &SimCameraConfig, t: f64, rng: &mut Rng) -> Vec<u8> {
+    let (width, height) = (config.width as usize, config.height as usize);
+    let mut level = config.brightness;
+    let mut objects = Vec::new();
+    for (effect, progress) in config.injections.iter().filter_map(|injection| injection.active(t)) {
+        match *effect {
+            CameraEffect::Flash { offset } => level += offset,
+            CameraEffect::Object { size, brightness } => {
+                let size = size as usize;
+                let x = (progress * (width + size) as f64) as usize;
+                objects.push((x.saturating_sub(size), x.min(width), size, brightness));
+            }
+        }
+    }
+    
+    let mut luma: Vec<u8> = (0..width * height)
+        .map(|_| (level + config.noise * rng.gaussian()).clamp(0.0, 255.0) as u8)
+        .collect();
+    for (left, right, size, brightness) in objects {
+        // Vertically centred
+        let (top, bottom) = (height.saturating_sub(size) / 2, (height + size) / 2);
+        for row in luma.chunks_mut(width).take(bottom.min(height)).skip(top) {
+            row[left..right].fill(brightness);
+        }
+    }
+    luma
+}
+
+impl HardwareDevice for SimulatedCamera {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Simulated
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.running.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.running.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Simulated SDR (`[[simulation.sdr]]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimSdrConfig {
+    pub name: String,
+    #[serde(default = "default_sim_center")]
+    pub center_mhz: f64,
+    /// Span of the spectrum (Hz)
+    #[serde(default = "default_sim_sample_rate")]
+    pub sample_rate: u32,
+    #[serde(default = "default_sim_fft_size")]
+    pub fft_size: usize,
+    /// Time between spectra (ms)
+    #[serde(default = "default_sim_interval")]
+    pub interval_ms: u64,
+    #[serde(default = "default_sim_floor")]
+    pub noise_floor_db: f64,
+    /// Stations always on the air, as carriers
+    #[serde(default)]
+    pub carriers: Vec<SdrEffect>,
+    #[serde(default, rename = "injection")]
+    pub injections: Vec<Injection<SdrEffect>>,
+}
+
+fn default_sim_center() -> f64 { 100.0 }
+fn default_sim_sample_rate() -> u32 { 2_048_000 }
+fn default_sim_fft_size() -> usize { 1024 }
+fn default_sim_interval() -> u64 { 1000 }
+fn default_sim_floor() -> f64 { -90.0 }
+
+/// SDR producing power spectra of carriers over a noise floor
+///
+/// Spectra go straight into the reading channel, like those of a tuned
+/// [`RtlSdr`](crate::sdr::RtlSdr), from the thread
+/// [`spawn_reader`](Self::spawn_reader) starts.
+pub struct SimulatedSdr {
+    config: SimSdrConfig,
+    seed: u64,
+    running: Arc<AtomicBool>,
+}
+
+impl SimulatedSdr {
+    pub fn new(config: SimSdrConfig, seed: u64) -> Self {
+        Self {
+            config,
+            seed,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    
+    /// Produce a spectrum every `interval_ms` while the SDR is open
+    pub fn spawn_reader(&self, tx: mpsc::Sender<SensorReading>) {
+        let config = self.config.clone();
+        let running = self.running.clone();
+        let mut rng = Rng::new(self.seed);
+        let interval = Duration::from_millis(config.interval_ms.max(1));
+        
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            while running.load(Ordering::Relaxed) {
+                let reading = spectrum(&config, started.elapsed().as_secs_f64(), &mut rng);
+                if tx.blocking_send(reading).is_err() {
+                    break;
+                }
+                std::thread::sleep(interval);
+            }
+            tracing::debug!("Simulated SDR {} stopped", config.name);
+        });
+    }
+}
+
+/// Spectrum reading of `config`'s band `t` seconds after start-up
+fn spectrum(config: &SimSdrConfig, t: f64, rng: &mut Rng) -> SensorReading {
+    let bins = config.fft_size.max(2);
+    let bin_hz = config.sample_rate.max(1) as f64 / bins as f64;
+    let start_hz = config.center_mhz * 1e6 - config.sample_rate as f64 / 2.0;
+    
+    // Floor with a couple of dB of jitter
+    let mut power: Vec<f64> = (0..bins).map(|_| config.noise_floor_db + 2.0 * rng.gaussian()).collect();
+    let injected = config.injections.iter().filter_map(|injection| injection.active(t)).map(|(effect, _)| effect);
+    for SdrEffect::Carrier { frequency_mhz, power_db } in config.carriers.iter().chain(injected) {
+        let bin = (frequency_mhz * 1e6 - start_hz) / bin_hz;
+        if bin < 0.0 || bin >= bins as f64 {
+            continue;
+        }
+        // A few bins of leakage either side of the peak
+        let peak = bin.round() as isize;
+        for offset in -3isize..=3 {
+            if let Some(p) = power.get_mut((peak + offset).max(0) as usize) {
+                let level = config.noise_floor_db + power_db - 6.0 * offset.unsigned_abs() as f64;
+                *p = 10.0 * (10f64.powf(*p / 10.0) + 10f64.powf(level / 10.0)).log10();
+            }
+        }
+    }
+    SensorReading::spectrum(&config.name, start_hz, bin_hz, power)
+}
+
+impl HardwareDevice for SimulatedSdr {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Simulated
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.running.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        self.running.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Open the simulated devices of `config`, seeded per device
+pub(crate) fn open_all(config: &SimConfig) -> (Vec<SimulatedSensor>, Vec<SimulatedCamera>, Vec<SimulatedSdr>) {
+    let sensors = config.sensors.iter().map(|c| SimulatedSensor::new(c.clone(), config.seed_for(&c.name))).collect();
+    let cameras = config.cameras.iter().map(|c| SimulatedCamera::new(c.clone(), config.seed_for(&c.name))).collect();
+    let sdrs = config.sdrs.iter().map(|c| SimulatedSdr::new(c.clone(), config.seed_for(&c.name))).collect();
+    (sensors, cameras, sdrs)
+}
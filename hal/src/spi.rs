@@ -1,8 +1,9 @@
 //! SPI interface for GlowBarn HAL
 
-use crate::{HalError, HardwareDevice, DeviceType};
-use std::fs::OpenOptions;
-use std::os::unix::io::AsRawFd;
+use crate::{HalError, HardwareDevice, DeviceType, SensorReading};
+use crate::buslog::{BusKind, BusOp, Trace};
+use crate::platform::DeviceHandle;
+use std::time::{Duration, Instant};
 
 /// SPI mode configuration
 #[derive(Debug, Clone, Copy)]
@@ -35,23 +36,15 @@ impl Default for SpiConfig {
 
 /// SPI Device wrapper
 pub struct SpiDevice {
-    path: String,
-    fd: Option<i32>,
+    handle: DeviceHandle,
     config: SpiConfig,
 }
 
 impl SpiDevice {
     /// Open SPI device
     pub fn open(path: &str, config: SpiConfig) -> Result<Self, HalError> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(path)?;
-        
-        let fd = file.as_raw_fd();
         let mut device = Self {
-            path: path.to_string(),
-            fd: Some(fd),
+            handle: DeviceHandle::open(path)?,
             config,
         };
         
@@ -63,7 +56,7 @@ impl SpiDevice {
     fn configure(&mut self) -> Result<(), HalError> {
         #[cfg(target_os = "linux")]
         unsafe {
-            if let Some(fd) = self.fd {
+            if let Some(fd) = self.handle.raw_fd() {
                 // Set mode (SPI_IOC_WR_MODE = 0x40016B01)
                 let mode = match self.config.mode {
                     SpiMode::Mode0 => 0,
@@ -85,13 +78,26 @@ impl SpiDevice {
     
     /// Transfer data (full-duplex)
     pub fn transfer(&self, tx: &[u8], rx: &mut [u8]) -> Result<(), HalError> {
+        let started = Instant::now();
+        let result = self.transfer_inner(tx, rx);
+        Trace {
+            kind: BusKind::SPI,
+            bus: self.path(),
+            op: BusOp::Transfer,
+            address: None,
+            register: None,
+        }.finish_transfer(started, tx, rx, &result);
+        result
+    }
+    
+    fn transfer_inner(&self, tx: &[u8], rx: &mut [u8]) -> Result<(), HalError> {
         if tx.len() != rx.len() {
             return Err(HalError::InvalidConfig("TX/RX buffer size mismatch".to_string()));
         }
         
         #[cfg(target_os = "linux")]
         unsafe {
-            if let Some(fd) = self.fd {
+            if let Some(fd) = self.handle.raw_fd() {
                 // spi_ioc_transfer structure
                 #[repr(C)]
                 struct SpiIocTransfer {
@@ -157,6 +163,16 @@ impl SpiDevice {
         
         Ok(full_rx[tx.len()..].to_vec())
     }
+    
+    /// Device path
+    pub fn path(&self) -> &str {
+        self.handle.path()
+    }
+    
+    /// Active bus configuration
+    pub fn config(&self) -> &SpiConfig {
+        &self.config
+    }
 }
 
 /// ADS1256 24-bit ADC for high-precision sensor readings
@@ -167,6 +183,12 @@ pub struct ADS1256 {
 }
 
 impl ADS1256 {
+    /// Data rate of the high-rate mode
+    pub const HIGH_RATE_SPS: f64 = 1000.0;
+    
+    /// Data rate of block captures (the ADC's maximum)
+    pub const MAX_SPS: f64 = 30_000.0;
+    
     pub fn new(spi_path: &str) -> Result<Self, HalError> {
         let config = SpiConfig {
             mode: SpiMode::Mode1,
@@ -198,13 +220,17 @@ impl ADS1256 {
         self.spi.write(&[0x01])?;  // RDATA
         let data = self.spi.read(3)?;
         
+        Ok(Self::decode(&data))
+    }
+    
+    /// 24-bit conversion result, sign extended
+    fn decode(data: &[u8]) -> i32 {
         let raw = ((data[0] as i32) << 16) | ((data[1] as i32) << 8) | (data[2] as i32);
         
-        // Sign extend 24-bit to 32-bit
         if raw & 0x800000 != 0 {
-            Ok(raw | 0xFF000000u32 as i32)
+            raw | 0xFF000000u32 as i32
         } else {
-            Ok(raw)
+            raw
         }
     }
     
@@ -213,6 +239,61 @@ impl ADS1256 {
         (raw as f64 / 8388607.0) * 5.0
     }
     
+    /// Switch between 50 SPS (precision) and 1000 SPS (burst capture)
+    pub fn set_high_rate(&self, enabled: bool) -> Result<(), HalError> {
+        self.set_data_rate(if enabled { 0xA1 } else { 0x63 })
+    }
+    
+    fn set_data_rate(&self, drate: u8) -> Result<(), HalError> {
+        self.spi.write(&[0x50 | 0x03, 0x00, drate])?;  // WREG DRATE
+        self.spi.write(&[0xF0])?;  // SELFCAL at the new rate
+        Ok(())
+    }
+    
+    /// Fill a buffer of `count` samples of `channel` at 30 kSPS, in volts
+    ///
+    /// Reads in continuous mode so no command bytes are spent between
+    /// conversions. The ADC is returned to 50 SPS afterwards, also when a
+    /// read fails.
+    pub fn read_burst(&self, channel: u8, count: usize) -> Result<Vec<f64>, HalError> {
+        let mux = (channel << 4) | 0x08;  // Single-ended, AINCOM
+        self.spi.write(&[0x50 | 0x01, 0x00, mux])?;  // WREG MUX
+        self.set_data_rate(0xF0)?;
+        self.spi.write(&[0x03])?;  // RDATAC
+        
+        let period = Duration::from_secs_f64(1.0 / Self::MAX_SPS);
+        let mut samples = Vec::with_capacity(count);
+        let mut next = Instant::now();
+        let result = (0..count).try_for_each(|_| {
+            // Conversions are too close together to sleep between
+            next += period;
+            while Instant::now() < next {
+                std::hint::spin_loop();
+            }
+            let data = self.spi.read(3)?;
+            samples.push(Self::raw_to_voltage(Self::decode(&data)));
+            Ok::<(), HalError>(())
+        });
+        
+        self.spi.write(&[0x0F])?;  // SDATAC
+        self.set_data_rate(0x63)?;
+        result.map(|_| samples)
+    }
+    
+    /// Capture `count` samples of `channel` at 1000 SPS as a waveform reading in volts
+    ///
+    /// Fast enough to resolve mains hum (50/60 Hz and low harmonics) on an
+    /// EMF probe. The ADC is returned to 50 SPS afterwards.
+    pub fn read_waveform(&self, sensor_name: &str, channel: u8, count: usize) -> Result<SensorReading, HalError> {
+        self.set_high_rate(true)?;
+        let samples: Result<Vec<f64>, HalError> = (0..count)
+            .map(|_| self.read_channel(channel).map(Self::raw_to_voltage))
+            .collect();
+        self.set_high_rate(false)?;
+        
+        Ok(SensorReading::waveform(sensor_name, "V", Self::HIGH_RATE_SPS, samples?))
+    }
+    
     /// Read all channels
     pub fn read_all_channels(&self) -> Result<Vec<f64>, HalError> {
         let mut results = Vec::new();
@@ -239,7 +320,7 @@ impl HardwareDevice for ADS1256 {
         std::thread::sleep(std::time::Duration::from_millis(10));
         
         // Configure for high precision
-        self.spi.write(&[0x50 | 0x00, 0x00, 0x01])?;  // STATUS: Auto-calibrate
+        self.spi.write(&[0x50, 0x00, 0x01])?;  // STATUS: Auto-calibrate
         self.spi.write(&[0x50 | 0x02, 0x00, 0x00])?;  // ADCON: Clock off, PGA=1
         self.spi.write(&[0x50 | 0x03, 0x00, 0x63])?;  // DRATE: 50 SPS
         
@@ -302,8 +383,8 @@ impl MCP3008 {
     /// Read all channels
     pub fn read_all(&self) -> Result<[u16; 8], HalError> {
         let mut values = [0u16; 8];
-        for i in 0..8 {
-            values[i] = self.read_channel(i as u8)?;
+        for (channel, value) in values.iter_mut().enumerate() {
+            *value = self.read_channel(channel as u8)?;
         }
         Ok(values)
     }
@@ -0,0 +1,257 @@
+//! Stepper motor control for GlowBarn HAL
+//! Step/dir drivers (A4988, DRV8825) and a scanning platform service
+
+use crate::{HalError, HardwareDevice, Sensor, SensorReading, DeviceType};
+use crate::gpio::{GpioPin, Direction};
+use std::time::Duration;
+
+/// Stepper driver configuration
+#[derive(Debug, Clone)]
+pub struct StepperConfig {
+    /// Full steps per revolution (200 for a 1.8° motor)
+    pub steps_per_rev: u32,
+    /// Microstepping factor set by the driver's MS pins
+    pub microsteps: u32,
+    /// Maximum speed in microsteps per second
+    pub max_speed: f64,
+    /// Acceleration in microsteps per second squared
+    pub acceleration: f64,
+    /// Invert the direction pin
+    pub invert_direction: bool,
+}
+
+impl Default for StepperConfig {
+    fn default() -> Self {
+        Self {
+            steps_per_rev: 200,
+            microsteps: 16,
+            max_speed: 1600.0,
+            acceleration: 3200.0,
+            invert_direction: false,
+        }
+    }
+}
+
+impl StepperConfig {
+    /// Microsteps per full revolution
+    pub fn steps_per_revolution(&self) -> u32 {
+        self.steps_per_rev * self.microsteps
+    }
+}
+
+/// Step/dir stepper motor driver (A4988, DRV8825)
+pub struct StepperMotor {
+    name: String,
+    step: GpioPin,
+    dir: GpioPin,
+    enable: Option<GpioPin>,
+    config: StepperConfig,
+    position: i64,
+    ready: bool,
+}
+
+impl StepperMotor {
+    /// Create stepper on the given step and direction pins
+    pub fn new(name: &str, step_pin: u32, dir_pin: u32, config: StepperConfig) -> Result<Self, HalError> {
+        let step = GpioPin::new(&format!("{}_step", name), step_pin, Direction::Output)?;
+        let dir = GpioPin::new(&format!("{}_dir", name), dir_pin, Direction::Output)?;
+        
+        Ok(Self {
+            name: name.to_string(),
+            step,
+            dir,
+            enable: None,
+            config,
+            position: 0,
+            ready: false,
+        })
+    }
+    
+    /// Use an active-low enable pin (driver EN)
+    pub fn with_enable_pin(mut self, pin: u32) -> Result<Self, HalError> {
+        let enable = GpioPin::new(&format!("{}_en", self.name), pin, Direction::Output)?;
+        enable.write(true)?;  // Disabled until init
+        self.enable = Some(enable);
+        Ok(self)
+    }
+    
+    /// Current position in microsteps
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+    
+    /// Current angle in degrees (0 - 360)
+    pub fn angle(&self) -> f64 {
+        let per_rev = self.config.steps_per_revolution() as i64;
+        self.position.rem_euclid(per_rev) as f64 * 360.0 / per_rev as f64
+    }
+    
+    /// Declare the current position as 0°
+    pub fn set_home(&mut self) {
+        self.position = 0;
+    }
+    
+    /// Move by a relative number of microsteps with acceleration ramps
+    pub fn move_steps(&mut self, steps: i64) -> Result<(), HalError> {
+        if steps == 0 {
+            return Ok(());
+        }
+        if !self.ready {
+            return Err(HalError::DeviceNotFound(format!("{} not initialized", self.name)));
+        }
+        
+        let forward = steps > 0;
+        self.dir.write(forward != self.config.invert_direction)?;
+        std::thread::sleep(Duration::from_micros(5));  // DIR setup time
+        
+        for delay in ramp_delays(steps.unsigned_abs(), self.config.max_speed, self.config.acceleration) {
+            self.step.write(true)?;
+            std::thread::sleep(Duration::from_micros(2));
+            self.step.write(false)?;
+            std::thread::sleep(delay);
+            self.position += if forward { 1 } else { -1 };
+        }
+        
+        Ok(())
+    }
+    
+    /// Move to an absolute angle using the shortest path
+    pub fn move_to_angle(&mut self, degrees: f64) -> Result<(), HalError> {
+        let per_rev = self.config.steps_per_revolution() as i64;
+        let target = (degrees.rem_euclid(360.0) / 360.0 * per_rev as f64).round() as i64;
+        let current = self.position.rem_euclid(per_rev);
+        
+        let mut delta = target - current;
+        if delta > per_rev / 2 {
+            delta -= per_rev;
+        } else if delta < -per_rev / 2 {
+            delta += per_rev;
+        }
+        
+        self.move_steps(delta)
+    }
+}
+
+/// Per-step delays for a trapezoidal speed profile
+pub fn ramp_delays(steps: u64, max_speed: f64, acceleration: f64) -> Vec<Duration> {
+    (0..steps)
+        .map(|i| {
+            // Distance to the nearer end of the move bounds the reachable speed
+            let edge = (i + 1).min(steps - i) as f64;
+            let speed = (2.0 * acceleration * edge).sqrt().min(max_speed).max(1.0);
+            Duration::from_secs_f64(1.0 / speed)
+        })
+        .collect()
+}
+
+impl HardwareDevice for StepperMotor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn device_type(&self) -> DeviceType {
+        DeviceType::GPIO
+    }
+    
+    fn init(&mut self) -> Result<(), HalError> {
+        self.step.write(false)?;
+        if let Some(ref enable) = self.enable {
+            enable.write(false)?;
+        }
+        self.ready = true;
+        Ok(())
+    }
+    
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+    
+    fn close(&mut self) -> Result<(), HalError> {
+        if let Some(ref enable) = self.enable {
+            enable.write(true)?;
+        }
+        self.ready = false;
+        Ok(())
+    }
+}
+
+/// Sensor reading tagged with the platform angle
+#[derive(Debug, Clone)]
+pub struct AngleReading {
+    pub angle: f64,
+    pub reading: SensorReading,
+}
+
+/// Rotating scan platform for a thermal camera or directional antenna
+pub struct Scanner {
+    motor: StepperMotor,
+    positions: Vec<f64>,
+    settle_time: Duration,
+}
+
+impl Scanner {
+    /// Create scanner visiting `positions` (degrees) in order
+    pub fn new(motor: StepperMotor, positions: Vec<f64>) -> Self {
+        Self {
+            motor,
+            positions,
+            settle_time: Duration::from_millis(250),
+        }
+    }
+    
+    /// Evenly spaced positions covering a full turn
+    pub fn full_turn(motor: StepperMotor, count: usize) -> Self {
+        let positions = (0..count)
+            .map(|i| i as f64 * 360.0 / count.max(1) as f64)
+            .collect();
+        Self::new(motor, positions)
+    }
+    
+    /// Time to wait after each move before measuring (vibration damping)
+    pub fn with_settle_time(mut self, settle_time: Duration) -> Self {
+        self.settle_time = settle_time;
+        self
+    }
+    
+    /// Configured positions
+    pub fn positions(&self) -> &[f64] {
+        &self.positions
+    }
+    
+    /// Current platform angle
+    pub fn current_angle(&self) -> f64 {
+        self.motor.angle()
+    }
+    
+    /// Access the motor (homing, manual moves)
+    pub fn motor_mut(&mut self) -> &mut StepperMotor {
+        &mut self.motor
+    }
+    
+    /// Visit each position and run `measure` there
+    pub fn scan<T, F>(&mut self, mut measure: F) -> Result<Vec<(f64, T)>, HalError>
+    where
+        F: FnMut(f64) -> Result<T, HalError>,
+    {
+        let mut results = Vec::with_capacity(self.positions.len());
+        
+        for &position in &self.positions {
+            self.motor.move_to_angle(position)?;
+            std::thread::sleep(self.settle_time);
+            
+            let angle = self.motor.angle();
+            results.push((angle, measure(angle)?));
+        }
+        
+        Ok(results)
+    }
+    
+    /// Visit each position and read a sensor, tagging readings with the angle
+    pub fn scan_sensor(&mut self, sensor: &dyn Sensor) -> Result<Vec<AngleReading>, HalError> {
+        let results = self.scan(|_| SensorReading::sample(sensor.name(), sensor))?;
+        
+        Ok(results.into_iter()
+            .map(|(angle, reading)| AngleReading { angle, reading })
+            .collect())
+    }
+}
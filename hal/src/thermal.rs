@@ -0,0 +1,146 @@
+//! Radiometric drift correction for thermal cameras
+//!
+//! Uncooled microbolometers read warmer or cooler as the camera body warms
+//! up, which looks exactly like the whole room changing temperature. A
+//! reference target in view whose true temperature is known — a blackbody,
+//! or any matt patch with a contact sensor on it — measures that drift: the
+//! difference between what the camera sees in the reference region and the
+//! known temperature is smoothed over frames and subtracted from every pixel,
+//! so cold-spot deviations reflect the scene rather than sensor warm-up.
+
+use crate::SensorReading;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Known-temperature reference in a thermal camera's view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalReference {
+    /// Top-left pixel of the reference region
+    pub x: u32,
+    pub y: u32,
+    #[serde(default = "default_size")]
+    pub width: u32,
+    #[serde(default = "default_size")]
+    pub height: u32,
+    /// Sensor measuring the reference (°C); takes precedence over `temperature`
+    #[serde(default)]
+    pub sensor: Option<String>,
+    /// Fixed reference temperature (°C), e.g. a regulated blackbody setpoint
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Weight of each frame in the smoothed offset (0-1)
+    #[serde(default = "default_smoothing")]
+    pub smoothing: f64,
+}
+
+fn default_size() -> u32 { 4 }
+fn default_smoothing() -> f64 { 0.1 }
+
+impl ThermalReference {
+    /// Mean temperature of the reference region of a frame `frame_width` wide
+    pub fn region_mean(&self, frame_width: u32, temperatures: &[f64]) -> Option<f64> {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for y in self.y..self.y + self.height.max(1) {
+            for x in self.x..(self.x + self.width.max(1)).min(frame_width) {
+                if let Some(&t) = temperatures.get((y * frame_width + x) as usize) {
+                    sum += t;
+                    count += 1;
+                }
+            }
+        }
+        (count > 0).then(|| sum / count as f64)
+    }
+}
+
+/// Latest values of reference sensors
+///
+/// Cheap to clone; the poll loop updates it and thermal cameras read it.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceTemperatures {
+    by_sensor: Arc<RwLock<HashMap<String, f64>>>,
+    watched: Arc<RwLock<Vec<String>>>,
+}
+
+impl ReferenceTemperatures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Start keeping the latest value of `sensor`
+    pub fn watch(&self, sensor: &str) {
+        let mut watched = self.watched.write().unwrap();
+        if !watched.iter().any(|s| s == sensor) {
+            watched.push(sensor.to_string());
+        }
+    }
+    
+    /// Record a reading if its sensor is watched
+    pub fn update(&self, reading: &SensorReading) {
+        if self.watched.read().unwrap().contains(&reading.sensor_name) {
+            self.by_sensor.write().unwrap().insert(reading.sensor_name.clone(), reading.value);
+        }
+    }
+    
+    pub fn get(&self, sensor: &str) -> Option<f64> {
+        self.by_sensor.read().unwrap().get(sensor).copied()
+    }
+}
+
+/// Drift offset of one camera, tracked from its reference
+#[derive(Debug, Clone)]
+pub struct DriftCorrection {
+    reference: ThermalReference,
+    readings: ReferenceTemperatures,
+    offset: Option<f64>,
+}
+
+impl DriftCorrection {
+    pub fn new(reference: ThermalReference, readings: ReferenceTemperatures) -> Self {
+        if let Some(sensor) = &reference.sensor {
+            readings.watch(sensor);
+        }
+        
+        Self {
+            reference,
+            readings,
+            offset: None,
+        }
+    }
+    
+    /// True temperature of the reference: its sensor's latest reading, else
+    /// the fixed temperature
+    pub fn known_temperature(&self) -> Option<f64> {
+        self.reference.sensor.as_deref()
+            .and_then(|sensor| self.readings.get(sensor))
+            .or(self.reference.temperature)
+    }
+    
+    /// Update the offset from a frame and subtract it from every pixel
+    ///
+    /// Until the reference temperature is known the frame is left as is.
+    /// Returns the offset applied (°C the camera reads high).
+    pub fn correct(&mut self, frame_width: u32, temperatures: &mut [f64]) -> f64 {
+        let measured = self.reference.region_mean(frame_width, temperatures);
+        if let (Some(known), Some(measured)) = (self.known_temperature(), measured) {
+            let error = measured - known;
+            let alpha = self.reference.smoothing.clamp(0.0, 1.0);
+            self.offset = Some(match self.offset {
+                Some(offset) => offset + alpha * (error - offset),
+                None => error,
+            });
+        }
+        
+        let offset = self.offset.unwrap_or(0.0);
+        if offset != 0.0 {
+            temperatures.iter_mut().for_each(|t| *t -= offset);
+        }
+        offset
+    }
+    
+    /// Current offset (°C the camera reads high), once measured
+    pub fn offset(&self) -> Option<f64> {
+        self.offset
+    }
+}
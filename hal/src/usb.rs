@@ -1,9 +1,12 @@
 //! USB device interface for GlowBarn HAL
 
 use crate::{HalError, HardwareDevice, DeviceType};
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Write};
-use std::path::PathBuf;
+use crate::buslog::{BusKind, BusOp, Trace};
+use crate::platform::{self, DeviceHandle};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 /// USB device information
 #[derive(Debug, Clone)]
@@ -20,7 +23,7 @@ pub struct UsbDeviceInfo {
 
 impl UsbDeviceInfo {
     /// Parse device info from sysfs
-    fn from_sysfs(path: &PathBuf) -> Result<Self, HalError> {
+    fn from_sysfs(path: &Path) -> Result<Self, HalError> {
         let read_attr = |attr: &str| -> String {
             let p = path.join(attr);
             if let Ok(mut f) = File::open(&p) {
@@ -44,7 +47,7 @@ impl UsbDeviceInfo {
             serial: read_attr("serial"),
             bus: bus_str.parse().unwrap_or(0),
             device: dev_str.parse().unwrap_or(0),
-            path: path.clone(),
+            path: path.to_path_buf(),
         })
     }
 }
@@ -82,11 +85,26 @@ pub fn find_device(vendor_id: u16, product_id: u16) -> Result<Option<UsbDeviceIn
     Ok(devices.into_iter().find(|d| d.vendor_id == vendor_id && d.product_id == product_id))
 }
 
+/// Serial number of the USB device behind a tty (`/dev/ttyACM0` or a
+/// `/dev/serial/by-id/...` link), from sysfs
+pub fn tty_serial(port: &str) -> Option<String> {
+    let tty = std::fs::canonicalize(port).ok()?;
+    let device = std::fs::canonicalize(PathBuf::from("/sys/class/tty").join(tty.file_name()?).join("device")).ok()?;
+    
+    // The tty's device is a USB interface; the serial is on its parent device
+    device.ancestors()
+        .find(|path| path.join("idVendor").exists())
+        .and_then(|path| std::fs::read_to_string(path.join("serial")).ok())
+        .map(|serial| serial.trim().to_string())
+        .filter(|serial| !serial.is_empty())
+}
+
 /// USB Serial device (CDC ACM, FTDI, etc.)
 pub struct UsbSerial {
     name: String,
     port: String,
-    file: Option<File>,
+    serial: Option<String>,
+    handle: DeviceHandle,
     baud: u32,
     ready: bool,
 }
@@ -94,54 +112,52 @@ pub struct UsbSerial {
 impl UsbSerial {
     /// Open USB serial port
     pub fn open(port: &str, baud: u32) -> Result<Self, HalError> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(port)?;
+        let handle = DeviceHandle::open(port)?;
         
         // Configure serial port
         #[cfg(target_os = "linux")]
-        unsafe {
-            let fd = std::os::unix::io::AsRawFd::as_raw_fd(&file);
-            
-            // Get current settings
-            let mut termios: libc::termios = std::mem::zeroed();
-            libc::tcgetattr(fd, &mut termios);
-            
-            // Raw mode
-            libc::cfmakeraw(&mut termios);
-            
-            // Set baud rate
-            let baud_const = match baud {
-                9600 => libc::B9600,
-                19200 => libc::B19200,
-                38400 => libc::B38400,
-                57600 => libc::B57600,
-                115200 => libc::B115200,
-                230400 => libc::B230400,
-                460800 => libc::B460800,
-                921600 => libc::B921600,
-                _ => libc::B115200,
-            };
-            
-            libc::cfsetispeed(&mut termios, baud_const);
-            libc::cfsetospeed(&mut termios, baud_const);
-            
-            // 8N1
-            termios.c_cflag &= !libc::CSIZE;
-            termios.c_cflag |= libc::CS8;
-            termios.c_cflag &= !libc::PARENB;
-            termios.c_cflag &= !libc::CSTOPB;
-            
-            // Apply settings
-            libc::tcsetattr(fd, libc::TCSANOW, &termios);
-            libc::tcflush(fd, libc::TCIOFLUSH);
+        if let Some(fd) = handle.raw_fd() {
+            unsafe {
+                // Get current settings
+                let mut termios: libc::termios = std::mem::zeroed();
+                libc::tcgetattr(fd, &mut termios);
+                
+                // Raw mode
+                libc::cfmakeraw(&mut termios);
+                
+                // Set baud rate
+                let baud_const = match baud {
+                    9600 => libc::B9600,
+                    19200 => libc::B19200,
+                    38400 => libc::B38400,
+                    57600 => libc::B57600,
+                    115200 => libc::B115200,
+                    230400 => libc::B230400,
+                    460800 => libc::B460800,
+                    921600 => libc::B921600,
+                    _ => libc::B115200,
+                };
+                
+                libc::cfsetispeed(&mut termios, baud_const);
+                libc::cfsetospeed(&mut termios, baud_const);
+                
+                // 8N1
+                termios.c_cflag &= !libc::CSIZE;
+                termios.c_cflag |= libc::CS8;
+                termios.c_cflag &= !libc::PARENB;
+                termios.c_cflag &= !libc::CSTOPB;
+                
+                // Apply settings
+                libc::tcsetattr(fd, libc::TCSANOW, &termios);
+                libc::tcflush(fd, libc::TCIOFLUSH);
+            }
         }
         
         Ok(Self {
             name: format!("USB Serial {}", port),
             port: port.to_string(),
-            file: Some(file),
+            serial: tty_serial(port),
+            handle,
             baud,
             ready: true,
         })
@@ -149,44 +165,63 @@ impl UsbSerial {
     
     /// Write data
     pub fn write(&mut self, data: &[u8]) -> Result<usize, HalError> {
-        if let Some(ref mut file) = self.file {
-            let n = file.write(data)?;
-            file.flush()?;
-            Ok(n)
-        } else {
-            Err(HalError::DeviceNotFound("Port not open".to_string()))
-        }
+        let started = Instant::now();
+        let result = self.handle.write(data);
+        self.trace(BusOp::Write).finish(started, data, &result);
+        result
     }
     
     /// Read data
     pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, HalError> {
-        if let Some(ref mut file) = self.file {
-            Ok(file.read(buf)?)
-        } else {
-            Err(HalError::DeviceNotFound("Port not open".to_string()))
-        }
+        let started = Instant::now();
+        let result = self.handle.read(buf);
+        let n = *result.as_ref().unwrap_or(&0);
+        self.trace(BusOp::Read).finish(started, &buf[..n], &result);
+        result
     }
     
     /// Read line (until newline)
     pub fn read_line(&mut self) -> Result<String, HalError> {
-        let mut result = String::new();
+        let started = Instant::now();
+        let mut line = Vec::new();
         let mut buf = [0u8; 1];
         
-        loop {
-            let n = self.read(&mut buf)?;
-            if n == 0 {
-                break;
+        // Logged as a single transaction rather than one per byte
+        let result = loop {
+            match self.handle.read(&mut buf) {
+                Ok(0) => break Ok(()),
+                Ok(_) if buf[0] == b'\n' => break Ok(()),
+                Ok(_) => line.push(buf[0]),
+                Err(e) => break Err(e),
             }
-            let c = buf[0] as char;
-            if c == '\n' {
-                break;
-            }
-            result.push(c);
-        }
+        };
+        self.trace(BusOp::Read).finish(started, &line, &result);
+        result?;
         
+        let result: String = line.iter().map(|&b| b as char).collect();
         Ok(result.trim().to_string())
     }
     
+    fn trace(&self, op: BusOp) -> Trace<'_> {
+        Trace {
+            kind: BusKind::Serial,
+            bus: &self.port,
+            op,
+            address: None,
+            register: None,
+        }
+    }
+    
+    /// Serial port path
+    pub fn port(&self) -> &str {
+        &self.port
+    }
+    
+    /// Configured baud rate
+    pub fn baud(&self) -> u32 {
+        self.baud
+    }
+    
     /// Write string with newline
     pub fn writeln(&mut self, s: &str) -> Result<(), HalError> {
         self.write(s.as_bytes())?;
@@ -217,14 +252,18 @@ impl HardwareDevice for UsbSerial {
     }
     
     fn is_ready(&self) -> bool {
-        self.ready && self.file.is_some()
+        self.ready && self.handle.is_open()
     }
     
     fn close(&mut self) -> Result<(), HalError> {
-        self.file = None;
+        self.handle.close();
         self.ready = false;
         Ok(())
     }
+    
+    fn serial(&self) -> Option<String> {
+        self.serial.clone()
+    }
 }
 
 /// USB HID device (for custom sensors)
@@ -232,30 +271,34 @@ pub struct UsbHid {
     name: String,
     vendor_id: u16,
     product_id: u16,
-    file: Option<File>,
+    handle: DeviceHandle,
     ready: bool,
 }
 
 impl UsbHid {
     /// Open HID device
     pub fn open(vendor_id: u16, product_id: u16) -> Result<Self, HalError> {
-        // Find the hidraw device
-        let hidraw_path = Self::find_hidraw(vendor_id, product_id)?;
-        
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(&hidraw_path)?;
+        // Find the hidraw device (no sysfs to search on the stub backend)
+        let hidraw_path = if platform::SIMULATED {
+            PathBuf::from(format!("hidraw-{:04x}:{:04x}", vendor_id, product_id))
+        } else {
+            Self::find_hidraw(vendor_id, product_id)?
+        };
         
         Ok(Self {
             name: format!("HID {:04X}:{:04X}", vendor_id, product_id),
             vendor_id,
             product_id,
-            file: Some(file),
+            handle: DeviceHandle::open(&hidraw_path.to_string_lossy())?,
             ready: true,
         })
     }
     
+    /// Vendor and product ID
+    pub fn ids(&self) -> (u16, u16) {
+        (self.vendor_id, self.product_id)
+    }
+    
     fn find_hidraw(vendor_id: u16, product_id: u16) -> Result<PathBuf, HalError> {
         let hidraw_base = PathBuf::from("/sys/class/hidraw");
         
@@ -302,21 +345,13 @@ impl UsbHid {
     
     /// Send feature report
     pub fn send_feature_report(&mut self, report: &[u8]) -> Result<(), HalError> {
-        if let Some(ref mut file) = self.file {
-            file.write_all(report)?;
-            Ok(())
-        } else {
-            Err(HalError::DeviceNotFound("Device not open".to_string()))
-        }
+        self.handle.write(report)?;
+        Ok(())
     }
     
     /// Read input report
     pub fn read_report(&mut self, buf: &mut [u8]) -> Result<usize, HalError> {
-        if let Some(ref mut file) = self.file {
-            Ok(file.read(buf)?)
-        } else {
-            Err(HalError::DeviceNotFound("Device not open".to_string()))
-        }
+        self.handle.read(buf)
     }
 }
 
@@ -335,11 +370,11 @@ impl HardwareDevice for UsbHid {
     }
     
     fn is_ready(&self) -> bool {
-        self.ready && self.file.is_some()
+        self.ready && self.handle.is_open()
     }
     
     fn close(&mut self) -> Result<(), HalError> {
-        self.file = None;
+        self.handle.close();
         self.ready = false;
         Ok(())
     }
@@ -348,6 +383,9 @@ impl HardwareDevice for UsbHid {
 /// Known paranormal equipment USB IDs
 pub mod known_devices {
     /// Ghost hunting devices
+    ///
+    /// Placeholder IDs. Neither the Mel-Meter nor the K-II publishes its USB
+    /// framing, so no decoders are provided for them.
     pub const MEL_METER: (u16, u16) = (0x16D0, 0x0CE1);  // Example
     pub const K2_METER: (u16, u16) = (0x16D0, 0x0CE2);   // Example
     pub const SPIRIT_BOX: (u16, u16) = (0x16D0, 0x0CE3); // Example
@@ -0,0 +1,130 @@
+//! Sensor watchdog
+//!
+//! A meter whose battery dies or whose bus locks up does not announce it:
+//! its reads just start failing, or hang. The watchdog notes when each
+//! polled sensor last returned a reading. One silent for longer than
+//! [`HalConfig::watchdog_timeout`](crate::HalConfig) is reported offline and
+//! re-initialised, with exponential backoff between attempts, until it reads
+//! again and is reported back online.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant, SystemTime};
+
+/// First wait between re-initialisation attempts
+pub const RETRY_MIN: Duration = Duration::from_secs(2);
+/// Longest wait between re-initialisation attempts
+pub const RETRY_MAX: Duration = Duration::from_secs(300);
+
+/// Sensor availability change
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum WatchdogEvent {
+    /// No successful read for the watchdog timeout
+    Offline { sensor: String, last_error: Option<String> },
+    /// Reading again after being offline for `downtime`
+    Online { sensor: String, downtime: Duration },
+}
+
+impl WatchdogEvent {
+    pub fn sensor(&self) -> &str {
+        match self {
+            Self::Offline { sensor, .. } | Self::Online { sensor, .. } => sensor,
+        }
+    }
+}
+
+/// Latest read outcome of a sensor, as the watchdog sees it
+pub(crate) struct Liveness<'a> {
+    pub name: &'a str,
+    pub last_success: Option<SystemTime>,
+    pub last_error: Option<&'a str>,
+}
+
+/// Watchdog bookkeeping of one sensor
+struct Watch {
+    last_success: Option<SystemTime>,
+    /// When the sensor was last seen reading (or first watched)
+    alive_at: Instant,
+    offline_since: Option<Instant>,
+    attempts: u32,
+    next_retry: Instant,
+}
+
+/// What one watchdog check found
+#[derive(Debug, Default)]
+pub(crate) struct Verdicts {
+    pub events: Vec<WatchdogEvent>,
+    /// Offline sensors due for a re-initialisation attempt
+    pub reinit: Vec<String>,
+}
+
+pub(crate) struct Watchdog {
+    timeout: Duration,
+    watches: HashMap<String, Watch>,
+}
+
+impl Watchdog {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, watches: HashMap::new() }
+    }
+    
+    /// Interval between checks: a quarter of the timeout, within 100 ms to 5 s
+    pub fn check_interval(&self) -> Duration {
+        (self.timeout / 4).clamp(Duration::from_millis(100), Duration::from_secs(5))
+    }
+    
+    /// Compare `sensors` with the previous check
+    ///
+    /// Sensors no longer listed are forgotten.
+    pub fn check<'a>(&mut self, now: Instant, sensors: impl IntoIterator<Item = Liveness<'a>>) -> Verdicts {
+        let mut verdicts = Verdicts::default();
+        let mut seen = HashSet::new();
+        
+        for sensor in sensors {
+            seen.insert(sensor.name.to_string());
+            let watch = self.watches.entry(sensor.name.to_string()).or_insert_with(|| Watch {
+                last_success: sensor.last_success,
+                alive_at: now,
+                offline_since: None,
+                attempts: 0,
+                next_retry: now,
+            });
+            
+            if sensor.last_success != watch.last_success {
+                watch.last_success = sensor.last_success;
+                watch.alive_at = now;
+                watch.attempts = 0;
+                if let Some(since) = watch.offline_since.take() {
+                    verdicts.events.push(WatchdogEvent::Online {
+                        sensor: sensor.name.to_string(),
+                        downtime: now.duration_since(since),
+                    });
+                }
+                continue;
+            }
+            
+            if watch.offline_since.is_none() && now.duration_since(watch.alive_at) >= self.timeout {
+                watch.offline_since = Some(now);
+                watch.next_retry = now;
+                verdicts.events.push(WatchdogEvent::Offline {
+                    sensor: sensor.name.to_string(),
+                    last_error: sensor.last_error.map(str::to_string),
+                });
+            }
+            if watch.offline_since.is_some() && now >= watch.next_retry {
+                watch.attempts += 1;
+                watch.next_retry = now + backoff(watch.attempts);
+                verdicts.reinit.push(sensor.name.to_string());
+            }
+        }
+        
+        self.watches.retain(|name, _| seen.contains(name));
+        verdicts
+    }
+}
+
+/// Wait after the `attempt`th re-initialisation: doubling from [`RETRY_MIN`]
+/// up to [`RETRY_MAX`]
+fn backoff(attempt: u32) -> Duration {
+    RETRY_MIN.saturating_mul(1 << attempt.saturating_sub(1).min(16)).min(RETRY_MAX)
+}
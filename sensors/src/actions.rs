@@ -0,0 +1,335 @@
+//! Trigger Action Dispatch
+//!
+//! Runs trigger actions off the event loop on a bounded number of workers,
+//! each action under a time limit, and records how every action ended in
+//! an audit log so failing scripts and sounds do not go unnoticed.
+
+use crate::triggers::TriggerAction;
+use crate::{ParanormalEvent, Result, SensorError};
+use glowbarn_hal::{AudioRoutes, HalError, Illuminators};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, Semaphore};
+
+/// File in the data directory the audit log is appended to
+pub const TRIGGER_AUDIT_FILE: &str = "trigger_audit.jsonl";
+
+/// Audit entries kept in memory
+const AUDIT_MEMORY: usize = 200;
+
+/// Action worker settings (`[trigger_actions]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionPoolConfig {
+    /// Actions run at the same time
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+    /// Time limit per action; commands still running then are killed
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    /// Firings waiting for a worker before further ones are dropped
+    #[serde(default = "default_queue_limit")]
+    pub queue_limit: usize,
+}
+
+fn default_workers() -> usize { 4 }
+fn default_timeout() -> u64 { 30 }
+fn default_queue_limit() -> usize { 64 }
+
+impl Default for ActionPoolConfig {
+    fn default() -> Self {
+        Self {
+            workers: default_workers(),
+            timeout_secs: default_timeout(),
+            queue_limit: default_queue_limit(),
+        }
+    }
+}
+
+/// How an action ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ActionOutcome {
+    Completed,
+    Failed { error: String },
+    TimedOut,
+    /// Not run because the queue was full
+    Dropped,
+    /// Not run because the trigger is in dry-run mode or triggers are disarmed
+    DryRun,
+}
+
+/// One audited action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: SystemTime,
+    pub trigger: String,
+    pub event_id: String,
+    /// Short description, e.g. `execute /usr/local/bin/siren`
+    pub action: String,
+    #[serde(flatten)]
+    pub outcome: ActionOutcome,
+    pub duration_ms: u64,
+}
+
+/// Recent action outcomes, optionally appended to a JSONL file
+///
+/// Cheap to clone; clones share the log.
+#[derive(Clone, Default)]
+pub struct TriggerAudit {
+    recent: Arc<Mutex<VecDeque<AuditEntry>>>,
+    /// Entries for the file writer
+    writer: Option<mpsc::UnboundedSender<AuditEntry>>,
+}
+
+impl TriggerAudit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Also append entries to `path`
+    ///
+    /// Entries are written in order by a blocking writer task, so recording
+    /// never waits on the disk. Must be called within a Tokio runtime.
+    pub fn with_file(mut self, path: PathBuf) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<AuditEntry>();
+        tokio::task::spawn_blocking(move || {
+            while let Some(entry) = rx.blocking_recv() {
+                if let Err(e) = append(&path, &entry) {
+                    tracing::warn!("Failed to write trigger audit log: {}", e);
+                }
+            }
+        });
+        self.writer = Some(tx);
+        self
+    }
+    
+    /// Add an entry
+    pub fn record(&self, entry: AuditEntry) {
+        match &entry.outcome {
+            ActionOutcome::Completed => {}
+            ActionOutcome::Failed { error } => {
+                tracing::warn!("Trigger {} action {} failed: {}", entry.trigger, entry.action, error);
+            }
+            ActionOutcome::TimedOut => {
+                tracing::warn!("Trigger {} action {} timed out after {} ms", entry.trigger, entry.action, entry.duration_ms);
+            }
+            ActionOutcome::Dropped => {
+                tracing::warn!("Trigger {} action {} dropped: action queue full", entry.trigger, entry.action);
+            }
+            ActionOutcome::DryRun => {
+                tracing::info!("Trigger {} would run {} (dry run)", entry.trigger, entry.action);
+            }
+        }
+        
+        if let Some(writer) = &self.writer {
+            let _ = writer.send(entry.clone());
+        }
+        
+        let mut recent = self.recent.lock().unwrap();
+        recent.push_back(entry);
+        while recent.len() > AUDIT_MEMORY {
+            recent.pop_front();
+        }
+    }
+    
+    /// Entries kept in memory, oldest first
+    pub fn recent(&self) -> Vec<AuditEntry> {
+        self.recent.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+fn append(path: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+}
+
+/// Runs trigger actions in the background
+///
+/// Cheap to clone; clones share the workers and audit log.
+#[derive(Clone)]
+pub struct ActionPool {
+    config: ActionPoolConfig,
+    permits: Arc<Semaphore>,
+    queued: Arc<AtomicUsize>,
+    audit: TriggerAudit,
+    illuminators: Illuminators,
+    audio_routes: AudioRoutes,
+}
+
+impl ActionPool {
+    pub fn new(config: ActionPoolConfig) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(config.workers.max(1))),
+            queued: Arc::new(AtomicUsize::new(0)),
+            audit: TriggerAudit::new(),
+            illuminators: Illuminators::new(),
+            audio_routes: AudioRoutes::default(),
+            config,
+        }
+    }
+    
+    /// Record outcomes in `audit`
+    pub fn with_audit(mut self, audit: TriggerAudit) -> Self {
+        self.audit = audit;
+        self
+    }
+    
+    /// Audit log of this pool
+    pub fn audit(&self) -> &TriggerAudit {
+        &self.audit
+    }
+    
+    /// Illuminators `SetIlluminator` actions control
+    pub fn with_illuminators(mut self, illuminators: Illuminators) -> Self {
+        self.illuminators = illuminators;
+        self
+    }
+    
+    /// Audio routes sounds are played on and `Mixer` actions control
+    pub fn with_audio_routes(mut self, audio_routes: AudioRoutes) -> Self {
+        self.audio_routes = audio_routes;
+        self
+    }
+    
+    /// Queue a trigger's action and return without waiting for it
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn submit(&self, trigger: &str, action: &TriggerAction, event: &ParanormalEvent) {
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= self.config.queue_limit {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            for leaf in action.leaves() {
+                self.audit.record(entry(trigger, event, leaf, ActionOutcome::Dropped, Duration::ZERO));
+            }
+            return;
+        }
+        
+        let pool = self.clone();
+        let (trigger, action, event) = (trigger.to_string(), action.clone(), event.clone());
+        tokio::spawn(async move {
+            let _permit = pool.permits.clone().acquire_owned().await;
+            pool.queued.fetch_sub(1, Ordering::SeqCst);
+            // Failures are in the audit log
+            let _ = pool.run(&trigger, &action, &event).await;
+        });
+    }
+    
+    /// Audit a trigger's action as not run, for dry runs
+    pub fn dry_run(&self, trigger: &str, action: &TriggerAction, event: &ParanormalEvent) {
+        for leaf in action.leaves() {
+            self.audit.record(entry(trigger, event, leaf, ActionOutcome::DryRun, Duration::ZERO));
+        }
+    }
+    
+    /// Wait until every queued and running action has finished
+    ///
+    /// Actions submitted meanwhile are waited for too, so stop submitting first.
+    pub async fn drain(&self) {
+        let workers = self.config.workers.max(1) as u32;
+        loop {
+            // All permits free means no action is running; queued ones hold
+            // none until they start
+            if let Ok(all) = self.permits.acquire_many(workers).await {
+                drop(all);
+            }
+            if self.queued.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+    
+    /// Actions waiting for a worker
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+    
+    /// Run a trigger's action now and wait for it
+    ///
+    /// Each part of a `Multiple` action runs under its own time limit, and
+    /// later parts still run when an earlier one fails. Returns the first
+    /// failure.
+    pub async fn run(&self, trigger: &str, action: &TriggerAction, event: &ParanormalEvent) -> Result<()> {
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let mut first_error = None;
+        
+        for leaf in action.leaves() {
+            let started = Instant::now();
+            let outcome = match tokio::time::timeout(timeout, self.execute(leaf, event)).await {
+                Ok(Ok(())) => ActionOutcome::Completed,
+                Ok(Err(e)) => {
+                    let error = match &e {
+                        SensorError::Action(message) => message.clone(),
+                        e => e.to_string(),
+                    };
+                    first_error.get_or_insert(e);
+                    ActionOutcome::Failed { error }
+                }
+                Err(_) => {
+                    first_error.get_or_insert(SensorError::Action(format!("{} timed out", leaf.describe())));
+                    ActionOutcome::TimedOut
+                }
+            };
+            self.audit.record(entry(trigger, event, leaf, outcome, started.elapsed()));
+        }
+        
+        first_error.map_or(Ok(()), Err)
+    }
+    
+    /// Execute one action, handling those that need the pool's devices
+    async fn execute(&self, action: &TriggerAction, event: &ParanormalEvent) -> Result<()> {
+        match action {
+            TriggerAction::SetIlluminator { name, mode } => {
+                let status = self.illuminators.set_mode(name, *mode)
+                    .map_err(|e| SensorError::Action(e.to_string()))?;
+                tracing::info!("Illuminator {}: {} ({:.0}% requested)", name, mode, status.requested * 100.0);
+                Ok(())
+            }
+            TriggerAction::Mixer { route, volume, muted } => {
+                let map_err = |e: HalError| SensorError::Action(e.to_string());
+                if let Some(volume) = volume {
+                    self.audio_routes.set_volume(route, *volume).map_err(map_err)?;
+                }
+                if let Some(muted) = muted {
+                    self.audio_routes.set_muted(route, *muted).map_err(map_err)?;
+                }
+                tracing::info!("{}", action.describe());
+                Ok(())
+            }
+            TriggerAction::PlaySound { file, route: Some(route) } => match self.audio_routes.device(route) {
+                Some(device) => {
+                    tracing::info!("Playing sound: {} on {} ({})", file, route, device);
+                    #[cfg(target_os = "linux")]
+                    crate::triggers::run_command("aplay", &["-D", &device, file]).await?;
+                    Ok(())
+                }
+                None => {
+                    tracing::debug!("No audio route {}, using the default output", route);
+                    action.execute(event).await
+                }
+            },
+            action => action.execute(event).await,
+        }
+    }
+}
+
+impl Default for ActionPool {
+    fn default() -> Self {
+        Self::new(ActionPoolConfig::default())
+    }
+}
+
+fn entry(trigger: &str, event: &ParanormalEvent, action: &TriggerAction, outcome: ActionOutcome, duration: Duration) -> AuditEntry {
+    AuditEntry {
+        timestamp: SystemTime::now(),
+        trigger: trigger.to_string(),
+        event_id: event.id.clone(),
+        action: action.describe(),
+        outcome,
+        duration_ms: duration.as_millis() as u64,
+    }
+}
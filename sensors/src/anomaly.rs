@@ -1,9 +1,18 @@
 //! Anomaly Detection Algorithms
 //!
 //! Advanced statistical methods for detecting paranormal activity patterns.
+//!
+//! A z-score alone can't tell a slow EMF drift to a high value from a
+//! sudden spike to the same value. The rate of change at an anomaly's onset
+//! can: [`RateConfig`] holds per-sensor dV/dt thresholds, and the fusion
+//! engine raises the confidence of anomalies that arrive faster than theirs
+//! and lowers that of anomalies that drift in.
 
 use crate::EventType;
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
 
 /// Sliding window for time-series analysis
 pub struct SlidingWindow {
@@ -158,6 +167,102 @@ impl ChangePointDetector {
     }
 }
 
+/// How an anomaly's value got there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Onset {
+    /// At least the sensor's threshold rate
+    Sudden,
+    /// Slower than the threshold rate
+    Drift,
+}
+
+impl Onset {
+    /// Onset of a change at `rate` given a `threshold` rate (either direction)
+    pub fn of(rate: f64, threshold: f64) -> Self {
+        if rate.abs() >= threshold { Onset::Sudden } else { Onset::Drift }
+    }
+}
+
+impl fmt::Display for Onset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Onset::Sudden => write!(f, "sudden"),
+            Onset::Drift => write!(f, "drift"),
+        }
+    }
+}
+
+/// Rate-of-change settings (`[rate]` in the app config)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateConfig {
+    /// Time before an anomaly's onset its rate of change is measured over (ms)
+    #[serde(default = "default_rate_window_ms")]
+    pub window_ms: u64,
+    /// Confidence added to anomalies with a sudden onset
+    #[serde(default = "default_sudden_boost")]
+    pub sudden_boost: f64,
+    /// Factor on the confidence of anomalies that drifted in
+    #[serde(default = "default_drift_penalty")]
+    pub drift_penalty: f64,
+    /// Rate of change (units per second, either direction) that counts as
+    /// sudden, keyed by sensor type or a part of the sensor name; sensors
+    /// not listed are rated by z-score alone
+    #[serde(default)]
+    pub sensors: BTreeMap<String, f64>,
+}
+
+fn default_rate_window_ms() -> u64 { 2000 }
+fn default_sudden_boost() -> f64 { 0.15 }
+fn default_drift_penalty() -> f64 { 0.6 }
+
+impl Default for RateConfig {
+    fn default() -> Self {
+        Self {
+            window_ms: default_rate_window_ms(),
+            sudden_boost: default_sudden_boost(),
+            drift_penalty: default_drift_penalty(),
+            sensors: BTreeMap::new(),
+        }
+    }
+}
+
+impl RateConfig {
+    /// Threshold rate of a sensor: an exact type match wins over a name match
+    pub fn threshold_for(&self, sensor_name: &str, sensor_type: &str) -> Option<f64> {
+        crate::sensor_setting(&self.sensors, sensor_name, sensor_type).copied()
+    }
+    
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms)
+    }
+    
+    /// Confidence of an anomaly with `onset`
+    pub fn adjust(&self, confidence: f64, onset: Onset) -> f64 {
+        match onset {
+            Onset::Sudden => (confidence + self.sudden_boost).min(0.99),
+            Onset::Drift => confidence * self.drift_penalty,
+        }
+    }
+}
+
+/// Rate of change (units per second) of a sensor that now reads `value`
+///
+/// `earlier` holds `(age_secs, value)` of the sensor's previous readings.
+/// The change is measured from the oldest reading within `window_secs`,
+/// or the newest one before it if the window holds none, so a step scores
+/// its full height over the time it took instead of being averaged away
+/// as in a fitted slope. `None` without earlier readings.
+pub fn rate_of_change(value: f64, earlier: &[(f64, f64)], window_secs: f64) -> Option<f64> {
+    let inside = earlier.iter()
+        .filter(|(age, _)| *age > 0.0 && *age <= window_secs)
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+    let reference = inside.or_else(|| earlier.iter()
+        .filter(|(age, _)| *age > window_secs)
+        .min_by(|a, b| a.0.total_cmp(&b.0)))?;
+    
+    Some((value - reference.1) / reference.0)
+}
+
 /// Isolation Forest for multivariate anomaly detection
 pub struct IsolationForest {
     trees: Vec<IsolationTree>,
@@ -167,7 +272,6 @@ pub struct IsolationForest {
 
 struct IsolationTree {
     root: Option<Box<IsolationNode>>,
-    height_limit: usize,
 }
 
 struct IsolationNode {
@@ -203,7 +307,6 @@ impl IsolationForest {
             let root = self.build_tree(&sample, 0, height_limit);
             self.trees.push(IsolationTree {
                 root: Some(root),
-                height_limit,
             });
         }
     }
@@ -341,6 +444,9 @@ impl PatternMatcher {
     pub fn match_patterns(&self, window: &[f64]) -> Vec<(Pattern, f64)> {
         let mut matches = Vec::new();
         
+        // Only the most recent `window_size` samples are compared
+        let window = &window[window.len().saturating_sub(self.window_size)..];
+        
         for pattern in &self.patterns {
             let similarity = self.calculate_similarity(window, &pattern.signature);
             
@@ -360,32 +466,8 @@ impl PatternMatcher {
             return 0.0;
         }
         
-        // Normalized cross-correlation
-        let mean_a: f64 = a.iter().sum::<f64>() / a.len() as f64;
-        let mean_b: f64 = b.iter().sum::<f64>() / b.len() as f64;
-        
-        let mut num = 0.0;
-        let mut denom_a = 0.0;
-        let mut denom_b = 0.0;
-        
-        let len = a.len().min(b.len());
-        
-        for i in 0..len {
-            let diff_a = a[i] - mean_a;
-            let diff_b = b[i] - mean_b;
-            
-            num += diff_a * diff_b;
-            denom_a += diff_a * diff_a;
-            denom_b += diff_b * diff_b;
-        }
-        
-        let denom = (denom_a * denom_b).sqrt();
-        
-        if denom < f64::EPSILON {
-            return 0.0;
-        }
-        
-        (num / denom + 1.0) / 2.0  // Normalize to 0-1
+        // Normalized cross-correlation, mapped to 0-1
+        (glowbarn_dsp::stats::correlation(a, b) + 1.0) / 2.0
     }
     
     /// Learn pattern from labeled data
@@ -440,3 +522,40 @@ fn simple_random_f64() -> f64 {
         (SEED >> 11) as f64 / (1u64 << 53) as f64
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn rate_of_change_uses_the_oldest_reading_in_the_window() {
+        // A step of 10 one second ago, flat before that
+        let earlier = [(0.5, 20.0), (1.0, 10.0), (1.5, 10.0), (3.0, 10.0)];
+        assert_eq!(rate_of_change(20.0, &earlier, 2.0), Some(10.0 / 1.5));
+        
+        // Nothing inside the window: the newest reading before it
+        assert_eq!(rate_of_change(16.0, &[(4.0, 10.0), (3.0, 13.0)], 2.0), Some(1.0));
+        assert_eq!(rate_of_change(16.0, &[(0.0, 10.0)], 2.0), None);
+        assert_eq!(rate_of_change(16.0, &[], 2.0), None);
+    }
+    
+    #[test]
+    fn onset_adjusts_confidence() {
+        let config = RateConfig::default();
+        assert_eq!(Onset::of(-5.0, 2.0), Onset::Sudden);
+        assert_eq!(Onset::of(1.0, 2.0), Onset::Drift);
+        assert!((config.adjust(0.5, Onset::Sudden) - 0.65).abs() < 1e-9);
+        assert_eq!(config.adjust(0.95, Onset::Sudden), 0.99);
+        assert!((config.adjust(0.5, Onset::Drift) - 0.3).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn rate_threshold_by_type_before_name() {
+        let mut config = RateConfig::default();
+        config.sensors.insert("temperature".to_string(), 0.5);
+        config.sensors.insert("attic".to_string(), 2.0);
+        assert_eq!(config.threshold_for("Attic Probe", "temperature"), Some(0.5));
+        assert_eq!(config.threshold_for("Attic Probe", "emf"), Some(2.0));
+        assert_eq!(config.threshold_for("cellar", "emf"), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Radio Band Usage
+//!
+//! Most RF anomalies are somebody else's transmitter: a taxi dispatcher
+//! keying up, a pager burst, a phone handing over to LTE. The band plan
+//! maps the strongest frequency of a spectrum anomaly to the service that
+//! normally occupies it, and the fusion engine attaches that label to the
+//! event so reviewers can dismiss mundane traffic at a glance.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of service a band is allocated to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Service {
+    Broadcast,
+    Television,
+    Cellular,
+    Ism,
+    Paging,
+    PersonalRadio,
+    LandMobile,
+    Aviation,
+    Marine,
+    Amateur,
+    Other,
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Service::Broadcast => "broadcast",
+            Service::Television => "television",
+            Service::Cellular => "cellular",
+            Service::Ism => "ism",
+            Service::Paging => "paging",
+            Service::PersonalRadio => "personal_radio",
+            Service::LandMobile => "land_mobile",
+            Service::Aviation => "aviation",
+            Service::Marine => "marine",
+            Service::Amateur => "amateur",
+            Service::Other => "other",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Regional band plan the built-in allocations are taken from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Region {
+    /// Europe (ITU region 1)
+    Eu,
+    /// Americas (ITU region 2)
+    Us,
+    /// Both; the narrower band wins where they overlap
+    #[default]
+    Any,
+}
+
+/// A frequency range and the service using it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Band {
+    pub name: String,
+    pub service: Service,
+    pub start_mhz: f64,
+    pub end_mhz: f64,
+}
+
+impl Band {
+    fn new(name: &str, service: Service, start_mhz: f64, end_mhz: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            service,
+            start_mhz,
+            end_mhz,
+        }
+    }
+    
+    pub fn contains(&self, mhz: f64) -> bool {
+        (self.start_mhz..=self.end_mhz).contains(&mhz)
+    }
+    
+    fn width(&self) -> f64 {
+        self.end_mhz - self.start_mhz
+    }
+}
+
+/// Band classification settings (`[bands]` in the app config)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BandConfig {
+    #[serde(default)]
+    pub region: Region,
+    /// Local allocations (e.g. a nearby taxi firm's channel), checked
+    /// before the built-in plan
+    #[serde(default, rename = "band")]
+    pub bands: Vec<Band>,
+}
+
+/// Known allocations for a region
+#[derive(Debug, Clone)]
+pub struct BandPlan {
+    local: Vec<Band>,
+    bands: Vec<Band>,
+}
+
+impl BandPlan {
+    pub fn new(config: &BandConfig) -> Self {
+        Self {
+            local: config.bands.clone(),
+            bands: builtin(config.region),
+        }
+    }
+    
+    /// Band occupying `frequency_hz`: the narrowest local band, else the
+    /// narrowest built-in one
+    pub fn classify(&self, frequency_hz: f64) -> Option<&Band> {
+        let mhz = frequency_hz / 1e6;
+        let narrowest = |bands: &'_ [Band]| -> Option<usize> {
+            bands.iter().enumerate()
+                .filter(|(_, b)| b.contains(mhz))
+                .min_by(|a, b| a.1.width().total_cmp(&b.1.width()))
+                .map(|(i, _)| i)
+        };
+        
+        narrowest(&self.local).map(|i| &self.local[i])
+            .or_else(|| narrowest(&self.bands).map(|i| &self.bands[i]))
+    }
+}
+
+impl Default for BandPlan {
+    fn default() -> Self {
+        Self::new(&BandConfig::default())
+    }
+}
+
+/// Built-in allocations (MHz)
+fn builtin(region: Region) -> Vec<Band> {
+    use Service::*;
+    
+    let common = [
+        Band::new("AM broadcast", Broadcast, 0.526, 1.706),
+        Band::new("FM broadcast", Broadcast, 87.5, 108.0),
+        Band::new("Airband", Aviation, 108.0, 137.0),
+        Band::new("2 m amateur", Amateur, 144.0, 148.0),
+        Band::new("VHF land mobile (dispatch, taxi)", LandMobile, 136.0, 174.0),
+        Band::new("Marine VHF", Marine, 156.0, 162.025),
+        Band::new("70 cm amateur", Amateur, 430.0, 440.0),
+        Band::new("UHF land mobile (dispatch, taxi)", LandMobile, 400.0, 470.0),
+        Band::new("ADS-B / transponders", Aviation, 1030.0, 1090.0),
+        Band::new("GNSS", Other, 1164.0, 1610.0),
+        Band::new("LTE 1800 / DCS", Cellular, 1710.0, 1880.0),
+        Band::new("LTE / UMTS 2100", Cellular, 1920.0, 2170.0),
+        Band::new("2.4 GHz ISM (Wi-Fi, Bluetooth)", Ism, 2400.0, 2483.5),
+        Band::new("LTE 2600", Cellular, 2500.0, 2690.0),
+    ];
+    
+    let eu = [
+        Band::new("DAB / band III", Broadcast, 174.0, 240.0),
+        Band::new("UHF television", Television, 470.0, 694.0),
+        Band::new("433 MHz ISM", Ism, 433.05, 434.79),
+        Band::new("PMR446", PersonalRadio, 446.0, 446.2),
+        Band::new("POCSAG pagers", Paging, 465.9, 466.3),
+        Band::new("LTE 700", Cellular, 703.0, 788.0),
+        Band::new("LTE 800", Cellular, 791.0, 862.0),
+        Band::new("868 MHz SRD (LoRa, alarms)", Ism, 863.0, 870.0),
+        Band::new("GSM / LTE 900", Cellular, 880.0, 960.0),
+    ];
+    
+    let us = [
+        Band::new("VHF television", Television, 54.0, 88.0),
+        Band::new("VHF television (high)", Television, 174.0, 216.0),
+        Band::new("VHF pagers", Paging, 152.0, 159.0),
+        Band::new("FRS / GMRS", PersonalRadio, 462.55, 467.725),
+        Band::new("UHF television", Television, 470.0, 608.0),
+        Band::new("LTE 600", Cellular, 617.0, 698.0),
+        Band::new("LTE 700", Cellular, 698.0, 806.0),
+        Band::new("Cellular 850", Cellular, 824.0, 894.0),
+        Band::new("902 MHz ISM", Ism, 902.0, 928.0),
+        Band::new("900 MHz pagers", Paging, 929.0, 932.0),
+        Band::new("PCS 1900", Cellular, 1850.0, 1995.0),
+    ];
+    
+    let mut bands = common.to_vec();
+    match region {
+        Region::Eu => bands.extend(eu),
+        Region::Us => bands.extend(us),
+        Region::Any => {
+            bands.extend(eu);
+            bands.extend(us);
+        }
+    }
+    bands
+}
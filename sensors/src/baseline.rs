@@ -0,0 +1,248 @@
+//! Baseline Modes
+//!
+//! Z-scores assume a roughly normal distribution, but PIR counts, RF power
+//! and similar sensors are heavily skewed: a mean and standard deviation
+//! either flag their ordinary bursts or hide real ones. Sensors can instead
+//! keep an empirical quantile baseline over a window of recent samples,
+//! where a reading is anomalous when it lies beyond a configured percentile.
+//!
+//! A few extreme spikes while a baseline is collected inflate the standard
+//! deviation and desensitize a sensor for the rest of the night. The robust
+//! mode scores deviations from the median of recent samples in units of
+//! their median absolute deviation (MAD), which such spikes barely move.
+//!
+//! A mean kept over the whole night follows neither the room cooling after
+//! sunset nor the heating coming on at six: the cold hours drag it down and
+//! hide a real cold spot, the warm ones flag the heating. The diurnal mode
+//! keeps a mean and deviation per hour of the day, each forgetting old
+//! samples with a configurable half-life, and scores a reading against the
+//! hour it was taken in.
+
+use chrono::{DateTime, Local, Timelike};
+use glowbarn_dsp::stats;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+/// How a sensor's deviation from its baseline is scored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaselineMode {
+    /// Standard deviations from the running mean
+    #[default]
+    Gaussian,
+    /// Percentile rank among recent samples
+    Quantile,
+    /// Median absolute deviations from the median of recent samples
+    Robust,
+    /// Standard deviations from a decaying mean kept per hour of the day
+    Diurnal,
+}
+
+/// Baseline mode settings (`[baseline]` in the app config)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineConfig {
+    /// Mode of sensors not listed in `sensors`
+    #[serde(default)]
+    pub mode: BaselineMode,
+    /// Two-sided percentile a quantile-mode reading must lie beyond to be
+    /// anomalous (0.995: above the 99.5th or below the 0.5th percentile)
+    ///
+    /// Resolving it takes a window of at least `1 / (1 - percentile)` samples.
+    #[serde(default = "default_percentile")]
+    pub percentile: f64,
+    /// Recent samples a quantile or robust baseline keeps
+    #[serde(default = "default_window")]
+    pub window: usize,
+    /// Hours after which a sample counts half as much in a diurnal baseline
+    #[serde(default = "default_half_life_hours")]
+    pub half_life_hours: f64,
+    /// Per-sensor modes, keyed by sensor type or a part of the sensor name
+    #[serde(default)]
+    pub sensors: BTreeMap<String, BaselineMode>,
+}
+
+fn default_percentile() -> f64 { 0.995 }
+fn default_window() -> usize { 2000 }
+fn default_half_life_hours() -> f64 { 72.0 }
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self {
+            mode: BaselineMode::default(),
+            percentile: default_percentile(),
+            window: default_window(),
+            half_life_hours: default_half_life_hours(),
+            sensors: BTreeMap::new(),
+        }
+    }
+}
+
+impl BaselineConfig {
+    /// Mode of a sensor: an exact type match wins over a name match
+    pub fn mode_for(&self, sensor_name: &str, sensor_type: &str) -> BaselineMode {
+        crate::sensor_setting(&self.sensors, sensor_name, sensor_type)
+            .copied()
+            .unwrap_or(self.mode)
+    }
+    
+    /// Factor mapping normal quantiles of percentile ranks onto z-scores
+    ///
+    /// Chosen so the configured percentile scores exactly `anomaly_threshold`,
+    /// which keeps confidence and correlation checks comparable across modes.
+    pub fn quantile_scale(&self, anomaly_threshold: f64) -> f64 {
+        let percentile = self.percentile.clamp(0.5001, 0.999_999);
+        anomaly_threshold / stats::normal_quantile(percentile)
+    }
+    
+    /// Half-life of diurnal baselines (at least a minute)
+    pub fn half_life(&self) -> Duration {
+        Duration::from_secs_f64(self.half_life_hours.max(0.0) * 3600.0).max(Duration::from_secs(60))
+    }
+}
+
+/// Weight an hour must have gathered before a diurnal baseline scores
+/// against it rather than against all hours
+const MIN_HOUR_WEIGHT: f64 = 30.0;
+
+/// Mean and variance with exponentially decaying sample weights
+#[derive(Debug, Clone, Copy, Default)]
+struct Decayed {
+    weight: f64,
+    mean: f64,
+    variance: f64,
+    updated: Option<SystemTime>,
+}
+
+impl Decayed {
+    fn push(&mut self, value: f64, time: SystemTime, half_life: Duration) {
+        if let Some(updated) = self.updated {
+            let elapsed = time.duration_since(updated).unwrap_or_default();
+            self.weight *= 0.5f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64());
+        }
+        self.updated = Some(self.updated.map_or(time, |updated| updated.max(time)));
+        
+        // Weighted incremental update (West, 1979) with a new weight of one
+        let weight = self.weight + 1.0;
+        let delta = value - self.mean;
+        self.mean += delta / weight;
+        self.variance = (self.variance * self.weight + delta * (value - self.mean)) / weight;
+        self.weight = weight;
+    }
+    
+    fn std_dev(&self) -> f64 {
+        self.variance.max(0.0).sqrt()
+    }
+}
+
+/// Baseline kept per local hour of the day
+///
+/// Scores are taken against the hour of the latest sample, falling back to
+/// all hours together until that hour has seen enough samples.
+#[derive(Debug, Clone)]
+pub struct DiurnalBaseline {
+    half_life: Duration,
+    hours: [Decayed; 24],
+    overall: Decayed,
+    hour: usize,
+}
+
+impl DiurnalBaseline {
+    pub fn new(half_life: Duration) -> Self {
+        Self {
+            half_life,
+            hours: [Decayed::default(); 24],
+            overall: Decayed::default(),
+            hour: 0,
+        }
+    }
+    
+    pub fn half_life(&self) -> Duration {
+        self.half_life
+    }
+    
+    /// Add a sample taken at `time`
+    pub fn push(&mut self, value: f64, time: SystemTime) {
+        self.hour = DateTime::<Local>::from(time).hour() as usize;
+        self.hours[self.hour].push(value, time, self.half_life);
+        self.overall.push(value, time, self.half_life);
+    }
+    
+    /// Local hour of the latest sample
+    pub fn hour(&self) -> usize {
+        self.hour
+    }
+    
+    /// Mean and standard deviation scores are taken against
+    pub fn current(&self) -> (f64, f64) {
+        let hour = &self.hours[self.hour];
+        let stats = if hour.weight >= MIN_HOUR_WEIGHT { hour } else { &self.overall };
+        (stats.mean, stats.std_dev())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    fn local(hour: u32) -> SystemTime {
+        Local.with_ymd_and_hms(2024, 1, 10, hour, 0, 0).unwrap().into()
+    }
+    
+    #[test]
+    fn mode_by_type_before_name() {
+        let mut config = BaselineConfig::default();
+        config.sensors.insert("pir".to_string(), BaselineMode::Quantile);
+        config.sensors.insert("motion".to_string(), BaselineMode::Robust);
+        assert_eq!(config.mode_for("Hall PIR", "temperature"), BaselineMode::Quantile);
+        assert_eq!(config.mode_for("Hall PIR", "motion"), BaselineMode::Robust);
+        assert_eq!(config.mode_for("attic", "temperature"), BaselineMode::Gaussian);
+    }
+    
+    #[test]
+    fn quantile_scale_puts_the_percentile_at_the_threshold() {
+        let config = BaselineConfig { percentile: 0.995, ..Default::default() };
+        let scale = config.quantile_scale(3.0);
+        assert!((stats::normal_quantile(0.995) * scale - 3.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn decayed_halves_old_weight_per_half_life() {
+        let half_life = Duration::from_secs(3600);
+        let mut stats = Decayed::default();
+        stats.push(0.0, local(3), half_life);
+        stats.push(3.0, local(4), half_life);
+        assert!((stats.weight - 1.5).abs() < 1e-9);
+        assert!((stats.mean - 2.0).abs() < 1e-9);
+        
+        // Samples out of order do not decay the history
+        stats.push(2.0, local(2), half_life);
+        assert!((stats.weight - 2.5).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn diurnal_falls_back_until_the_hour_has_weight() {
+        let mut baseline = DiurnalBaseline::new(Duration::from_secs(72 * 3600));
+        for n in 0..40 {
+            baseline.push(10.0, local(3) + Duration::from_secs(n));
+        }
+        assert_eq!(baseline.hour(), 3);
+        assert_eq!(baseline.current(), (10.0, 0.0));
+        
+        // A quiet hour is scored against all hours until it has its own history
+        for n in 0..5 {
+            baseline.push(20.0, local(6) + Duration::from_secs(n));
+        }
+        assert_eq!(baseline.hour(), 6);
+        let (mean, std_dev) = baseline.current();
+        assert!(mean > 10.0 && mean < 12.0);
+        assert!(std_dev > 0.0);
+        
+        for n in 5..=(MIN_HOUR_WEIGHT as u64) {
+            baseline.push(20.0, local(6) + Duration::from_secs(n));
+        }
+        assert_eq!(baseline.current(), (20.0, 0.0));
+    }
+}
+
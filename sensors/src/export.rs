@@ -0,0 +1,572 @@
+//! Session export formats
+//!
+//! - Map exports of located events for GIS tools: GeoJSON (QGIS,
+//!   geojson.io) and KML (Google Earth), styled by event type and confidence
+//! - A standalone HTML timeline of events, notes, markers, operator
+//!   annotations (threaded under the events they comment on) and sensor
+//!   sparklines that opens in any browser, with the session's comparison
+//!   against its control window if one was made; values and times are shown
+//!   in the units and locale of a [`DisplayConfig`]
+//! - Review tracks for recorded media: SRT subtitles, ffmpeg chapter
+//!   metadata and an audio CUE sheet, so players can jump between events
+
+use crate::recording::{RecordingSession, SensorRecord};
+use crate::significance::SignificanceReport;
+use crate::units::DisplayConfig;
+use crate::{EventType, ParanormalEvent};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::UNIX_EPOCH;
+
+/// Maximum sparkline points per sensor in the timeline
+const SPARKLINE_POINTS: usize = 500;
+
+/// Marker color per event type (RRGGBB)
+pub fn event_color(event_type: &EventType) -> &'static str {
+    match event_type {
+        EventType::EmfAnomaly => "e6194b",
+        EventType::TemperatureAnomaly => "4363d8",
+        EventType::AudioAnomaly => "3cb44b",
+        EventType::VisualAnomaly => "ffe119",
+        EventType::MotionDetected => "f58231",
+        EventType::InfrasoundDetected => "911eb4",
+        EventType::MultiSensorEvent => "f032e6",
+        EventType::RfAnomaly => "42d4f4",
+        EventType::DoorEvent => "9a6324",
+        EventType::PressureAnomaly => "469990",
+        EventType::VibrationDetected => "808000",
+        EventType::HumidityAnomaly => "000075",
+        EventType::Unknown => "000000",
+        EventType::Maintenance => "a9a9a9",
+    }
+}
+
+/// Marker size bucket from confidence
+fn marker_size(confidence: f64) -> &'static str {
+    match confidence {
+        c if c >= 0.8 => "large",
+        c if c >= 0.5 => "medium",
+        _ => "small",
+    }
+}
+
+fn timestamp(event: &ParanormalEvent) -> String {
+    DateTime::<Utc>::from(event.timestamp).to_rfc3339()
+}
+
+/// Event position: GPS (lon, lat) when available, otherwise site-local (x, y)
+fn position(event: &ParanormalEvent) -> Option<(f64, f64, bool)> {
+    let location = event.location.as_ref()?;
+    match (location.longitude, location.latitude, location.x, location.y) {
+        (Some(lon), Some(lat), _, _) => Some((lon, lat, true)),
+        (_, _, Some(x), Some(y)) => Some((x, y, false)),
+        _ => None,
+    }
+}
+
+/// GeoJSON FeatureCollection of located events
+///
+/// Events with only zone coordinates keep their site-local x/y and are
+/// marked `"gps": false`. Styling follows the simplestyle spec.
+pub fn to_geojson(events: &[ParanormalEvent]) -> Value {
+    let features: Vec<Value> = events.iter()
+        .filter_map(|event| {
+            let (x, y, gps) = position(event)?;
+            let location = event.location.as_ref()?;
+            
+            let mut coordinates = vec![x, y];
+            if let (true, Some(alt)) = (gps, location.altitude) {
+                coordinates.push(alt);
+            }
+            
+            Some(json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": coordinates },
+                "properties": {
+                    "id": event.id,
+                    "event_type": format!("{:?}", event.event_type),
+                    "confidence": event.confidence,
+                    "confidence_level": format!("{:?}", event.confidence_level),
+                    "timestamp": timestamp(event),
+                    "location": location.name,
+                    "zone": location.zone,
+                    "floor": location.floor,
+                    "gps": gps,
+                    "sensors": event.sensor_data.iter().map(|s| s.sensor_name.as_str()).collect::<Vec<_>>(),
+                    "marker-color": format!("#{}", event_color(&event.event_type)),
+                    "marker-size": marker_size(event.confidence),
+                    "marker-symbol": "star",
+                },
+            }))
+        })
+        .collect();
+    
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+fn style_id(event: &ParanormalEvent) -> String {
+    format!("{:?}_{}", event.event_type, marker_size(event.confidence))
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// KML document of GPS-located events
+///
+/// Events without GPS coordinates are skipped (KML is geographic only).
+pub fn to_kml(name: &str, events: &[ParanormalEvent]) -> String {
+    let mut kml = String::new();
+    kml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    kml.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n");
+    kml.push_str(&format!("  <name>{}</name>\n", escape_xml(name)));
+    
+    // One style per event type and size bucket; KML colors are aabbggrr
+    let mut styled: Vec<String> = Vec::new();
+    for event in events {
+        let id = style_id(event);
+        if styled.contains(&id) {
+            continue;
+        }
+        
+        let rgb = event_color(&event.event_type);
+        let abgr = format!("ff{}{}{}", &rgb[4..6], &rgb[2..4], &rgb[0..2]);
+        let scale = match marker_size(event.confidence) {
+            "large" => 1.4,
+            "medium" => 1.0,
+            _ => 0.7,
+        };
+        kml.push_str(&format!(
+            "  <Style id=\"{}\"><IconStyle><color>{}</color><scale>{:.1}</scale></IconStyle></Style>\n",
+            id, abgr, scale
+        ));
+        styled.push(id);
+    }
+    
+    for event in events {
+        let Some((lon, lat, true)) = position(event) else {
+            continue;
+        };
+        let location = event.location.as_ref();
+        let altitude = location.and_then(|l| l.altitude).unwrap_or(0.0);
+        
+        let sensors: Vec<&str> = event.sensor_data.iter().map(|s| s.sensor_name.as_str()).collect();
+        let description = format!(
+            "Confidence: {:.0}%<br/>Location: {}<br/>Sensors: {}",
+            event.confidence * 100.0,
+            location.map(|l| l.name.as_str()).unwrap_or(""),
+            sensors.join(", ")
+        );
+        
+        kml.push_str("  <Placemark>\n");
+        kml.push_str(&format!("    <name>{:?}</name>\n", event.event_type));
+        kml.push_str(&format!("    <description>{}</description>\n", escape_xml(&description)));
+        kml.push_str(&format!("    <TimeStamp><when>{}</when></TimeStamp>\n", timestamp(event)));
+        kml.push_str(&format!("    <styleUrl>#{}</styleUrl>\n", style_id(event)));
+        kml.push_str(&format!(
+            "    <Point><coordinates>{},{},{}</coordinates></Point>\n",
+            lon, lat, altitude
+        ));
+        kml.push_str("  </Placemark>\n");
+    }
+    
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+/// How long each event subtitle stays on screen (ms)
+const SUBTITLE_MS: i64 = 4000;
+
+/// Maximum tracks allowed in a CUE sheet
+const CUE_MAX_TRACKS: usize = 99;
+
+/// Event positions relative to the start of a media file
+///
+/// `media_start` is the wall-clock time of the first media frame; events
+/// before it are dropped.
+fn media_offsets(media_start: DateTime<Utc>, events: &[ParanormalEvent]) -> Vec<(i64, &ParanormalEvent)> {
+    let start = media_start.timestamp_millis();
+    let mut offsets: Vec<(i64, &ParanormalEvent)> = events.iter()
+        .map(|e| (epoch_ms(e.timestamp) - start, e))
+        .filter(|(t, _)| *t >= 0)
+        .collect();
+    offsets.sort_by_key(|(t, _)| *t);
+    offsets
+}
+
+fn event_title(event: &ParanormalEvent) -> String {
+    let mut title = format!("{:?} {:.0}%", event.event_type, event.confidence * 100.0);
+    if let Some(zone) = event.location.as_ref().and_then(|l| l.zone.as_ref()) {
+        title.push_str(&format!(" [{}]", zone));
+    }
+    title
+}
+
+fn srt_time(ms: i64) -> String {
+    format!("{:02}:{:02}:{:02},{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+/// SRT subtitle track with one cue per event
+pub fn to_srt(media_start: DateTime<Utc>, events: &[ParanormalEvent]) -> String {
+    let mut srt = String::new();
+    for (i, (t, event)) in media_offsets(media_start, events).into_iter().enumerate() {
+        let sensors: Vec<&str> = event.sensor_data.iter().map(|s| s.sensor_name.as_str()).collect();
+        srt.push_str(&format!("{}\n{} --> {}\n{}\n", i + 1, srt_time(t), srt_time(t + SUBTITLE_MS), event_title(event)));
+        if !sensors.is_empty() {
+            srt.push_str(&sensors.join(", "));
+            srt.push('\n');
+        }
+        srt.push('\n');
+    }
+    srt
+}
+
+/// ffmpeg FFMETADATA chapters, one per event (each lasts until the next)
+pub fn to_ffmetadata(title: &str, media_start: DateTime<Utc>, events: &[ParanormalEvent]) -> String {
+    let escape = |s: &str| {
+        s.replace('\\', "\\\\")
+            .replace('=', "\\=")
+            .replace(';', "\\;")
+            .replace('#', "\\#")
+            .replace('\n', "\\\n")
+    };
+    
+    let offsets = media_offsets(media_start, events);
+    let mut meta = format!(";FFMETADATA1\ntitle={}\n", escape(title));
+    for (i, (t, event)) in offsets.iter().enumerate() {
+        let end = offsets.get(i + 1).map(|(next, _)| *next).unwrap_or(t + SUBTITLE_MS).max(t + 1);
+        meta.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            t, end, escape(&event_title(event))
+        ));
+    }
+    meta
+}
+
+/// CUE sheet splitting an audio recording at each event
+///
+/// Track 1 covers the audio before the first event. Events sharing a CUE
+/// frame are merged, and output stops at the format's 99-track limit.
+pub fn to_cue_sheet(title: &str, audio_file: &str, media_start: DateTime<Utc>, events: &[ParanormalEvent]) -> String {
+    // CUE positions are mm:ss:ff with 75 frames per second
+    let cue_time = |ms: i64| {
+        let frames = ms * 75 / 1000;
+        format!("{:02}:{:02}:{:02}", frames / 75 / 60, frames / 75 % 60, frames % 75)
+    };
+    let quote = |s: &str| s.replace('"', "'");
+    
+    let mut cue = format!(
+        "TITLE \"{}\"\nPERFORMER \"GlowBarn\"\nFILE \"{}\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"Session start\"\n    INDEX 01 00:00:00\n",
+        quote(title), quote(audio_file)
+    );
+    
+    let mut track = 1;
+    let mut last_frame = 0;
+    for (t, event) in media_offsets(media_start, events) {
+        let frame = t * 75 / 1000;
+        if frame <= last_frame {
+            continue;
+        }
+        if track >= CUE_MAX_TRACKS {
+            tracing::warn!("CUE sheet truncated at {} tracks", CUE_MAX_TRACKS);
+            break;
+        }
+        
+        track += 1;
+        last_frame = frame;
+        cue.push_str(&format!(
+            "  TRACK {:02} AUDIO\n    TITLE \"{}\"\n    INDEX 01 {}\n",
+            track, quote(&event_title(event)), cue_time(t)
+        ));
+    }
+    cue
+}
+
+fn epoch_ms(time: std::time::SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as i64
+}
+
+/// Recover a note's time from its "[HH:MM:SS]" prefix
+fn note_time(session: &RecordingSession, note: &str) -> Option<(DateTime<Utc>, String)> {
+    let rest = note.strip_prefix('[')?;
+    let (clock, text) = rest.split_once("] ")?;
+    let clock = NaiveTime::parse_from_str(clock, "%H:%M:%S").ok()?;
+    
+    let mut time = session.start_time.date_naive().and_time(clock).and_utc();
+    if time < session.start_time - Duration::seconds(1) {
+        // Note taken after midnight
+        time += Duration::days(1);
+    }
+    Some((time, text.to_string()))
+}
+
+/// Standalone HTML timeline for a session
+///
+/// Sparklines come from recorded sensor samples; when none were recorded,
+/// the sensor values captured in each event are used instead.
+pub fn to_timeline_html(
+    session: &RecordingSession,
+    events: &[ParanormalEvent],
+    samples: &[SensorRecord],
+    significance: Option<&SignificanceReport>,
+    display: &DisplayConfig,
+) -> String {
+    let start = session.start_time.timestamp_millis();
+    let end = session.end_time
+        .map(|t| t.timestamp_millis())
+        .into_iter()
+        .chain(events.iter().map(|e| epoch_ms(e.timestamp)))
+        .chain(samples.iter().map(|s| epoch_ms(s.timestamp)))
+        .max()
+        .unwrap_or(start)
+        .max(start + 1000);
+    
+    let event_data: Vec<Value> = events.iter()
+        .map(|event| json!({
+            "id": event.id,
+            "t": epoch_ms(event.timestamp),
+            "type": format!("{:?}", event.event_type),
+            "confidence": event.confidence,
+            "color": format!("#{}", event_color(&event.event_type)),
+            "zone": event.location.as_ref().and_then(|l| l.zone.clone()),
+            "sensors": event.sensor_data.iter().map(|s| s.sensor_name.as_str()).collect::<Vec<_>>(),
+        }))
+        .collect();
+    
+    let note_data: Vec<Value> = session.notes.iter()
+        .filter_map(|note| {
+            let (time, text) = note_time(session, note)?;
+            Some(json!({
+                "t": time.timestamp_millis(),
+                "text": text,
+                "marker": text.starts_with("Mark at"),
+            }))
+        })
+        .collect();
+    
+    let annotation_data: Vec<Value> = session.annotations.iter()
+        .map(|a| json!({
+            "t": a.timestamp.timestamp_millis(),
+            "author": a.author,
+            "text": a.text,
+            "tags": a.tags,
+            "event": a.event_id,
+        }))
+        .collect();
+    
+    // Sensor series, from samples or event snapshots
+    let mut series: BTreeMap<String, (String, Vec<(i64, f64)>)> = BTreeMap::new();
+    if samples.is_empty() {
+        for event in events {
+            for snapshot in &event.sensor_data {
+                series.entry(snapshot.sensor_name.clone())
+                    .or_insert_with(|| (snapshot.unit.clone(), Vec::new()))
+                    .1.push((epoch_ms(event.timestamp), snapshot.value));
+            }
+        }
+    } else {
+        for sample in samples {
+            series.entry(sample.sensor_name.clone())
+                .or_insert_with(|| (sample.unit.clone(), Vec::new()))
+                .1.push((epoch_ms(sample.timestamp), sample.value));
+        }
+    }
+    
+    let sensor_data: Vec<Value> = series.into_iter()
+        .map(|(name, (unit, points))| {
+            let stride = points.len().div_ceil(SPARKLINE_POINTS).max(1);
+            let points: Vec<(i64, f64)> = points.into_iter()
+                .step_by(stride)
+                .map(|(t, value)| (t, display.value(value, &unit).0))
+                .collect();
+            json!({ "name": name, "unit": display.value(0.0, &unit).1, "points": points })
+        })
+        .collect();
+    
+    let significance = significance.map(|report| {
+        let mut report = report.clone();
+        for c in &mut report.comparisons {
+            let (investigation, unit) = display.value(c.investigation, &c.unit);
+            c.investigation = investigation;
+            c.control = display.value(c.control, &c.unit).0;
+            c.unit = unit;
+        }
+        report
+    });
+    
+    let data = json!({
+        "name": session.name,
+        "location": session.location,
+        "start": start,
+        "end": end,
+        "events": event_data,
+        "notes": note_data,
+        "annotations": annotation_data,
+        "sensors": sensor_data,
+        "significance": significance,
+        "display": {
+            "locale": display.locale(),
+            "utc": display.utc,
+            "hour12": display.hour12(),
+        },
+    });
+    
+    // Keep "</script>" inside strings from closing the data block
+    let data = data.to_string().replace("</", "<\\/");
+    
+    TIMELINE_TEMPLATE
+        .replace("{{title}}", &escape_xml(&session.name))
+        .replace("{{data}}", &data)
+}
+
+const TIMELINE_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{title}} - GlowBarn timeline</title>
+<style>
+body { font-family: sans-serif; background: #111; color: #ddd; margin: 20px; }
+h1 { font-size: 1.3em; margin: 0 0 4px; }
+.meta { color: #888; margin-bottom: 16px; }
+svg { display: block; width: 100%; }
+.label { fill: #aaa; font-size: 11px; }
+.axis { stroke: #444; }
+#detail { min-height: 1.5em; margin: 8px 0; color: #fff; }
+#thread div { margin: 3px 0; }
+#thread .reply { margin-left: 24px; color: #ccc; }
+#thread .tag { color: #8cf; }
+#significance table { border-collapse: collapse; margin-top: 6px; }
+#significance td, #significance th { padding: 2px 10px; text-align: right; }
+#significance td:first-child, #significance th:first-child { text-align: left; }
+#significance .low { color: #fc6; }
+</style>
+</head>
+<body>
+<h1>{{title}}</h1>
+<div class="meta" id="meta"></div>
+<div id="detail">Hover an event or note for details</div>
+<svg id="timeline" height="160"></svg>
+<div id="sparklines"></div>
+<div id="thread"></div>
+<div id="significance"></div>
+<script id="data" type="application/json">{{data}}</script>
+<script>
+const d = JSON.parse(document.getElementById('data').textContent);
+const NS = 'http://www.w3.org/2000/svg';
+const W = 1000, L = 150;
+const x = t => L + (t - d.start) / (d.end - d.start) * (W - L - 10);
+const opts = {hour12: d.display.hour12, timeZone: d.display.utc ? 'UTC' : undefined};
+const locale = d.display.locale || undefined;
+const fmt = t => new Date(t).toLocaleTimeString(locale, opts);
+const day = t => new Date(t).toLocaleString(locale, opts);
+function el(parent, name, attrs, text) {
+  const e = document.createElementNS(NS, name);
+  for (const k in attrs) e.setAttribute(k, attrs[k]);
+  if (text !== undefined) e.textContent = text;
+  parent.appendChild(e);
+  return e;
+}
+function hover(e, text) {
+  e.addEventListener('mouseover', () => document.getElementById('detail').textContent = text);
+  el(e, 'title', {}, text);
+}
+document.getElementById('meta').textContent =
+  `${d.location} - ${day(d.start)} to ${day(d.end)} - ${d.events.length} events`;
+const tl = document.getElementById('timeline');
+tl.setAttribute('viewBox', `0 0 ${W} 160`);
+el(tl, 'line', {x1: L, x2: W - 10, y1: 130, y2: 130, class: 'axis'});
+for (let i = 0; i <= 4; i++) {
+  const t = d.start + (d.end - d.start) * i / 4;
+  el(tl, 'text', {x: x(t), y: 148, class: 'label', 'text-anchor': 'middle'}, fmt(t));
+}
+el(tl, 'text', {x: 0, y: 70, class: 'label'}, 'events (by confidence)');
+for (const n of d.notes) {
+  const line = el(tl, 'line', {x1: x(n.t), x2: x(n.t), y1: 10, y2: 130,
+    stroke: n.marker ? '#ff0' : '#888', 'stroke-dasharray': n.marker ? '' : '3,3'});
+  hover(line, `${fmt(n.t)} ${n.text}`);
+}
+const said = a => `${fmt(a.t)} ${a.author}: ${a.text}` + a.tags.map(t => ` #${t}`).join('');
+for (const a of d.annotations.filter(a => !a.event)) {
+  const line = el(tl, 'line', {x1: x(a.t), x2: x(a.t), y1: 10, y2: 130, stroke: '#c8f', 'stroke-dasharray': '1,2'});
+  hover(line, said(a));
+}
+for (const e of d.events) {
+  const replies = d.annotations.filter(a => a.event === e.id);
+  const c = el(tl, 'circle', {cx: x(e.t), cy: 125 - e.confidence * 110, r: 4 + e.confidence * 4, fill: e.color, opacity: 0.85,
+    stroke: replies.length ? '#c8f' : 'none'});
+  hover(c, `${fmt(e.t)} ${e.type} ${(e.confidence * 100).toFixed(0)}%` +
+    (e.zone ? ` [${e.zone}]` : '') + (e.sensors.length ? ` - ${e.sensors.join(', ')}` : '') +
+    replies.map(a => ` | ${a.author}: ${a.text}`).join(''));
+}
+function row(parent, cls, text, tags) {
+  const div = document.createElement('div');
+  div.className = cls;
+  div.textContent = text;
+  for (const t of tags || []) {
+    const span = document.createElement('span');
+    span.className = 'tag';
+    span.textContent = ` #${t}`;
+    div.appendChild(span);
+  }
+  parent.appendChild(div);
+}
+const thread = document.getElementById('thread');
+const items = d.annotations.filter(a => !a.event).map(a => ({t: a.t, annotation: a}))
+  .concat(d.events.filter(e => d.annotations.some(a => a.event === e.id)).map(e => ({t: e.t, event: e})))
+  .sort((a, b) => a.t - b.t);
+if (items.length) row(thread, '', `Annotations (${d.annotations.length})`);
+for (const item of items) {
+  if (item.annotation) {
+    row(thread, '', `${fmt(item.t)} ${item.annotation.author}: ${item.annotation.text}`, item.annotation.tags);
+    continue;
+  }
+  const e = item.event;
+  row(thread, '', `${fmt(e.t)} ${e.type} ${(e.confidence * 100).toFixed(0)}%`);
+  for (const a of d.annotations.filter(a => a.event === e.id)) {
+    row(thread, 'reply', `${fmt(a.t)} ${a.author}: ${a.text}`, a.tags);
+  }
+}
+const sp = document.getElementById('sparklines');
+for (const s of d.sensors) {
+  const svg = el(sp, 'svg', {viewBox: `0 0 ${W} 40`, height: 40});
+  const vals = s.points.map(p => p[1]);
+  const lo = Math.min(...vals), hi = Math.max(...vals), span = (hi - lo) || 1;
+  el(svg, 'text', {x: 0, y: 16, class: 'label'}, s.name);
+  el(svg, 'text', {x: 0, y: 30, class: 'label'}, `${lo.toFixed(2)}-${hi.toFixed(2)} ${s.unit}`);
+  el(svg, 'polyline', {fill: 'none', stroke: '#6cf', 'stroke-width': 1.2,
+    points: s.points.map(p => `${x(p[0])},${36 - (p[1] - lo) / span * 32}`).join(' ')});
+}
+const sig = d.significance;
+if (sig) {
+  const div = document.getElementById('significance');
+  row(div, '', `Compared with ${day(sig.control.start)} to ${day(sig.control.end)} ` +
+    `(${sig.investigation.bins} vs ${sig.control.bins} bins of ${sig.bin_minutes} min, ` +
+    `${sig.permutations} permutations; p adjusted by Holm for ${sig.comparisons.length} tests)`);
+  const table = document.createElement('table');
+  const tr = (cells, tag, cls) => {
+    const r = table.insertRow();
+    if (cls) r.className = cls;
+    for (const c of cells) {
+      const cell = document.createElement(tag);
+      cell.textContent = c;
+      r.appendChild(cell);
+    }
+  };
+  tr(['', 'session', 'control', '', 'p', 'p adjusted'], 'th');
+  for (const c of sig.comparisons) {
+    tr([c.subject, c.investigation.toFixed(2), c.control.toFixed(2), c.unit,
+      c.p_value.toFixed(4), c.p_adjusted.toFixed(4)], 'td', c.p_adjusted < 0.05 ? 'low' : '');
+  }
+  div.appendChild(table);
+}
+</script>
+</body>
+</html>
+"#;
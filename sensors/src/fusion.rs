@@ -3,15 +3,29 @@
 //! Combines multiple sensor inputs using statistical methods
 //! to improve detection accuracy and reduce false positives.
 
-use crate::{EventType, ParanormalEvent, SensorSnapshot, Result};
-use glowbarn_hal::SensorReading;
-use std::collections::HashMap;
+use crate::{EventType, ParanormalEvent, SensorHistory, SensorSnapshot, Result};
+use crate::anomaly::{self, Onset, RateConfig};
+use crate::bands::{BandConfig, BandPlan};
+use crate::baseline::{BaselineConfig, BaselineMode, DiurnalBaseline};
+use crate::persistence::PersistenceConfig;
+use crate::kalman::{FusionMode, KalmanConfig, KalmanFusion};
+use crate::heatmap::{HeatmapConfig, MotionHeatmap};
+use crate::hum::{self, HumConfig, HumLevel};
+use crate::live::{LiveReading, LiveReadings};
+use crate::pressure::{self, PressureConfig};
+use glowbarn_dsp::{self as dsp, stats, SortedWindow};
+use glowbarn_hal::{BreakAnalysis, PassDirection, ReadingPayload, SensorReading};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc;
 
+/// MAD of a normal distribution per standard deviation
+const MAD_SCALE: f64 = 1.4826;
+
 /// Baseline statistics for a sensor
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SensorBaseline {
     pub name: String,
     pub mean: f64,
@@ -20,6 +34,31 @@ pub struct SensorBaseline {
     pub max: f64,
     pub sample_count: usize,
     pub last_calibration: SystemTime,
+    pub mode: BaselineMode,
+    /// Median of the recent window (quantile and robust modes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median: Option<f64>,
+    /// Median absolute deviation of the recent window (robust mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mad: Option<f64>,
+    /// Recent samples (quantile and robust modes)
+    #[serde(skip)]
+    window: Option<SortedWindow>,
+    /// Normal quantile to z-score factor (quantile mode)
+    #[serde(skip)]
+    quantile_scale: f64,
+    /// Local hour the latest sample was scored against (diurnal mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour: Option<usize>,
+    /// Mean scores are taken against in that hour (diurnal mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour_mean: Option<f64>,
+    /// Standard deviation scores are taken against in that hour (diurnal mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour_std_dev: Option<f64>,
+    /// Per-hour statistics (diurnal mode)
+    #[serde(skip)]
+    diurnal: Option<DiurnalBaseline>,
 }
 
 impl SensorBaseline {
@@ -32,11 +71,90 @@ impl SensorBaseline {
             max: f64::MIN,
             sample_count: 0,
             last_calibration: SystemTime::now(),
+            mode: BaselineMode::Gaussian,
+            median: None,
+            mad: None,
+            window: None,
+            quantile_scale: 1.0,
+            hour: None,
+            hour_mean: None,
+            hour_std_dev: None,
+            diurnal: None,
         }
     }
     
+    /// Score deviations by percentile rank among the last `window` samples
+    ///
+    /// See [`BaselineConfig::quantile_scale`] for `scale`.
+    pub fn with_quantiles(mut self, window: usize, scale: f64) -> Self {
+        self.mode = BaselineMode::Quantile;
+        self.window = Some(SortedWindow::new(window));
+        self.quantile_scale = scale;
+        self
+    }
+    
+    /// Score deviations from the median of the last `window` samples in
+    /// units of their MAD, scaled to match standard deviations for normal data
+    pub fn with_robust(mut self, window: usize) -> Self {
+        self.mode = BaselineMode::Robust;
+        self.window = Some(SortedWindow::new(window));
+        self
+    }
+    
+    /// Score deviations against a decaying mean kept per hour of the day,
+    /// forgetting samples with `half_life`
+    pub fn with_diurnal(mut self, half_life: Duration) -> Self {
+        self.mode = BaselineMode::Diurnal;
+        self.diurnal = Some(DiurnalBaseline::new(half_life));
+        self
+    }
+    
+    /// Empty baseline of the same mode
+    pub fn fresh(&self) -> Self {
+        let mut baseline = Self::new(&self.name);
+        if let Some(diurnal) = &self.diurnal {
+            baseline = baseline.with_diurnal(diurnal.half_life());
+        }
+        if let Some(window) = &self.window {
+            let mut window = window.clone();
+            window.clear();
+            baseline.mode = self.mode;
+            baseline.window = Some(window);
+            baseline.quantile_scale = self.quantile_scale;
+        }
+        baseline
+    }
+    
+    /// Typical value: the median in quantile and robust modes, the mean of
+    /// the current hour in diurnal mode, otherwise the mean
+    pub fn center(&self) -> f64 {
+        self.median
+            .or_else(|| self.diurnal.as_ref().map(|diurnal| diurnal.current().0))
+            .unwrap_or(self.mean)
+    }
+    
     /// Update baseline with new sample
     pub fn update(&mut self, value: f64) {
+        self.update_at(value, SystemTime::now());
+    }
+    
+    /// Update baseline with a sample taken at `time`
+    pub fn update_at(&mut self, value: f64, time: SystemTime) {
+        if let Some(diurnal) = &mut self.diurnal {
+            diurnal.push(value, time);
+            let (mean, std_dev) = diurnal.current();
+            self.hour = Some(diurnal.hour());
+            self.hour_mean = Some(mean);
+            self.hour_std_dev = Some(std_dev);
+        }
+        if let Some(window) = &mut self.window {
+            window.push(value);
+            self.median = Some(window.median());
+            if self.mode == BaselineMode::Robust {
+                self.mad = Some(window.mad());
+            }
+        }
+        
         self.sample_count += 1;
         self.min = self.min.min(value);
         self.max = self.max.max(value);
@@ -54,11 +172,37 @@ impl SensorBaseline {
     }
     
     /// Calculate z-score for a value
+    ///
+    /// In quantile mode this is the normal quantile of the value's
+    /// percentile rank, scaled so the configured percentile scores the
+    /// anomaly threshold. In robust mode it is the distance from the median
+    /// in scaled MADs, falling back to the standard deviation while more
+    /// than half the recent samples are identical. In diurnal mode it is
+    /// the distance from the current hour's mean in its standard deviations.
     pub fn z_score(&self, value: f64) -> f64 {
+        if let Some(diurnal) = &self.diurnal {
+            let (mean, std_dev) = diurnal.current();
+            return if std_dev > 0.0 { (value - mean) / std_dev } else { 0.0 };
+        }
+        
+        match (&self.window, self.mode) {
+            (Some(window), _) if window.is_empty() => return 0.0,
+            (Some(window), BaselineMode::Quantile) => {
+                return stats::normal_quantile(window.rank(value)) * self.quantile_scale;
+            }
+            (Some(_), BaselineMode::Robust) => {
+                let spread = self.mad.unwrap_or(0.0) * MAD_SCALE;
+                if spread > 0.0 {
+                    return (value - self.center()) / spread;
+                }
+            }
+            _ => {}
+        }
+        
         if self.std_dev == 0.0 {
             return 0.0;
         }
-        (value - self.mean) / self.std_dev
+        (value - self.center()) / self.std_dev
     }
     
     /// Check if value is anomalous (beyond n standard deviations)
@@ -78,6 +222,26 @@ pub struct FusionConfig {
     pub correlation_window_ms: u64,
     /// Minimum confidence for event reporting
     pub min_confidence: f64,
+    /// Seconds of lead-up readings attached to each event (0 disables)
+    pub history_secs: u64,
+    /// Mains hum tracking on waveform channels
+    pub hum: HumConfig,
+    /// Barometric pressure trend detection
+    pub pressure: PressureConfig,
+    /// Band plan used to label RF anomalies
+    pub bands: BandConfig,
+    /// Per-camera motion heatmaps
+    pub heatmap: HeatmapConfig,
+    /// Per-sensor baseline modes (applied to new and reset baselines)
+    pub baseline: BaselineConfig,
+    /// How long anomalies must persist before they are reported
+    pub persistence: PersistenceConfig,
+    /// Rate-of-change thresholds telling sudden anomalies from drifts
+    pub rate: RateConfig,
+    /// How an anomaly's confidence is rated
+    pub mode: FusionMode,
+    /// Sensor groups and noise levels of Kalman mode
+    pub kalman: KalmanConfig,
     /// Weight factors for different sensor types
     pub sensor_weights: HashMap<String, f64>,
 }
@@ -90,22 +254,159 @@ impl Default for FusionConfig {
         weights.insert("audio".to_string(), 1.0);
         weights.insert("motion".to_string(), 0.8);
         weights.insert("infrared".to_string(), 1.3);
+        weights.insert("vibration".to_string(), 0.9);
         
         Self {
             anomaly_threshold: 2.5,  // 2.5 standard deviations
             min_baseline_samples: 100,
             correlation_window_ms: 5000,  // 5 second window
             min_confidence: 0.4,
+            history_secs: 10,
+            hum: HumConfig::default(),
+            pressure: PressureConfig::default(),
+            bands: BandConfig::default(),
+            heatmap: HeatmapConfig::default(),
+            baseline: BaselineConfig::default(),
+            persistence: PersistenceConfig::default(),
+            rate: RateConfig::default(),
+            mode: FusionMode::default(),
+            kalman: KalmanConfig::default(),
             sensor_weights: weights,
         }
     }
 }
 
+/// Deviation of one payload feature (vector axis, audio crest factor,
+/// spectral peak, frame motion) from its baseline
+#[derive(Debug, Clone)]
+struct FeatureDeviation {
+    name: String,
+    value: f64,
+    mean: f64,
+    z_score: f64,
+}
+
+/// Latest hum measurement of a waveform channel
+#[derive(Debug, Clone)]
+struct HumState {
+    level: HumLevel,
+    time: SystemTime,
+    z_score: f64,
+    /// Diagnostic `<sensor>.hum` reading
+    reading: SensorReading,
+}
+
+/// A sensor's current run of anomalous samples
+#[derive(Debug, Clone, Copy)]
+struct AnomalyRun {
+    samples: u32,
+    since: SystemTime,
+    /// Rate of change at the first sample, for sensors with a rate threshold
+    rate: Option<f64>,
+}
+
+/// Samples and sample rate of an audio or waveform payload
+fn waveform_samples(payload: &ReadingPayload) -> Option<(Vec<f64>, f64)> {
+    match payload {
+        ReadingPayload::Audio { sample_rate, samples } => Some((dsp::pcm_to_f64(samples), *sample_rate as f64)),
+        ReadingPayload::Waveform { sample_rate, samples } => Some((samples.to_vec(), *sample_rate)),
+        _ => None,
+    }
+}
+
+/// `crest` and `zcr` of a waveform
+fn waveform_features(samples: &[f64], sample_rate: f64) -> Vec<(String, f64)> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    vec![
+        ("crest".to_string(), stats::crest_factor(samples)),
+        ("zcr".to_string(), stats::zero_crossing_rate(samples, sample_rate.max(1.0))),
+    ]
+}
+
+/// Modality-specific features of a reading's payload
+///
+/// - vector: each axis
+/// - audio and waveform: `crest` (peak / RMS, high for knocks and clicks)
+///   and `zcr` (zero-crossing rate in Hz, shifts with voice-band content)
+/// - spectrum: `peak_snr` (strongest bin above the median, in dB)
+/// - frame: `motion` (mean absolute luma change from `previous_frame`)
+///
+/// Channels of multi-channel readings are split off as readings of their
+/// own before they get here, and baselined under their own names.
+fn payload_features(payload: &ReadingPayload, previous_frame: Option<&[u8]>) -> Vec<(String, f64)> {
+    match payload {
+        ReadingPayload::Scalar | ReadingPayload::Channels(_) => Vec::new(),
+        ReadingPayload::Vector(components) => components.clone(),
+        ReadingPayload::Audio { .. } | ReadingPayload::Waveform { .. } => match waveform_samples(payload) {
+            Some((samples, sample_rate)) => waveform_features(&samples, sample_rate),
+            None => Vec::new(),
+        },
+        ReadingPayload::Spectrum { bins, .. } => {
+            if bins.is_empty() {
+                return Vec::new();
+            }
+            let peak = bins.iter().cloned().fold(f64::MIN, f64::max);
+            vec![("peak_snr".to_string(), peak - stats::median(bins))]
+        }
+        ReadingPayload::Frame { luma, .. } => match previous_frame {
+            Some(previous) if previous.len() == luma.len() && !luma.is_empty() => {
+                let diff: u64 = luma.iter().zip(previous)
+                    .map(|(&a, &b)| (a as i16 - b as i16).unsigned_abs() as u64)
+                    .sum();
+                vec![("motion".to_string(), diff as f64 / luma.len() as f64)]
+            }
+            _ => Vec::new(),
+        },
+    }
+}
+
+/// Frequency of the strongest bin of a spectrum
+fn spectrum_peak_hz(payload: &ReadingPayload) -> Option<f64> {
+    let ReadingPayload::Spectrum { start_hz, bin_hz, bins } = payload else {
+        return None;
+    };
+    let (index, _) = bins.iter().enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    Some(start_hz + (index as f64 + 0.5) * bin_hz)
+}
+
+/// Angle between the current vector and the baseline mean vector
+fn rotation_degrees(axes: &[FeatureDeviation]) -> f64 {
+    let dot: f64 = axes.iter().map(|a| a.value * a.mean).sum();
+    let current: f64 = axes.iter().map(|a| a.value * a.value).sum::<f64>().sqrt();
+    let mean: f64 = axes.iter().map(|a| a.mean * a.mean).sum::<f64>().sqrt();
+    
+    if current == 0.0 || mean == 0.0 {
+        return 0.0;
+    }
+    (dot / (current * mean)).clamp(-1.0, 1.0).acos().to_degrees()
+}
+
 /// Sensor Fusion Engine
 pub struct FusionEngine {
     config: FusionConfig,
+    band_plan: BandPlan,
     baselines: Arc<RwLock<HashMap<String, SensorBaseline>>>,
+    /// Baselines of payload features, keyed `sensor.feature`
+    feature_baselines: Arc<RwLock<HashMap<String, SensorBaseline>>>,
+    /// Last frame per camera, for motion features
+    previous_frames: Arc<RwLock<HashMap<String, Arc<[u8]>>>>,
+    /// Accumulated motion per camera
+    heatmaps: Arc<RwLock<HashMap<String, MotionHeatmap>>>,
     recent_readings: Arc<RwLock<Vec<(SystemTime, SensorReading)>>>,
+    contact_states: Arc<RwLock<HashMap<String, f64>>>,
+    /// Latest hum per waveform channel
+    hum_levels: Arc<RwLock<HashMap<String, HumState>>>,
+    /// Pressure sensors currently in a reported fast-change excursion
+    pressure_excursions: Arc<RwLock<HashSet<String>>>,
+    /// Anomalies waiting to persist long enough to be reported
+    anomaly_runs: Arc<RwLock<HashMap<String, AnomalyRun>>>,
+    /// Activity estimates per sensor group (Kalman mode)
+    kalman: Arc<RwLock<KalmanFusion>>,
+    /// Latest value per sensor
+    live: LiveReadings,
     event_tx: mpsc::Sender<ParanormalEvent>,
 }
 
@@ -115,15 +416,62 @@ impl FusionEngine {
         let (tx, rx) = mpsc::channel(100);
         
         (Self {
+            band_plan: BandPlan::new(&config.bands),
+            kalman: Arc::new(RwLock::new(KalmanFusion::new(config.kalman.clone()))),
             config,
             baselines: Arc::new(RwLock::new(HashMap::new())),
+            feature_baselines: Arc::new(RwLock::new(HashMap::new())),
+            previous_frames: Arc::new(RwLock::new(HashMap::new())),
+            heatmaps: Arc::new(RwLock::new(HashMap::new())),
             recent_readings: Arc::new(RwLock::new(Vec::new())),
+            contact_states: Arc::new(RwLock::new(HashMap::new())),
+            hum_levels: Arc::new(RwLock::new(HashMap::new())),
+            pressure_excursions: Arc::new(RwLock::new(HashSet::new())),
+            anomaly_runs: Arc::new(RwLock::new(HashMap::new())),
+            live: LiveReadings::new(),
             event_tx: tx,
         }, rx)
     }
     
     /// Process incoming sensor reading
+    #[tracing::instrument(level = "debug", name = "fusion", skip_all, fields(sensor = %reading.sensor_name, event_id))]
     pub async fn process_reading(&self, reading: SensorReading) -> Result<Option<ParanormalEvent>> {
+        let latest = reading.clone();
+        let result = self.evaluate(reading).await;
+        
+        let baseline = self.valid_baseline(&self.baselines, &latest.sensor_name);
+        self.live.publish(LiveReading {
+            sensor_type: self.reading_type(&latest),
+            reading: latest,
+            baseline,
+        });
+        
+        result
+    }
+    
+    /// Baseline `key` from `baselines`, once it has enough samples
+    fn valid_baseline(&self, baselines: &RwLock<HashMap<String, SensorBaseline>>, key: &str) -> Option<SensorBaseline> {
+        baselines.read().unwrap()
+            .get(key)
+            .filter(|b| b.sample_count >= self.config.min_baseline_samples)
+            .cloned()
+    }
+    
+    /// Empty baseline for a sensor, in the mode configured for it
+    fn new_baseline(&self, sensor_name: &str, sensor_type: &str) -> SensorBaseline {
+        let baseline = SensorBaseline::new(sensor_name);
+        match self.config.baseline.mode_for(sensor_name, sensor_type) {
+            BaselineMode::Gaussian => baseline,
+            BaselineMode::Quantile => baseline.with_quantiles(
+                self.config.baseline.window,
+                self.config.baseline.quantile_scale(self.config.anomaly_threshold),
+            ),
+            BaselineMode::Robust => baseline.with_robust(self.config.baseline.window),
+            BaselineMode::Diurnal => baseline.with_diurnal(self.config.baseline.half_life()),
+        }
+    }
+    
+    async fn evaluate(&self, reading: SensorReading) -> Result<Option<ParanormalEvent>> {
         let now = SystemTime::now();
         
         // Store reading for correlation analysis
@@ -131,21 +479,37 @@ impl FusionEngine {
             let mut recent = self.recent_readings.write().unwrap();
             recent.push((now, reading.clone()));
             
-            // Prune old readings
-            let cutoff = now - Duration::from_millis(self.config.correlation_window_ms * 2);
+            // Prune readings no longer needed for correlation or history
+            let keep = Duration::from_millis(self.config.correlation_window_ms * 2)
+                .max(Duration::from_secs(self.config.history_secs))
+                .max(Duration::from_secs(self.config.pressure.window_secs))
+                .max(self.config.rate.window());
+            let cutoff = now - keep;
             recent.retain(|(t, _)| *t > cutoff);
         }
         
+        // Contacts are binary: report state changes rather than deviations
+        if self.get_sensor_type(&reading.sensor_name) == "contact" {
+            return self.process_contact(reading, now).await;
+        }
+        
+        // Pressure drifts with the weather: report fast trends, not levels
+        if self.reading_type(&reading) == "pressure" {
+            return self.process_pressure(reading, now).await;
+        }
+        
         // Update baseline
         let is_baseline_valid = {
             let mut baselines = self.baselines.write().unwrap();
             let baseline = baselines
                 .entry(reading.sensor_name.clone())
-                .or_insert_with(|| SensorBaseline::new(&reading.sensor_name));
+                .or_insert_with(|| self.new_baseline(&reading.sensor_name, &self.reading_type(&reading)));
             
-            baseline.update(reading.value);
+            baseline.update_at(reading.value, reading.timestamp);
             baseline.sample_count >= self.config.min_baseline_samples
         };
+        let hum = self.track_hum(&reading, now);
+        let features = self.update_features(&reading, hum.as_ref());
         
         // Skip anomaly detection during baseline collection
         if !is_baseline_valid {
@@ -165,60 +529,528 @@ impl FusionEngine {
             (baseline.z_score(reading.value), baseline.clone())
         };
         
+        // Feature deviations catch changes the summary value hides, e.g. field
+        // rotation without a magnitude change or a knock in steady noise
+        let strongest = features.iter()
+            .max_by(|a, b| a.z_score.abs().total_cmp(&b.z_score.abs()))
+            .filter(|feature| feature.z_score.abs() > z_score.abs());
+        let z_score = strongest.map(|feature| feature.z_score).unwrap_or(z_score);
+        
+        // Every reading, anomalous or not, moves its group's activity estimate
+        let estimate = match self.config.mode {
+            FusionMode::Kalman => Some(self.kalman.write().unwrap().observe(
+                &reading.sensor_name,
+                z_score,
+                self.sensor_weight(&reading) * reading.quality as f64,
+                now,
+            )),
+            FusionMode::Threshold => None,
+        };
+        
         if z_score.abs() <= self.config.anomaly_threshold {
+            self.anomaly_runs.write().unwrap().remove(&reading.sensor_name);
             return Ok(None);
         }
         
-        // Anomaly detected - calculate confidence
-        let base_confidence = self.calculate_confidence(z_score);
+        // Glitch filter: wait until the anomaly has lasted long enough
+        let persistence = self.config.persistence.for_sensor(&reading.sensor_name, &self.reading_type(&reading));
+        let rate_threshold = self.config.rate.threshold_for(&reading.sensor_name, &self.reading_type(&reading));
+        let run = {
+            let mut runs = self.anomaly_runs.write().unwrap();
+            let run = runs.entry(reading.sensor_name.clone())
+                .or_insert_with(|| AnomalyRun {
+                    samples: 0,
+                    since: now,
+                    rate: rate_threshold.and_then(|_| self.onset_rate(&reading, now)),
+                });
+            run.samples += 1;
+            *run
+        };
+        let elapsed = now.duration_since(run.since).unwrap_or_default();
+        if !persistence.satisfied(run.samples, elapsed) {
+            tracing::debug!(z_score, samples = run.samples, "Anomaly not yet persistent");
+            return Ok(None);
+        }
         
         // Check for correlated events
         let correlated = self.find_correlated_anomalies(&reading.sensor_name, now);
-        let correlation_boost = correlated.len() as f64 * 0.1;
         
-        let final_confidence = (base_confidence + correlation_boost).min(0.99);
+        // Anomaly detected - calculate confidence: the posterior probability
+        // that the group is active, else the z-score plus a correlation bonus
+        let mut final_confidence = match &estimate {
+            Some(estimate) => estimate.probability_above(self.config.anomaly_threshold).min(0.99),
+            None => {
+                let base_confidence = self.calculate_confidence(z_score);
+                let correlation_boost = correlated.len() as f64 * 0.1;
+                (base_confidence + correlation_boost).min(0.99)
+            }
+        };
+        
+        // A spike is likelier an event than a slow drift to the same level
+        let onset = rate_threshold.zip(run.rate).map(|(threshold, rate)| (Onset::of(rate, threshold), rate));
+        if let Some((onset, _)) = onset {
+            final_confidence = self.config.rate.adjust(final_confidence, onset);
+        }
+        
+        // Wiring noise: discount anomalies that coincide with a hum surge
+        let surge = self.hum_surge(&reading, now);
+        if surge.is_some() {
+            final_confidence *= self.config.hum.penalty;
+        }
         
         if final_confidence < self.config.min_confidence {
+            tracing::debug!(z_score, confidence = final_confidence, hum_correlated = surge.is_some(), onset = ?onset, "Anomaly below minimum confidence");
             return Ok(None);
         }
         
         // Determine event type
         let event_type = self.classify_event(&reading, &correlated);
+        let rf_peak_hz = match event_type {
+            EventType::RfAnomaly => spectrum_peak_hz(&reading.payload),
+            _ => None,
+        };
         
         // Create event
         let mut event = ParanormalEvent::new(event_type, final_confidence)
             .with_sensor_data(SensorSnapshot {
                 sensor_name: reading.sensor_name.clone(),
-                sensor_type: self.get_sensor_type(&reading.sensor_name),
+                sensor_type: self.reading_type(&reading),
                 value: reading.value,
-                unit: reading.unit,
-                baseline: Some(baseline.mean),
+                unit: reading.unit.clone(),
+                baseline: Some(baseline.center()),
                 deviation: Some(z_score),
+                components: reading.components().to_vec(),
             })
             .with_metadata("z_score", &format!("{:.2}", z_score))
             .with_metadata("correlated_sensors", &format!("{}", correlated.len()));
         
+        if let Some(estimate) = &estimate {
+            event = event
+                .with_metadata("fusion_group", self.kalman.read().unwrap().group_of(&reading.sensor_name))
+                .with_metadata("group_activity", &format!("{:.2}", estimate.activity))
+                .with_metadata("group_activity_sd", &format!("{:.2}", estimate.variance.sqrt()));
+        }
+        
+        if !persistence.is_immediate() {
+            event = event
+                .with_metadata("anomaly_samples", &run.samples.to_string())
+                .with_metadata("anomaly_secs", &format!("{:.1}", elapsed.as_secs_f64()));
+        }
+        
+        if let Some((onset, rate)) = onset {
+            event = event
+                .with_metadata("onset", &onset.to_string())
+                .with_metadata("onset_rate_per_sec", &format!("{:+.3}", rate));
+        }
+        
+        if let Some(feature) = strongest {
+            if let ReadingPayload::Vector(_) = reading.payload {
+                event = event
+                    .with_metadata("axis", &feature.name)
+                    .with_metadata("rotation_deg", &format!("{:.1}", rotation_degrees(&features)));
+            } else {
+                event = event
+                    .with_metadata("feature", &feature.name)
+                    .with_metadata("feature_value", &format!("{:.3}", feature.value));
+            }
+        }
+        
+        if let Some((sensor, state)) = surge {
+            event = event
+                .with_metadata("hum_correlated", "true")
+                .with_metadata("hum_sensor", &sensor)
+                .with_metadata("hum_hz", &format!("{:.0}", state.level.mains_hz))
+                .with_metadata("hum_ratio", &format!("{:.2}", state.level.ratio));
+        }
+        
+        // Name the service that normally transmits there, so reviewers can
+        // dismiss pagers and dispatch radio
+        if let Some(peak_hz) = rf_peak_hz {
+            event = event.with_metadata("peak_mhz", &format!("{:.3}", peak_hz / 1e6));
+            if let Some(band) = self.band_plan.classify(peak_hz) {
+                event = event
+                    .with_metadata("rf_band", &band.name)
+                    .with_metadata("rf_service", &band.service.to_string());
+            }
+        }
+        
         // Add correlated sensor data
         for (_, corr_reading) in correlated {
             let corr_baselines = self.baselines.read().unwrap();
             if let Some(corr_baseline) = corr_baselines.get(&corr_reading.sensor_name) {
                 event = event.with_sensor_data(SensorSnapshot {
                     sensor_name: corr_reading.sensor_name.clone(),
-                    sensor_type: self.get_sensor_type(&corr_reading.sensor_name),
+                    sensor_type: self.reading_type(&corr_reading),
                     value: corr_reading.value,
-                    unit: corr_reading.unit,
-                    baseline: Some(corr_baseline.mean),
+                    unit: corr_reading.unit.clone(),
+                    baseline: Some(corr_baseline.center()),
                     deviation: Some(corr_baseline.z_score(corr_reading.value)),
+                    components: corr_reading.components().to_vec(),
                 });
             }
         }
         
+        event.history = self.history(&event, now);
+        tracing::Span::current().record("event_id", event.id.as_str());
+        tracing::debug!(
+            z_score,
+            confidence = final_confidence,
+            correlated = event.sensor_data.len() - 1,
+            "Anomaly reported as {:?}",
+            event.event_type
+        );
+        
         // Send event
         let _ = self.event_tx.send(event.clone()).await;
         
         Ok(Some(event))
     }
     
+    /// Readings from the last `history_secs` for each sensor in the event
+    fn history(&self, event: &ParanormalEvent, now: SystemTime) -> Vec<SensorHistory> {
+        if self.config.history_secs == 0 {
+            return Vec::new();
+        }
+        
+        let window = Duration::from_secs(self.config.history_secs);
+        let recent = self.recent_readings.read().unwrap();
+        let mut history: Vec<SensorHistory> = Vec::new();
+        
+        for snapshot in &event.sensor_data {
+            if history.iter().any(|h| h.sensor_name == snapshot.sensor_name) {
+                continue;
+            }
+            
+            let samples = recent.iter()
+                .filter(|(_, r)| r.sensor_name == snapshot.sensor_name)
+                .filter_map(|(t, r)| {
+                    let age = now.duration_since(*t).ok().filter(|age| *age <= window)?;
+                    Some((-age.as_secs_f64(), r.value))
+                })
+                .collect();
+            
+            history.push(SensorHistory {
+                sensor_name: snapshot.sensor_name.clone(),
+                unit: snapshot.unit.clone(),
+                samples,
+            });
+        }
+        
+        history
+    }
+    
+    /// Measure mains hum on a waveform reading and update its baseline
+    fn track_hum(&self, reading: &SensorReading, now: SystemTime) -> Option<HumLevel> {
+        if !self.config.hum.enabled {
+            return None;
+        }
+        let (samples, sample_rate) = waveform_samples(&reading.payload)?;
+        if samples.is_empty() {
+            return None;
+        }
+        
+        let mains_hz = self.config.hum.mains_hz
+            .unwrap_or_else(|| hum::detect_mains(&samples, sample_rate));
+        let level = hum::measure(&samples, sample_rate, mains_hz, self.config.hum.harmonics);
+        
+        let key = format!("{}.hum", reading.sensor_name);
+        let z_score = {
+            let mut baselines = self.feature_baselines.write().unwrap();
+            let baseline = baselines
+                .entry(key.clone())
+                .or_insert_with(|| SensorBaseline::new(&key));
+            baseline.update(level.rms);
+            if baseline.sample_count >= self.config.min_baseline_samples {
+                baseline.z_score(level.rms)
+            } else {
+                0.0
+            }
+        };
+        
+        // Report in the channel's own level unit
+        let diagnostic = match reading.payload {
+            ReadingPayload::Audio { .. } => SensorReading::new(&key, dsp::amplitude_to_db(level.rms.max(1.0) / 32768.0), "dBFS"),
+            _ => SensorReading::new(&key, level.rms, &reading.unit),
+        };
+        
+        tracing::trace!(mains_hz, rms = level.rms, ratio = level.ratio, z_score, "Hum measured");
+        self.live.publish(LiveReading {
+            reading: diagnostic.clone(),
+            sensor_type: "hum".to_string(),
+            baseline: self.valid_baseline(&self.feature_baselines, &key),
+        });
+        self.hum_levels.write().unwrap().insert(reading.sensor_name.clone(), HumState {
+            level: level.clone(),
+            time: now,
+            z_score,
+            reading: diagnostic,
+        });
+        
+        Some(level)
+    }
+    
+    /// Strongest recent hum surge, if the reading is of a hum-sensitive type
+    fn hum_surge(&self, reading: &SensorReading, now: SystemTime) -> Option<(String, HumState)> {
+        if !self.config.hum.enabled {
+            return None;
+        }
+        if !matches!(self.reading_type(reading).as_str(), "emf" | "audio" | "infrasound") {
+            return None;
+        }
+        
+        let window = Duration::from_millis(self.config.correlation_window_ms);
+        self.hum_levels.read().unwrap().iter()
+            .filter(|(_, state)| now.duration_since(state.time).unwrap_or(Duration::MAX) < window)
+            .filter(|(_, state)| state.z_score > self.config.hum.surge_threshold)
+            .max_by(|a, b| a.1.z_score.total_cmp(&b.1.z_score))
+            .map(|(sensor, state)| (sensor.clone(), state.clone()))
+    }
+    
+    /// Add a frame pair to the camera's motion heatmap
+    fn accumulate_heatmap(&self, camera: &str, width: u32, height: u32, previous: &[u8], current: &[u8]) {
+        let config = &self.config.heatmap;
+        if !config.enabled {
+            return;
+        }
+        
+        let mut heatmaps = self.heatmaps.write().unwrap();
+        let heatmap = heatmaps.entry(camera.to_string())
+            .or_insert_with(|| MotionHeatmap::new(camera, width, height, config.cell_px));
+        if !heatmap.fits(width, height) {
+            tracing::debug!("Camera {} changed resolution; restarting its heatmap", camera);
+            *heatmap = MotionHeatmap::new(camera, width, height, config.cell_px);
+        }
+        heatmap.accumulate(previous, current, config.threshold);
+    }
+    
+    /// Update payload feature baselines, returning deviations once they are valid
+    ///
+    /// Waveforms have `hum` subtracted first when hum rejection is enabled.
+    fn update_features(&self, reading: &SensorReading, hum: Option<&HumLevel>) -> Vec<FeatureDeviation> {
+        let features = match (&reading.payload, hum) {
+            (ReadingPayload::Frame { width, height, luma }, _) => {
+                let previous = self.previous_frames.write().unwrap()
+                    .insert(reading.sensor_name.clone(), luma.clone());
+                if let Some(previous) = &previous {
+                    self.accumulate_heatmap(&reading.sensor_name, *width, *height, previous, luma);
+                }
+                payload_features(&reading.payload, previous.as_deref())
+            }
+            (payload, Some(hum)) if self.config.hum.reject => match waveform_samples(payload) {
+                Some((mut samples, sample_rate)) => {
+                    hum::reject(&mut samples, sample_rate, hum.mains_hz, self.config.hum.harmonics);
+                    waveform_features(&samples, sample_rate)
+                }
+                None => payload_features(payload, None),
+            },
+            (payload, _) => payload_features(payload, None),
+        };
+        
+        let mut baselines = self.feature_baselines.write().unwrap();
+        let mut deviations = Vec::with_capacity(features.len());
+        
+        for (name, value) in features {
+            let key = format!("{}.{}", reading.sensor_name, name);
+            let baseline = baselines
+                .entry(key.clone())
+                .or_insert_with(|| SensorBaseline::new(&key));
+            baseline.update(value);
+            
+            if baseline.sample_count >= self.config.min_baseline_samples {
+                deviations.push(FeatureDeviation {
+                    name,
+                    value,
+                    mean: baseline.mean,
+                    z_score: baseline.z_score(value),
+                });
+            }
+        }
+        
+        deviations
+    }
+    
+    /// Emit a door event when a contact sensor changes state
+    async fn process_contact(&self, reading: SensorReading, now: SystemTime) -> Result<Option<ParanormalEvent>> {
+        let previous = self.contact_states.write().unwrap()
+            .insert(reading.sensor_name.clone(), reading.value);
+        
+        // First reading only establishes the state
+        let previous = match previous {
+            Some(previous) if previous != reading.value => previous,
+            _ => return Ok(None),
+        };
+        
+        // An opening with no motion sensor active nearby is the interesting case
+        let window = Duration::from_millis(self.config.correlation_window_ms);
+        let motion_nearby = self.recent_readings.read().unwrap().iter().any(|(t, r)| {
+            self.get_sensor_type(&r.sensor_name) == "motion" &&
+            r.value > 0.5 &&
+            now.duration_since(*t).unwrap_or(Duration::MAX) < window
+        });
+        
+        let opened = reading.value > 0.5;
+        let confidence = match (opened, motion_nearby) {
+            (true, false) => 0.8,
+            (false, false) => 0.6,
+            (_, true) => 0.3,
+        };
+        
+        if confidence < self.config.min_confidence {
+            return Ok(None);
+        }
+        
+        let mut event = ParanormalEvent::new(EventType::DoorEvent, confidence)
+            .with_sensor_data(SensorSnapshot {
+                sensor_name: reading.sensor_name.clone(),
+                sensor_type: "contact".to_string(),
+                value: reading.value,
+                unit: reading.unit,
+                baseline: Some(previous),
+                deviation: None,
+                components: Vec::new(),
+            })
+            .with_metadata("state", if opened { "opened" } else { "closed" })
+            .with_metadata("motion_nearby", &motion_nearby.to_string());
+        event.history = self.history(&event, now);
+        tracing::Span::current().record("event_id", event.id.as_str());
+        
+        let _ = self.event_tx.send(event.clone()).await;
+        
+        Ok(Some(event))
+    }
+    
+    /// Report a completed laser grid pass as a MotionDetected event
+    ///
+    /// The metadata carries the break order (`beam_path`), direction, speed,
+    /// position and blocked heights, so triggers can match specific routes.
+    /// A pass through several beam positions in order is the most credible;
+    /// a single beam is often an insect or dust.
+    #[tracing::instrument(level = "debug", name = "fusion", skip_all, fields(sensor = %pass.grid, event_id))]
+    pub async fn process_beam_pass(&self, pass: &BreakAnalysis) -> Result<Option<ParanormalEvent>> {
+        let now = SystemTime::now();
+        let confidence = match pass.direction {
+            PassDirection::Forward | PassDirection::Backward => 0.8,
+            PassDirection::Erratic => 0.6,
+            PassDirection::Stationary if pass.order().len() > 1 => 0.5,
+            PassDirection::Stationary => 0.35,
+        };
+        
+        if confidence < self.config.min_confidence {
+            tracing::debug!(grid = %pass.grid, path = %pass.path(), confidence, "Laser grid pass below minimum confidence");
+            return Ok(None);
+        }
+        
+        let order = pass.order();
+        let direction = match pass.direction {
+            PassDirection::Forward => "forward",
+            PassDirection::Backward => "backward",
+            PassDirection::Stationary => "stationary",
+            PassDirection::Erratic => "erratic",
+        };
+        
+        let mut event = ParanormalEvent::new(EventType::MotionDetected, confidence)
+            .with_sensor_data(SensorSnapshot {
+                sensor_name: pass.grid.clone(),
+                sensor_type: "laser".to_string(),
+                value: order.len() as f64,
+                unit: "beams".to_string(),
+                baseline: None,
+                deviation: None,
+                components: pass.breaks.iter()
+                    .map(|b| (b.beam.clone(), b.offset.as_secs_f64()))
+                    .collect(),
+            })
+            .with_metadata("beam_path", &pass.path())
+            .with_metadata("direction", direction)
+            .with_metadata("position_m", &format!("{:.2}", pass.position))
+            .with_metadata("height_m", &format!("{:.2}-{:.2}", pass.height_range.0, pass.height_range.1))
+            .with_metadata("pass_ms", &pass.duration.as_millis().to_string());
+        if let Some(speed) = pass.speed {
+            event = event.with_metadata("speed_m_s", &format!("{:.2}", speed));
+        }
+        event.history = self.history(&event, now);
+        tracing::Span::current().record("event_id", event.id.as_str());
+        
+        let _ = self.event_tx.send(event.clone()).await;
+        
+        Ok(Some(event))
+    }
+    
+    /// Emit a pressure event when the pressure trend first exceeds the rate threshold
+    ///
+    /// One event per excursion: the sensor re-arms once the rate falls
+    /// below half the threshold.
+    async fn process_pressure(&self, reading: SensorReading, now: SystemTime) -> Result<Option<ParanormalEvent>> {
+        let config = &self.config.pressure;
+        let window = Duration::from_secs(config.window_secs);
+        let samples: Vec<(f64, f64)> = self.recent_readings.read().unwrap().iter()
+            .filter(|(_, r)| r.sensor_name == reading.sensor_name)
+            .filter_map(|(t, r)| {
+                let age = now.duration_since(*t).ok().filter(|age| *age <= window)?;
+                Some((-age.as_secs_f64(), pressure::to_hpa(r.value, &r.unit)))
+            })
+            .collect();
+        
+        let Some(rate) = pressure::trend(&samples, config.window_secs as f64 / 2.0) else {
+            return Ok(None);
+        };
+        
+        let threshold = config.threshold_hpa_per_min;
+        {
+            let mut excursions = self.pressure_excursions.write().unwrap();
+            if rate.abs() < threshold {
+                if rate.abs() < threshold / 2.0 {
+                    excursions.remove(&reading.sensor_name);
+                }
+                return Ok(None);
+            }
+            if !excursions.insert(reading.sensor_name.clone()) {
+                return Ok(None);
+            }
+        }
+        
+        let confidence = (0.5 + 0.25 * (rate.abs() / threshold - 1.0)).min(0.95);
+        tracing::debug!(rate, confidence, "Pressure trend");
+        if confidence < self.config.min_confidence {
+            return Ok(None);
+        }
+        
+        let start = samples.iter().min_by(|a, b| a.0.total_cmp(&b.0)).map(|s| s.1);
+        let mut event = ParanormalEvent::new(EventType::PressureAnomaly, confidence)
+            .with_sensor_data(SensorSnapshot {
+                sensor_name: reading.sensor_name.clone(),
+                sensor_type: "pressure".to_string(),
+                value: pressure::to_hpa(reading.value, &reading.unit),
+                unit: "hPa".to_string(),
+                baseline: start,
+                deviation: None,
+                components: Vec::new(),
+            })
+            .with_metadata("trend", if rate < 0.0 { "falling" } else { "rising" })
+            .with_metadata("rate_hpa_per_min", &format!("{:+.2}", rate))
+            .with_metadata("window_secs", &config.window_secs.to_string());
+        event.history = self.history(&event, now);
+        tracing::Span::current().record("event_id", event.id.as_str());
+        
+        let _ = self.event_tx.send(event.clone()).await;
+        
+        Ok(Some(event))
+    }
+    
+    /// Rate of change of a sensor up to `reading`, over the rate window
+    fn onset_rate(&self, reading: &SensorReading, now: SystemTime) -> Option<f64> {
+        let earlier: Vec<(f64, f64)> = self.recent_readings.read().unwrap().iter()
+            .filter(|(_, r)| r.sensor_name == reading.sensor_name)
+            .filter_map(|(t, r)| Some((now.duration_since(*t).ok()?.as_secs_f64(), r.value)))
+            .collect();
+        anomaly::rate_of_change(reading.value, &earlier, self.config.rate.window().as_secs_f64())
+    }
+    
+    /// Weight of a reading's sensor type (1 if not listed)
+    fn sensor_weight(&self, reading: &SensorReading) -> f64 {
+        self.config.sensor_weights.get(&self.reading_type(reading)).copied().unwrap_or(1.0)
+    }
+    
     /// Calculate confidence from z-score
     fn calculate_confidence(&self, z_score: f64) -> f64 {
         // Sigmoid-like mapping from z-score to confidence
@@ -228,6 +1060,9 @@ impl FusionEngine {
     }
     
     /// Find correlated anomalies in time window
+    ///
+    /// Readings the HAL flagged with quality 0 (a sensor stuck at one value)
+    /// never count.
     fn find_correlated_anomalies(&self, exclude_sensor: &str, time: SystemTime) -> Vec<(SystemTime, SensorReading)> {
         let window = Duration::from_millis(self.config.correlation_window_ms);
         let baselines = self.baselines.read().unwrap();
@@ -236,6 +1071,7 @@ impl FusionEngine {
         recent.iter()
             .filter(|(t, r)| {
                 r.sensor_name != exclude_sensor &&
+                r.quality > 0.0 &&
                 time.duration_since(*t).unwrap_or(Duration::MAX) < window
             })
             .filter(|(_, r)| {
@@ -251,7 +1087,7 @@ impl FusionEngine {
     
     /// Classify event type based on sensor data
     fn classify_event(&self, primary: &SensorReading, correlated: &[(SystemTime, SensorReading)]) -> EventType {
-        let sensor_type = self.get_sensor_type(&primary.sensor_name);
+        let sensor_type = self.reading_type(primary);
         
         // Check for multi-sensor event
         if correlated.len() >= 2 {
@@ -259,28 +1095,40 @@ impl FusionEngine {
         }
         
         // Single sensor classification
-        match sensor_type.as_str() {
-            "emf" | "magnetometer" => EventType::EmfAnomaly,
-            "temperature" | "ir_temperature" | "thermal" => EventType::TemperatureAnomaly,
-            "audio" | "microphone" => EventType::AudioAnomaly,
-            "camera" | "ir_camera" => EventType::VisualAnomaly,
-            "pir" | "motion" | "laser" => EventType::MotionDetected,
-            "infrasound" => EventType::InfrasoundDetected,
-            "sdr" | "rf" | "radio" => EventType::RfAnomaly,
-            _ => EventType::EmfAnomaly,
+        EventType::for_sensor_type(&sensor_type)
+    }
+    
+    /// Sensor type, letting typed payloads override the name heuristics
+    fn reading_type(&self, reading: &SensorReading) -> String {
+        let by_name = self.get_sensor_type(&reading.sensor_name);
+        match reading.payload {
+            ReadingPayload::Audio { .. } if by_name != "infrasound" => "audio".to_string(),
+            ReadingPayload::Spectrum { .. } => "sdr".to_string(),
+            ReadingPayload::Frame { .. } => "camera".to_string(),
+            _ if matches!(reading.unit.as_str(), "hPa" | "Pa" | "kPa" | "mbar") => "pressure".to_string(),
+            _ if matches!(reading.unit.as_str(), "%RH" | "%rh" | "RH") => "humidity".to_string(),
+            _ => by_name,
         }
     }
     
     /// Get sensor type from name
-    fn get_sensor_type(&self, name: &str) -> String {
+    pub fn get_sensor_type(&self, name: &str) -> String {
         let name_lower = name.to_lowercase();
         
         if name_lower.contains("emf") || name_lower.contains("mag") || name_lower.contains("hmc") {
             "emf".to_string()
+        } else if name_lower.contains("pressure") || name_lower.contains("baro") {
+            "pressure".to_string()
+        } else if name_lower.contains("humid") || name_lower.contains("dht") {
+            "humidity".to_string()
         } else if name_lower.contains("temp") || name_lower.contains("mlx") || name_lower.contains("bme") {
             "temperature".to_string()
         } else if name_lower.contains("audio") || name_lower.contains("mic") {
             "audio".to_string()
+        } else if name_lower.contains("door") || name_lower.contains("contact") || name_lower.contains("reed") {
+            "contact".to_string()
+        } else if name_lower.contains("vib") || name_lower.contains("sw420") || name_lower.contains("knock") {
+            "vibration".to_string()
         } else if name_lower.contains("pir") || name_lower.contains("motion") {
             "motion".to_string()
         } else if name_lower.contains("camera") || name_lower.contains("video") {
@@ -294,24 +1142,186 @@ impl FusionEngine {
         }
     }
     
+    /// Current configuration
+    pub fn config(&self) -> &FusionConfig {
+        &self.config
+    }
+    
+    /// Replace configuration (baselines and Kalman estimates are kept)
+    ///
+    /// Existing quantile baselines pick up the new percentile; a changed
+    /// mode takes effect when a sensor's baseline is reset.
+    pub fn set_config(&mut self, config: FusionConfig) {
+        self.band_plan = BandPlan::new(&config.bands);
+        self.kalman.write().unwrap().set_config(config.kalman.clone());
+        self.config = config;
+        
+        let scale = self.config.baseline.quantile_scale(self.config.anomaly_threshold);
+        for baseline in self.baselines.write().unwrap().values_mut() {
+            if baseline.mode == BaselineMode::Quantile {
+                baseline.quantile_scale = scale;
+            }
+        }
+    }
+    
+    /// Snapshot of a reading against its baseline (once the baseline is valid)
+    pub fn snapshot(&self, reading: &SensorReading) -> SensorSnapshot {
+        let baseline = self.valid_baseline(&self.baselines, &reading.sensor_name);
+        
+        SensorSnapshot {
+            sensor_name: reading.sensor_name.clone(),
+            sensor_type: self.reading_type(reading),
+            value: reading.value,
+            unit: reading.unit.clone(),
+            baseline: baseline.as_ref().map(|b| b.center()),
+            deviation: baseline.as_ref().map(|b| b.z_score(reading.value)),
+            components: reading.components().to_vec(),
+        }
+    }
+    
+    /// Latest value of every sensor, as watch channels
+    ///
+    /// The handle can be cloned and kept; it stays current as readings
+    /// are processed.
+    pub fn live(&self) -> LiveReadings {
+        self.live.clone()
+    }
+    
+    /// Motion heatmaps accumulated so far, by camera
+    pub fn heatmaps(&self) -> Vec<MotionHeatmap> {
+        let mut heatmaps: Vec<_> = self.heatmaps.read().unwrap().values().cloned().collect();
+        heatmaps.sort_by(|a, b| a.camera.cmp(&b.camera));
+        heatmaps
+    }
+    
+    /// Start every camera's heatmap over (e.g. for a new session)
+    pub fn reset_heatmaps(&self) {
+        self.heatmaps.write().unwrap().clear();
+    }
+    
+    /// Latest mains hum measured on a waveform channel
+    pub fn hum_level(&self, sensor_name: &str) -> Option<HumLevel> {
+        self.hum_levels.read().unwrap().get(sensor_name).map(|state| state.level.clone())
+    }
+    
+    /// Diagnostic `<sensor>.hum` reading from the latest hum measurement
+    pub fn hum_reading(&self, sensor_name: &str) -> Option<SensorReading> {
+        self.hum_levels.read().unwrap().get(sensor_name).map(|state| state.reading.clone())
+    }
+    
     /// Get baseline for sensor
     pub fn get_baseline(&self, sensor_name: &str) -> Option<SensorBaseline> {
         self.baselines.read().unwrap().get(sensor_name).cloned()
     }
     
+    /// All baselines
+    pub fn baselines(&self) -> Vec<SensorBaseline> {
+        self.baselines.read().unwrap().values().cloned().collect()
+    }
+    
     /// Reset baseline for sensor
     pub fn reset_baseline(&self, sensor_name: &str) {
         let mut baselines = self.baselines.write().unwrap();
         if let Some(baseline) = baselines.get_mut(sensor_name) {
-            *baseline = SensorBaseline::new(sensor_name);
+            *baseline = self.new_baseline(sensor_name, &self.known_type(sensor_name));
         }
+        self.anomaly_runs.write().unwrap().remove(sensor_name);
+        self.kalman.write().unwrap().reset(sensor_name);
+        
+        let prefix = format!("{}.", sensor_name);
+        self.feature_baselines.write().unwrap()
+            .retain(|key, _| !key.starts_with(&prefix));
     }
     
     /// Reset all baselines
     pub fn reset_all_baselines(&self) {
         let mut baselines = self.baselines.write().unwrap();
         for (name, baseline) in baselines.iter_mut() {
-            *baseline = SensorBaseline::new(name);
+            *baseline = self.new_baseline(name, &self.known_type(name));
+        }
+        self.anomaly_runs.write().unwrap().clear();
+        self.kalman.write().unwrap().clear();
+        self.feature_baselines.write().unwrap().clear();
+    }
+    
+    /// Type of a sensor from its latest reading, else from its name
+    fn known_type(&self, sensor_name: &str) -> String {
+        self.live.get(sensor_name)
+            .map(|l| l.sensor_type)
+            .unwrap_or_else(|| self.get_sensor_type(sensor_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Local, Timelike};
+    
+    #[test]
+    fn quantile_baseline_tolerates_skewed_bursts() {
+        let scale = BaselineConfig::default().quantile_scale(3.0);
+        let mut baseline = SensorBaseline::new("pir").with_quantiles(1000, scale);
+        // Mostly idle, with a burst every tenth sample
+        for n in 0..1000 {
+            baseline.update(if n % 10 == 0 { 5.0 } else { 0.0 });
+        }
+        assert_eq!(baseline.center(), 0.0);
+        assert!(!baseline.is_anomalous(5.0, 3.0));
+        assert!(baseline.is_anomalous(50.0, 3.0));
+        
+        let fresh = baseline.fresh();
+        assert_eq!(fresh.mode, BaselineMode::Quantile);
+        assert_eq!(fresh.z_score(50.0), 0.0);
+    }
+    
+    #[test]
+    fn robust_baseline_resists_spikes() {
+        let mut robust = SensorBaseline::new("emf").with_robust(100);
+        let mut gaussian = SensorBaseline::new("emf");
+        for n in 0..100 {
+            let value = match n {
+                n if n % 20 == 0 => 1000.0,
+                n if n % 2 == 0 => 10.0,
+                _ => 11.0,
+            };
+            robust.update(value);
+            gaussian.update(value);
+        }
+        
+        // The spikes inflate the standard deviation enough to hide a
+        // real deviation, but not the MAD
+        assert!(!gaussian.is_anomalous(50.0, 3.0));
+        assert!(robust.is_anomalous(50.0, 3.0));
+        assert!(!robust.is_anomalous(11.0, 3.0));
+        assert_eq!(robust.center(), 11.0);
+    }
+    
+    #[test]
+    fn robust_baseline_falls_back_on_constant_input() {
+        let mut baseline = SensorBaseline::new("emf").with_robust(10);
+        for _ in 0..10 {
+            baseline.update(5.0);
+        }
+        assert_eq!(baseline.mad, Some(0.0));
+        assert_eq!(baseline.z_score(6.0), 1.0 / baseline.std_dev);
+    }
+    
+    #[test]
+    fn diurnal_baseline_reports_the_scored_hour() {
+        let mut baseline = SensorBaseline::new("temp").with_diurnal(Duration::from_secs(3600));
+        let time = SystemTime::now();
+        for _ in 0..50 {
+            baseline.update_at(15.0, time);
         }
+        baseline.update_at(16.0, time);
+        
+        let hour = DateTime::<Local>::from(time).hour() as usize;
+        assert_eq!(baseline.hour, Some(hour));
+        let mean = baseline.hour_mean.unwrap();
+        assert!(mean > 15.0 && mean < 16.0);
+        assert_eq!(baseline.center(), mean);
+        assert!(baseline.z_score(16.0) > 0.0);
+        assert_eq!(baseline.fresh().mode, BaselineMode::Diurnal);
     }
 }
+
@@ -0,0 +1,201 @@
+//! Handheld Logger Import
+//!
+//! Teams carry handheld meters alongside the rig: EMF meters and temperature
+//! loggers that write CSV files with their own column names, date formats and
+//! clocks. A [`CsvMapping`] from the app config says which columns hold the
+//! time and which hold readings; [`parse`] turns a file into sensor records
+//! that go into a session's sensor log, next to the rig's own samples.
+
+use crate::recording::SensorRecord;
+use crate::{Result, SensorError};
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// CSV layout of one kind of logger (`[[csv_import]]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvMapping {
+    pub name: String,
+    
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    
+    /// Decimal comma in numbers ("21,5"), common with `;` delimiters
+    #[serde(default)]
+    pub decimal_comma: bool,
+    
+    /// Lines before the header (logger model, serial number, ...)
+    #[serde(default)]
+    pub skip_rows: usize,
+    
+    /// Whether the first row after `skip_rows` names the columns; without
+    /// one, columns are referred to by number from 1
+    #[serde(default = "default_header")]
+    pub header: bool,
+    
+    /// Column holding the timestamp (or the time of day, with `date_column`)
+    pub time_column: String,
+    
+    /// Column holding the date, for loggers that split date and time
+    #[serde(default)]
+    pub date_column: Option<String>,
+    
+    /// chrono format of the timestamp (date and time joined by a space when
+    /// split), or "unix" / "unix_ms"
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+    
+    /// Offset of the logger's clock from UTC (minutes); the host's local
+    /// time zone if unset. Ignored for formats that carry an offset.
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+    
+    /// Added to every timestamp, for loggers whose clock runs off (s)
+    #[serde(default)]
+    pub clock_correction_secs: f64,
+    
+    pub columns: Vec<CsvColumn>,
+}
+
+/// One value column of a logger CSV
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumn {
+    pub column: String,
+    /// Sensor name the readings are logged under
+    pub sensor: String,
+    #[serde(default)]
+    pub unit: String,
+    /// Linear conversion: value * scale + offset
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    #[serde(default)]
+    pub offset: f64,
+}
+
+fn default_delimiter() -> char { ',' }
+fn default_header() -> bool { true }
+fn default_time_format() -> String { "%Y-%m-%d %H:%M:%S".to_string() }
+fn default_scale() -> f64 { 1.0 }
+
+/// Records read from a logger file
+#[derive(Debug, Clone)]
+pub struct CsvImport {
+    pub records: Vec<SensorRecord>,
+    /// Data rows without a readable timestamp
+    pub skipped_rows: usize,
+}
+
+/// Parse a logger CSV with `mapping`
+///
+/// Empty or unreadable value cells are skipped; a missing column is an error.
+pub fn parse(mapping: &CsvMapping, reader: impl BufRead) -> Result<CsvImport> {
+    let mut lines = reader.lines()
+        .map_while(|line| line.ok())
+        .skip(mapping.skip_rows)
+        .filter(|line| !line.trim().is_empty());
+    
+    let header = if mapping.header {
+        split_row(&lines.next().unwrap_or_default(), mapping.delimiter)
+    } else {
+        Vec::new()
+    };
+    let index = |column: &str| -> Result<usize> {
+        header.iter().position(|h| h.trim().trim_start_matches('\u{feff}').eq_ignore_ascii_case(column))
+            .or_else(|| column.parse::<usize>().ok().filter(|&n| n > 0).map(|n| n - 1))
+            .ok_or_else(|| SensorError::Recording(format!("{}: no column {:?}", mapping.name, column)))
+    };
+    
+    let time = index(&mapping.time_column)?;
+    let date = mapping.date_column.as_deref().map(index).transpose()?;
+    let columns = mapping.columns.iter()
+        .map(|c| Ok((index(&c.column)?, c)))
+        .collect::<Result<Vec<_>>>()?;
+    
+    let mut import = CsvImport { records: Vec::new(), skipped_rows: 0 };
+    for line in lines {
+        let row = split_row(&line, mapping.delimiter);
+        let text = match date {
+            Some(date) => format!("{} {}", cell(&row, date), cell(&row, time)),
+            None => cell(&row, time).to_string(),
+        };
+        let Some(timestamp) = parse_time(mapping, &text) else {
+            import.skipped_rows += 1;
+            continue;
+        };
+        
+        for (i, column) in &columns {
+            let Some(value) = parse_number(cell(&row, *i), mapping.decimal_comma) else {
+                continue;
+            };
+            import.records.push(SensorRecord {
+                timestamp,
+                sensor_name: column.sensor.clone(),
+                value: value * column.scale + column.offset,
+                unit: column.unit.clone(),
+            });
+        }
+    }
+    Ok(import)
+}
+
+fn cell(row: &[String], i: usize) -> &str {
+    row.get(i).map_or("", |c| c.trim())
+}
+
+fn parse_number(text: &str, decimal_comma: bool) -> Option<f64> {
+    if decimal_comma {
+        text.replace('.', "").replace(',', ".").parse().ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+fn parse_time(mapping: &CsvMapping, text: &str) -> Option<SystemTime> {
+    let correction = Duration::from_secs_f64(mapping.clock_correction_secs.abs());
+    let time = match mapping.time_format.as_str() {
+        "unix" => UNIX_EPOCH + Duration::try_from_secs_f64(text.parse().ok()?).ok()?,
+        "unix_ms" => UNIX_EPOCH + Duration::from_millis(text.parse().ok()?),
+        format => {
+            let time = match DateTime::parse_from_str(text, format) {
+                Ok(time) => time,
+                Err(_) => {
+                    let naive = NaiveDateTime::parse_from_str(text, format).ok()?;
+                    match mapping.utc_offset_minutes {
+                        Some(minutes) => FixedOffset::east_opt(minutes * 60)?.from_local_datetime(&naive).single()?,
+                        None => Local.from_local_datetime(&naive).earliest()?.fixed_offset(),
+                    }
+                }
+            };
+            SystemTime::from(time)
+        }
+    };
+    
+    if mapping.clock_correction_secs < 0.0 {
+        Some(time - correction)
+    } else {
+        Some(time + correction)
+    }
+}
+
+/// Split a CSV row, honouring double-quoted fields
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
@@ -0,0 +1,211 @@
+//! Motion Heatmaps
+//!
+//! Each camera frame is compared with the previous one and every cell of a
+//! coarse grid where enough pixels changed counts one frame of motion.
+//! Over a session the counts show where in the room movement keeps
+//! happening, both for analysis and for placing sensors on the next visit.
+//! Heatmaps are saved with the session and exported as PNG images.
+
+use serde::{Deserialize, Serialize};
+
+/// Heatmap settings (`[heatmap]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeatmapConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Grid cell size (pixels); larger cells keep saved heatmaps small
+    #[serde(default = "default_cell")]
+    pub cell_px: u32,
+    /// Luma change (0-255) that counts a pixel as moving
+    #[serde(default = "default_threshold")]
+    pub threshold: u8,
+    /// How often heatmaps are written to the session
+    #[serde(default = "default_save_interval")]
+    pub save_interval_secs: u64,
+}
+
+fn default_enabled() -> bool { true }
+fn default_cell() -> u32 { 4 }
+fn default_threshold() -> u8 { 25 }
+fn default_save_interval() -> u64 { 60 }
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            cell_px: default_cell(),
+            threshold: default_threshold(),
+            save_interval_secs: default_save_interval(),
+        }
+    }
+}
+
+/// Motion counts per grid cell of one camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MotionHeatmap {
+    pub camera: String,
+    /// Frame size (pixels)
+    pub width: u32,
+    pub height: u32,
+    pub cell_px: u32,
+    /// Frame pairs compared
+    pub frames: u64,
+    /// Frames with motion per cell, row-major
+    pub counts: Vec<u32>,
+}
+
+impl MotionHeatmap {
+    pub fn new(camera: &str, width: u32, height: u32, cell_px: u32) -> Self {
+        let cell_px = cell_px.max(1);
+        let cells = (width.div_ceil(cell_px) * height.div_ceil(cell_px)) as usize;
+        Self {
+            camera: camera.to_string(),
+            width,
+            height,
+            cell_px,
+            frames: 0,
+            counts: vec![0; cells],
+        }
+    }
+    
+    /// Grid columns
+    pub fn columns(&self) -> u32 {
+        self.width.div_ceil(self.cell_px)
+    }
+    
+    /// Whether frames of `width` x `height` belong to this heatmap
+    pub fn fits(&self, width: u32, height: u32) -> bool {
+        self.width == width && self.height == height
+    }
+    
+    /// Count the cells that changed between two grayscale frames
+    ///
+    /// Frames of a different size than the heatmap are ignored.
+    pub fn accumulate(&mut self, previous: &[u8], current: &[u8], threshold: u8) {
+        let pixels = (self.width * self.height) as usize;
+        if previous.len() != pixels || current.len() != pixels {
+            return;
+        }
+        
+        let columns = self.columns();
+        let mut moved = vec![false; self.counts.len()];
+        for (i, (&a, &b)) in previous.iter().zip(current).enumerate() {
+            if a.abs_diff(b) > threshold {
+                let x = i as u32 % self.width / self.cell_px;
+                let y = i as u32 / self.width / self.cell_px;
+                moved[(y * columns + x) as usize] = true;
+            }
+        }
+        
+        for (count, moved) in self.counts.iter_mut().zip(moved) {
+            *count += moved as u32;
+        }
+        self.frames += 1;
+    }
+    
+    /// Heatmap as a PNG at the camera's resolution, scaled to the busiest cell
+    pub fn to_png(&self) -> Vec<u8> {
+        let peak = self.counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let columns = self.columns();
+        
+        let mut rgb = Vec::with_capacity((self.width * self.height * 3) as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = (y / self.cell_px * columns + x / self.cell_px) as usize;
+                let level = self.counts.get(cell).copied().unwrap_or(0) as f64 / peak;
+                rgb.extend_from_slice(&heat_color(level));
+            }
+        }
+        png::encode_rgb(self.width, self.height, &rgb)
+    }
+}
+
+/// Black through blue, red and yellow to white for 0-1
+fn heat_color(level: f64) -> [u8; 3] {
+    const STOPS: [(f64, [f64; 3]); 5] = [
+        (0.0, [0.0, 0.0, 0.0]),
+        (0.25, [0.0, 0.0, 255.0]),
+        (0.5, [255.0, 0.0, 0.0]),
+        (0.75, [255.0, 255.0, 0.0]),
+        (1.0, [255.0, 255.0, 255.0]),
+    ];
+    
+    let level = level.clamp(0.0, 1.0);
+    let upper = STOPS.iter().position(|&(at, _)| at >= level).unwrap_or(STOPS.len() - 1).max(1);
+    let (a, from) = STOPS[upper - 1];
+    let (b, to) = STOPS[upper];
+    let t = (level - a) / (b - a);
+    [0, 1, 2].map(|c| (from[c] + (to[c] - from[c]) * t).round() as u8)
+}
+
+/// Minimal PNG writer: 8-bit RGB, stored (uncompressed) deflate blocks
+mod png {
+    pub fn encode_rgb(width: u32, height: u32, rgb: &[u8]) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(rgb.len() + height as usize);
+        for row in rgb.chunks((width * 3).max(1) as usize) {
+            raw.push(0); // filter: none
+            raw.extend_from_slice(row);
+        }
+        
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut png, b"IHDR", &header);
+        chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        chunk(&mut png, b"IEND", &[]);
+        png
+    }
+    
+    fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+    
+    fn zlib_stored(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x78, 0x01];
+        let mut blocks = data.chunks(u16::MAX as usize).peekable();
+        if blocks.peek().is_none() {
+            out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        while let Some(block) = blocks.next() {
+            out.push(blocks.peek().is_none() as u8);
+            let len = block.len() as u16;
+            out.extend_from_slice(&len.to_le_bytes());
+            out.extend_from_slice(&(!len).to_le_bytes());
+            out.extend_from_slice(block);
+        }
+        out.extend_from_slice(&adler32(data).to_be_bytes());
+        out
+    }
+    
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
+    
+    fn adler32(data: &[u8]) -> u32 {
+        let (mut a, mut b) = (1u32, 0u32);
+        for chunk in data.chunks(5552) {
+            for &byte in chunk {
+                a += byte as u32;
+                b += a;
+            }
+            a %= 65521;
+            b %= 65521;
+        }
+        (b << 16) | a
+    }
+}
@@ -0,0 +1,336 @@
+//! Location History
+//!
+//! One night's events say little about a place; the same cold spot in the
+//! nursery at ten past three on four different nights says a lot. [`analyze`]
+//! gathers the events of every session recorded at one location and looks
+//! for what recurs: times of day that keep producing events, zones that
+//! produce more than others, and the same kind of event in the same zone at
+//! about the same time across sessions.
+//!
+//! Times of day are the recorder's local time (or UTC), and each is weighed
+//! against the sessions that were recording then, so a time only one session
+//! covered cannot pass for a pattern.
+
+use crate::recording::{EventRecorder, RecordingSession};
+use crate::{EventType, ParanormalEvent, Result};
+use chrono::{DateTime, Local, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Location history settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// Time-of-day bin length for hotspots (minutes)
+    #[serde(default = "default_bin")]
+    pub bin_minutes: u32,
+    /// Widest spread of times of day a recurring pattern may have (minutes)
+    #[serde(default = "default_window")]
+    pub window_minutes: u32,
+    /// Sessions a hotspot or pattern must recur in
+    #[serde(default = "default_min_sessions")]
+    pub min_sessions: usize,
+    /// Times of day in UTC instead of local time
+    #[serde(default)]
+    pub utc: bool,
+}
+
+fn default_bin() -> u32 { 10 }
+fn default_window() -> u32 { 20 }
+fn default_min_sessions() -> usize { 2 }
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            bin_minutes: default_bin(),
+            window_minutes: default_window(),
+            min_sessions: default_min_sessions(),
+            utc: false,
+        }
+    }
+}
+
+/// Time-of-day bin with events in several sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hotspot {
+    /// Bin start, minutes after midnight
+    pub start_minute: u32,
+    pub end_minute: u32,
+    pub events: usize,
+    /// Sessions with events in the bin
+    pub sessions: usize,
+    /// Sessions recording at the time
+    pub covered: usize,
+    /// `sessions / covered`
+    pub recurrence: f64,
+}
+
+/// Events of one zone over all sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneActivity {
+    /// Zone name, `None` for events without one
+    pub zone: Option<String>,
+    pub events: usize,
+    /// Sessions with events in the zone
+    pub sessions: usize,
+    /// Events per recorded hour at the location
+    pub per_hour: f64,
+    /// Events by type
+    pub types: BTreeMap<String, usize>,
+}
+
+/// The same event type in the same zone at about the same time of day in
+/// several sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringPattern {
+    pub zone: Option<String>,
+    pub event_type: EventType,
+    /// Median time of day, minutes after midnight
+    pub minute: u32,
+    /// Minutes between the earliest and latest occurrence
+    pub spread_minutes: u32,
+    pub events: usize,
+    /// Sessions it occurred in
+    pub sessions: Vec<String>,
+    /// Sessions recording at `minute`
+    pub covered: usize,
+}
+
+/// What recurs at a location (`glowbarn-cli history`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationHistory {
+    pub location: String,
+    pub generated_at: DateTime<Utc>,
+    pub sessions: usize,
+    pub recorded_hours: f64,
+    pub events: usize,
+    pub first_event: Option<DateTime<Utc>>,
+    pub last_event: Option<DateTime<Utc>>,
+    pub bin_minutes: u32,
+    pub utc: bool,
+    /// Most recurrent first
+    pub hotspots: Vec<Hotspot>,
+    /// Busiest first
+    pub zones: Vec<ZoneActivity>,
+    /// Most sessions first
+    pub patterns: Vec<RecurringPattern>,
+}
+
+/// Locations with recorded sessions, and how many each
+pub fn locations(recorder: &EventRecorder) -> Result<BTreeMap<String, usize>> {
+    let mut locations = BTreeMap::new();
+    for session in recorder.list_sessions()? {
+        *locations.entry(session.location).or_insert(0) += 1;
+    }
+    Ok(locations)
+}
+
+/// Events of one zone, while counting
+#[derive(Default)]
+struct ZoneTally {
+    events: usize,
+    sessions: BTreeSet<usize>,
+    types: BTreeMap<String, usize>,
+}
+
+/// (time of day, session index) of each event of one zone and type
+type Occurrences = Vec<(u32, usize)>;
+
+/// One session's events and the minutes of the day it recorded
+struct SessionData {
+    id: String,
+    events: Vec<ParanormalEvent>,
+    coverage: Vec<bool>,
+    hours: f64,
+}
+
+/// Analyze every session recorded at `location` (case-insensitive)
+pub fn analyze(recorder: &EventRecorder, location: &str, config: &HistoryConfig) -> Result<LocationHistory> {
+    let minute_of = |time: DateTime<Utc>| -> u32 {
+        let seconds = if config.utc {
+            time.num_seconds_from_midnight()
+        } else {
+            time.with_timezone(&Local).num_seconds_from_midnight()
+        };
+        seconds / 60
+    };
+    
+    let mut name = location.to_string();
+    let mut sessions = Vec::new();
+    for session in recorder.list_sessions()? {
+        if !session.location.trim().eq_ignore_ascii_case(location.trim()) {
+            continue;
+        }
+        name = session.location.clone();
+        let events: Vec<ParanormalEvent> = recorder.load_events(&session.id)?.into_iter()
+            .filter(|e| e.event_type != EventType::Maintenance)
+            .collect();
+        let (start, end) = span(&session, &events);
+        
+        let mut coverage = vec![false; MINUTES_PER_DAY as usize];
+        let minutes = (end - start).num_minutes().clamp(0, MINUTES_PER_DAY as i64) as u32;
+        let first = minute_of(start);
+        for offset in 0..=minutes.min(MINUTES_PER_DAY - 1) {
+            coverage[((first + offset) % MINUTES_PER_DAY) as usize] = true;
+        }
+        
+        sessions.push(SessionData {
+            id: session.id,
+            events,
+            coverage,
+            hours: (end - start).num_seconds().max(0) as f64 / 3600.0,
+        });
+    }
+    
+    let covered = |minute: u32| sessions.iter().filter(|s| s.coverage[(minute % MINUTES_PER_DAY) as usize]).count();
+    let recorded_hours: f64 = sessions.iter().map(|s| s.hours).sum();
+    let all_events = || sessions.iter().enumerate()
+        .flat_map(|(i, s)| s.events.iter().map(move |e| (i, e)));
+    let times: Vec<DateTime<Utc>> = all_events().map(|(_, e)| e.timestamp.into()).collect();
+    
+    // Time-of-day hotspots
+    let bin = config.bin_minutes.clamp(1, MINUTES_PER_DAY);
+    let mut bins: BTreeMap<u32, (usize, BTreeSet<usize>)> = BTreeMap::new();
+    for (session, event) in all_events() {
+        let entry = bins.entry(minute_of(event.timestamp.into()) / bin * bin).or_default();
+        entry.0 += 1;
+        entry.1.insert(session);
+    }
+    let mut hotspots: Vec<Hotspot> = bins.into_iter()
+        .filter(|(_, (_, hit))| hit.len() >= config.min_sessions)
+        .map(|(start, (events, hit))| {
+            let end = (start + bin).min(MINUTES_PER_DAY);
+            let covered = covered(start + (end - start) / 2).max(hit.len());
+            Hotspot {
+                start_minute: start,
+                end_minute: end,
+                events,
+                sessions: hit.len(),
+                covered,
+                recurrence: hit.len() as f64 / covered as f64,
+            }
+        })
+        .collect();
+    hotspots.sort_by(|a, b| b.recurrence.total_cmp(&a.recurrence)
+        .then(b.sessions.cmp(&a.sessions))
+        .then(b.events.cmp(&a.events)));
+    
+    // Per-zone frequencies
+    let mut zones: BTreeMap<Option<String>, ZoneTally> = BTreeMap::new();
+    for (session, event) in all_events() {
+        let tally = zones.entry(zone_of(event)).or_default();
+        tally.events += 1;
+        tally.sessions.insert(session);
+        *tally.types.entry(format!("{:?}", event.event_type)).or_insert(0) += 1;
+    }
+    let mut zones: Vec<ZoneActivity> = zones.into_iter()
+        .map(|(zone, tally)| ZoneActivity {
+            zone,
+            events: tally.events,
+            sessions: tally.sessions.len(),
+            per_hour: if recorded_hours > 0.0 { tally.events as f64 / recorded_hours } else { 0.0 },
+            types: tally.types,
+        })
+        .collect();
+    zones.sort_by_key(|zone| std::cmp::Reverse(zone.events));
+    
+    // Recurring zone/type patterns
+    let mut groups: BTreeMap<(Option<String>, String), (EventType, Occurrences)> = BTreeMap::new();
+    for (session, event) in all_events() {
+        groups.entry((zone_of(event), format!("{:?}", event.event_type)))
+            .or_insert_with(|| (event.event_type.clone(), Vec::new()))
+            .1.push((minute_of(event.timestamp.into()), session));
+    }
+    let mut patterns = Vec::new();
+    for ((zone, _), (event_type, occurrences)) in groups {
+        for (minutes, hit) in recurring(occurrences, config.window_minutes, config.min_sessions) {
+            let minute = minutes[minutes.len() / 2] % MINUTES_PER_DAY;
+            patterns.push(RecurringPattern {
+                zone: zone.clone(),
+                event_type: event_type.clone(),
+                minute,
+                spread_minutes: minutes[minutes.len() - 1] - minutes[0],
+                events: minutes.len(),
+                sessions: hit.iter().map(|&i| sessions[i].id.clone()).collect(),
+                covered: covered(minute).max(hit.len()),
+            });
+        }
+    }
+    patterns.sort_by(|a, b| b.sessions.len().cmp(&a.sessions.len()).then(b.events.cmp(&a.events)));
+    
+    Ok(LocationHistory {
+        location: name,
+        generated_at: Utc::now(),
+        sessions: sessions.len(),
+        recorded_hours,
+        events: times.len(),
+        first_event: times.iter().min().copied(),
+        last_event: times.iter().max().copied(),
+        bin_minutes: bin,
+        utc: config.utc,
+        hotspots,
+        zones,
+        patterns,
+    })
+}
+
+/// Recording span of a session; one that never ended lasts until its last event
+fn span(session: &RecordingSession, events: &[ParanormalEvent]) -> (DateTime<Utc>, DateTime<Utc>) {
+    let end = session.end_time.unwrap_or_else(|| {
+        events.iter().map(|e| DateTime::<Utc>::from(e.timestamp)).max().unwrap_or(session.start_time)
+    });
+    (session.start_time, end.max(session.start_time))
+}
+
+fn zone_of(event: &ParanormalEvent) -> Option<String> {
+    event.location.as_ref().and_then(|l| l.zone.clone())
+}
+
+/// Groups of (time of day, session) occurrences within `window` minutes of
+/// each other that span at least `min_sessions` sessions
+///
+/// Greedy: the window with the most sessions is taken first and its
+/// occurrences removed. Windows may wrap past midnight, in which case the
+/// returned minutes run past 1440. Each group comes back as its sorted
+/// minutes and the sessions in it.
+fn recurring(mut occurrences: Occurrences, window: u32, min_sessions: usize) -> Vec<(Vec<u32>, BTreeSet<usize>)> {
+    occurrences.sort_unstable();
+    let n = occurrences.len();
+    let mut used = vec![false; n];
+    let mut groups = Vec::new();
+    
+    loop {
+        let mut best: Option<(usize, usize, Vec<usize>)> = None;
+        for start in (0..n).filter(|&i| !used[i]) {
+            let from = occurrences[start].0;
+            // Indices inside [from, from + window], wrapping past midnight
+            let members: Vec<usize> = (start..start + n)
+                .map(|k| (k % n, occurrences[k % n].0 + if k >= n { MINUTES_PER_DAY } else { 0 }))
+                .take_while(|&(_, minute)| minute <= from + window)
+                .filter(|&(i, _)| !used[i])
+                .map(|(i, _)| i)
+                .collect();
+            let sessions = members.iter().map(|&i| occurrences[i].1).collect::<BTreeSet<_>>().len();
+            if best.as_ref().is_none_or(|(s, e, _)| (sessions, members.len()) > (*s, *e)) {
+                best = Some((sessions, members.len(), members));
+            }
+        }
+        
+        match best {
+            Some((sessions, _, members)) if sessions >= min_sessions.max(1) => {
+                let from = occurrences[members[0]].0;
+                let mut minutes: Vec<u32> = members.iter()
+                    .map(|&i| occurrences[i].0 + if occurrences[i].0 < from { MINUTES_PER_DAY } else { 0 })
+                    .collect();
+                minutes.sort_unstable();
+                let hit = members.iter().map(|&i| occurrences[i].1).collect();
+                for i in members {
+                    used[i] = true;
+                }
+                groups.push((minutes, hit));
+            }
+            _ => return groups,
+        }
+    }
+}
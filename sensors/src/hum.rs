@@ -0,0 +1,116 @@
+//! Mains Hum Tracking
+//!
+//! Measures 50/60 Hz hum and its harmonics on waveform channels (audio
+//! inputs, EMF probes sampled by an ADC) with Goertzel filters. The fusion
+//! engine reports the hum level as a diagnostic reading, subtracts it
+//! before computing waveform features, and flags anomalies that coincide
+//! with a hum surge so wiring noise is not reported as activity.
+
+use glowbarn_dsp::{stats, Goertzel};
+use serde::{Deserialize, Serialize};
+
+/// Mains frequencies tried when none is configured
+pub const MAINS_FREQUENCIES: [f64; 2] = [50.0, 60.0];
+
+/// Hum tracking settings (`[hum]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HumConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Mains frequency in Hz; detected per block from 50/60 Hz when unset
+    #[serde(default)]
+    pub mains_hz: Option<f64>,
+    /// Number of harmonics tracked, fundamental included
+    #[serde(default = "default_harmonics")]
+    pub harmonics: usize,
+    /// Subtract hum from waveforms before feature extraction
+    #[serde(default = "default_enabled")]
+    pub reject: bool,
+    /// Hum z-score that counts as a surge
+    #[serde(default = "default_surge_threshold")]
+    pub surge_threshold: f64,
+    /// Confidence multiplier for anomalies during a hum surge
+    #[serde(default = "default_penalty")]
+    pub penalty: f64,
+}
+
+fn default_enabled() -> bool { true }
+fn default_harmonics() -> usize { 3 }
+fn default_surge_threshold() -> f64 { 2.5 }
+fn default_penalty() -> f64 { 0.5 }
+
+impl Default for HumConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            mains_hz: None,
+            harmonics: default_harmonics(),
+            reject: default_enabled(),
+            surge_threshold: default_surge_threshold(),
+            penalty: default_penalty(),
+        }
+    }
+}
+
+/// Hum measured in one block of samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HumLevel {
+    /// Fundamental frequency (Hz)
+    pub mains_hz: f64,
+    /// Combined RMS of the tracked harmonics, in the waveform's units
+    pub rms: f64,
+    /// Share of the block's AC RMS that is hum (0-1)
+    pub ratio: f64,
+    /// Peak amplitude per harmonic, fundamental first
+    pub harmonics: Vec<f64>,
+}
+
+/// Harmonic detectors below the Nyquist frequency
+fn detectors(mains_hz: f64, harmonics: usize, sample_rate: f64) -> Vec<Goertzel> {
+    (1..=harmonics.max(1))
+        .map(|k| k as f64 * mains_hz)
+        .take_while(|&f| f < sample_rate / 2.0)
+        .map(|f| Goertzel::new(f, sample_rate))
+        .collect()
+}
+
+/// Samples with the DC offset removed
+fn ac(samples: &[f64]) -> Vec<f64> {
+    let mean = stats::mean(samples);
+    samples.iter().map(|&x| x - mean).collect()
+}
+
+/// Whichever of 50 or 60 Hz is stronger in `samples`
+pub fn detect_mains(samples: &[f64], sample_rate: f64) -> f64 {
+    let samples = ac(samples);
+    MAINS_FREQUENCIES.into_iter()
+        .map(|f| (f, Goertzel::new(f, sample_rate).power(&samples)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(f, _)| f)
+        .unwrap_or(MAINS_FREQUENCIES[0])
+}
+
+/// Hum at `mains_hz` and its harmonics in `samples`
+pub fn measure(samples: &[f64], sample_rate: f64, mains_hz: f64, harmonics: usize) -> HumLevel {
+    let samples = ac(samples);
+    let amplitudes: Vec<f64> = detectors(mains_hz, harmonics, sample_rate).iter()
+        .map(|g| g.amplitude(&samples))
+        .collect();
+    
+    let rms = (amplitudes.iter().map(|a| a * a / 2.0).sum::<f64>()).sqrt();
+    let total = stats::rms(&samples);
+    
+    HumLevel {
+        mains_hz,
+        rms,
+        ratio: if total > 0.0 { (rms / total).min(1.0) } else { 0.0 },
+        harmonics: amplitudes,
+    }
+}
+
+/// Subtract hum at `mains_hz` and its harmonics from `samples` in place
+pub fn reject(samples: &mut [f64], sample_rate: f64, mains_hz: f64, harmonics: usize) {
+    for detector in detectors(mains_hz, harmonics, sample_rate) {
+        detector.subtract(samples);
+    }
+}
@@ -0,0 +1,217 @@
+//! Kalman Fusion
+//!
+//! Threshold fusion rates an anomaly by its own z-score and adds a fixed
+//! bonus per sensor deviating at the same time. Kalman fusion instead keeps
+//! a running estimate of the "activity" of each sensor group (a zone, or a
+//! lone sensor): a hidden level in standard deviations that every reading's
+//! deviation is a noisy measurement of. Each reading updates its group's
+//! estimate with a scalar Kalman filter, readings of heavily weighted sensor
+//! types counting for more, and an event's confidence is the posterior
+//! probability that the group's activity exceeds the anomaly threshold. A
+//! one-sample spike on a single sensor moves the estimate a little; a
+//! deviation that persists, or that other sensors in the room agree with,
+//! moves it a lot.
+
+use glowbarn_dsp::stats;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
+
+/// How anomalies are rated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FusionMode {
+    /// Confidence from the z-score plus a bonus per correlated sensor
+    #[default]
+    Threshold,
+    /// Confidence from a Kalman estimate of each sensor group's activity
+    Kalman,
+}
+
+/// Kalman fusion settings (`[kalman]` in the app config)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KalmanConfig {
+    /// Variance the activity gains per second without readings (σ²/s);
+    /// higher follows changes faster and trusts single readings more
+    #[serde(default = "default_process_noise")]
+    pub process_noise: f64,
+    /// Variance of one reading's deviation around the activity (σ²) for a
+    /// sensor of weight 1, divided by the sensor type's weight
+    #[serde(default = "default_measurement_noise")]
+    pub measurement_noise: f64,
+    /// Sensors sharing one estimate, by group name; a sensor in no group
+    /// is estimated on its own
+    #[serde(default)]
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+fn default_process_noise() -> f64 { 1.0 }
+fn default_measurement_noise() -> f64 { 1.0 }
+
+impl Default for KalmanConfig {
+    fn default() -> Self {
+        Self {
+            process_noise: default_process_noise(),
+            measurement_noise: default_measurement_noise(),
+            groups: BTreeMap::new(),
+        }
+    }
+}
+
+/// Activity estimate of one sensor group
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupEstimate {
+    /// Estimated activity (standard deviations from baseline)
+    pub activity: f64,
+    /// Variance of the estimate
+    pub variance: f64,
+    pub observations: u64,
+    pub updated: SystemTime,
+}
+
+impl GroupEstimate {
+    fn new(now: SystemTime) -> Self {
+        Self { activity: 0.0, variance: 1.0, observations: 0, updated: now }
+    }
+    
+    /// Let the estimate drift for the time since the last update
+    fn predict(&mut self, now: SystemTime, process_noise: f64) {
+        let elapsed = now.duration_since(self.updated).unwrap_or_default();
+        self.variance += process_noise * elapsed.as_secs_f64();
+        self.updated = now;
+    }
+    
+    /// Fold in a measured deviation with variance `noise`
+    fn update(&mut self, deviation: f64, noise: f64) {
+        let gain = self.variance / (self.variance + noise);
+        self.activity += gain * (deviation - self.activity);
+        self.variance *= 1.0 - gain;
+        self.observations += 1;
+    }
+    
+    /// Posterior probability that the activity exceeds `level`
+    pub fn probability_above(&self, level: f64) -> f64 {
+        let sd = self.variance.sqrt();
+        if sd <= 0.0 {
+            return if self.activity > level { 1.0 } else { 0.0 };
+        }
+        1.0 - stats::normal_cdf((level - self.activity) / sd)
+    }
+}
+
+/// Activity estimates of the sensor groups
+#[derive(Debug, Clone, Default)]
+pub struct KalmanFusion {
+    config: KalmanConfig,
+    group_of: HashMap<String, String>,
+    estimates: HashMap<String, GroupEstimate>,
+}
+
+impl KalmanFusion {
+    pub fn new(config: KalmanConfig) -> Self {
+        let mut fusion = Self::default();
+        fusion.set_config(config);
+        fusion
+    }
+    
+    /// Replace the settings, keeping the estimates of groups that remain
+    pub fn set_config(&mut self, config: KalmanConfig) {
+        self.group_of = config.groups.iter()
+            .flat_map(|(group, sensors)| sensors.iter().map(move |sensor| (sensor.clone(), group.clone())))
+            .collect();
+        self.config = config;
+    }
+    
+    /// Group `sensor` is estimated in (its own name if in none)
+    pub fn group_of<'a>(&'a self, sensor: &'a str) -> &'a str {
+        self.group_of.get(sensor).map_or(sensor, String::as_str)
+    }
+    
+    /// Update the group of `sensor` with its deviation (z-score) at `now`
+    ///
+    /// `weight` is the sensor type's weight scaled by the reading's quality;
+    /// a weight of 2 counts a reading as much as two readings of weight 1,
+    /// and a weight of 0 not at all.
+    pub fn observe(&mut self, sensor: &str, deviation: f64, weight: f64, now: SystemTime) -> GroupEstimate {
+        let group = self.group_of(sensor).to_string();
+        let noise = self.config.measurement_noise / weight.max(f64::EPSILON);
+        let estimate = self.estimates.entry(group).or_insert_with(|| GroupEstimate::new(now));
+        
+        estimate.predict(now, self.config.process_noise);
+        estimate.update(deviation.abs(), noise);
+        estimate.clone()
+    }
+    
+    /// Current estimate of `group`
+    pub fn estimate(&self, group: &str) -> Option<&GroupEstimate> {
+        self.estimates.get(group)
+    }
+    
+    /// Forget the estimate of the group of `sensor`
+    pub fn reset(&mut self, sensor: &str) {
+        let group = self.group_of(sensor).to_string();
+        self.estimates.remove(&group);
+    }
+    
+    /// Forget all estimates
+    pub fn clear(&mut self) {
+        self.estimates.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    fn zoned() -> KalmanFusion {
+        let mut config = KalmanConfig::default();
+        config.groups.insert("attic".to_string(), vec!["emf".to_string(), "temp".to_string()]);
+        KalmanFusion::new(config)
+    }
+    
+    #[test]
+    fn first_reading_moves_halfway() {
+        let mut fusion = KalmanFusion::new(KalmanConfig::default());
+        let now = SystemTime::now();
+        let estimate = fusion.observe("emf", -4.0, 1.0, now);
+        assert_eq!(estimate.activity, 2.0);
+        assert_eq!(estimate.variance, 0.5);
+        assert_eq!(estimate.observations, 1);
+        
+        // Variance grows with the process noise while no readings arrive
+        let later = fusion.observe("emf", 2.0, 0.0, now + Duration::from_secs(2));
+        assert!((later.variance - 2.5).abs() < 1e-9);
+        assert!((later.activity - 2.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn grouped_sensors_share_an_estimate() {
+        let mut fusion = zoned();
+        assert_eq!(fusion.group_of("temp"), "attic");
+        assert_eq!(fusion.group_of("pir"), "pir");
+        
+        let now = SystemTime::now();
+        fusion.observe("emf", 4.0, 1.0, now);
+        let agreed = fusion.observe("temp", 4.0, 1.0, now);
+        let alone = fusion.observe("pir", 4.0, 1.0, now);
+        assert_eq!(agreed.observations, 2);
+        assert!(agreed.activity > alone.activity);
+        assert!(agreed.probability_above(2.0) > alone.probability_above(2.0));
+        
+        fusion.reset("emf");
+        assert!(fusion.estimate("attic").is_none());
+        assert!(fusion.estimate("pir").is_some());
+    }
+    
+    #[test]
+    fn probability_above_level() {
+        let now = SystemTime::now();
+        let mut estimate = GroupEstimate::new(now);
+        assert!((estimate.probability_above(0.0) - 0.5).abs() < 1e-6);
+        estimate.activity = 3.0;
+        estimate.variance = 0.0;
+        assert_eq!(estimate.probability_above(2.0), 1.0);
+        assert_eq!(estimate.probability_above(3.0), 0.0);
+    }
+}
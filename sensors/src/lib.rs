@@ -7,11 +7,56 @@ pub mod fusion;
 pub mod anomaly;
 pub mod recording;
 pub mod triggers;
+pub mod maintenance;
+pub mod export;
+pub mod zones;
+pub mod hum;
+pub mod pressure;
+pub mod bands;
+pub mod heatmap;
+pub mod handheld;
+pub mod live;
+pub mod actions;
+pub mod baseline;
+pub mod persistence;
+pub mod kalman;
+pub mod redact;
+pub mod significance;
+pub mod history;
+pub mod summary;
+pub mod reliability;
+pub mod manifest;
+pub mod units;
 
 use glowbarn_hal::{SensorReading, HalError};
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 use serde::{Serialize, Deserialize};
 
+/// Version of the event and session export schema
+///
+/// Bumped whenever recorded fields change. Readers accept any version:
+/// unknown fields are ignored, missing fields take defaults and unknown
+/// event types load as [`EventType::Unknown`].
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Schema of records written before versioning
+fn legacy_schema() -> u32 { 1 }
+
+/// Per-sensor setting keyed by sensor type or a part of the sensor name
+///
+/// An exact type match wins over a name match.
+pub(crate) fn sensor_setting<'a, T>(settings: &'a BTreeMap<String, T>, sensor_name: &str, sensor_type: &str) -> Option<&'a T> {
+    if let Some(setting) = settings.get(sensor_type) {
+        return Some(setting);
+    }
+    
+    let name = sensor_name.to_lowercase();
+    settings.iter()
+        .find(|(pattern, _)| name.contains(&pattern.to_lowercase()))
+        .map(|(_, setting)| setting)
+}
+
 /// Paranormal event types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum EventType {
@@ -31,6 +76,66 @@ pub enum EventType {
     MultiSensorEvent,
     /// Radio frequency anomaly
     RfAnomaly,
+    /// Door/window contact opened or closed
+    DoorEvent,
+    /// Rapid barometric pressure change (door slams, drafts)
+    PressureAnomaly,
+    /// Vibration or knock (recorded as MotionDetected before this type existed)
+    VibrationDetected,
+    /// Relative humidity anomaly
+    HumidityAnomaly,
+    /// Scheduled maintenance (re-baselining) system event
+    Maintenance,
+    /// Type recorded by a newer GlowBarn version
+    #[serde(other)]
+    Unknown,
+}
+
+impl EventType {
+    /// Short names accepted by event filters, e.g. `glowbarn-cli events -t contact`
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            EventType::EmfAnomaly => &["emf"],
+            EventType::TemperatureAnomaly => &["temperature", "cold_spot"],
+            EventType::AudioAnomaly => &["audio", "evp"],
+            EventType::VisualAnomaly => &["visual"],
+            EventType::MotionDetected => &["motion"],
+            EventType::InfrasoundDetected => &["infrasound"],
+            EventType::MultiSensorEvent => &["multi"],
+            EventType::RfAnomaly => &["rf"],
+            EventType::DoorEvent => &["door", "contact"],
+            EventType::Maintenance => &["maintenance"],
+            EventType::PressureAnomaly => &["pressure"],
+            EventType::VibrationDetected => &["vibration", "knock"],
+            EventType::HumidityAnomaly => &["humidity"],
+            EventType::Unknown => &["unknown"],
+        }
+    }
+    
+    /// Event type for an anomaly on a single sensor of `sensor_type`
+    pub fn for_sensor_type(sensor_type: &str) -> Self {
+        match sensor_type {
+            "emf" | "magnetometer" => EventType::EmfAnomaly,
+            "temperature" | "ir_temperature" | "thermal" => EventType::TemperatureAnomaly,
+            "audio" | "microphone" => EventType::AudioAnomaly,
+            "camera" | "ir_camera" => EventType::VisualAnomaly,
+            "pir" | "motion" | "laser" => EventType::MotionDetected,
+            "vibration" => EventType::VibrationDetected,
+            "humidity" => EventType::HumidityAnomaly,
+            "infrasound" => EventType::InfrasoundDetected,
+            "sdr" | "rf" | "radio" => EventType::RfAnomaly,
+            "contact" | "door" | "reed" => EventType::DoorEvent,
+            "pressure" => EventType::PressureAnomaly,
+            _ => EventType::EmfAnomaly,
+        }
+    }
+    
+    /// Whether `filter` is part of the type name or one of its aliases (case-insensitive)
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        format!("{:?}", self).to_lowercase().contains(&filter)
+            || self.aliases().iter().any(|alias| *alias == filter)
+    }
 }
 
 /// Confidence level for detected events
@@ -67,6 +172,9 @@ impl Confidence {
 pub struct ParanormalEvent {
     /// Unique event ID
     pub id: String,
+    /// Schema the event was recorded with
+    #[serde(default = "legacy_schema")]
+    pub schema_version: u32,
     /// Event type
     pub event_type: EventType,
     /// Detection timestamp
@@ -76,11 +184,17 @@ pub struct ParanormalEvent {
     /// Confidence level
     pub confidence_level: Confidence,
     /// Raw sensor readings that triggered this event
+    #[serde(default)]
     pub sensor_data: Vec<SensorSnapshot>,
     /// Location (if available)
+    #[serde(default)]
     pub location: Option<Location>,
     /// Additional metadata
+    #[serde(default)]
     pub metadata: std::collections::HashMap<String, String>,
+    /// Lead-up readings of the involved sensors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<SensorHistory>,
 }
 
 impl ParanormalEvent {
@@ -90,6 +204,7 @@ impl ParanormalEvent {
         
         Self {
             id,
+            schema_version: SCHEMA_VERSION,
             event_type,
             timestamp: SystemTime::now(),
             confidence,
@@ -97,6 +212,7 @@ impl ParanormalEvent {
             sensor_data: Vec::new(),
             location: None,
             metadata: std::collections::HashMap::new(),
+            history: Vec::new(),
         }
     }
     
@@ -128,10 +244,14 @@ pub struct SensorSnapshot {
     pub unit: String,
     pub baseline: Option<f64>,
     pub deviation: Option<f64>,
+    /// Axis values for vector sensors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<(String, f64)>,
 }
 
 impl From<SensorReading> for SensorSnapshot {
     fn from(reading: SensorReading) -> Self {
+        let components = reading.components().to_vec();
         Self {
             sensor_name: reading.sensor_name,
             sensor_type: "unknown".to_string(),
@@ -139,10 +259,20 @@ impl From<SensorReading> for SensorSnapshot {
             unit: reading.unit,
             baseline: None,
             deviation: None,
+            components,
         }
     }
 }
 
+/// Recent readings of one sensor leading up to an event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorHistory {
+    pub sensor_name: String,
+    pub unit: String,
+    /// (seconds relative to the event, negative before it; value)
+    pub samples: Vec<(f64, f64)>,
+}
+
 /// Location information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Location {
@@ -151,6 +281,15 @@ pub struct Location {
     pub x: Option<f64>,
     pub y: Option<f64>,
     pub floor: Option<i32>,
+    /// GPS latitude (degrees, WGS84)
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    /// GPS longitude (degrees, WGS84)
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    /// GPS altitude (meters)
+    #[serde(default)]
+    pub altitude: Option<f64>,
 }
 
 /// Sensor status
@@ -215,6 +354,9 @@ pub enum SensorError {
     
     #[error("Recording error: {0}")]
     Recording(String),
+    
+    #[error("Trigger action failed: {0}")]
+    Action(String),
 }
 
 pub type Result<T> = std::result::Result<T, SensorError>;
@@ -0,0 +1,103 @@
+//! Latest Sensor Values
+//!
+//! One tokio watch channel per sensor holding its most recent reading and
+//! baseline, so UI components and triggers can ask for the current value
+//! of a sensor without consuming or re-buffering the reading stream.
+
+use crate::fusion::SensorBaseline;
+use crate::SensorSnapshot;
+use glowbarn_hal::SensorReading;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::watch;
+
+/// A sensor's latest reading with its baseline at that time
+#[derive(Debug, Clone)]
+pub struct LiveReading {
+    pub reading: SensorReading,
+    /// Sensor type as classified by the fusion engine
+    pub sensor_type: String,
+    /// Baseline including this reading, once it has enough samples
+    pub baseline: Option<SensorBaseline>,
+}
+
+impl LiveReading {
+    /// z-score of the reading against its baseline
+    pub fn deviation(&self) -> Option<f64> {
+        self.baseline.as_ref().map(|b| b.z_score(self.reading.value))
+    }
+    
+    /// Serializable summary of the reading
+    pub fn snapshot(&self) -> SensorSnapshot {
+        SensorSnapshot {
+            sensor_name: self.reading.sensor_name.clone(),
+            sensor_type: self.sensor_type.clone(),
+            value: self.reading.value,
+            unit: self.reading.unit.clone(),
+            baseline: self.baseline.as_ref().map(|b| b.center()),
+            deviation: self.deviation(),
+            components: self.reading.components().to_vec(),
+        }
+    }
+}
+
+/// Per-sensor latest-value channels
+///
+/// Cheap to clone; clones share the channels.
+#[derive(Clone, Default)]
+pub struct LiveReadings {
+    channels: Arc<RwLock<HashMap<String, watch::Sender<Option<LiveReading>>>>>,
+}
+
+impl LiveReadings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Replace a sensor's latest value, notifying its subscribers
+    pub fn publish(&self, live: LiveReading) {
+        if let Some(tx) = self.channels.read().unwrap().get(&live.reading.sensor_name) {
+            tx.send_replace(Some(live));
+            return;
+        }
+        
+        let name = live.reading.sensor_name.clone();
+        self.channels.write().unwrap()
+            .entry(name)
+            .or_insert_with(|| watch::Sender::new(None))
+            .send_replace(Some(live));
+    }
+    
+    /// Watch a sensor's latest value (`None` until its first reading)
+    ///
+    /// Sensors that have not reported yet can be subscribed to ahead of time.
+    pub fn subscribe(&self, sensor_name: &str) -> watch::Receiver<Option<LiveReading>> {
+        if let Some(tx) = self.channels.read().unwrap().get(sensor_name) {
+            return tx.subscribe();
+        }
+        
+        self.channels.write().unwrap()
+            .entry(sensor_name.to_string())
+            .or_insert_with(|| watch::Sender::new(None))
+            .subscribe()
+    }
+    
+    /// Latest value of a sensor
+    pub fn get(&self, sensor_name: &str) -> Option<LiveReading> {
+        self.channels.read().unwrap().get(sensor_name)?.borrow().clone()
+    }
+    
+    /// Latest values of all sensors that have reported, sorted by name
+    pub fn all(&self) -> Vec<LiveReading> {
+        let mut all: Vec<LiveReading> = self.channels.read().unwrap().values()
+            .filter_map(|tx| tx.borrow().clone())
+            .collect();
+        all.sort_by(|a, b| a.reading.sensor_name.cmp(&b.reading.sensor_name));
+        all
+    }
+    
+    /// Forget a sensor; its subscribers see the channel close
+    pub fn remove(&self, sensor_name: &str) {
+        self.channels.write().unwrap().remove(sensor_name);
+    }
+}
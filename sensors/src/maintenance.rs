@@ -0,0 +1,238 @@
+//! Scheduled maintenance
+//!
+//! Re-captures sensor baselines during configured quiet windows (e.g.
+//! 14:00 daily, when nobody is on site) and works out re-zero offsets for
+//! drift-prone sensors. Before/after statistics are reported as
+//! [`EventType::Maintenance`] system events.
+
+use crate::fusion::{FusionEngine, SensorBaseline};
+use crate::{EventType, ParanormalEvent, SensorError, SensorSnapshot, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Quiet window configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// Window name (for logs and events)
+    pub name: String,
+    /// Daily start time, local "HH:MM"
+    pub start: String,
+    /// Window length in minutes
+    #[serde(default = "default_duration")]
+    pub duration_minutes: u64,
+    /// Sensor types whose baselines are re-captured
+    #[serde(default = "default_rebaseline")]
+    pub rebaseline: Vec<String>,
+    /// Sensor types re-zeroed to their previous baseline
+    #[serde(default)]
+    pub rezero: Vec<String>,
+}
+
+fn default_duration() -> u64 { 15 }
+fn default_rebaseline() -> Vec<String> { vec!["emf".to_string(), "audio".to_string()] }
+
+impl MaintenanceWindow {
+    fn start_time(&self) -> Result<NaiveTime> {
+        NaiveTime::parse_from_str(&self.start, "%H:%M")
+            .map_err(|e| SensorError::InvalidConfig(format!("{}: bad start time '{}': {}", self.name, self.start, e)))
+    }
+    
+    fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration_minutes * 60)
+    }
+    
+    fn covers(&self, engine: &FusionEngine, sensor: &str) -> bool {
+        let sensor_type = engine.get_sensor_type(sensor);
+        self.rebaseline.contains(&sensor_type) || self.rezero.contains(&sensor_type)
+    }
+}
+
+/// Re-zero adjustment for one sensor
+#[derive(Debug, Clone)]
+pub struct Rezero {
+    pub sensor_name: String,
+    /// Change in quiet-time mean since the previous baseline
+    pub drift: f64,
+}
+
+/// What a scheduler tick did
+#[derive(Debug, Clone)]
+pub enum MaintenanceOutcome {
+    /// Window opened; listed baselines were reset
+    Started {
+        window: String,
+        before: Vec<SensorBaseline>,
+    },
+    /// Window closed with fresh baselines
+    Completed {
+        window: String,
+        before: Vec<SensorBaseline>,
+        after: Vec<SensorBaseline>,
+        rezero: Vec<Rezero>,
+    },
+}
+
+impl MaintenanceOutcome {
+    /// System event describing this outcome
+    pub fn to_event(&self) -> ParanormalEvent {
+        // sensor_type marks which side of the window the statistics are from
+        let snapshot = |b: &SensorBaseline, phase: &str| SensorSnapshot {
+            sensor_name: b.name.clone(),
+            sensor_type: phase.to_string(),
+            value: b.mean,
+            unit: String::new(),
+            baseline: Some(b.mean),
+            deviation: Some(b.std_dev),
+            components: Vec::new(),
+        };
+        
+        match self {
+            MaintenanceOutcome::Started { window, before } => {
+                let mut event = ParanormalEvent::new(EventType::Maintenance, 1.0)
+                    .with_metadata("window", window)
+                    .with_metadata("phase", "started");
+                for baseline in before {
+                    event = event.with_sensor_data(snapshot(baseline, "before"));
+                }
+                event
+            }
+            MaintenanceOutcome::Completed { window, before, after, rezero } => {
+                let mut event = ParanormalEvent::new(EventType::Maintenance, 1.0)
+                    .with_metadata("window", window)
+                    .with_metadata("phase", "completed");
+                for baseline in before {
+                    event = event.with_sensor_data(snapshot(baseline, "before"));
+                }
+                for baseline in after {
+                    event = event.with_sensor_data(snapshot(baseline, "after"));
+                }
+                for adjustment in rezero {
+                    event = event.with_metadata(
+                        &format!("drift.{}", adjustment.sensor_name),
+                        &format!("{:.4}", adjustment.drift),
+                    );
+                }
+                event
+            }
+        }
+    }
+}
+
+struct ActiveWindow {
+    index: usize,
+    ends: DateTime<Local>,
+    before: Vec<SensorBaseline>,
+}
+
+/// Runs maintenance windows against a fusion engine
+pub struct MaintenanceScheduler {
+    windows: Vec<(MaintenanceWindow, NaiveTime)>,
+    last_run: Vec<Option<NaiveDate>>,
+    active: Option<ActiveWindow>,
+}
+
+impl MaintenanceScheduler {
+    /// Create scheduler; fails on unparseable start times
+    pub fn new(windows: Vec<MaintenanceWindow>) -> Result<Self> {
+        let windows = windows.into_iter()
+            .map(|w| w.start_time().map(|t| (w, t)))
+            .collect::<Result<Vec<_>>>()?;
+        let last_run = vec![None; windows.len()];
+        
+        Ok(Self {
+            windows,
+            last_run,
+            active: None,
+        })
+    }
+    
+    /// Whether a window is currently open
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+    
+    /// Advance the schedule; call periodically (e.g. every 30 s)
+    pub fn tick(&mut self, now: DateTime<Local>, engine: &FusionEngine) -> Option<MaintenanceOutcome> {
+        if let Some(ref active) = self.active {
+            if now < active.ends {
+                return None;
+            }
+            let active = self.active.take()?;
+            return Some(self.complete(active, engine));
+        }
+        
+        let today = now.date_naive();
+        let index = self.windows.iter().enumerate().position(|(i, (window, start))| {
+            let Some(start) = Local.from_local_datetime(&today.and_time(*start)).earliest() else {
+                return false;
+            };
+            let end = start + window.duration();
+            self.last_run[i] != Some(today) && now >= start && now < end
+        })?;
+        
+        let (window, start) = &self.windows[index];
+        let before = Self::snapshot(window, engine);
+        for baseline in &before {
+            engine.reset_baseline(&baseline.name);
+        }
+        
+        tracing::info!("Maintenance window '{}' started: re-capturing {} baselines", window.name, before.len());
+        
+        let started = Local.from_local_datetime(&today.and_time(*start)).earliest().unwrap_or(now);
+        self.last_run[index] = Some(today);
+        self.active = Some(ActiveWindow {
+            index,
+            ends: started + window.duration(),
+            before: before.clone(),
+        });
+        
+        Some(MaintenanceOutcome::Started {
+            window: window.name.clone(),
+            before,
+        })
+    }
+    
+    fn complete(&self, active: ActiveWindow, engine: &FusionEngine) -> MaintenanceOutcome {
+        let (window, _) = &self.windows[active.index];
+        let after = Self::snapshot(window, engine);
+        
+        let rezero = after.iter()
+            .filter(|b| window.rezero.contains(&engine.get_sensor_type(&b.name)))
+            .filter_map(|b| {
+                let before = active.before.iter().find(|p| p.name == b.name)?;
+                Some(Rezero {
+                    sensor_name: b.name.clone(),
+                    drift: b.mean - before.mean,
+                })
+            })
+            .collect::<Vec<_>>();
+        
+        for baseline in &after {
+            let previous = active.before.iter().find(|p| p.name == baseline.name);
+            tracing::info!(
+                "Baseline {}: mean {:.3} -> {:.3}, σ {:.3} -> {:.3}",
+                baseline.name,
+                previous.map(|p| p.mean).unwrap_or(f64::NAN),
+                baseline.mean,
+                previous.map(|p| p.std_dev).unwrap_or(f64::NAN),
+                baseline.std_dev,
+            );
+        }
+        tracing::info!("Maintenance window '{}' completed", window.name);
+        
+        MaintenanceOutcome::Completed {
+            window: window.name.clone(),
+            before: active.before,
+            after,
+            rezero,
+        }
+    }
+    
+    fn snapshot(window: &MaintenanceWindow, engine: &FusionEngine) -> Vec<SensorBaseline> {
+        engine.baselines()
+            .into_iter()
+            .filter(|b| window.covers(engine, &b.name))
+            .collect()
+    }
+}
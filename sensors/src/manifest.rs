@@ -0,0 +1,181 @@
+//! Signed Rig Manifests
+//!
+//! Evidence is only worth as much as what is known about the rig that
+//! produced it. A manifest describes the running setup in full (resolved
+//! configuration, devices and sensors, zones, triggers, calibration ages,
+//! software versions) and is appended to the session's `manifest.jsonl`
+//! when recording starts and whenever the configuration changes, so every
+//! event can be traced to the exact setup it came from.
+//!
+//! Manifests are signed with HMAC-SHA256 under a per-node key kept in the
+//! data directory (`manifest.key`). Anyone holding the key can check that a
+//! manifest was produced by that node and not edited since; the key ID in
+//! the signature tells which node's key to ask for.
+
+use crate::redact::sha256;
+use crate::{Result, SensorError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Session file holding the manifests, one per line
+pub const MANIFEST_FILE: &str = "manifest.jsonl";
+/// Signing key file in the data directory
+pub const KEY_FILE: &str = "manifest.key";
+/// Signature algorithm name
+pub const ALGORITHM: &str = "hmac-sha256";
+
+/// Per-node secret signing manifests
+#[derive(Clone)]
+pub struct SigningKey {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKey").field("id", &self.id()).finish()
+    }
+}
+
+impl SigningKey {
+    pub fn new(key: Vec<u8>) -> Self {
+        Self { key }
+    }
+    
+    /// Key stored in `data_dir` (`None` if the node has not created one yet)
+    pub fn load(data_dir: &Path) -> Result<Option<Self>> {
+        let path = data_dir.join(KEY_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let hex = std::fs::read_to_string(&path)
+            .map_err(|e| SensorError::Recording(format!("Failed to read {}: {}", path.display(), e)))?;
+        let key = from_hex(hex.trim())
+            .ok_or_else(|| SensorError::InvalidConfig(format!("{} is not a hex key", path.display())))?;
+        Ok(Some(Self { key }))
+    }
+    
+    /// Key stored in `data_dir`, created on first use (readable by the owner only)
+    pub fn load_or_create(data_dir: &Path) -> Result<Self> {
+        if let Some(key) = Self::load(data_dir)? {
+            return Ok(key);
+        }
+        
+        let key = Self { key: random_key()? };
+        std::fs::create_dir_all(data_dir)
+            .map_err(|e| SensorError::Recording(format!("Failed to create {}: {}", data_dir.display(), e)))?;
+        let path = data_dir.join(KEY_FILE);
+        std::fs::write(&path, to_hex(&key.key))
+            .map_err(|e| SensorError::Recording(format!("Failed to write {}: {}", path.display(), e)))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+        }
+        tracing::info!("Created manifest signing key {}", key.id());
+        Ok(key)
+    }
+    
+    /// Public identifier of the key: the start of its SHA-256
+    pub fn id(&self) -> String {
+        to_hex(&sha256(&self.key)[..8])
+    }
+    
+    fn sign(&self, message: &[u8]) -> String {
+        to_hex(&hmac_sha256(&self.key, message))
+    }
+}
+
+/// Signature over a manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    pub algorithm: String,
+    pub key_id: String,
+    /// Hex digest of the manifest's compact JSON
+    pub value: String,
+}
+
+/// A manifest with its signature (a line of `manifest.jsonl`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedManifest {
+    pub manifest: Value,
+    pub signature: ManifestSignature,
+}
+
+impl SignedManifest {
+    /// Sign `manifest` with `key`
+    pub fn sign(manifest: &impl Serialize, key: &SigningKey) -> Result<Self> {
+        let manifest = serde_json::to_value(manifest)
+            .map_err(|e| SensorError::Recording(format!("Serialization error: {}", e)))?;
+        let value = key.sign(&canonical(&manifest));
+        Ok(Self {
+            manifest,
+            signature: ManifestSignature {
+                algorithm: ALGORITHM.to_string(),
+                key_id: key.id(),
+                value,
+            },
+        })
+    }
+    
+    /// Whether the signature matches the manifest under `key`
+    pub fn verify(&self, key: &SigningKey) -> bool {
+        self.signature.algorithm == ALGORITHM
+            && self.signature.key_id == key.id()
+            && constant_time_eq(key.sign(&canonical(&self.manifest)).as_bytes(), self.signature.value.as_bytes())
+    }
+}
+
+/// Serialized form the signature covers
+///
+/// A manifest read back from disk keeps its key order and exact float
+/// values, so it serializes to the same bytes it was signed as.
+fn canonical(manifest: &Value) -> Vec<u8> {
+    serde_json::to_vec(manifest).unwrap_or_default()
+}
+
+/// HMAC (RFC 2104) over SHA-256
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    
+    let mut inner: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(message);
+    let mut outer: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// 32 bytes from the system's random source
+fn random_key() -> Result<Vec<u8>> {
+    use std::io::Read;
+    
+    let mut key = vec![0u8; 32];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut key))
+        .map_err(|e| SensorError::Recording(format!("No random source for a signing key: {}", e)))?;
+    Ok(key)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
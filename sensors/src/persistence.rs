@@ -0,0 +1,64 @@
+//! Anomaly Persistence
+//!
+//! Cheap sensors produce single-sample glitches that clear the anomaly
+//! threshold. Rather than raising thresholds globally, a sensor can require
+//! its anomaly to persist for a number of consecutive samples and/or a
+//! length of time before it is reported as an event.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How long one sensor's anomaly must last
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Persistence {
+    /// Consecutive anomalous samples (1 reports the first one)
+    #[serde(default = "default_samples")]
+    pub samples: u32,
+    /// Seconds from the first anomalous sample
+    #[serde(default)]
+    pub secs: f64,
+}
+
+fn default_samples() -> u32 { 1 }
+
+impl Default for Persistence {
+    fn default() -> Self {
+        Self {
+            samples: default_samples(),
+            secs: 0.0,
+        }
+    }
+}
+
+impl Persistence {
+    /// Whether an anomaly `samples` long that started `elapsed` ago counts
+    pub fn satisfied(&self, samples: u32, elapsed: Duration) -> bool {
+        samples >= self.samples && elapsed.as_secs_f64() >= self.secs
+    }
+    
+    /// Whether every anomalous sample is reported
+    pub fn is_immediate(&self) -> bool {
+        self.samples <= 1 && self.secs <= 0.0
+    }
+}
+
+/// Anomaly persistence settings (`[persistence]` in the app config)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersistenceConfig {
+    /// Requirement of sensors not listed in `sensors`
+    #[serde(flatten)]
+    pub default: Persistence,
+    /// Per-sensor requirements, keyed by sensor type or a part of the sensor name
+    #[serde(default)]
+    pub sensors: BTreeMap<String, Persistence>,
+}
+
+impl PersistenceConfig {
+    /// Requirement of a sensor: an exact type match wins over a name match
+    pub fn for_sensor(&self, sensor_name: &str, sensor_type: &str) -> Persistence {
+        crate::sensor_setting(&self.sensors, sensor_name, sensor_type)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
@@ -0,0 +1,68 @@
+//! Barometric Pressure Trends
+//!
+//! Absolute pressure drifts with the weather, so pressure readings are
+//! not baselined. Instead the fusion engine fits the rate of change over
+//! a short window; a fast drop or rise usually means a door slammed or a
+//! draft moved through, which explains cold spots and motion nearby.
+
+use serde::{Deserialize, Serialize};
+
+/// Pressure trend settings (`[pressure]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureConfig {
+    /// Window the trend is fitted over
+    #[serde(default = "default_window")]
+    pub window_secs: u64,
+    /// Rate of change (hPa per minute, either direction) that raises an event
+    #[serde(default = "default_threshold")]
+    pub threshold_hpa_per_min: f64,
+}
+
+fn default_window() -> u64 { 10 }
+fn default_threshold() -> f64 { 0.5 }
+
+impl Default for PressureConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: default_window(),
+            threshold_hpa_per_min: default_threshold(),
+        }
+    }
+}
+
+/// Pressure in hPa from a reading value in `unit` ("Pa", "kPa" or hPa/mbar)
+pub fn to_hpa(value: f64, unit: &str) -> f64 {
+    match unit {
+        "Pa" => value / 100.0,
+        "kPa" => value * 10.0,
+        _ => value,
+    }
+}
+
+/// Least-squares slope in hPa per minute of `(seconds, hPa)` samples
+///
+/// `None` with fewer than three samples or when they span less than
+/// `min_span_secs`.
+pub fn trend(samples: &[(f64, f64)], min_span_secs: f64) -> Option<f64> {
+    if samples.len() < 3 {
+        return None;
+    }
+    
+    let (first, last) = samples.iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(t, _)| (lo.min(t), hi.max(t)));
+    if last - first < min_span_secs {
+        return None;
+    }
+    
+    let n = samples.len() as f64;
+    let mean_t = samples.iter().map(|s| s.0).sum::<f64>() / n;
+    let mean_p = samples.iter().map(|s| s.1).sum::<f64>() / n;
+    let (num, denom) = samples.iter().fold((0.0, 0.0), |(num, denom), &(t, p)| {
+        (num + (t - mean_t) * (p - mean_p), denom + (t - mean_t).powi(2))
+    });
+    
+    if denom < f64::EPSILON {
+        return None;
+    }
+    Some(num / denom * 60.0)
+}
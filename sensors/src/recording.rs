@@ -2,13 +2,21 @@
 //!
 //! Persistent storage for paranormal events and sensor data.
 
-use crate::{ParanormalEvent, SensorSnapshot, Result, SensorError};
+use crate::heatmap::MotionHeatmap;
+use crate::redact::RedactMode;
+use crate::significance::SignificanceReport;
+use crate::manifest::{SignedManifest, MANIFEST_FILE};
+use crate::summary::{RollingSummaries, SummaryRecord, SUMMARIES_FILE};
+use crate::{ParanormalEvent, SensorSnapshot, Result, SensorError, SCHEMA_VERSION};
+use glowbarn_hal::{buslog, BurstTrace, ClockReport};
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions, create_dir_all};
 use std::io::{Write, BufWriter, BufReader, BufRead};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
 
 /// Recording session
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,9 +25,40 @@ pub struct RecordingSession {
     pub name: String,
     pub location: String,
     pub start_time: DateTime<Utc>,
+    #[serde(default)]
     pub end_time: Option<DateTime<Utc>>,
+    #[serde(default)]
     pub event_count: usize,
+    #[serde(default)]
     pub notes: Vec<String>,
+    /// Operator annotations, in the order they were added
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+}
+
+/// Note added by a team member, e.g. from a phone during the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Sequence number within the session
+    pub id: u32,
+    pub author: String,
+    pub timestamp: DateTime<Utc>,
+    pub text: String,
+    /// Event the annotation comments on
+    #[serde(default)]
+    pub event_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl std::fmt::Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}: {}", self.timestamp.format("%H:%M:%S"), self.author, self.text)?;
+        for tag in &self.tags {
+            write!(f, " #{}", tag)?;
+        }
+        Ok(())
+    }
 }
 
 impl RecordingSession {
@@ -34,6 +73,7 @@ impl RecordingSession {
             end_time: None,
             event_count: 0,
             notes: Vec::new(),
+            annotations: Vec::new(),
         }
     }
     
@@ -41,6 +81,25 @@ impl RecordingSession {
         self.notes.push(format!("[{}] {}", Utc::now().format("%H:%M:%S"), note));
     }
     
+    /// Add an annotation stamped now
+    pub fn annotate(&mut self, author: &str, text: &str, event_id: Option<&str>, tags: &[String]) -> &Annotation {
+        let id = self.annotations.last().map_or(1, |a| a.id + 1);
+        self.annotations.push(Annotation {
+            id,
+            author: author.to_string(),
+            timestamp: Utc::now(),
+            text: text.to_string(),
+            event_id: event_id.map(str::to_string),
+            tags: tags.to_vec(),
+        });
+        self.annotations.last().unwrap()
+    }
+    
+    /// Annotations on `event_id`
+    pub fn annotations_for<'a>(&'a self, event_id: &'a str) -> impl Iterator<Item = &'a Annotation> {
+        self.annotations.iter().filter(move |a| a.event_id.as_deref() == Some(event_id))
+    }
+    
     pub fn end(&mut self) {
         self.end_time = Some(Utc::now());
     }
@@ -51,6 +110,57 @@ impl RecordingSession {
     }
 }
 
+/// Which sensor samples are written to `sensors.jsonl`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorLogMode {
+    /// No sensor log
+    Off,
+    /// Every sample
+    Full,
+    /// Samples off baseline, plus periodic keepalives
+    Adaptive,
+}
+
+/// Sensor log settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SensorLogPolicy {
+    pub mode: SensorLogMode,
+    /// Deviation (standard deviations) above which adaptive mode logs a sample
+    pub delta: f64,
+    /// Longest gap between logged samples of a sensor in adaptive mode
+    pub keepalive: Duration,
+}
+
+impl Default for SensorLogPolicy {
+    fn default() -> Self {
+        Self {
+            mode: SensorLogMode::Full,
+            delta: 0.5,
+            keepalive: Duration::from_secs(60),
+        }
+    }
+}
+
+impl SensorLogPolicy {
+    /// Whether a sample should be written, given when the sensor was last logged
+    ///
+    /// Samples without a baseline yet are always logged in adaptive mode.
+    pub fn should_log(&self, snapshot: &SensorSnapshot, last_logged: Option<SystemTime>, now: SystemTime) -> bool {
+        match self.mode {
+            SensorLogMode::Off => false,
+            SensorLogMode::Full => true,
+            SensorLogMode::Adaptive => {
+                let deviates = snapshot.deviation.is_none_or(|z| z.abs() > self.delta);
+                let keepalive_due = last_logged
+                    .and_then(|t| now.duration_since(t).ok())
+                    .is_none_or(|gap| gap >= self.keepalive);
+                deviates || keepalive_due
+            }
+        }
+    }
+}
+
 /// Event recorder
 pub struct EventRecorder {
     base_path: PathBuf,
@@ -58,6 +168,11 @@ pub struct EventRecorder {
     event_writer: Option<BufWriter<File>>,
     sensor_writer: Option<BufWriter<File>>,
     max_file_size: usize,
+    sensor_bytes: usize,
+    log_policy: SensorLogPolicy,
+    last_logged: HashMap<String, SystemTime>,
+    /// Per-sensor window statistics for `summaries.jsonl`, if enabled
+    summaries: Option<RollingSummaries>,
 }
 
 impl EventRecorder {
@@ -72,6 +187,10 @@ impl EventRecorder {
             event_writer: None,
             sensor_writer: None,
             max_file_size: 100 * 1024 * 1024,  // 100 MB
+            sensor_bytes: 0,
+            log_policy: SensorLogPolicy::default(),
+            last_logged: HashMap::new(),
+            summaries: Some(RollingSummaries::new(Duration::from_secs(60))),
         })
     }
     
@@ -105,8 +224,18 @@ impl EventRecorder {
         std::fs::write(&metadata_path, metadata_json)
             .map_err(|e| SensorError::Recording(format!("Failed to write metadata: {}", e)))?;
         
+        // Bus traffic goes to the session too (only written while bus logging is enabled)
+        if let Err(e) = buslog::logger().open(&session_path.join("bus.jsonl")) {
+            tracing::warn!("Failed to open bus log: {}", e);
+        }
+        
         self.event_writer = Some(BufWriter::new(event_file));
         self.sensor_writer = Some(BufWriter::new(sensor_file));
+        self.sensor_bytes = 0;
+        self.last_logged.clear();
+        if let Some(ref mut summaries) = self.summaries {
+            summaries.flush();
+        }
         self.session = Some(session);
         
         tracing::info!("Recording session started: {}", name);
@@ -116,6 +245,10 @@ impl EventRecorder {
     
     /// End current session
     pub fn end_session(&mut self) -> Result<Option<RecordingSession>> {
+        if let Some(records) = self.summaries.as_mut().map(RollingSummaries::flush) {
+            self.record_summaries(&records);
+        }
+        
         if let Some(mut session) = self.session.take() {
             session.end();
             
@@ -139,6 +272,7 @@ impl EventRecorder {
             
             self.event_writer = None;
             self.sensor_writer = None;
+            buslog::logger().close();
             
             tracing::info!("Recording session ended: {} ({} events)", 
                 session.name, session.event_count);
@@ -169,11 +303,30 @@ impl EventRecorder {
         Ok(())
     }
     
-    /// Record sensor snapshot
+    /// Record sensor snapshot, subject to the sensor log policy
+    ///
+    /// Every snapshot counts towards the rolling summaries, whether or not
+    /// the sample itself is logged.
     pub fn record_sensor(&mut self, snapshot: &SensorSnapshot) -> Result<()> {
+        let now = SystemTime::now();
+        if self.session.is_some() {
+            if let Some(records) = self.summaries.as_mut().map(|s| s.add(&snapshot.sensor_name, &snapshot.unit, snapshot.value, now)) {
+                self.record_summaries(&records);
+            }
+        }
+        
         if let Some(ref mut writer) = self.sensor_writer {
+            let last_logged = self.last_logged.get(&snapshot.sensor_name).copied();
+            if !self.log_policy.should_log(snapshot, last_logged, now) {
+                return Ok(());
+            }
+            
+            if self.sensor_bytes >= self.max_file_size {
+                return Err(SensorError::Recording("Sensor log size limit reached".to_string()));
+            }
+            
             let record = SensorRecord {
-                timestamp: SystemTime::now(),
+                timestamp: now,
                 sensor_name: snapshot.sensor_name.clone(),
                 value: snapshot.value,
                 unit: snapshot.unit.clone(),
@@ -184,11 +337,145 @@ impl EventRecorder {
             
             writeln!(writer, "{}", json)
                 .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+            self.sensor_bytes += json.len() + 1;
+            self.last_logged.insert(snapshot.sensor_name.clone(), now);
         }
         
         Ok(())
     }
     
+    /// Attach high-rate burst traces to an event (`bursts/<event_id>.json`)
+    ///
+    /// Does nothing when no session is being recorded.
+    pub fn record_burst(&self, event_id: &str, traces: &[BurstTrace]) -> Result<()> {
+        let Some(ref session) = self.session else {
+            return Ok(());
+        };
+        
+        let dir = self.base_path.join(&session.id).join("bursts");
+        create_dir_all(&dir)
+            .map_err(|e| SensorError::Recording(format!("Failed to create burst dir: {}", e)))?;
+        
+        let json = serde_json::to_string(traces)
+            .map_err(|e| SensorError::Recording(format!("Serialization error: {}", e)))?;
+        std::fs::write(dir.join(format!("{}.json", event_id)), json)
+            .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+        
+        Ok(())
+    }
+    
+    /// Where an audio clip of an event belongs (`clips/<event_id>_<sensor>.wav`),
+    /// and that path relative to the session directory
+    ///
+    /// `None` when no session is being recorded.
+    pub fn clip_path(&self, event_id: &str, sensor: &str) -> Option<(PathBuf, String)> {
+        let session = self.session.as_ref()?;
+        let file: String = format!("{}_{}.wav", event_id, sensor).chars()
+            .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+            .collect();
+        let relative = format!("clips/{}", file);
+        Some((self.base_path.join(&session.id).join(&relative), relative))
+    }
+    
+    /// Save a camera's motion heatmap (`heatmaps/<camera>.json`), replacing
+    /// the previous save
+    ///
+    /// Does nothing when no session is being recorded.
+    pub fn record_heatmap(&self, heatmap: &MotionHeatmap) -> Result<()> {
+        let Some(ref session) = self.session else {
+            return Ok(());
+        };
+        
+        let dir = self.base_path.join(&session.id).join("heatmaps");
+        create_dir_all(&dir)
+            .map_err(|e| SensorError::Recording(format!("Failed to create heatmap dir: {}", e)))?;
+        
+        let json = serde_json::to_string(heatmap)
+            .map_err(|e| SensorError::Recording(format!("Serialization error: {}", e)))?;
+        std::fs::write(dir.join(format!("{}.json", heatmap.camera)), json)
+            .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+        
+        Ok(())
+    }
+    
+    /// Save the session's stream clock alignment (`clock.json`), replacing
+    /// the previous save
+    ///
+    /// Does nothing when no session is being recorded.
+    pub fn record_clock(&self, report: &ClockReport) -> Result<()> {
+        let Some(ref session) = self.session else {
+            return Ok(());
+        };
+        
+        let json = serde_json::to_string_pretty(report)
+            .map_err(|e| SensorError::Recording(format!("Serialization error: {}", e)))?;
+        std::fs::write(self.base_path.join(&session.id).join("clock.json"), json)
+            .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+        
+        Ok(())
+    }
+    
+    /// Append a periodic status record to the session's `heartbeat.jsonl`
+    ///
+    /// Does nothing when no session is being recorded.
+    pub fn record_heartbeat<T: Serialize>(&self, record: &T) -> Result<()> {
+        self.append_record("heartbeat.jsonl", record)
+    }
+    
+    /// Append a wideband sweep summary to the session's `sweeps.jsonl`
+    ///
+    /// Does nothing when no session is being recorded.
+    pub fn record_sweep<T: Serialize>(&self, summary: &T) -> Result<()> {
+        self.append_record("sweeps.jsonl", summary)
+    }
+    
+    fn record_summaries(&self, records: &[SummaryRecord]) {
+        for record in records {
+            if let Err(e) = self.append_record(SUMMARIES_FILE, record) {
+                tracing::warn!("Sensor summary not saved: {}", e);
+                return;
+            }
+        }
+    }
+    
+    fn append_record<T: Serialize>(&self, file_name: &str, record: &T) -> Result<()> {
+        let Some(ref session) = self.session else {
+            return Ok(());
+        };
+        
+        let json = serde_json::to_string(record)
+            .map_err(|e| SensorError::Recording(format!("Serialization error: {}", e)))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.base_path.join(&session.id).join(file_name))
+            .map_err(|e| SensorError::Recording(format!("Failed to open {}: {}", file_name, e)))?;
+        writeln!(file, "{}", json)
+            .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+        
+        Ok(())
+    }
+    
+    /// Set maximum sensor log size in bytes
+    pub fn set_max_file_size(&mut self, bytes: usize) {
+        self.max_file_size = bytes;
+    }
+    
+    /// Choose which sensor samples are logged
+    pub fn set_log_policy(&mut self, policy: SensorLogPolicy) {
+        self.log_policy = policy;
+    }
+    
+    /// Length of the rolling summary windows (`None` disables summaries)
+    pub fn set_summary_interval(&mut self, interval: Option<Duration>) {
+        if self.summaries.as_ref().map(RollingSummaries::interval) != interval {
+            if let Some(records) = self.summaries.as_mut().map(RollingSummaries::flush) {
+                self.record_summaries(&records);
+            }
+            self.summaries = interval.map(RollingSummaries::new);
+        }
+    }
+    
     /// Flush writers
     pub fn flush(&mut self) -> Result<()> {
         if let Some(ref mut writer) = self.event_writer {
@@ -199,6 +486,7 @@ impl EventRecorder {
             writer.flush()
                 .map_err(|e| SensorError::Recording(format!("Flush error: {}", e)))?;
         }
+        buslog::logger().flush()?;
         Ok(())
     }
     
@@ -209,6 +497,29 @@ impl EventRecorder {
         }
     }
     
+    /// Annotate the current session
+    ///
+    /// The session metadata is rewritten right away, so annotations survive
+    /// a crash before the session ends.
+    pub fn annotate(&mut self, author: &str, text: &str, event_id: Option<&str>, tags: &[String]) -> Result<Option<Annotation>> {
+        let Some(ref mut session) = self.session else {
+            return Ok(None);
+        };
+        let annotation = session.annotate(author, text, event_id, tags).clone();
+        
+        let metadata_json = serde_json::to_string_pretty(&*session)
+            .map_err(|e| SensorError::Recording(format!("Failed to serialize session: {}", e)))?;
+        std::fs::write(self.base_path.join(&session.id).join("session.json"), metadata_json)
+            .map_err(|e| SensorError::Recording(format!("Failed to write metadata: {}", e)))?;
+        
+        Ok(Some(annotation))
+    }
+    
+    /// Session being recorded, if any
+    pub fn current_session(&self) -> Option<&RecordingSession> {
+        self.session.as_ref()
+    }
+    
     /// List all sessions
     pub fn list_sessions(&self) -> Result<Vec<RecordingSession>> {
         let mut sessions = Vec::new();
@@ -233,7 +544,7 @@ impl EventRecorder {
         }
         
         // Sort by start time (newest first)
-        sessions.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.start_time));
         
         Ok(sessions)
     }
@@ -247,42 +558,230 @@ impl EventRecorder {
         
         let reader = BufReader::new(file);
         let mut events = Vec::new();
+        let mut skipped = 0;
         
         for line in reader.lines() {
             let line = line.map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?;
             
-            if let Ok(event) = serde_json::from_str::<ParanormalEvent>(&line) {
-                events.push(event);
+            match serde_json::from_str::<ParanormalEvent>(&line) {
+                Ok(event) => events.push(event),
+                Err(e) => {
+                    tracing::debug!("Unreadable event in {}: {}", session_id, e);
+                    skipped += 1;
+                }
             }
         }
         
+        if skipped > 0 {
+            tracing::warn!("Skipped {} unreadable events in {}", skipped, session_id);
+        }
+        if let Some(newest) = events.iter().map(|e| e.schema_version).max().filter(|v| *v > SCHEMA_VERSION) {
+            tracing::warn!("Session {} has schema version {} (this build reads {}); newer fields are ignored",
+                session_id, newest, SCHEMA_VERSION);
+        }
+        
         Ok(events)
     }
     
-    /// Export session to portable format
-    pub fn export_session(&self, session_id: &str, output_path: &Path) -> Result<()> {
-        let session_path = self.base_path.join(session_id);
-        
-        // Load session metadata
-        let metadata_path = session_path.join("session.json");
-        let session: RecordingSession = serde_json::from_str(
+    /// Load session metadata
+    pub fn load_session(&self, session_id: &str) -> Result<RecordingSession> {
+        let metadata_path = self.base_path.join(session_id).join("session.json");
+        serde_json::from_str(
             &std::fs::read_to_string(&metadata_path)
                 .map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?
-        ).map_err(|e| SensorError::Recording(format!("Parse error: {}", e)))?;
+        ).map_err(|e| SensorError::Recording(format!("Parse error: {}", e)))
+    }
+    
+    /// Annotate a recorded session, e.g. with a reviewer's verdict on one of its events
+    ///
+    /// Writes to the session's `session.json`; the session being recorded
+    /// is annotated with [`annotate`](Self::annotate) instead.
+    pub fn annotate_stored(&self, session_id: &str, author: &str, text: &str, event_id: Option<&str>, tags: &[String]) -> Result<Annotation> {
+        if self.session.as_ref().is_some_and(|s| s.id == session_id) {
+            return Err(SensorError::Recording(format!("Session {} is being recorded", session_id)));
+        }
         
-        // Load events
-        let events = self.load_events(session_id)?;
+        let mut session = self.load_session(session_id)?;
+        let annotation = session.annotate(author, text, event_id, tags).clone();
+        let metadata_json = serde_json::to_string_pretty(&session)
+            .map_err(|e| SensorError::Recording(format!("Failed to serialize session: {}", e)))?;
+        std::fs::write(self.base_path.join(session_id).join("session.json"), metadata_json)
+            .map_err(|e| SensorError::Recording(format!("Failed to write metadata: {}", e)))?;
+        Ok(annotation)
+    }
+    
+    /// Load recorded sensor samples in time order (empty if none were recorded)
+    pub fn load_sensor_records(&self, session_id: &str) -> Result<Vec<SensorRecord>> {
+        let mut records: Vec<SensorRecord> = self.load_records(session_id, "sensors.jsonl")?;
+        records.sort_by_key(|r| r.timestamp);
+        Ok(records)
+    }
+    
+    /// Add samples from elsewhere (e.g. handheld loggers) to a session's
+    /// sensor log, bypassing the log policy
+    pub fn import_sensor_records(&mut self, session_id: &str, records: &[SensorRecord]) -> Result<()> {
+        let mut lines = String::new();
+        for record in records {
+            let json = serde_json::to_string(record)
+                .map_err(|e| SensorError::Recording(format!("Serialization error: {}", e)))?;
+            lines.push_str(&json);
+            lines.push('\n');
+        }
         
-        // Create export structure
-        let export = SessionExport {
-            session,
-            events,
+        let recording = self.session.as_ref().is_some_and(|s| s.id == session_id);
+        match &mut self.sensor_writer {
+            Some(writer) if recording => {
+                writer.write_all(lines.as_bytes())
+                    .and_then(|_| writer.flush())
+                    .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+                self.sensor_bytes += lines.len();
+            }
+            _ => {
+                let session_path = self.base_path.join(session_id);
+                if !session_path.join("session.json").exists() {
+                    return Err(SensorError::Recording(format!("No session {}", session_id)));
+                }
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(session_path.join("sensors.jsonl"))
+                    .and_then(|mut file| file.write_all(lines.as_bytes()))
+                    .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Load a session's rolling sensor summaries in time order (empty if none were recorded)
+    pub fn load_summaries(&self, session_id: &str) -> Result<Vec<SummaryRecord>> {
+        self.load_records(session_id, SUMMARIES_FILE)
+    }
+    
+    /// Append a signed rig manifest to the running session
+    pub fn record_manifest(&self, manifest: &SignedManifest) -> Result<()> {
+        self.append_record(MANIFEST_FILE, manifest)
+    }
+    
+    /// Load the rig manifests of a session, oldest first (empty if none were saved)
+    pub fn load_manifests(&self, session_id: &str) -> Result<Vec<SignedManifest>> {
+        self.load_records(session_id, MANIFEST_FILE)
+    }
+    
+    /// Load wideband sweep summaries (empty if none were recorded)
+    pub fn load_sweeps<T: DeserializeOwned>(&self, session_id: &str) -> Result<Vec<T>> {
+        self.load_records(session_id, "sweeps.jsonl")
+    }
+    
+    fn load_records<T: DeserializeOwned>(&self, session_id: &str, file_name: &str) -> Result<Vec<T>> {
+        let path = self.base_path.join(session_id).join(file_name);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let file = File::open(&path)
+            .map_err(|e| SensorError::Recording(format!("Open error: {}", e)))?;
+        
+        Ok(BufReader::new(file)
+            .lines()
+            .map_while(|line| line.ok())
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect())
+    }
+    
+    /// Load the burst traces attached to an event (empty if none were captured)
+    pub fn load_burst(&self, session_id: &str, event_id: &str) -> Result<Vec<BurstTrace>> {
+        let path = self.base_path.join(session_id).join("bursts").join(format!("{}.json", event_id));
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        
+        serde_json::from_str(
+            &std::fs::read_to_string(&path)
+                .map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?
+        ).map_err(|e| SensorError::Recording(format!("Parse error: {}", e)))
+    }
+    
+    /// Load the session's stream clock alignment, if it was saved
+    pub fn load_clock(&self, session_id: &str) -> Result<Option<ClockReport>> {
+        let path = self.base_path.join(session_id).join("clock.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        serde_json::from_str(
+            &std::fs::read_to_string(&path)
+                .map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?
+        ).map(Some).map_err(|e| SensorError::Recording(format!("Parse error: {}", e)))
+    }
+    
+    /// Save a session's comparison with its control window
+    /// (`significance.json`), replacing the previous one
+    pub fn record_significance(&self, report: &SignificanceReport) -> Result<()> {
+        let json = serde_json::to_string_pretty(report)
+            .map_err(|e| SensorError::Recording(format!("Serialization error: {}", e)))?;
+        std::fs::write(self.base_path.join(&report.session_id).join("significance.json"), json)
+            .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))
+    }
+    
+    /// Load the session's comparison with its control window, if one was saved
+    pub fn load_significance(&self, session_id: &str) -> Result<Option<SignificanceReport>> {
+        let path = self.base_path.join(session_id).join("significance.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        serde_json::from_str(
+            &std::fs::read_to_string(&path)
+                .map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?
+        ).map(Some).map_err(|e| SensorError::Recording(format!("Parse error: {}", e)))
+    }
+    
+    /// Load the session's saved motion heatmaps (empty if none were saved)
+    pub fn load_heatmaps(&self, session_id: &str) -> Result<Vec<MotionHeatmap>> {
+        let dir = self.base_path.join(session_id).join("heatmaps");
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let mut heatmaps = Vec::new();
+        for entry in std::fs::read_dir(&dir)
+            .map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?
+        {
+            let path = entry.map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?;
+                heatmaps.push(serde_json::from_str(&content)
+                    .map_err(|e| SensorError::Recording(format!("Parse error: {}", e)))?);
+            }
+        }
+        heatmaps.sort_by(|a: &MotionHeatmap, b| a.camera.cmp(&b.camera));
+        Ok(heatmaps)
+    }
+    
+    /// Export session to portable format
+    pub fn export_session(&self, session_id: &str, output_path: &Path) -> Result<()> {
+        Self::write_export(&self.session_export(session_id)?, output_path)
+    }
+    
+    /// Session and events as an export, e.g. to redact before writing
+    pub fn session_export(&self, session_id: &str) -> Result<SessionExport> {
+        Ok(SessionExport {
+            session: self.load_session(session_id)?,
+            events: self.load_events(session_id)?,
             exported_at: Utc::now(),
             version: "1.0".to_string(),
-        };
+            schema_version: SCHEMA_VERSION,
+            redacted: None,
+        })
+    }
+    
+    /// Write an export as JSON
+    pub fn write_export(export: &SessionExport, output_path: &Path) -> Result<()> {
+        let session_id = &export.session.id;
         
         // Write to output file
-        let json = serde_json::to_string_pretty(&export)
+        let json = serde_json::to_string_pretty(export)
             .map_err(|e| SensorError::Recording(format!("Serialize error: {}", e)))?;
         
         std::fs::write(output_path, json)
@@ -292,20 +791,75 @@ impl EventRecorder {
         
         Ok(())
     }
+    
+    /// Read a session export, whichever GlowBarn version wrote it
+    pub fn load_export(path: &Path) -> Result<SessionExport> {
+        let export: SessionExport = serde_json::from_str(
+            &std::fs::read_to_string(path)
+                .map_err(|e| SensorError::Recording(format!("Read error: {}", e)))?
+        ).map_err(|e| SensorError::Recording(format!("Parse error: {}", e)))?;
+        
+        if export.schema_version > SCHEMA_VERSION {
+            tracing::warn!("{:?} has schema version {} (this build reads {}); newer fields are ignored",
+                path, export.schema_version, SCHEMA_VERSION);
+        }
+        Ok(export)
+    }
+    
+    /// Unpack an export into the data directory as a regular session
+    ///
+    /// Fails if a session with the same ID already exists.
+    pub fn import_export(&self, export: &SessionExport) -> Result<()> {
+        let session_path = self.base_path.join(&export.session.id);
+        if session_path.exists() {
+            return Err(SensorError::Recording(format!("Session {} already exists", export.session.id)));
+        }
+        create_dir_all(&session_path)
+            .map_err(|e| SensorError::Recording(format!("Failed to create session directory: {}", e)))?;
+        
+        let metadata_json = serde_json::to_string_pretty(&export.session)
+            .map_err(|e| SensorError::Recording(format!("Serialize error: {}", e)))?;
+        std::fs::write(session_path.join("session.json"), metadata_json)
+            .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+        
+        let mut events = String::new();
+        for event in &export.events {
+            let json = serde_json::to_string(event)
+                .map_err(|e| SensorError::Recording(format!("Serialize error: {}", e)))?;
+            events.push_str(&json);
+            events.push('\n');
+        }
+        std::fs::write(session_path.join("events.jsonl"), events)
+            .map_err(|e| SensorError::Recording(format!("Write error: {}", e)))?;
+        
+        tracing::info!("Imported session {} ({} events)", export.session.id, export.events.len());
+        Ok(())
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct SensorRecord {
-    timestamp: SystemTime,
-    sensor_name: String,
-    value: f64,
-    unit: String,
+/// Recorded sensor sample (sensors.jsonl)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorRecord {
+    pub timestamp: SystemTime,
+    pub sensor_name: String,
+    pub value: f64,
+    pub unit: String,
 }
 
+/// Portable session export (`glowbarn-cli export --format json`)
 #[derive(Debug, Serialize, Deserialize)]
-struct SessionExport {
-    session: RecordingSession,
-    events: Vec<ParanormalEvent>,
-    exported_at: DateTime<Utc>,
-    version: String,
+pub struct SessionExport {
+    pub session: RecordingSession,
+    #[serde(default)]
+    pub events: Vec<ParanormalEvent>,
+    pub exported_at: DateTime<Utc>,
+    /// Export format label, kept for readers that predate `schema_version`
+    #[serde(default)]
+    pub version: String,
+    /// Schema of the exported session and events
+    #[serde(default = "crate::legacy_schema")]
+    pub schema_version: u32,
+    /// How identifying fields were redacted, if they were
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redacted: Option<RedactMode>,
 }
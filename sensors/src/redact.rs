@@ -0,0 +1,339 @@
+//! Export Redaction
+//!
+//! Sessions recorded in private homes cannot be published as they are: the
+//! session and event locations name the residence, GPS fixes pinpoint it and
+//! annotations carry the team's names. A [`Redaction`] rewrites a session
+//! before export, either stripping those fields or replacing them with salted
+//! hashes, which keep "the same operator" and "the same room" recognizable
+//! without revealing who or where. Occurrences of the redacted values in free
+//! text (notes, annotation text, event metadata) are replaced as well.
+//!
+//! Camera frames can have faces pixelated by [`blur_faces`], a skin-tone
+//! region detector that is cheap enough for a Pi but will miss faces in poor
+//! light and catch the odd wooden beam.
+
+use crate::recording::{RecordingSession, SessionExport};
+use crate::ParanormalEvent;
+use glowbarn_hal::camera::{Frame, PixelFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// How identifying fields are rewritten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactMode {
+    /// Replace with a fixed placeholder
+    Strip,
+    /// Replace with a salted hash, stable within one salt
+    Hash,
+}
+
+impl std::str::FromStr for RedactMode {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "strip" => Ok(Self::Strip),
+            "hash" => Ok(Self::Hash),
+            _ => Err(format!("unknown redaction mode: {} (expected strip or hash)", s)),
+        }
+    }
+}
+
+/// Redaction of locations, GPS coordinates and operator identities
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    mode: RedactMode,
+    salt: String,
+    replacements: Vec<(String, String)>,
+}
+
+impl Redaction {
+    /// Redaction with a random salt, so hashes differ between exports
+    pub fn new(mode: RedactMode) -> Self {
+        use std::hash::{BuildHasher, Hasher};
+        let salt = std::collections::hash_map::RandomState::new().build_hasher().finish();
+        Self::with_salt(mode, &format!("{:016x}", salt))
+    }
+    
+    /// Redaction with a fixed salt, so several exports hash alike
+    pub fn with_salt(mode: RedactMode, salt: &str) -> Self {
+        Self {
+            mode,
+            salt: salt.to_string(),
+            replacements: Vec::new(),
+        }
+    }
+    
+    pub fn mode(&self) -> RedactMode {
+        self.mode
+    }
+    
+    /// Redact a session export in place
+    pub fn export(&mut self, export: &mut SessionExport) {
+        self.session(&mut export.session, &mut export.events);
+        export.redacted = Some(self.mode);
+    }
+    
+    /// Redact a session and its events in place
+    pub fn session(&mut self, session: &mut RecordingSession, events: &mut [ParanormalEvent]) {
+        session.location = self.replace("location", &session.location);
+        for annotation in &mut session.annotations {
+            annotation.author = self.replace("operator", &annotation.author);
+        }
+        for event in events.iter_mut() {
+            if let Some(location) = &mut event.location {
+                location.name = self.replace("location", &location.name);
+                location.latitude = None;
+                location.longitude = None;
+                location.altitude = None;
+            }
+        }
+        
+        // Free text last, once every identifying value is known
+        for note in &mut session.notes {
+            *note = self.scrub(note);
+        }
+        for annotation in &mut session.annotations {
+            annotation.text = self.scrub(&annotation.text);
+        }
+        for event in events.iter_mut() {
+            for value in event.metadata.values_mut() {
+                *value = self.scrub(value);
+            }
+        }
+    }
+    
+    /// Replacement for an identifying `value` of some `kind`
+    fn replace(&mut self, kind: &str, value: &str) -> String {
+        let replacement = match self.mode {
+            RedactMode::Strip => "[redacted]".to_string(),
+            RedactMode::Hash => {
+                let digest = sha256(format!("{}\0{}\0{}", self.salt, kind, value).as_bytes());
+                let hex: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+                format!("{}-{}", kind, hex)
+            }
+        };
+        
+        // Very short values would mangle unrelated words in free text
+        if value.chars().count() >= 3 && !self.replacements.iter().any(|(v, _)| v == value) {
+            self.replacements.push((value.to_string(), replacement.clone()));
+        }
+        replacement
+    }
+    
+    /// Replace redacted values inside free text, longest first
+    fn scrub(&self, text: &str) -> String {
+        let mut replacements: Vec<&(String, String)> = self.replacements.iter().collect();
+        replacements.sort_by_key(|(value, _)| std::cmp::Reverse(value.len()));
+        
+        let mut text = text.to_string();
+        for (value, replacement) in replacements {
+            text = text.replace(value.as_str(), replacement);
+        }
+        text
+    }
+}
+
+/// Skin-coloured pixels a cell needs to count as skin (fraction)
+const SKIN_FILL: f64 = 0.5;
+/// Smallest face candidate (cells)
+const MIN_FACE_CELLS: usize = 4;
+/// Detection cell size (pixels)
+const CELL_PX: u32 = 8;
+
+/// Pixelate face-like skin regions of a frame in place
+///
+/// Works on RGB24, BGR24 and YUYV frames; other formats carry no colour
+/// and are left alone. Returns the number of regions pixelated.
+pub fn blur_faces(frame: &mut Frame) -> usize {
+    let (unit_bytes, unit_px) = match frame.format {
+        PixelFormat::RGB24 | PixelFormat::BGR24 => (3, 1),
+        PixelFormat::YUYV => (4, 2),
+        _ => return 0,
+    };
+    let (width, height) = (frame.width, frame.height);
+    if frame.data.len() < (width * height) as usize * unit_bytes / unit_px as usize {
+        return 0;
+    }
+    
+    // Skin map on a coarse grid
+    let (columns, rows) = (width.div_ceil(CELL_PX), height.div_ceil(CELL_PX));
+    let mut skin_px = vec![0u32; (columns * rows) as usize];
+    let mut cell_px = vec![0u32; skin_px.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let cell = ((y / CELL_PX) * columns + x / CELL_PX) as usize;
+            cell_px[cell] += 1;
+            let (cb, cr) = chroma(frame, x, y);
+            if (77..=127).contains(&cb) && (133..=173).contains(&cr) {
+                skin_px[cell] += 1;
+            }
+        }
+    }
+    let skin: Vec<bool> = skin_px.iter().zip(&cell_px)
+        .map(|(&s, &n)| n > 0 && s as f64 / n as f64 >= SKIN_FILL)
+        .collect();
+    
+    // Face-shaped connected regions
+    let mut seen = vec![false; skin.len()];
+    let mut regions = Vec::new();
+    for start in 0..skin.len() {
+        if !skin[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut queue = VecDeque::from([start]);
+        let (mut cells, mut x0, mut y0, mut x1, mut y1) = (0, u32::MAX, u32::MAX, 0, 0);
+        while let Some(cell) = queue.pop_front() {
+            let (cx, cy) = (cell as u32 % columns, cell as u32 / columns);
+            cells += 1;
+            (x0, y0, x1, y1) = (x0.min(cx), y0.min(cy), x1.max(cx), y1.max(cy));
+            
+            let neighbours = [
+                (cx > 0).then(|| cell - 1),
+                (cx + 1 < columns).then(|| cell + 1),
+                (cy > 0).then(|| cell - columns as usize),
+                (cy + 1 < rows).then(|| cell + columns as usize),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if skin[next] && !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        
+        let (w, h) = ((x1 - x0 + 1) as f64, (y1 - y0 + 1) as f64);
+        let aspect = h / w;
+        if cells >= MIN_FACE_CELLS && (0.8..=2.0).contains(&aspect) && cells as f64 / (w * h) >= 0.4 {
+            regions.push((x0, y0, x1, y1));
+        }
+    }
+    
+    for &(x0, y0, x1, y1) in &regions {
+        // One cell of margin for hair and jaw lines
+        let left = x0.saturating_sub(1) * CELL_PX;
+        let top = y0.saturating_sub(1) * CELL_PX;
+        let right = ((x1 + 2) * CELL_PX).min(width);
+        let bottom = ((y1 + 2) * CELL_PX).min(height);
+        let block = ((right - left) / 6).max(CELL_PX).next_multiple_of(unit_px);
+        pixelate(frame, unit_bytes, unit_px, (left, top, right, bottom), block);
+    }
+    regions.len()
+}
+
+/// (Cb, Cr) of a pixel
+fn chroma(frame: &Frame, x: u32, y: u32) -> (u8, u8) {
+    let i = (y * frame.width + x) as usize;
+    let data = &frame.data;
+    match frame.format {
+        PixelFormat::YUYV => {
+            let pair = (i / 2) * 4;
+            (data[pair + 1], data[pair + 3])
+        }
+        format => {
+            let (r, g, b) = match format {
+                PixelFormat::BGR24 => (data[i * 3 + 2], data[i * 3 + 1], data[i * 3]),
+                _ => (data[i * 3], data[i * 3 + 1], data[i * 3 + 2]),
+            };
+            let (r, g, b) = (r as f64, g as f64, b as f64);
+            let cb = 128.0 - 0.168736 * r - 0.331264 * g + 0.5 * b;
+            let cr = 128.0 + 0.5 * r - 0.418688 * g - 0.081312 * b;
+            (cb.clamp(0.0, 255.0) as u8, cr.clamp(0.0, 255.0) as u8)
+        }
+    }
+}
+
+/// Replace each `block`-sized square of a region with its mean
+///
+/// Pixels are handled in units (one RGB pixel, or a YUYV pixel pair) so
+/// shared chroma stays consistent.
+fn pixelate(frame: &mut Frame, unit_bytes: usize, unit_px: u32, (left, top, right, bottom): (u32, u32, u32, u32), block: u32) {
+    let stride = (frame.width / unit_px) as usize * unit_bytes;
+    let (left, right) = (left / unit_px, right / unit_px);
+    let block_units = (block / unit_px).max(1);
+    
+    for by in (top..bottom).step_by(block as usize) {
+        for bx in (left..right).step_by(block_units as usize) {
+            let (ys, xs) = (by..(by + block).min(bottom), bx..(bx + block_units).min(right));
+            let offset = |y: u32, x: u32| y as usize * stride + x as usize * unit_bytes;
+            
+            let mut sums = vec![0u64; unit_bytes];
+            let mut count = 0u64;
+            for y in ys.clone() {
+                for x in xs.clone() {
+                    let at = offset(y, x);
+                    for (sum, &byte) in sums.iter_mut().zip(&frame.data[at..at + unit_bytes]) {
+                        *sum += byte as u64;
+                    }
+                    count += 1;
+                }
+            }
+            let mean: Vec<u8> = sums.iter().map(|s| (s / count.max(1)) as u8).collect();
+            for y in ys.clone() {
+                for x in xs.clone() {
+                    let at = offset(y, x);
+                    frame.data[at..at + unit_bytes].copy_from_slice(&mean);
+                }
+            }
+        }
+    }
+}
+
+/// SHA-256 digest (FIPS 180-4)
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+    ];
+    
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+    
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for (k, w) in K.iter().zip(w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(*k).wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (hh, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+    
+    let mut digest = [0u8; 32];
+    for (bytes, word) in digest.chunks_mut(4).zip(h) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
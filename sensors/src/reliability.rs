@@ -0,0 +1,358 @@
+//! Confidence Reliability
+//!
+//! An event reported at 80% confidence should turn out genuine about four
+//! times in five. Reviewers label events by annotating them with a
+//! `confirmed` or `debunked` tag (`glowbarn-cli label`, or `annotate -t`
+//! while recording); [`analyze`] bins the labelled events of all sessions
+//! by reported confidence and compares each bin with how often its events
+//! were confirmed. The result is a reliability diagram, the Brier score and
+//! expected calibration error, and a suggested monotone remapping from
+//! reported to empirical confidence fitted by isotonic regression.
+
+use crate::recording::{Annotation, EventRecorder, RecordingSession};
+use crate::{EventType, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Annotation tag labelling an event as genuine
+pub const CONFIRMED_TAG: &str = "confirmed";
+/// Annotation tag labelling an event as explained or false
+pub const DEBUNKED_TAG: &str = "debunked";
+
+/// Reviewer verdict on an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Confirmed,
+    Debunked,
+}
+
+impl Verdict {
+    /// Verdict carried by an annotation's tags
+    pub fn from_tags(tags: &[String]) -> Option<Self> {
+        let has = |wanted: &str| tags.iter().any(|t| t.eq_ignore_ascii_case(wanted));
+        match (has(CONFIRMED_TAG), has(DEBUNKED_TAG)) {
+            (true, false) => Some(Verdict::Confirmed),
+            (false, true) => Some(Verdict::Debunked),
+            _ => None,
+        }
+    }
+    
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Verdict::Confirmed => CONFIRMED_TAG,
+            Verdict::Debunked => DEBUNKED_TAG,
+        }
+    }
+}
+
+/// Latest verdict on each event of a session, by event ID
+pub fn verdicts(session: &RecordingSession) -> HashMap<String, Verdict> {
+    let mut labelled: Vec<&Annotation> = session.annotations.iter()
+        .filter(|a| a.event_id.is_some())
+        .collect();
+    labelled.sort_by_key(|a| a.id);
+    
+    let mut verdicts = HashMap::new();
+    for annotation in labelled {
+        if let (Some(event_id), Some(verdict)) = (&annotation.event_id, Verdict::from_tags(&annotation.tags)) {
+            verdicts.insert(event_id.clone(), verdict);
+        }
+    }
+    verdicts
+}
+
+/// Reliability analysis settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReliabilityConfig {
+    /// Equal-width confidence bins between 0 and 1
+    #[serde(default = "default_bins")]
+    pub bins: usize,
+    /// Labelled events a bin needs to count towards the remapping
+    #[serde(default = "default_min_labelled")]
+    pub min_labelled: usize,
+    /// Only sessions at this location (case-insensitive)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Only events of this type (as accepted by [`EventType::matches`])
+    #[serde(default)]
+    pub event_type: Option<String>,
+}
+
+fn default_bins() -> usize { 10 }
+fn default_min_labelled() -> usize { 5 }
+
+impl Default for ReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            bins: default_bins(),
+            min_labelled: default_min_labelled(),
+            location: None,
+            event_type: None,
+        }
+    }
+}
+
+/// Labelled events whose reported confidence fell in one bin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReliabilityBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub confirmed: usize,
+    pub debunked: usize,
+    /// Mean reported confidence of the bin's labelled events
+    pub mean_confidence: Option<f64>,
+    /// Share of the bin's labelled events that were confirmed
+    pub observed: Option<f64>,
+}
+
+impl ReliabilityBin {
+    pub fn labelled(&self) -> usize {
+        self.confirmed + self.debunked
+    }
+}
+
+/// Point of the suggested remapping: events reported at `reported` were
+/// confirmed at rate `calibrated`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RemapPoint {
+    pub reported: f64,
+    pub calibrated: f64,
+    /// Labelled events behind the point
+    pub events: usize,
+}
+
+/// How well reported confidence matches reviewer verdicts (`glowbarn-cli reliability`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReliabilityReport {
+    pub generated_at: DateTime<Utc>,
+    pub location: Option<String>,
+    pub event_type: Option<String>,
+    pub sessions: usize,
+    pub events: usize,
+    pub confirmed: usize,
+    pub debunked: usize,
+    pub bins: Vec<ReliabilityBin>,
+    /// Mean squared difference between reported confidence and verdict (0 is perfect)
+    pub brier_score: Option<f64>,
+    /// Labelled-event weighted mean gap between reported and observed confidence per bin
+    pub calibration_error: Option<f64>,
+    /// Monotone reported-to-empirical mapping, ascending
+    pub remap: Vec<RemapPoint>,
+}
+
+impl ReliabilityReport {
+    pub fn labelled(&self) -> usize {
+        self.confirmed + self.debunked
+    }
+    
+    /// Empirical confidence of an event reported at `confidence`, by linear
+    /// interpolation along the remapping (`None` without one)
+    pub fn calibrate(&self, confidence: f64) -> Option<f64> {
+        let first = self.remap.first()?;
+        let last = self.remap.last()?;
+        if confidence <= first.reported {
+            return Some(first.calibrated);
+        }
+        if confidence >= last.reported {
+            return Some(last.calibrated);
+        }
+        self.remap.windows(2)
+            .find(|pair| confidence <= pair[1].reported)
+            .map(|pair| {
+                let span = pair[1].reported - pair[0].reported;
+                let t = if span > 0.0 { (confidence - pair[0].reported) / span } else { 0.0 };
+                pair[0].calibrated + t * (pair[1].calibrated - pair[0].calibrated)
+            })
+    }
+}
+
+/// Compare reported confidence with reviewer verdicts over all recorded sessions
+pub fn analyze(recorder: &EventRecorder, config: &ReliabilityConfig) -> Result<ReliabilityReport> {
+    let bins = config.bins.max(1);
+    let mut tallies = vec![(0usize, 0usize, 0.0f64); bins];
+    let mut sessions = 0;
+    let mut events = 0;
+    let mut squared_error = 0.0;
+    
+    for session in recorder.list_sessions()? {
+        if config.location.as_deref().is_some_and(|l| !session.location.trim().eq_ignore_ascii_case(l.trim())) {
+            continue;
+        }
+        sessions += 1;
+        
+        let verdicts = verdicts(&session);
+        for event in recorder.load_events(&session.id)? {
+            if event.event_type == EventType::Maintenance
+                || config.event_type.as_deref().is_some_and(|t| !event.event_type.matches(t)) {
+                continue;
+            }
+            events += 1;
+            let Some(verdict) = verdicts.get(&event.id) else {
+                continue;
+            };
+            
+            let confidence = event.confidence.clamp(0.0, 1.0);
+            let bin = ((confidence * bins as f64) as usize).min(bins - 1);
+            let outcome = if *verdict == Verdict::Confirmed { 1.0 } else { 0.0 };
+            match verdict {
+                Verdict::Confirmed => tallies[bin].0 += 1,
+                Verdict::Debunked => tallies[bin].1 += 1,
+            }
+            tallies[bin].2 += confidence;
+            squared_error += (confidence - outcome).powi(2);
+        }
+    }
+    
+    let bins: Vec<ReliabilityBin> = tallies.into_iter().enumerate()
+        .map(|(i, (confirmed, debunked, confidence_sum))| {
+            let labelled = confirmed + debunked;
+            ReliabilityBin {
+                lower: i as f64 / bins as f64,
+                upper: (i + 1) as f64 / bins as f64,
+                confirmed,
+                debunked,
+                mean_confidence: (labelled > 0).then(|| confidence_sum / labelled as f64),
+                observed: (labelled > 0).then(|| confirmed as f64 / labelled as f64),
+            }
+        })
+        .collect();
+    
+    let confirmed: usize = bins.iter().map(|b| b.confirmed).sum();
+    let debunked: usize = bins.iter().map(|b| b.debunked).sum();
+    let labelled = confirmed + debunked;
+    let calibration_error = (labelled > 0).then(|| {
+        bins.iter()
+            .filter_map(|b| Some(b.labelled() as f64 * (b.mean_confidence? - b.observed?).abs()))
+            .sum::<f64>() / labelled as f64
+    });
+    
+    Ok(ReliabilityReport {
+        generated_at: Utc::now(),
+        location: config.location.clone(),
+        event_type: config.event_type.clone(),
+        sessions,
+        events,
+        confirmed,
+        debunked,
+        remap: remap(&bins, config.min_labelled),
+        bins,
+        brier_score: (labelled > 0).then(|| squared_error / labelled as f64),
+        calibration_error,
+    })
+}
+
+/// Isotonic fit of the observed rates over the bins with enough labels
+///
+/// Adjacent bins whose rates do not rise with confidence are pooled until
+/// the rates strictly increase (pool adjacent violators), so higher reported
+/// confidence never maps to lower empirical confidence.
+fn remap(bins: &[ReliabilityBin], min_labelled: usize) -> Vec<RemapPoint> {
+    // (confidence sum, confirmed, labelled) per pooled block
+    let mut blocks: Vec<(f64, usize, usize)> = Vec::new();
+    for bin in bins.iter().filter(|b| b.labelled() > 0 && b.labelled() >= min_labelled) {
+        let labelled = bin.labelled();
+        blocks.push((bin.mean_confidence.unwrap_or_default() * labelled as f64, bin.confirmed, labelled));
+        
+        while blocks.len() >= 2 {
+            let (_, c1, n1) = blocks[blocks.len() - 2];
+            let (_, c2, n2) = blocks[blocks.len() - 1];
+            if (c1 as f64 / n1 as f64) < (c2 as f64 / n2 as f64) {
+                break;
+            }
+            let last = blocks.pop().unwrap_or_default();
+            let into = blocks.last_mut().expect("two blocks");
+            into.0 += last.0;
+            into.1 += last.1;
+            into.2 += last.2;
+        }
+    }
+    
+    blocks.into_iter()
+        .map(|(confidence_sum, confirmed, labelled)| RemapPoint {
+            reported: confidence_sum / labelled as f64,
+            calibrated: confirmed as f64 / labelled as f64,
+            events: labelled,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn bin(mean_confidence: f64, confirmed: usize, debunked: usize) -> ReliabilityBin {
+        let labelled = confirmed + debunked;
+        ReliabilityBin {
+            lower: 0.0,
+            upper: 1.0,
+            confirmed,
+            debunked,
+            mean_confidence: Some(mean_confidence),
+            observed: Some(confirmed as f64 / labelled as f64),
+        }
+    }
+    
+    fn annotation(id: u32, event_id: &str, tag: &str) -> Annotation {
+        Annotation {
+            id,
+            author: "tester".to_string(),
+            timestamp: Utc::now(),
+            text: String::new(),
+            event_id: Some(event_id.to_string()),
+            tags: vec![tag.to_string()],
+        }
+    }
+    
+    #[test]
+    fn verdict_from_tags() {
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        assert_eq!(Verdict::from_tags(&tags(&["Confirmed", "cold"])), Some(Verdict::Confirmed));
+        assert_eq!(Verdict::from_tags(&tags(&["debunked"])), Some(Verdict::Debunked));
+        assert_eq!(Verdict::from_tags(&tags(&["confirmed", "debunked"])), None);
+        assert_eq!(Verdict::from_tags(&tags(&[])), None);
+    }
+    
+    #[test]
+    fn latest_verdict_wins() {
+        let mut session = RecordingSession::new("night", "attic");
+        session.annotations = vec![
+            annotation(3, "evt_1", DEBUNKED_TAG),
+            annotation(1, "evt_1", CONFIRMED_TAG),
+            annotation(2, "evt_2", CONFIRMED_TAG),
+            annotation(4, "evt_2", "draft"),
+        ];
+        let verdicts = verdicts(&session);
+        assert_eq!(verdicts.get("evt_1"), Some(&Verdict::Debunked));
+        assert_eq!(verdicts.get("evt_2"), Some(&Verdict::Confirmed));
+    }
+    
+    #[test]
+    fn remap_pools_violators_and_skips_sparse_bins() {
+        let bins = [bin(0.2, 4, 6), bin(0.5, 3, 7), bin(0.7, 0, 2), bin(0.9, 9, 1)];
+        let remap = remap(&bins, 5);
+        assert_eq!(remap.len(), 2);
+        assert!((remap[0].reported - 0.35).abs() < 1e-9);
+        assert!((remap[0].calibrated - 0.35).abs() < 1e-9);
+        assert_eq!(remap[0].events, 20);
+        assert!((remap[1].calibrated - 0.9).abs() < 1e-9);
+        
+        let report = ReliabilityReport {
+            generated_at: Utc::now(),
+            location: None,
+            event_type: None,
+            sessions: 1,
+            events: 32,
+            confirmed: 16,
+            debunked: 16,
+            bins: bins.to_vec(),
+            brier_score: None,
+            calibration_error: None,
+            remap,
+        };
+        assert_eq!(report.calibrate(0.1), Some(0.35));
+        assert_eq!(report.calibrate(1.0), Some(0.9));
+        assert!((report.calibrate(0.625).unwrap() - 0.625).abs() < 1e-9);
+    }
+}
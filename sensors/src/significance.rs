@@ -0,0 +1,499 @@
+//! Significance Testing
+//!
+//! Twelve EMF events in a night sound remarkable until the night before,
+//! with nobody in the building, turns out to have had ten. [`compare`] sets a
+//! session against a control window, by default the same hours of the
+//! previous night, and uses permutation tests to ask how often chance alone
+//! would produce a difference as large as the one observed. Both windows are
+//! cut into bins first, so that thousands of slowly drifting sensor samples
+//! do not count as thousands of independent observations. Only bins covered
+//! by a recording session take part. Every p-value is also reported
+//! Holm-adjusted for the number of comparisons made.
+
+use crate::recording::{EventRecorder, SensorRecord};
+use crate::{ParanormalEvent, Result, SensorError};
+use chrono::{DateTime, Utc};
+use glowbarn_dsp::stats::mean;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, SystemTime};
+
+/// Fewest bins per window for a comparison to be made
+const MIN_BINS: usize = 2;
+
+/// Significance settings (`[significance]` in the app config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignificanceConfig {
+    /// Compare each session against its control window when it ends
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Session to use as the control, instead of a shifted window
+    #[serde(default)]
+    pub control_session: Option<String>,
+    /// How far before the session the control window lies (hours)
+    #[serde(default = "default_offset")]
+    pub control_offset_hours: f64,
+    /// Bin length (minutes)
+    #[serde(default = "default_bin")]
+    pub bin_minutes: f64,
+    /// Random relabellings per test
+    #[serde(default = "default_permutations")]
+    pub permutations: usize,
+    /// Seed of the relabellings, so a report can be reproduced
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+}
+
+fn default_enabled() -> bool { true }
+fn default_offset() -> f64 { 24.0 }
+fn default_bin() -> f64 { 10.0 }
+fn default_permutations() -> usize { 9999 }
+fn default_seed() -> u64 { 0x676c_6f77 }
+
+impl Default for SignificanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            control_session: None,
+            control_offset_hours: default_offset(),
+            bin_minutes: default_bin(),
+            permutations: default_permutations(),
+            seed: default_seed(),
+        }
+    }
+}
+
+/// What a comparison tests
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestKind {
+    /// Events per bin, difference of means
+    EventRate,
+    /// Per-bin sensor means, Kolmogorov-Smirnov distance
+    SensorDistribution,
+}
+
+/// One permutation test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    pub kind: TestKind,
+    /// "all events", an event type or a sensor name
+    pub subject: String,
+    /// Events per hour, or mean sensor value, in the investigation window
+    pub investigation: f64,
+    /// The same in the control window
+    pub control: f64,
+    pub unit: String,
+    /// Observed test statistic
+    pub statistic: f64,
+    pub p_value: f64,
+    /// Holm-adjusted over all comparisons in the report
+    pub p_adjusted: f64,
+}
+
+/// Time span compared and how much of it was usable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowSummary {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Bins covered by a recording session
+    pub bins: usize,
+    /// Sessions the data came from
+    pub sessions: Vec<String>,
+}
+
+/// Investigation vs. control comparison of a session (`significance.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignificanceReport {
+    pub session_id: String,
+    pub generated_at: DateTime<Utc>,
+    pub investigation: WindowSummary,
+    pub control: WindowSummary,
+    pub bin_minutes: f64,
+    pub permutations: usize,
+    pub seed: u64,
+    pub comparisons: Vec<Comparison>,
+}
+
+/// Events and samples of a time window, from the sessions that recorded it
+#[derive(Debug, Clone)]
+pub struct WindowData {
+    pub start: SystemTime,
+    pub end: SystemTime,
+    /// Spans during which a session was recording
+    pub coverage: Vec<(SystemTime, SystemTime)>,
+    pub sessions: Vec<String>,
+    pub events: Vec<ParanormalEvent>,
+    pub records: Vec<SensorRecord>,
+}
+
+impl WindowData {
+    /// Collect `start`..`end` from every session recording in it except `exclude`
+    pub fn load(recorder: &EventRecorder, start: SystemTime, end: SystemTime, exclude: Option<&str>) -> Result<Self> {
+        let mut window = Self {
+            start,
+            end,
+            coverage: Vec::new(),
+            sessions: Vec::new(),
+            events: Vec::new(),
+            records: Vec::new(),
+        };
+        for session in recorder.list_sessions()? {
+            if Some(session.id.as_str()) == exclude {
+                continue;
+            }
+            let (from, to) = session_span(&session);
+            if from < end && to > start {
+                window.add_session(recorder, &session.id, from, to)?;
+            }
+        }
+        Ok(window)
+    }
+    
+    /// The whole of one session
+    pub fn session(recorder: &EventRecorder, session_id: &str) -> Result<Self> {
+        let session = recorder.load_session(session_id)?;
+        let (start, end) = session_span(&session);
+        let mut window = Self {
+            start,
+            end,
+            coverage: Vec::new(),
+            sessions: Vec::new(),
+            events: Vec::new(),
+            records: Vec::new(),
+        };
+        window.add_session(recorder, session_id, start, end)?;
+        Ok(window)
+    }
+    
+    fn add_session(&mut self, recorder: &EventRecorder, session_id: &str, from: SystemTime, to: SystemTime) -> Result<()> {
+        let inside = |t: SystemTime| t >= self.start && t < self.end;
+        self.events.extend(recorder.load_events(session_id)?.into_iter().filter(|e| inside(e.timestamp)));
+        self.records.extend(recorder.load_sensor_records(session_id)?.into_iter().filter(|r| inside(r.timestamp)));
+        self.coverage.push((from, to));
+        self.sessions.push(session_id.to_string());
+        Ok(())
+    }
+    
+    /// Start times of the bins that a session covered completely
+    fn bins(&self, width: Duration) -> Vec<SystemTime> {
+        let mut bins = Vec::new();
+        let mut bin = self.start;
+        while bin + width <= self.end {
+            if self.coverage.iter().any(|(from, to)| *from <= bin && bin + width <= *to) {
+                bins.push(bin);
+            }
+            bin += width;
+        }
+        bins
+    }
+    
+    fn summary(&self, bins: usize) -> WindowSummary {
+        WindowSummary {
+            start: self.start.into(),
+            end: self.end.into(),
+            bins,
+            sessions: self.sessions.clone(),
+        }
+    }
+}
+
+fn session_span(session: &crate::recording::RecordingSession) -> (SystemTime, SystemTime) {
+    let end = session.end_time.unwrap_or_else(Utc::now);
+    (session.start_time.into(), end.into())
+}
+
+/// Compare a session with its control window
+pub fn compare(recorder: &EventRecorder, session_id: &str, config: &SignificanceConfig) -> Result<SignificanceReport> {
+    let investigation = WindowData::session(recorder, session_id)?;
+    let control = match &config.control_session {
+        Some(control) => WindowData::session(recorder, control)?,
+        None => {
+            let offset = Duration::try_from_secs_f64(config.control_offset_hours * 3600.0)
+                .map_err(|_| SensorError::Recording(format!("invalid control offset {} h", config.control_offset_hours)))?;
+            WindowData::load(recorder, investigation.start - offset, investigation.end - offset, Some(session_id))?
+        }
+    };
+    if control.sessions.is_empty() {
+        return Err(SensorError::Recording("no session recorded during the control window".to_string()));
+    }
+    
+    analyze(&investigation, &control, config)
+}
+
+/// Run the event rate and sensor distribution tests on two windows
+///
+/// Subjects with fewer than two covered bins on either side are left out.
+pub fn analyze(investigation: &WindowData, control: &WindowData, config: &SignificanceConfig) -> Result<SignificanceReport> {
+    let width = Duration::try_from_secs_f64(config.bin_minutes * 60.0)
+        .ok()
+        .filter(|w| !w.is_zero())
+        .ok_or_else(|| SensorError::Recording(format!("invalid bin length {} min", config.bin_minutes)))?;
+    let (inv_bins, ctl_bins) = (investigation.bins(width), control.bins(width));
+    let mut rng = SplitMix64(config.seed);
+    let mut comparisons = Vec::new();
+    
+    // Event rates, overall and per type
+    if inv_bins.len() >= MIN_BINS && ctl_bins.len() >= MIN_BINS {
+        let subjects: BTreeSet<String> = investigation.events.iter().chain(&control.events)
+            .map(|e| format!("{:?}", e.event_type))
+            .collect();
+        let per_hour = 3600.0 / width.as_secs_f64();
+        
+        for subject in std::iter::once("all events".to_string()).chain(subjects) {
+            let counts = |window: &WindowData, bins: &[SystemTime]| -> Vec<f64> {
+                let times: Vec<SystemTime> = window.events.iter()
+                    .filter(|e| subject == "all events" || format!("{:?}", e.event_type) == subject)
+                    .map(|e| e.timestamp)
+                    .collect();
+                bins.iter()
+                    .map(|&bin| times.iter().filter(|&&t| t >= bin && t < bin + width).count() as f64)
+                    .collect()
+            };
+            let (a, b) = (counts(investigation, &inv_bins), counts(control, &ctl_bins));
+            let (statistic, p_value) = permutation_test(&a, &b, mean_difference, config.permutations, &mut rng);
+            comparisons.push(Comparison {
+                kind: TestKind::EventRate,
+                subject,
+                investigation: mean(&a) * per_hour,
+                control: mean(&b) * per_hour,
+                unit: "events/h".to_string(),
+                statistic,
+                p_value,
+                p_adjusted: p_value,
+            });
+        }
+    }
+    
+    // Sensor distributions, over per-bin means
+    let mut sensors: BTreeMap<&str, &str> = BTreeMap::new();
+    for record in &investigation.records {
+        sensors.entry(&record.sensor_name).or_insert(&record.unit);
+    }
+    for (sensor, unit) in sensors {
+        let means = |window: &WindowData, bins: &[SystemTime]| -> Vec<f64> {
+            let samples: Vec<&SensorRecord> = window.records.iter().filter(|r| r.sensor_name == sensor).collect();
+            bins.iter()
+                .filter_map(|&bin| {
+                    let values: Vec<f64> = samples.iter()
+                        .filter(|r| r.timestamp >= bin && r.timestamp < bin + width)
+                        .map(|r| r.value)
+                        .collect();
+                    (!values.is_empty()).then(|| mean(&values))
+                })
+                .collect()
+        };
+        let (a, b) = (means(investigation, &inv_bins), means(control, &ctl_bins));
+        if a.len() < MIN_BINS || b.len() < MIN_BINS {
+            continue;
+        }
+        let (statistic, p_value) = permutation_test(&a, &b, ks_distance, config.permutations, &mut rng);
+        comparisons.push(Comparison {
+            kind: TestKind::SensorDistribution,
+            subject: sensor.to_string(),
+            investigation: mean(&a),
+            control: mean(&b),
+            unit: unit.to_string(),
+            statistic,
+            p_value,
+            p_adjusted: p_value,
+        });
+    }
+    
+    holm(&mut comparisons);
+    Ok(SignificanceReport {
+        session_id: investigation.sessions.first().cloned().unwrap_or_default(),
+        generated_at: Utc::now(),
+        investigation: investigation.summary(inv_bins.len()),
+        control: control.summary(ctl_bins.len()),
+        bin_minutes: config.bin_minutes,
+        permutations: config.permutations,
+        seed: config.seed,
+        comparisons,
+    })
+}
+
+/// Observed statistic and its p-value under random relabelling of `a` and `b`
+///
+/// The p-value counts the observed labelling as one of the permutations, so
+/// it is never zero.
+pub fn permutation_test(a: &[f64], b: &[f64], statistic: fn(&[f64], &[f64]) -> f64, permutations: usize, rng: &mut SplitMix64) -> (f64, f64) {
+    let observed = statistic(a, b);
+    let mut pooled: Vec<f64> = a.iter().chain(b).copied().collect();
+    let mut extreme = 0;
+    
+    for _ in 0..permutations {
+        for i in (1..pooled.len()).rev() {
+            pooled.swap(i, rng.below(i + 1));
+        }
+        let (x, y) = pooled.split_at(a.len());
+        if statistic(x, y) >= observed - 1e-12 {
+            extreme += 1;
+        }
+    }
+    (observed, (extreme + 1) as f64 / (permutations + 1) as f64)
+}
+
+/// Absolute difference of means
+pub fn mean_difference(a: &[f64], b: &[f64]) -> f64 {
+    (mean(a) - mean(b)).abs()
+}
+
+/// Two-sample Kolmogorov-Smirnov distance
+pub fn ks_distance(a: &[f64], b: &[f64]) -> f64 {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort_by(f64::total_cmp);
+    b.sort_by(f64::total_cmp);
+    
+    let (mut i, mut j, mut distance) = (0, 0, 0.0f64);
+    while i < a.len() && j < b.len() {
+        let value = a[i].min(b[j]);
+        while i < a.len() && a[i] <= value {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= value {
+            j += 1;
+        }
+        distance = distance.max((i as f64 / a.len() as f64 - j as f64 / b.len() as f64).abs());
+    }
+    distance
+}
+
+/// Holm-Bonferroni step-down adjustment of the p-values
+fn holm(comparisons: &mut [Comparison]) {
+    let m = comparisons.len();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| comparisons[a].p_value.total_cmp(&comparisons[b].p_value));
+    
+    let mut running = 0.0f64;
+    for (rank, &i) in order.iter().enumerate() {
+        running = running.max(((m - rank) as f64 * comparisons[i].p_value).min(1.0));
+        comparisons[i].p_adjusted = running;
+    }
+}
+
+/// Small seedable generator for the relabellings
+#[derive(Debug, Clone)]
+pub struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    
+    /// Uniform integer below `n`
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventType;
+    
+    fn window(start: SystemTime, events_per_bin: usize, temperature: f64) -> WindowData {
+        let end = start + Duration::from_secs(3600);
+        let mut events = Vec::new();
+        let mut records = Vec::new();
+        for minute in (0..60).step_by(10) {
+            let bin = start + Duration::from_secs(minute * 60);
+            for n in 0..events_per_bin {
+                let mut event = ParanormalEvent::new(EventType::EmfAnomaly, 0.8);
+                event.timestamp = bin + Duration::from_secs(n as u64 + 1);
+                events.push(event);
+            }
+            records.push(SensorRecord {
+                timestamp: bin + Duration::from_secs(30),
+                sensor_name: "attic_temp".to_string(),
+                value: temperature + (minute % 20) as f64 / 10.0,
+                unit: "°C".to_string(),
+            });
+        }
+        WindowData {
+            start,
+            end,
+            coverage: vec![(start, end)],
+            sessions: vec![format!("session_{}", events_per_bin)],
+            events,
+            records,
+        }
+    }
+    
+    #[test]
+    fn ks_distance_of_samples() {
+        assert_eq!(ks_distance(&[1.0, 2.0, 3.0], &[3.0, 1.0, 2.0]), 0.0);
+        assert_eq!(ks_distance(&[1.0, 2.0], &[3.0, 4.0]), 1.0);
+        assert_eq!(ks_distance(&[1.0, 2.0, 3.0, 4.0], &[3.0, 4.0, 5.0, 6.0]), 0.5);
+    }
+    
+    #[test]
+    fn permutation_test_is_reproducible() {
+        let a = [5.0, 6.0, 5.0, 7.0, 6.0];
+        let b = [0.0, 1.0, 0.0, 1.0, 0.0];
+        let (statistic, p) = permutation_test(&a, &b, mean_difference, 999, &mut SplitMix64(1));
+        assert!((statistic - 5.4).abs() < 1e-9);
+        assert!(p < 0.05);
+        assert_eq!(permutation_test(&a, &b, mean_difference, 999, &mut SplitMix64(1)).1, p);
+        
+        // Identical samples can never be beaten
+        let (statistic, p) = permutation_test(&a, &a, mean_difference, 99, &mut SplitMix64(1));
+        assert_eq!((statistic, p), (0.0, 1.0));
+    }
+    
+    #[test]
+    fn holm_adjusts_step_down() {
+        let mut comparisons: Vec<Comparison> = [0.04, 0.01, 0.03]
+            .iter()
+            .map(|&p_value| Comparison {
+                kind: TestKind::EventRate,
+                subject: String::new(),
+                investigation: 0.0,
+                control: 0.0,
+                unit: String::new(),
+                statistic: 0.0,
+                p_value,
+                p_adjusted: p_value,
+            })
+            .collect();
+        holm(&mut comparisons);
+        let adjusted: Vec<f64> = comparisons.iter().map(|c| c.p_adjusted).collect();
+        assert!((adjusted[1] - 0.03).abs() < 1e-12);
+        assert!((adjusted[2] - 0.06).abs() < 1e-12);
+        assert!((adjusted[0] - 0.06).abs() < 1e-12);
+    }
+    
+    #[test]
+    fn analyze_compares_rates_and_distributions() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let investigation = window(start, 3, 12.0);
+        let control = window(start - Duration::from_secs(24 * 3600), 0, 12.0);
+        let config = SignificanceConfig { permutations: 999, ..Default::default() };
+        let report = analyze(&investigation, &control, &config).unwrap();
+        
+        assert_eq!((report.investigation.bins, report.control.bins), (6, 6));
+        assert_eq!(report.comparisons.len(), 3);
+        let all = &report.comparisons[0];
+        assert_eq!(all.subject, "all events");
+        assert_eq!((all.investigation, all.control), (18.0, 0.0));
+        assert!(all.p_value < 0.01);
+        assert!(all.p_adjusted >= all.p_value);
+        
+        let temperature = &report.comparisons[2];
+        assert_eq!(temperature.kind, TestKind::SensorDistribution);
+        assert_eq!(temperature.statistic, 0.0);
+        assert_eq!(temperature.p_value, 1.0);
+    }
+    
+    #[test]
+    fn analyze_rejects_an_empty_bin_length() {
+        let start = SystemTime::UNIX_EPOCH;
+        let config = SignificanceConfig { bin_minutes: 0.0, ..Default::default() };
+        assert!(analyze(&window(start, 1, 0.0), &window(start, 1, 0.0), &config).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Rolling Sensor Summaries
+//!
+//! Every sample is folded into a per-sensor window of fixed length (a
+//! minute by default) aligned to the clock, and each finished window is
+//! saved as one line of the session's `summaries.jsonl`: count, min, max,
+//! mean and standard deviation. That happens whatever the sensor log mode,
+//! so long-term charts and reports can be drawn from a few hundred lines
+//! per sensor and night instead of replaying every raw sample.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// Session file holding the summaries
+pub const SUMMARIES_FILE: &str = "summaries.jsonl";
+
+/// Statistics of one sensor over one window (a line of `summaries.jsonl`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryRecord {
+    /// Start of the window, on a multiple of the interval since the epoch
+    pub start: SystemTime,
+    pub interval_secs: u64,
+    pub sensor_name: String,
+    pub unit: String,
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Population standard deviation
+    pub std_dev: f64,
+}
+
+/// Running statistics of the open window (Welford)
+#[derive(Debug, Clone)]
+struct Window {
+    unit: String,
+    count: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Window {
+    fn new(unit: &str) -> Self {
+        Self {
+            unit: unit.to_string(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+    
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+}
+
+/// Accumulates samples into aligned windows
+#[derive(Debug, Clone)]
+pub struct RollingSummaries {
+    interval: Duration,
+    /// Start of the open window
+    start: Option<SystemTime>,
+    windows: HashMap<String, Window>,
+}
+
+impl RollingSummaries {
+    /// Windows of `interval` (at least a second)
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval: interval.max(Duration::from_secs(1)),
+            start: None,
+            windows: HashMap::new(),
+        }
+    }
+    
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+    
+    /// Start of the window holding `at`
+    fn window_start(&self, at: SystemTime) -> SystemTime {
+        let since_epoch = at.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+        let secs = self.interval.as_secs();
+        SystemTime::UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs() / secs * secs)
+    }
+    
+    /// Add a sample taken at `at`, returning the summaries of the window it closed
+    ///
+    /// Samples timestamped before the open window (late arrivals) count
+    /// towards it. Non-finite values are skipped.
+    pub fn add(&mut self, sensor_name: &str, unit: &str, value: f64, at: SystemTime) -> Vec<SummaryRecord> {
+        if !value.is_finite() {
+            return Vec::new();
+        }
+        
+        let start = self.window_start(at);
+        let closed = match self.start {
+            Some(open) if start > open => self.flush(),
+            _ => Vec::new(),
+        };
+        if self.start.is_none_or(|open| start > open) {
+            self.start = Some(start);
+        }
+        
+        self.windows.entry(sensor_name.to_string())
+            .or_insert_with(|| Window::new(unit))
+            .add(value);
+        closed
+    }
+    
+    /// Close the open window, e.g. at the end of a session
+    pub fn flush(&mut self) -> Vec<SummaryRecord> {
+        let Some(start) = self.start.take() else {
+            return Vec::new();
+        };
+        
+        let mut records: Vec<SummaryRecord> = self.windows.drain()
+            .filter(|(_, window)| window.count > 0)
+            .map(|(sensor_name, window)| SummaryRecord {
+                start,
+                interval_secs: self.interval.as_secs(),
+                sensor_name,
+                unit: window.unit,
+                count: window.count,
+                min: window.min,
+                max: window.max,
+                mean: window.mean,
+                std_dev: (window.m2 / window.count as f64).sqrt(),
+            })
+            .collect();
+        records.sort_by(|a, b| a.sensor_name.cmp(&b.sensor_name));
+        records
+    }
+}
+
+/// Combine saved summaries into longer windows of `interval`, e.g. hourly rows
+/// for a chart across a whole night
+///
+/// Counts, extremes, means and standard deviations are pooled exactly.
+pub fn merge(records: &[SummaryRecord], interval: Duration) -> Vec<SummaryRecord> {
+    let rolling = RollingSummaries::new(interval);
+    let mut merged: Vec<SummaryRecord> = Vec::new();
+    for record in records {
+        let start = rolling.window_start(record.start);
+        let Some(into) = merged.iter_mut().find(|m| m.start == start && m.sensor_name == record.sensor_name) else {
+            merged.push(SummaryRecord {
+                start,
+                interval_secs: rolling.interval.as_secs(),
+                ..record.clone()
+            });
+            continue;
+        };
+        
+        let count = into.count + record.count;
+        let mean = (into.mean * into.count as f64 + record.mean * record.count as f64) / count as f64;
+        let m2 = into.std_dev.powi(2) * into.count as f64 + record.std_dev.powi(2) * record.count as f64
+            + (into.mean - record.mean).powi(2) * (into.count * record.count) as f64 / count as f64;
+        into.min = into.min.min(record.min);
+        into.max = into.max.max(record.max);
+        into.mean = mean;
+        into.std_dev = (m2 / count as f64).sqrt();
+        into.count = count;
+    }
+    merged.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.sensor_name.cmp(&b.sensor_name)));
+    merged
+}
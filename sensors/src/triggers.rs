@@ -2,11 +2,57 @@
 //!
 //! Configurable triggers for automated responses to paranormal events.
 
-use crate::{EventType, ParanormalEvent, Result};
+use crate::actions::{ActionPool, TriggerAudit};
+use crate::live::LiveReadings;
+use crate::zones::ZoneMap;
+use crate::{EventType, ParanormalEvent, SensorError, SensorSnapshot, Result};
+use glowbarn_hal::IlluminatorMode;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 use std::pin::Pin;
 use std::future::Future;
 
+/// File in the data directory holding when each trigger last fired
+pub const TRIGGER_STATE_FILE: &str = "trigger_state.json";
+
+/// Audio route alert sounds are played on, when configured
+pub const ALERT_ROUTE: &str = "alerts";
+
+/// Comparison of a sensor value against a limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Below,
+    AtMost,
+    Above,
+    AtLeast,
+}
+
+impl Comparison {
+    /// Whether `value` compares true against `limit`
+    pub fn holds(self, value: f64, limit: f64) -> bool {
+        match self {
+            Comparison::Below => value < limit,
+            Comparison::AtMost => value <= limit,
+            Comparison::Above => value > limit,
+            Comparison::AtLeast => value >= limit,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparison::Below => "<",
+            Comparison::AtMost => "<=",
+            Comparison::Above => ">",
+            Comparison::AtLeast => ">=",
+        })
+    }
+}
+
 /// Trigger condition
 #[derive(Debug, Clone)]
 pub enum TriggerCondition {
@@ -18,6 +64,18 @@ pub enum TriggerCondition {
     EventBurst { count: usize, window: Duration },
     /// Trigger on specific sensor anomaly
     SensorAnomaly { sensor_pattern: String, threshold: f64 },
+    /// An event of this type occurred within `window` before this one
+    RecentEvent { event_type: EventType, window: Duration },
+    /// Latest value of a sensor whose name or type contains the pattern
+    /// compares true against `value` (e.g. temperature below 10 °C)
+    SensorValue { sensor_pattern: String, op: Comparison, value: f64 },
+    /// The event happened in this zone (or spans it, for multi-zone events)
+    InZone(String),
+    /// Event metadata `key` contains `value` (e.g. a laser grid `beam_path`
+    /// of `door>hall` for passes entering through the door)
+    MetadataContains { key: String, value: String },
+    /// Negated condition (e.g. suppress during a pressure drop)
+    Not(Box<TriggerCondition>),
     /// Compound condition (AND)
     All(Vec<TriggerCondition>),
     /// Compound condition (OR)
@@ -26,7 +84,10 @@ pub enum TriggerCondition {
 
 impl TriggerCondition {
     /// Check if condition is satisfied
-    pub fn check(&self, event: &ParanormalEvent, history: &[ParanormalEvent]) -> bool {
+    ///
+    /// `SensorValue` conditions are checked against `live`, the latest
+    /// sensor values, rather than the event.
+    pub fn check(&self, event: &ParanormalEvent, history: &[ParanormalEvent], live: &LiveReadings) -> bool {
         match self {
             TriggerCondition::EventType(et) => event.event_type == *et,
             
@@ -47,13 +108,91 @@ impl TriggerCondition {
                 })
             }
             
+            TriggerCondition::RecentEvent { event_type, window } => {
+                let cutoff = event.timestamp - *window;
+                history.iter().any(|e| {
+                    e.event_type == *event_type && e.timestamp > cutoff && e.timestamp <= event.timestamp
+                })
+            }
+            
+            TriggerCondition::SensorValue { .. } => !self.matching_sensors(live).is_empty(),
+            
+            TriggerCondition::InZone(zone) => {
+                let located = event.location.as_ref()
+                    .and_then(|l| l.zone.as_deref())
+                    .is_some_and(|z| z == zone);
+                located || event.metadata.get("zones")
+                    .is_some_and(|zones| zones.split(',').any(|z| z == zone))
+            }
+            
+            TriggerCondition::MetadataContains { key, value } => {
+                event.metadata.get(key).is_some_and(|v| v.contains(value.as_str()))
+            }
+            
+            TriggerCondition::Not(condition) => !condition.check(event, history, live),
+            
             TriggerCondition::All(conditions) => {
-                conditions.iter().all(|c| c.check(event, history))
+                conditions.iter().all(|c| c.check(event, history, live))
             }
             
             TriggerCondition::Any(conditions) => {
-                conditions.iter().any(|c| c.check(event, history))
+                conditions.iter().any(|c| c.check(event, history, live))
+            }
+        }
+    }
+    
+    /// Latest values satisfying the `SensorValue` conditions in this tree
+    pub fn matching_sensors(&self, live: &LiveReadings) -> Vec<SensorSnapshot> {
+        match self {
+            TriggerCondition::SensorValue { sensor_pattern, op, value } => {
+                let pattern = sensor_pattern.to_lowercase();
+                live.all().iter()
+                    .filter(|l| {
+                        l.reading.sensor_name.to_lowercase().contains(&pattern) || l.sensor_type.contains(&pattern)
+                    })
+                    .filter(|l| op.holds(l.reading.value, *value))
+                    .map(|l| l.snapshot())
+                    .collect()
+            }
+            TriggerCondition::All(conditions) | TriggerCondition::Any(conditions) => {
+                conditions.iter().flat_map(|c| c.matching_sensors(live)).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+    
+    /// This condition restricted to events in `zone`
+    pub fn in_zone(self, zone: &str) -> Self {
+        TriggerCondition::All(vec![self, TriggerCondition::InZone(zone.to_string())])
+    }
+    
+    /// Whether the condition only depends on sensor values (and recent
+    /// events), so it is checked on every value tick instead of per event
+    pub fn is_value_condition(&self) -> bool {
+        self.reads_values() && !self.needs_event()
+    }
+    
+    fn reads_values(&self) -> bool {
+        match self {
+            TriggerCondition::SensorValue { .. } => true,
+            TriggerCondition::Not(condition) => condition.reads_values(),
+            TriggerCondition::All(conditions) | TriggerCondition::Any(conditions) => {
+                conditions.iter().any(|c| c.reads_values())
+            }
+            _ => false,
+        }
+    }
+    
+    fn needs_event(&self) -> bool {
+        match self {
+            TriggerCondition::SensorValue { .. }
+            | TriggerCondition::RecentEvent { .. }
+            | TriggerCondition::InZone(_) => false,
+            TriggerCondition::Not(condition) => condition.needs_event(),
+            TriggerCondition::All(conditions) | TriggerCondition::Any(conditions) => {
+                conditions.iter().any(|c| c.needs_event())
             }
+            _ => true,
         }
     }
 }
@@ -63,14 +202,20 @@ impl TriggerCondition {
 pub enum TriggerAction {
     /// Log message
     Log { level: String, message: String },
-    /// Play sound
-    PlaySound { file: String },
+    /// Play sound, on a named audio route when one is configured
+    PlaySound { file: String, route: Option<String> },
     /// Send notification
     Notify { title: String, body: String },
     /// Execute command
     Execute { command: String, args: Vec<String> },
     /// Control GPIO (for lights, alarms, etc.)
     GpioControl { pin: u32, state: bool },
+    /// Set an IR illuminator's mode (run by the action pool, which holds
+    /// the illuminators)
+    SetIlluminator { name: String, mode: IlluminatorMode },
+    /// Change the volume and/or mute state of an audio route (run by the
+    /// action pool, which holds the routes)
+    Mixer { route: String, volume: Option<f64>, muted: Option<bool> },
     /// Start recording
     StartRecording { name: String },
     /// Mark timestamp
@@ -80,7 +225,7 @@ pub enum TriggerAction {
 }
 
 impl TriggerAction {
-    /// Execute the action
+    /// Execute the action, waiting for any command it starts
     pub fn execute<'a>(&'a self, event: &'a ParanormalEvent) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
         Box::pin(async move {
             match self {
@@ -99,15 +244,11 @@ impl TriggerAction {
                     }
                 }
                 
-                TriggerAction::PlaySound { file } => {
+                TriggerAction::PlaySound { file, .. } => {
                     // In production, this would use audio playback
                     tracing::info!("Playing sound: {}", file);
                     #[cfg(target_os = "linux")]
-                    {
-                        let _ = std::process::Command::new("aplay")
-                            .arg(file)
-                            .spawn();
-                    }
+                    run_command("aplay", &[file.as_str()]).await?;
                 }
                 
                 TriggerAction::Notify { title, body } => {
@@ -118,20 +259,14 @@ impl TriggerAction {
                     tracing::info!("Notification: {} - {}", title, formatted_body);
                     
                     #[cfg(target_os = "linux")]
-                    {
-                        let _ = std::process::Command::new("notify-send")
-                            .arg(title)
-                            .arg(&formatted_body)
-                            .spawn();
-                    }
+                    run_command("notify-send", &[title.as_str(), formatted_body.as_str()]).await?;
                 }
                 
                 TriggerAction::Execute { command, args } => {
                     tracing::info!("Executing: {} {:?}", command, args);
                     
-                    let _ = std::process::Command::new(command)
-                        .args(args)
-                        .spawn();
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    run_command(command, &args).await?;
                 }
                 
                 TriggerAction::GpioControl { pin, state } => {
@@ -139,10 +274,16 @@ impl TriggerAction {
                     
                     // In production, this would use glowbarn-hal GPIO
                     let path = format!("/sys/class/gpio/gpio{}/value", pin);
-                    if let Ok(mut file) = std::fs::OpenOptions::new().write(true).open(&path) {
-                        use std::io::Write;
-                        let _ = file.write_all(if *state { b"1" } else { b"0" });
-                    }
+                    tokio::fs::write(&path, if *state { "1" } else { "0" }).await
+                        .map_err(|e| SensorError::Action(format!("{}: {}", path, e)))?;
+                }
+                
+                TriggerAction::SetIlluminator { name, .. } => {
+                    return Err(SensorError::Action(format!("illuminator {}: no illuminators outside an action pool", name)));
+                }
+                
+                TriggerAction::Mixer { route, .. } => {
+                    return Err(SensorError::Action(format!("audio route {}: no routes outside an action pool", route)));
                 }
                 
                 TriggerAction::StartRecording { name } => {
@@ -165,6 +306,67 @@ impl TriggerAction {
             Ok(())
         })
     }
+    
+    /// The single actions this action consists of, in order
+    pub fn leaves(&self) -> Vec<&TriggerAction> {
+        match self {
+            TriggerAction::Multiple(actions) => actions.iter().flat_map(|a| a.leaves()).collect(),
+            action => vec![action],
+        }
+    }
+    
+    /// Short description for logs and the audit log
+    pub fn describe(&self) -> String {
+        match self {
+            TriggerAction::Log { level, .. } => format!("log {}", level),
+            TriggerAction::PlaySound { file, route: None } => format!("play {}", file),
+            TriggerAction::PlaySound { file, route: Some(route) } => format!("play {} on {}", file, route),
+            TriggerAction::Notify { title, .. } => format!("notify \"{}\"", title),
+            TriggerAction::Execute { command, .. } => format!("execute {}", command),
+            TriggerAction::GpioControl { pin, state } => format!("gpio {} {}", pin, if *state { "high" } else { "low" }),
+            TriggerAction::SetIlluminator { name, mode } => format!("illuminator {} {}", name, mode),
+            TriggerAction::Mixer { route, volume, muted } => {
+                let mut parts = vec![format!("mixer {}", route)];
+                parts.extend(volume.map(|v| format!("{:.0}%", v)));
+                parts.extend(muted.map(|m| if m { "mute" } else { "unmute" }.to_string()));
+                parts.join(" ")
+            }
+            TriggerAction::StartRecording { name } => format!("start recording {}", name),
+            TriggerAction::MarkTimestamp { label } => format!("mark {}", label),
+            TriggerAction::Multiple(actions) => format!("{} actions", actions.len()),
+        }
+    }
+}
+
+/// Run a command to completion; killed if the future is dropped (timeout)
+pub(crate) async fn run_command(program: &str, args: &[&str]) -> Result<()> {
+    let status = tokio::process::Command::new(program)
+        .args(args)
+        .kill_on_drop(true)
+        .status()
+        .await
+        .map_err(|e| SensorError::Action(format!("{}: {}", program, e)))?;
+    
+    if status.success() {
+        Ok(())
+    } else {
+        Err(SensorError::Action(format!("{} exited with {}", program, status)))
+    }
+}
+
+/// Current state of a trigger, for status views
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerState {
+    pub name: String,
+    pub enabled: bool,
+    pub cooldown_secs: u64,
+    pub last_triggered: Option<SystemTime>,
+    /// Threshold trigger whose condition holds right now
+    pub value_active: bool,
+    /// Evaluated and logged, but its action is not run
+    pub dry_run: bool,
+    /// Firings since startup, dry runs included
+    pub fire_count: u64,
 }
 
 /// Event trigger
@@ -172,10 +374,13 @@ impl TriggerAction {
 pub struct Trigger {
     pub name: String,
     pub enabled: bool,
+    /// Log and audit firings without running the action
+    pub dry_run: bool,
     pub condition: TriggerCondition,
     pub action: TriggerAction,
     pub cooldown: Duration,
     last_triggered: Option<SystemTime>,
+    fire_count: u64,
 }
 
 impl Trigger {
@@ -184,10 +389,12 @@ impl Trigger {
         Self {
             name: name.to_string(),
             enabled: true,
+            dry_run: false,
             condition,
             action,
             cooldown: Duration::from_secs(5),
             last_triggered: None,
+            fire_count: 0,
         }
     }
     
@@ -197,32 +404,76 @@ impl Trigger {
         self
     }
     
-    /// Check and execute trigger
-    pub async fn check_and_execute(&mut self, event: &ParanormalEvent, history: &[ParanormalEvent]) -> Result<bool> {
+    /// Copy of this trigger limited to events in `zone`, with its own action
+    ///
+    /// Lets one condition tree serve several rooms, e.g. a siren in the
+    /// cellar and a light in the attic. The copy is named `<name>_<zone>`.
+    pub fn for_zone(&self, zone: &str, action: TriggerAction) -> Self {
+        Self {
+            name: format!("{}_{}", self.name, zone),
+            enabled: self.enabled,
+            dry_run: self.dry_run,
+            condition: self.condition.clone().in_zone(zone),
+            action,
+            cooldown: self.cooldown,
+            last_triggered: None,
+            fire_count: 0,
+        }
+    }
+    
+    /// When the trigger last fired (restored across restarts when the
+    /// manager has a state file)
+    pub fn last_triggered(&self) -> Option<SystemTime> {
+        self.last_triggered
+    }
+    
+    /// Firings since startup, dry runs included
+    pub fn fire_count(&self) -> u64 {
+        self.fire_count
+    }
+    
+    /// Set dry-run mode
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+    
+    /// Check the trigger and queue its action on `pool` if it fires
+    ///
+    /// In dry-run mode, or when not `armed`, a firing is logged and audited
+    /// but the action does not run. Cooldowns apply either way, so a dry run
+    /// fires exactly as often as the armed trigger would.
+    pub fn check_and_fire(&mut self, event: &ParanormalEvent, history: &[ParanormalEvent], live: &LiveReadings, pool: &ActionPool, armed: bool) -> bool {
         if !self.enabled {
-            return Ok(false);
+            return false;
         }
         
         // Check cooldown
         if let Some(last) = self.last_triggered {
             if let Ok(elapsed) = event.timestamp.duration_since(last) {
                 if elapsed < self.cooldown {
-                    return Ok(false);
+                    return false;
                 }
             }
         }
         
         // Check condition
-        if !self.condition.check(event, history) {
-            return Ok(false);
+        if !self.condition.check(event, history, live) {
+            return false;
         }
         
-        // Execute action
-        tracing::info!("Trigger activated: {}", self.name);
-        self.action.execute(event).await?;
+        if self.dry_run || !armed {
+            tracing::info!("Trigger activated (dry run): {}", self.name);
+            pool.dry_run(&self.name, &self.action, event);
+        } else {
+            // Run the action in the background so slow commands do not hold up events
+            tracing::info!("Trigger activated: {}", self.name);
+            pool.submit(&self.name, &self.action, event);
+        }
         self.last_triggered = Some(event.timestamp);
+        self.fire_count += 1;
         
-        Ok(true)
+        true
     }
 }
 
@@ -231,6 +482,20 @@ pub struct TriggerManager {
     triggers: Vec<Trigger>,
     event_history: Vec<ParanormalEvent>,
     history_limit: usize,
+    /// Latest sensor values for `SensorValue` conditions
+    live: LiveReadings,
+    /// Value triggers whose condition held at the last check
+    value_active: HashSet<String>,
+    /// Locates value trigger events for `InZone` conditions
+    zones: ZoneMap,
+    /// Where last-fired times are persisted
+    state_path: Option<PathBuf>,
+    /// Last-fired times loaded from the state file
+    restored: BTreeMap<String, SystemTime>,
+    /// Runs actions of fired triggers
+    pool: ActionPool,
+    /// Whether any action runs; disarmed, every trigger is a dry run
+    armed: bool,
 }
 
 impl TriggerManager {
@@ -239,14 +504,108 @@ impl TriggerManager {
             triggers: Vec::new(),
             event_history: Vec::new(),
             history_limit: 1000,
+            live: LiveReadings::new(),
+            value_active: HashSet::new(),
+            zones: ZoneMap::default(),
+            state_path: None,
+            restored: BTreeMap::new(),
+            pool: ActionPool::default(),
+            armed: true,
+        }
+    }
+    
+    /// Run actions on `pool` (worker count, time limit, audit log)
+    pub fn with_actions(mut self, pool: ActionPool) -> Self {
+        self.pool = pool;
+        self
+    }
+    
+    /// Pool running the actions of fired triggers
+    pub fn actions(&self) -> &ActionPool {
+        &self.pool
+    }
+    
+    /// Outcomes of recent actions
+    pub fn audit(&self) -> &TriggerAudit {
+        self.pool.audit()
+    }
+    
+    /// Check `SensorValue` conditions against these latest values
+    /// (usually [`FusionEngine::live`](crate::fusion::FusionEngine::live))
+    pub fn with_live(mut self, live: LiveReadings) -> Self {
+        self.live = live;
+        self
+    }
+    
+    /// Locate value trigger events with this zone map
+    ///
+    /// Events passed to [`process_event`](Self::process_event) should
+    /// already be located.
+    pub fn with_zones(mut self, zones: ZoneMap) -> Self {
+        self.zones = zones;
+        self
+    }
+    
+    /// Persist last-fired times to `path` and restore them now
+    ///
+    /// Cooldowns then survive restarts, so a crash-looping node does not
+    /// repeat notifications. A missing or unreadable file starts fresh.
+    pub fn with_state_file(mut self, path: &Path) -> Self {
+        match load_state(path) {
+            Ok(restored) => {
+                for trigger in &mut self.triggers {
+                    if let Some(&last) = restored.get(&trigger.name) {
+                        trigger.last_triggered = Some(last);
+                    }
+                }
+                tracing::debug!("Restored last-fired times of {} triggers", restored.len());
+                self.restored = restored;
+            }
+            Err(e) => tracing::warn!("Trigger state not restored: {}", e),
         }
+        self.state_path = Some(path.to_path_buf());
+        self
     }
     
     /// Add trigger
-    pub fn add_trigger(&mut self, trigger: Trigger) {
+    pub fn add_trigger(&mut self, mut trigger: Trigger) {
+        if let Some(&last) = self.restored.get(&trigger.name) {
+            trigger.last_triggered = Some(last);
+        }
         self.triggers.push(trigger);
     }
     
+    /// Write last-fired times to the state file, if any
+    ///
+    /// The file is written on a blocking thread.
+    async fn save_state(&self) {
+        let Some(path) = self.state_path.clone() else {
+            return;
+        };
+        
+        let state: BTreeMap<&str, SystemTime> = self.triggers.iter()
+            .filter_map(|t| Some((t.name.as_str(), t.last_triggered?)))
+            .collect();
+        let json = match serde_json::to_string_pretty(&state) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize trigger state: {}", e);
+                return;
+            }
+        };
+        
+        let result = tokio::task::spawn_blocking(move || {
+            // Write then rename so a crash mid-write keeps the old state
+            let tmp = path.with_extension("json.tmp");
+            std::fs::write(&tmp, json).and_then(|_| std::fs::rename(&tmp, &path))
+        }).await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!("Failed to write trigger state: {}", e),
+            Err(e) => tracing::warn!("Trigger state writer failed: {}", e),
+        }
+    }
+    
     /// Remove trigger by name
     pub fn remove_trigger(&mut self, name: &str) {
         self.triggers.retain(|t| t.name != name);
@@ -259,16 +618,53 @@ impl TriggerManager {
         }
     }
     
+    /// Put a trigger in or out of dry-run mode; false if no trigger has that name
+    pub fn set_trigger_dry_run(&mut self, name: &str, dry_run: bool) -> bool {
+        match self.triggers.iter_mut().find(|t| t.name == name) {
+            Some(trigger) => {
+                trigger.dry_run = dry_run;
+                tracing::info!("Trigger {} {}", name, if dry_run { "in dry-run mode" } else { "live" });
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Arm or disarm all triggers
+    ///
+    /// Disarmed, conditions are still evaluated and firings audited as dry
+    /// runs, but no action runs.
+    pub fn set_armed(&mut self, armed: bool) {
+        if armed != self.armed {
+            tracing::info!("Triggers {}", if armed { "armed" } else { "disarmed" });
+        }
+        self.armed = armed;
+    }
+    
+    pub fn is_armed(&self) -> bool {
+        self.armed
+    }
+    
     /// Process event through all triggers
+    ///
+    /// Value triggers are skipped; [`process_values`](Self::process_values)
+    /// runs them.
     pub async fn process_event(&mut self, event: ParanormalEvent) -> Result<Vec<String>> {
         let mut triggered = Vec::new();
         
         for trigger in &mut self.triggers {
-            if trigger.check_and_execute(&event, &self.event_history).await? {
+            if trigger.condition.is_value_condition() {
+                continue;
+            }
+            if trigger.check_and_fire(&event, &self.event_history, &self.live, &self.pool, self.armed) {
                 triggered.push(trigger.name.clone());
             }
         }
         
+        if !triggered.is_empty() {
+            self.save_state().await;
+        }
+        
         // Add to history
         self.event_history.push(event);
         
@@ -280,11 +676,80 @@ impl TriggerManager {
         Ok(triggered)
     }
     
+    /// Check value triggers against the latest sensor values
+    ///
+    /// A trigger fires when its condition starts holding (subject to its
+    /// cooldown), not on every check while it keeps holding. Its action
+    /// gets an event built from the matching sensors.
+    pub async fn process_values(&mut self) -> Result<Vec<String>> {
+        let mut triggered = Vec::new();
+        
+        for trigger in &mut self.triggers {
+            if !trigger.enabled || !trigger.condition.is_value_condition() {
+                continue;
+            }
+            
+            let matches = trigger.condition.matching_sensors(&self.live);
+            let mut event = threshold_event(&trigger.name, matches);
+            self.zones.locate(&mut event);
+            if !trigger.condition.check(&event, &self.event_history, &self.live) {
+                self.value_active.remove(&trigger.name);
+                continue;
+            }
+            if self.value_active.contains(&trigger.name) {
+                continue;
+            }
+            
+            if trigger.check_and_fire(&event, &self.event_history, &self.live, &self.pool, self.armed) {
+                self.value_active.insert(trigger.name.clone());
+                triggered.push(trigger.name.clone());
+            }
+        }
+        
+        if !triggered.is_empty() {
+            self.save_state().await;
+        }
+        
+        Ok(triggered)
+    }
+    
+    /// Run a trigger's action once on a synthetic event, ignoring its
+    /// condition, cooldown and enabled flag
+    ///
+    /// The returned future runs the action (audited, under the time limit)
+    /// and reports its first failure. `None` if no trigger has that name.
+    pub fn test_trigger(&self, name: &str) -> Option<impl Future<Output = Result<()>> + Send + 'static> {
+        let trigger = self.triggers.iter().find(|t| t.name == name)?;
+        
+        let event = ParanormalEvent::new(EventType::MultiSensorEvent, 1.0)
+            .with_metadata("test", "true");
+        tracing::info!("Testing trigger: {}", trigger.name);
+        
+        // The returned future owns everything, so callers need not hold the manager
+        let (pool, name, action) = (self.pool.clone(), trigger.name.clone(), trigger.action.clone());
+        Some(async move { pool.run(&name, &action, &event).await })
+    }
+    
     /// List all triggers
     pub fn list_triggers(&self) -> Vec<&Trigger> {
         self.triggers.iter().collect()
     }
     
+    /// State of every trigger
+    pub fn states(&self) -> Vec<TriggerState> {
+        self.triggers.iter()
+            .map(|t| TriggerState {
+                name: t.name.clone(),
+                enabled: t.enabled,
+                cooldown_secs: t.cooldown.as_secs(),
+                last_triggered: t.last_triggered,
+                value_active: self.value_active.contains(&t.name),
+                dry_run: t.dry_run,
+                fire_count: t.fire_count,
+            })
+            .collect()
+    }
+    
     /// Load default triggers
     pub fn load_defaults(&mut self) {
         // High confidence EMF alert
@@ -301,11 +766,12 @@ impl TriggerManager {
                 },
                 TriggerAction::PlaySound {
                     file: "/usr/share/glowbarn/sounds/alert.wav".to_string(),
+                    route: Some(ALERT_ROUTE.to_string()),
                 },
             ]),
         ));
         
-        // Temperature anomaly alert
+        // Temperature anomaly alert, unless a door or draft just moved the air
         self.add_trigger(Trigger::new(
             "cold_spot_alert",
             TriggerCondition::All(vec![
@@ -314,6 +780,16 @@ impl TriggerManager {
                     sensor_pattern: "temp".to_string(),
                     threshold: 3.0,
                 },
+                TriggerCondition::Not(Box::new(TriggerCondition::Any(vec![
+                    TriggerCondition::RecentEvent {
+                        event_type: EventType::PressureAnomaly,
+                        window: Duration::from_secs(60),
+                    },
+                    TriggerCondition::RecentEvent {
+                        event_type: EventType::DoorEvent,
+                        window: Duration::from_secs(60),
+                    },
+                ]))),
             ]),
             TriggerAction::Notify {
                 title: "Cold Spot Detected".to_string(),
@@ -339,6 +815,37 @@ impl TriggerManager {
             ]),
         ));
         
+        // Knocks with nobody moving nearby
+        self.add_trigger(Trigger::new(
+            "knock_alert",
+            TriggerCondition::All(vec![
+                TriggerCondition::EventType(EventType::VibrationDetected),
+                TriggerCondition::ConfidenceAbove(0.7),
+                TriggerCondition::Not(Box::new(TriggerCondition::RecentEvent {
+                    event_type: EventType::MotionDetected,
+                    window: Duration::from_secs(10),
+                })),
+            ]),
+            TriggerAction::Log {
+                level: "warn".to_string(),
+                message: "Knock with no motion nearby: {confidence}".to_string(),
+            },
+        ).with_cooldown(Duration::from_secs(30)));
+        
+        // Classic meter-style alarm on the raw field strength
+        self.add_trigger(Trigger::new(
+            "emf_limit_alarm",
+            TriggerCondition::SensorValue {
+                sensor_pattern: "emf".to_string(),
+                op: Comparison::Above,
+                value: 2000.0,
+            },
+            TriggerAction::Log {
+                level: "warn".to_string(),
+                message: "EMF above 2000 mG".to_string(),
+            },
+        ).with_cooldown(Duration::from_secs(60)));
+        
         // Event burst detection
         self.add_trigger(Trigger::new(
             "activity_burst",
@@ -361,6 +868,32 @@ impl TriggerManager {
     }
 }
 
+/// Last-fired times from a state file (empty if it does not exist)
+fn load_state(path: &Path) -> Result<BTreeMap<String, SystemTime>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(SensorError::Recording(format!("Failed to read {}: {}", path.display(), e))),
+    };
+    serde_json::from_str(&content)
+        .map_err(|e| SensorError::Recording(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+/// Event passed to a value trigger's action
+fn threshold_event(trigger: &str, matches: Vec<SensorSnapshot>) -> ParanormalEvent {
+    let event_type = matches.first()
+        .map(|s| EventType::for_sensor_type(&s.sensor_type))
+        .unwrap_or(EventType::MultiSensorEvent);
+    
+    let mut event = ParanormalEvent::new(event_type, 1.0)
+        .with_metadata("trigger", trigger)
+        .with_metadata("threshold", "true");
+    for snapshot in matches {
+        event = event.with_sensor_data(snapshot);
+    }
+    event
+}
+
 impl Default for TriggerManager {
     fn default() -> Self {
         let mut manager = Self::new();
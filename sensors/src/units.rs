@@ -0,0 +1,177 @@
+//! Display Units and Timestamps
+//!
+//! Readings, events and exports are always stored in SI units (temperatures
+//! in °C, pressure in hPa) and UTC. A [`DisplayConfig`] only changes how they
+//! are shown to people: CLI tables and HTML reports convert temperatures to
+//! °F and pressure to inHg when imperial units are selected, and write dates
+//! and times the way the configured locale does.
+//!
+//! Locale support is a table of the common date orders and 12/24-hour
+//! conventions, not full CLDR data; unknown locales fall back to ISO 8601.
+
+use crate::pressure::to_hpa;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+/// hPa per inch of mercury
+const HPA_PER_INHG: f64 = 33.8639;
+
+/// Unit system values are displayed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    /// SI units, as recorded
+    #[default]
+    Metric,
+    /// °F and inHg
+    Imperial,
+}
+
+impl std::str::FromStr for UnitSystem {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "metric" | "si" => Ok(Self::Metric),
+            "imperial" | "us" => Ok(Self::Imperial),
+            _ => Err(format!("unknown unit system: {} (expected metric or imperial)", s)),
+        }
+    }
+}
+
+/// How values and timestamps are presented (`[display]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisplayConfig {
+    #[serde(default)]
+    pub units: UnitSystem,
+    
+    /// Locale for dates and times, e.g. "en-US" or "de-DE"; empty takes
+    /// LC_ALL, LC_TIME or LANG
+    #[serde(default)]
+    pub locale: String,
+    
+    /// Show times in UTC instead of the local time zone
+    #[serde(default)]
+    pub utc: bool,
+}
+
+/// Date and time patterns of a locale
+struct LocaleFormat {
+    date: &'static str,
+    time: &'static str,
+    time_ms: &'static str,
+    hour12: bool,
+}
+
+const ISO: LocaleFormat = LocaleFormat { date: "%Y-%m-%d", time: "%H:%M:%S", time_ms: "%H:%M:%S%.3f", hour12: false };
+
+impl LocaleFormat {
+    fn new(date: &'static str, hour12: bool) -> Self {
+        if hour12 {
+            Self { date, time: "%I:%M:%S %p", time_ms: "%I:%M:%S%.3f %p", hour12 }
+        } else {
+            Self { date, time: "%H:%M:%S", time_ms: "%H:%M:%S%.3f", hour12 }
+        }
+    }
+    
+    fn for_tag(tag: &str) -> Self {
+        let mut parts = tag.split('-');
+        let language = parts.next().unwrap_or_default();
+        let region = parts.find(|p| p.len() == 2 || p.len() == 3).unwrap_or_default();
+        
+        match (language, region) {
+            ("en", "" | "US" | "PH") => Self::new("%m/%d/%Y", true),
+            ("en", "CA") => Self::new("%Y-%m-%d", true),
+            ("en", "AU" | "NZ" | "IN") => Self::new("%d/%m/%Y", true),
+            ("en", _) => Self::new("%d/%m/%Y", false),
+            ("de" | "da" | "fi" | "nb" | "nn" | "no" | "ru" | "pl" | "cs" | "sk" | "tr" | "uk" | "ro", _) => Self::new("%d.%m.%Y", false),
+            ("fr" | "es" | "it" | "pt" | "el" | "ca", _) => Self::new("%d/%m/%Y", false),
+            ("nl", _) => Self::new("%d-%m-%Y", false),
+            ("hu", _) => Self::new("%Y. %m. %d.", false),
+            ("ja" | "zh", _) => Self::new("%Y/%m/%d", false),
+            ("ko", _) => Self::new("%Y. %m. %d.", false),
+            _ => ISO,
+        }
+    }
+}
+
+impl DisplayConfig {
+    /// BCP 47 tag of the configured or environment locale, empty for C/POSIX
+    pub fn locale(&self) -> String {
+        let raw = if self.locale.is_empty() {
+            ["LC_ALL", "LC_TIME", "LANG"].iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|value| !value.is_empty())
+                .unwrap_or_default()
+        } else {
+            self.locale.clone()
+        };
+        
+        // "en_US.UTF-8@euro" -> "en-US"
+        let tag = raw.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+        match tag.as_str() {
+            "C" | "POSIX" => String::new(),
+            _ => tag,
+        }
+    }
+    
+    fn format(&self) -> LocaleFormat {
+        LocaleFormat::for_tag(&self.locale())
+    }
+    
+    /// Whether times use a 12-hour clock
+    pub fn hour12(&self) -> bool {
+        self.format().hour12
+    }
+    
+    fn render(&self, time: DateTime<Utc>, pattern: &str) -> String {
+        if self.utc {
+            time.format(pattern).to_string()
+        } else {
+            time.with_timezone(&Local).format(pattern).to_string()
+        }
+    }
+    
+    /// Date and time, to the second
+    pub fn datetime(&self, time: DateTime<Utc>) -> String {
+        let format = self.format();
+        self.render(time, &format!("{} {}", format.date, format.time))
+    }
+    
+    /// Date and time, to the minute
+    pub fn datetime_short(&self, time: DateTime<Utc>) -> String {
+        let format = self.format();
+        let clock = if format.hour12 { "%I:%M %p" } else { "%H:%M" };
+        self.render(time, &format!("{} {}", format.date, clock))
+    }
+    
+    /// Time of day, to the second
+    pub fn time(&self, time: DateTime<Utc>) -> String {
+        self.render(time, self.format().time)
+    }
+    
+    /// Time of day, to the millisecond
+    pub fn time_ms(&self, time: DateTime<Utc>) -> String {
+        self.render(time, self.format().time_ms)
+    }
+    
+    /// `value` recorded in `unit`, converted for display, with its display unit
+    ///
+    /// Only temperatures and pressures change; everything else passes through.
+    pub fn value(&self, value: f64, unit: &str) -> (f64, String) {
+        if self.units == UnitSystem::Metric {
+            return (value, unit.to_string());
+        }
+        match unit {
+            "C" | "°C" | "degC" | "celsius" => (value * 9.0 / 5.0 + 32.0, "°F".to_string()),
+            "hPa" | "Pa" | "kPa" | "mbar" => (to_hpa(value, unit) / HPA_PER_INHG, "inHg".to_string()),
+            _ => (value, unit.to_string()),
+        }
+    }
+    
+    /// Temperature in °C as a display string
+    pub fn temperature(&self, celsius: f64) -> String {
+        let (value, unit) = self.value(celsius, "°C");
+        format!("{:.1} {}", value, unit)
+    }
+}
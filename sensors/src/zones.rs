@@ -0,0 +1,123 @@
+//! Zone map for locating events
+//!
+//! Maps sensor names to named zones (rooms) with floor, floor-plan and
+//! GPS coordinates, so events can be tagged with where they happened and
+//! triggers and reports can filter by room.
+
+use crate::{Location, ParanormalEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A room or area of the site
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneConfig {
+    /// Zone name
+    pub name: String,
+    /// Floor number (0 = ground)
+    #[serde(default)]
+    pub floor: Option<i32>,
+    /// Floor-plan coordinates (meters)
+    #[serde(default)]
+    pub x: Option<f64>,
+    #[serde(default)]
+    pub y: Option<f64>,
+    /// GPS position (degrees, WGS84 / meters)
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    #[serde(default)]
+    pub altitude: Option<f64>,
+    /// Sensors installed in this zone
+    #[serde(default)]
+    pub sensors: Vec<String>,
+}
+
+/// Sensor to zone lookup
+#[derive(Debug, Clone, Default)]
+pub struct ZoneMap {
+    site: String,
+    zones: Vec<ZoneConfig>,
+    by_sensor: HashMap<String, usize>,
+}
+
+impl ZoneMap {
+    /// Build a map for `site` from zone definitions
+    pub fn new(site: &str, zones: Vec<ZoneConfig>) -> Self {
+        let mut by_sensor = HashMap::new();
+        for (index, zone) in zones.iter().enumerate() {
+            for sensor in &zone.sensors {
+                if by_sensor.insert(sensor.clone(), index).is_some() {
+                    tracing::warn!("Sensor {} is listed in more than one zone", sensor);
+                }
+            }
+        }
+        
+        Self {
+            site: site.to_string(),
+            zones,
+            by_sensor,
+        }
+    }
+    
+    /// Place a sensor in a zone; returns false if the zone is not defined
+    pub fn assign(&mut self, sensor: &str, zone: &str) -> bool {
+        match self.zones.iter().position(|z| z.name == zone) {
+            Some(index) => {
+                self.by_sensor.insert(sensor.to_string(), index);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Defined zones
+    pub fn zones(&self) -> &[ZoneConfig] {
+        &self.zones
+    }
+    
+    /// Zone a sensor is installed in
+    pub fn zone_of(&self, sensor: &str) -> Option<&ZoneConfig> {
+        self.by_sensor.get(sensor).map(|&index| &self.zones[index])
+    }
+    
+    /// Location of a sensor
+    pub fn locate_sensor(&self, sensor: &str) -> Option<Location> {
+        let zone = self.zone_of(sensor)?;
+        Some(Location {
+            name: self.site.clone(),
+            zone: Some(zone.name.clone()),
+            x: zone.x,
+            y: zone.y,
+            floor: zone.floor,
+            latitude: zone.latitude,
+            longitude: zone.longitude,
+            altitude: zone.altitude,
+        })
+    }
+    
+    /// Fill in an event's location from its sensors
+    ///
+    /// Uses the primary sensor's zone, falling back to the first correlated
+    /// sensor with one. When the sensors span several zones they are listed
+    /// in the `zones` metadata. Events that already have a location keep it.
+    pub fn locate(&self, event: &mut ParanormalEvent) {
+        let mut zones: Vec<&str> = Vec::new();
+        for snapshot in &event.sensor_data {
+            if let Some(zone) = self.zone_of(&snapshot.sensor_name) {
+                if !zones.contains(&zone.name.as_str()) {
+                    zones.push(&zone.name);
+                }
+            }
+        }
+        
+        if zones.len() > 1 {
+            event.metadata.insert("zones".to_string(), zones.join(","));
+        }
+        
+        if event.location.is_none() {
+            event.location = event.sensor_data.iter()
+                .find_map(|snapshot| self.locate_sensor(&snapshot.sensor_name));
+        }
+    }
+}